@@ -0,0 +1,65 @@
+extern crate autogenerated_assimp_sys as ffi;
+extern crate open_asset_importer as assimp;
+
+use std::ptr::NonNull;
+
+use assimp::math::{Vector3D, Vector3DIter};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+fn points(count: usize) -> Vec<Vector3D> {
+    (0..count)
+        .map(|i| {
+            let i = i as f32;
+            Vector3D::new(i.sin(), i.cos(), i * 1e-6)
+        })
+        .collect()
+}
+
+fn iter(points: &[Vector3D]) -> Vector3DIter<'_> {
+    unsafe {
+        Vector3DIter::new(
+            NonNull::new(points.as_ptr() as *mut ffi::aiVector3D),
+            points.len(),
+        )
+    }
+}
+
+fn bench_sum_positions(c: &mut Criterion) {
+    let src = points(1_000_000);
+
+    let mut group = c.benchmark_group("sum 1M positions");
+    group.bench_function("next", |b| {
+        b.iter(|| iter(black_box(&src)).fold(Vector3D::new(0.0, 0.0, 0.0), |sum, p| sum + p))
+    });
+    group.bench_function("as_slice", |b| {
+        b.iter(|| {
+            let slice = iter(black_box(&src)).as_slice().unwrap();
+            slice
+                .iter()
+                .fold(Vector3D::new(0.0, 0.0, 0.0), |sum, &p| sum + p)
+        })
+    });
+    group.bench_function("chunks8", |b| {
+        b.iter(|| {
+            let positions = iter(black_box(&src));
+            let chunks = positions.chunks8();
+            let rest = chunks.remainder();
+
+            // Eight independent sums, so that the additions don't depend on each other.
+            let mut sums = [Vector3D::new(0.0, 0.0, 0.0); 8];
+            for chunk in chunks {
+                for (sum, &p) in sums.iter_mut().zip(chunk) {
+                    *sum = *sum + p;
+                }
+            }
+            let sum = sums
+                .iter()
+                .fold(Vector3D::new(0.0, 0.0, 0.0), |a, &b| a + b);
+            rest.iter().fold(sum, |sum, &p| sum + p)
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_sum_positions);
+criterion_main!(benches);
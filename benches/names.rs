@@ -0,0 +1,71 @@
+extern crate autogenerated_assimp_sys as ffi;
+extern crate open_asset_importer as assimp;
+
+use std::ptr::NonNull;
+
+use assimp::Node;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+fn aistring(name: &str) -> ffi::aiString {
+    let mut string: ffi::aiString = unsafe { std::mem::zeroed() };
+    for (dst, &src) in string.data.iter_mut().zip(name.as_bytes()) {
+        *dst = src as _;
+    }
+    string.length = name.len() as _;
+    string
+}
+
+/// A root node with `count - 1` children, where the last child has the name that's searched
+/// for. The names share a long multibyte prefix, so that validating them isn't free.
+struct Tree {
+    root: Box<ffi::aiNode>,
+    _children: Vec<Box<ffi::aiNode>>,
+    _pointers: Vec<*mut ffi::aiNode>,
+}
+
+fn tree(count: usize) -> Tree {
+    let mut children: Vec<Box<ffi::aiNode>> = (1..count)
+        .map(|i| {
+            let mut node: Box<ffi::aiNode> = Box::new(unsafe { std::mem::zeroed() });
+            node.mName = aistring(&format!("Körper/Gelenk/骨_{}", i));
+            node
+        })
+        .collect();
+    let mut pointers: Vec<*mut ffi::aiNode> = children
+        .iter_mut()
+        .map(|node| &mut **node as *mut _)
+        .collect();
+
+    let mut root: Box<ffi::aiNode> = Box::new(unsafe { std::mem::zeroed() });
+    root.mName = aistring("Wurzel");
+    root.mNumChildren = pointers.len() as u32;
+    root.mChildren = pointers.as_mut_ptr();
+
+    Tree {
+        root,
+        _children: children,
+        _pointers: pointers,
+    }
+}
+
+fn bench_find_node(c: &mut Criterion) {
+    let tree = tree(500);
+    let root = unsafe { Node::from_raw(NonNull::from(&*tree.root)) };
+    let target = "Körper/Gelenk/骨_499";
+
+    let mut group = c.benchmark_group("find 1 of 500 nodes by name");
+    group.bench_function("str", |b| {
+        b.iter(|| {
+            root.descendants()
+                .find(|node| node.name() == black_box(target))
+                .unwrap()
+        })
+    });
+    group.bench_function("cstr", |b| {
+        b.iter(|| root.find(black_box(target.as_bytes())).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_find_node);
+criterion_main!(benches);
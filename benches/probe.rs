@@ -0,0 +1,32 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::import::SceneProbe;
+use assimp::Importer;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+fn bench_probe(c: &mut Criterion) {
+    for file in &["examples/spider.obj", "examples/rigged.gltf"] {
+        let mut full = Importer::new();
+        full.triangulate(true);
+        full.generate_normals(|args| {
+            args.enable = true;
+            args.smooth = true;
+        });
+        let importer = Importer::new();
+
+        let mut group = c.benchmark_group(format!("summarise {}", file));
+        group.bench_function("read_file", |b| {
+            b.iter(|| {
+                let scene = full.read_file(black_box(file)).unwrap();
+                SceneProbe::from_scene(&scene)
+            })
+        });
+        group.bench_function("probe", |b| {
+            b.iter(|| importer.probe(black_box(file)).unwrap())
+        });
+        group.finish();
+    }
+}
+
+criterion_group!(benches, bench_probe);
+criterion_main!(benches);
@@ -0,0 +1,44 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::math::{transform_points, Matrix4x4, Vector3D};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+fn points(count: usize) -> Vec<Vector3D> {
+    (0..count)
+        .map(|i| {
+            let i = i as f32;
+            Vector3D::new(i.sin(), i.cos(), i * 1e-6)
+        })
+        .collect()
+}
+
+fn bench_transform_points(c: &mut Criterion) {
+    let src = points(1_000_000);
+    let matrix = Matrix4x4::new(
+        0.0, -2.0, 0.0, 1.0, 1.0, 0.0, 0.0, 2.0, 0.0, 0.0, 3.0, 3.0, 0.0, 0.0, 0.0, 1.0,
+    );
+
+    let mut group = c.benchmark_group("transform 1M points");
+    group.bench_function("naive", |b| {
+        let mut dst = Vec::new();
+        b.iter(|| {
+            let matrix = black_box(&matrix);
+            dst.clear();
+            for p in &src {
+                dst.push(matrix.transform_point(*p));
+            }
+            dst.len()
+        })
+    });
+    group.bench_function("transform_points", |b| {
+        let mut dst = Vec::new();
+        b.iter(|| {
+            transform_points(black_box(&matrix), &src, &mut dst);
+            dst.len()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_transform_points);
+criterion_main!(benches);
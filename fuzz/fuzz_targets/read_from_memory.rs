@@ -0,0 +1,6 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use open_asset_importer::fixtures::fuzz_read_from_memory;
+
+fuzz_target!(|data: &[u8]| fuzz_read_from_memory(data));
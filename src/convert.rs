@@ -0,0 +1,329 @@
+//! Converting a file to another format in one call, see `convert`.
+//!
+//! `convert` only puts together what the rest of the crate provides: `Importer::apply_config`,
+//! `Scene::snapshot`, `scene::convert::axis_convert`, `scene::prune::prune`,
+//! `Scene::extract_embedded_textures` and `export::write_snapshot`. Programs that need a step
+//! `ConvertOptions` doesn't offer can run the same steps themselves.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::export::{self, ExportWarning};
+use crate::import::structs::ImporterConfig;
+use crate::import::ImportTimings;
+use crate::math::Matrix4x4;
+use crate::scene::convert::{axis_convert, AxisConvention};
+use crate::scene::prune::{prune, PruneFilter, PruneReport};
+use crate::scene::{NamingStrategy, SceneSnapshot, ValidationFinding};
+use crate::{Error, Importer};
+
+/// What `convert` does when a file it would write already exists.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum OverwritePolicy {
+    /// Fail with an `Error::Io` of kind `AlreadyExists` before writing anything.
+    Fail,
+    /// Replace the existing files.
+    Overwrite,
+}
+
+impl Default for OverwritePolicy {
+    fn default() -> Self {
+        OverwritePolicy::Fail
+    }
+}
+
+/// Options for `convert`. The default imports without any post-process steps, writes the
+/// format of the output's extension, doesn't change the scene and doesn't overwrite anything.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConvertOptions {
+    /// The importer configuration, see `Importer::apply_config`. Default: nothing enabled
+    pub config: ImporterConfig,
+    /// The format to write, one of `export::FORMATS`. Default: `None`, which takes the
+    /// extension of the output path
+    pub format: Option<String>,
+    /// Convert the scene from the first convention to the second, see
+    /// `scene::convert::axis_convert`. Default: `None`
+    pub axis: Option<(AxisConvention, AxisConvention)>,
+    /// Scale the whole scene by this factor, after the axis conversion. Default: 1
+    pub scale: f32,
+    /// Extract the embedded textures, named with this strategy, to a directory next to the
+    /// output, named after the output with `_textures` appended to its stem. The written formats
+    /// don't refer to them, so this is only for keeping the textures. Default: `None`
+    pub extract_textures: Option<NamingStrategy>,
+    /// What to drop from the scene before writing it, see `scene::prune::prune`. Default:
+    /// nothing
+    pub prune: PruneFilter,
+    /// Default: `OverwritePolicy::Fail`
+    pub overwrite: OverwritePolicy,
+    /// Run every step but writing, and report what would have been written. Default: false
+    pub dry_run: bool,
+}
+
+impl Default for ConvertOptions {
+    fn default() -> Self {
+        ConvertOptions {
+            config: ImporterConfig::default(),
+            format: None,
+            axis: None,
+            scale: 1.0,
+            extract_textures: None,
+            prune: PruneFilter::default(),
+            overwrite: OverwritePolicy::Fail,
+            dry_run: false,
+        }
+    }
+}
+
+/// Something that went wrong in a `convert` without failing it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConvertWarning {
+    /// A warning of `Importer::validate_data_structure`, if the config enables it.
+    Validation(ValidationFinding),
+    /// Something the output format couldn't hold.
+    Export(ExportWarning),
+}
+
+/// How long each stage of a `convert` took.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct ConvertTimings {
+    /// The timings of the import, see `Importer::instrumented`.
+    pub import: ImportTimings,
+    /// Taking the snapshot, converting axes, scaling and pruning.
+    pub process: Duration,
+    /// Encoding the output and writing the files, which is only encoding in a dry run.
+    pub write: Duration,
+}
+
+impl ConvertTimings {
+    /// The time of the whole conversion.
+    pub fn total(&self) -> Duration {
+        self.import.total() + self.process + self.write
+    }
+}
+
+/// What `convert` did.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConvertReport {
+    /// The format that was written.
+    pub format: String,
+    /// The number of meshes, vertices and faces in the scene that was written, counting each
+    /// mesh once however many nodes refer to it.
+    pub meshes: usize,
+    pub vertices: usize,
+    pub faces: usize,
+    pub materials: usize,
+    pub textures: usize,
+    pub animations: usize,
+    /// What `ConvertOptions::prune` dropped.
+    pub pruned: PruneReport,
+    pub warnings: Vec<ConvertWarning>,
+    pub timings: ConvertTimings,
+    /// The files that were written, the output first and then the extracted textures, or
+    /// that would have been written in a dry run.
+    pub files: Vec<PathBuf>,
+    /// The size of the output, not counting the textures.
+    pub output_bytes: u64,
+    pub dry_run: bool,
+}
+
+/// Import `input`, process it as `options` say and write it to `output`.
+///
+/// Nothing is written until the scene has been imported, processed and encoded, and all the
+/// files are checked against `ConvertOptions::overwrite` before the first one is written. The
+/// files are first written next to where they go, with `.partial` appended to their names,
+/// and only renamed into place once all of them were written, so if a step fails before that,
+/// the partial files are removed and the existing files are left as they were.
+///
+/// An unknown format gives `Error::Unsupported`, a scale that isn't finite and positive
+/// `Error::InvalidConfig`, and the other errors are those of the steps, see the module
+/// documentation.
+pub fn convert<P: AsRef<Path>, Q: AsRef<Path>>(
+    input: P,
+    output: Q,
+    options: ConvertOptions,
+) -> Result<ConvertReport, Error> {
+    let output = output.as_ref();
+    let format = match &options.format {
+        Some(format) => format.clone(),
+        None => output
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or_default()
+            .to_ascii_lowercase(),
+    };
+    if !export::FORMATS.contains(&format.as_str()) {
+        return Err(Error::Unsupported {
+            what: format!("export format {:?}", format),
+        });
+    }
+    if !(options.scale.is_finite() && options.scale > 0.0) {
+        return Err(Error::InvalidConfig {
+            message: format!("scale must be finite and positive, not {}", options.scale),
+        });
+    }
+    if options.overwrite == OverwritePolicy::Fail {
+        check_free(output)?;
+    }
+
+    let mut importer = Importer::new();
+    importer.apply_config(options.config);
+    importer.instrumented(true);
+    let scene = importer.read_file(input)?;
+    let diagnostics = scene.import_diagnostics();
+
+    let start = Instant::now();
+    let mut snapshot = scene.snapshot();
+    if let Some((from, to)) = options.axis {
+        axis_convert(&mut snapshot, from, to)?;
+    }
+    if options.scale != 1.0 {
+        scale(&mut snapshot, options.scale);
+    }
+    let pruned = prune(&mut snapshot, options.prune);
+    let process = start.elapsed();
+
+    let start = Instant::now();
+    let mut encoded = Vec::new();
+    let export_warnings = export::write_snapshot(&snapshot, &format, &mut encoded)?;
+
+    let mut files = vec![output.to_owned()];
+    let (texture_dir, texture_names) = match &options.extract_textures {
+        Some(naming) if scene.num_textures() > 0 => {
+            (Some(textures_dir(output)), scene.texture_file_names(naming))
+        }
+        _ => (None, Vec::new()),
+    };
+    if let Some(dir) = &texture_dir {
+        files.extend(texture_names.iter().map(|name| dir.join(name)));
+    }
+    if options.overwrite == OverwritePolicy::Fail {
+        for file in &files[1..] {
+            check_free(file)?;
+        }
+    }
+
+    if !options.dry_run {
+        let mut staged = Staged::default();
+        staged.write(output, &encoded)?;
+        if let (Some(dir), Some(naming)) = (&texture_dir, options.extract_textures) {
+            let staging = partial(dir);
+            staged.dirs.push(staging.clone());
+            if staging.exists() {
+                // Left behind by a conversion that was interrupted.
+                fs::remove_dir_all(&staging)?;
+            }
+            scene.extract_embedded_textures(&staging, naming)?;
+            fs::create_dir_all(dir)?;
+            for name in &texture_names {
+                staged.files.push((staging.join(name), dir.join(name)));
+            }
+        }
+        staged.commit()?;
+    }
+    let write = start.elapsed();
+
+    let validation = diagnostics
+        .validation
+        .iter()
+        .cloned()
+        .map(ConvertWarning::Validation);
+    let export_warnings = export_warnings.into_iter().map(ConvertWarning::Export);
+    Ok(ConvertReport {
+        format,
+        meshes: snapshot.meshes.len(),
+        vertices: snapshot
+            .meshes
+            .iter()
+            .map(|mesh| mesh.positions.len())
+            .sum(),
+        faces: snapshot.meshes.iter().map(|mesh| mesh.faces.len()).sum(),
+        materials: snapshot.materials.len(),
+        textures: snapshot.textures.len(),
+        animations: snapshot.animations.len(),
+        pruned,
+        warnings: validation.chain(export_warnings).collect(),
+        timings: ConvertTimings {
+            import: diagnostics.timings.clone().unwrap_or_default(),
+            process,
+            write,
+        },
+        files,
+        output_bytes: encoded.len() as u64,
+        dry_run: options.dry_run,
+    })
+}
+
+/// The directory `ConvertOptions::extract_textures` extracts to.
+fn textures_dir(output: &Path) -> PathBuf {
+    let stem = output.file_stem().unwrap_or_default().to_string_lossy();
+    output.with_file_name(format!("{}_textures", stem))
+}
+
+/// Where `path` is written before it's renamed into place.
+fn partial(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_owned();
+    name.push(".partial");
+    path.with_file_name(name)
+}
+
+fn check_free(path: &Path) -> Result<(), Error> {
+    if path.exists() {
+        let message = format!("{} already exists", path.display());
+        return Err(io::Error::new(io::ErrorKind::AlreadyExists, message).into());
+    }
+    Ok(())
+}
+
+/// Scale the whole scene around the origin, by scaling the root transform.
+fn scale(snapshot: &mut SceneSnapshot, factor: f32) {
+    let scaling = Matrix4x4::from_rows([
+        [factor, 0.0, 0.0, 0.0],
+        [0.0, factor, 0.0, 0.0],
+        [0.0, 0.0, factor, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ]);
+    if let Some(root) = &mut snapshot.root {
+        root.transform = scaling * root.transform;
+    }
+}
+
+/// Files written under a `.partial` name, which are renamed into place by `commit`, or removed
+/// along with the staging directories if it's dropped before that.
+#[derive(Default)]
+struct Staged {
+    /// `(partial path, final path)` pairs.
+    files: Vec<(PathBuf, PathBuf)>,
+    dirs: Vec<PathBuf>,
+}
+
+impl Staged {
+    fn write(&mut self, path: &Path, contents: &[u8]) -> Result<(), Error> {
+        let partial = partial(path);
+        self.files.push((partial.clone(), path.to_owned()));
+        fs::write(&partial, contents)?;
+        Ok(())
+    }
+
+    fn commit(mut self) -> Result<(), Error> {
+        for (partial, path) in &self.files {
+            fs::rename(partial, path)?;
+        }
+        self.files.clear();
+        Ok(())
+    }
+}
+
+impl Drop for Staged {
+    fn drop(&mut self) {
+        // Errors are ignored, since there's nothing more to do about them, and the files that
+        // were never written don't exist.
+        for (partial, _) in &self.files {
+            let _ = fs::remove_file(partial);
+        }
+        for dir in &self.dirs {
+            let _ = fs::remove_dir_all(dir);
+        }
+    }
+}
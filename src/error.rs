@@ -0,0 +1,177 @@
+//! The error type shared by the fallible APIs of the crate, see `Error`.
+
+use std::error;
+use std::ffi::NulError;
+use std::fmt;
+use std::io;
+use std::str::Utf8Error;
+
+use crate::io::FileError;
+use crate::scene::ValidationFinding;
+
+/// The error type of the crate's fallible APIs.
+///
+/// New variants may be added, so matches on it need a wildcard arm.
+///
+/// # Migrating from earlier versions
+///
+/// The `Importer` read methods and `Importer::apply_postprocessing` used to return `Err(&str)`.
+/// They now return `Err(Error)`. When Assimp fails, its `Display` output is Assimp's message as
+/// before, so code that only printed the error keeps working, and `error.to_string()` gives an
+/// owned message where one is needed.
+///
+/// The `TryFrom<u32>` conversions of Assimp's enums, e.g. `LightType` or `BlendOp`, return
+/// `Error::Unsupported` instead of `()`. `File::read`, `File::write` and `File::seek` return an
+/// `io::Error` instead of `()`, which `Importer::read_file_with_io` returns as `Error::Io`
+/// if the import fails. The source of that `Error::Io` is an `io::FileError` with the path
+/// of the file, whose source is the original error.
+///
+/// Errors of the algorithms under `scene`, such as `strips::StripError`, `joints::JointError`
+/// or `binary::BinaryError`, are their own types with a variant for each way the input can be
+/// wrong, and so are `TimeoutError` and `InlineString`'s `StringTooLong`.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// Assimp couldn't import a file, or couldn't post-process the scene, with the message it
+    /// gave.
+    ImportFailed { message: String },
+    /// Assimp failed an import because `Importer::validate_data_structure` found errors in the
+    /// scene. `message` is Assimp's message, and `findings` everything the validation found,
+    /// warnings included.
+    ValidationFailed {
+        message: String,
+        findings: Vec<ValidationFinding>,
+    },
+    /// Writing something out failed, e.g. encoding an embedded texture in
+    /// `Scene::extract_embedded_textures`.
+    ExportFailed { message: String },
+    /// The arguments or configuration can't be used, e.g. a path containing a nul byte.
+    InvalidConfig { message: String },
+    /// Reading or writing a file failed.
+    Io(io::Error),
+    /// A string from the scene isn't valid UTF-8.
+    Utf8(Utf8Error),
+    /// An index stored in the scene refers past the end of the array it indexes. `what` is
+    /// the name of the array, e.g. `"textures"`.
+    IndexOutOfRange {
+        what: &'static str,
+        index: usize,
+        len: usize,
+    },
+    /// Something the scene should contain isn't there.
+    MissingData { what: String },
+    /// A value isn't one this version of the crate knows about, e.g. an enum value from a
+    /// newer version of Assimp.
+    Unsupported { what: String },
+    /// An import read more than a `ByteBudgetFileIO` allows, or a stream passed to
+    /// `Importer::read_from_reader` is longer than its `SpoolPolicy` allows. `what` is the
+    /// limit that was hit, `"bytes read"`, `"file size"`, `"files opened"` or `"stream size"`.
+    BudgetExceeded {
+        what: &'static str,
+        limit: u64,
+        attempted: u64,
+    },
+    /// A file wasn't imported because `Importer::allowed_formats` or
+    /// `Importer::denied_formats` refuse its format. `format` is the extension of the format,
+    /// or `None` if the format couldn't be told and only some formats are allowed.
+    FormatNotAllowed { format: Option<String> },
+    /// An imported scene is larger than `Importer::limits` allows. `what` is the cap that was
+    /// exceeded, `"meshes"`, `"vertices"`, `"nodes"` or `"texture bytes"`, and `found` how
+    /// many were counted before counting stopped, which is more than `limit` but may be less
+    /// than the scene has.
+    LimitExceeded {
+        what: &'static str,
+        limit: u64,
+        found: u64,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::ImportFailed { message } => write!(f, "{}", message),
+            Error::ValidationFailed { message, .. } => write!(f, "{}", message),
+            Error::ExportFailed { message } => write!(f, "{}", message),
+            Error::InvalidConfig { message } => write!(f, "{}", message),
+            Error::Io(err) => write!(f, "I/O error: {}", err),
+            Error::Utf8(err) => write!(f, "string isn't valid UTF-8: {}", err),
+            Error::IndexOutOfRange { what, index, len } => {
+                write!(f, "index {} is out of range for {} {}", index, len, what)
+            }
+            Error::MissingData { what } => write!(f, "missing {}", what),
+            Error::Unsupported { what } => write!(f, "unsupported {}", what),
+            Error::BudgetExceeded {
+                what,
+                limit,
+                attempted,
+            } => {
+                write!(f, "{} exceeded the budget: {} > {}", what, attempted, limit)
+            }
+            Error::FormatNotAllowed {
+                format: Some(format),
+            } => {
+                write!(f, "importing .{} files isn't allowed", format)
+            }
+            Error::FormatNotAllowed { format: None } => {
+                write!(
+                    f,
+                    "the file's format is unknown, and only some formats are allowed"
+                )
+            }
+            Error::LimitExceeded { what, limit, found } => {
+                write!(
+                    f,
+                    "the scene has more {} than the limit: {} > {}",
+                    what, found, limit
+                )
+            }
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            // Skip the `io::Error` that carries the context of a failed import, so the context
+            // is the source rather than the source of the source.
+            Error::Io(err) => match err
+                .get_ref()
+                .and_then(|err| err.downcast_ref::<FileError>())
+            {
+                Some(file_error) => Some(file_error),
+                None => Some(err),
+            },
+            Error::Utf8(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<Utf8Error> for Error {
+    fn from(err: Utf8Error) -> Self {
+        Error::Utf8(err)
+    }
+}
+
+/// Strings passed to Assimp can't contain nul bytes.
+impl From<NulError> for Error {
+    fn from(err: NulError) -> Self {
+        Error::InvalidConfig {
+            message: format!("string contains a nul byte: {}", err),
+        }
+    }
+}
+
+impl Error {
+    pub(crate) fn unsupported(what: &str, value: u32) -> Error {
+        Error::Unsupported {
+            what: format!("{} {}", what, value),
+        }
+    }
+}
@@ -1 +1,345 @@
-//! The `export` module implements functionality for exporting scenes (not yet implemented).
+//! The `export` module writes scenes to files, see `write_snapshot`.
+//!
+//! Assimp's own exporters aren't wrapped yet. The formats here are written by the crate from a
+//! `SceneSnapshot`, so they include any changes made to the snapshot, e.g. by
+//! `scene::convert::axis_convert` or `scene::prune::prune`.
+
+use std::io::Write;
+
+use crate::math::{Color4D, Matrix4x4, Vector3D};
+use crate::scene::{MeshData, NodeData, SceneSnapshot};
+use crate::Error;
+
+/// The format ids that `write_snapshot` supports, which are also the file extensions of the
+/// formats.
+pub const FORMATS: &[&str] = &["obj", "ply"];
+
+/// Something `write_snapshot` left out because the format has no place for it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExportWarning {
+    /// `count` items of `what` weren't written, e.g. `"animations"`.
+    Dropped { what: &'static str, count: usize },
+}
+
+/// Write `snapshot` to `out` in the format `format`, one of `FORMATS`, and return what had to
+/// be left out.
+///
+/// Both formats are written as text, and hold geometry only. Every mesh is written once for
+/// each node that refers to it, with the world transform of the node applied, so the written
+/// file has no hierarchy. Meshes that no node refers to aren't written. Normals, the first UV
+/// channel and, for PLY, the first color set are written if every written mesh has them. OBJ
+/// files get one object per mesh instance, named after the mesh, and PLY files a single mesh.
+///
+/// Unknown formats give `Error::Unsupported`, faces that refer past the vertices of their mesh
+/// `Error::IndexOutOfRange`, and failed writes `Error::Io`. `out` may have been partly written
+/// when an error is returned.
+pub fn write_snapshot<W: Write>(
+    snapshot: &SceneSnapshot,
+    format: &str,
+    out: &mut W,
+) -> Result<Vec<ExportWarning>, Error> {
+    if !FORMATS.contains(&format) {
+        return Err(Error::Unsupported {
+            what: format!("export format {:?}", format),
+        });
+    }
+
+    let mut instances = Vec::new();
+    if let Some(root) = &snapshot.root {
+        collect_instances(snapshot, root, root.transform, &mut instances);
+    }
+    for (mesh, _) in &instances {
+        check_faces(mesh)?;
+    }
+    let layout = Layout::new(&instances);
+
+    match format {
+        "obj" => write_obj(&instances, layout, out)?,
+        _ => write_ply(&instances, layout, out)?,
+    }
+    out.flush()?;
+
+    Ok(warnings(snapshot, &instances, layout, format))
+}
+
+/// The vertex attributes that every written mesh has, and so get written.
+#[derive(Debug, Copy, Clone)]
+struct Layout {
+    normals: bool,
+    uvs: bool,
+    colors: bool,
+}
+
+impl Layout {
+    fn new(instances: &[(&MeshData, Matrix4x4)]) -> Layout {
+        let all = |has: &dyn Fn(&MeshData) -> bool| instances.iter().all(|(mesh, _)| has(mesh));
+        Layout {
+            normals: all(&|mesh| mesh.normals.len() == mesh.positions.len()),
+            uvs: all(&|mesh| uv_channel(mesh).is_some()),
+            colors: all(&|mesh| color_set(mesh).is_some()),
+        }
+    }
+}
+
+fn collect_instances<'a>(
+    snapshot: &'a SceneSnapshot,
+    node: &NodeData,
+    world: Matrix4x4,
+    instances: &mut Vec<(&'a MeshData, Matrix4x4)>,
+) {
+    for &index in &node.meshes {
+        if let Some(mesh) = snapshot.meshes.get(index as usize) {
+            instances.push((mesh, world));
+        }
+    }
+    for child in &node.children {
+        collect_instances(snapshot, child, world * child.transform, instances);
+    }
+}
+
+fn check_faces(mesh: &MeshData) -> Result<(), Error> {
+    let len = mesh.positions.len();
+    for face in &mesh.faces {
+        if let Some(&index) = face.iter().find(|&&index| index as usize >= len) {
+            return Err(Error::IndexOutOfRange {
+                what: "vertices",
+                index: index as usize,
+                len,
+            });
+        }
+    }
+    Ok(())
+}
+
+fn uv_channel(mesh: &MeshData) -> Option<&[Vector3D]> {
+    match mesh.texture_coords.first() {
+        Some(Some(uvs)) if uvs.len() == mesh.positions.len() => Some(uvs),
+        _ => None,
+    }
+}
+
+fn color_set(mesh: &MeshData) -> Option<&[Color4D]> {
+    match mesh.colors.first() {
+        Some(Some(colors)) if colors.len() == mesh.positions.len() => Some(colors),
+        _ => None,
+    }
+}
+
+/// Whether `world` mirrors, in which case faces are reversed to keep them facing outwards.
+fn mirrors(world: &Matrix4x4) -> bool {
+    let a = Vector3D::new(world.a1, world.a2, world.a3);
+    let b = Vector3D::new(world.b1, world.b2, world.b3);
+    let c = Vector3D::new(world.c1, world.c2, world.c3);
+    a.dot(b.cross(c)) < 0.0
+}
+
+/// The vertices of `face` in the order they're written, see `mirrors`.
+fn face_order(face: &[u32], mirrored: bool) -> Box<dyn Iterator<Item = u32> + '_> {
+    if mirrored {
+        Box::new(face.iter().rev().copied())
+    } else {
+        Box::new(face.iter().copied())
+    }
+}
+
+fn write_obj<W: Write>(
+    instances: &[(&MeshData, Matrix4x4)],
+    layout: Layout,
+    out: &mut W,
+) -> Result<(), Error> {
+    // OBJ indices are 1-based and count every vertex before them in the file.
+    let mut first = 1;
+    for (mesh, world) in instances {
+        let normal_matrix = world.normal_matrix();
+        let mirrored = mirrors(world);
+        let name = if mesh.name.trim().is_empty() {
+            "mesh"
+        } else {
+            mesh.name.trim()
+        };
+        writeln!(out, "o {}", name)?;
+        for p in &mesh.positions {
+            let p = world.transform_point(*p);
+            writeln!(out, "v {} {} {}", p.x, p.y, p.z)?;
+        }
+        if let (true, Some(uvs)) = (layout.uvs, uv_channel(mesh)) {
+            for uv in uvs {
+                writeln!(out, "vt {} {}", uv.x, uv.y)?;
+            }
+        }
+        if layout.normals {
+            for n in &mesh.normals {
+                let n = normal_matrix.transform_vector(*n).normalize();
+                writeln!(out, "vn {} {} {}", n.x, n.y, n.z)?;
+            }
+        }
+
+        for face in &mesh.faces {
+            let keyword = match face.len() {
+                0 => continue,
+                1 => "p",
+                2 => "l",
+                _ => "f",
+            };
+            write!(out, "{}", keyword)?;
+            for index in face_order(face, mirrored) {
+                let index = first + index as usize;
+                match (keyword, layout.uvs, layout.normals) {
+                    ("f", true, true) => write!(out, " {0}/{0}/{0}", index)?,
+                    ("f", false, true) => write!(out, " {0}//{0}", index)?,
+                    ("f", true, false) | ("l", true, _) => write!(out, " {0}/{0}", index)?,
+                    _ => write!(out, " {}", index)?,
+                }
+            }
+            writeln!(out)?;
+        }
+        first += mesh.positions.len();
+    }
+    Ok(())
+}
+
+fn write_ply<W: Write>(
+    instances: &[(&MeshData, Matrix4x4)],
+    layout: Layout,
+    out: &mut W,
+) -> Result<(), Error> {
+    let vertices: usize = instances.iter().map(|(mesh, _)| mesh.positions.len()).sum();
+    let faces: usize = instances.iter().map(|(mesh, _)| mesh.faces.len()).sum();
+    if let Some(face) = instances
+        .iter()
+        .flat_map(|(mesh, _)| &mesh.faces)
+        .find(|face| face.len() > u8::MAX as usize)
+    {
+        return Err(Error::Unsupported {
+            what: format!("PLY face with {} vertices", face.len()),
+        });
+    }
+
+    writeln!(out, "ply\nformat ascii 1.0")?;
+    writeln!(out, "element vertex {}", vertices)?;
+    writeln!(out, "property float x\nproperty float y\nproperty float z")?;
+    if layout.normals {
+        writeln!(
+            out,
+            "property float nx\nproperty float ny\nproperty float nz"
+        )?;
+    }
+    if layout.uvs {
+        writeln!(out, "property float s\nproperty float t")?;
+    }
+    if layout.colors {
+        writeln!(out, "property uchar red\nproperty uchar green")?;
+        writeln!(out, "property uchar blue\nproperty uchar alpha")?;
+    }
+    writeln!(out, "element face {}", faces)?;
+    writeln!(out, "property list uchar int vertex_indices\nend_header")?;
+
+    for (mesh, world) in instances {
+        let normal_matrix = world.normal_matrix();
+        for (i, p) in mesh.positions.iter().enumerate() {
+            let p = world.transform_point(*p);
+            write!(out, "{} {} {}", p.x, p.y, p.z)?;
+            if layout.normals {
+                let n = normal_matrix.transform_vector(mesh.normals[i]).normalize();
+                write!(out, " {} {} {}", n.x, n.y, n.z)?;
+            }
+            if let (true, Some(uvs)) = (layout.uvs, uv_channel(mesh)) {
+                write!(out, " {} {}", uvs[i].x, uvs[i].y)?;
+            }
+            if let (true, Some(colors)) = (layout.colors, color_set(mesh)) {
+                let c = colors[i];
+                let byte = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+                write!(
+                    out,
+                    " {} {} {} {}",
+                    byte(c.r),
+                    byte(c.g),
+                    byte(c.b),
+                    byte(c.a)
+                )?;
+            }
+            writeln!(out)?;
+        }
+    }
+
+    let mut first = 0;
+    for (mesh, world) in instances {
+        let mirrored = mirrors(world);
+        for face in &mesh.faces {
+            write!(out, "{}", face.len())?;
+            for index in face_order(face, mirrored) {
+                write!(out, " {}", first + index as usize)?;
+            }
+            writeln!(out)?;
+        }
+        first += mesh.positions.len();
+    }
+    Ok(())
+}
+
+fn warnings(
+    snapshot: &SceneSnapshot,
+    instances: &[(&MeshData, Matrix4x4)],
+    layout: Layout,
+    format: &str,
+) -> Vec<ExportWarning> {
+    let meshes = || instances.iter().map(|(mesh, _)| mesh);
+    let count = |has: &dyn Fn(&MeshData) -> bool| meshes().filter(|mesh| has(mesh)).count();
+
+    let mut dropped = vec![
+        ("animations", snapshot.animations.len()),
+        ("cameras", snapshot.cameras.len()),
+        ("lights", snapshot.lights.len()),
+        ("embedded textures", snapshot.textures.len()),
+        ("bones", meshes().map(|mesh| mesh.bones.len()).sum()),
+        ("tangents", count(&|mesh| !mesh.tangents.is_empty())),
+        (
+            "UV channels",
+            meshes()
+                .map(|mesh| {
+                    let channels = mesh
+                        .texture_coords
+                        .iter()
+                        .filter(|uvs| uvs.is_some())
+                        .count();
+                    channels.saturating_sub(layout.uvs as usize)
+                })
+                .sum(),
+        ),
+        (
+            "color sets",
+            meshes()
+                .map(|mesh| {
+                    let sets = mesh.colors.iter().filter(|colors| colors.is_some()).count();
+                    sets.saturating_sub((layout.colors && format == "ply") as usize)
+                })
+                .sum(),
+        ),
+    ];
+    if !layout.normals {
+        dropped.push(("normals", count(&|mesh| !mesh.normals.is_empty())));
+    }
+    // No MTL file is written for OBJ files.
+    dropped.push(("materials", snapshot.materials.len()));
+    let unused = snapshot
+        .meshes
+        .len()
+        .saturating_sub(unique_meshes(instances));
+    dropped.push(("unused meshes", unused));
+
+    dropped
+        .into_iter()
+        .filter(|&(_, count)| count > 0)
+        .map(|(what, count)| ExportWarning::Dropped { what, count })
+        .collect()
+}
+
+fn unique_meshes(instances: &[(&MeshData, Matrix4x4)]) -> usize {
+    let mut seen: Vec<*const MeshData> = instances
+        .iter()
+        .map(|(mesh, _)| *mesh as *const _)
+        .collect();
+    seen.sort();
+    seen.dedup();
+    seen.len()
+}
@@ -0,0 +1,293 @@
+//! Small models with known contents, for the tests of crates that use this one. Enable the
+//! `test-fixtures` feature in your dev-dependencies to use them:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! open-asset-importer = { version = "0.2", features = ["test-fixtures"] }
+//! ```
+//!
+//! The files are embedded in the crate, so the fixtures work from any directory. Each one has
+//! a constant with its bytes, a function that imports it and a `FixtureCounts` constant with
+//! what the imported scene contains. The functions panic if the import fails, which means
+//! that Assimp was built without the importer for the format.
+//!
+//! The `_with` functions import a fixture with the post-processing of a given `Importer`, for
+//! tests of the steps themselves.
+//!
+//! `fuzz_read_from_memory` is the body of the crate's fuzz target, in `fuzz/`, so the crate's
+//! tests can replay its seeds and regression inputs without cargo-fuzz.
+
+use crate::import::ImportLimits;
+use crate::io::MemoryFileIO;
+use crate::scene::HashOptions;
+use crate::{Importer, Scene};
+
+/// A unit cube centered on the origin, as an OBJ file with a UV and a normal for each corner
+/// of each face and no material library.
+pub const UNIT_CUBE_OBJ: &[u8] = include_bytes!("../examples/unit_cube.obj");
+
+/// A glTF file with a unit quad skinned to two joints, `Root` and its child `Tip`, and one
+/// animation, `Bend`, that rotates `Tip`. The two bottom vertices are weighted to `Root` and
+/// the two top ones to `Tip`.
+pub const SKINNED_QUAD_GLTF: &[u8] = include_bytes!("../examples/skinned_quad.gltf");
+
+/// An OBJ file with two quads, the first with the material `Red` and the second with `Blue`
+/// from `TWO_MATERIAL_MTL`.
+pub const TWO_MATERIAL_OBJ: &[u8] = include_bytes!("../examples/two_materials.obj");
+
+/// The material library of `TWO_MATERIAL_OBJ`, which it refers to as `two_materials.mtl`.
+pub const TWO_MATERIAL_MTL: &[u8] = include_bytes!("../examples/two_materials.mtl");
+
+/// A binary glTF file with a textured triangle, whose material `Painted` uses a PNG that's
+/// embedded in the file.
+pub const EMBEDDED_TEXTURE_GLB: &[u8] = include_bytes!("../examples/embedded_texture.glb");
+
+/// What a fixture contains once imported, for tests to assert against.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct FixtureCounts {
+    pub meshes: u32,
+    /// The number of vertices of all meshes.
+    pub vertices: u32,
+    /// The number of faces of all meshes.
+    pub faces: u32,
+    pub materials: u32,
+    /// The number of bones of all meshes.
+    pub bones: u32,
+    pub animations: u32,
+    pub textures: u32,
+}
+
+impl FixtureCounts {
+    /// Count what `scene` contains, to compare with the constants.
+    pub fn of(scene: &Scene) -> FixtureCounts {
+        let meshes = || scene.meshes();
+        FixtureCounts {
+            meshes: scene.num_meshes(),
+            vertices: meshes().map(|mesh| mesh.num_vertices()).sum(),
+            faces: meshes().map(|mesh| mesh.num_faces()).sum(),
+            materials: scene.num_materials(),
+            bones: meshes().map(|mesh| mesh.num_bones()).sum(),
+            animations: scene.num_animations(),
+            textures: scene.num_textures(),
+        }
+    }
+}
+
+/// The contents of `unit_cube`: four vertices and two triangles for each side, and the
+/// default material that the OBJ importer adds.
+pub const UNIT_CUBE: FixtureCounts = FixtureCounts {
+    meshes: 1,
+    vertices: 24,
+    faces: 12,
+    materials: 1,
+    bones: 0,
+    animations: 0,
+    textures: 0,
+};
+
+/// The contents of `skinned_quad`. The glTF importer adds a default material after `Skin`.
+pub const SKINNED_QUAD: FixtureCounts = FixtureCounts {
+    meshes: 1,
+    vertices: 4,
+    faces: 2,
+    materials: 2,
+    bones: 2,
+    animations: 1,
+    textures: 0,
+};
+
+/// The contents of `two_material_obj`: a mesh for each quad, each split into two triangles,
+/// and `Red` and `Blue` after the default material that the OBJ importer adds.
+pub const TWO_MATERIAL: FixtureCounts = FixtureCounts {
+    meshes: 2,
+    vertices: 8,
+    faces: 4,
+    materials: 3,
+    bones: 0,
+    animations: 0,
+    textures: 0,
+};
+
+/// The contents of `embedded_texture_glb`. The glTF importer adds a default material after
+/// `Painted`.
+pub const EMBEDDED_TEXTURE: FixtureCounts = FixtureCounts {
+    meshes: 1,
+    vertices: 3,
+    faces: 1,
+    materials: 2,
+    bones: 0,
+    animations: 0,
+    textures: 1,
+};
+
+/// Import `UNIT_CUBE_OBJ`, triangulated, with identical vertices joined. See `UNIT_CUBE`.
+pub fn unit_cube() -> Scene<'static> {
+    let mut importer = Importer::new();
+    importer.triangulate(true);
+    importer.join_identical_vertices(true);
+    unit_cube_with(&importer)
+}
+
+/// Import `UNIT_CUBE_OBJ` with the post-processing of `importer`. Without any, the cube has a
+/// quad for each side, and its 24 vertices.
+pub fn unit_cube_with(importer: &Importer) -> Scene<'static> {
+    importer
+        .read_memory_with_hint(UNIT_CUBE_OBJ, "obj")
+        .expect("failed to import the unit cube fixture")
+}
+
+/// Import `SKINNED_QUAD_GLTF`, triangulated. See `SKINNED_QUAD`.
+pub fn skinned_quad() -> Scene<'static> {
+    let mut importer = Importer::new();
+    importer.triangulate(true);
+    skinned_quad_with(&importer)
+}
+
+/// Import `SKINNED_QUAD_GLTF` with the post-processing of `importer`.
+pub fn skinned_quad_with(importer: &Importer) -> Scene<'static> {
+    importer
+        .read_memory_with_hint(SKINNED_QUAD_GLTF, "gltf")
+        .expect("failed to import the skinned quad fixture")
+}
+
+/// Import `TWO_MATERIAL_OBJ`, triangulated. The OBJ importer has to open the material
+/// library, so this imports through `file_io` rather than with `Importer::read_memory`. See
+/// `TWO_MATERIAL`.
+pub fn two_material_obj() -> Scene<'static> {
+    let mut importer = Importer::new();
+    importer.triangulate(true);
+    two_material_obj_with(&importer)
+}
+
+/// Import `TWO_MATERIAL_OBJ` with the post-processing of `importer`. Without any, each quad
+/// stays one face.
+pub fn two_material_obj_with(importer: &Importer) -> Scene<'static> {
+    importer
+        .read_file_with_io("two_materials.obj", &file_io())
+        .expect("failed to import the two material fixture")
+}
+
+/// Import `EMBEDDED_TEXTURE_GLB`. See `EMBEDDED_TEXTURE`.
+pub fn embedded_texture_glb() -> Scene<'static> {
+    embedded_texture_glb_with(&Importer::new())
+}
+
+/// Import `EMBEDDED_TEXTURE_GLB` with the post-processing of `importer`.
+pub fn embedded_texture_glb_with(importer: &Importer) -> Scene<'static> {
+    importer
+        .read_memory_with_hint(EMBEDDED_TEXTURE_GLB, "glb")
+        .expect("failed to import the embedded texture fixture")
+}
+
+/// A `MemoryFileIO` with the fixture files, named `unit_cube.obj`, `skinned_quad.gltf`,
+/// `two_materials.obj`, `two_materials.mtl` and `embedded_texture.glb`, for tests of the
+/// `_with_io` methods.
+pub fn file_io() -> MemoryFileIO {
+    MemoryFileIO::new()
+        .with_file("unit_cube.obj", UNIT_CUBE_OBJ)
+        .with_file("skinned_quad.gltf", SKINNED_QUAD_GLTF)
+        .with_file("two_materials.obj", TWO_MATERIAL_OBJ)
+        .with_file("two_materials.mtl", TWO_MATERIAL_MTL)
+        .with_file("embedded_texture.glb", EMBEDDED_TEXTURE_GLB)
+}
+
+/// Whether `scene` is currently owned by a `Scene` on this thread, for tests of
+/// `Scene::from_raw` and `Scene::into_raw`. Ownership is only tracked in debug builds, so this
+/// always returns `false` in release builds.
+pub fn is_owned_scene(scene: *const ffi::aiScene) -> bool {
+    crate::scene::is_owned_scene(scene)
+}
+
+/// The format hints `fuzz_read_from_memory` picks from, the empty hint first, which lets
+/// Assimp tell the format from the data.
+pub const FUZZ_HINTS: &[&str] = &[
+    "", "obj", "ply", "stl", "dae", "gltf", "glb", "fbx", "3ds", "x", "md2", "md3", "mdl", "blend",
+    "assbin", "off",
+];
+
+/// Import `input` as untrusted data and walk the scene with `walk`. The first byte picks the
+/// format hint from `FUZZ_HINTS`, modulo their number, and the rest is the data, which is
+/// imported with `Importer::read_from_memory` and `ImportLimits::untrusted`. Import errors are
+/// ignored, so this only fails by panicking or crashing.
+pub fn fuzz_read_from_memory(input: &[u8]) {
+    let (&selector, data) = match input.split_first() {
+        Some(split) => split,
+        None => return,
+    };
+    let hint = FUZZ_HINTS[selector as usize % FUZZ_HINTS.len()];
+
+    let mut importer = Importer::new();
+    importer.set_limits(ImportLimits::untrusted());
+    if let Ok(scene) = importer.read_from_memory(data, hint) {
+        walk(&scene);
+    }
+}
+
+/// Read everything in `scene` through the accessors that check for null pointers, lengths and
+/// UTF-8, the way a program handling untrusted files should, then take a snapshot and a
+/// content hash, which read the rest. The results are thrown away: this is for finding the
+/// accessors that panic or read out of bounds.
+pub fn walk(scene: &Scene) {
+    if let Some(root) = scene.root_node() {
+        for node in root.descendants() {
+            let _ = node.try_name();
+            let _ = node.transform();
+            let _ = node.meshes();
+            for (key, value) in node.metadata() {
+                let _ = key.to_str();
+                let _ = value.get();
+            }
+        }
+    }
+
+    for mesh in scene.meshes() {
+        let _ = mesh.try_name();
+        let _ = mesh.primitive_types();
+        let _ = mesh.vertices().count();
+        for set in 0..8 {
+            let _ = mesh.vertex_colors(set).count();
+            let _ = mesh.texture_coords(set).count();
+        }
+        for face in mesh.faces() {
+            let _ = face.indices();
+        }
+        for bone in mesh.bones() {
+            let _ = bone.try_name();
+            let _ = bone.weights().count();
+        }
+    }
+
+    for material in scene.materials() {
+        for property in material.properties() {
+            let _ = property.try_key();
+            let _ = property.data();
+        }
+    }
+
+    for animation in scene.animations() {
+        let _ = animation.try_name();
+        for channel in animation.node_anims() {
+            let _ = channel.try_node_name();
+            let _ = channel.position_keys().count();
+            let _ = channel.rotation_keys().count();
+            let _ = channel.scaling_keys().count();
+        }
+    }
+
+    for texture in scene.textures() {
+        let _ = texture.try_format_hint();
+        let _ = texture.try_filename();
+        let _ = texture.data().map(|data| data.bytes().len());
+    }
+
+    for camera in scene.camera() {
+        let _ = camera.try_name();
+    }
+
+    for light in scene.light() {
+        let _ = light.try_name();
+    }
+
+    let _ = scene.snapshot();
+    let _ = scene.content_hash(HashOptions::default());
+}
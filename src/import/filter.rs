@@ -0,0 +1,154 @@
+//! Refusing files by format before Assimp reads them, see `Importer::allowed_formats`.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+use crate::io::FileIO;
+use crate::Error;
+
+use super::identify::{magic_format, MAGIC_LEN};
+use super::Importer;
+
+/// The formats an importer may import, as lowercase extensions without a dot.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub(super) struct FormatFilter {
+    /// `None` if every format that isn't denied is allowed.
+    allowed: Option<BTreeSet<String>>,
+    denied: BTreeSet<String>,
+}
+
+impl FormatFilter {
+    fn is_active(&self) -> bool {
+        self.allowed.is_some() || !self.denied.is_empty()
+    }
+
+    fn check(&self, format: Option<&str>) -> Result<(), Error> {
+        let allowed = match format {
+            Some(format) => {
+                !self.denied.contains(format)
+                    && self
+                        .allowed
+                        .as_ref()
+                        .map_or(true, |allowed| allowed.contains(format))
+            }
+            None => self.allowed.is_none(),
+        };
+        if allowed {
+            Ok(())
+        } else {
+            Err(Error::FormatNotAllowed {
+                format: format.map(str::to_owned),
+            })
+        }
+    }
+
+    /// Check the format of a file. `extension` is checked first, so that a file with a
+    /// refused extension is never read, then the format of its magic bytes, if `magic` gives
+    /// the start of the file.
+    fn check_file<F>(&self, extension: Option<&str>, magic: F) -> Result<(), Error>
+    where
+        F: FnOnce() -> Vec<u8>,
+    {
+        if !self.is_active() {
+            return Ok(());
+        }
+
+        let extension = extension
+            .map(normalize)
+            .filter(|extension| !extension.is_empty());
+        if let Some(extension) = &extension {
+            self.check(Some(extension))?;
+        }
+        match magic_format(&magic()) {
+            Some(format) => self.check(Some(format)),
+            None if extension.is_none() => self.check(None),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Importer {
+    /// Only import files in the formats of `list`, given as extensions with or without a
+    /// leading `.` or `*.`, e.g. `&["obj", "gltf", "glb"]`. The check isn't case-sensitive.
+    /// Replaces the list of an earlier call, and an empty list allows every format again.
+    ///
+    /// Every read method checks the format before Assimp sees the file, and returns
+    /// `Error::FormatNotAllowed` without importing it if it isn't allowed. The format is
+    /// checked twice: first by the extension of the file, or the hint for
+    /// `read_memory_with_hint`, before the file is read at all, and then by the magic bytes
+    /// of the binary formats that `identify_bytes` recognises, which reads the first few
+    /// bytes of the file. So a `.txt` file is refused without being read, and so is a binary
+    /// glTF file named `.obj`. Files whose format can't be told either way are refused as
+    /// well, since they can't be shown to be in an allowed format.
+    ///
+    /// Assimp has no properties to turn individual importers off, so the filter is only
+    /// enforced by the crate.
+    pub fn allowed_formats(&mut self, list: &[&str]) {
+        self.format_filter.allowed = if list.is_empty() {
+            None
+        } else {
+            Some(list.iter().map(|format| normalize(format)).collect())
+        };
+    }
+
+    /// Never import files in the formats of `list`, which take precedence over
+    /// `allowed_formats`. Files whose format can't be told are only refused if there's an
+    /// allowed list. Otherwise this works like `allowed_formats`, and an empty list denies
+    /// nothing.
+    pub fn denied_formats(&mut self, list: &[&str]) {
+        self.format_filter.denied = list.iter().map(|format| normalize(format)).collect();
+    }
+
+    /// Check the format of `file` for `read_file`.
+    pub(super) fn check_path_format(&self, file: &Path) -> Result<(), Error> {
+        let extension = file
+            .extension()
+            .map(|extension| extension.to_string_lossy());
+        self.format_filter.check_file(extension.as_deref(), || {
+            let mut data = Vec::new();
+            if let Ok(file) = fs::File::open(file) {
+                let _ = file.take(MAGIC_LEN).read_to_end(&mut data);
+            }
+            data
+        })
+    }
+
+    /// Check the format of `file` for `read_file_with_io`, reading its start through
+    /// `file_io` if the extension is allowed.
+    pub(super) fn check_io_format<T: FileIO>(&self, file: &str, file_io: &T) -> Result<(), Error> {
+        let extension = Path::new(file)
+            .extension()
+            .and_then(|extension| extension.to_str());
+        self.format_filter.check_file(extension, || {
+            let mut data = vec![0; MAGIC_LEN as usize];
+            let mut len = 0;
+            if let Some(mut opened) = file_io.open(file, "rb") {
+                while len < data.len() {
+                    match opened.read(&mut data[len..]) {
+                        Ok(0) | Err(_) => break,
+                        Ok(read) => len += read,
+                    }
+                }
+                opened.close();
+            }
+            data.truncate(len);
+            data
+        })
+    }
+
+    /// Check the format of `data` for `read_memory_with_hint`.
+    pub(super) fn check_memory_format(&self, data: &[u8], hint: &str) -> Result<(), Error> {
+        let start = &data[..data.len().min(MAGIC_LEN as usize)];
+        self.format_filter.check_file(Some(hint), || start.to_vec())
+    }
+}
+
+/// `format` in lowercase, without a leading `.` or `*.`.
+fn normalize(format: &str) -> String {
+    format
+        .trim_start_matches('*')
+        .trim_start_matches('.')
+        .to_ascii_lowercase()
+}
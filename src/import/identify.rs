@@ -0,0 +1,137 @@
+//! Guessing the format of a file without importing it, see `Importer::identify`.
+
+use std::ffi::CString;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+use ffi::{aiIsExtensionSupported, AI_TRUE};
+
+use crate::Error;
+
+use super::Importer;
+
+/// The magic bytes at the start of the binary formats that `identify` recognises, and the
+/// extension of each format.
+const MAGIC: [(&[u8], &str); 4] = [
+    (b"glTF", "glb"),
+    (b"Kaydara FBX Binary  \0", "fbx"),
+    (b"BLENDER", "blend"),
+    (b"ASSIMP.binary-dump.", "assbin"),
+];
+
+/// How many bytes `identify` reads from the start of a file, enough for every entry of
+/// `MAGIC`.
+pub(super) const MAGIC_LEN: u64 = 32;
+
+/// How sure `Importer::identify` is about `IdentifyResult::likely_format`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Confidence {
+    /// Nothing is known about the format.
+    Unknown,
+    /// The format is only a guess from the file's extension.
+    Extension,
+    /// The file starts with the magic bytes of the format.
+    Magic,
+}
+
+/// What `Importer::identify` found out about a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdentifyResult {
+    /// The extension of the format the file seems to be in, in lowercase and without a dot,
+    /// e.g. `"glb"`, or `None` if nothing is known.
+    pub likely_format: Option<String>,
+    /// Whether Assimp has an importer for `likely_format`. This doesn't mean the import will
+    /// succeed.
+    pub supported: bool,
+    pub confidence: Confidence,
+}
+
+impl IdentifyResult {
+    fn unknown() -> IdentifyResult {
+        IdentifyResult {
+            likely_format: None,
+            supported: false,
+            confidence: Confidence::Unknown,
+        }
+    }
+}
+
+impl Importer {
+    /// Guess the format of `file` from its first few bytes and its extension, and whether it
+    /// can be imported, without importing it. See `identify_bytes`.
+    ///
+    /// Returns `Error::Io` if the file can't be read.
+    pub fn identify<P: AsRef<Path>>(file: P) -> Result<IdentifyResult, Error> {
+        let file = file.as_ref();
+        let mut data = Vec::new();
+        fs::File::open(file)?
+            .take(MAGIC_LEN)
+            .read_to_end(&mut data)?;
+
+        let extension = file.extension().and_then(|extension| extension.to_str());
+        Ok(identify(&data, extension))
+    }
+
+    /// Guess the format of `data`, the contents or at least the start of a file, and whether
+    /// it can be imported, without importing it. `name` is the file's name or extension, if
+    /// it's known, e.g. `"model.obj"` or `"obj"`.
+    ///
+    /// The binary formats glTF (`.glb`), FBX, Blender and Assimp's own `.assbin` are
+    /// recognised by their magic bytes, whatever the extension. Other files are assumed to be
+    /// in the format of their extension. Empty data is always `Confidence::Unknown`.
+    pub fn identify_bytes(data: &[u8], name: Option<&str>) -> IdentifyResult {
+        let extension = name.map(|name| match Path::new(name).extension() {
+            Some(extension) => extension.to_str().unwrap_or(""),
+            None => name.trim_start_matches('.'),
+        });
+        identify(data, extension)
+    }
+
+    /// Whether Assimp has an importer for files with `extension`, which may be given with or
+    /// without a leading `.` or `*.`, e.g. `"obj"`, `".obj"` or `"*.obj"`. The check isn't
+    /// case-sensitive.
+    pub fn supports_extension(extension: &str) -> bool {
+        let extension = extension.trim_start_matches('*').trim_start_matches('.');
+        if extension.is_empty() {
+            return false;
+        }
+        match CString::new(format!(".{}", extension)) {
+            Ok(extension) => unsafe {
+                aiIsExtensionSupported(extension.as_ptr()) == AI_TRUE as i32
+            },
+            Err(_) => false,
+        }
+    }
+}
+
+fn identify(data: &[u8], extension: Option<&str>) -> IdentifyResult {
+    if data.is_empty() {
+        return IdentifyResult::unknown();
+    }
+
+    if let Some(format) = magic_format(data) {
+        return IdentifyResult {
+            likely_format: Some(format.to_owned()),
+            supported: Importer::supports_extension(format),
+            confidence: Confidence::Magic,
+        };
+    }
+
+    match extension.filter(|extension| !extension.is_empty()) {
+        Some(extension) => IdentifyResult {
+            likely_format: Some(extension.to_ascii_lowercase()),
+            supported: Importer::supports_extension(extension),
+            confidence: Confidence::Extension,
+        },
+        None => IdentifyResult::unknown(),
+    }
+}
+
+/// The extension of the format whose magic bytes `data` starts with, if any.
+pub(super) fn magic_format(data: &[u8]) -> Option<&'static str> {
+    MAGIC
+        .iter()
+        .find(|(magic, _)| data.starts_with(magic))
+        .map(|&(_, format)| format)
+}
@@ -0,0 +1,269 @@
+//! Timing of imports, see `Importer::instrumented`.
+
+use std::cell::Cell;
+use std::io;
+use std::ptr::NonNull;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use ffi::*;
+
+use crate::io::{File, FileIO, SeekFrom};
+use crate::scene::{ImportDiagnostics, OptimizeMeshesReport, Ownership, Scene};
+use crate::Error;
+
+use super::structs::PostProcessSteps;
+use super::{last_error, Importer};
+
+/// The post-process steps in the order Assimp runs them, which is the order of
+/// `GetPostProcessingStepInstanceList` in Assimp's `PostStepRegistry.cpp`. Validation isn't
+/// here, since it runs as soon as the file is read. The handedness and UV conversions run
+/// first in Assimp 5, and don't affect the instancing and graph steps after them.
+const STEP_ORDER: [PostProcessSteps; 26] = [
+    PostProcessSteps::MAKE_LEFT_HANDED,
+    PostProcessSteps::FLIP_UVS,
+    PostProcessSteps::FLIP_WINDING_ORDER,
+    PostProcessSteps::REMOVE_COMPONENT,
+    PostProcessSteps::REMOVE_REDUNDANT_MATERIALS,
+    PostProcessSteps::FIND_INSTANCES,
+    PostProcessSteps::OPTIMIZE_GRAPH,
+    PostProcessSteps::OPTIMIZE_MESHES,
+    PostProcessSteps::GEN_UV_COORDS,
+    PostProcessSteps::TRANSFORM_UV_COORDS,
+    PostProcessSteps::GLOBAL_SCALE,
+    PostProcessSteps::PRE_TRANSFORM_VERTICES,
+    PostProcessSteps::TRIANGULATE,
+    PostProcessSteps::FIND_DEGENERATES,
+    PostProcessSteps::SORT_BY_PTYPE,
+    PostProcessSteps::FIND_INVALID_DATA,
+    PostProcessSteps::FIX_INFACING_NORMALS,
+    PostProcessSteps::SPLIT_BY_BONE_COUNT,
+    PostProcessSteps::SPLIT_LARGE_MESHES,
+    PostProcessSteps::GEN_NORMALS,
+    PostProcessSteps::GEN_SMOOTH_NORMALS,
+    PostProcessSteps::CALC_TANGENT_SPACE,
+    PostProcessSteps::JOIN_IDENTICAL_VERTICES,
+    PostProcessSteps::DEBONE,
+    PostProcessSteps::LIMIT_BONE_WEIGHTS,
+    PostProcessSteps::IMPROVE_CACHE_LOCALITY,
+];
+
+/// The steps that Assimp runs between the two halves of `SPLIT_LARGE_MESHES`, which splits by
+/// triangle count before them and by vertex count after them.
+fn split_large_meshes_span() -> PostProcessSteps {
+    PostProcessSteps::GEN_NORMALS
+        | PostProcessSteps::GEN_SMOOTH_NORMALS
+        | PostProcessSteps::CALC_TANGENT_SPACE
+        | PostProcessSteps::JOIN_IDENTICAL_VERTICES
+}
+
+/// How long an import took, see `Importer::instrumented` and `Scene::import_diagnostics`.
+///
+/// Every time is measured with `Instant`, so none of them can be negative, and they don't
+/// overlap except that `io_read_time` is part of `import_wall_time`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct ImportTimings {
+    /// The time spent in the `File::read` calls of the `FileIO`, for
+    /// `Importer::read_file_with_io`. `None` for the other read methods, where Assimp reads
+    /// the file itself.
+    pub io_read_time: Option<Duration>,
+    /// The number of bytes those reads returned, likewise.
+    pub io_bytes: Option<u64>,
+    /// The time the `aiImportFile*` call took, which reads the file and validates it if
+    /// `validate_data_structure` is enabled, but runs no other steps.
+    pub import_wall_time: Duration,
+    /// The time each post-process step took, in the order they ran. Each entry is usually a
+    /// single step, but steps that can't be run on their own are run together and share an
+    /// entry, see `Importer::instrumented`.
+    pub per_step: Vec<(PostProcessSteps, Duration)>,
+    /// The time the crate spent on the scene once Assimp was done, e.g. to collect the
+    /// findings of `validate_data_structure`.
+    pub wrapper_time: Duration,
+}
+
+impl ImportTimings {
+    /// The time spent in post-processing.
+    pub fn post_process_time(&self) -> Duration {
+        self.per_step.iter().map(|&(_, time)| time).sum()
+    }
+
+    /// The time of the whole import: reading, post-processing and the crate's own work.
+    pub fn total(&self) -> Duration {
+        self.import_wall_time + self.post_process_time() + self.wrapper_time
+    }
+}
+
+/// The groups of `steps` that `Importer::instrumented` runs with one `aiApplyPostProcessing`
+/// each, in the order it runs them.
+pub(crate) fn step_groups(steps: PostProcessSteps) -> Vec<PostProcessSteps> {
+    let joined = if steps.contains(PostProcessSteps::SPLIT_LARGE_MESHES) {
+        steps & (PostProcessSteps::SPLIT_LARGE_MESHES | split_large_meshes_span())
+    } else {
+        PostProcessSteps::empty()
+    };
+
+    let mut groups = Vec::new();
+    for &step in &STEP_ORDER {
+        if !steps.contains(step) {
+            continue;
+        }
+        if joined.contains(step) {
+            if !groups.contains(&joined) {
+                groups.push(joined);
+            }
+        } else {
+            groups.push(step);
+        }
+    }
+    groups
+}
+
+impl Importer {
+    /// Like `finish_import(import(self.import_flags()))`, but importing without any steps but
+    /// validation, then running the steps one group at a time and timing everything.
+    pub(super) fn import_instrumented<'a, F>(&self, import: F) -> Result<Scene<'a>, Error>
+    where
+        F: FnOnce(aiPostProcessSteps) -> *const aiScene,
+    {
+        let steps = PostProcessSteps::from_bits_truncate(self.flags);
+        let mut timings = ImportTimings::default();
+
+        let start = Instant::now();
+        let raw_scene = import(self.flags & aiPostProcessSteps_aiProcess_ValidateDataStructure);
+        timings.import_wall_time = start.elapsed();
+        let mut raw_scene = match NonNull::new(raw_scene as *mut aiScene) {
+            Some(raw_scene) => raw_scene,
+            None => {
+                return Err(Error::ImportFailed {
+                    message: last_error().to_owned(),
+                })
+            }
+        };
+
+        let mut diagnostics = ImportDiagnostics::default();
+        for group in step_groups(steps) {
+            let meshes_before = unsafe { raw_scene.as_ref().mNumMeshes };
+            let start = Instant::now();
+            // Assimp post-processes the scene in place, and frees it if that fails.
+            let processed = unsafe { aiApplyPostProcessing(raw_scene.as_ptr(), group.bits()) };
+            let time = start.elapsed();
+            raw_scene = match NonNull::new(processed as *mut aiScene) {
+                Some(raw_scene) => raw_scene,
+                None => {
+                    let message = format!("{} failed, see output log for errors.", group);
+                    return Err(Error::ImportFailed { message });
+                }
+            };
+            if group.contains(PostProcessSteps::OPTIMIZE_MESHES) {
+                let meshes_after = unsafe { raw_scene.as_ref().mNumMeshes };
+                diagnostics.optimize_meshes = Some(OptimizeMeshesReport {
+                    meshes_before,
+                    meshes_after,
+                });
+            }
+            timings.per_step.push((group, time));
+        }
+
+        let start = Instant::now();
+        let mut scene = unsafe { Scene::from_raw(raw_scene, Ownership::Owned) };
+        timings.wrapper_time = start.elapsed();
+        diagnostics.timings = Some(timings);
+        scene.set_import_diagnostics(diagnostics);
+        Ok(scene)
+    }
+}
+
+/// The time spent reading the files of a `TimedFileIO`, and how much they read.
+#[derive(Default)]
+pub(crate) struct ReadTotals {
+    time: Cell<Duration>,
+    bytes: Cell<u64>,
+}
+
+impl ReadTotals {
+    /// Put the totals in the timings of `scene`, if it has any.
+    pub(crate) fn record(&self, scene: &mut Scene) {
+        let mut diagnostics = scene.import_diagnostics().clone();
+        if let Some(timings) = &mut diagnostics.timings {
+            timings.io_read_time = Some(self.time.get());
+            timings.io_bytes = Some(self.bytes.get());
+            scene.set_import_diagnostics(diagnostics);
+        }
+    }
+}
+
+/// A `FileIO` that passes everything on to another one, adding up the time its files spend
+/// reading. Like `ObservedFileIO`, but only used on the thread of the import.
+pub(crate) struct TimedFileIO<'a, T> {
+    inner: &'a T,
+    totals: Rc<ReadTotals>,
+}
+
+impl<'a, T: FileIO> TimedFileIO<'a, T> {
+    pub(crate) fn new(inner: &'a T) -> TimedFileIO<'a, T> {
+        TimedFileIO {
+            inner,
+            totals: Rc::default(),
+        }
+    }
+
+    pub(crate) fn totals(&self) -> &ReadTotals {
+        &self.totals
+    }
+}
+
+impl<T: FileIO> FileIO for TimedFileIO<'_, T> {
+    fn open(&self, file_path: &str, mode: &str) -> Option<Box<dyn File>> {
+        let inner = self.inner.open(file_path, mode)?;
+        Some(Box::new(TimedFile {
+            inner,
+            totals: self.totals.clone(),
+        }))
+    }
+
+    fn on_unclosed(&self, file_path: &str) {
+        self.inner.on_unclosed(file_path)
+    }
+}
+
+struct TimedFile {
+    inner: Box<dyn File>,
+    totals: Rc<ReadTotals>,
+}
+
+impl File for TimedFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let start = Instant::now();
+        let result = self.inner.read(buf);
+        let totals = &self.totals;
+        totals.time.set(totals.time.get() + start.elapsed());
+        if let Ok(bytes) = result {
+            totals.bytes.set(totals.bytes.get() + bytes as u64);
+        }
+        result
+    }
+
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.write(buf)
+    }
+
+    fn tell(&mut self) -> u64 {
+        self.inner.tell()
+    }
+
+    fn size(&mut self) -> u64 {
+        self.inner.size()
+    }
+
+    fn seek(&mut self, seek_from: SeekFrom) -> io::Result<()> {
+        self.inner.seek(seek_from)
+    }
+
+    fn flush(&mut self) {
+        self.inner.flush()
+    }
+
+    fn close(&mut self) {
+        self.inner.close()
+    }
+}
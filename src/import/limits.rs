@@ -0,0 +1,168 @@
+//! Caps on the size of imported scenes, and imports of untrusted data from memory, see
+//! `Importer::limits` and `Importer::read_from_memory`.
+
+use crate::io::{ByteBudget, ByteBudgetFileIO, File, FileIO, MemoryFileIO};
+use crate::scene::Scene;
+use crate::Error;
+
+use super::Importer;
+
+/// The largest scene an `Importer` returns, see `Importer::limits`. Each cap is `None` for no
+/// limit, which is the default for all of them.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct ImportLimits {
+    /// The number of meshes.
+    pub max_meshes: Option<u64>,
+    /// The number of vertices of all meshes together.
+    pub max_vertices: Option<u64>,
+    /// The number of nodes in the hierarchy, the root included.
+    pub max_nodes: Option<u64>,
+    /// The size of the data of all embedded textures together: the file size of compressed
+    /// textures, and 4 bytes per texel of uncompressed ones.
+    pub max_texture_bytes: Option<u64>,
+    /// The budget for the files that `Importer::read_from_memory` reads, the data itself
+    /// included. The other read methods don't use it.
+    pub io_budget: ByteBudget,
+}
+
+impl ImportLimits {
+    /// Caps for scenes from untrusted sources, generous enough for large but reasonable files:
+    /// 4096 meshes, 16 million vertices, 65536 nodes and 256 MiB of textures, with the default
+    /// `ByteBudget`.
+    pub fn untrusted() -> ImportLimits {
+        ImportLimits {
+            max_meshes: Some(4096),
+            max_vertices: Some(16 << 20),
+            max_nodes: Some(65536),
+            max_texture_bytes: Some(256 << 20),
+            io_budget: ByteBudget::default(),
+        }
+    }
+
+    /// Check `scene` against the caps, returning `Error::LimitExceeded` for the first one it
+    /// exceeds. Counting stops as soon as a cap is exceeded, so this takes time linear in the
+    /// caps rather than in the size of the scene, and a hierarchy with a cycle, which Assimp
+    /// doesn't produce but a broken scene could contain, is still counted in bounded time.
+    ///
+    /// Everything is read through the accessors that skip null entries and check their
+    /// lengths, so this doesn't panic on a scene that's inconsistent.
+    pub(crate) fn check(&self, scene: &Scene) -> Result<(), Error> {
+        if let Some(limit) = self.max_meshes {
+            check("meshes", limit, u64::from(scene.num_meshes()))?;
+        }
+
+        if let Some(limit) = self.max_vertices {
+            let mut vertices = 0u64;
+            for mesh in scene.meshes() {
+                vertices += u64::from(mesh.num_vertices());
+                check("vertices", limit, vertices)?;
+            }
+        }
+
+        if let (Some(limit), Some(root)) = (self.max_nodes, scene.root_node()) {
+            let bound = limit.saturating_add(1).min(usize::MAX as u64) as usize;
+            check(
+                "nodes",
+                limit,
+                root.descendants().take(bound).count() as u64,
+            )?;
+        }
+
+        if let Some(limit) = self.max_texture_bytes {
+            let mut bytes = 0u64;
+            for texture in scene.textures() {
+                bytes += if texture.is_compressed() {
+                    u64::from(texture.width())
+                } else {
+                    u64::from(texture.width()) * u64::from(texture.height()) * 4
+                };
+                check("texture bytes", limit, bytes)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn check(what: &'static str, limit: u64, found: u64) -> Result<(), Error> {
+    if found > limit {
+        return Err(Error::LimitExceeded { what, limit, found });
+    }
+    Ok(())
+}
+
+/// The name `Importer::read_from_memory` gives its data, which no importer would make up for a
+/// file it refers to.
+const MEMORY_FILE: &str = "$$memory$$";
+
+impl Importer {
+    /// Import a scene from `data`, which can come from an untrusted source, e.g. a file
+    /// uploaded to a server. `hint` is the extension of the format, as for
+    /// `read_memory_with_hint`, or empty to tell the format from the data.
+    ///
+    /// `read_memory_with_hint` lets Assimp open any other file the data names, e.g. the MTL
+    /// file of an OBJ file, from the file system. This refuses all of them, so the data can't
+    /// make the importer read local files, and reads the data itself through a
+    /// `ByteBudgetFileIO` with the `ImportLimits::io_budget` of `limits`. Use
+    /// `read_from_memory_with_io` to provide the other files. The data is copied once.
+    ///
+    /// For a hardened import, also set `limits`, e.g. to `ImportLimits::untrusted`, and
+    /// consider `read_file_with_timeout` for formats whose importers can take long.
+    pub fn read_from_memory<'a>(&self, data: &[u8], hint: &str) -> Result<Scene<'a>, Error> {
+        self.read_from_memory_with_io(data, hint, &NoFiles)
+    }
+
+    /// Like `read_from_memory`, but opening the other files the data names with `file_io`,
+    /// within the same `ByteBudget`.
+    pub fn read_from_memory_with_io<'a, T: FileIO>(
+        &self,
+        data: &[u8],
+        hint: &str,
+        file_io: &T,
+    ) -> Result<Scene<'a>, Error> {
+        if hint.contains(|c| c == '/' || c == '\\' || c == '\0') {
+            let message = format!("format hint {:?} isn't an extension", hint);
+            return Err(Error::InvalidConfig { message });
+        }
+        let name = memory_file_name(hint);
+        let memory_io = WithOthers {
+            memory: MemoryFileIO::new().with_file(&name, data),
+            others: file_io,
+        };
+        let budget_io = ByteBudgetFileIO::new(memory_io, self.limits.io_budget);
+        self.read_file_with_io(&name, &budget_io)
+    }
+}
+
+/// The name Assimp is given for data with the format hint `hint`, from which it picks the
+/// importer.
+pub(super) fn memory_file_name(hint: &str) -> String {
+    if hint.is_empty() {
+        MEMORY_FILE.to_owned()
+    } else {
+        format!("{}.{}", MEMORY_FILE, hint)
+    }
+}
+
+/// A `FileIO` that opens no files.
+struct NoFiles;
+
+impl FileIO for NoFiles {
+    fn open(&self, _file_path: &str, _mode: &str) -> Option<Box<dyn File>> {
+        None
+    }
+}
+
+/// Serves the data of `memory`, and opens every other file with `others`.
+struct WithOthers<'a, T> {
+    memory: MemoryFileIO,
+    others: &'a T,
+}
+
+impl<T: FileIO> FileIO for WithOthers<'_, T> {
+    fn open(&self, file_path: &str, mode: &str) -> Option<Box<dyn File>> {
+        self.memory
+            .open(file_path, mode)
+            .or_else(|| self.others.open(file_path, mode))
+    }
+}
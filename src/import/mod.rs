@@ -13,66 +13,190 @@
 //! }
 //! ```
 
+use std::collections::BTreeMap;
 use std::ffi::{CStr, CString};
-use std::mem;
+use std::path::Path;
 use std::ptr::{self, NonNull};
 use std::str;
+use std::time::Instant;
 
 use ffi::*;
 
 use crate::io::*;
 use crate::math::matrix4::*;
+use crate::scene::mesh_stats::CacheLocalityReport;
 use crate::scene::*;
+use crate::Error;
 
 pub mod structs;
 use self::structs::*;
 
+mod filter;
+use self::filter::FormatFilter;
+
+mod identify;
+pub use self::identify::{Confidence, IdentifyResult};
+
+mod instrument;
+pub use self::instrument::ImportTimings;
+use self::instrument::TimedFileIO;
+
+mod limits;
+pub use self::limits::ImportLimits;
+
+mod probe;
+pub use self::probe::{Aabb, SceneProbe};
+
+mod spool;
+pub use self::spool::SpoolPolicy;
+
+mod timeout;
+pub use self::timeout::{
+    orphaned_imports, set_max_orphaned_imports, TimeoutError, DEFAULT_MAX_ORPHANED_IMPORTS,
+};
+
+/// The steps that Assimp runs before `aiProcess_OptimizeMeshes`, see `Importer::import_flags`.
+/// Assimp always runs its steps in the same order, so running these first and the rest
+/// afterwards gives the same scene as running them all at once. Validation runs as soon as the
+/// file is read, whatever else is enabled.
+const STEPS_BEFORE_OPTIMIZE_MESHES: aiPostProcessSteps =
+    aiPostProcessSteps_aiProcess_ValidateDataStructure
+        | aiPostProcessSteps_aiProcess_RemoveComponent
+        | aiPostProcessSteps_aiProcess_RemoveRedundantMaterials
+        | aiPostProcessSteps_aiProcess_FindInstances
+        | aiPostProcessSteps_aiProcess_OptimizeGraph;
+
+/// The number of log messages kept while a validated scene is imported, see `Importer::import`.
+/// The findings of files that log more than that start with the last of them.
+const VALIDATION_LOG_CAPACITY: usize = 4096;
+
 /// The `Importer` type.
 ///
 /// See [module-level documentation](index.html) for examples.
+///
+/// The importer owns a single Assimp property store, which is created the first time a property
+/// is set and reused by every read, so a configured importer can import any number of files.
+/// Changing a property between reads only affects the reads that follow. Cloning an importer
+/// gives the clone its own store with the same properties.
+///
+/// The store is a raw pointer, so an `Importer` can't be sent to or shared with other threads.
 pub struct Importer {
+    /// Null until the first property is set. Assimp treats a null store as "all defaults".
     property_store: *mut aiPropertyStore,
     flags: aiPostProcessSteps,
+    /// A copy of every property set in `property_store`, which Assimp doesn't let us read back.
+    properties: BTreeMap<String, ImportProperty>,
+    format_filter: FormatFilter,
+    /// Whether reads time their steps, see `instrumented`.
+    instrumented: bool,
+    limits: ImportLimits,
 }
 
 impl Importer {
     /// Create a new Importer.
     pub fn new() -> Importer {
-        Importer { property_store: unsafe { aiCreatePropertyStore() }, flags: 0 }
+        Importer {
+            property_store: ptr::null_mut(),
+            flags: 0,
+            properties: BTreeMap::new(),
+            format_filter: FormatFilter::default(),
+            instrumented: false,
+            limits: ImportLimits::default(),
+        }
     }
 
-    /// Load a scene from the specified file.
+    /// The post-process steps that will be passed to Assimp when reading a file, as configured
+    /// by the step methods so far. Nothing is imported.
     ///
-    /// If the call succeeds, return value is `Ok`, containing the loaded `Scene` structure.
-    /// If the call fails, return value is `Err`, containing the error string returned from
-    /// the Assimp library.
-    pub fn read_file<'a>(&self, file: &str) -> Result<Scene<'a>, &str> {
-        let cstr = CString::new(file).unwrap();
-        let raw_scene = unsafe {
-            aiImportFileExWithProperties(
-                cstr.as_ptr(),
-                self.flags,
-                ptr::null_mut(),
-                self.property_store,
-            )
-        };
+    /// Combine this with `property` to check exactly what a configuration does, e.g. that
+    /// `find_degenerates` with `remove = true` also sets `PP_FD_REMOVE`.
+    pub fn effective_flags(&self) -> PostProcessSteps {
+        PostProcessSteps::from_bits_truncate(self.flags)
+    }
 
-        if let Some(raw_scene) = NonNull::new(raw_scene as *mut _) {
-            unsafe { Ok(Scene::from_raw(raw_scene)) }
-        } else {
-            let error_str = unsafe { aiGetErrorString() };
-            if error_str.is_null() {
-                Err("Unknown error")
-            } else {
-                unsafe {
-                    let cstr = CStr::from_ptr(error_str);
-                    match cstr.to_str() {
-                        Ok(s) => Err(s),
-                        Err(_) => Err("Unknown error"),
-                    }
+    /// The value of the import property `name` (e.g. `"PP_FD_REMOVE"`), if it has been set.
+    /// Boolean properties are stored by Assimp as integers.
+    pub fn property(&self, name: &str) -> Option<&ImportProperty> {
+        self.properties.get(name)
+    }
+
+    /// Iterate over every import property that has been set, in order of name.
+    pub fn properties(&self) -> impl Iterator<Item = (&str, &ImportProperty)> {
+        self.properties
+            .iter()
+            .map(|(name, value)| (name.as_str(), value))
+    }
+
+    /// A hash of the post-process steps and every property that has been set, which is the same
+    /// for importers that are configured the same way, however that was done. Like
+    /// `Scene::content_hash`, it's stable between runs and Rust versions, so it can be part of
+    /// a cache key.
+    pub fn fingerprint(&self) -> u64 {
+        let mut hasher = StableHasher::new(0.0);
+        hasher.u64(self.flags as u64);
+        hasher.u64(self.properties.len() as u64);
+        for (name, value) in &self.properties {
+            hasher.str(name);
+            match value {
+                ImportProperty::Int(value) => {
+                    hasher.u32(0);
+                    hasher.u32(*value as u32);
+                }
+                ImportProperty::Float(value) => {
+                    hasher.u32(1);
+                    hasher.f32(*value);
+                }
+                ImportProperty::String(value) => {
+                    hasher.u32(2);
+                    hasher.str(value);
+                }
+                ImportProperty::Matrix(value) => {
+                    hasher.u32(3);
+                    hasher.matrix(value);
                 }
             }
         }
+        hasher.finish()
+    }
+
+    /// Clear every import property back to its default, releasing the property store. The
+    /// post-process steps are kept.
+    pub fn reset_properties(&mut self) {
+        if !self.property_store.is_null() {
+            unsafe { aiReleasePropertyStore(self.property_store) }
+            self.property_store = ptr::null_mut();
+        }
+        self.properties.clear();
+    }
+
+    /// Load a scene from the specified file.
+    ///
+    /// If the call succeeds, return value is `Ok`, containing the loaded `Scene` structure.
+    /// If the call fails, return value is `Err`, containing `Error::ImportFailed` with the
+    /// error string returned from the Assimp library.
+    ///
+    /// Paths don't need to be valid UTF-8. On Unix the bytes of the path are passed to Assimp
+    /// as they are. On Windows, paths that can't be converted to UTF-8 are opened by Rust
+    /// instead, through the same mechanism as `read_file_with_io`. Paths containing a nul
+    /// byte can't be passed to Assimp at all, and return `Error::InvalidConfig`.
+    ///
+    /// Returns `Error::FormatNotAllowed` if the file's format is refused by `allowed_formats`
+    /// or `denied_formats`, like the other read methods.
+    pub fn read_file<'a, P: AsRef<Path>>(&self, file: P) -> Result<Scene<'a>, Error> {
+        let file = file.as_ref();
+        self.check_path_format(file)?;
+        let cstr = match path_to_cstring(file) {
+            Ok(cstr) => cstr,
+            #[cfg(windows)]
+            Err(PathError::NotUnicode) => {
+                let file_io = NativePathIO::new(file);
+                return self.read_file_with_io(file_io.virtual_name(), &file_io);
+            }
+            Err(error) => return Err(error.into()),
+        };
+        self.import(|flags| unsafe {
+            aiImportFileExWithProperties(cstr.as_ptr(), flags, ptr::null_mut(), self.property_store)
+        })
     }
     /// Load a scene from the specified file using custom IO logic.
     ///
@@ -81,83 +205,189 @@ impl Importer {
     /// etc).
     ///
     /// If the call succeeds, return value is `Ok`, containing the loaded `Scene` structure.
-    /// If the call fails, return value is `Err`, containing the error string returned from
-    /// the Assimp library.
+    /// If the call fails, return value is `Err`. That's the first error returned by one of the
+    /// files, as `Error::Io` or `Error::BudgetExceeded`, if there was one, and otherwise
+    /// `Error::ImportFailed` with the error string returned from the Assimp library. The
+    /// source of an `Error::Io` is an `io::FileError`, with the path of the file and the
+    /// operation that failed.
+    ///
+    /// Every file that `file_io` opens is closed exactly once before this returns, including
+    /// any that Assimp leaves open, see `FileIO::on_unclosed`. If `file_io` or one of its files
+    /// panics, the panic is resumed here once they're all closed.
+    ///
+    /// If the importer is `instrumented`, the time spent reading the files of `file_io` is
+    /// part of the `ImportTimings`.
     pub fn read_file_with_io<'a, T: FileIO>(
         &self,
         file: &str,
         file_io: &T,
-    ) -> Result<Scene<'a>, &str> {
-        let cstr = CString::new(file).unwrap();
-        let mut ai_file_io = crate::io::wrap_file_io(file_io);
-        let raw_scene = unsafe {
+    ) -> Result<Scene<'a>, Error> {
+        let cstr = match CString::new(file) {
+            Ok(cstr) => cstr,
+            Err(_) => return Err(PathError::Nul.into()),
+        };
+        self.check_io_format(file, file_io)?;
+        if !self.instrumented {
+            return self.import_with_io(&cstr, file_io);
+        }
+
+        let timed_io = TimedFileIO::new(file_io);
+        let mut scene = self.import_with_io(&cstr, &timed_io)?;
+        timed_io.totals().record(&mut scene);
+        Ok(scene)
+    }
+
+    fn import_with_io<'a, T: FileIO>(&self, cstr: &CStr, file_io: &T) -> Result<Scene<'a>, Error> {
+        let mut ai_file_io = crate::io::WrappedFileIO::new(file_io);
+        let result = self.import(|flags| unsafe {
             aiImportFileExWithProperties(
                 cstr.as_ptr(),
-                self.flags,
-                &mut ai_file_io,
+                flags,
+                ai_file_io.as_mut_ptr(),
                 self.property_store,
             )
-        };
-
-        if let Some(raw_scene) = NonNull::new(raw_scene as *mut _) {
-            unsafe { Ok(Scene::from_raw(raw_scene)) }
-        } else {
-            let error_str = unsafe { aiGetErrorString() };
-            if error_str.is_null() {
-                Err("Unknown error")
-            } else {
-                unsafe {
-                    let cstr = CStr::from_ptr(error_str);
-                    match cstr.to_str() {
-                        Ok(s) => Err(s),
-                        Err(_) => Err("Unknown error"),
-                    }
-                }
-            }
+        });
+        let io_error = ai_file_io.take_error();
+        if let Some(payload) = ai_file_io.finish() {
+            drop(result);
+            std::panic::resume_unwind(payload);
         }
+
+        result.map_err(|error| match (error, io_error) {
+            (Error::ImportFailed { .. }, Some(io_error)) => crate::io::into_error(io_error),
+            (error, _) => error,
+        })
     }
     /// Load a scene from memory with a file extension hint.
     ///
     /// If the call succeeds, return value is `Ok`, containing the loaded `Scene` structure.
-    /// If the call fails, return value is `Err`, containing the error string returned from
-    /// the Assimp library.
-    pub fn read_memory_with_hint<'a>(&self, data: &[u8], hint: &str) -> Result<Scene<'a>, &str> {
-        let cstr = CString::new(hint).unwrap();
-        let raw_scene = unsafe {
+    /// If the call fails, return value is `Err`, containing `Error::ImportFailed` with the
+    /// error string returned from the Assimp library.
+    pub fn read_memory_with_hint<'a>(&self, data: &[u8], hint: &str) -> Result<Scene<'a>, Error> {
+        let cstr = match CString::new(hint) {
+            Ok(cstr) => cstr,
+            Err(_) => {
+                let message = "Format hint contains a nul byte".to_owned();
+                return Err(Error::InvalidConfig { message });
+            }
+        };
+        self.check_memory_format(data, hint)?;
+        self.import(|flags| unsafe {
             aiImportFileFromMemoryWithProperties(
                 data.as_ptr() as *const _,
                 data.len() as u32,
-                self.flags,
+                flags,
                 cstr.as_ptr(),
                 self.property_store,
             )
-        };
+        })
+    }
+
+    /// Load a scene from memory.
+    ///
+    /// If the call succeeds, return value is `Ok`, containing the loaded `Scene` structure.
+    /// If the call fails, return value is `Err`, containing `Error::ImportFailed` with the
+    /// error string returned from the Assimp library.
+    pub fn read_memory<'a>(&self, data: &[u8]) -> Result<Scene<'a>, Error> {
+        self.read_memory_with_hint(data, "")
+    }
 
-        if let Some(raw_scene) = NonNull::new(raw_scene as *mut _) {
-            unsafe { Ok(Scene::from_raw(raw_scene)) }
+    /// The post-process steps to pass to the `aiImportFile*` functions. If `optimize_meshes`
+    /// is enabled, that's only the steps that Assimp runs before it, so that `finish_import`
+    /// can count the meshes in between.
+    fn import_flags(&self) -> aiPostProcessSteps {
+        if self.flags & aiPostProcessSteps_aiProcess_OptimizeMeshes != 0 {
+            self.flags & STEPS_BEFORE_OPTIMIZE_MESHES
         } else {
-            let error_str = unsafe { aiGetErrorString() };
-            if error_str.is_null() {
-                Err("Unknown error")
+            self.flags
+        }
+    }
+
+    /// Call one of the `aiImportFile*` functions with `import`, passing it the steps to run,
+    /// and `finish_import` its scene, or time the steps if the importer is `instrumented`.
+    /// If `validate_data_structure` is enabled, the log of this thread is captured meanwhile,
+    /// and the findings of the validation are parsed from it for `Scene::validation_findings`,
+    /// or returned as `Error::ValidationFailed` if there are errors among them.
+    fn import<'a, F>(&self, import: F) -> Result<Scene<'a>, Error>
+    where
+        F: FnOnce(aiPostProcessSteps) -> *const aiScene,
+    {
+        let run = |import: F| {
+            let scene = if self.instrumented {
+                self.import_instrumented(import)?
             } else {
-                unsafe {
-                    let cstr = CStr::from_ptr(error_str);
-                    match cstr.to_str() {
-                        Ok(s) => Err(s),
-                        Err(_) => Err("Unknown error"),
-                    }
+                self.finish_import(import(self.import_flags()))?
+            };
+            self.limits.check(&scene)?;
+            Ok(scene)
+        };
+        if self.flags & aiPostProcessSteps_aiProcess_ValidateDataStructure == 0 {
+            return run(import);
+        }
+
+        let (result, records) =
+            crate::log::with_thread_logs(VALIDATION_LOG_CAPACITY, || run(import));
+        let start = Instant::now();
+        match result {
+            Ok(mut scene) => {
+                let mut diagnostics = scene.import_diagnostics().clone();
+                diagnostics.validation = validation_findings(&records, None);
+                if let Some(timings) = &mut diagnostics.timings {
+                    timings.wrapper_time += start.elapsed();
                 }
+                scene.set_import_diagnostics(diagnostics);
+                Ok(scene)
             }
+            Err(Error::ImportFailed { message }) => {
+                let findings = validation_findings(&records, Some(&message));
+                if findings
+                    .iter()
+                    .any(|finding| finding.severity == ValidationSeverity::Error)
+                {
+                    Err(Error::ValidationFailed { message, findings })
+                } else {
+                    Err(Error::ImportFailed { message })
+                }
+            }
+            Err(error) => Err(error),
         }
     }
 
-    /// Load a scene from memory.
-    ///
-    /// If the call succeeds, return value is `Ok`, containing the loaded `Scene` structure.
-    /// If the call fails, return value is `Err`, containing the error string returned from
-    /// the Assimp library.
-    pub fn read_memory<'a>(&self, data: &[u8]) -> Result<Scene<'a>, &str> {
-        self.read_memory_with_hint(data, "")
+    /// Wrap a scene returned by one of the `aiImportFile*` functions, or return Assimp's error
+    /// if the import failed. Any steps that `import_flags` held back are run here.
+    fn finish_import<'a>(&self, raw_scene: *const aiScene) -> Result<Scene<'a>, Error> {
+        let mut raw_scene = match NonNull::new(raw_scene as *mut aiScene) {
+            Some(raw_scene) => raw_scene,
+            None => {
+                return Err(Error::ImportFailed {
+                    message: last_error().to_owned(),
+                })
+            }
+        };
+
+        let mut diagnostics = ImportDiagnostics::default();
+        let remaining = self.flags & !self.import_flags();
+        if remaining != 0 {
+            let meshes_before = unsafe { raw_scene.as_ref().mNumMeshes };
+            // Assimp post-processes the scene in place, and frees it if that fails.
+            let processed = unsafe { aiApplyPostProcessing(raw_scene.as_ptr(), remaining) };
+            raw_scene = match NonNull::new(processed as *mut aiScene) {
+                Some(raw_scene) => raw_scene,
+                None => {
+                    let message = "post-processing failed, see output log for errors.".to_owned();
+                    return Err(Error::ImportFailed { message });
+                }
+            };
+            let meshes_after = unsafe { raw_scene.as_ref().mNumMeshes };
+            diagnostics.optimize_meshes = Some(OptimizeMeshesReport {
+                meshes_before,
+                meshes_after,
+            });
+        }
+
+        let mut scene = unsafe { Scene::from_raw(raw_scene, Ownership::Owned) };
+        scene.set_import_diagnostics(diagnostics);
+        Ok(scene)
     }
 
     /// Apply post-processing to an already-imported scene.
@@ -172,7 +402,15 @@ impl Importer {
     /// # Return value
     /// The new scene, with new post-processing steps applied. Note that it is possible for this
     /// method to fail, in which case the return value is `Err`.
-    pub fn apply_postprocessing<'a>(&'a self, scene: Scene<'a>) -> Result<Scene, &str> {
+    ///
+    /// Copies made with `Scene::try_clone` can't be post-processed, since Assimp doesn't know
+    /// which importer they came from. They're dropped and `Error::Unsupported` is returned.
+    pub fn apply_postprocessing<'a>(&'a self, scene: Scene<'a>) -> Result<Scene, Error> {
+        if scene.is_copy() {
+            let what = "post-processing of copied scenes".to_owned();
+            return Err(Error::Unsupported { what });
+        }
+
         let raw_scene = unsafe { aiApplyPostProcessing(&*scene, self.flags) };
         if !raw_scene.is_null() {
             // Return original scene, Assimp applies post-processing in-place so returning
@@ -180,12 +418,64 @@ impl Importer {
             Ok(scene)
         } else {
             // Assimp frees the scene on failure, dropping would cause the memory to be
-            // freed twice so use mem::forget to prevent that happening.
-            mem::forget(scene);
-            Err("apply_postprocessing failed, see output log for errors.")
+            // freed twice so give up ownership to prevent that happening.
+            scene.into_raw();
+            let message = "apply_postprocessing failed, see output log for errors.".to_owned();
+            Err(Error::ImportFailed { message })
         }
     }
 
+    /// Like `apply_postprocessing`, but also measure the vertex cache statistics of every mesh
+    /// before and after, see `scene::mesh_stats`, to tell whether `improve_cache_locality`
+    /// helped. The cache size is the one set with `improve_cache_locality`, or its default if
+    /// it wasn't set.
+    pub fn apply_postprocessing_with_cache_report<'a>(
+        &'a self,
+        scene: Scene<'a>,
+    ) -> Result<(Scene, CacheLocalityReport), Error> {
+        let cache_size = match self.property("PP_ICL_PTCACHE_SIZE") {
+            Some(&ImportProperty::Int(size)) if size > 0 => size as u32,
+            _ => ImproveCacheLocality::default().cache_size,
+        };
+        let before = scene.vertex_cache_stats(cache_size);
+        let scene = self.apply_postprocessing(scene)?;
+        let after = scene.vertex_cache_stats(cache_size);
+        Ok((scene, CacheLocalityReport { before, after }))
+    }
+
+    /// Time each part of the following reads, and keep the times in the scene's
+    /// `ImportDiagnostics::timings`, see `ImportTimings`.
+    ///
+    /// Assimp only times the steps it runs in one go if `measure_time` is enabled, and then
+    /// only in the log. So instead the file is imported with no post-process steps but
+    /// validation, and the steps are run afterwards with one `aiApplyPostProcessing` each, in
+    /// the order Assimp would run them, which gives the same scene but is slower. A step that
+    /// Assimp runs in two parts with other steps in between can't be run on its own without
+    /// changing the result: `SPLIT_LARGE_MESHES` runs together with whichever of
+    /// `GEN_NORMALS`, `GEN_SMOOTH_NORMALS`, `CALC_TANGENT_SPACE` and `JOIN_IDENTICAL_VERTICES`
+    /// are enabled, and they share one entry of `ImportTimings::per_step`.
+    /// `VALIDATE_DATA_STRUCTURE` runs while the file is read, and is part of
+    /// `ImportTimings::import_wall_time`.
+    pub fn instrumented(&mut self, enable: bool) {
+        self.instrumented = enable;
+    }
+
+    /// Fail the following reads with `Error::LimitExceeded` if the scene is larger than
+    /// `ImportLimits` allows. The scene is checked once Assimp has imported and post-processed
+    /// it, and freed if it's too large, so the caller never sees it. See `ImportLimits` for
+    /// the available caps and their defaults, and `ImportLimits::untrusted` for caps suitable
+    /// for untrusted files.
+    pub fn limits<F: Fn(&mut ImportLimits)>(&mut self, closure: F) {
+        let mut args = ImportLimits::default();
+        closure(&mut args);
+        self.set_limits(args);
+    }
+
+    /// Like `limits`, but taking the limits by value.
+    pub fn set_limits(&mut self, limits: ImportLimits) {
+        self.limits = limits;
+    }
+
     /// Enables time measurements.
     ///
     /// If enabled, measures the time needed for each part of the loading process (i.e. IO time,
@@ -224,33 +514,32 @@ impl Importer {
 
     /// Helper method to set an integer import property.
     fn set_int_property(&mut self, name: &str, value: i32) {
-        let cstr = CString::new(name).unwrap();
-        unsafe {
-            aiSetImportPropertyInteger(self.property_store, cstr.as_ptr(), value);
-        }
+        self.set_property(name, ImportProperty::Int(value));
     }
 
     /// Helper method to set a floating point import property.
     fn set_float_property(&mut self, name: &str, value: f32) {
-        let cstr = CString::new(name).unwrap();
-        unsafe {
-            aiSetImportPropertyFloat(self.property_store, cstr.as_ptr(), value);
-        }
+        self.set_property(name, ImportProperty::Float(value));
     }
 
     /// Helper method to set a 4x4 matrix import property.
     fn set_matrix_property(&mut self, name: &str, value: Matrix4x4) {
-        let cstr = CString::new(name).unwrap();
-        unsafe {
-            aiSetImportPropertyMatrix(self.property_store, cstr.as_ptr(), &*value);
-        }
+        self.set_property(name, ImportProperty::Matrix(value));
     }
 
     /// Helper method to set a string import property.
     fn set_string_property(&mut self, name: &str, value: &str) {
-        let cstr = CString::new(name).unwrap();
-        let aistr = crate::str_to_aistring(value);
-        unsafe { aiSetImportPropertyString(self.property_store, cstr.as_ptr(), &aistr) }
+        self.set_property(name, ImportProperty::String(value.to_owned()));
+    }
+
+    /// Write a property to the property store, creating the store if this is the first one,
+    /// and keep a copy of it.
+    fn set_property(&mut self, name: &str, value: ImportProperty) {
+        if self.property_store.is_null() {
+            self.property_store = unsafe { aiCreatePropertyStore() };
+        }
+        unsafe { write_property(self.property_store, name, &value) }
+        self.properties.insert(name.to_owned(), value);
     }
 
     /// Calculates the tangents and bitangents for the imported meshes.
@@ -262,7 +551,11 @@ impl Importer {
     pub fn calc_tangent_space<F: Fn(&mut CalcTangentSpace)>(&mut self, closure: F) {
         let mut args = CalcTangentSpace::default();
         closure(&mut args);
+        self.set_calc_tangent_space(args);
+    }
 
+    /// Like `calc_tangent_space`, but taking the arguments by value.
+    pub fn set_calc_tangent_space(&mut self, args: CalcTangentSpace) {
         self.set_import_flag(aiPostProcessSteps_aiProcess_CalcTangentSpace, args.enable);
         if args.enable {
             self.set_float_property(
@@ -339,7 +632,11 @@ impl Importer {
     pub fn remove_component<F: Fn(&mut RemoveComponent)>(&mut self, closure: F) {
         let mut args = RemoveComponent::default();
         closure(&mut args);
+        self.set_remove_component(args);
+    }
 
+    /// Like `remove_component`, but taking the arguments by value.
+    pub fn set_remove_component(&mut self, args: RemoveComponent) {
         self.set_import_flag(aiPostProcessSteps_aiProcess_RemoveComponent, args.enable);
         if args.enable {
             self.set_int_property(
@@ -365,7 +662,11 @@ impl Importer {
     pub fn generate_normals<F: Fn(&mut GenerateNormals)>(&mut self, closure: F) {
         let mut args = GenerateNormals::default();
         closure(&mut args);
+        self.set_generate_normals(args);
+    }
 
+    /// Like `generate_normals`, but taking the arguments by value.
+    pub fn set_generate_normals(&mut self, args: GenerateNormals) {
         if args.enable {
             if args.smooth {
                 self.flags |= aiPostProcessSteps_aiProcess_GenSmoothNormals;
@@ -397,10 +698,17 @@ impl Importer {
     ///
     /// Note that splitting is generally a time-consuming task, but only if there's something to
     /// split. The use of this step is recommended for most users.
+    ///
+    /// Assimp doesn't record which of the output meshes came from the same input mesh, use
+    /// `Scene::mesh_groups` to recover this.
     pub fn split_large_meshes<F: Fn(&mut SplitLargeMeshes)>(&mut self, closure: F) {
         let mut args = SplitLargeMeshes::default();
         closure(&mut args);
+        self.set_split_large_meshes(args);
+    }
 
+    /// Like `split_large_meshes`, but taking the arguments by value.
+    pub fn set_split_large_meshes(&mut self, args: SplitLargeMeshes) {
         self.set_import_flag(aiPostProcessSteps_aiProcess_SplitLargeMeshes, args.enable);
         if args.enable {
             self.set_int_property(
@@ -432,7 +740,11 @@ impl Importer {
     pub fn pre_transform_vertices<F: Fn(&mut PreTransformVertices)>(&mut self, closure: F) {
         let mut args = PreTransformVertices::default();
         closure(&mut args);
+        self.set_pre_transform_vertices(args);
+    }
 
+    /// Like `pre_transform_vertices`, but taking the arguments by value.
+    pub fn set_pre_transform_vertices(&mut self, args: PreTransformVertices) {
         self.set_import_flag(aiPostProcessSteps_aiProcess_PreTransformVertices, args.enable);
         if args.enable {
             self.set_bool_property(
@@ -475,7 +787,11 @@ impl Importer {
     pub fn limit_bone_weights<F: Fn(&mut LimitBoneWeights)>(&mut self, closure: F) {
         let mut args = LimitBoneWeights::default();
         closure(&mut args);
+        self.set_limit_bone_weights(args);
+    }
 
+    /// Like `limit_bone_weights`, but taking the arguments by value.
+    pub fn set_limit_bone_weights(&mut self, args: LimitBoneWeights) {
         self.set_import_flag(aiPostProcessSteps_aiProcess_LimitBoneWeights, args.enable);
         if args.enable {
             self.set_int_property(
@@ -504,6 +820,10 @@ impl Importer {
     ///   in #aiScene::mFlags</li>
     ///
     /// This post-processing step is not time-consuming. Its use is not compulsory, but recommended.
+    ///
+    /// While it's enabled, the read methods capture the log during each import and parse the
+    /// messages of the step: warnings are available from `Scene::validation_findings`, and
+    /// errors fail the import with `Error::ValidationFailed`.
     pub fn validate_data_structure(&mut self, enable: bool) {
         self.set_import_flag(aiPostProcessSteps_aiProcess_ValidateDataStructure, enable);
     }
@@ -519,7 +839,11 @@ impl Importer {
     pub fn improve_cache_locality<F: Fn(&mut ImproveCacheLocality)>(&mut self, closure: F) {
         let mut args = ImproveCacheLocality::default();
         closure(&mut args);
+        self.set_improve_cache_locality(args);
+    }
 
+    /// Like `improve_cache_locality`, but taking the arguments by value.
+    pub fn set_improve_cache_locality(&mut self, args: ImproveCacheLocality) {
         self.set_import_flag(aiPostProcessSteps_aiProcess_ImproveCacheLocality, args.enable);
         if args.enable {
             self.set_int_property(
@@ -545,7 +869,11 @@ impl Importer {
     pub fn remove_redudant_materials<F: Fn(&mut RemoveRedundantMaterials)>(&mut self, closure: F) {
         let mut args = RemoveRedundantMaterials::default();
         closure(&mut args);
+        self.set_remove_redundant_materials(args);
+    }
 
+    /// Like `remove_redudant_materials`, but taking the arguments by value.
+    pub fn set_remove_redundant_materials(&mut self, args: RemoveRedundantMaterials) {
         self.set_import_flag(aiPostProcessSteps_aiProcess_RemoveRedundantMaterials, args.enable);
         if args.enable {
             self.set_string_property(
@@ -582,7 +910,11 @@ impl Importer {
     pub fn sort_by_primitive_type<F: Fn(&mut SortByPrimitiveType)>(&mut self, closure: F) {
         let mut args = SortByPrimitiveType::default();
         closure(&mut args);
+        self.set_sort_by_primitive_type(args);
+    }
 
+    /// Like `sort_by_primitive_type`, but taking the arguments by value.
+    pub fn set_sort_by_primitive_type(&mut self, args: SortByPrimitiveType) {
         self.set_import_flag(aiPostProcessSteps_aiProcess_SortByPType, args.enable);
         if args.enable {
             // Removing all primitives is a bad thing and causes Assimp to segfault when
@@ -627,7 +959,11 @@ impl Importer {
     pub fn find_degenerates<F: Fn(&mut FindDegenerates)>(&mut self, closure: F) {
         let mut args = FindDegenerates::default();
         closure(&mut args);
+        self.set_find_degenerates(args);
+    }
 
+    /// Like `find_degenerates`, but taking the arguments by value.
+    pub fn set_find_degenerates(&mut self, args: FindDegenerates) {
         self.set_import_flag(aiPostProcessSteps_aiProcess_FindDegenerates, args.enable);
         if args.enable {
             self.set_bool_property(
@@ -649,7 +985,11 @@ impl Importer {
     pub fn find_invalid_data<F: Fn(&mut FindInvalidData)>(&mut self, closure: F) {
         let mut args = FindInvalidData::default();
         closure(&mut args);
+        self.set_find_invalid_data(args);
+    }
 
+    /// Like `find_invalid_data`, but taking the arguments by value.
+    pub fn set_find_invalid_data(&mut self, args: FindInvalidData) {
         self.set_import_flag(aiPostProcessSteps_aiProcess_FindInvalidData, args.enable);
         if args.enable {
             self.set_float_property(
@@ -691,7 +1031,11 @@ impl Importer {
     pub fn transform_uv_coords<F: Fn(&mut TransformUVCoords)>(&mut self, closure: F) {
         let mut args = TransformUVCoords::default();
         closure(&mut args);
+        self.set_transform_uv_coords(args);
+    }
 
+    /// Like `transform_uv_coords`, but taking the arguments by value.
+    pub fn set_transform_uv_coords(&mut self, args: TransformUVCoords) {
         self.set_import_flag(aiPostProcessSteps_aiProcess_TransformUVCoords, args.enable);
         if args.enable {
             self.set_int_property(
@@ -719,9 +1063,49 @@ impl Importer {
     ///
     /// This is a very effective optimization and is recommended to be used together with
     /// `optimize_graph`, if possible. The flag is fully compatible with both `split_large_meshes`
-    /// and `sort_by_primitive_type`.
-    pub fn optimize_meshes(&mut self, enable: bool) {
-        self.set_import_flag(aiPostProcessSteps_aiProcess_OptimizeMeshes, enable);
+    /// and `sort_by_primitive_type`. Only meshes attached to the same node are merged, so
+    /// without `optimize_graph` the node hierarchy is kept. With `sort_by_primitive_type`, only
+    /// meshes with the same primitive types are merged, so the sorted meshes stay sorted even
+    /// though this step runs first.
+    ///
+    /// Scenes imported with this step record how many meshes there were before and after it,
+    /// see `Scene::import_diagnostics`.
+    pub fn optimize_meshes<F: Fn(&mut OptimizeMeshes)>(&mut self, closure: F) {
+        let mut args = OptimizeMeshes::default();
+        closure(&mut args);
+        self.set_optimize_meshes(args);
+    }
+
+    /// Like `optimize_meshes`, but taking the arguments by value.
+    pub fn set_optimize_meshes(&mut self, args: OptimizeMeshes) {
+        self.set_import_flag(aiPostProcessSteps_aiProcess_OptimizeMeshes, args.enable);
+        if args.enable {
+            if !args.exclude_list.is_empty() {
+                self.set_string_property(
+                    CStr::from_bytes_with_nul(AI_CONFIG_PP_OG_EXCLUDE_LIST)
+                        .unwrap()
+                        .to_str()
+                        .unwrap(),
+                    &args.exclude_list,
+                );
+            }
+            if let Some((vertex_limit, triangle_limit)) = args.size_limits {
+                self.set_int_property(
+                    CStr::from_bytes_with_nul(AI_CONFIG_PP_SLM_VERTEX_LIMIT)
+                        .unwrap()
+                        .to_str()
+                        .unwrap(),
+                    vertex_limit as i32,
+                );
+                self.set_int_property(
+                    CStr::from_bytes_with_nul(AI_CONFIG_PP_SLM_TRIANGLE_LIMIT)
+                        .unwrap()
+                        .to_str()
+                        .unwrap(),
+                    triangle_limit as i32,
+                );
+            }
+        }
     }
 
     /// A postprocessing step to optimize the scene hierarchy.
@@ -746,7 +1130,11 @@ impl Importer {
     pub fn optimize_graph<F: Fn(&mut OptimizeGraph)>(&mut self, closure: F) {
         let mut args = OptimizeGraph::default();
         closure(&mut args);
+        self.set_optimize_graph(args);
+    }
 
+    /// Like `optimize_graph`, but taking the arguments by value.
+    pub fn set_optimize_graph(&mut self, args: OptimizeGraph) {
         self.set_import_flag(aiPostProcessSteps_aiProcess_OptimizeGraph, args.enable);
         if args.enable {
             self.set_string_property(
@@ -795,7 +1183,11 @@ impl Importer {
     pub fn split_by_bone_count<F: Fn(&mut SplitByBoneCount)>(&mut self, closure: F) {
         let mut args = SplitByBoneCount::default();
         closure(&mut args);
+        self.set_split_by_bone_count(args);
+    }
 
+    /// Like `split_by_bone_count`, but taking the arguments by value.
+    pub fn set_split_by_bone_count(&mut self, args: SplitByBoneCount) {
         self.set_import_flag(aiPostProcessSteps_aiProcess_SplitByBoneCount, args.enable);
         if args.enable {
             self.set_int_property(
@@ -818,7 +1210,11 @@ impl Importer {
     pub fn debone<F: Fn(&mut Debone)>(&mut self, closure: F) {
         let mut args = Debone::default();
         closure(&mut args);
+        self.set_debone(args);
+    }
 
+    /// Like `debone`, but taking the arguments by value.
+    pub fn set_debone(&mut self, args: Debone) {
         self.set_import_flag(aiPostProcessSteps_aiProcess_Debone, args.enable);
         if args.enable {
             self.set_float_property(
@@ -860,6 +1256,51 @@ impl Importer {
         );
     }
 
+    /// Configure the OBJ importer. See `ObjOptions` for the available options and their
+    /// defaults.
+    pub fn obj_options<F: Fn(&mut ObjOptions)>(&mut self, closure: F) {
+        let mut args = ObjOptions::default();
+        closure(&mut args);
+        self.set_obj_options(args);
+    }
+
+    /// Like `obj_options`, but taking the arguments by value.
+    pub fn set_obj_options(&mut self, args: ObjOptions) {
+        self.set_bool_property(
+            CStr::from_bytes_with_nul(AI_CONFIG_IMPORT_OBJ_SEPARATE_BY_MATERIAL)
+                .unwrap()
+                .to_str()
+                .unwrap(),
+            args.separate_by_material,
+        );
+    }
+
+    /// Configure the FBX importer. See `FbxOptions` for the available options and their
+    /// defaults.
+    ///
+    /// This sets every FBX option at once, so it overrides any earlier calls to the individual
+    /// `fbx_*` methods.
+    pub fn fbx_options<F: Fn(&mut FbxOptions)>(&mut self, closure: F) {
+        let mut args = FbxOptions::default();
+        closure(&mut args);
+        self.set_fbx_options(args);
+    }
+
+    /// Like `fbx_options`, but taking the arguments by value.
+    pub fn set_fbx_options(&mut self, args: FbxOptions) {
+        self.fbx_read_all_geometry_layers(args.read_all_geometry_layers);
+        self.fbx_read_all_materials(args.read_all_materials);
+        self.fbx_read_materials(args.read_materials);
+        self.fbx_read_textures(args.read_textures);
+        self.fbx_read_cameras(args.read_cameras);
+        self.fbx_read_lights(args.read_lights);
+        self.fbx_read_animations(args.read_animations);
+        self.fbx_strict_mode(args.strict_mode);
+        self.fbx_preserve_pivots(args.preserve_pivots);
+        self.fbx_optimize_empty_animation_curves(args.optimize_empty_animation_curves);
+        self.fbx_convert_to_meters(args.convert_to_meters);
+    }
+
     /// Set whether the FBX importer will merge all geometry layers present in the source file or
     /// take only the first.
     ///
@@ -987,6 +1428,19 @@ impl Importer {
         );
     }
 
+    /// Set whether the FBX importer will convert the scene from the file's units to meters.
+    ///
+    /// Default: false.
+    pub fn fbx_convert_to_meters(&mut self, enable: bool) {
+        self.set_bool_property(
+            CStr::from_bytes_with_nul(AI_CONFIG_FBX_CONVERT_TO_M)
+                .unwrap()
+                .to_str()
+                .unwrap(),
+            enable,
+        );
+    }
+
     /// Set the vertex animation keyframe to be imported
     ///
     /// Assimp does not support vertex keyframes (only bone animation is supported). The library
@@ -1358,6 +1812,109 @@ impl Importer {
         );
     }
 
+    /// Tells the Collada importer to name nodes and meshes using the Collada `name` attribute
+    /// instead of the `id` attribute.
+    ///
+    /// Default: false.
+    pub fn collada_use_collada_names(&mut self, enable: bool) {
+        self.set_bool_property(
+            CStr::from_bytes_with_nul(AI_CONFIG_IMPORT_COLLADA_USE_COLLADA_NAMES)
+                .unwrap()
+                .to_str()
+                .unwrap(),
+            enable,
+        );
+    }
+
+    /// Configure the Collada importer. See `ColladaOptions` for the available options and their
+    /// defaults.
+    pub fn collada_options<F: Fn(&mut ColladaOptions)>(&mut self, closure: F) {
+        let mut args = ColladaOptions::default();
+        closure(&mut args);
+        self.set_collada_options(args);
+    }
+
+    /// Like `collada_options`, but taking the arguments by value.
+    pub fn set_collada_options(&mut self, args: ColladaOptions) {
+        self.collada_ignore_up_direction(args.ignore_up_direction);
+        self.collada_use_collada_names(args.use_collada_names);
+    }
+
+    /// Configure the glTF importer. See `GltfOptions` for the available options and their
+    /// defaults.
+    ///
+    /// The glTF importer has no options of its own for these, so they set options shared by
+    /// every format: `keyframe` sets `global_keyframe`, and `apply_scene_scale` enables the
+    /// global scale step. Each is only set if it differs from its default, so that changing
+    /// one option doesn't undo configuration done elsewhere.
+    pub fn gltf_options<F: Fn(&mut GltfOptions)>(&mut self, closure: F) {
+        let mut args = GltfOptions::default();
+        closure(&mut args);
+        self.set_gltf_options(args);
+    }
+
+    /// Like `gltf_options`, but taking the arguments by value.
+    pub fn set_gltf_options(&mut self, args: GltfOptions) {
+        if args.keyframe != 0 {
+            self.global_keyframe(args.keyframe);
+        }
+        if args.apply_scene_scale {
+            self.set_import_flag(aiPostProcessSteps_aiProcess_GlobalScale, true);
+            self.set_float_property(
+                CStr::from_bytes_with_nul(AI_CONFIG_GLOBAL_SCALE_FACTOR_KEY)
+                    .unwrap()
+                    .to_str()
+                    .unwrap(),
+                args.scale_factor,
+            );
+        }
+    }
+
+    /// Configure every post-process step, and the format options that are `Some`, from an
+    /// `ImporterConfig`, e.g. one deserialized from a settings file. This is the same as calling
+    /// each `set_*` method in the order of the fields, so every step that isn't enabled in the
+    /// config is disabled, and `optimize_graph` wins over `optimize_meshes` for the exclude
+    /// list they share.
+    pub fn apply_config(&mut self, config: ImporterConfig) {
+        self.set_calc_tangent_space(config.calc_tangent_space);
+        self.join_identical_vertices(config.join_identical_vertices);
+        self.make_left_handed(config.make_left_handed);
+        self.triangulate(config.triangulate);
+        self.set_remove_component(config.remove_component);
+        self.set_generate_normals(config.generate_normals);
+        self.set_split_large_meshes(config.split_large_meshes);
+        self.set_pre_transform_vertices(config.pre_transform_vertices);
+        self.set_limit_bone_weights(config.limit_bone_weights);
+        self.validate_data_structure(config.validate_data_structure);
+        self.set_improve_cache_locality(config.improve_cache_locality);
+        self.set_remove_redundant_materials(config.remove_redundant_materials);
+        self.fix_infacing_normals(config.fix_infacing_normals);
+        self.set_sort_by_primitive_type(config.sort_by_primitive_type);
+        self.set_find_degenerates(config.find_degenerates);
+        self.set_find_invalid_data(config.find_invalid_data);
+        self.gen_uv_coords(config.gen_uv_coords);
+        self.set_transform_uv_coords(config.transform_uv_coords);
+        self.find_instances(config.find_instances);
+        self.set_optimize_meshes(config.optimize_meshes);
+        self.set_optimize_graph(config.optimize_graph);
+        self.flip_uvs(config.flip_uvs);
+        self.flip_winding_order(config.flip_winding_order);
+        self.set_split_by_bone_count(config.split_by_bone_count);
+        self.set_debone(config.debone);
+        if let Some(options) = config.obj_options {
+            self.set_obj_options(options);
+        }
+        if let Some(options) = config.fbx_options {
+            self.set_fbx_options(options);
+        }
+        if let Some(options) = config.collada_options {
+            self.set_collada_options(options);
+        }
+        if let Some(options) = config.gltf_options {
+            self.set_gltf_options(options);
+        }
+    }
+
     /// Get a list of all file extensions supported by Assimp.
     ///
     /// If a file extension is contained in the list this does, of course, not mean that Assimp is
@@ -1370,13 +1927,194 @@ impl Importer {
         let mut ext_list = aiString { length: 0, data: [0; 1024] };
         unsafe { aiGetExtensionList(&mut ext_list) };
 
-        let extensions = unsafe { crate::aistring_to_cstr(&ext_list).to_str().unwrap().split(';') };
+        let extensions = crate::aistring_to_str(&ext_list).unwrap_or("").split(';');
         extensions.map(|x| x.trim_start_matches("*.").to_owned()).collect()
     }
 }
 
+impl Clone for Importer {
+    /// Create an importer with the same post-process steps, properties and format filter. The
+    /// clone gets its own property store, so setting a property on one doesn't affect the
+    /// other.
+    fn clone(&self) -> Importer {
+        let mut importer = Importer::new();
+        importer.flags = self.flags;
+        for (name, value) in &self.properties {
+            importer.set_property(name, value.clone());
+        }
+        importer.format_filter = self.format_filter.clone();
+        importer.instrumented = self.instrumented;
+        importer.limits = self.limits;
+        importer
+    }
+}
+
 impl Drop for Importer {
     fn drop(&mut self) {
-        unsafe { aiReleasePropertyStore(self.property_store) }
+        if !self.property_store.is_null() {
+            unsafe { aiReleasePropertyStore(self.property_store) }
+        }
+    }
+}
+
+/// Why a path can't be passed to Assimp's `char *` API.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum PathError {
+    Nul,
+    /// Only returned on platforms where paths aren't bytes.
+    #[cfg_attr(unix, allow(dead_code))]
+    NotUnicode,
+}
+
+/// The error message of the last failed import, from `aiGetErrorString`.
+fn last_error<'a>() -> &'a str {
+    let error_str = unsafe { aiGetErrorString() };
+    if error_str.is_null() {
+        "Unknown error"
+    } else {
+        unsafe {
+            CStr::from_ptr(error_str)
+                .to_str()
+                .unwrap_or("Unknown error")
+        }
+    }
+}
+
+impl From<PathError> for Error {
+    fn from(error: PathError) -> Error {
+        let message = match error {
+            PathError::Nul => "File path contains a nul byte",
+            PathError::NotUnicode => "File path isn't valid Unicode",
+        };
+        Error::InvalidConfig {
+            message: message.to_owned(),
+        }
+    }
+}
+
+#[cfg(unix)]
+fn path_to_cstring(path: &Path) -> Result<CString, PathError> {
+    use std::os::unix::ffi::OsStrExt;
+
+    CString::new(path.as_os_str().as_bytes()).map_err(|_| PathError::Nul)
+}
+
+/// Assimp converts paths from UTF-8 to the native encoding itself on Windows.
+#[cfg(not(unix))]
+fn path_to_cstring(path: &Path) -> Result<CString, PathError> {
+    let path = path.to_str().ok_or(PathError::NotUnicode)?;
+    CString::new(path).map_err(|_| PathError::Nul)
+}
+
+/// Opens a file whose path can't be passed to Assimp, and the files next to it, by their
+/// native paths. Assimp is given a made-up name with the same extension, which it needs to
+/// pick an importer, and asks for other files relative to it.
+#[cfg(windows)]
+struct NativePathIO {
+    path: std::path::PathBuf,
+    virtual_name: String,
+}
+
+#[cfg(windows)]
+impl NativePathIO {
+    fn new(path: &Path) -> NativePathIO {
+        let extension = path
+            .extension()
+            .map(|ext| ext.to_string_lossy().into_owned());
+        let virtual_name = match extension {
+            Some(extension) => format!("file.{}", extension),
+            None => "file".to_owned(),
+        };
+
+        NativePathIO {
+            path: path.to_owned(),
+            virtual_name,
+        }
+    }
+
+    fn virtual_name(&self) -> &str {
+        &self.virtual_name
+    }
+}
+
+#[cfg(windows)]
+impl FileIO for NativePathIO {
+    fn open(&self, file_path: &str, mode: &str) -> Option<Box<dyn File>> {
+        if mode.contains('w') || mode.contains('a') {
+            return None;
+        }
+
+        let path = if file_path == self.virtual_name {
+            self.path.clone()
+        } else {
+            self.path
+                .parent()
+                .unwrap_or_else(|| Path::new(""))
+                .join(file_path)
+        };
+        let file = std::fs::File::open(path).ok()?;
+        Some(Box::new(NativeFile(file)))
+    }
+}
+
+/// A file opened from the file system, for the `FileIO`s that only open particular files.
+struct NativeFile(std::fs::File);
+
+impl File for NativeFile {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        use std::io::Read;
+
+        // Assimp expects short reads only at the end of the file.
+        let mut total = 0;
+        while total < buf.len() {
+            match self.0.read(&mut buf[total..]) {
+                Ok(0) => break,
+                Ok(read) => total += read,
+                Err(ref err) if err.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(total)
+    }
+
+    fn write(&mut self, _buf: &[u8]) -> std::io::Result<usize> {
+        Err(std::io::ErrorKind::PermissionDenied.into())
+    }
+
+    fn tell(&mut self) -> u64 {
+        use std::io::Seek;
+
+        self.0.seek(SeekFrom::Current(0)).unwrap_or(0)
+    }
+
+    fn size(&mut self) -> u64 {
+        self.0
+            .metadata()
+            .map(|metadata| metadata.len())
+            .unwrap_or(0)
+    }
+
+    fn seek(&mut self, seek_from: SeekFrom) -> std::io::Result<()> {
+        use std::io::Seek;
+
+        self.0.seek(seek_from).map(|_| ())
+    }
+
+    fn flush(&mut self) {}
+
+    fn close(&mut self) {}
+}
+
+/// Write one property to `store`, which must be a live property store.
+unsafe fn write_property(store: *mut aiPropertyStore, name: &str, value: &ImportProperty) {
+    let cstr = CString::new(name).unwrap();
+    match value {
+        ImportProperty::Int(value) => aiSetImportPropertyInteger(store, cstr.as_ptr(), *value),
+        ImportProperty::Float(value) => aiSetImportPropertyFloat(store, cstr.as_ptr(), *value),
+        ImportProperty::Matrix(value) => aiSetImportPropertyMatrix(store, cstr.as_ptr(), &**value),
+        ImportProperty::String(value) => {
+            let aistr = crate::str_to_aistring(value);
+            aiSetImportPropertyString(store, cstr.as_ptr(), &aistr)
+        }
     }
 }
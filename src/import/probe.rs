@@ -0,0 +1,147 @@
+//! Quick summaries of files without keeping the scene, see `Importer::probe`.
+
+use std::path::Path;
+
+use crate::math::Vector3D;
+use crate::scene::convert::Axis;
+use crate::scene::{Mesh, Scene};
+use crate::Error;
+
+use super::structs::ComponentTypes;
+use super::Importer;
+
+/// An axis-aligned bounding box.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Aabb {
+    pub min: Vector3D,
+    pub max: Vector3D,
+}
+
+impl Aabb {
+    /// The bounds of the positions of `mesh`, in the mesh's own space, or `None` if it has no
+    /// vertices.
+    pub fn of_mesh(mesh: &Mesh) -> Option<Aabb> {
+        let mut positions = mesh.positions();
+        let first = positions.next()?;
+        Some(positions.fold(
+            Aabb {
+                min: first,
+                max: first,
+            },
+            |aabb, p| Aabb {
+                min: Vector3D::new(
+                    aabb.min.x.min(p.x),
+                    aabb.min.y.min(p.y),
+                    aabb.min.z.min(p.z),
+                ),
+                max: Vector3D::new(
+                    aabb.max.x.max(p.x),
+                    aabb.max.y.max(p.y),
+                    aabb.max.z.max(p.z),
+                ),
+            },
+        ))
+    }
+}
+
+/// What `Importer::probe` found in a file.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SceneProbe {
+    pub num_meshes: u32,
+    /// The total number of vertices across all meshes.
+    pub num_vertices: u64,
+    /// The total number of faces across all meshes.
+    pub num_faces: u64,
+    pub num_nodes: u32,
+    /// The bounds of each mesh in its own space, in the order of `Scene::meshes`. Meshes
+    /// without vertices have `None`.
+    pub mesh_bounds: Vec<Option<Aabb>>,
+    /// The name of every node, in the order of `Node::descendants`. Names that aren't valid
+    /// UTF-8 are converted lossily.
+    pub node_names: Vec<String>,
+    /// The up direction from the scene's `UpAxis` and `UpAxisSign` metadata, e.g. `(0, 0, 1)`
+    /// for a Z-up file, see `Scene::source_conventions`. Only some importers, such as FBX,
+    /// record this, and others report `None` even if the file specifies an up direction. The
+    /// Collada importer applies the up direction to the root node's transform instead, unless
+    /// `collada_ignore_up_direction` is set.
+    pub up_axis: Option<Vector3D>,
+}
+
+impl SceneProbe {
+    /// Summarise `scene`, see `Importer::probe`.
+    pub fn from_scene(scene: &Scene) -> SceneProbe {
+        let mut probe = SceneProbe {
+            num_meshes: scene.num_meshes(),
+            up_axis: scene.source_conventions().up.map(Axis::unit),
+            ..Default::default()
+        };
+
+        for mesh in scene.meshes() {
+            probe.num_vertices += mesh.num_vertices() as u64;
+            probe.num_faces += mesh.num_faces() as u64;
+            probe.mesh_bounds.push(Aabb::of_mesh(mesh));
+        }
+        if let Some(root) = scene.root_node() {
+            for node in root.descendants() {
+                probe.num_nodes += 1;
+                probe
+                    .node_names
+                    .push(node.name_cstr().to_string_lossy().into_owned());
+            }
+        }
+
+        probe
+    }
+}
+
+impl Importer {
+    /// Configure the importer to read only the geometry and node hierarchy of a file, as fast
+    /// as possible.
+    ///
+    /// This replaces every post-process step with `remove_component`, removing animations,
+    /// textures, materials, lights, cameras, bone weights and vertex colors, so that they're
+    /// freed as early as possible. Every mesh then uses a single default material. It also
+    /// tells the FBX importer not to read any of those in the first place, and sets
+    /// `favour_speed`. Other properties are left as they are.
+    ///
+    /// Steps enabled after this are run as usual. See `probe` for a summary of a file that
+    /// doesn't keep the scene.
+    pub fn metadata_only(&mut self) {
+        self.flags = 0;
+        self.remove_component(|args| {
+            args.enable = true;
+            args.components = ComponentTypes::ANIMATIONS
+                | ComponentTypes::TEXTURES
+                | ComponentTypes::MATERIALS
+                | ComponentTypes::LIGHTS
+                | ComponentTypes::CAMERAS
+                | ComponentTypes::BONE_WEIGHTS
+                | ComponentTypes::COLORS;
+        });
+        self.fbx_read_animations(false);
+        self.fbx_read_materials(false);
+        self.fbx_read_textures(false);
+        self.fbx_read_cameras(false);
+        self.fbx_read_lights(false);
+        self.favour_speed(true);
+    }
+
+    /// Summarise `file`: its mesh, vertex, face and node counts, the bounds of each mesh, the
+    /// names of its nodes and its up direction, see `SceneProbe`.
+    ///
+    /// The file is read with a copy of this importer's properties and the `metadata_only`
+    /// preset, so this importer's post-process steps aren't run. The scene is freed before
+    /// this returns, so probing many files doesn't hold on to their memory.
+    ///
+    /// How much faster this is than a full import depends on how much of the file isn't
+    /// geometry. `cargo bench --bench probe` compares the two.
+    pub fn probe<P: AsRef<Path>>(&self, file: P) -> Result<SceneProbe, Error> {
+        let mut importer = self.clone();
+        importer.metadata_only();
+
+        let scene = importer.read_file(file)?;
+        let probe = SceneProbe::from_scene(&scene);
+        drop(scene);
+        Ok(probe)
+    }
+}
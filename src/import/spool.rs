@@ -0,0 +1,184 @@
+//! Imports from streams whose size isn't known upfront, see `Importer::read_from_reader`.
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::io::{ByteBudgetFileIO, File, FileIO};
+use crate::scene::Scene;
+use crate::Error;
+
+use super::limits::memory_file_name;
+use super::{Importer, NativeFile};
+
+/// How `Importer::read_from_reader` buffers a stream before importing it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpoolPolicy {
+    /// Read the whole stream into memory and import it from there, failing with
+    /// `Error::BudgetExceeded` if it's longer than `max_size` bytes.
+    Memory { max_size: Option<u64> },
+    /// Read streams of up to `threshold` bytes into memory like `Memory`. Longer streams are
+    /// written to a temporary file in `dir`, or `std::env::temp_dir` if that's `None`, which is
+    /// imported and then deleted, whether or not the import succeeds. This also fails with
+    /// `Error::BudgetExceeded` if the stream is longer than `max_size` bytes.
+    TempFile {
+        threshold: u64,
+        max_size: Option<u64>,
+        dir: Option<PathBuf>,
+    },
+}
+
+impl Default for SpoolPolicy {
+    /// `Memory` without a size limit.
+    fn default() -> Self {
+        SpoolPolicy::Memory { max_size: None }
+    }
+}
+
+/// Numbers the temporary files of this process.
+static NEXT_FILE: AtomicUsize = AtomicUsize::new(0);
+
+/// A temporary file that's deleted when this is dropped.
+struct SpoolFile {
+    path: PathBuf,
+}
+
+impl SpoolFile {
+    /// Create a new file in `dir`, with the extension `hint` if it's given. The file has to be
+    /// closed before the `SpoolFile` is dropped, as Windows can't delete open files.
+    fn create(dir: &Path, hint: Option<&str>) -> io::Result<(SpoolFile, fs::File)> {
+        loop {
+            let mut name = format!(
+                "open-asset-importer-{}-{}",
+                process::id(),
+                NEXT_FILE.fetch_add(1, Ordering::Relaxed)
+            );
+            if let Some(hint) = hint {
+                name.push('.');
+                name.push_str(hint);
+            }
+            let path = dir.join(name);
+            // A file left over from a crashed process with the same id is skipped.
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(file) => return Ok((SpoolFile { path }, file)),
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
+
+impl Drop for SpoolFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Opens the temporary file under the name Assimp is given for it, and no other file.
+struct SpooledFileIO<'a> {
+    path: &'a Path,
+    name: &'a str,
+}
+
+impl FileIO for SpooledFileIO<'_> {
+    fn open(&self, file_path: &str, mode: &str) -> Option<Box<dyn File>> {
+        if file_path != self.name || mode.contains('w') || mode.contains('a') {
+            return None;
+        }
+        let file = fs::File::open(self.path).ok()?;
+        Some(Box::new(NativeFile(file)))
+    }
+}
+
+/// Append the stream to `buffer` until it ends or `buffer` is longer than `limit`.
+fn read_up_to<R: Read>(reader: &mut R, buffer: &mut Vec<u8>, limit: u64) -> io::Result<()> {
+    let remaining = limit.saturating_sub(buffer.len() as u64).saturating_add(1);
+    reader.take(remaining).read_to_end(buffer)?;
+    Ok(())
+}
+
+fn too_long(limit: u64, attempted: u64) -> Error {
+    Error::BudgetExceeded {
+        what: "stream size",
+        limit,
+        attempted,
+    }
+}
+
+impl Importer {
+    /// Load a scene from a stream that may not be seekable or have a known size, such as
+    /// standard input or the body of an HTTP response. `hint` is the file extension of the
+    /// format, e.g. `"obj"`, like for `read_memory_with_hint`.
+    ///
+    /// `spool` decides whether the stream is buffered in memory or in a temporary file. If it's
+    /// longer than the `max_size` of the policy, this returns `Error::BudgetExceeded`, with
+    /// the number of bytes read so far as `attempted`, without reading the rest. Errors while
+    /// reading the stream or writing the temporary file are returned as `Error::Io`.
+    ///
+    /// Like `read_from_memory`, this opens no other files than the stream, so formats that
+    /// refer to other files, e.g. OBJ to its materials, import without them, and the stream
+    /// can't make the importer read local files. The stream is read through a
+    /// `ByteBudgetFileIO` with the `ImportLimits::io_budget` of `limits`, whether it's in
+    /// memory or in a temporary file.
+    pub fn read_from_reader<'a, R: Read>(
+        &self,
+        mut reader: R,
+        hint: Option<&str>,
+        spool: SpoolPolicy,
+    ) -> Result<Scene<'a>, Error> {
+        let hint = hint
+            .map(|hint| hint.trim_start_matches('.'))
+            .filter(|hint| !hint.is_empty());
+        if let Some(hint) = hint {
+            if hint.contains(&['/', '\\', '\0'][..]) {
+                let message = format!("Format hint {:?} isn't a file extension", hint);
+                return Err(Error::InvalidConfig { message });
+            }
+        }
+
+        let (threshold, max_size, dir) = match spool {
+            SpoolPolicy::Memory { max_size } => (u64::MAX, max_size, None),
+            SpoolPolicy::TempFile {
+                threshold,
+                max_size,
+                dir,
+            } => (threshold, max_size, dir),
+        };
+        let max_size = max_size.unwrap_or(u64::MAX);
+
+        let mut buffer = Vec::new();
+        read_up_to(&mut reader, &mut buffer, threshold.min(max_size))?;
+        let len = buffer.len() as u64;
+        if len > max_size {
+            return Err(too_long(max_size, len));
+        }
+        if len <= threshold {
+            return self.read_from_memory(&buffer, hint.unwrap_or(""));
+        }
+
+        let dir = dir.unwrap_or_else(std::env::temp_dir);
+        // `file` is declared after `spooled`, so it's closed first if this returns early.
+        let (spooled, mut file) = SpoolFile::create(&dir, hint)?;
+        file.write_all(&buffer)?;
+        drop(buffer);
+        let remaining = max_size.saturating_sub(len).saturating_add(1);
+        let copied = io::copy(&mut reader.take(remaining), &mut file)?;
+        if len + copied > max_size {
+            return Err(too_long(max_size, len + copied));
+        }
+        file.flush()?;
+        drop(file);
+
+        // Assimp resolves the files a file refers to relative to it, so it's given a made-up
+        // name rather than the path in `dir`.
+        let name = memory_file_name(hint.unwrap_or(""));
+        let spooled_io = SpooledFileIO {
+            path: &spooled.path,
+            name: &name,
+        };
+        let budget_io = ByteBudgetFileIO::new(spooled_io, self.limits.io_budget);
+        self.read_file_with_io(&name, &budget_io)
+    }
+}
@@ -3,6 +3,7 @@
 use ffi::*;
 
 use std::convert::TryFrom;
+use std::fmt;
 
 use crate::math::Matrix4x4;
 
@@ -37,6 +38,159 @@ bitflags::bitflags! {
     }
 }
 
+bitflags::bitflags! {
+    /// The Assimp post-process steps (`aiProcess_*` flags) to run on import. See
+    /// `Importer::effective_flags`.
+    #[derive(Default)]
+    pub struct PostProcessSteps: aiPostProcessSteps {
+        const CALC_TANGENT_SPACE         = aiPostProcessSteps_aiProcess_CalcTangentSpace;
+        const JOIN_IDENTICAL_VERTICES    = aiPostProcessSteps_aiProcess_JoinIdenticalVertices;
+        const MAKE_LEFT_HANDED           = aiPostProcessSteps_aiProcess_MakeLeftHanded;
+        const TRIANGULATE                = aiPostProcessSteps_aiProcess_Triangulate;
+        const REMOVE_COMPONENT           = aiPostProcessSteps_aiProcess_RemoveComponent;
+        const GEN_NORMALS                = aiPostProcessSteps_aiProcess_GenNormals;
+        const GEN_SMOOTH_NORMALS         = aiPostProcessSteps_aiProcess_GenSmoothNormals;
+        const SPLIT_LARGE_MESHES         = aiPostProcessSteps_aiProcess_SplitLargeMeshes;
+        const PRE_TRANSFORM_VERTICES     = aiPostProcessSteps_aiProcess_PreTransformVertices;
+        const LIMIT_BONE_WEIGHTS         = aiPostProcessSteps_aiProcess_LimitBoneWeights;
+        const VALIDATE_DATA_STRUCTURE    = aiPostProcessSteps_aiProcess_ValidateDataStructure;
+        const IMPROVE_CACHE_LOCALITY     = aiPostProcessSteps_aiProcess_ImproveCacheLocality;
+        const REMOVE_REDUNDANT_MATERIALS = aiPostProcessSteps_aiProcess_RemoveRedundantMaterials;
+        const FIX_INFACING_NORMALS       = aiPostProcessSteps_aiProcess_FixInfacingNormals;
+        const SORT_BY_PTYPE              = aiPostProcessSteps_aiProcess_SortByPType;
+        const FIND_DEGENERATES           = aiPostProcessSteps_aiProcess_FindDegenerates;
+        const FIND_INVALID_DATA          = aiPostProcessSteps_aiProcess_FindInvalidData;
+        const GEN_UV_COORDS              = aiPostProcessSteps_aiProcess_GenUVCoords;
+        const TRANSFORM_UV_COORDS        = aiPostProcessSteps_aiProcess_TransformUVCoords;
+        const FIND_INSTANCES             = aiPostProcessSteps_aiProcess_FindInstances;
+        const OPTIMIZE_MESHES            = aiPostProcessSteps_aiProcess_OptimizeMeshes;
+        const OPTIMIZE_GRAPH             = aiPostProcessSteps_aiProcess_OptimizeGraph;
+        const FLIP_UVS                   = aiPostProcessSteps_aiProcess_FlipUVs;
+        const FLIP_WINDING_ORDER         = aiPostProcessSteps_aiProcess_FlipWindingOrder;
+        const SPLIT_BY_BONE_COUNT        = aiPostProcessSteps_aiProcess_SplitByBoneCount;
+        const DEBONE                     = aiPostProcessSteps_aiProcess_Debone;
+        const GLOBAL_SCALE               = aiPostProcessSteps_aiProcess_GlobalScale;
+    }
+}
+
+/// The name Assimp uses for each post-process step, for `Display`.
+const POST_PROCESS_STEP_NAMES: [(PostProcessSteps, &str); 27] = [
+    (
+        PostProcessSteps::CALC_TANGENT_SPACE,
+        "aiProcess_CalcTangentSpace",
+    ),
+    (
+        PostProcessSteps::JOIN_IDENTICAL_VERTICES,
+        "aiProcess_JoinIdenticalVertices",
+    ),
+    (
+        PostProcessSteps::MAKE_LEFT_HANDED,
+        "aiProcess_MakeLeftHanded",
+    ),
+    (PostProcessSteps::TRIANGULATE, "aiProcess_Triangulate"),
+    (
+        PostProcessSteps::REMOVE_COMPONENT,
+        "aiProcess_RemoveComponent",
+    ),
+    (PostProcessSteps::GEN_NORMALS, "aiProcess_GenNormals"),
+    (
+        PostProcessSteps::GEN_SMOOTH_NORMALS,
+        "aiProcess_GenSmoothNormals",
+    ),
+    (
+        PostProcessSteps::SPLIT_LARGE_MESHES,
+        "aiProcess_SplitLargeMeshes",
+    ),
+    (
+        PostProcessSteps::PRE_TRANSFORM_VERTICES,
+        "aiProcess_PreTransformVertices",
+    ),
+    (
+        PostProcessSteps::LIMIT_BONE_WEIGHTS,
+        "aiProcess_LimitBoneWeights",
+    ),
+    (
+        PostProcessSteps::VALIDATE_DATA_STRUCTURE,
+        "aiProcess_ValidateDataStructure",
+    ),
+    (
+        PostProcessSteps::IMPROVE_CACHE_LOCALITY,
+        "aiProcess_ImproveCacheLocality",
+    ),
+    (
+        PostProcessSteps::REMOVE_REDUNDANT_MATERIALS,
+        "aiProcess_RemoveRedundantMaterials",
+    ),
+    (
+        PostProcessSteps::FIX_INFACING_NORMALS,
+        "aiProcess_FixInfacingNormals",
+    ),
+    (PostProcessSteps::SORT_BY_PTYPE, "aiProcess_SortByPType"),
+    (
+        PostProcessSteps::FIND_DEGENERATES,
+        "aiProcess_FindDegenerates",
+    ),
+    (
+        PostProcessSteps::FIND_INVALID_DATA,
+        "aiProcess_FindInvalidData",
+    ),
+    (PostProcessSteps::GEN_UV_COORDS, "aiProcess_GenUVCoords"),
+    (
+        PostProcessSteps::TRANSFORM_UV_COORDS,
+        "aiProcess_TransformUVCoords",
+    ),
+    (PostProcessSteps::FIND_INSTANCES, "aiProcess_FindInstances"),
+    (
+        PostProcessSteps::OPTIMIZE_MESHES,
+        "aiProcess_OptimizeMeshes",
+    ),
+    (PostProcessSteps::OPTIMIZE_GRAPH, "aiProcess_OptimizeGraph"),
+    (PostProcessSteps::FLIP_UVS, "aiProcess_FlipUVs"),
+    (
+        PostProcessSteps::FLIP_WINDING_ORDER,
+        "aiProcess_FlipWindingOrder",
+    ),
+    (
+        PostProcessSteps::SPLIT_BY_BONE_COUNT,
+        "aiProcess_SplitByBoneCount",
+    ),
+    (PostProcessSteps::DEBONE, "aiProcess_Debone"),
+    (PostProcessSteps::GLOBAL_SCALE, "aiProcess_GlobalScale"),
+];
+
+impl fmt::Display for PostProcessSteps {
+    /// Lists the steps using their Assimp names, e.g. `aiProcess_Triangulate | aiProcess_FlipUVs`,
+    /// so that the output can be compared directly with C++ code.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut first = true;
+
+        for &(step, name) in &POST_PROCESS_STEP_NAMES {
+            if self.contains(step) {
+                if !first {
+                    write!(f, " | ")?;
+                }
+                write!(f, "{}", name)?;
+                first = false;
+            }
+        }
+
+        if first {
+            write!(f, "(none)")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A value set in the importer's property store, see `Importer::property`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImportProperty {
+    Int(i32),
+    Float(f32),
+    String(String),
+    Matrix(Matrix4x4),
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 #[repr(u32)]
 pub enum PrimitiveType {
@@ -47,15 +201,15 @@ pub enum PrimitiveType {
 }
 
 impl TryFrom<u32> for PrimitiveType {
-    type Error = ();
+    type Error = crate::Error;
 
-    fn try_from(other: u32) -> Result<Self, ()> {
+    fn try_from(other: u32) -> Result<Self, Self::Error> {
         match other {
             ffi::aiPrimitiveType_aiPrimitiveType_POINT => Ok(Self::Point),
             ffi::aiPrimitiveType_aiPrimitiveType_LINE => Ok(Self::Line),
             ffi::aiPrimitiveType_aiPrimitiveType_TRIANGLE => Ok(Self::Triangle),
             ffi::aiPrimitiveType_aiPrimitiveType_POLYGON => Ok(Self::Polygon),
-            _ => Err(()),
+            _ => Err(crate::Error::unsupported("primitive type", other)),
         }
     }
 }
@@ -83,6 +237,45 @@ bitflags::bitflags! {
     }
 }
 
+// Serializes bitflags as their bits, rejecting bits that aren't one of the flags.
+#[cfg(feature = "serde")]
+macro_rules! serde_bits {
+    ($($i:ident: $t:ty),*) => ($(
+        impl serde::Serialize for $i {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                self.bits().serialize(serializer)
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $i {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<$i, D::Error> {
+                let bits = <$t as serde::Deserialize>::deserialize(deserializer)?;
+                $i::from_bits(bits).ok_or_else(|| {
+                    serde::de::Error::custom(format_args!(
+                        "invalid {} bits {:#x}",
+                        stringify!($i),
+                        bits
+                    ))
+                })
+            }
+        }
+    )*)
+}
+
+#[cfg(feature = "serde")]
+serde_bits! {
+    ComponentTypes: aiComponent,
+    UVTransformFlags: u32,
+    PostProcessSteps: aiPostProcessSteps,
+    PrimitiveTypes: aiPrimitiveType
+}
+
+impl From<PrimitiveType> for PrimitiveTypes {
+    fn from(type_: PrimitiveType) -> Self {
+        PrimitiveTypes::from_bits_truncate(type_ as aiPrimitiveType)
+    }
+}
+
 // Macro to simplify defining and structs and implementing Default trait
 // NOTE: pub keyword in field definition is to workaround rust issue #24189
 macro_rules! struct_with_defaults {
@@ -90,6 +283,9 @@ macro_rules! struct_with_defaults {
         $($(#[$field_attr:meta])* pub $n:ident: $t:ty = $v:expr),*
     }) => (
         $(#[$struct_attr])*
+        #[derive(Debug, Clone, PartialEq)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        #[cfg_attr(feature = "serde", serde(default))]
         pub struct $i {
             /// Whether to enable the step. Default: false
             pub enable: bool,
@@ -107,6 +303,30 @@ macro_rules! struct_with_defaults {
     )
 }
 
+// Like `struct_with_defaults`, but for groups of importer options that aren't tied to a
+// single post-process step and so have no `enable` field.
+macro_rules! options_with_defaults {
+    ($(#[$struct_attr:meta])* struct $i:ident {
+        $($(#[$field_attr:meta])* pub $n:ident: $t:ty = $v:expr),*
+    }) => (
+        $(#[$struct_attr])*
+        #[derive(Debug, Clone, PartialEq)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        #[cfg_attr(feature = "serde", serde(default))]
+        pub struct $i {
+            $($(#[$field_attr])* pub $n: $t),*
+        }
+
+        impl Default for $i {
+            fn default() -> $i {
+                $i {
+                    $($n: $v),*
+                }
+            }
+        }
+    )
+}
+
 struct_with_defaults! {
     /// Arguments for `calc_tangent_space` post-process step.
     struct CalcTangentSpace {
@@ -157,6 +377,7 @@ struct_with_defaults! {
         /// `root_transformation` field. Default: false
         pub add_root_transformation: bool = false,
         /// Transformation matrix to use.
+        #[cfg_attr(feature = "serde", serde(with = "crate::math::matrix4::matrix_rows"))]
         pub root_transformation: Matrix4x4 = Matrix4x4::new(1.0, 0.0, 0.0, 0.0,
                                                             0.0, 1.0, 0.0, 0.0,
                                                             0.0, 0.0, 1.0, 0.0,
@@ -221,6 +442,21 @@ struct_with_defaults! {
     }
 }
 
+struct_with_defaults! {
+    /// Arguments for `optimize_meshes` post-process step.
+    struct OptimizeMeshes {
+        /// Space-delimited list of nodes to keep, in the same format as
+        /// `OptimizeGraph::exclude_list`. Assimp stores both lists in the same property, so
+        /// this is only set if it isn't empty, and whichever of the two steps is configured
+        /// last wins. Default: empty
+        pub exclude_list: String = String::new(),
+        /// The largest number of vertices and triangles that a merged mesh may have. Assimp
+        /// shares these limits with `split_large_meshes` and only applies them if that step is
+        /// enabled too, so they're only set if they're `Some`. Default: `None`
+        pub size_limits: Option<(u32, u32)> = None
+    }
+}
+
 struct_with_defaults! {
     /// Arguments for `optimize_graph` post-process step.
     struct OptimizeGraph {
@@ -247,3 +483,113 @@ struct_with_defaults! {
         pub all_or_none: bool = false
     }
 }
+
+options_with_defaults! {
+    /// Options for the OBJ importer, see `Importer::obj_options`.
+    #[non_exhaustive]
+    struct ObjOptions {
+        /// Whether to split each object into one mesh per material. Default: false
+        pub separate_by_material: bool = false
+    }
+}
+
+options_with_defaults! {
+    /// Options for the FBX importer, see `Importer::fbx_options`.
+    #[non_exhaustive]
+    struct FbxOptions {
+        /// Whether to merge all geometry layers present in the source file or take only the
+        /// first. Default: true
+        pub read_all_geometry_layers: bool = true,
+        /// Whether to read all materials present in the source file or only the referenced
+        /// materials. Has no effect if `read_materials` is false. Default: false
+        pub read_all_materials: bool = false,
+        /// Whether to read materials. Default: true
+        pub read_materials: bool = true,
+        /// Whether to read embedded textures. Default: true
+        pub read_textures: bool = true,
+        /// Whether to read cameras. Default: true
+        pub read_cameras: bool = true,
+        /// Whether to read light sources. Default: true
+        pub read_lights: bool = true,
+        /// Whether to read animations. Default: true
+        pub read_animations: bool = true,
+        /// Whether to reject any FBX version other than FBX 2013. Default: false
+        pub strict_mode: bool = false,
+        /// Whether to preserve pivot points for transformations as extra nodes. Default: true
+        pub preserve_pivots: bool = true,
+        /// Whether to drop empty animation curves, or curves which match the bind pose over
+        /// their entire range. Default: true
+        pub optimize_empty_animation_curves: bool = true,
+        /// Whether to convert the scene from the file's units to meters. Default: false
+        pub convert_to_meters: bool = false
+    }
+}
+
+options_with_defaults! {
+    /// Options for the Collada importer, see `Importer::collada_options`.
+    #[non_exhaustive]
+    struct ColladaOptions {
+        /// Whether to ignore the up direction specified in the file. Default: false
+        pub ignore_up_direction: bool = false,
+        /// Whether to name nodes and meshes using the Collada `name` attribute instead of the
+        /// `id` attribute. Default: false
+        pub use_collada_names: bool = false
+    }
+}
+
+options_with_defaults! {
+    /// Options for the glTF importer, see `Importer::gltf_options`.
+    #[non_exhaustive]
+    struct GltfOptions {
+        /// The keyframe of vertex animations to import. This sets the global keyframe, which
+        /// applies to every format, and 0 leaves it as it is. Default: 0
+        pub keyframe: i32 = 0,
+        /// Whether to apply `scale_factor` to the whole scene. Note that this enables the
+        /// global scale post-process step, which applies to every format, and false leaves
+        /// the step as it is. Default: false
+        pub apply_scene_scale: bool = false,
+        /// The factor to scale the scene by if `apply_scene_scale` is true. Default: 1.0
+        pub scale_factor: f32 = 1.0
+    }
+}
+
+/// Every post-process step and importer option, for configuring an `Importer` from data, e.g.
+/// a settings file, with `Importer::apply_config`. Steps that take arguments use the same
+/// structs as the `Importer` methods, and the format options are left as they are if `None`.
+///
+/// With the `serde` feature, missing fields are taken from `Default`, which enables nothing,
+/// and bitflags such as `RemoveComponent::components` are (de)serialized as their bits.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ImporterConfig {
+    pub calc_tangent_space: CalcTangentSpace,
+    pub join_identical_vertices: bool,
+    pub make_left_handed: bool,
+    pub triangulate: bool,
+    pub remove_component: RemoveComponent,
+    pub generate_normals: GenerateNormals,
+    pub split_large_meshes: SplitLargeMeshes,
+    pub pre_transform_vertices: PreTransformVertices,
+    pub limit_bone_weights: LimitBoneWeights,
+    pub validate_data_structure: bool,
+    pub improve_cache_locality: ImproveCacheLocality,
+    pub remove_redundant_materials: RemoveRedundantMaterials,
+    pub fix_infacing_normals: bool,
+    pub sort_by_primitive_type: SortByPrimitiveType,
+    pub find_degenerates: FindDegenerates,
+    pub find_invalid_data: FindInvalidData,
+    pub gen_uv_coords: bool,
+    pub transform_uv_coords: TransformUVCoords,
+    pub find_instances: bool,
+    pub optimize_meshes: OptimizeMeshes,
+    pub optimize_graph: OptimizeGraph,
+    pub flip_uvs: bool,
+    pub flip_winding_order: bool,
+    pub split_by_bone_count: SplitByBoneCount,
+    pub debone: Debone,
+    pub obj_options: Option<ObjOptions>,
+    pub fbx_options: Option<FbxOptions>,
+    pub collada_options: Option<ColladaOptions>,
+    pub gltf_options: Option<GltfOptions>,
+}
@@ -0,0 +1,232 @@
+//! Imports with a time limit, see `Importer::read_file_with_timeout`.
+
+use std::error::Error;
+use std::fmt;
+use std::mem;
+use std::path::Path;
+use std::ptr::NonNull;
+use std::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use ffi::aiScene;
+
+use crate::io::FileIO;
+use crate::scene::{ImportDiagnostics, Ownership, Scene};
+
+use super::Importer;
+
+/// The default for `set_max_orphaned_imports`.
+pub const DEFAULT_MAX_ORPHANED_IMPORTS: usize = 4;
+
+/// The number of imports that have timed out but are still running.
+static ORPHANED: AtomicUsize = AtomicUsize::new(0);
+static MAX_ORPHANED: AtomicUsize = AtomicUsize::new(DEFAULT_MAX_ORPHANED_IMPORTS);
+
+// The states of an import thread.
+const RUNNING: u8 = 0;
+const FINISHED: u8 = 1;
+const ORPHANED_STATE: u8 = 2;
+
+/// Why `Importer::read_file_with_timeout` failed.
+#[derive(Debug)]
+pub enum TimeoutError {
+    /// The import didn't finish in time. It carries on in the background, and the scene is
+    /// freed when it finishes.
+    Timeout,
+    /// Too many imports that timed out are still running, see `set_max_orphaned_imports`.
+    /// Nothing was imported.
+    Busy,
+    /// The import failed, with the same error `read_file` would have returned.
+    Failed(crate::Error),
+    /// The import thread couldn't be started, or panicked, with a description of what
+    /// happened.
+    Thread(String),
+}
+
+impl fmt::Display for TimeoutError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TimeoutError::Timeout => write!(f, "import timed out"),
+            TimeoutError::Busy => write!(f, "too many timed out imports are still running"),
+            TimeoutError::Failed(err) => write!(f, "import failed: {}", err),
+            TimeoutError::Thread(message) => write!(f, "import thread failed: {}", message),
+        }
+    }
+}
+
+impl Error for TimeoutError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            TimeoutError::Failed(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// The number of imports that have timed out but whose threads are still running.
+pub fn orphaned_imports() -> usize {
+    ORPHANED.load(Ordering::SeqCst)
+}
+
+/// Set how many imports that timed out may still be running before
+/// `Importer::read_file_with_timeout` refuses to start new imports, returning
+/// `TimeoutError::Busy`. This applies to the whole process. The default is
+/// `DEFAULT_MAX_ORPHANED_IMPORTS`.
+pub fn set_max_orphaned_imports(max: usize) {
+    MAX_ORPHANED.store(max, Ordering::SeqCst);
+}
+
+/// An owned scene being handed over from the import thread, with its diagnostics. The scene
+/// is freed if it's dropped without being received, e.g. because the import timed out.
+struct SentScene(NonNull<aiScene>, ImportDiagnostics);
+
+// The scene isn't used by the import thread once it's sent, and Assimp doesn't care which
+// thread frees it.
+unsafe impl Send for SentScene {}
+
+impl SentScene {
+    fn new(scene: Scene) -> SentScene {
+        let diagnostics = scene.import_diagnostics().clone();
+        SentScene(
+            NonNull::new(scene.into_raw() as *mut aiScene).unwrap(),
+            diagnostics,
+        )
+    }
+
+    fn into_scene<'a>(mut self) -> Scene<'a> {
+        let raw = self.0;
+        let diagnostics = mem::take(&mut self.1);
+        mem::forget(self);
+        let mut scene = unsafe { Scene::from_raw(raw, Ownership::Owned) };
+        scene.set_import_diagnostics(diagnostics);
+        scene
+    }
+}
+
+impl Drop for SentScene {
+    fn drop(&mut self) {
+        drop(unsafe { Scene::from_raw(self.0, Ownership::Owned) });
+    }
+}
+
+/// Marks an import thread as finished when it's dropped, even if the import panicked, and
+/// takes it off the orphaned count if the import timed out.
+struct FinishGuard(Arc<AtomicU8>);
+
+impl Drop for FinishGuard {
+    fn drop(&mut self) {
+        if self.0.swap(FINISHED, Ordering::SeqCst) == ORPHANED_STATE {
+            ORPHANED.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+}
+
+type ImportResult = Result<SentScene, crate::Error>;
+
+impl Importer {
+    /// Like `read_file`, but gives up after `timeout`, returning `TimeoutError::Timeout`.
+    ///
+    /// Assimp can't be interrupted, so the import runs on a new thread with a copy of this
+    /// importer's configuration. If it times out, the thread carries on in the background and
+    /// frees the scene when it finishes. To stop broken files from piling up threads, no new
+    /// imports are started while too many of these are still running, see
+    /// `set_max_orphaned_imports`. Imports started at the same time can all time out, so the
+    /// number of background threads can exceed the limit by the number of imports that were
+    /// in progress when it was reached.
+    pub fn read_file_with_timeout<'a, P: AsRef<Path>>(
+        &self,
+        file: P,
+        timeout: Duration,
+    ) -> Result<Scene<'a>, TimeoutError> {
+        let file = file.as_ref().to_owned();
+        self.import_with_timeout(timeout, move |importer| {
+            importer.read_file(&file).map(SentScene::new)
+        })
+    }
+
+    /// Like `read_file_with_io`, but with a time limit, see `read_file_with_timeout`. The
+    /// `FileIO` is moved to the import thread, and dropped there when the import finishes.
+    pub fn read_file_with_io_and_timeout<'a, T: FileIO + Send + 'static>(
+        &self,
+        file: &str,
+        file_io: T,
+        timeout: Duration,
+    ) -> Result<Scene<'a>, TimeoutError> {
+        let file = file.to_owned();
+        self.import_with_timeout(timeout, move |importer| {
+            importer
+                .read_file_with_io(&file, &file_io)
+                .map(SentScene::new)
+        })
+    }
+
+    fn import_with_timeout<'a, F>(
+        &self,
+        timeout: Duration,
+        import: F,
+    ) -> Result<Scene<'a>, TimeoutError>
+    where
+        F: FnOnce(&Importer) -> ImportResult + Send + 'static,
+    {
+        if ORPHANED.load(Ordering::SeqCst) >= MAX_ORPHANED.load(Ordering::SeqCst) {
+            return Err(TimeoutError::Busy);
+        }
+
+        let flags = self.flags;
+        let properties = self.properties.clone();
+        let format_filter = self.format_filter.clone();
+        let instrumented = self.instrumented;
+        let limits = self.limits;
+        let state = Arc::new(AtomicU8::new(RUNNING));
+        let guard = FinishGuard(state.clone());
+        let (sender, receiver) = mpsc::channel::<ImportResult>();
+
+        thread::Builder::new()
+            .name("assimp import".to_owned())
+            .spawn(move || {
+                let _guard = guard;
+                let mut importer = Importer::new();
+                importer.flags = flags;
+                for (name, value) in properties {
+                    importer.set_property(&name, value);
+                }
+                importer.format_filter = format_filter;
+                importer.instrumented = instrumented;
+                importer.limits = limits;
+
+                // If the receiver is gone the scene is dropped, and freed, right here.
+                let _ = sender.send(import(&importer));
+            })
+            .map_err(|err| TimeoutError::Thread(format!("couldn't start: {}", err)))?;
+
+        let received = match receiver.recv_timeout(timeout) {
+            Err(RecvTimeoutError::Timeout) => {
+                // Count the thread as orphaned before it can see that it is, so that the count
+                // never goes negative.
+                ORPHANED.fetch_add(1, Ordering::SeqCst);
+                if state
+                    .compare_exchange(RUNNING, ORPHANED_STATE, Ordering::SeqCst, Ordering::SeqCst)
+                    .is_ok()
+                {
+                    return Err(TimeoutError::Timeout);
+                }
+
+                // The import finished just in time, so the result is on its way.
+                ORPHANED.fetch_sub(1, Ordering::SeqCst);
+                receiver.recv().ok()
+            }
+            Err(RecvTimeoutError::Disconnected) => None,
+            Ok(result) => Some(result),
+        };
+
+        match received {
+            Some(result) => result
+                .map(SentScene::into_scene)
+                .map_err(TimeoutError::Failed),
+            None => Err(TimeoutError::Thread("panicked".to_owned())),
+        }
+    }
+}
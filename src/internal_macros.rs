@@ -13,13 +13,52 @@ macro_rules! define_iter {
 
         #[doc(hidden)]
         impl<'a> $name<'a> {
-            pub fn new(ptr: Option<$raw>, len: usize) -> $name<'a> {
+            /// Create an iterator over the array of `len` elements at `ptr`. The lifetime is
+            /// whatever the caller chooses, so it's up to the caller to tie it to the owner of
+            /// the array, e.g. by returning the iterator from a method that borrows `self`.
+            ///
+            /// # Safety
+            ///
+            /// If `ptr` isn't `None`, it must point to `len` valid elements that aren't modified
+            /// or freed for the lifetime `'a`.
+            pub unsafe fn new(ptr: Option<$raw>, len: usize) -> $name<'a> {
                 $name { ptr, len: len, _mk: ::std::marker::PhantomData }
             }
         }
     )
 }
 
+// Like `define_iter`, for arrays of pointers. The non-null entries are counted once, up front,
+// so that `len` doesn't scan the rest of the array each time.
+macro_rules! define_iter_indirect {
+    ($(#[$iter_attr:meta])* struct $name:ident -> $raw:ty) => (
+        $(#[$iter_attr])*
+        pub struct $name<'a> {
+            ptr: Option<$raw>,
+            len: usize,
+            non_null: usize,
+            _mk: ::std::marker::PhantomData<&'a ()>
+        }
+
+        #[doc(hidden)]
+        impl<'a> $name<'a> {
+            /// Create an iterator over the array of `len` pointers at `ptr`. The lifetime is
+            /// whatever the caller chooses, so it's up to the caller to tie it to the owner of
+            /// the array, e.g. by returning the iterator from a method that borrows `self`.
+            ///
+            /// # Safety
+            ///
+            /// If `ptr` isn't `None`, it must point to `len` pointers that are each null or
+            /// valid, none of which are modified or freed for the lifetime `'a`.
+            pub unsafe fn new(ptr: Option<$raw>, len: usize) -> $name<'a> {
+                let mut iter = $name { ptr, len, non_null: 0, _mk: ::std::marker::PhantomData };
+                iter.non_null = iter.remaining().filter(|&is_null| !is_null).count();
+                iter
+            }
+        }
+    )
+}
+
 macro_rules! impl_iterator {
     ($name:ident, $item:ident) => {
         impl<'a> Iterator for $name<'a> {
@@ -56,28 +95,69 @@ macro_rules! impl_iterator_indirect {
         impl<'a> Iterator for $name<'a> {
             type Item = &'a $item;
 
+            // Null entries are skipped rather than ending the iteration, so that one bad entry
+            // doesn't hide the valid ones after it. Use `null_indices` to find them.
             fn next(&mut self) -> Option<Self::Item> {
-                if self.len > 0 {
+                while self.len > 0 {
                     unsafe {
                         let ptr = self.ptr?;
-
-                        let item =
-                            $item::from_raw(::std::ptr::NonNull::new(*ptr.as_ptr() as *mut _)?);
+                        let element = *ptr.as_ptr();
 
                         self.ptr = ::std::ptr::NonNull::new(ptr.as_ptr().offset(1) as *mut _);
                         self.len -= 1;
 
-                        Some(item)
+                        if let Some(element) = ::std::ptr::NonNull::new(element as *mut _) {
+                            self.non_null -= 1;
+                            return Some($item::from_raw(element));
+                        }
                     }
-                } else {
-                    None
                 }
+
+                None
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                let len = self.len();
+                (len, Some(len))
             }
         }
 
         impl<'a> ExactSizeIterator for $name<'a> {
+            /// The number of non-null entries remaining.
             fn len(&self) -> usize {
-                self.len
+                self.non_null
+            }
+        }
+
+        impl<'a> $name<'a> {
+            /// The indices of the null entries remaining in the array, relative to the next
+            /// entry. These are skipped by the iterator, so this is a way to check that
+            /// nothing was skipped.
+            pub fn null_indices(&self) -> Vec<usize> {
+                self.remaining()
+                    .enumerate()
+                    .filter(|&(_, is_null)| is_null)
+                    .map(|(index, _)| index)
+                    .collect()
+            }
+
+            /// Like collecting the iterator, but returns the indices of the null entries
+            /// instead if there are any.
+            pub fn strict(self) -> Result<Vec<&'a $item>, Vec<usize>> {
+                let nulls = self.null_indices();
+                if nulls.is_empty() {
+                    Ok(self.collect())
+                } else {
+                    Err(nulls)
+                }
+            }
+
+            /// Whether each remaining entry is null.
+            fn remaining(&self) -> impl Iterator<Item = bool> {
+                let ptr = self.ptr;
+                let len = if ptr.is_some() { self.len } else { 0 };
+
+                (0..len).map(move |index| unsafe { (*ptr.unwrap().as_ptr().add(index)).is_null() })
             }
         }
     };
@@ -110,9 +190,30 @@ macro_rules! impl_iterator_pod {
                 self.len
             }
         }
+
+        impl<'a> $name<'a> {
+            /// The remaining elements as a slice. The elements are always contiguous, so this
+            /// only returns `None` if there's no array at all.
+            pub fn as_slice(&self) -> Option<&'a [$item]> {
+                let ptr = self.ptr?;
+
+                // `$item` is a transparent wrapper around the raw type.
+                Some(unsafe { $crate::array_slice(ptr.as_ptr() as *const $item, self.len) })
+            }
+
+            /// The remaining elements in blocks of 8, for loops that the compiler can
+            /// vectorize. The elements after the last full block are left out, and are
+            /// available from `Chunks8::remainder`. This doesn't advance the iterator.
+            pub fn chunks8(&self) -> $crate::math::Chunks8<'a, $item> {
+                $crate::math::Chunks8::new(self.as_slice().unwrap_or(&[]))
+            }
+        }
     };
 }
 
+// The reference types are only handed out as `&'scene $name`, borrowed from the `Scene` or
+// from another borrow of it, so the reference carries the scene's lifetime, and so does
+// everything their accessors borrow from `&self`. Only `from_raw` picks a lifetime of its own.
 macro_rules! define_type {
     // Reference type
     ($(#[$type_attr:meta])* struct $name:ident(&$raw:ty)) => (
@@ -121,7 +222,15 @@ macro_rules! define_type {
         pub struct $name($raw);
 
         impl $name {
-            /// Create a borrow of this struct from a raw pointer
+            /// Create a borrow of this struct from a raw pointer. Everything borrowed from the
+            /// returned reference, e.g. names and slices, lives for the lifetime `'a` that the
+            /// caller chooses.
+            ///
+            /// # Safety
+            ///
+            /// `raw` must point to a valid struct, and everything it points to must stay valid
+            /// and unmodified for `'a`. Choose `'a` explicitly rather than letting it be
+            /// inferred, e.g. as the lifetime of a borrow of the `Scene` that owns the struct.
             pub unsafe fn from_raw<'a>(raw: ::std::ptr::NonNull<$raw>) -> &'a $name {
                 ::std::mem::transmute(raw)
             }
@@ -148,8 +257,14 @@ macro_rules! define_type {
     // Non-reference type = POD
     ($(#[$type_attr:meta])* struct $name:ident($raw:ty)) => (
         $(#[$type_attr])*
+        #[repr(transparent)]
         pub struct $name(pub $raw);
 
+        // Slices of the raw type are viewed as slices of this type, so make sure the layouts
+        // really are the same.
+        const _: [(); ::std::mem::size_of::<$raw>()] = [(); ::std::mem::size_of::<$name>()];
+        const _: [(); ::std::mem::align_of::<$raw>()] = [(); ::std::mem::align_of::<$name>()];
+
         impl $name {
             /// Create this struct from the equivalent struct in assimp
             pub fn from_raw(raw: $raw) -> $name {
@@ -197,7 +312,34 @@ macro_rules! define_type_and_iterator_indirect {
         $(#[$iter_attr:meta])* struct $iter_name:ident
     ) => (
         define_type!($(#[$type_attr])* struct $type_name(&$raw));
-        define_iter!($(#[$iter_attr])* struct $iter_name -> ::std::ptr::NonNull<*const $raw>);
+        define_iter_indirect!(
+            $(#[$iter_attr])* struct $iter_name -> ::std::ptr::NonNull<*const $raw>
+        );
         impl_iterator_indirect!($iter_name, $type_name);
+        impl_identity!($type_name);
     );
 }
+
+// Equality and hashing by address, for the types that Assimp keeps in arrays of pointers, so
+// that each value is a distinct object of the scene rather than an element of an array.
+macro_rules! impl_identity {
+    ($name:ident) => {
+        /// Identity, not structural, equality: two borrows are equal if they borrow the same
+        /// struct of the same scene, even if they were obtained separately. Structs with the
+        /// same contents, e.g. two nodes with the same name and transform, are different.
+        impl PartialEq for $name {
+            fn eq(&self, other: &$name) -> bool {
+                ::std::ptr::eq(self, other)
+            }
+        }
+
+        impl Eq for $name {}
+
+        /// Hashes the address, consistently with `PartialEq`.
+        impl ::std::hash::Hash for $name {
+            fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+                (self as *const $name as usize).hash(state)
+            }
+        }
+    };
+}
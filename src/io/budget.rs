@@ -0,0 +1,236 @@
+use std::collections::HashSet;
+use std::error;
+use std::fmt;
+use std::io;
+use std::sync::{Arc, Mutex};
+
+use super::{record_io_error, File, FileError, FileIO, SeekFrom};
+use crate::Error;
+
+/// The limits enforced by `ByteBudgetFileIO`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ByteBudget {
+    /// The number of bytes that can be read across all files, including files that are
+    /// read more than once. Default: 256 MiB
+    pub max_total_bytes: u64,
+    /// The largest file that can be opened, by the size the file reports and by the bytes
+    /// read from it. Default: 64 MiB
+    pub max_file_size: u64,
+    /// The number of distinct paths that can be opened, e.g. an OBJ file, its MTL file and
+    /// any textures the importer reads. Default: 64
+    pub max_files: u32,
+}
+
+impl Default for ByteBudget {
+    fn default() -> Self {
+        ByteBudget {
+            max_total_bytes: 256 << 20,
+            max_file_size: 64 << 20,
+            max_files: 64,
+        }
+    }
+}
+
+/// A `FileIO` that passes everything on to another one, but fails once an import reads more
+/// than a `ByteBudget` allows. This bounds the memory an import of an untrusted file can use
+/// for its input, although not what the importer makes of it.
+///
+/// Reads past the budget fail, and so does opening a file that's too large or would be one
+/// file too many. If the import fails because of it, `Importer::read_file_with_io` returns
+/// `Error::BudgetExceeded`. Some importers carry on without files they can't open, e.g. the
+/// OBJ importer without its materials, so check `files_opened` to tell whether that happened.
+///
+/// The counters are shared by all imports that use the same `ByteBudgetFileIO`.
+pub struct ByteBudgetFileIO<T> {
+    inner: T,
+    budget: ByteBudget,
+    usage: Arc<Mutex<Usage>>,
+}
+
+#[derive(Default)]
+struct Usage {
+    bytes_read: u64,
+    paths: HashSet<String>,
+}
+
+impl<T: FileIO> ByteBudgetFileIO<T> {
+    pub fn new(inner: T, budget: ByteBudget) -> ByteBudgetFileIO<T> {
+        ByteBudgetFileIO {
+            inner,
+            budget,
+            usage: Arc::default(),
+        }
+    }
+
+    pub fn budget(&self) -> ByteBudget {
+        self.budget
+    }
+
+    /// The number of bytes read from all files so far.
+    pub fn bytes_read(&self) -> u64 {
+        self.usage.lock().unwrap().bytes_read
+    }
+
+    /// The number of distinct paths that were opened so far. Files that the inner `FileIO`
+    /// couldn't open, or that were refused because of the budget, don't count.
+    pub fn files_opened(&self) -> u32 {
+        self.usage.lock().unwrap().paths.len() as u32
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T: FileIO> FileIO for ByteBudgetFileIO<T> {
+    fn open(&self, file_path: &str, mode: &str) -> Option<Box<dyn File>> {
+        // `open` can't return an error, so keep it for the import like a failed read.
+        let refuse = |what, limit, attempted| {
+            record_io_error(
+                BudgetExceeded {
+                    what,
+                    limit,
+                    attempted,
+                }
+                .into(),
+            );
+            None
+        };
+
+        // Importers probe for files that may not exist, so only count the ones that do.
+        let mut file = self.inner.open(file_path, mode)?;
+
+        let mut usage = self.usage.lock().unwrap();
+        let count = usage.paths.len() as u64 + u64::from(!usage.paths.contains(file_path));
+        if count > u64::from(self.budget.max_files) {
+            file.close();
+            return refuse("files opened", u64::from(self.budget.max_files), count);
+        }
+        let size = file.size();
+        if size > self.budget.max_file_size {
+            file.close();
+            return refuse("file size", self.budget.max_file_size, size);
+        }
+
+        usage.paths.insert(file_path.to_owned());
+        Some(Box::new(BudgetFile {
+            inner: file,
+            budget: self.budget,
+            usage: self.usage.clone(),
+            bytes_read: 0,
+        }))
+    }
+}
+
+struct BudgetFile {
+    inner: Box<dyn File>,
+    budget: ByteBudget,
+    usage: Arc<Mutex<Usage>>,
+    /// The bytes read from this file.
+    bytes_read: u64,
+}
+
+impl File for BudgetFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+
+        let file_total = self.bytes_read + read as u64;
+        if file_total > self.budget.max_file_size {
+            let (what, limit) = ("file size", self.budget.max_file_size);
+            return Err(BudgetExceeded {
+                what,
+                limit,
+                attempted: file_total,
+            }
+            .into());
+        }
+        let mut usage = self.usage.lock().unwrap();
+        let total = usage.bytes_read + read as u64;
+        if total > self.budget.max_total_bytes {
+            let (what, limit) = ("bytes read", self.budget.max_total_bytes);
+            return Err(BudgetExceeded {
+                what,
+                limit,
+                attempted: total,
+            }
+            .into());
+        }
+
+        usage.bytes_read = total;
+        self.bytes_read = file_total;
+        Ok(read)
+    }
+
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.write(buf)
+    }
+
+    fn tell(&mut self) -> u64 {
+        self.inner.tell()
+    }
+
+    fn size(&mut self) -> u64 {
+        self.inner.size()
+    }
+
+    fn seek(&mut self, seek_from: SeekFrom) -> io::Result<()> {
+        self.inner.seek(seek_from)
+    }
+
+    fn flush(&mut self) {
+        self.inner.flush()
+    }
+
+    fn close(&mut self) {
+        self.inner.close()
+    }
+}
+
+/// The payload of the `io::Error`s returned when the budget runs out, which `into_error` turns
+/// into `Error::BudgetExceeded`.
+#[derive(Debug)]
+struct BudgetExceeded {
+    what: &'static str,
+    limit: u64,
+    attempted: u64,
+}
+
+impl BudgetExceeded {
+    fn to_error(&self) -> Error {
+        Error::BudgetExceeded {
+            what: self.what,
+            limit: self.limit,
+            attempted: self.attempted,
+        }
+    }
+}
+
+impl fmt::Display for BudgetExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.to_error(), f)
+    }
+}
+
+impl error::Error for BudgetExceeded {}
+
+impl From<BudgetExceeded> for io::Error {
+    fn from(err: BudgetExceeded) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, err)
+    }
+}
+
+/// The error to return for an import that failed because of `err`.
+pub(crate) fn into_error(err: FileError) -> Error {
+    let io_error = err.io_error();
+    match io_error
+        .get_ref()
+        .and_then(|err| err.downcast_ref::<BudgetExceeded>())
+    {
+        Some(exceeded) => exceeded.to_error(),
+        None => Error::Io(err.into()),
+    }
+}
@@ -0,0 +1,174 @@
+use std::cell::RefCell;
+use std::error;
+use std::fmt;
+use std::io;
+
+/// The operation on a file that failed, see `FileError`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum FileOperation {
+    Open,
+    Read,
+    Write,
+    Seek,
+}
+
+impl fmt::Display for FileOperation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            FileOperation::Open => "open",
+            FileOperation::Read => "read",
+            FileOperation::Write => "write",
+            FileOperation::Seek => "seek",
+        })
+    }
+}
+
+/// An error returned by a `File` or `FileIO` during an import, with the file it came from.
+///
+/// When an import fails because of it, `Importer::read_file_with_io` returns `Error::Io`,
+/// with this as the payload of the `io::Error`, so it's the `source` of the `Error`, and the
+/// error the `File` returned is the `source` of this.
+#[derive(Debug)]
+pub struct FileError {
+    path: String,
+    operation: FileOperation,
+    error: io::Error,
+}
+
+impl FileError {
+    /// The path that Assimp opened the file with.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn operation(&self) -> FileOperation {
+        self.operation
+    }
+
+    /// The error that the `File` or `FileIO` returned.
+    pub fn io_error(&self) -> &io::Error {
+        &self.error
+    }
+
+    pub fn into_io_error(self) -> io::Error {
+        self.error
+    }
+}
+
+impl fmt::Display for FileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "couldn't {} `{}`: {}",
+            self.operation, self.path, self.error
+        )
+    }
+}
+
+impl error::Error for FileError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// Keeps the kind of the original error, so matching on `io::Error::kind` works the same
+/// with or without the context.
+impl From<FileError> for io::Error {
+    fn from(err: FileError) -> Self {
+        io::Error::new(err.error.kind(), err)
+    }
+}
+
+/// The errors kept for one import.
+#[derive(Default)]
+struct Frame {
+    /// The first error of the import. Later errors are usually caused by the first, e.g. a
+    /// failed seek followed by reads of the wrong data.
+    first: Option<FileError>,
+    /// An error recorded by `record_io_error` from inside `FileIO::open`, which doesn't know
+    /// the path yet, see `attribute_open_error`.
+    unattributed: Option<io::Error>,
+}
+
+thread_local! {
+    /// A frame for each import running on this thread, innermost last. Imports nest when a
+    /// `FileIO` imports another file while opening one.
+    static FRAMES: RefCell<Vec<Frame>> = RefCell::new(Vec::new());
+}
+
+/// Keeps the errors of the files of one import from the time it's made until it's dropped.
+/// Errors only go to the innermost scope on the thread, so a nested import doesn't see the
+/// errors of the import around it and vice versa, and nothing is left over for the next one.
+pub(crate) struct ErrorScope {
+    depth: usize,
+}
+
+impl ErrorScope {
+    pub(crate) fn enter() -> ErrorScope {
+        FRAMES.with(|frames| {
+            let mut frames = frames.borrow_mut();
+            frames.push(Frame::default());
+            ErrorScope {
+                depth: frames.len() - 1,
+            }
+        })
+    }
+
+    /// Take the first error of the import so far, if any.
+    pub(crate) fn take_error(&self) -> Option<FileError> {
+        FRAMES.with(|frames| {
+            let mut frames = frames.borrow_mut();
+            let frame = frames.get_mut(self.depth)?;
+            frame.unattributed = None;
+            frame.first.take()
+        })
+    }
+}
+
+impl Drop for ErrorScope {
+    fn drop(&mut self) {
+        // Also pops the frames of nested imports whose scopes were leaked.
+        let _ = FRAMES.try_with(|frames| frames.borrow_mut().truncate(self.depth));
+    }
+}
+
+/// Keep `error` for the innermost import, unless it already has an error.
+pub(crate) fn record_file_error(path: &str, operation: FileOperation, error: io::Error) {
+    FRAMES.with(|frames| {
+        if let Some(frame) = frames.borrow_mut().last_mut() {
+            if frame.first.is_none() {
+                frame.first = Some(FileError {
+                    path: path.to_owned(),
+                    operation,
+                    error,
+                });
+            }
+        }
+    });
+}
+
+/// Keep `error` for the innermost import, to be given the path of the file that was being
+/// opened by `attribute_open_error`. For `FileIO`s that wrap another, whose `open` can't
+/// return an error.
+pub(crate) fn record_io_error(error: io::Error) {
+    FRAMES.with(|frames| {
+        if let Some(frame) = frames.borrow_mut().last_mut() {
+            if frame.unattributed.is_none() {
+                frame.unattributed = Some(error);
+            }
+        }
+    });
+}
+
+/// Record the error kept by `record_io_error` while `path` was being opened, if any.
+pub(crate) fn attribute_open_error(path: &str) {
+    let error = FRAMES.with(|frames| {
+        frames
+            .borrow_mut()
+            .last_mut()
+            .and_then(|frame| frame.unattributed.take())
+    });
+    if let Some(error) = error {
+        record_file_error(path, FileOperation::Open, error);
+    }
+}
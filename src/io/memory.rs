@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+use std::io;
+use std::sync::Arc;
+
+use super::{File, FileIO, SeekFrom};
+
+/// A `FileIO` that serves files from memory, e.g. for data that was downloaded or embedded in
+/// the program, or for tests.
+///
+/// Files are looked up by file name, ignoring the directories in front of it, since importers
+/// open the files a file refers to relative to its directory. Everything else, and every
+/// attempt to open a file for writing, fails, so an import through a `MemoryFileIO` can't
+/// read or write the file system.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryFileIO {
+    files: HashMap<String, Arc<[u8]>>,
+}
+
+impl MemoryFileIO {
+    /// A `MemoryFileIO` without any files.
+    pub fn new() -> MemoryFileIO {
+        MemoryFileIO::default()
+    }
+
+    /// Add the file `name` with the contents `data`, replacing any file with the same name.
+    pub fn insert<D: Into<Arc<[u8]>>>(&mut self, name: &str, data: D) {
+        self.files.insert(file_name(name).to_owned(), data.into());
+    }
+
+    /// Like `insert`, but taking and returning `self`, to build a `MemoryFileIO` in one
+    /// expression.
+    pub fn with_file<D: Into<Arc<[u8]>>>(mut self, name: &str, data: D) -> MemoryFileIO {
+        self.insert(name, data);
+        self
+    }
+
+    /// The contents of the file `name`, if there is one.
+    pub fn get(&self, name: &str) -> Option<&[u8]> {
+        self.files.get(file_name(name)).map(|data| &data[..])
+    }
+}
+
+impl FileIO for MemoryFileIO {
+    fn open(&self, file_path: &str, mode: &str) -> Option<Box<dyn File>> {
+        if mode.contains('w') || mode.contains('a') || mode.contains('+') {
+            return None;
+        }
+        let data = self.files.get(file_name(file_path))?;
+        Some(Box::new(MemoryFile::new(data.clone())))
+    }
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit(&['/', '\\'][..]).next().unwrap_or(path)
+}
+
+/// A read-only `File` with its contents in memory, as opened by `MemoryFileIO`. Writes fail
+/// with `io::ErrorKind::PermissionDenied`.
+#[derive(Debug, Clone)]
+pub struct MemoryFile {
+    data: Arc<[u8]>,
+    pos: usize,
+}
+
+impl MemoryFile {
+    /// A file with the contents `data`, positioned at its start.
+    pub fn new<D: Into<Arc<[u8]>>>(data: D) -> MemoryFile {
+        MemoryFile {
+            data: data.into(),
+            pos: 0,
+        }
+    }
+}
+
+impl File for MemoryFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = buf.len().min(self.data.len() - self.pos);
+        buf[..len].copy_from_slice(&self.data[self.pos..self.pos + len]);
+        self.pos += len;
+        Ok(len)
+    }
+
+    fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+        Err(io::ErrorKind::PermissionDenied.into())
+    }
+
+    fn tell(&mut self) -> u64 {
+        self.pos as u64
+    }
+
+    fn size(&mut self) -> u64 {
+        self.data.len() as u64
+    }
+
+    fn seek(&mut self, seek_from: SeekFrom) -> io::Result<()> {
+        let pos = match seek_from {
+            SeekFrom::Start(pos) => pos as i64,
+            SeekFrom::Current(offset) => self.pos as i64 + offset,
+            SeekFrom::End(offset) => self.data.len() as i64 + offset,
+        };
+        if pos < 0 || pos > self.data.len() as i64 {
+            return Err(io::ErrorKind::InvalidInput.into());
+        }
+        self.pos = pos as usize;
+        Ok(())
+    }
+
+    fn flush(&mut self) {}
+
+    fn close(&mut self) {}
+}
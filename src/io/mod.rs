@@ -2,31 +2,175 @@
 //!
 //! Implement the FileIO trait for your custom resource loading, with its open() method returning
 //! objects satisfying the File trait.
-use std::convert::TryInto;
+use std::any::Any;
+use std::cell::RefCell;
+use std::convert::TryFrom;
 use std::ffi::CStr;
+use std::io;
 pub use std::io::SeekFrom;
+use std::panic::{self, AssertUnwindSafe};
 
 use ffi::*;
 
+pub(crate) use self::budget::into_error;
+pub use self::budget::{ByteBudget, ByteBudgetFileIO};
+use self::context::{attribute_open_error, record_file_error, record_io_error, ErrorScope};
+pub use self::context::{FileError, FileOperation};
+pub use self::memory::{MemoryFile, MemoryFileIO};
+pub use self::observe::{IoEvent, ObservedFileIO};
+
+mod budget;
+mod context;
+mod memory;
+mod observe;
+
 /// Implement this trait along with the associated File type to use custom resource loading using
 /// the with_io() loading methods.
+///
+/// Every file that `open` returns during an import is closed before
+/// `Importer::read_file_with_io` returns, whether the import succeeds, fails or panics. If a
+/// `File` or the `FileIO` panics, the panic is caught before it reaches Assimp, which sees the
+/// operation fail, and is resumed once the import has returned and every file is closed.
 pub trait FileIO {
     fn open(&self, file_path: &str, mode: &str) -> Option<Box<dyn File>>;
+
+    /// Called when an import returns without Assimp having closed the file opened with
+    /// `file_path`, right before the file is closed. Assimp normally closes every file it
+    /// opens, so this is a way to find leaks in importers.
+    fn on_unclosed(&self, _file_path: &str) {}
 }
 
 /// Implement this for a given resource to support custom resource loading.
+///
+/// Assimp only learns that an operation failed, not why. The first error returned during an
+/// import is kept with the path of the file and the operation, as a `FileError`, and returned
+/// as `Error::Io` by `Importer::read_file_with_io` if the import fails because of it, or as
+/// `Error::BudgetExceeded` if it came from a `ByteBudgetFileIO`. Errors of an import that
+/// succeeds anyway are dropped.
 pub trait File {
     /// Should return the number of bytes read, or Err if read unsuccessful.
-    fn read(&mut self, buf: &mut [u8]) -> Result<usize, ()>;
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize>;
     /// Should return the number of bytes written, or Err if write unsuccessful.
-    fn write(&mut self, buf: &[u8]) -> Result<usize, ()>;
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize>;
     fn tell(&mut self) -> u64;
     fn size(&mut self) -> u64;
-    fn seek(&mut self, seek_from: SeekFrom) -> Result<(), ()>;
+    fn seek(&mut self, seek_from: SeekFrom) -> io::Result<()>;
     fn flush(&mut self);
     fn close(&mut self);
 }
 
+thread_local! {
+    /// The first panic of a `File` or `FileIO` on this thread since the last `take_panic`.
+    static PANIC: RefCell<Option<Box<dyn Any + Send>>> = RefCell::new(None);
+}
+
+/// Call `f`, returning `failed` instead if it panics, so that the panic doesn't unwind into
+/// Assimp. The first panic is kept for `take_panic`, like the first error of a file is kept by
+/// `ErrorScope`.
+fn catch_panic<R>(failed: R, f: impl FnOnce() -> R) -> R {
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(result) => result,
+        Err(payload) => {
+            PANIC.with(|panic| {
+                let mut panic = panic.borrow_mut();
+                if panic.is_none() {
+                    *panic = Some(payload);
+                }
+            });
+            failed
+        }
+    }
+}
+
+/// Take the panic kept by `catch_panic`, if any.
+fn take_panic() -> Option<Box<dyn Any + Send>> {
+    PANIC.with(|panic| panic.borrow_mut().take())
+}
+
+/// What the `UserData` of an `aiFileIO` made by this module points to.
+struct IoState<'a> {
+    file_io: &'a dyn FileIO,
+    /// The files that Assimp opened and hasn't closed yet, with their paths.
+    open: RefCell<Vec<(*mut aiFile, String)>>,
+}
+
+impl IoState<'_> {
+    /// Close the files that Assimp didn't, telling `FileIO::on_unclosed` about each.
+    unsafe fn close_leftovers(&self) {
+        let leftovers = std::mem::take(&mut *self.open.borrow_mut());
+        for (ai_file, file_path) in leftovers {
+            catch_panic((), || self.file_io.on_unclosed(&file_path));
+            close_file(ai_file);
+        }
+    }
+}
+
+/// What the `UserData` of an `aiFile` made by `io_open` points to.
+struct OpenFile {
+    file: Box<dyn File>,
+    /// The path the file was opened with, for the `FileError`s of its operations.
+    path: String,
+}
+
+/// Close a file made by `io_open`, and free it.
+unsafe fn close_file(ai_file: *mut aiFile) {
+    let ai_file = Box::from_raw(ai_file);
+    let mut open_file: Box<OpenFile> = Box::from_raw(ai_file.UserData as *mut OpenFile);
+    catch_panic((), || open_file.file.close());
+}
+
+/// An `aiFileIO` for an import, which closes the files that Assimp leaves open once it's
+/// dropped, see `finish`, and keeps the first error of its files, see `take_error`.
+pub(crate) struct WrappedFileIO<'a> {
+    raw: aiFileIO,
+    state: Box<IoState<'a>>,
+    errors: ErrorScope,
+}
+
+impl<'a> WrappedFileIO<'a> {
+    pub(crate) fn new<T: FileIO>(file_io: &'a T) -> WrappedFileIO<'a> {
+        // Don't resume a panic left over from an import that was abandoned, e.g. because it
+        // panicked itself.
+        take_panic();
+        let state = Box::new(IoState {
+            file_io,
+            open: RefCell::default(),
+        });
+        let raw = aiFileIO {
+            OpenProc: Some(FileWrapper::<T>::io_open),
+            CloseProc: Some(FileWrapper::<T>::io_close),
+            UserData: &*state as *const IoState as *mut i8,
+        };
+        WrappedFileIO {
+            raw,
+            state,
+            errors: ErrorScope::enter(),
+        }
+    }
+
+    pub(crate) fn as_mut_ptr(&mut self) -> *mut aiFileIO {
+        &mut self.raw
+    }
+
+    /// Take the first error returned by the `FileIO` or its files since this was made.
+    pub(crate) fn take_error(&self) -> Option<FileError> {
+        self.errors.take_error()
+    }
+
+    /// Close the files that Assimp left open, once the import has returned. Returns the first
+    /// panic of the `FileIO` or its files during the import, which the caller should resume.
+    pub(crate) fn finish(self) -> Option<Box<dyn Any + Send>> {
+        drop(self);
+        take_panic()
+    }
+}
+
+impl Drop for WrappedFileIO<'_> {
+    fn drop(&mut self) {
+        unsafe { self.state.close_leftovers() }
+    }
+}
+
 /// This type allows us to generate C stubs for whatever trait object the user supplies.
 struct FileWrapper<T: FileIO> {
     _phantom_t: std::marker::PhantomData<T>,
@@ -39,19 +183,27 @@ impl<T: FileIO> FileWrapper<T> {
         file_path: *const ::std::os::raw::c_char,
         mode: *const ::std::os::raw::c_char,
     ) -> *mut aiFile {
-        let file_io = Box::leak(Box::from_raw((*ai_file_io).UserData as *mut &mut dyn FileIO));
+        let state = &*((*ai_file_io).UserData as *const IoState);
 
-        let file_path = CStr::from_ptr(file_path).to_str().unwrap_or("Invalid UTF-8 Filename");
-        let mode = CStr::from_ptr(mode).to_str().unwrap_or("Invalid UTF-8 Mode");
-        let file = match file_io.open(file_path, mode) {
-            None => return std::ptr::null_mut(),
+        let file_path = CStr::from_ptr(file_path)
+            .to_str()
+            .unwrap_or("Invalid UTF-8 Filename");
+        let mode = CStr::from_ptr(mode)
+            .to_str()
+            .unwrap_or("Invalid UTF-8 Mode");
+        let file = match catch_panic(None, || state.file_io.open(file_path, mode)) {
+            None => {
+                attribute_open_error(file_path);
+                return std::ptr::null_mut();
+            }
             Some(file) => file,
         };
 
-        // Take the returned file, and double box it here so that it can be converted to a single
-        // raw pointer.
-        let double_box = Box::new(file);
-        let user_data = Box::into_raw(double_box) as *mut i8;
+        let open_file = Box::new(OpenFile {
+            file,
+            path: file_path.to_owned(),
+        });
+        let user_data = Box::into_raw(open_file) as *mut i8;
         let ai_file = aiFile {
             ReadProc: Some(Self::io_read),
             WriteProc: Some(Self::io_write),
@@ -61,18 +213,23 @@ impl<T: FileIO> FileWrapper<T> {
             FlushProc: Some(Self::io_flush),
             UserData: user_data,
         };
-        Box::into_raw(Box::new(ai_file))
+        let ai_file = Box::into_raw(Box::new(ai_file));
+        state
+            .open
+            .borrow_mut()
+            .push((ai_file, file_path.to_owned()));
+        ai_file
     }
 
     /// Implementation for aiFileIO::CloseProc.
-    unsafe extern "C" fn io_close(_ai_file_io: *mut aiFileIO, ai_file: *mut aiFile) {
-        // Given that this is close, we are careful to not leak, but instead drop the file when we
-        // exit this scope.
-        let mut file: Box<Box<dyn File>> = Box::from_raw((*ai_file).UserData as *mut Box<dyn File>);
-        file.close();
+    unsafe extern "C" fn io_close(ai_file_io: *mut aiFileIO, ai_file: *mut aiFile) {
+        let state = &*((*ai_file_io).UserData as *const IoState);
+        state.open.borrow_mut().retain(|&(open, _)| open != ai_file);
+        // Given that this is close, we are careful to not leak, but instead drop the file.
+        close_file(ai_file);
     }
-    unsafe fn get_file<'a>(ai_file: *mut aiFile) -> &'a mut Box<dyn File> {
-        Box::leak(Box::from_raw((*ai_file).UserData as *mut Box<dyn File>))
+    unsafe fn get_file<'a>(ai_file: *mut aiFile) -> &'a mut OpenFile {
+        &mut *((*ai_file).UserData as *mut OpenFile)
     }
     unsafe extern "C" fn io_read(
         ai_file: *mut aiFile,
@@ -80,30 +237,32 @@ impl<T: FileIO> FileWrapper<T> {
         size: size_t,
         count: size_t,
     ) -> size_t {
-        let file = Self::get_file(ai_file);
-        let mut buffer =
-            std::slice::from_raw_parts_mut(buffer as *mut u8, (size * count).try_into().unwrap());
-        if size == 0 {
-            panic!("Size 0 is invalid");
-        }
-        if count == 0 {
-            panic!("Count 0 is invalid");
-        }
-        if size > std::usize::MAX as u64 {
-            panic!("huge read size not supported");
-        }
+        catch_panic(0, || Self::read(ai_file, buffer, size, count))
+    }
+    unsafe fn read(
+        ai_file: *mut aiFile,
+        buffer: *mut std::os::raw::c_char,
+        size: size_t,
+        count: size_t,
+    ) -> size_t {
+        let OpenFile { file, path } = Self::get_file(ai_file);
+        // Panicking here would unwind into C, so treat sizes we can't handle as a failed read.
+        let len = match buffer_len(size, count) {
+            Some(len) => len,
+            None => return 0,
+        };
+        let mut buffer = std::slice::from_raw_parts_mut(buffer as *mut u8, len);
         let size = size as usize;
         if size == 1 {
             // This looks like a memcpy.
-            if count > std::usize::MAX as u64 {
-                panic!("huge read not supported");
-            }
-            let count = count as usize;
-
-            let (buffer, _) = buffer.split_at_mut(count);
             match file.read(buffer) {
                 Ok(size) => size as u64,
-                Err(_) => std::u64::MAX,
+                // Like `fread` and `fwrite`, report nothing done rather than an error value,
+                // which Assimp would take as a size.
+                Err(err) => {
+                    record_file_error(path, FileOperation::Read, err);
+                    0
+                }
             }
         } else {
             // We have to copy in strides. Implement this by looping for each object and tally the
@@ -113,7 +272,10 @@ impl<T: FileIO> FileWrapper<T> {
                 let split = buffer.split_at_mut(size as usize);
                 buffer = split.1;
                 let bytes_read = match file.read(split.0) {
-                    Err(_) => break,
+                    Err(err) => {
+                        record_file_error(path, FileOperation::Read, err);
+                        break;
+                    }
                     Ok(bytes_read) => bytes_read,
                 };
                 if bytes_read != size {
@@ -130,29 +292,28 @@ impl<T: FileIO> FileWrapper<T> {
         size: size_t,
         count: size_t,
     ) -> size_t {
-        let file = Self::get_file(ai_file);
-        let mut buffer =
-            std::slice::from_raw_parts(buffer as *mut u8, (size * count).try_into().unwrap());
-        if size == 0 {
-            panic!("Write of size 0");
-        }
-        if count == 0 {
-            panic!("Write of count 0");
-        }
-        if size > std::usize::MAX as u64 {
-            panic!("huge write size not supported");
-        }
+        catch_panic(0, || Self::write(ai_file, buffer, size, count))
+    }
+    unsafe fn write(
+        ai_file: *mut aiFile,
+        buffer: *const std::os::raw::c_char,
+        size: size_t,
+        count: size_t,
+    ) -> size_t {
+        let OpenFile { file, path } = Self::get_file(ai_file);
+        let len = match buffer_len(size, count) {
+            Some(len) => len,
+            None => return 0,
+        };
+        let mut buffer = std::slice::from_raw_parts(buffer as *mut u8, len);
         let size = size as usize;
         if size == 1 {
-            if count > std::usize::MAX as u64 {
-                panic!("huge write not supported");
-            }
-            let count = count as usize;
-
-            let (buffer, _) = buffer.split_at(count);
             match file.write(buffer) {
                 Ok(size) => size as u64,
-                Err(_) => std::u64::MAX,
+                Err(err) => {
+                    record_file_error(path, FileOperation::Write, err);
+                    0
+                }
             }
         } else {
             // Write in strides. Implement this by looping for each object and tally the
@@ -162,7 +323,10 @@ impl<T: FileIO> FileWrapper<T> {
                 let split = buffer.split_at(size as usize);
                 buffer = split.1;
                 let bytes_written = match file.write(split.0) {
-                    Err(_) => break,
+                    Err(err) => {
+                        record_file_error(path, FileOperation::Write, err);
+                        break;
+                    }
                     Ok(bytes_written) => bytes_written,
                 };
                 if bytes_written != size {
@@ -174,29 +338,45 @@ impl<T: FileIO> FileWrapper<T> {
         }
     }
     unsafe extern "C" fn io_tell(ai_file: *mut aiFile) -> size_t {
-        let file = Self::get_file(ai_file);
-        file.tell()
+        let file = &mut Self::get_file(ai_file).file;
+        catch_panic(0, || file.tell())
     }
     unsafe extern "C" fn io_size(ai_file: *mut aiFile) -> size_t {
-        let file = Self::get_file(ai_file);
-        file.size()
+        let file = &mut Self::get_file(ai_file).file;
+        catch_panic(0, || file.size())
     }
     unsafe extern "C" fn io_seek(ai_file: *mut aiFile, pos: size_t, origin: aiOrigin) -> aiReturn {
-        let file = Self::get_file(ai_file);
+        let OpenFile { file, path } = Self::get_file(ai_file);
         let seek_from = match origin {
             autogenerated_assimp_sys::aiOrigin_aiOrigin_SET => SeekFrom::Start(pos),
             autogenerated_assimp_sys::aiOrigin_aiOrigin_CUR => SeekFrom::Current(pos as i64),
             autogenerated_assimp_sys::aiOrigin_aiOrigin_END => SeekFrom::End(pos as i64),
-            _ => panic!("Assimp passed invalid origin"),
+            _ => return autogenerated_assimp_sys::aiReturn_aiReturn_FAILURE,
         };
-        match file.seek(seek_from) {
-            Ok(()) => 0,
-            Err(()) => autogenerated_assimp_sys::aiReturn_aiReturn_FAILURE,
+        match catch_panic(None, || Some(file.seek(seek_from))) {
+            Some(Ok(())) => 0,
+            Some(Err(err)) => {
+                record_file_error(path, FileOperation::Seek, err);
+                autogenerated_assimp_sys::aiReturn_aiReturn_FAILURE
+            }
+            None => autogenerated_assimp_sys::aiReturn_aiReturn_FAILURE,
         }
     }
     unsafe extern "C" fn io_flush(ai_file: *mut aiFile) {
-        let file = Self::get_file(ai_file);
-        file.flush();
+        let file = &mut Self::get_file(ai_file).file;
+        catch_panic((), || file.flush());
+    }
+}
+
+/// The total size in bytes of a read or write of `count` objects of `size` bytes each, or `None`
+/// if it's empty or too big to address.
+fn buffer_len(size: size_t, count: size_t) -> Option<usize> {
+    let len = usize::try_from(size.checked_mul(count)?).ok()?;
+
+    if len == 0 {
+        None
+    } else {
+        Some(len)
     }
 }
 
@@ -207,11 +387,15 @@ pub fn wrap_file_io<T>(file_io: &T) -> aiFileIO
 where
     T: FileIO,
 {
-    let trait_obj: &dyn FileIO = file_io;
-    let user_data = Box::into_raw(Box::new(trait_obj)) as *mut i8;
+    // Unlike with `WrappedFileIO`, nobody knows when the import is over, so the state is leaked
+    // and files that Assimp leaves open stay open.
+    let state = Box::new(IoState {
+        file_io,
+        open: RefCell::default(),
+    });
     aiFileIO {
         OpenProc: Some(FileWrapper::<T>::io_open),
         CloseProc: Some(FileWrapper::<T>::io_close),
-        UserData: user_data,
+        UserData: Box::into_raw(state) as *mut i8,
     }
 }
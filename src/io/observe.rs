@@ -0,0 +1,128 @@
+use std::io;
+use std::sync::Arc;
+
+use super::{File, FileIO, SeekFrom};
+
+/// Something that happened to a file opened through an `ObservedFileIO`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum IoEvent<'a> {
+    /// The inner `FileIO` opened the file. Files that it couldn't open aren't reported, as
+    /// importers often probe for files that don't exist.
+    FileOpened { path: &'a str, mode: &'a str },
+    /// A read of the file returned `bytes` bytes, starting `offset` bytes into it. Failed
+    /// reads aren't reported.
+    FileRead {
+        path: &'a str,
+        bytes: usize,
+        offset: u64,
+    },
+    /// The file was closed, by Assimp or by the crate once the import returned.
+    FileClosed { path: &'a str },
+}
+
+/// A `FileIO` that passes everything on to another one, and tells an observer about every file
+/// that's opened, read and closed, e.g. to show which file an import is reading.
+///
+/// The observer is called from the thread running the import, from inside Assimp's IO
+/// callbacks, so it should be quick. The path of each file is kept from when it's opened, so
+/// reads don't allocate. If the observer panics, the panic is resumed once the import returns,
+/// like panics of a `File`.
+pub struct ObservedFileIO<T, F> {
+    inner: T,
+    observer: Arc<F>,
+}
+
+impl<T, F> ObservedFileIO<T, F>
+where
+    T: FileIO,
+    F: Fn(IoEvent) + Send + Sync + 'static,
+{
+    pub fn new(inner: T, observer: F) -> ObservedFileIO<T, F> {
+        ObservedFileIO {
+            inner,
+            observer: Arc::new(observer),
+        }
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T, F> FileIO for ObservedFileIO<T, F>
+where
+    T: FileIO,
+    F: Fn(IoEvent) + Send + Sync + 'static,
+{
+    fn open(&self, file_path: &str, mode: &str) -> Option<Box<dyn File>> {
+        let file = self.inner.open(file_path, mode)?;
+        (self.observer)(IoEvent::FileOpened {
+            path: file_path,
+            mode,
+        });
+        Some(Box::new(ObservedFile {
+            inner: file,
+            path: file_path.into(),
+            offset: 0,
+            observer: self.observer.clone(),
+        }))
+    }
+
+    fn on_unclosed(&self, file_path: &str) {
+        self.inner.on_unclosed(file_path)
+    }
+}
+
+struct ObservedFile<F> {
+    inner: Box<dyn File>,
+    path: Box<str>,
+    /// Where the next read starts.
+    offset: u64,
+    observer: Arc<F>,
+}
+
+impl<F: Fn(IoEvent)> File for ObservedFile<F> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let bytes = self.inner.read(buf)?;
+        (self.observer)(IoEvent::FileRead {
+            path: &self.path,
+            bytes,
+            offset: self.offset,
+        });
+        self.offset += bytes as u64;
+        Ok(bytes)
+    }
+
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.offset += written as u64;
+        Ok(written)
+    }
+
+    fn tell(&mut self) -> u64 {
+        self.inner.tell()
+    }
+
+    fn size(&mut self) -> u64 {
+        self.inner.size()
+    }
+
+    fn seek(&mut self, seek_from: SeekFrom) -> io::Result<()> {
+        self.inner.seek(seek_from)?;
+        self.offset = self.inner.tell();
+        Ok(())
+    }
+
+    fn flush(&mut self) {
+        self.inner.flush()
+    }
+
+    fn close(&mut self) {
+        self.inner.close();
+        (self.observer)(IoEvent::FileClosed { path: &self.path });
+    }
+}
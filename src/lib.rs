@@ -1,19 +1,27 @@
 //! # assimp - Open Asset Import Library
 //!
 //! Bindings for the [Assimp](http://assimp.org/) library.
+//!
+//! The types that most programs need are in `prelude`, to import with
+//! `use open_asset_importer::prelude::*;`.
 
 // #![warn(missing_docs)]
 
 extern crate autogenerated_assimp_sys as ffi;
 
-pub use import::{structs::PrimitiveType, Importer};
+pub use error::Error;
+pub use import::{
+    structs::{PrimitiveType, PrimitiveTypes},
+    Importer,
+};
 pub use io::{File, FileIO};
 pub use log::LogStream;
 pub use math::{Color3D, Color4D, Matrix3x3, Matrix4x4, Quaternion, Vector2D, Vector3D};
 pub use scene::{
-    Animation, BlendOp, Camera, Face, Light, Mapping, Material, MaterialBlendOp, MaterialComponent,
-    MaterialKey, MaterialValue, Mesh, Metadata, MetadataEntry, MetadataValue, Node, NodeAnim,
-    QuatKey, Scene, Texture, TextureData, VectorKey,
+    Animation, BlendOp, Camera, CameraProjection, Face, Light, LightType, Mapping, Material,
+    MaterialBlendOp, MaterialComponent, MaterialComponentType, MaterialKey, MaterialValue, Mesh,
+    Metadata, MetadataEntry, MetadataValue, Node, NodeAnim, QuatKey, Scene, Texture, TextureData,
+    TextureDefinition, VectorKey,
 };
 
 use std::{cmp, fmt, ops};
@@ -21,11 +29,16 @@ use std::{cmp, fmt, ops};
 #[macro_use]
 mod internal_macros;
 
+pub mod convert;
+mod error;
 pub mod export;
+#[cfg(feature = "test-fixtures")]
+pub mod fixtures;
 pub mod import;
 pub mod io;
 pub mod log;
 pub mod math;
+pub mod prelude;
 pub mod scene;
 
 /// An "inline string", used in Assimp instead of heap-allocated cstrings. These are big - over 1000 bytes
@@ -45,15 +58,24 @@ impl cmp::PartialEq for InlineString {
     }
 }
 
+/// Invalid UTF-8 is replaced with `U+FFFD`, rather than panicking like dereferencing does.
 impl fmt::Display for InlineString {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", &**self)
+        write!(f, "{}", aistring_to_cstr(&self.0).to_string_lossy())
     }
 }
 
 impl fmt::Debug for InlineString {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:?}", &**self)
+        write!(f, "{:?}", aistring_to_cstr(&self.0).to_string_lossy())
+    }
+}
+
+impl InlineString {
+    /// The contents of the string, or an error if it isn't valid UTF-8. Dereferencing an
+    /// `InlineString` panics in that case, so prefer this for strings read from untrusted files.
+    pub fn to_str(&self) -> Result<&str, std::str::Utf8Error> {
+        aistring_to_str(&self.0)
     }
 }
 
@@ -61,7 +83,7 @@ impl ops::Deref for InlineString {
     type Target = str;
 
     fn deref(&self) -> &Self::Target {
-        unsafe { aistring_to_cstr(&self.0).to_str().unwrap() }
+        self.to_str().expect("string is not valid UTF-8")
     }
 }
 
@@ -108,10 +130,22 @@ fn aireturn_to_result(aireturn: ffi::aiReturn) -> Result<(), AiError> {
     }
 }
 
-unsafe fn aistring_to_cstr(aistring: &ffi::aiString) -> &std::ffi::CStr {
-    std::ffi::CStr::from_bytes_with_nul_unchecked(std::mem::transmute(
-        &aistring.data[..aistring.length as usize + 1],
-    ))
+/// Get the contents of an `aiString`. The length comes straight from the input file, so this
+/// clamps it to the size of the buffer and cuts the string off at the first nul, returning an
+/// empty string if there isn't one.
+fn aistring_to_cstr(aistring: &ffi::aiString) -> &std::ffi::CStr {
+    let data = unsafe { &*(&aistring.data[..] as *const [_] as *const [u8]) };
+    let len = (aistring.length as usize).min(data.len() - 1);
+
+    match data[..=len].iter().position(|&byte| byte == 0) {
+        Some(end) => unsafe { std::ffi::CStr::from_bytes_with_nul_unchecked(&data[..=end]) },
+        None => Default::default(),
+    }
+}
+
+/// Like `aistring_to_cstr`, but fails if the string isn't valid UTF-8.
+fn aistring_to_str(aistring: &ffi::aiString) -> Result<&str, std::str::Utf8Error> {
+    aistring_to_cstr(aistring).to_str()
 }
 
 fn str_to_aistring(val: &str) -> ffi::aiString {
@@ -127,3 +161,62 @@ fn str_to_aistring(val: &str) -> ffi::aiString {
 
     ffi::aiString { length: bytes.len() as _, data }
 }
+
+/// The most items of a list that the `Debug` impls of the wrapper types print.
+const DEBUG_LIST_LIMIT: usize = 8;
+
+/// Formats the items of an iterator as a list for a `Debug` impl, printing at most
+/// `DEBUG_LIST_LIMIT` of them followed by `...` if there are more.
+struct BoundedList<I>(I);
+
+impl<I> fmt::Debug for BoundedList<I>
+where
+    I: Iterator + Clone,
+    I::Item: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        struct Ellipsis;
+
+        impl fmt::Debug for Ellipsis {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("...")
+            }
+        }
+
+        let mut items = self.0.clone();
+        let mut list = f.debug_list();
+        list.entries(items.by_ref().take(DEBUG_LIST_LIMIT));
+        if items.next().is_some() {
+            list.entry(&Ellipsis);
+        }
+        list.finish()
+    }
+}
+
+/// Get a pointer to the `index`th element of the array starting at `base`, or `None` if `base`
+/// is null or the byte offset would overflow an `isize`. Counts come straight from the input
+/// file so we can't trust them not to be absurdly large.
+unsafe fn array_element<T>(base: *mut T, index: usize) -> Option<*mut T> {
+    let base = std::ptr::NonNull::new(base)?;
+    let bytes = index.checked_mul(std::mem::size_of::<T>())?;
+
+    if bytes > isize::MAX as usize {
+        None
+    } else {
+        Some(base.as_ptr().add(index))
+    }
+}
+
+/// Build a slice from an Assimp array and count, returning an empty slice if the pointer is
+/// null or the total size of the array would overflow an `isize`.
+unsafe fn array_slice<'a, T>(base: *const T, len: usize) -> &'a [T] {
+    let fits = len
+        .checked_mul(std::mem::size_of::<T>())
+        .map_or(false, |bytes| bytes <= isize::MAX as usize);
+
+    if base.is_null() || !fits {
+        &[]
+    } else {
+        std::slice::from_raw_parts(base, len)
+    }
+}
@@ -0,0 +1,273 @@
+use std::collections::VecDeque;
+use std::ffi::CStr;
+use std::os::raw::c_char;
+use std::sync::{Mutex, Once};
+use std::thread::{self, ThreadId};
+use std::time::SystemTime;
+
+use super::LogStream;
+
+/// Serializes `with_captured_logs`.
+static CAPTURE_LOCK: Mutex<()> = Mutex::new(());
+
+/// The severity of a log message, from the prefix that Assimp's logger writes before it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LogLevel {
+    Verbose,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+/// A message captured by a `LogCapture`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogRecord {
+    /// When the message was received.
+    pub time: SystemTime,
+    /// The severity, or `None` if the message doesn't start with one of the prefixes that
+    /// Assimp uses.
+    pub level: Option<LogLevel>,
+    /// The message, without the level and thread prefix and without the trailing newline.
+    pub message: String,
+}
+
+impl LogRecord {
+    fn parse(raw: &str) -> LogRecord {
+        let raw = raw.trim_end_matches(&['\n', '\r'][..]);
+        let levels = [
+            ("Verbose", LogLevel::Verbose),
+            ("Debug", LogLevel::Debug),
+            ("Info", LogLevel::Info),
+            ("Warn", LogLevel::Warn),
+            ("Error", LogLevel::Error),
+        ];
+        let mut level = None;
+        let mut message = raw;
+        // The default logger writes e.g. "Warn,  T0: message".
+        if let Some(comma) = raw.find(',') {
+            let prefix = &raw[..comma];
+            if let Some(&(_, found)) = levels.iter().find(|(name, _)| *name == prefix) {
+                level = Some(found);
+                message = raw[comma + 1..].trim_start();
+                if message.starts_with('T') {
+                    if let Some(colon) = message.find(": ") {
+                        message = &message[colon + 2..];
+                    }
+                }
+            }
+        }
+        LogRecord {
+            time: SystemTime::now(),
+            level,
+            message: message.to_owned(),
+        }
+    }
+
+    /// Whether the record is at least as severe as `min_level` and its message contains
+    /// `substring`. Records without a level only match a `min_level` of `Verbose`.
+    pub fn matches(&self, min_level: LogLevel, substring: &str) -> bool {
+        let level = self.level.unwrap_or(LogLevel::Verbose);
+        level >= min_level && self.message.contains(substring)
+    }
+}
+
+struct Buffer {
+    records: VecDeque<LogRecord>,
+    capacity: usize,
+    dropped: u64,
+}
+
+impl Buffer {
+    fn new(capacity: usize) -> Buffer {
+        Buffer {
+            records: VecDeque::new(),
+            capacity,
+            dropped: 0,
+        }
+    }
+
+    fn push(&mut self, record: LogRecord) {
+        if self.capacity == 0 {
+            self.dropped += 1;
+            return;
+        }
+        if self.records.len() == self.capacity {
+            self.records.pop_front();
+            self.dropped += 1;
+        }
+        self.records.push_back(record);
+    }
+}
+
+unsafe extern "C" fn capture_callback(message: *const c_char, user: *mut c_char) {
+    let buffer = &*(user as *const Mutex<Buffer>);
+    let record = LogRecord::parse(&CStr::from_ptr(message).to_string_lossy());
+
+    buffer
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .push(record);
+}
+
+/// A log stream that keeps the messages in a bounded buffer, dropping the oldest ones when it's
+/// full.
+///
+/// Assimp's log streams are global: while a capture is attached it receives the messages of
+/// every import in the process, on every thread, not just of the imports started by its owner.
+/// `with_captured_logs` serializes captures so that at least they don't overlap each other.
+///
+/// The stream is attached by `new` and detached when the capture is dropped.
+pub struct LogCapture {
+    // Declared first so it's detached before the buffer is freed.
+    stream: LogStream,
+    buffer: Box<Mutex<Buffer>>,
+}
+
+impl LogCapture {
+    /// Attach a capture that keeps up to `capacity` records.
+    pub fn new(capacity: usize) -> LogCapture {
+        let buffer = Box::new(Mutex::new(Buffer::new(capacity)));
+        let user = &*buffer as *const Mutex<Buffer> as *mut c_char;
+        let mut stream = LogStream::callback_with_user(capture_callback, user);
+        stream.attach();
+        LogCapture { stream, buffer }
+    }
+
+    /// Whether the capture is still receiving messages.
+    pub fn attached(&self) -> bool {
+        self.stream.attached()
+    }
+
+    /// Stop receiving messages. The records captured so far can still be drained.
+    pub fn detach(&mut self) {
+        self.stream.detach()
+    }
+
+    /// Remove and return all captured records, oldest first.
+    pub fn drain(&self) -> Vec<LogRecord> {
+        self.lock().records.drain(..).collect()
+    }
+
+    /// Remove and return the records that match `min_level` and `substring`, see
+    /// `LogRecord::matches`. The other records are kept.
+    pub fn drain_matching(&self, min_level: LogLevel, substring: &str) -> Vec<LogRecord> {
+        let mut buffer = self.lock();
+        let (matching, rest): (VecDeque<_>, VecDeque<_>) = buffer
+            .records
+            .drain(..)
+            .partition(|record| record.matches(min_level, substring));
+        buffer.records = rest;
+        matching.into()
+    }
+
+    /// The number of records that were dropped because the buffer was full.
+    pub fn dropped(&self) -> u64 {
+        self.lock().dropped
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Buffer> {
+        self.buffer
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Run `f` with a `LogCapture` attached, and return its result together with the records
+/// logged while it ran. The capture is detached before this returns, even if `f` panics.
+///
+/// Calls from different threads wait for each other, so each call sees a consistent view of
+/// the log. Because Assimp's log streams are global, the records still include messages from
+/// imports on other threads that don't use `with_captured_logs`. Calling this from inside `f`
+/// deadlocks.
+pub fn with_captured_logs<T, F: FnOnce() -> T>(f: F) -> (T, Vec<LogRecord>) {
+    let _lock = CAPTURE_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let mut capture = LogCapture::new(usize::MAX);
+    let result = f();
+    capture.detach();
+    (result, capture.drain())
+}
+
+/// The buffers of the `with_thread_logs` calls in progress, innermost last on each thread.
+static THREAD_BUFFERS: Mutex<Vec<(ThreadId, Buffer)>> = Mutex::new(Vec::new());
+
+/// Attaches the stream of `with_thread_logs`.
+static THREAD_STREAM: Once = Once::new();
+
+/// Keeps the message for the innermost `with_thread_logs` call on the thread that logs it, if
+/// there is one. Assimp writes to the streams on the thread that logs.
+unsafe extern "C" fn thread_callback(message: *const c_char, _user: *mut c_char) {
+    let current = thread::current().id();
+    let mut buffers = THREAD_BUFFERS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some((_, buffer)) = buffers
+        .iter_mut()
+        .rev()
+        .find(|(thread, _)| *thread == current)
+    {
+        buffer.push(LogRecord::parse(&CStr::from_ptr(message).to_string_lossy()));
+    }
+}
+
+/// Removes the innermost buffer of this thread from `THREAD_BUFFERS` when dropped, even if the
+/// thread is panicking.
+struct ThreadBuffer {
+    thread: ThreadId,
+}
+
+impl ThreadBuffer {
+    fn take(self) -> Buffer {
+        let mut buffers = THREAD_BUFFERS
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let index = buffers
+            .iter()
+            .rposition(|(thread, _)| *thread == self.thread)
+            .expect("the buffer of with_thread_logs was removed");
+        let (_, buffer) = buffers.remove(index);
+        drop(buffers);
+        std::mem::forget(self);
+        buffer
+    }
+}
+
+impl Drop for ThreadBuffer {
+    fn drop(&mut self) {
+        let mut buffers = THREAD_BUFFERS
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(index) = buffers
+            .iter()
+            .rposition(|(thread, _)| *thread == self.thread)
+        {
+            buffers.remove(index);
+        }
+    }
+}
+
+/// Run `f` and return its result together with up to `capacity` of the messages logged on this
+/// thread meanwhile, dropping the oldest ones beyond that. The messages of other threads are
+/// left out, and so are those of nested calls on this thread, which get their own.
+///
+/// The stream this captures with is attached on the first call and stays attached for the rest
+/// of the process, so calls on different threads don't wait for each other, and never detach
+/// the last stream, which would destroy Assimp's logger while other threads log to it.
+pub(crate) fn with_thread_logs<T, F: FnOnce() -> T>(capacity: usize, f: F) -> (T, Vec<LogRecord>) {
+    THREAD_STREAM.call_once(|| {
+        let mut stream = LogStream::callback(thread_callback);
+        stream.attach();
+        std::mem::forget(stream);
+    });
+
+    let thread = thread::current().id();
+    THREAD_BUFFERS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .push((thread, Buffer::new(capacity)));
+    let buffer = ThreadBuffer { thread };
+    let result = f();
+    (result, buffer.take().records.into())
+}
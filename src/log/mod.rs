@@ -4,6 +4,11 @@ use std::ptr;
 use ffi::*;
 use std::os::raw::{c_char, c_void};
 
+pub(crate) use self::capture::with_thread_logs;
+pub use self::capture::{with_captured_logs, LogCapture, LogLevel, LogRecord};
+
+mod capture;
+
 pub struct LogStream {
     raw: aiLogStream,
     attached: bool,
@@ -66,6 +71,19 @@ impl LogStream {
         }
     }
 
+    fn callback_with_user(
+        cb: unsafe extern "C" fn(*const c_char, *mut c_char),
+        user: *mut c_char,
+    ) -> LogStream {
+        LogStream {
+            raw: aiLogStream {
+                callback: Some(cb),
+                user,
+            },
+            attached: false,
+        }
+    }
+
     pub fn attached(&self) -> bool {
         self.attached
     }
@@ -0,0 +1,91 @@
+//! Wrappers for the math helpers exported by the Assimp C API.
+//!
+//! These give exactly the results Assimp computes internally, which matters when comparing
+//! against transforms the library produced. Most have pure-Rust equivalents, such as the `Mul`
+//! implementation of `Matrix4x4`, which should be preferred otherwise.
+//!
+//! The methods that mirror a C function that modifies its argument in place, such as
+//! `aiMultiplyMatrix4`, also modify `self` in place. The rest return a new value.
+
+use ffi::*;
+
+use super::matrix3::Matrix3x3;
+use super::matrix4::Matrix4x4;
+use super::quaternion::Quaternion;
+use super::vector3::Vector3D;
+
+impl Matrix4x4 {
+    /// The identity matrix, from `aiIdentityMatrix4`.
+    pub fn identity_assimp() -> Matrix4x4 {
+        let mut mat = Matrix4x4::identity();
+        unsafe { aiIdentityMatrix4(&mut mat.0) };
+        mat
+    }
+
+    /// Multiply this matrix by `other` in place with `aiMultiplyMatrix4`, so that it
+    /// becomes `self * other`.
+    pub fn multiply_assimp(&mut self, other: &Matrix4x4) {
+        unsafe { aiMultiplyMatrix4(&mut self.0, &other.0) }
+    }
+
+    /// Transpose this matrix in place with `aiTransposeMatrix4`.
+    pub fn transpose_assimp(&mut self) {
+        unsafe { aiTransposeMatrix4(&mut self.0) }
+    }
+
+    /// Decompose this matrix with `aiDecomposeMatrix`, returning `(scaling, rotation,
+    /// position)`. The matrix must be a combination of a scale, a rotation and a translation,
+    /// applied in that order, for the result to be meaningful.
+    pub fn decompose_assimp(&self) -> (Vector3D, Quaternion, Vector3D) {
+        let mut scaling = Vector3D::new(0.0, 0.0, 0.0);
+        let mut rotation = Quaternion::new(1.0, 0.0, 0.0, 0.0);
+        let mut position = Vector3D::new(0.0, 0.0, 0.0);
+        unsafe {
+            aiDecomposeMatrix(&self.0, &mut scaling.0, &mut rotation.0, &mut position.0);
+        }
+        (scaling, rotation, position)
+    }
+}
+
+impl Matrix3x3 {
+    /// The identity matrix, from `aiIdentityMatrix3`.
+    pub fn identity_assimp() -> Matrix3x3 {
+        let mut mat = Matrix3x3::new(0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0);
+        unsafe { aiIdentityMatrix3(&mut mat.0) };
+        mat
+    }
+
+    /// Multiply this matrix by `other` in place with `aiMultiplyMatrix3`, so that it
+    /// becomes `self * other`.
+    pub fn multiply_assimp(&mut self, other: &Matrix3x3) {
+        unsafe { aiMultiplyMatrix3(&mut self.0, &other.0) }
+    }
+
+    /// Transpose this matrix in place with `aiTransposeMatrix3`.
+    pub fn transpose_assimp(&mut self) {
+        unsafe { aiTransposeMatrix3(&mut self.0) }
+    }
+}
+
+impl Quaternion {
+    /// The rotation described by `mat`, which must be a rotation matrix, from
+    /// `aiCreateQuaternionFromMatrix`.
+    pub fn from_matrix_assimp(mat: &Matrix3x3) -> Quaternion {
+        let mut quat = Quaternion::new(1.0, 0.0, 0.0, 0.0);
+        unsafe { aiCreateQuaternionFromMatrix(&mut quat.0, &mat.0) };
+        quat
+    }
+}
+
+impl Vector3D {
+    /// Transform this vector in place by `mat` with `aiTransformVecByMatrix4`, as a point
+    /// including the translation part. The same as `Matrix4x4::transform_point`.
+    pub fn transform_assimp(&mut self, mat: &Matrix4x4) {
+        unsafe { aiTransformVecByMatrix4(&mut self.0, &mat.0) }
+    }
+
+    /// Transform this vector in place by `mat` with `aiTransformVecByMatrix3`.
+    pub fn transform_by_matrix3_assimp(&mut self, mat: &Matrix3x3) {
+        unsafe { aiTransformVecByMatrix3(&mut self.0, &mat.0) }
+    }
+}
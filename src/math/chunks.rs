@@ -0,0 +1,40 @@
+use std::convert::TryFrom;
+use std::slice::ChunksExact;
+
+/// Iterator over blocks of 8 elements, for loops that the compiler can vectorize. See
+/// `Vector3DIter::chunks8`.
+///
+/// Like `slice::ChunksExact`, the last elements are left out if there are fewer than 8, and
+/// are available from `remainder`.
+pub struct Chunks8<'a, T> {
+    chunks: ChunksExact<'a, T>,
+}
+
+impl<'a, T> Chunks8<'a, T> {
+    pub(crate) fn new(slice: &'a [T]) -> Chunks8<'a, T> {
+        Chunks8 {
+            chunks: slice.chunks_exact(8),
+        }
+    }
+
+    /// The elements after the last full block.
+    pub fn remainder(&self) -> &'a [T] {
+        self.chunks.remainder()
+    }
+}
+
+impl<'a, T> Iterator for Chunks8<'a, T> {
+    type Item = &'a [T; 8];
+
+    fn next(&mut self) -> Option<&'a [T; 8]> {
+        self.chunks
+            .next()
+            .map(|chunk| <&[T; 8]>::try_from(chunk).unwrap())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for Chunks8<'_, T> {}
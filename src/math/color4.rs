@@ -20,6 +20,83 @@ impl Color4D {
             a: a,
         })
     }
+
+    /// Quantize each component to 8 bits without any color space conversion. Components are
+    /// clamped to 0..1 and rounded to the nearest value, and NaN becomes 0.
+    pub fn to_rgba8(self) -> [u8; 4] {
+        [
+            quantize(self.r),
+            quantize(self.g),
+            quantize(self.b),
+            quantize(self.a),
+        ]
+    }
+
+    /// Encode this linear color as 8-bit sRGB, e.g. for an `R8G8B8A8_SRGB` vertex buffer. The
+    /// alpha component is quantized without conversion, see `to_rgba8`.
+    pub fn to_srgb8(self) -> [u8; 4] {
+        self.to_srgb().to_rgba8()
+    }
+
+    /// Convert this color from sRGB to linear, applying the standard sRGB transfer function to
+    /// the red, green and blue components. Alpha is left untouched. Values outside 0..1 are
+    /// converted without clamping, and NaN stays NaN.
+    pub fn to_linear(self) -> Color4D {
+        Color4D::new(
+            srgb_to_linear(self.r),
+            srgb_to_linear(self.g),
+            srgb_to_linear(self.b),
+            self.a,
+        )
+    }
+
+    /// The inverse of `to_linear`, converting this color from linear to sRGB.
+    pub fn to_srgb(self) -> Color4D {
+        Color4D::new(
+            linear_to_srgb(self.r),
+            linear_to_srgb(self.g),
+            linear_to_srgb(self.b),
+            self.a,
+        )
+    }
+
+    /// The relative luminance of this linear color, using the Rec. 709 coefficients. Alpha is
+    /// ignored.
+    pub fn luminance(self) -> f32 {
+        0.2126 * self.r + 0.7152 * self.g + 0.0722 * self.b
+    }
+
+    /// Linearly interpolate between this color and `other`, componentwise. `t` is not clamped,
+    /// so values outside 0..1 extrapolate.
+    pub fn lerp(self, other: Color4D, t: f32) -> Color4D {
+        Color4D::new(
+            self.r + (other.r - self.r) * t,
+            self.g + (other.g - self.g) * t,
+            self.b + (other.b - self.b) * t,
+            self.a + (other.a - self.a) * t,
+        )
+    }
+}
+
+fn quantize(value: f32) -> u8 {
+    // `max` and `min` return the other argument when one is NaN, so NaN ends up as 0.
+    (value.max(0.0).min(1.0) * 255.0).round() as u8
+}
+
+fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
 }
 
 impl From<[f32; 4]> for Color4D {
@@ -2,6 +2,8 @@
 use cgmath::Matrix4;
 use ffi::aiMatrix4x4;
 
+use crate::math::Vector3D;
+
 define_type! {
     /// Matrix4x4 docs
     #[derive(Clone, Copy, Debug, PartialEq)]
@@ -46,6 +48,134 @@ impl Matrix4x4 {
             d4: c3r3,
         })
     }
+
+    /// The identity matrix.
+    pub fn identity() -> Matrix4x4 {
+        Matrix4x4::new(
+            1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0,
+        )
+    }
+
+    /// Transform a point by this matrix, including the translation part.
+    pub fn transform_point(&self, p: Vector3D) -> Vector3D {
+        Vector3D::new(
+            self.a1 * p.x + self.a2 * p.y + self.a3 * p.z + self.a4,
+            self.b1 * p.x + self.b2 * p.y + self.b3 * p.z + self.b4,
+            self.c1 * p.x + self.c2 * p.y + self.c3 * p.z + self.c4,
+        )
+    }
+
+    /// Transform a direction by this matrix, ignoring the translation part.
+    pub fn transform_vector(&self, v: Vector3D) -> Vector3D {
+        Vector3D::new(
+            self.a1 * v.x + self.a2 * v.y + self.a3 * v.z,
+            self.b1 * v.x + self.b2 * v.y + self.b3 * v.z,
+            self.c1 * v.x + self.c2 * v.y + self.c3 * v.z,
+        )
+    }
+
+    /// A matrix for transforming normals consistently with this matrix: the inverse transpose
+    /// of the upper 3x3 part, up to a positive scale factor, so transformed normals need to be
+    /// renormalized. The translation part is zero.
+    pub fn normal_matrix(&self) -> Matrix4x4 {
+        // The rows of the cofactor matrix, which is the inverse transpose scaled by the
+        // determinant.
+        let a = Vector3D::new(self.a1, self.a2, self.a3);
+        let b = Vector3D::new(self.b1, self.b2, self.b3);
+        let c = Vector3D::new(self.c1, self.c2, self.c3);
+        let rows = [b.cross(c), c.cross(a), a.cross(b)];
+
+        let sign = if a.dot(rows[0]) < 0.0 { -1.0 } else { 1.0 };
+        let row = |r: Vector3D| [r.x * sign, r.y * sign, r.z * sign, 0.0];
+
+        Matrix4x4::from_rows([
+            row(rows[0]),
+            row(rows[1]),
+            row(rows[2]),
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    /// The inverse of this matrix, assuming that its last row is `[0, 0, 0, 1]` as it is for
+    /// node transforms and bone offsets. Returns `None` if the upper 3x3 part is singular.
+    pub fn affine_inverse(&self) -> Option<Matrix4x4> {
+        let a = Vector3D::new(self.a1, self.a2, self.a3);
+        let b = Vector3D::new(self.b1, self.b2, self.b3);
+        let c = Vector3D::new(self.c1, self.c2, self.c3);
+        let cofactors = [b.cross(c), c.cross(a), a.cross(b)];
+        let det = a.dot(cofactors[0]);
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+
+        // The inverse of the 3x3 part is the transpose of the cofactor matrix over the
+        // determinant, and the translation is undone by moving back by its image.
+        let column = |i: usize| cofactors[i] * (1.0 / det);
+        let (x, y, z) = (column(0), column(1), column(2));
+        let inverse = Matrix4x4::from_rows([
+            [x.x, y.x, z.x, 0.0],
+            [x.y, y.y, z.y, 0.0],
+            [x.z, y.z, z.z, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ]);
+        let t = inverse.transform_vector(Vector3D::new(self.a4, self.b4, self.c4));
+        let mut rows = inverse.rows();
+        rows[0][3] = -t.x;
+        rows[1][3] = -t.y;
+        rows[2][3] = -t.z;
+        Some(Matrix4x4::from_rows(rows))
+    }
+
+    /// The transpose of this matrix.
+    pub fn transpose(&self) -> Matrix4x4 {
+        let r = self.rows();
+
+        Matrix4x4::from_rows([
+            [r[0][0], r[1][0], r[2][0], r[3][0]],
+            [r[0][1], r[1][1], r[2][1], r[3][1]],
+            [r[0][2], r[1][2], r[2][2], r[3][2]],
+            [r[0][3], r[1][3], r[2][3], r[3][3]],
+        ])
+    }
+
+    /// The rows of this matrix. Assimp matrices are row-major, with the translation in the last
+    /// column.
+    pub fn rows(&self) -> [[f32; 4]; 4] {
+        [
+            [self.a1, self.a2, self.a3, self.a4],
+            [self.b1, self.b2, self.b3, self.b4],
+            [self.c1, self.c2, self.c3, self.c4],
+            [self.d1, self.d2, self.d3, self.d4],
+        ]
+    }
+
+    /// Create a matrix from its rows, see `rows`.
+    pub fn from_rows(r: [[f32; 4]; 4]) -> Matrix4x4 {
+        Matrix4x4::new(
+            r[0][0], r[0][1], r[0][2], r[0][3], r[1][0], r[1][1], r[1][2], r[1][3], r[2][0],
+            r[2][1], r[2][2], r[2][3], r[3][0], r[3][1], r[3][2], r[3][3],
+        )
+    }
+}
+
+impl std::ops::Mul for Matrix4x4 {
+    type Output = Matrix4x4;
+
+    /// The matrix product, so `(a * b).transform_point(p)` is
+    /// `a.transform_point(b.transform_point(p))`.
+    fn mul(self, other: Matrix4x4) -> Matrix4x4 {
+        let a = self.rows();
+        let b = other.rows();
+        let mut out = [[0.0; 4]; 4];
+
+        for (i, row) in out.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = (0..4).map(|k| a[i][k] * b[k][j]).sum();
+            }
+        }
+
+        Matrix4x4::from_rows(out)
+    }
 }
 
 #[cfg(feature = "cgmath")]
@@ -67,3 +197,19 @@ impl From<Matrix4x4> for Matrix4<f32> {
         )
     }
 }
+
+/// Serializes a `Matrix4x4` as its rows.
+#[cfg(feature = "serde")]
+pub(crate) mod matrix_rows {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::Matrix4x4;
+
+    pub fn serialize<S: Serializer>(matrix: &Matrix4x4, serializer: S) -> Result<S::Ok, S::Error> {
+        matrix.rows().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Matrix4x4, D::Error> {
+        <[[f32; 4]; 4]>::deserialize(deserializer).map(Matrix4x4::from_rows)
+    }
+}
@@ -3,19 +3,39 @@
 //! Not really anything useful here. Conversion traits are implemented on each type to convert
 //! into/from the much more useful `cgmath` types.
 //! e.g. `Matrix3x3` converts to/from `cgmath::Matrix3<f32>`.
+//!
+//! The `_assimp` methods, such as `Matrix4x4::multiply_assimp`, call the math helpers of the
+//! Assimp C API, for results that exactly match what Assimp computes internally.
 
+pub use self::chunks::Chunks8;
 pub use self::color3::Color3D;
-pub use self::color4::Color4D;
+pub use self::color4::{Color4D, Color4DIter};
 pub use self::matrix3::Matrix3x3;
 pub use self::matrix4::Matrix4x4;
 pub use self::quaternion::Quaternion;
+pub use self::transform::{transform_normals, transform_points};
 pub use self::vector2::Vector2D;
-pub use self::vector3::Vector3D;
+pub use self::vector3::{Vector3D, Vector3DIter};
+
+mod c_api;
 
+// Everything in these is re-exported above, which is where to import it from. They're public so
+// that older paths such as `math::vector3::Vector3D` keep working.
+#[doc(hidden)]
+pub mod chunks;
+#[doc(hidden)]
 pub mod color3;
+#[doc(hidden)]
 pub mod color4;
+#[doc(hidden)]
 pub mod matrix3;
+#[doc(hidden)]
 pub mod matrix4;
+#[doc(hidden)]
 pub mod quaternion;
+#[doc(hidden)]
+pub mod transform;
+#[doc(hidden)]
 pub mod vector2;
+#[doc(hidden)]
 pub mod vector3;
@@ -17,6 +17,57 @@ impl Quaternion {
             z: z,
         })
     }
+
+    /// The identity rotation.
+    pub fn identity() -> Quaternion {
+        Quaternion::new(1.0, 0.0, 0.0, 0.0)
+    }
+
+    /// The dot product of this quaternion and `other`. For unit quaternions, the closer the
+    /// absolute value is to 1, the closer the rotations are.
+    pub fn dot(self, other: Quaternion) -> f32 {
+        self.w * other.w + self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    /// This quaternion scaled to unit length. Zero-length quaternions are returned unchanged.
+    pub fn normalize(self) -> Quaternion {
+        let len = self.dot(self).sqrt();
+
+        if len == 0.0 {
+            self
+        } else {
+            Quaternion::new(self.w / len, self.x / len, self.y / len, self.z / len)
+        }
+    }
+
+    /// Spherically interpolate between this rotation and `other`, taking the shortest path.
+    /// Both must be unit quaternions, and `t` should be in 0..1.
+    pub fn slerp(self, other: Quaternion, t: f32) -> Quaternion {
+        let mut cos = self.dot(other);
+        let mut other = other;
+        if cos < 0.0 {
+            cos = -cos;
+            other = Quaternion::new(-other.w, -other.x, -other.y, -other.z);
+        }
+
+        // Very close rotations would divide by almost zero, and linear interpolation is as
+        // good there.
+        let (a, b) = if cos > 0.9995 {
+            (1.0 - t, t)
+        } else {
+            let angle = cos.acos();
+            let sin = angle.sin();
+            (((1.0 - t) * angle).sin() / sin, (t * angle).sin() / sin)
+        };
+
+        Quaternion::new(
+            a * self.w + b * other.w,
+            a * self.x + b * other.x,
+            a * self.y + b * other.y,
+            a * self.z + b * other.z,
+        )
+        .normalize()
+    }
 }
 
 #[cfg(feature = "cgmath")]
@@ -0,0 +1,71 @@
+//! Bulk transformation of points and normals, e.g. for baking node transforms into vertex data
+//! or computing world-space bounds.
+//!
+//! The results are exactly the same as calling `Matrix4x4::transform_point` on each point, or
+//! `normal_matrix().transform_vector(n).normalize()` on each normal, but the loops are
+//! unrolled so that the compiler can vectorize them.
+
+use super::matrix4::Matrix4x4;
+use super::vector3::Vector3D;
+
+/// The rows of the upper 3x4 part of a matrix, copied out so that the loops don't read through
+/// a reference.
+#[derive(Copy, Clone)]
+struct Affine([f32; 12]);
+
+impl Affine {
+    fn new(m: &Matrix4x4) -> Affine {
+        Affine([
+            m.a1, m.a2, m.a3, m.a4, m.b1, m.b2, m.b3, m.b4, m.c1, m.c2, m.c3, m.c4,
+        ])
+    }
+
+    #[inline(always)]
+    fn point(&self, p: Vector3D) -> Vector3D {
+        let m = &self.0;
+        Vector3D::new(
+            m[0] * p.x + m[1] * p.y + m[2] * p.z + m[3],
+            m[4] * p.x + m[5] * p.y + m[6] * p.z + m[7],
+            m[8] * p.x + m[9] * p.y + m[10] * p.z + m[11],
+        )
+    }
+
+    #[inline(always)]
+    fn vector(&self, v: Vector3D) -> Vector3D {
+        let m = &self.0;
+        Vector3D::new(
+            m[0] * v.x + m[1] * v.y + m[2] * v.z,
+            m[4] * v.x + m[5] * v.y + m[6] * v.z,
+            m[8] * v.x + m[9] * v.y + m[10] * v.z,
+        )
+    }
+}
+
+/// Transform every point in `src` by `matrix`, including the translation part, replacing the
+/// contents of `dst`. Reusing `dst` between calls avoids reallocating it.
+pub fn transform_points(matrix: &Matrix4x4, src: &[Vector3D], dst: &mut Vec<Vector3D>) {
+    let m = Affine::new(matrix);
+    transform_with(src, dst, |p| m.point(p));
+}
+
+/// Transform every normal in `src` by the normal matrix of `matrix` and renormalize it,
+/// replacing the contents of `dst`. Translation doesn't affect normals, and non-uniform
+/// scaling keeps them perpendicular to the transformed surface. Zero-length normals stay
+/// zero-length.
+pub fn transform_normals(matrix: &Matrix4x4, src: &[Vector3D], dst: &mut Vec<Vector3D>) {
+    let m = Affine::new(&matrix.normal_matrix());
+    transform_with(src, dst, |n| m.vector(n).normalize());
+}
+
+#[inline(always)]
+fn transform_with<F: Fn(Vector3D) -> Vector3D>(src: &[Vector3D], dst: &mut Vec<Vector3D>, f: F) {
+    dst.clear();
+    dst.reserve(src.len());
+
+    let chunks = src.chunks_exact(4);
+    let rest = chunks.remainder();
+    for chunk in chunks {
+        dst.extend_from_slice(&[f(chunk[0]), f(chunk[1]), f(chunk[2]), f(chunk[3])]);
+    }
+    dst.extend(rest.iter().map(|&v| f(v)));
+}
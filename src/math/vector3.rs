@@ -14,6 +14,74 @@ impl Vector3D {
     pub fn new(x: f32, y: f32, z: f32) -> Vector3D {
         Vector3D(aiVector3D { x: x, y: y, z: z })
     }
+
+    /// The dot product of this vector and `other`.
+    pub fn dot(self, other: Vector3D) -> f32 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    /// The cross product of this vector and `other`.
+    pub fn cross(self, other: Vector3D) -> Vector3D {
+        Vector3D::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+
+    /// The length of this vector.
+    pub fn length(self) -> f32 {
+        self.dot(self).sqrt()
+    }
+
+    /// This vector scaled to unit length. Zero-length vectors are returned unchanged.
+    pub fn normalize(self) -> Vector3D {
+        let len = self.length();
+
+        if len == 0.0 {
+            self
+        } else {
+            self * (1.0 / len)
+        }
+    }
+
+    /// Linearly interpolate between this vector and `other`. `t` is not clamped, so values
+    /// outside 0..1 extrapolate.
+    pub fn lerp(self, other: Vector3D, t: f32) -> Vector3D {
+        self + (other - self) * t
+    }
+}
+
+impl std::ops::Add for Vector3D {
+    type Output = Vector3D;
+
+    fn add(self, other: Vector3D) -> Vector3D {
+        Vector3D::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl std::ops::Sub for Vector3D {
+    type Output = Vector3D;
+
+    fn sub(self, other: Vector3D) -> Vector3D {
+        Vector3D::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl std::ops::Mul<f32> for Vector3D {
+    type Output = Vector3D;
+
+    fn mul(self, factor: f32) -> Vector3D {
+        Vector3D::new(self.x * factor, self.y * factor, self.z * factor)
+    }
+}
+
+impl std::ops::Neg for Vector3D {
+    type Output = Vector3D;
+
+    fn neg(self) -> Vector3D {
+        Vector3D::new(-self.x, -self.y, -self.z)
+    }
 }
 
 impl From<[f32; 3]> for Vector3D {
@@ -0,0 +1,15 @@
+//! The types that most users of the crate need, to glob import with
+//! `use open_asset_importer::prelude::*;`.
+//!
+//! These paths are stable: the prelude only grows, so a glob import of it keeps compiling when
+//! the modules that define the types are rearranged. Everything else is reachable from the
+//! crate root or from `import`, `io`, `log`, `math` and `scene`.
+
+pub use crate::import::structs::{PrimitiveType, PrimitiveTypes};
+pub use crate::import::Importer;
+pub use crate::io::{File, FileIO};
+pub use crate::log::LogStream;
+pub use crate::math::{Color3D, Color4D, Matrix3x3, Matrix4x4, Quaternion, Vector2D, Vector3D};
+pub use crate::scene::{
+    Animation, Material, MaterialComponentType, Mesh, Node, NodeAnim, Scene, TextureDefinition,
+};
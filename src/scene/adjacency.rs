@@ -0,0 +1,77 @@
+//! Edge adjacency of polygons, for the algorithms that walk across the surface of a mesh.
+
+use std::collections::HashMap;
+
+use crate::math::Vector3D;
+
+/// A position as a hashable key, with `-0.0` and `0.0` the same.
+pub(crate) fn position_key(p: Vector3D) -> [u32; 3] {
+    [
+        (p.x + 0.0).to_bits(),
+        (p.y + 0.0).to_bits(),
+        (p.z + 0.0).to_bits(),
+    ]
+}
+
+type EdgeKey = ([u32; 3], [u32; 3]);
+
+/// The faces around every edge of a set of polygons. Edges are compared by the positions of
+/// their ends rather than by index, so faces are connected across the seams where importers
+/// split vertices for different normals or texture coordinates. Faces with fewer than three
+/// indices and edges of zero length don't connect anything.
+pub(crate) struct EdgeAdjacency {
+    keys: Vec<[u32; 3]>,
+    edges: HashMap<EdgeKey, Vec<usize>>,
+}
+
+impl EdgeAdjacency {
+    /// Every index in `faces` must be below `positions.len()`.
+    pub(crate) fn new<'a, I>(positions: &[Vector3D], faces: I) -> Self
+    where
+        I: IntoIterator<Item = &'a [u32]>,
+    {
+        let keys: Vec<_> = positions.iter().map(|&p| position_key(p)).collect();
+        let mut edges: HashMap<EdgeKey, Vec<usize>> = HashMap::new();
+        for (index, face) in faces.into_iter().enumerate() {
+            for key in edge_keys(&keys, face) {
+                edges.entry(key).or_default().push(index);
+            }
+        }
+        EdgeAdjacency { keys, edges }
+    }
+
+    /// For each edge of `face`, the faces that contain it, including `face` itself.
+    pub(crate) fn around<'s>(&'s self, face: &'s [u32]) -> impl Iterator<Item = &'s [usize]> {
+        edge_keys(&self.keys, face)
+            .map(move |key| self.edges.get(&key).map_or(&[][..], |faces| &faces[..]))
+    }
+
+    /// The edges of `face` that no other face has, i.e. the parts of the open boundary of the
+    /// surface along `face`, as pairs of indices.
+    pub(crate) fn open_edges<'s>(&'s self, face: &'s [u32]) -> impl Iterator<Item = [u32; 2]> + 's {
+        let n = if face.len() < 3 { 0 } else { face.len() };
+        (0..n)
+            .map(move |k| [face[k], face[(k + 1) % n]])
+            .filter(move |&[a, b]| {
+                let (a, b) = (self.keys[a as usize], self.keys[b as usize]);
+                let key = if a < b { (a, b) } else { (b, a) };
+                a != b && self.edges.get(&key).map_or(false, |faces| faces.len() == 1)
+            })
+    }
+}
+
+/// The edges of `face` that have a length, as sorted pairs of position keys.
+fn edge_keys<'a>(keys: &'a [[u32; 3]], face: &'a [u32]) -> impl Iterator<Item = EdgeKey> + 'a {
+    let n = if face.len() < 3 { 0 } else { face.len() };
+    (0..n).filter_map(move |k| {
+        let a = keys[face[k] as usize];
+        let b = keys[face[(k + 1) % n] as usize];
+        if a < b {
+            Some((a, b))
+        } else if b < a {
+            Some((b, a))
+        } else {
+            None
+        }
+    })
+}
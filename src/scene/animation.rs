@@ -1,6 +1,13 @@
 use crate::math::{Quaternion, Vector3D};
 use ffi::{aiAnimation, aiNodeAnim, aiQuatKey, aiVectorKey};
+use std::ffi::CStr;
+use std::fmt;
 use std::ptr::NonNull;
+use std::str::Utf8Error;
+
+/// The ticks per second Assimp assumes for animations that don't specify it, as used by
+/// `Animation::effective_fps`.
+pub const DEFAULT_TICKS_PER_SECOND: f64 = 25.0;
 
 define_type_and_iterator_indirect! {
     /// Animation type (not yet implemented)
@@ -10,27 +17,94 @@ define_type_and_iterator_indirect! {
 }
 
 impl Animation {
+    /// The name of the animation. Files that only contain a single animation will often leave
+    /// this empty.
+    ///
+    /// Panics if the animation name isn't valid UTF-8, see `try_name`.
+    pub fn name(&self) -> &str {
+        self.try_name().expect("animation name is not valid UTF-8")
+    }
+
+    /// Like `name`, but returns an error if the animation name isn't valid UTF-8.
+    pub fn try_name(&self) -> Result<&str, Utf8Error> {
+        crate::aistring_to_str(&self.mName)
+    }
+
+    /// The name of the animation as a `CStr`, without checking that it's valid UTF-8.
+    pub fn name_cstr(&self) -> &CStr {
+        crate::aistring_to_cstr(&self.mName)
+    }
+
+    /// Whether the animation is named `name`, compared byte for byte.
+    pub fn eq_name(&self, name: &[u8]) -> bool {
+        self.name_cstr().to_bytes() == name
+    }
+
+    /// The raw `mTicksPerSecond` of the animation.
+    #[deprecated(
+        note = "this is the raw value, which is often 0; use `ticks_per_second` or `effective_fps`"
+    )]
     pub fn fps(&self) -> f64 {
+        self.ticks_per_second()
+    }
+
+    /// The number of ticks per second as stored in the file. This is 0 if the file doesn't
+    /// specify it, which is common for FBX and Collada files; `effective_fps` applies the
+    /// default in that case.
+    pub fn ticks_per_second(&self) -> f64 {
         self.mTicksPerSecond
     }
 
+    /// The number of ticks per second to play the animation at: `ticks_per_second` if the file
+    /// specifies it, otherwise `default`, or `DEFAULT_TICKS_PER_SECOND` if that's `None`.
+    pub fn effective_fps(&self, default: Option<f64>) -> f64 {
+        effective_fps(self.mTicksPerSecond, default)
+    }
+
+    /// The duration in ticks, see `duration_seconds`.
     pub fn duration(&self) -> f64 {
         self.mDuration
     }
 
+    /// The duration in seconds, at `effective_fps(None)` ticks per second.
+    pub fn duration_seconds(&self) -> f64 {
+        self.ticks_to_seconds(self.mDuration)
+    }
+
+    /// Convert a time in ticks, like those of the keys, to seconds at `effective_fps(None)`.
+    pub fn ticks_to_seconds(&self, ticks: f64) -> f64 {
+        ticks / self.effective_fps(None)
+    }
+
+    /// Convert a time in seconds to ticks at `effective_fps(None)`, the inverse of
+    /// `ticks_to_seconds`.
+    pub fn seconds_to_ticks(&self, seconds: f64) -> f64 {
+        seconds * self.effective_fps(None)
+    }
+
     pub fn node_anims(&self) -> NodeAnimIter {
-        NodeAnimIter::new(
-            NonNull::new(self.mChannels as *mut *const _),
-            self.mNumChannels as usize,
-        )
+        unsafe {
+            NodeAnimIter::new(
+                NonNull::new(self.mChannels as *mut *const _),
+                self.mNumChannels as usize,
+            )
+        }
+    }
+
+    /// The first channel that animates the node named `node_name`. Names are compared byte for
+    /// byte, so this doesn't check them for valid UTF-8.
+    pub fn channel_for(&self, node_name: &[u8]) -> Option<&NodeAnim> {
+        self.node_anims()
+            .find(|channel| channel.eq_node_name(node_name))
     }
 
     pub fn get_node_anim(&self, id: usize) -> Option<&NodeAnim> {
         if id < self.mNumChannels as usize {
             unsafe {
-                Some(NodeAnim::from_raw(NonNull::new(
-                    *(NonNull::new(self.mChannels)?.as_ptr().offset(id as isize)),
-                )?))
+                Some(NodeAnim::from_raw(NonNull::new(*crate::array_element(
+                    self.mChannels,
+                    id,
+                )?)?))
             }
         } else {
             None
@@ -38,6 +112,28 @@ impl Animation {
     }
 }
 
+/// Prints the animation's name, duration, raw ticks per second and number of channels, but not the
+/// channels themselves.
+impl fmt::Debug for Animation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Animation")
+            .field("name", &self.name_cstr().to_string_lossy())
+            .field("duration", &self.duration())
+            .field("ticks_per_second", &self.ticks_per_second())
+            .field("num_channels", &self.mNumChannels)
+            .finish()
+    }
+}
+
+/// `ticks_per_second` if it's positive, otherwise `default` or `DEFAULT_TICKS_PER_SECOND`.
+pub(crate) fn effective_fps(ticks_per_second: f64, default: Option<f64>) -> f64 {
+    if ticks_per_second > 0.0 {
+        ticks_per_second
+    } else {
+        default.unwrap_or(DEFAULT_TICKS_PER_SECOND)
+    }
+}
+
 define_type_and_iterator_indirect! {
     /// NodeAnim type (not yet implemented)
     struct NodeAnim(&aiNodeAnim)
@@ -59,6 +155,36 @@ define_type_and_iterator! {
     struct QuatKeyIter
 }
 
+/// How to get from a key to the next one, see `VectorKey::interpolation`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Interpolation {
+    /// Hold the key's value until the next key.
+    Step,
+    /// Interpolate linearly to the next key.
+    Linear,
+    /// Interpolate spherically to the next key, which is how rotations are interpolated.
+    SphericalLinear,
+    /// Follow a cubic spline to the next key. Assimp doesn't keep the tangents of the spline,
+    /// so sampling interpolates these keys like `Linear` ones.
+    CubicSpline,
+}
+
+impl Interpolation {
+    /// The interpolation of a raw key, or `default` for values that this crate doesn't know.
+    #[cfg(feature = "assimp-5-1")]
+    fn from_raw(raw: ffi::aiAnimInterpolation, default: Interpolation) -> Interpolation {
+        match raw {
+            ffi::aiAnimInterpolation_aiAnimInterpolation_Step => Interpolation::Step,
+            ffi::aiAnimInterpolation_aiAnimInterpolation_Linear => Interpolation::Linear,
+            ffi::aiAnimInterpolation_aiAnimInterpolation_Spherical_Linear => {
+                Interpolation::SphericalLinear
+            }
+            ffi::aiAnimInterpolation_aiAnimInterpolation_Cubic_Spline => Interpolation::CubicSpline,
+            _ => default,
+        }
+    }
+}
+
 impl VectorKey {
     pub fn time(&self) -> f64 {
         self.mTime
@@ -67,6 +193,19 @@ impl VectorKey {
     pub fn value(&self) -> Vector3D {
         Vector3D(self.mValue)
     }
+
+    /// How to get from this key to the next one.
+    #[cfg(feature = "assimp-5-1")]
+    pub fn interpolation(&self) -> Interpolation {
+        Interpolation::from_raw(self.mInterpolation, Interpolation::Linear)
+    }
+
+    /// How to get from this key to the next one. Versions of Assimp before 5.1 don't record
+    /// this, so without the `assimp-5-1` feature this is always `Interpolation::Linear`.
+    #[cfg(not(feature = "assimp-5-1"))]
+    pub fn interpolation(&self) -> Interpolation {
+        Interpolation::Linear
+    }
 }
 
 impl QuatKey {
@@ -77,23 +216,50 @@ impl QuatKey {
     pub fn value(&self) -> Quaternion {
         Quaternion(self.mValue)
     }
+
+    /// How to get from this key to the next one.
+    #[cfg(feature = "assimp-5-1")]
+    pub fn interpolation(&self) -> Interpolation {
+        Interpolation::from_raw(self.mInterpolation, Interpolation::SphericalLinear)
+    }
+
+    /// How to get from this key to the next one. Versions of Assimp before 5.1 don't record
+    /// this, so without the `assimp-5-1` feature this is always
+    /// `Interpolation::SphericalLinear`.
+    #[cfg(not(feature = "assimp-5-1"))]
+    pub fn interpolation(&self) -> Interpolation {
+        Interpolation::SphericalLinear
+    }
 }
 
 impl NodeAnim {
+    /// Panics if the node name isn't valid UTF-8, see `try_node_name`.
     pub fn node_name(&self) -> &str {
-        unsafe { crate::aistring_to_cstr(&self.mNodeName) }
-            .to_str()
-            .unwrap()
+        self.try_node_name().expect("node name is not valid UTF-8")
+    }
+
+    /// Like `node_name`, but returns an error if the node name isn't valid UTF-8.
+    pub fn try_node_name(&self) -> Result<&str, Utf8Error> {
+        crate::aistring_to_str(&self.mNodeName)
+    }
+
+    /// The name of the animated node as a `CStr`, without checking that it's valid UTF-8.
+    pub fn node_name_cstr(&self) -> &CStr {
+        crate::aistring_to_cstr(&self.mNodeName)
+    }
+
+    /// Whether the animated node is named `name`, compared byte for byte.
+    pub fn eq_node_name(&self, name: &[u8]) -> bool {
+        self.node_name_cstr().to_bytes() == name
     }
 
     pub fn get_position_key(&self, id: usize) -> Option<&VectorKey> {
         if id < self.mNumPositionKeys as usize {
             unsafe {
-                Some(VectorKey::from_raw(NonNull::new(
-                    NonNull::new(self.mPositionKeys)?
-                        .as_ptr()
-                        .offset(id as isize),
-                )?))
+                Some(VectorKey::from_raw(NonNull::new(crate::array_element(
+                    self.mPositionKeys,
+                    id,
+                )?)?))
             }
         } else {
             None
@@ -101,34 +267,39 @@ impl NodeAnim {
     }
 
     pub fn position_keys(&self) -> VectorKeyIter {
-        VectorKeyIter::new(
-            NonNull::new(self.mPositionKeys),
-            self.mNumPositionKeys as usize,
-        )
+        unsafe {
+            VectorKeyIter::new(
+                NonNull::new(self.mPositionKeys),
+                self.mNumPositionKeys as usize,
+            )
+        }
     }
 
     pub fn rotation_keys(&self) -> QuatKeyIter {
-        QuatKeyIter::new(
-            NonNull::new(self.mRotationKeys),
-            self.mNumRotationKeys as usize,
-        )
+        unsafe {
+            QuatKeyIter::new(
+                NonNull::new(self.mRotationKeys),
+                self.mNumRotationKeys as usize,
+            )
+        }
     }
 
     pub fn scaling_keys(&self) -> VectorKeyIter {
-        VectorKeyIter::new(
-            NonNull::new(self.mScalingKeys),
-            self.mNumScalingKeys as usize,
-        )
+        unsafe {
+            VectorKeyIter::new(
+                NonNull::new(self.mScalingKeys),
+                self.mNumScalingKeys as usize,
+            )
+        }
     }
 
     pub fn get_rotation_key(&self, id: usize) -> Option<&QuatKey> {
         if id < self.mNumRotationKeys as usize {
             unsafe {
-                Some(QuatKey::from_raw(NonNull::new(
-                    NonNull::new(self.mRotationKeys)?
-                        .as_ptr()
-                        .offset(id as isize),
-                )?))
+                Some(QuatKey::from_raw(NonNull::new(crate::array_element(
+                    self.mRotationKeys,
+                    id,
+                )?)?))
             }
         } else {
             None
@@ -137,14 +308,88 @@ impl NodeAnim {
     pub fn get_scaling_key(&self, id: usize) -> Option<&VectorKey> {
         if id < self.mNumScalingKeys as usize {
             unsafe {
-                Some(VectorKey::from_raw(NonNull::new(
-                    NonNull::new(self.mScalingKeys)?
-                        .as_ptr()
-                        .offset(id as isize),
-                )?))
+                Some(VectorKey::from_raw(NonNull::new(crate::array_element(
+                    self.mScalingKeys,
+                    id,
+                )?)?))
             }
         } else {
             None
         }
     }
+
+    /// The position at `time`, in ticks. Each key's `interpolation` says how to get to the
+    /// next key: `Step` keys hold their value, and the others are interpolated linearly. Times
+    /// before the first key or after the last give the value of that key. Returns `None` if
+    /// there are no position keys.
+    pub fn position_at(&self, time: f64) -> Option<Vector3D> {
+        let keys: &[VectorKey] = unsafe { key_slice(self.mPositionKeys, self.mNumPositionKeys) };
+        sample_keys(
+            keys,
+            time,
+            |key| (key.time(), key.value(), key.interpolation()),
+            Vector3D::lerp,
+        )
+    }
+
+    /// The rotation at `time`, in ticks, see `position_at`. Keys that aren't `Step` keys are
+    /// interpolated spherically.
+    pub fn rotation_at(&self, time: f64) -> Option<Quaternion> {
+        let keys: &[QuatKey] = unsafe { key_slice(self.mRotationKeys, self.mNumRotationKeys) };
+        sample_keys(
+            keys,
+            time,
+            |key| (key.time(), key.value(), key.interpolation()),
+            Quaternion::slerp,
+        )
+    }
+
+    /// The scale at `time`, in ticks, see `position_at`.
+    pub fn scaling_at(&self, time: f64) -> Option<Vector3D> {
+        let keys: &[VectorKey] = unsafe { key_slice(self.mScalingKeys, self.mNumScalingKeys) };
+        sample_keys(
+            keys,
+            time,
+            |key| (key.time(), key.value(), key.interpolation()),
+            Vector3D::lerp,
+        )
+    }
+}
+
+/// The keys of a track as a slice. `K` must be a transparent wrapper around `R`, like
+/// `VectorKey` and `QuatKey` are.
+unsafe fn key_slice<'a, K, R>(keys: *mut R, len: u32) -> &'a [K] {
+    crate::array_slice(keys as *const K, len as usize)
+}
+
+/// The value of a track at `time`. `key` gives the time, value and interpolation of a key,
+/// and keys must be sorted by time, which Assimp guarantees. `interpolate` is used between
+/// keys that aren't `Interpolation::Step` keys.
+pub(crate) fn sample_keys<K, T: Copy>(
+    keys: &[K],
+    time: f64,
+    key: impl Fn(&K) -> (f64, T, Interpolation),
+    interpolate: impl Fn(T, T, f32) -> T,
+) -> Option<T> {
+    let (first, last) = (key(keys.first()?), key(keys.last()?));
+    if time <= first.0 {
+        return Some(first.1);
+    }
+    if time >= last.0 {
+        return Some(last.1);
+    }
+
+    let next = keys.partition_point(|k| key(k).0 <= time);
+    let (t0, v0, interpolation) = key(&keys[next - 1]);
+    let (t1, v1, _) = key(&keys[next]);
+    if interpolation == Interpolation::Step {
+        return Some(v0);
+    }
+    let t = if t1 > t0 {
+        (time - t0) / (t1 - t0)
+    } else {
+        0.0
+    };
+
+    Some(interpolate(v0, v1, t as f32))
 }
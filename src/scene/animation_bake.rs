@@ -0,0 +1,210 @@
+//! Baking animations into flat tables of joint matrices, for runtimes that play animations
+//! back by looking up a frame rather than by interpolating keys.
+//!
+//! `bake_to_table` samples every joint of a skeleton at a fixed frame rate and stores a matrix
+//! per joint per frame in one contiguous buffer, frame after frame, which can be uploaded to
+//! the GPU as it is.
+
+use std::slice;
+
+use crate::math::{Matrix4x4, Quaternion, Vector3D};
+
+use super::animation::Animation;
+use super::joints::Joint;
+use super::mesh::Mesh;
+use super::node::Node;
+
+/// An animation sampled at a fixed frame rate, as returned by `bake_to_table`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BakedAnimation {
+    /// The frames per second the animation was sampled at.
+    pub fps: f64,
+    /// The number of frames. Frame `i` is at `i / fps` seconds.
+    pub frame_count: u32,
+    /// The duration of the animation in seconds, see `Animation::duration_seconds`.
+    pub duration: f64,
+    /// The names of the joints, in the order of the matrices of each frame.
+    pub joint_names: Vec<String>,
+    /// `frame_count` frames of one matrix per joint, frame-major, so the matrix of joint `j`
+    /// in frame `f` is at `f * joint_names.len() + j`.
+    pub matrices: Vec<Matrix4x4>,
+}
+
+impl BakedAnimation {
+    /// The number of joints, and so of matrices in each frame.
+    pub fn joint_count(&self) -> usize {
+        self.joint_names.len()
+    }
+
+    /// The matrices of `frame`, one per joint, or `None` if there's no such frame.
+    pub fn frame(&self, frame: u32) -> Option<&[Matrix4x4]> {
+        let count = self.joint_count();
+        let start = frame as usize * count;
+        self.matrices
+            .get(start..start + count)
+            .filter(|_| frame < self.frame_count)
+    }
+
+    /// The matrices as floats, 16 per matrix. Like `Matrix4x4::rows`, each matrix is stored
+    /// row by row, so shaders that expect column-major matrices need to transpose them.
+    pub fn as_floats(&self) -> &[f32] {
+        // `Matrix4x4` is a transparent wrapper around `aiMatrix4x4`, which is 16 `f32`s.
+        unsafe {
+            slice::from_raw_parts(
+                self.matrices.as_ptr() as *const f32,
+                self.matrices.len() * 16,
+            )
+        }
+    }
+}
+
+/// Sample the joints `joints` of `animation` every `1 / fps` seconds, from 0 up to the
+/// duration of the animation, which is the last frame if the duration is a multiple of
+/// the frame time.
+///
+/// Each joint is sampled with `NodeAnim::position_at` and friends, using the tracks of the
+/// channel for its node. Joints without a channel, and tracks that a channel doesn't have,
+/// keep the node's bind pose, its `Node::transform`. Each matrix is the transform of the
+/// joint relative to the scene's root node, composed from the transforms of its parents in the
+/// order of `joints`, including any animated nodes between two joints that aren't joints
+/// themselves.
+///
+/// With `inverse_bind`, which has a matrix for each joint, each matrix is multiplied by the
+/// joint's inverse bind matrix to give the skinning matrix that moves vertices from their bind
+/// pose to the animated one. `inverse_bind_matrices` gives these for the joints of a mesh.
+///
+/// Panics if `fps` isn't positive, or if `inverse_bind` doesn't have a matrix for each joint.
+pub fn bake_to_table(
+    animation: &Animation,
+    joints: &[Joint],
+    fps: f64,
+    inverse_bind: Option<&[Matrix4x4]>,
+) -> BakedAnimation {
+    assert!(fps > 0.0, "the frame rate must be positive");
+    if let Some(inverse_bind) = inverse_bind {
+        assert_eq!(
+            inverse_bind.len(),
+            joints.len(),
+            "one inverse bind matrix per joint"
+        );
+    }
+
+    // The nodes from each joint's parent joint, or the root node, down to the joint.
+    let chains: Vec<Vec<&Node>> = joints
+        .iter()
+        .map(|joint| {
+            let stop = joint
+                .parent_index
+                .map(|parent| joints[parent].node as *const Node);
+            let mut chain = vec![joint.node];
+            let mut ancestor = joint.node.parent();
+            while let Some(node) = ancestor {
+                if Some(node as *const Node) == stop {
+                    break;
+                }
+                chain.push(node);
+                ancestor = node.parent();
+            }
+            chain.reverse();
+            chain
+        })
+        .collect();
+
+    let duration = animation.duration_seconds();
+    // The tolerance keeps the last frame when the duration is a multiple of the frame time.
+    let frame_count = (duration.max(0.0) * fps + 1e-6).floor() as u32 + 1;
+
+    let mut matrices = Vec::with_capacity(frame_count as usize * joints.len());
+    let mut globals: Vec<Matrix4x4> = Vec::with_capacity(joints.len());
+    for frame in 0..frame_count {
+        let time = animation.seconds_to_ticks(frame as f64 / fps);
+        globals.clear();
+        for (joint, chain) in joints.iter().zip(&chains) {
+            let local = chain.iter().fold(Matrix4x4::identity(), |transform, node| {
+                transform * sample_node(animation, node, time)
+            });
+            let global = match joint.parent_index {
+                Some(parent) => globals[parent] * local,
+                None => local,
+            };
+            globals.push(global);
+        }
+
+        match inverse_bind {
+            Some(inverse_bind) => matrices.extend(
+                globals
+                    .iter()
+                    .zip(inverse_bind)
+                    .map(|(&global, &offset)| global * offset),
+            ),
+            None => matrices.extend_from_slice(&globals),
+        }
+    }
+
+    BakedAnimation {
+        fps,
+        frame_count,
+        duration,
+        joint_names: joints
+            .iter()
+            .map(|joint| joint.node.name_cstr().to_string_lossy().into_owned())
+            .collect(),
+        matrices,
+    }
+}
+
+/// The offset matrix of the bone of each joint in `joints`, which must come from
+/// `joints::mesh_joints` for `mesh`, for the `inverse_bind` of `bake_to_table`.
+pub fn inverse_bind_matrices(mesh: &Mesh, joints: &[Joint]) -> Vec<Matrix4x4> {
+    joints
+        .iter()
+        .map(|joint| {
+            mesh.bone(joint.source as u32)
+                .map_or_else(Matrix4x4::identity, |bone| bone.offset_matrix())
+        })
+        .collect()
+}
+
+/// The transform of `node` relative to its parent at `time`, in ticks.
+fn sample_node(animation: &Animation, node: &Node, time: f64) -> Matrix4x4 {
+    let channel = match animation.channel_for(node.name_cstr().to_bytes()) {
+        Some(channel) => channel,
+        None => return node.transform(),
+    };
+
+    let (scaling, rotation, position) = node.transform().decompose_assimp();
+    compose(
+        channel.position_at(time).unwrap_or(position),
+        channel.rotation_at(time).unwrap_or(rotation),
+        channel.scaling_at(time).unwrap_or(scaling),
+    )
+}
+
+/// The matrix that scales, then rotates, then translates.
+fn compose(position: Vector3D, rotation: Quaternion, scaling: Vector3D) -> Matrix4x4 {
+    let q = rotation.normalize();
+    let (w, x, y, z) = (q.w, q.x, q.y, q.z);
+    let (s, t) = (scaling, position);
+
+    Matrix4x4::from_rows([
+        [
+            (1.0 - 2.0 * (y * y + z * z)) * s.x,
+            2.0 * (x * y - w * z) * s.y,
+            2.0 * (x * z + w * y) * s.z,
+            t.x,
+        ],
+        [
+            2.0 * (x * y + w * z) * s.x,
+            (1.0 - 2.0 * (x * x + z * z)) * s.y,
+            2.0 * (y * z - w * x) * s.z,
+            t.y,
+        ],
+        [
+            2.0 * (x * z - w * y) * s.x,
+            2.0 * (y * z + w * x) * s.y,
+            (1.0 - 2.0 * (x * x + y * y)) * s.z,
+            t.z,
+        ],
+        [0.0, 0.0, 0.0, 1.0],
+    ])
+}
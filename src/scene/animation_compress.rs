@@ -0,0 +1,239 @@
+//! Keyframe reduction of owned animation data, for oversampled animations, e.g. motion
+//! capture with a key every frame.
+//!
+//! `reduce_keys` removes the keys of a channel that interpolating the keys around them
+//! reproduces within a `ReduceTolerance`, and `reduce_animation` does that for every channel
+//! of an `AnimationData` and reports the error that was actually introduced, measured by
+//! sampling the channels before and after densely.
+
+use crate::math::{Quaternion, Vector3D};
+
+use super::snapshot::{AnimationData, ChannelData};
+
+/// The number of times sampled between two consecutive keys of the original track, besides
+/// the keys themselves, when checking rotations and measuring errors.
+const SAMPLES_PER_KEY: usize = 8;
+
+/// The largest error `reduce_keys` may introduce in each kind of track. Default: 0.001 units
+/// of position and scale and 0.1 degrees of rotation
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ReduceTolerance {
+    /// The largest distance between a position and the original.
+    pub position: f32,
+    /// The largest angle between a rotation and the original, in degrees.
+    pub rotation_degrees: f32,
+    /// The largest distance between a scale and the original, treating the scales as vectors.
+    pub scale: f32,
+}
+
+impl Default for ReduceTolerance {
+    fn default() -> Self {
+        ReduceTolerance {
+            position: 0.001,
+            rotation_degrees: 0.1,
+            scale: 0.001,
+        }
+    }
+}
+
+/// The largest differences between a channel and a reduced copy of it, see `measure_error`.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct ChannelError {
+    pub position: f32,
+    pub rotation_degrees: f32,
+    pub scale: f32,
+}
+
+/// What `reduce_animation` did to one channel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelReduction {
+    pub node_name: String,
+    /// The total number of position, rotation and scaling keys.
+    pub keys_before: usize,
+    pub keys_after: usize,
+    /// The error measured by sampling the channel before and after, not the tolerance.
+    pub max_error: ChannelError,
+}
+
+/// A copy of `channel` without the keys that interpolating the remaining keys reproduces
+/// within `tolerance`. The first and last keys of each track are always kept, so the tracks
+/// start and end at the same times.
+///
+/// Keys are visited in order, and a key is dropped if interpolating from the last kept key to
+/// the key after it reproduces it, and every key dropped since the last kept key, within
+/// `tolerance`. Errors are measured against the original keys, so they don't add up over
+/// runs of dropped keys. Positions and scales are interpolated linearly, so checking the keys
+/// bounds the error at every time in between. Rotations are also checked at times between
+/// the keys, since spherical interpolation between farther apart keys takes a different path.
+///
+/// The result only depends on `channel` and `tolerance`. Reducing can take time quadratic in
+/// the number of keys of a track if most of them are dropped.
+pub fn reduce_keys(channel: &ChannelData, tolerance: ReduceTolerance) -> ChannelData {
+    ChannelData {
+        node_name: channel.node_name.clone(),
+        position_keys: reduce_track(
+            &channel.position_keys,
+            Vector3D::lerp,
+            vector_distance,
+            tolerance.position,
+            0,
+        ),
+        rotation_keys: reduce_track(
+            &channel.rotation_keys,
+            Quaternion::slerp,
+            rotation_angle,
+            tolerance.rotation_degrees,
+            SAMPLES_PER_KEY,
+        ),
+        scaling_keys: reduce_track(
+            &channel.scaling_keys,
+            Vector3D::lerp,
+            vector_distance,
+            tolerance.scale,
+            0,
+        ),
+    }
+}
+
+/// Replace every channel of `anim` with `reduce_keys` of it, and return what was done to each
+/// channel, in the order of `anim.channels`.
+pub fn reduce_animation(
+    anim: &mut AnimationData,
+    tolerance: ReduceTolerance,
+) -> Vec<ChannelReduction> {
+    anim.channels
+        .iter_mut()
+        .map(|channel| {
+            let reduced = reduce_keys(channel, tolerance);
+            let stats = ChannelReduction {
+                node_name: channel.node_name.clone(),
+                keys_before: num_keys(channel),
+                keys_after: num_keys(&reduced),
+                max_error: measure_error(channel, &reduced),
+            };
+            *channel = reduced;
+            stats
+        })
+        .collect()
+}
+
+/// The largest differences between `original` and `reduced`, sampled with
+/// `ChannelData::position_at` and friends at every key of `original` and several times
+/// between each pair of them. A track that's in one channel and not the other is ignored.
+pub fn measure_error(original: &ChannelData, reduced: &ChannelData) -> ChannelError {
+    let position = track_error(&original.position_keys, |time| {
+        Some(vector_distance(
+            original.position_at(time)?,
+            reduced.position_at(time)?,
+        ))
+    });
+    let rotation = track_error(&original.rotation_keys, |time| {
+        Some(rotation_angle(
+            original.rotation_at(time)?,
+            reduced.rotation_at(time)?,
+        ))
+    });
+    let scale = track_error(&original.scaling_keys, |time| {
+        Some(vector_distance(
+            original.scaling_at(time)?,
+            reduced.scaling_at(time)?,
+        ))
+    });
+
+    ChannelError {
+        position,
+        rotation_degrees: rotation,
+        scale,
+    }
+}
+
+fn reduce_track<T: Copy>(
+    keys: &[(f64, T)],
+    interpolate: impl Fn(T, T, f32) -> T,
+    error: impl Fn(T, T) -> f32,
+    tolerance: f32,
+    samples_per_key: usize,
+) -> Vec<(f64, T)> {
+    if keys.len() <= 2 {
+        return keys.to_vec();
+    }
+
+    // Whether interpolating from `keys[start]` to `keys[end]` reproduces the original track
+    // in between.
+    let reproduces = |start: usize, end: usize| {
+        let (t0, v0) = keys[start];
+        let (t1, v1) = keys[end];
+        let at = |time: f64| {
+            let t = if t1 > t0 {
+                (time - t0) / (t1 - t0)
+            } else {
+                0.0
+            };
+            interpolate(v0, v1, t as f32)
+        };
+
+        (start + 1..end).all(|i| error(at(keys[i].0), keys[i].1) <= tolerance)
+            && (start..end).all(|i| {
+                let ((ta, va), (tb, vb)) = (keys[i], keys[i + 1]);
+                (1..=samples_per_key).all(|sample| {
+                    let t = sample as f64 / (samples_per_key + 1) as f64;
+                    let time = ta + (tb - ta) * t;
+                    error(at(time), interpolate(va, vb, t as f32)) <= tolerance
+                })
+            })
+    };
+
+    let mut kept = vec![0];
+    for i in 1..keys.len() - 1 {
+        let last_kept = *kept.last().unwrap();
+        if !reproduces(last_kept, i + 1) {
+            kept.push(i);
+        }
+    }
+    kept.push(keys.len() - 1);
+
+    kept.into_iter().map(|i| keys[i]).collect()
+}
+
+/// The largest value of `error` at the keys of `keys` and the times between them.
+fn track_error<T>(keys: &[(f64, T)], error: impl Fn(f64) -> Option<f32>) -> f32 {
+    let mut max = 0.0f32;
+    for (i, &(time, _)) in keys.iter().enumerate() {
+        max = max.max(error(time).unwrap_or(0.0));
+        if let Some(&(next, _)) = keys.get(i + 1) {
+            for sample in 1..=SAMPLES_PER_KEY {
+                let t = sample as f64 / (SAMPLES_PER_KEY + 1) as f64;
+                max = max.max(error(time + (next - time) * t).unwrap_or(0.0));
+            }
+        }
+    }
+    max
+}
+
+fn vector_distance(a: Vector3D, b: Vector3D) -> f32 {
+    (a - b).length()
+}
+
+/// The angle of the rotation between `a` and `b`, in degrees. Computed from the distance
+/// between the quaternions in `f64`, since `acos` of the dot product loses the small angles
+/// that tolerances are made of.
+fn rotation_angle(a: Quaternion, b: Quaternion) -> f32 {
+    let (a, b) = (a.normalize(), b.normalize());
+    // `q` and `-q` are the same rotation.
+    let sign = if a.dot(b) < 0.0 { -1.0 } else { 1.0 };
+    let a = [a.w as f64, a.x as f64, a.y as f64, a.z as f64];
+    let b = [b.w as f64, b.x as f64, b.y as f64, b.z as f64];
+    let (mut difference, mut sum) = (0.0, 0.0);
+    for i in 0..4 {
+        difference += (a[i] - sign * b[i]).powi(2);
+        sum += (a[i] + sign * b[i]).powi(2);
+    }
+
+    // This is half the angle between the quaternions on the unit sphere, which is half the
+    // angle of the rotation.
+    (4.0 * difference.sqrt().atan2(sum.sqrt())).to_degrees() as f32
+}
+
+fn num_keys(channel: &ChannelData) -> usize {
+    channel.position_keys.len() + channel.rotation_keys.len() + channel.scaling_keys.len()
+}
@@ -0,0 +1,232 @@
+//! Processing of owned animation data, e.g. to shrink animations for a game build.
+//!
+//! Each function works on an `AnimationData` from a `SceneSnapshot` and returns the number of
+//! channels and keys before and after, so that pipelines can log how much was saved.
+
+use crate::math::{Quaternion, Vector3D};
+
+use super::animation::{effective_fps, sample_keys, Interpolation};
+use super::snapshot::{AnimationData, ChannelData};
+
+/// The size of an animation before and after processing.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct KeyStatistics {
+    pub channels_before: usize,
+    pub channels_after: usize,
+    /// The total number of position, rotation and scaling keys.
+    pub keys_before: usize,
+    pub keys_after: usize,
+}
+
+impl KeyStatistics {
+    fn before(anim: &AnimationData) -> KeyStatistics {
+        KeyStatistics {
+            channels_before: anim.channels.len(),
+            keys_before: count_keys(anim),
+            ..Default::default()
+        }
+    }
+
+    fn after(self, anim: &AnimationData) -> KeyStatistics {
+        KeyStatistics {
+            channels_after: anim.channels.len(),
+            keys_after: count_keys(anim),
+            ..self
+        }
+    }
+
+    /// The number of keys that were removed. This is negative if processing added keys, as
+    /// `resample` can.
+    pub fn keys_removed(&self) -> isize {
+        self.keys_before as isize - self.keys_after as isize
+    }
+}
+
+/// Remove the position, rotation and scaling tracks of `anim` whose keys never deviate from
+/// the first key by more than `epsilon`, componentwise.
+///
+/// A constant track at the identity (no translation, no rotation or a scale of 1) is removed
+/// completely, since that's what a missing track means to most engines. Other constant tracks
+/// are reduced to their first key, so the value isn't lost. Channels left without any keys are
+/// removed.
+pub fn strip_constant_channels(anim: &mut AnimationData, epsilon: f32) -> KeyStatistics {
+    let stats = KeyStatistics::before(anim);
+    let vector_close = |a: Vector3D, b: Vector3D| {
+        (a.x - b.x).abs() <= epsilon && (a.y - b.y).abs() <= epsilon && (a.z - b.z).abs() <= epsilon
+    };
+    // `q` and `-q` are the same rotation.
+    let rotation_close = |a: Quaternion, b: Quaternion| {
+        let same = |b: Quaternion| {
+            (a.w - b.w).abs() <= epsilon
+                && (a.x - b.x).abs() <= epsilon
+                && (a.y - b.y).abs() <= epsilon
+                && (a.z - b.z).abs() <= epsilon
+        };
+        same(b) || same(Quaternion::new(-b.w, -b.x, -b.y, -b.z))
+    };
+
+    for channel in &mut anim.channels {
+        strip_track(
+            &mut channel.position_keys,
+            Vector3D::new(0.0, 0.0, 0.0),
+            vector_close,
+        );
+        strip_track(
+            &mut channel.rotation_keys,
+            Quaternion::identity(),
+            rotation_close,
+        );
+        strip_track(
+            &mut channel.scaling_keys,
+            Vector3D::new(1.0, 1.0, 1.0),
+            vector_close,
+        );
+    }
+    anim.channels.retain(|channel| num_keys(channel) > 0);
+
+    stats.after(anim)
+}
+
+/// Keep only the channels of `anim` whose node name matches `predicate`, e.g. to drop
+/// channels for bones that aren't in any mesh.
+pub fn retain_channels<F: FnMut(&str) -> bool>(
+    anim: &mut AnimationData,
+    mut predicate: F,
+) -> KeyStatistics {
+    let stats = KeyStatistics::before(anim);
+    anim.channels
+        .retain(|channel| predicate(&channel.node_name));
+    stats.after(anim)
+}
+
+/// Replace the keys of every track of `anim` with keys spaced uniformly at `fps` frames per
+/// second, sampled with `ChannelData::position_at` and friends. Each track keeps its
+/// first and last keys exactly, the last key being closer to the one before than the others
+/// if the track's length isn't a multiple of the frame time. The duration is unchanged.
+///
+/// The frame time is converted to ticks with `AnimationData::seconds_to_ticks`. Tracks with a
+/// single key are left as they are.
+///
+/// Panics if `fps` isn't positive.
+pub fn resample(anim: &mut AnimationData, fps: f64) -> KeyStatistics {
+    assert!(fps > 0.0, "the frame rate must be positive");
+
+    let stats = KeyStatistics::before(anim);
+    let step = anim.seconds_to_ticks(1.0 / fps);
+
+    for channel in &mut anim.channels {
+        let positions = frame_times(&channel.position_keys, step)
+            .into_iter()
+            .map(|time| (time, channel.position_at(time).unwrap()))
+            .collect();
+        let rotations = frame_times(&channel.rotation_keys, step)
+            .into_iter()
+            .map(|time| (time, channel.rotation_at(time).unwrap()))
+            .collect();
+        let scalings = frame_times(&channel.scaling_keys, step)
+            .into_iter()
+            .map(|time| (time, channel.scaling_at(time).unwrap()))
+            .collect();
+
+        channel.position_keys = positions;
+        channel.rotation_keys = rotations;
+        channel.scaling_keys = scalings;
+    }
+
+    stats.after(anim)
+}
+
+impl AnimationData {
+    /// The number of ticks per second to play the animation at, see `Animation::effective_fps`.
+    pub fn effective_fps(&self, default: Option<f64>) -> f64 {
+        effective_fps(self.ticks_per_second, default)
+    }
+
+    /// The duration in seconds, at `effective_fps(None)` ticks per second.
+    pub fn duration_seconds(&self) -> f64 {
+        self.ticks_to_seconds(self.duration)
+    }
+
+    /// Convert a time in ticks to seconds at `effective_fps(None)`.
+    pub fn ticks_to_seconds(&self, ticks: f64) -> f64 {
+        ticks / self.effective_fps(None)
+    }
+
+    /// Convert a time in seconds to ticks at `effective_fps(None)`, e.g. for sampling a
+    /// channel with `ChannelData::position_at`.
+    pub fn seconds_to_ticks(&self, seconds: f64) -> f64 {
+        seconds * self.effective_fps(None)
+    }
+}
+
+impl ChannelData {
+    /// The position at `time`, in ticks, interpolated linearly between keys. Times before
+    /// the first key or after the last give the value of that key. Returns `None` if there
+    /// are no position keys. Snapshots don't keep the interpolation of the keys, so unlike
+    /// `NodeAnim::position_at` this also interpolates `Interpolation::Step` keys.
+    pub fn position_at(&self, time: f64) -> Option<Vector3D> {
+        sample(&self.position_keys, time, Vector3D::lerp)
+    }
+
+    /// The rotation at `time`, in ticks, spherically interpolated between keys, see
+    /// `position_at`.
+    pub fn rotation_at(&self, time: f64) -> Option<Quaternion> {
+        sample(&self.rotation_keys, time, Quaternion::slerp)
+    }
+
+    /// The scale at `time`, in ticks, interpolated linearly between keys, see `position_at`.
+    pub fn scaling_at(&self, time: f64) -> Option<Vector3D> {
+        sample(&self.scaling_keys, time, Vector3D::lerp)
+    }
+}
+
+/// Snapshots don't keep the interpolation of the keys, so every key is interpolated.
+fn sample<T: Copy, F: Fn(T, T, f32) -> T>(
+    keys: &[(f64, T)],
+    time: f64,
+    interpolate: F,
+) -> Option<T> {
+    let key = |&(key_time, value): &(f64, T)| (key_time, value, Interpolation::Linear);
+    sample_keys(keys, time, key, interpolate)
+}
+
+/// The times of the resampled keys of a track, from its first key to its last.
+fn frame_times<T>(keys: &[(f64, T)], step: f64) -> Vec<f64> {
+    let (start, end) = match (keys.first(), keys.last()) {
+        (Some(first), Some(last)) if keys.len() > 1 => (first.0, last.0),
+        _ => return keys.iter().map(|&(time, _)| time).collect(),
+    };
+
+    // Computing each time from the start, rather than adding up steps, avoids accumulating
+    // rounding errors. The tolerance stops a frame landing a hair before the last key.
+    let mut times: Vec<f64> = (0..)
+        .map(|frame| start + frame as f64 * step)
+        .take_while(|&time| time < end - step * 1e-6)
+        .collect();
+    times.push(end);
+    times
+}
+
+fn strip_track<T: Copy, F: Fn(T, T) -> bool>(keys: &mut Vec<(f64, T)>, identity: T, close: F) {
+    let first = match keys.first() {
+        Some(&(_, first)) => first,
+        None => return,
+    };
+    if !keys.iter().all(|&(_, value)| close(first, value)) {
+        return;
+    }
+
+    if close(first, identity) {
+        keys.clear();
+    } else {
+        keys.truncate(1);
+    }
+}
+
+fn num_keys(channel: &ChannelData) -> usize {
+    channel.position_keys.len() + channel.rotation_keys.len() + channel.scaling_keys.len()
+}
+
+fn count_keys(anim: &AnimationData) -> usize {
+    anim.channels.iter().map(num_keys).sum()
+}
@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::math::Matrix4x4;
+
+use super::identity::NodeId;
+use super::node::Node;
+use super::scene::Scene;
+
+/// A node in a `NodeArena`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NodeRecord {
+    /// The name of the node, with invalid UTF-8 replaced.
+    pub name: String,
+    /// The transform relative to the parent, see `Node::transform`.
+    #[cfg_attr(feature = "serde", serde(with = "crate::math::matrix4::matrix_rows"))]
+    pub local_transform: Matrix4x4,
+    /// The index of the parent, or `None` for the root node.
+    pub parent: Option<u32>,
+    /// The indices of the children, which are always contiguous. Empty for leaves.
+    pub children: Range<u32>,
+    /// Indices into `Scene::meshes`, see `Node::meshes`.
+    pub mesh_indices: Vec<u32>,
+}
+
+/// The node hierarchy of a scene as a flat list with integer links, as returned by
+/// `Scene::node_arena`. Unlike `Node`s, it doesn't borrow the scene, so it can be kept,
+/// sent between threads and serialized.
+///
+/// The root node is at index 0. The children of each node are stored next to each other, and
+/// the blocks of children are laid out depth-first: the children of the root come first, then
+/// the children of its first child, then those of that child's first child, and so on.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NodeArena {
+    records: Vec<NodeRecord>,
+    /// The index of each node by `NodeId::as_usize`.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    addresses: HashMap<usize, u32>,
+}
+
+impl NodeArena {
+    /// Every node, see `NodeArena` for the order.
+    pub fn records(&self) -> &[NodeRecord] {
+        &self.records
+    }
+
+    /// The number of nodes.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Whether there are no nodes, i.e. the scene had no root node.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// The node at `index`.
+    pub fn get(&self, index: u32) -> Option<&NodeRecord> {
+        self.records.get(index as usize)
+    }
+
+    /// The children of the node at `index`, or an empty slice if there's no such node.
+    pub fn children(&self, index: u32) -> &[NodeRecord] {
+        match self.get(index) {
+            Some(record) => {
+                &self.records[record.children.start as usize..record.children.end as usize]
+            }
+            None => &[],
+        }
+    }
+
+    /// The index of `node`, which is compared by address. Returns `None` for nodes of other
+    /// scenes and for arenas that were deserialized rather than built by `Scene::node_arena`.
+    pub fn index_of(&self, node: &Node) -> Option<u32> {
+        self.index_of_id(node.id())
+    }
+
+    /// The index of the node with the id `id`, like `index_of`, for code that keeps `NodeId`s
+    /// rather than borrowing the scene.
+    pub fn index_of_id(&self, id: NodeId) -> Option<u32> {
+        self.addresses.get(&id.as_usize()).copied()
+    }
+
+    /// The world transform of every node, indexed like `records`, see `Node::world_transform`.
+    /// Parents come before their children, so this takes a single pass.
+    pub fn world_transforms(&self) -> Vec<Matrix4x4> {
+        let mut world: Vec<Matrix4x4> = Vec::with_capacity(self.records.len());
+        for record in &self.records {
+            let transform = match record.parent {
+                Some(parent) => world[parent as usize] * record.local_transform,
+                None => record.local_transform,
+            };
+            world.push(transform);
+        }
+        world
+    }
+
+    /// The index of the first node named `name`, in arena order.
+    pub fn by_name(&self, name: &str) -> Option<u32> {
+        self.records
+            .iter()
+            .position(|record| record.name == name)
+            .map(|index| index as u32)
+    }
+}
+
+impl Scene<'_> {
+    /// Copy the node hierarchy into a `NodeArena` in a single traversal. The arena is empty if
+    /// the scene has no root node.
+    pub fn node_arena(&self) -> NodeArena {
+        let mut arena = NodeArena::default();
+        let root = match self.root_node() {
+            Some(root) => root,
+            None => return arena,
+        };
+
+        let mut nodes = Vec::new();
+        push_record(&mut arena, &mut nodes, root, None);
+
+        // Allocate all children of a node at once, then continue with its first child.
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            let node = nodes[index as usize];
+            let start = arena.records.len() as u32;
+            for child in node.children() {
+                push_record(&mut arena, &mut nodes, child, Some(index));
+            }
+            let end = arena.records.len() as u32;
+            arena.records[index as usize].children = start..end;
+            stack.extend((start..end).rev());
+        }
+
+        arena
+    }
+}
+
+fn push_record<'a>(
+    arena: &mut NodeArena,
+    nodes: &mut Vec<&'a Node>,
+    node: &'a Node,
+    parent: Option<u32>,
+) {
+    let index = arena.records.len() as u32;
+    arena.records.push(NodeRecord {
+        name: node.name_cstr().to_string_lossy().into_owned(),
+        local_transform: node.transform(),
+        parent,
+        children: 0..0,
+        mesh_indices: node.meshes().to_vec(),
+    });
+    arena.addresses.insert(node.id().as_usize(), index);
+    nodes.push(node);
+}
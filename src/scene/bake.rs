@@ -0,0 +1,630 @@
+//! Baking of per-vertex data from the geometry of a whole scene, such as ambient occlusion,
+//! and of node transforms into the vertices of a `SceneSnapshot`.
+//!
+//! `vertex_ao` places every mesh in world space using the node graph, builds a bounding volume
+//! hierarchy over the triangles, and casts rays from each vertex into the hemisphere around its
+//! normal. With the `rayon` feature the vertices of each mesh are processed in parallel, which
+//! doesn't change the result.
+//!
+//! `pretransform_subtree` is a selective `Importer::pre_transform_vertices`: it bakes the
+//! transforms of one subtree, such as the static environment, and leaves the hierarchy of the
+//! rest of the scene, such as animated characters, as it is.
+
+use std::cmp::Ordering;
+use std::fmt;
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::math::{transform_normals, transform_points, Matrix4x4, Vector3D};
+use crate::Error;
+
+use super::mesh::Mesh;
+use super::scene::Scene;
+use super::snapshot::{MeshData, NodeData, SceneSnapshot};
+
+/// Which meshes block the rays cast by `vertex_ao`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Occluders {
+    /// Every mesh in the scene.
+    All,
+    /// Only the meshes with these indices. Occlusion is still computed for the vertices of
+    /// every mesh.
+    Meshes(Vec<u32>),
+}
+
+/// Options for `vertex_ao`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AoOptions {
+    /// The number of rays cast from each vertex. Default: 64
+    pub rays: u32,
+    /// Rays that travel further than this in world space without hitting anything aren't
+    /// occluded. Default: `f32::INFINITY`
+    pub max_distance: f32,
+    /// Default: `Occluders::All`
+    pub occluders: Occluders,
+    /// The seed for the ray directions. The same seed always gives the same result.
+    /// Default: 0
+    pub seed: u64,
+}
+
+impl Default for AoOptions {
+    fn default() -> Self {
+        AoOptions {
+            rays: 64,
+            max_distance: f32::INFINITY,
+            occluders: Occluders::All,
+            seed: 0,
+        }
+    }
+}
+
+/// The ambient occlusion of every vertex of every mesh in `scene`, from 0 for a vertex that
+/// sees only sky to 1 for one that is completely enclosed. The result has one `Vec` per mesh,
+/// with one value per vertex.
+///
+/// Meshes are placed in world space by the world transforms of the nodes that reference them,
+/// and occlude once for every such node. A mesh's own vertices are placed by the first of these
+/// nodes in the order of `Node::descendants`. Meshes that no node references are used as they
+/// are, in their own space.
+///
+/// The rays are cosine-distributed around the vertex normal, and start slightly above the
+/// vertex to avoid hitting its own triangles. Meshes without normals use the area-weighted
+/// normals of their faces. Vertices without a usable normal get 0. Faces with more than three
+/// indices are treated as triangle fans, and points and lines don't occlude.
+pub fn vertex_ao(scene: &Scene, opts: AoOptions) -> Vec<Vec<f32>> {
+    let placements = placements(scene);
+
+    let mut triangles = Vec::new();
+    for (index, mesh) in scene.meshes().enumerate() {
+        let occludes = match &opts.occluders {
+            Occluders::All => true,
+            Occluders::Meshes(meshes) => meshes.contains(&(index as u32)),
+        };
+        if occludes {
+            for world in &placements[index] {
+                add_triangles(mesh, &mesh.positions_transformed(world), &mut triangles);
+            }
+        }
+    }
+    let bvh = Bvh::new(triangles);
+    let bias = bvh.extent() * 1e-4;
+
+    scene
+        .meshes()
+        .enumerate()
+        .map(|(index, mesh)| {
+            let world = placements[index][0];
+            let positions = mesh.positions_transformed(&world);
+            let normals = if mesh.mNormals.is_null() {
+                face_normals(mesh, &positions)
+            } else {
+                mesh.normals_transformed(&world)
+            };
+
+            map_vertices(positions.len(), |vertex| {
+                let seed = mix(opts.seed ^ mix(((index as u64) << 32) | vertex as u64));
+                bvh.occlusion(positions[vertex], normals[vertex], bias, seed, &opts)
+            })
+        })
+        .collect()
+}
+
+#[cfg(feature = "rayon")]
+fn map_vertices<F: Fn(usize) -> f32 + Send + Sync>(count: usize, f: F) -> Vec<f32> {
+    (0..count).into_par_iter().map(f).collect()
+}
+
+#[cfg(not(feature = "rayon"))]
+fn map_vertices<F: Fn(usize) -> f32>(count: usize, f: F) -> Vec<f32> {
+    (0..count).map(f).collect()
+}
+
+/// The world transforms of the nodes that reference each mesh, or the identity for meshes
+/// without a node.
+pub(super) fn placements(scene: &Scene) -> Vec<Vec<Matrix4x4>> {
+    let mut placements = vec![Vec::new(); scene.num_meshes() as usize];
+    if let Some(root) = scene.root_node() {
+        for node in root.descendants() {
+            let world = node.world_transform();
+            for &mesh in node.meshes() {
+                if let Some(placement) = placements.get_mut(mesh as usize) {
+                    placement.push(world);
+                }
+            }
+        }
+    }
+    for placement in &mut placements {
+        if placement.is_empty() {
+            placement.push(Matrix4x4::identity());
+        }
+    }
+    placements
+}
+
+/// The triangles of the faces of `mesh`, with triangle fans for polygons. Faces with indices
+/// out of range or positions that aren't finite are left out.
+fn triangles<'a>(
+    mesh: &'a Mesh,
+    positions: &'a [Vector3D],
+) -> impl Iterator<Item = [Vector3D; 3]> + 'a {
+    mesh.faces()
+        .filter(move |face| {
+            face.indices().len() >= 3
+                && face.indices().iter().all(|&i| {
+                    positions.get(i as usize).map_or(false, |p| {
+                        p.x.is_finite() && p.y.is_finite() && p.z.is_finite()
+                    })
+                })
+        })
+        .flat_map(move |face| {
+            let indices = face.indices();
+            let first = positions[indices[0] as usize];
+            indices[1..].windows(2).map(move |pair| {
+                [
+                    first,
+                    positions[pair[0] as usize],
+                    positions[pair[1] as usize],
+                ]
+            })
+        })
+}
+
+fn add_triangles(mesh: &Mesh, positions: &[Vector3D], out: &mut Vec<Triangle>) {
+    out.extend(triangles(mesh, positions).map(|[a, b, c]| Triangle {
+        a,
+        edge1: b - a,
+        edge2: c - a,
+    }));
+}
+
+/// Per-vertex normals from the sum of the (area-scaled) normals of the faces around each vertex.
+fn face_normals(mesh: &Mesh, positions: &[Vector3D]) -> Vec<Vector3D> {
+    let mut normals = vec![Vector3D::new(0.0, 0.0, 0.0); positions.len()];
+    for face in mesh.faces() {
+        let indices = face.indices();
+        if indices.len() < 3 || indices.iter().any(|&i| i as usize >= positions.len()) {
+            continue;
+        }
+        let a = positions[indices[0] as usize];
+        for pair in indices[1..].windows(2) {
+            let (b, c) = (positions[pair[0] as usize], positions[pair[1] as usize]);
+            let normal = (b - a).cross(c - a);
+            for &v in &[indices[0], pair[0], pair[1]] {
+                normals[v as usize] = normals[v as usize] + normal;
+            }
+        }
+    }
+    normals
+}
+
+/// A triangle stored for ray intersection.
+struct Triangle {
+    a: Vector3D,
+    edge1: Vector3D,
+    edge2: Vector3D,
+}
+
+impl Triangle {
+    fn centroid(&self, axis: usize) -> f32 {
+        component(self.a, axis) + (component(self.edge1, axis) + component(self.edge2, axis)) / 3.0
+    }
+
+    /// Whether the ray from `origin` along `direction` hits the triangle, from either side,
+    /// closer than `max_distance`.
+    fn hit(&self, origin: Vector3D, direction: Vector3D, max_distance: f32) -> bool {
+        let p = direction.cross(self.edge2);
+        let det = self.edge1.dot(p);
+        if det.abs() <= f32::MIN_POSITIVE {
+            return false;
+        }
+        let inv = 1.0 / det;
+        let s = origin - self.a;
+        let u = s.dot(p) * inv;
+        if !(0.0..=1.0).contains(&u) {
+            return false;
+        }
+        let q = s.cross(self.edge1);
+        let v = direction.dot(q) * inv;
+        if v < 0.0 || u + v > 1.0 {
+            return false;
+        }
+        let t = self.edge2.dot(q) * inv;
+        t > 0.0 && t < max_distance
+    }
+}
+
+/// The maximum number of triangles in a leaf of a `Bvh`.
+const LEAF_SIZE: usize = 4;
+
+/// A node of a `Bvh`. Leaves have `count` triangles starting at `start`. Other nodes have
+/// their first child right after them, and their second child at `start`.
+struct BvhNode {
+    min: Vector3D,
+    max: Vector3D,
+    start: usize,
+    count: usize,
+}
+
+/// A bounding volume hierarchy over triangles, split at the median along the longest axis.
+struct Bvh {
+    nodes: Vec<BvhNode>,
+    triangles: Vec<Triangle>,
+}
+
+impl Bvh {
+    fn new(triangles: Vec<Triangle>) -> Self {
+        let mut bvh = Bvh {
+            nodes: Vec::new(),
+            triangles,
+        };
+        if !bvh.triangles.is_empty() {
+            bvh.build(0, bvh.triangles.len());
+        }
+        bvh
+    }
+
+    fn build(&mut self, start: usize, end: usize) -> usize {
+        let triangles = &mut self.triangles[start..end];
+        let (mut min, mut max) = (triangles[0].a, triangles[0].a);
+        let (mut centroid_min, mut centroid_max) = ([f32::INFINITY; 3], [f32::NEG_INFINITY; 3]);
+        for triangle in triangles.iter() {
+            for &p in &[
+                triangle.a,
+                triangle.a + triangle.edge1,
+                triangle.a + triangle.edge2,
+            ] {
+                min = Vector3D::new(min.x.min(p.x), min.y.min(p.y), min.z.min(p.z));
+                max = Vector3D::new(max.x.max(p.x), max.y.max(p.y), max.z.max(p.z));
+            }
+            for axis in 0..3 {
+                let centroid = triangle.centroid(axis);
+                centroid_min[axis] = centroid_min[axis].min(centroid);
+                centroid_max[axis] = centroid_max[axis].max(centroid);
+            }
+        }
+
+        let index = self.nodes.len();
+        self.nodes.push(BvhNode {
+            min,
+            max,
+            start,
+            count: end - start,
+        });
+        if end - start <= LEAF_SIZE {
+            return index;
+        }
+
+        let axis = (0..3)
+            .max_by(|&a, &b| {
+                let extent = |axis: usize| centroid_max[axis] - centroid_min[axis];
+                extent(a).partial_cmp(&extent(b)).unwrap_or(Ordering::Equal)
+            })
+            .unwrap_or(0);
+        triangles.sort_unstable_by(|a, b| {
+            a.centroid(axis)
+                .partial_cmp(&b.centroid(axis))
+                .unwrap_or(Ordering::Equal)
+        });
+
+        let middle = (start + end) / 2;
+        self.build(start, middle);
+        let second = self.build(middle, end);
+        self.nodes[index].start = second;
+        self.nodes[index].count = 0;
+        index
+    }
+
+    /// The length of the diagonal of the bounds of all triangles.
+    fn extent(&self) -> f32 {
+        self.nodes
+            .first()
+            .map_or(0.0, |root| (root.max - root.min).length())
+    }
+
+    /// Whether the ray from `origin` along `direction` hits any triangle closer than
+    /// `max_distance`.
+    fn hit(&self, origin: Vector3D, direction: Vector3D, max_distance: f32) -> bool {
+        if self.nodes.is_empty() {
+            return false;
+        }
+        let inverse = Vector3D::new(1.0 / direction.x, 1.0 / direction.y, 1.0 / direction.z);
+
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if !slab_test(node, origin, inverse, max_distance) {
+                continue;
+            }
+            if node.count > 0 {
+                let triangles = &self.triangles[node.start..node.start + node.count];
+                if triangles
+                    .iter()
+                    .any(|triangle| triangle.hit(origin, direction, max_distance))
+                {
+                    return true;
+                }
+            } else {
+                stack.push(node.start);
+                stack.push(index + 1);
+            }
+        }
+        false
+    }
+
+    /// The fraction of `opts.rays` cosine-distributed rays around `normal` that hit something.
+    fn occlusion(
+        &self,
+        position: Vector3D,
+        normal: Vector3D,
+        bias: f32,
+        seed: u64,
+        opts: &AoOptions,
+    ) -> f32 {
+        let normal = normal.normalize();
+        let length = normal.length();
+        if opts.rays == 0 || !length.is_finite() || length < 0.5 {
+            return 0.0;
+        }
+        let origin = position + normal * bias;
+        let (tangent, bitangent) = basis(normal);
+
+        let mut state = seed;
+        let mut hits = 0;
+        for _ in 0..opts.rays {
+            let (u1, u2) = (random(&mut state), random(&mut state));
+            let (r, phi) = (u1.sqrt(), 2.0 * std::f32::consts::PI * u2);
+            let direction = tangent * (r * phi.cos())
+                + bitangent * (r * phi.sin())
+                + normal * (1.0 - u1).max(0.0).sqrt();
+            if self.hit(origin, direction, opts.max_distance) {
+                hits += 1;
+            }
+        }
+        hits as f32 / opts.rays as f32
+    }
+}
+
+/// Whether the ray from `origin` with the inverse direction `inverse` enters the bounds of
+/// `node` closer than `max_distance`.
+fn slab_test(node: &BvhNode, origin: Vector3D, inverse: Vector3D, max_distance: f32) -> bool {
+    let (mut near, mut far) = (0.0f32, max_distance);
+    for axis in 0..3 {
+        let (o, inv) = (component(origin, axis), component(inverse, axis));
+        let t0 = (component(node.min, axis) - o) * inv;
+        let t1 = (component(node.max, axis) - o) * inv;
+        near = near.max(t0.min(t1));
+        far = far.min(t0.max(t1));
+    }
+    near <= far
+}
+
+fn component(v: Vector3D, axis: usize) -> f32 {
+    match axis {
+        0 => v.x,
+        1 => v.y,
+        _ => v.z,
+    }
+}
+
+/// Two unit vectors that form an orthonormal basis with the unit vector `n`, after Duff et
+/// al., "Building an Orthonormal Basis, Revisited".
+fn basis(n: Vector3D) -> (Vector3D, Vector3D) {
+    let sign = 1f32.copysign(n.z);
+    let a = -1.0 / (sign + n.z);
+    let b = n.x * n.y * a;
+    (
+        Vector3D::new(1.0 + sign * n.x * n.x * a, sign * b, -sign * n.x),
+        Vector3D::new(b, sign + n.y * n.y * a, -n.y),
+    )
+}
+
+/// The SplitMix64 finalizer, which scrambles `x` so that nearby seeds give unrelated streams.
+fn mix(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// The next number from the SplitMix64 stream at `state`, uniform in `0..1`.
+fn random(state: &mut u64) -> f32 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    (mix(*state) >> 40) as f32 / (1u64 << 24) as f32
+}
+
+/// The node whose subtree `pretransform_subtree` bakes.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum NodeSelector {
+    /// The first node with this name, in the order of `NodeArena`.
+    Name(String),
+    /// The node at this index in the order of `NodeArena`, so an index from
+    /// `Scene::node_arena` selects the same node in a snapshot of that scene.
+    Index(u32),
+}
+
+impl fmt::Display for NodeSelector {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NodeSelector::Name(name) => write!(f, "node named `{}`", name),
+            NodeSelector::Index(index) => write!(f, "node at index {}", index),
+        }
+    }
+}
+
+/// What `pretransform_subtree` changed.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PretransformReport {
+    /// The meshes whose vertices were transformed, copies included, in the order they were
+    /// baked.
+    pub baked: Vec<u32>,
+    /// `(original, copy)` for each mesh that was copied because it's also referenced from
+    /// outside the subtree, or more than once within it. The subtree now refers to the copy,
+    /// which is appended to `SceneSnapshot::meshes`.
+    pub duplicated: Vec<(u32, u32)>,
+    /// The number of nodes removed by collapsing the subtree, which is 0 with `keep_hierarchy`.
+    pub removed_nodes: u32,
+}
+
+/// Bake the transforms of the node that `node` selects and of all of its descendants into the
+/// meshes they reference, so that the subtree looks the same with identity transforms.
+///
+/// Each mesh reference beneath the node is transformed by the product of the transforms from
+/// the node down to the referencing node. The transforms of the node's ancestors aren't baked,
+/// so the world-space bounds of the scene stay the same. Positions are transformed as points,
+/// normals by the normal matrix, and tangents and bitangents as directions. Bone offsets are
+/// adjusted so that skinning gives the same result, and the winding of faces is reversed if
+/// the transform mirrors. A mesh that's also referenced from outside the subtree, or by more
+/// than one node within it, is copied for all but its last reference, so that the other
+/// references still see the original. References with an identity transform are left alone.
+///
+/// With `keep_hierarchy` the transforms of the nodes in the subtree are reset to the identity.
+/// Otherwise the subtree is collapsed into the selected node, which gets the meshes of all of
+/// its descendants, parents before children, and loses its children. Either way, animation
+/// channels of the baked nodes would move them again, and bones, cameras and lights that
+/// refer to collapsed nodes by name lose them, so this is meant for static parts of a scene.
+///
+/// Returns `Error::MissingData`, without changing anything, if the snapshot has no root node
+/// or `node` doesn't select a node.
+pub fn pretransform_subtree(
+    snapshot: &mut SceneSnapshot,
+    node: NodeSelector,
+    keep_hierarchy: bool,
+) -> Result<PretransformReport, Error> {
+    let SceneSnapshot { root, meshes, .. } = snapshot;
+    let root = root.as_mut().ok_or_else(|| Error::MissingData {
+        what: "root node".to_owned(),
+    })?;
+    let path = select(root, &node).ok_or_else(|| Error::MissingData {
+        what: node.to_string(),
+    })?;
+
+    // Count every reference to each mesh, so the last one can be baked in place.
+    let mut remaining = vec![0u32; meshes.len()];
+    root.visit_mut(&mut |node| {
+        for &mesh in &node.meshes {
+            if let Some(count) = remaining.get_mut(mesh as usize) {
+                *count += 1;
+            }
+        }
+    });
+
+    let subtree = node_at_mut(root, &path);
+    let mut report = PretransformReport::default();
+    bake_node(
+        subtree,
+        Matrix4x4::identity(),
+        meshes,
+        &mut remaining,
+        &mut report,
+    );
+
+    if keep_hierarchy {
+        subtree.visit_mut(&mut |node| node.transform = Matrix4x4::identity());
+    } else {
+        let mut collapsed = Vec::new();
+        let mut count = 0;
+        subtree.visit_mut(&mut |node| {
+            collapsed.extend_from_slice(&node.meshes);
+            count += 1;
+        });
+        subtree.meshes = collapsed;
+        subtree.children.clear();
+        subtree.transform = Matrix4x4::identity();
+        report.removed_nodes = count - 1;
+    }
+
+    Ok(report)
+}
+
+/// The path of child indices from the root to the node that `selector` selects.
+fn select(root: &NodeData, selector: &NodeSelector) -> Option<Vec<usize>> {
+    // The paths of all nodes in arena order, laid out like `Scene::node_arena` does.
+    let mut paths = vec![Vec::new()];
+    let mut stack = vec![0];
+    while let Some(index) = stack.pop() {
+        let start = paths.len();
+        let num_children = node_at(root, &paths[index]).children.len();
+        for child in 0..num_children {
+            let mut path = paths[index].clone();
+            path.push(child);
+            paths.push(path);
+        }
+        stack.extend((start..paths.len()).rev());
+    }
+
+    match selector {
+        NodeSelector::Index(index) => paths.into_iter().nth(*index as usize),
+        NodeSelector::Name(name) => paths
+            .into_iter()
+            .find(|path| node_at(root, path).name == *name),
+    }
+}
+
+fn node_at<'a>(root: &'a NodeData, path: &[usize]) -> &'a NodeData {
+    path.iter().fold(root, |node, &child| &node.children[child])
+}
+
+fn node_at_mut<'a>(root: &'a mut NodeData, path: &[usize]) -> &'a mut NodeData {
+    path.iter()
+        .fold(root, |node, &child| &mut node.children[child])
+}
+
+/// Bake `parent * node.transform` into the meshes of `node`, and likewise for its children.
+fn bake_node(
+    node: &mut NodeData,
+    parent: Matrix4x4,
+    meshes: &mut Vec<MeshData>,
+    remaining: &mut [u32],
+    report: &mut PretransformReport,
+) {
+    let transform = parent * node.transform;
+    if transform != Matrix4x4::identity() {
+        for mesh in &mut node.meshes {
+            let count = match remaining.get_mut(*mesh as usize) {
+                Some(count) => count,
+                None => continue,
+            };
+            *count -= 1;
+            if *count > 0 {
+                let copy = meshes.len() as u32;
+                meshes.push(meshes[*mesh as usize].clone());
+                report.duplicated.push((*mesh, copy));
+                *mesh = copy;
+            }
+            bake_mesh(&mut meshes[*mesh as usize], &transform);
+            report.baked.push(*mesh);
+        }
+    }
+
+    for child in &mut node.children {
+        bake_node(child, transform, meshes, remaining, report);
+    }
+}
+
+fn bake_mesh(mesh: &mut MeshData, transform: &Matrix4x4) {
+    let mut out = Vec::new();
+    transform_points(transform, &mesh.positions, &mut out);
+    mesh.positions = out;
+    let mut out = Vec::new();
+    transform_normals(transform, &mesh.normals, &mut out);
+    mesh.normals = out;
+    for v in mesh.tangents.iter_mut().chain(&mut mesh.bitangents) {
+        *v = transform.transform_vector(*v).normalize();
+    }
+
+    // Offsets map mesh space to bone space, so the baked mesh needs to be moved back first.
+    if let Some(inverse) = transform.affine_inverse() {
+        for bone in &mut mesh.bones {
+            bone.offset_matrix = bone.offset_matrix * inverse;
+        }
+    }
+
+    let rows = transform.rows();
+    let row = |i: usize| Vector3D::new(rows[i][0], rows[i][1], rows[i][2]);
+    if row(0).dot(row(1).cross(row(2))) < 0.0 {
+        for face in &mut mesh.faces {
+            face.reverse();
+        }
+    }
+}
@@ -0,0 +1,1475 @@
+//! A binary format for `SceneSnapshot`s that's fast to load, see `write` and `read`.
+//!
+//! Importing a scene with Assimp parses a text or binary format made for exchange, and runs
+//! the post-process steps. A program that loads the same scenes often can do that once,
+//! offline, and ship the snapshots in this format, which is mostly arrays that can be copied
+//! or used in place. It's also a cache for `Scene::snapshot`.
+//!
+//! # Layout
+//!
+//! All numbers are little-endian, and floats are IEEE 754, so the files are the same on every
+//! platform and every value round-trips exactly. Offsets are in bytes.
+//!
+//! The file starts with a 32-byte header:
+//!
+//! | Offset | Type      | Contents                                               |
+//! |--------|-----------|--------------------------------------------------------|
+//! | 0      | `[u8; 8]` | `MAGIC`, `AISNAP\r\n`                                  |
+//! | 8      | `u16`     | The major version, `MAJOR_VERSION`                     |
+//! | 10     | `u16`     | The minor version, `MINOR_VERSION`                     |
+//! | 12     | `u32`     | The number of chunks                                   |
+//! | 16     | `u64`     | The size of the file                                   |
+//! | 24     | `u64`     | Zero                                                   |
+//!
+//! The chunk table follows, with a 24-byte entry for each chunk: a 4-byte ASCII tag, a zero
+//! `u32`, and the offset and size of the chunk from the start of the file as `u64`s. Every
+//! chunk starts at a multiple of 16 bytes, and the gaps are filled with zeros.
+//!
+//! Two chunks hold the payloads the others refer to:
+//!
+//! - `STRS`, strings: UTF-8 without terminators, referred to by a string reference, a `u32`
+//!   offset from the start of the chunk and a `u32` length.
+//! - `DATA`, buffers: arrays of numbers, referred to by a buffer reference, a `u64` offset from
+//!   the start of the chunk and a `u64` length. Every buffer starts at a multiple of 16 bytes
+//!   from the start of the chunk, and so of the file.
+//!
+//! The other chunks are tables of fixed-size records. A table starts with a 16-byte header,
+//! the number of records and the size of each as `u32`s and a zero `u64`, and the records
+//! follow. Ranges of other tables are a `u32` index of the first record and a `u32` count.
+//!
+//! | Tag    | Record                                                                        |
+//! |--------|-------------------------------------------------------------------------------|
+//! | `NODE` | Name, `u32` parent or `u32::MAX` for the root, zero `u32`, the transform as 16 `f32`s by rows, buffer of `u32` mesh indices |
+//! | `MESH` | Name, `u32`s material index, vertex count, face count, face size or 0 if the faces have different sizes, number of color sets, number of UV channels, range of `ATTR`, range of `BONE`; buffers of positions, normals, tangents and bitangents as 3 `f32`s each, the indices of all faces together as `u32`s, the size of each face as `u32`s if the face size is 0 |
+//! | `ATTR` | `u32` kind, 0 for a color set and 1 for a UV channel, `u32` index of the set, buffer of colors as 4 `f32`s or UVs as 3 `f32`s |
+//! | `BONE` | Name, buffer of `(u32 vertex, f32 weight)` pairs, the offset matrix as 16 `f32`s |
+//! | `MATL` | Name, range of `PROP`                                                         |
+//! | `PROP` | Key, `u32` semantic, `u32` index, `u32` type, 0 for a string, 1 for `u32`s and 2 for `f32`s, zero `u32`, buffer of the value |
+//! | `TEXR` | File name, format hint, `u32` width, `u32` height, `u32` 1 if the data is there, zero `u32`, buffer of the data |
+//! | `ANIM` | Name, `f64` duration, `f64` ticks per second, range of `CHAN`                   |
+//! | `CHAN` | Node name, then the position, rotation and scaling tracks                     |
+//! | `CAMR` | Name, position, up and look-at as 3 `f32`s each, `f32`s horizontal field of view, near and far clip planes and aspect ratio, 4 zero bytes |
+//! | `LGHT` | Name, `u32` light type, position, direction, up and attenuation as 3 `f32`s each, diffuse, specular and ambient colors as 3 `f32`s each, `f32`s inner and outer cone angle, width and height |
+//!
+//! Nodes are in depth-first order, so the root comes first and each node after its parent.
+//!
+//! Materials are lists of properties, like Assimp's. Each texture is a `$tex.file` property
+//! with the path, whose semantic is the `MaterialComponentType` and index the index within it,
+//! followed by properties with the same semantic and index: `$tex.target`, 2 `u32`s, 0 for an
+//! external file, 1 for an embedded texture and 2 for a missing one, and the index of the
+//! embedded texture; `$tex.uvwsrc`, the UV channel as a `u32`; and `$tex.uvtrafo`, if the
+//! texture has a UV transform, its translation, scaling and rotation as 5 `f32`s.
+//!
+//! A track is 56 bytes: a `u32` kind, a `u32` key count, an `f64` start time, an `f64` time
+//! step, a buffer of `f64` times and a buffer of values, 3 `f32`s for positions and scalings
+//! and 4 for rotations, `w` first. Keyed tracks, kind 0, store the time of each key. Baked
+//! tracks, kind 1, have evenly spaced keys, at the start time plus a multiple of the time step,
+//! and no times.
+//!
+//! # Versions
+//!
+//! Files of a newer minor version can add chunks, properties and fields at the end of
+//! records, which `read` skips, so it reads them as this version. A new major version means a
+//! change that older readers can't skip, and `read` refuses it with
+//! `BinaryError::UnsupportedVersion`.
+//!
+//! # Validation
+//!
+//! `read` checks every offset, length and count against the file before reading through it,
+//! and every count against the data it needs, so a file that's truncated or corrupted gives
+//! `BinaryError::Corrupt` instead of a panic, an out of bounds read or a huge allocation. The
+//! indices that refer to other parts of the scene, such as the face indices or
+//! `MeshData::material_index`, are read as they are, like `Scene::snapshot` copies them.
+
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Read, Write};
+
+use crate::math::{Color3D, Color4D, Matrix4x4, Quaternion, Vector2D, Vector3D};
+
+use super::light::LightType;
+use super::material::{MaterialComponentType, UvTransform};
+use super::snapshot::{
+    AnimationData, BoneData, CameraData, ChannelData, EmbeddedTextureData, LightData, MaterialData,
+    MaterialTextureData, MeshData, NodeData, SceneSnapshot, TextureTarget,
+};
+
+/// The first 8 bytes of every file. The line break catches files mangled by a text-mode
+/// transfer, like PNG's.
+pub const MAGIC: [u8; 8] = *b"AISNAP\r\n";
+/// The major version `write` writes, and the only one `read` reads.
+pub const MAJOR_VERSION: u16 = 1;
+/// The minor version `write` writes. `read` reads every minor version.
+pub const MINOR_VERSION: u16 = 0;
+
+/// The deepest hierarchy `write` and `read` accept, with the root at depth 0. `NodeData` is
+/// recursive, so deeper hierarchies could overflow the stack when they're dropped.
+pub const MAX_NODE_DEPTH: usize = 1024;
+
+/// The most color sets or UV channels a mesh can have. Assimp has 8 of each.
+const MAX_SETS: u32 = 64;
+
+const HEADER_SIZE: usize = 32;
+const CHUNK_ENTRY_SIZE: usize = 24;
+const TABLE_HEADER_SIZE: usize = 16;
+const ALIGNMENT: usize = 16;
+const NO_PARENT: u32 = u32::MAX;
+
+/// What went wrong in `write` or `read`.
+#[derive(Debug)]
+pub enum BinaryError {
+    /// Reading or writing the stream failed.
+    Io(io::Error),
+    /// The data doesn't start with `MAGIC`, so it isn't a snapshot.
+    NotSnapshot,
+    /// The file is of a major version other than `MAJOR_VERSION`.
+    UnsupportedVersion { major: u16, minor: u16 },
+    /// The file is truncated or corrupted. `what` is the value that's wrong, and `offset` where
+    /// it was read, in bytes from the start of the file.
+    Corrupt { what: &'static str, offset: usize },
+    /// The snapshot has more of something than the format can hold, e.g. more than
+    /// `u32::MAX` vertices in a mesh or a hierarchy deeper than `MAX_NODE_DEPTH`.
+    TooLarge { what: &'static str, len: usize },
+}
+
+impl fmt::Display for BinaryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BinaryError::Io(err) => write!(f, "{}", err),
+            BinaryError::NotSnapshot => write!(f, "the data isn't a binary scene snapshot"),
+            BinaryError::UnsupportedVersion { major, minor } => write!(
+                f,
+                "binary snapshot version {}.{} isn't supported, only {}.x",
+                major, minor, MAJOR_VERSION
+            ),
+            BinaryError::Corrupt { what, offset } => {
+                write!(
+                    f,
+                    "the binary snapshot is corrupt: bad {} at {}",
+                    what, offset
+                )
+            }
+            BinaryError::TooLarge { what, len } => {
+                write!(f, "{} {} are too many for a binary snapshot", len, what)
+            }
+        }
+    }
+}
+
+impl Error for BinaryError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            BinaryError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for BinaryError {
+    fn from(err: io::Error) -> Self {
+        BinaryError::Io(err)
+    }
+}
+
+/// The tables, in the order `write` writes them.
+const NODE: [u8; 4] = *b"NODE";
+const MESH: [u8; 4] = *b"MESH";
+const ATTR: [u8; 4] = *b"ATTR";
+const BONE: [u8; 4] = *b"BONE";
+const MATL: [u8; 4] = *b"MATL";
+const PROP: [u8; 4] = *b"PROP";
+const TEXR: [u8; 4] = *b"TEXR";
+const ANIM: [u8; 4] = *b"ANIM";
+const CHAN: [u8; 4] = *b"CHAN";
+const CAMR: [u8; 4] = *b"CAMR";
+const LGHT: [u8; 4] = *b"LGHT";
+const STRS: [u8; 4] = *b"STRS";
+const DATA: [u8; 4] = *b"DATA";
+
+const TABLES: [([u8; 4], usize); 11] = [
+    (NODE, 96),
+    (MESH, 144),
+    (ATTR, 24),
+    (BONE, 88),
+    (MATL, 16),
+    (PROP, 40),
+    (TEXR, 48),
+    (ANIM, 32),
+    (CHAN, 8 + 3 * TRACK_SIZE),
+    (CAMR, 64),
+    (LGHT, 112),
+];
+
+const TRACK_SIZE: usize = 56;
+
+const ATTR_COLORS: u32 = 0;
+const ATTR_UVS: u32 = 1;
+
+const PROP_STRING: u32 = 0;
+const PROP_U32: u32 = 1;
+const PROP_F32: u32 = 2;
+
+const TARGET_EXTERNAL: u32 = 0;
+const TARGET_EMBEDDED: u32 = 1;
+const TARGET_MISSING: u32 = 2;
+
+const TRACK_KEYED: u32 = 0;
+const TRACK_BAKED: u32 = 1;
+
+/// Write `snapshot` to `writer` in the format described in the module documentation.
+///
+/// The file is put together in memory and written in one go, so `writer` doesn't need to be
+/// buffered.
+pub fn write<W: Write>(snapshot: &SceneSnapshot, mut writer: W) -> Result<(), BinaryError> {
+    let mut encoder = Encoder::default();
+    if let Some(root) = &snapshot.root {
+        encoder.node(root, NO_PARENT, 0)?;
+    }
+    for mesh in &snapshot.meshes {
+        encoder.mesh(mesh)?;
+    }
+    for material in &snapshot.materials {
+        encoder.material(material)?;
+    }
+    for texture in &snapshot.textures {
+        encoder.texture(texture)?;
+    }
+    for animation in &snapshot.animations {
+        encoder.animation(animation)?;
+    }
+    for camera in &snapshot.cameras {
+        encoder.camera(camera)?;
+    }
+    for light in &snapshot.lights {
+        encoder.light(light)?;
+    }
+    writer.write_all(&encoder.finish()?)?;
+    Ok(())
+}
+
+/// Read a snapshot that `write` wrote from `reader`, to its end. See `from_bytes`.
+pub fn read<R: Read>(mut reader: R) -> Result<SceneSnapshot, BinaryError> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+    from_bytes(&data)
+}
+
+/// Read a snapshot that `write` wrote from `data`, e.g. a memory-mapped file. `data` doesn't
+/// need to be aligned, and is validated as described in the module documentation.
+pub fn from_bytes(data: &[u8]) -> Result<SceneSnapshot, BinaryError> {
+    Decoder::new(data)?.snapshot()
+}
+
+/// A record being written, which is checked against the size of its table when it's added.
+#[derive(Default)]
+struct Record(Vec<u8>);
+
+impl Record {
+    fn u32(mut self, value: u32) -> Self {
+        self.0.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    fn f32s(mut self, values: &[f32]) -> Self {
+        for value in values {
+            self.0.extend_from_slice(&value.to_le_bytes());
+        }
+        self
+    }
+
+    fn f64(mut self, value: f64) -> Self {
+        self.0.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    fn bytes(mut self, bytes: &[u8]) -> Self {
+        self.0.extend_from_slice(bytes);
+        self
+    }
+}
+
+#[derive(Default)]
+struct Encoder {
+    strings: Vec<u8>,
+    data: Vec<u8>,
+    /// The records of each table, in the order of `TABLES`.
+    tables: [Vec<u8>; 11],
+    counts: [u32; 11],
+}
+
+impl Encoder {
+    fn push(&mut self, tag: [u8; 4], record: Record) -> Result<(), BinaryError> {
+        let table = TABLES.iter().position(|&(t, _)| t == tag).unwrap_or(0);
+        debug_assert_eq!(record.0.len(), TABLES[table].1);
+        self.counts[table] = len32("records", self.counts[table] as usize + 1)?;
+        self.tables[table].extend_from_slice(&record.0);
+        Ok(())
+    }
+
+    /// The index the next record of `tag` gets.
+    fn next(&self, tag: [u8; 4]) -> u32 {
+        let table = TABLES.iter().position(|&(t, _)| t == tag).unwrap_or(0);
+        self.counts[table]
+    }
+
+    /// Add `value` to the strings, returning its reference.
+    fn string(&mut self, value: &str) -> Result<[u8; 8], BinaryError> {
+        let offset = len32("string bytes", self.strings.len())?;
+        let len = len32("string bytes", value.len())?;
+        self.strings.extend_from_slice(value.as_bytes());
+        len32("string bytes", self.strings.len())?;
+        let mut reference = [0; 8];
+        reference[..4].copy_from_slice(&offset.to_le_bytes());
+        reference[4..].copy_from_slice(&len.to_le_bytes());
+        Ok(reference)
+    }
+
+    /// Add `bytes` to the buffers, aligned, returning its reference.
+    fn buffer(&mut self, bytes: &[u8]) -> [u8; 16] {
+        pad(&mut self.data);
+        let mut reference = [0; 16];
+        reference[..8].copy_from_slice(&(self.data.len() as u64).to_le_bytes());
+        reference[8..].copy_from_slice(&(bytes.len() as u64).to_le_bytes());
+        self.data.extend_from_slice(bytes);
+        reference
+    }
+
+    fn f32_buffer<I: IntoIterator<Item = f32>>(&mut self, values: I) -> [u8; 16] {
+        let bytes: Vec<u8> = values.into_iter().flat_map(f32::to_le_bytes).collect();
+        self.buffer(&bytes)
+    }
+
+    fn u32_buffer<I: IntoIterator<Item = u32>>(&mut self, values: I) -> [u8; 16] {
+        let bytes: Vec<u8> = values.into_iter().flat_map(u32::to_le_bytes).collect();
+        self.buffer(&bytes)
+    }
+
+    fn vectors(&mut self, vectors: &[Vector3D]) -> [u8; 16] {
+        self.f32_buffer(vectors.iter().flat_map(|v| vec![v.x, v.y, v.z]))
+    }
+
+    fn node(&mut self, node: &NodeData, parent: u32, depth: usize) -> Result<(), BinaryError> {
+        if depth > MAX_NODE_DEPTH {
+            return Err(BinaryError::TooLarge {
+                what: "node levels",
+                len: depth + 1,
+            });
+        }
+        let index = self.next(NODE);
+        let name = self.string(&node.name)?;
+        let meshes = self.u32_buffer(node.meshes.iter().copied());
+        let transform = node.transform.rows();
+        let record = Record::default()
+            .bytes(&name)
+            .u32(parent)
+            .u32(0)
+            .f32s(&transform[0])
+            .f32s(&transform[1])
+            .f32s(&transform[2])
+            .f32s(&transform[3])
+            .bytes(&meshes);
+        self.push(NODE, record)?;
+        for child in &node.children {
+            self.node(child, index, depth + 1)?;
+        }
+        Ok(())
+    }
+
+    fn mesh(&mut self, mesh: &MeshData) -> Result<(), BinaryError> {
+        let vertices = len32("vertices", mesh.positions.len())?;
+        let faces = len32("faces", mesh.faces.len())?;
+        let color_sets = sets(mesh.colors.len())?;
+        let uv_channels = sets(mesh.texture_coords.len())?;
+
+        let first_attr = self.next(ATTR);
+        let colors = mesh
+            .colors
+            .iter()
+            .map(|set| (ATTR_COLORS, set.as_ref().map(|c| colors(c))));
+        let uvs = mesh
+            .texture_coords
+            .iter()
+            .map(|channel| (ATTR_UVS, channel.as_ref()));
+        let mut attrs = 0;
+        for (set, (kind, colors)) in colors.enumerate() {
+            if let Some(colors) = colors {
+                let colors = self.f32_buffer(colors);
+                self.push(
+                    ATTR,
+                    Record::default().u32(kind).u32(set as u32).bytes(&colors),
+                )?;
+                attrs += 1;
+            }
+        }
+        for (set, (kind, uvs)) in uvs.enumerate() {
+            if let Some(uvs) = uvs {
+                let uvs = self.vectors(uvs);
+                self.push(
+                    ATTR,
+                    Record::default().u32(kind).u32(set as u32).bytes(&uvs),
+                )?;
+                attrs += 1;
+            }
+        }
+
+        let first_bone = self.next(BONE);
+        for bone in &mesh.bones {
+            let name = self.string(&bone.name)?;
+            let weights: Vec<u8> = bone
+                .weights
+                .iter()
+                .flat_map(|&(vertex, weight)| {
+                    let mut pair = [0; 8];
+                    pair[..4].copy_from_slice(&vertex.to_le_bytes());
+                    pair[4..].copy_from_slice(&weight.to_le_bytes());
+                    pair
+                })
+                .collect();
+            let weights = self.buffer(&weights);
+            let offset = bone.offset_matrix.rows();
+            let record = Record::default()
+                .bytes(&name)
+                .bytes(&weights)
+                .f32s(&offset[0])
+                .f32s(&offset[1])
+                .f32s(&offset[2])
+                .f32s(&offset[3]);
+            self.push(BONE, record)?;
+        }
+
+        let face_size = match mesh.faces.first() {
+            Some(first) if mesh.faces.iter().all(|face| face.len() == first.len()) => {
+                len32("face indices", first.len())?
+            }
+            _ => 0,
+        };
+        let indices = self.u32_buffer(mesh.faces.iter().flatten().copied());
+        let face_sizes = if face_size == 0 {
+            let sizes: Result<Vec<u32>, _> = mesh
+                .faces
+                .iter()
+                .map(|face| len32("face indices", face.len()))
+                .collect();
+            self.u32_buffer(sizes?)
+        } else {
+            self.buffer(&[])
+        };
+
+        let name = self.string(&mesh.name)?;
+        let positions = self.vectors(&mesh.positions);
+        let normals = self.vectors(&mesh.normals);
+        let tangents = self.vectors(&mesh.tangents);
+        let bitangents = self.vectors(&mesh.bitangents);
+        let record = Record::default()
+            .bytes(&name)
+            .u32(mesh.material_index)
+            .u32(vertices)
+            .u32(faces)
+            .u32(face_size)
+            .u32(color_sets)
+            .u32(uv_channels)
+            .u32(first_attr)
+            .u32(attrs)
+            .u32(first_bone)
+            .u32(len32("bones", mesh.bones.len())?)
+            .bytes(&positions)
+            .bytes(&normals)
+            .bytes(&tangents)
+            .bytes(&bitangents)
+            .bytes(&indices)
+            .bytes(&face_sizes);
+        self.push(MESH, record)
+    }
+
+    fn material(&mut self, material: &MaterialData) -> Result<(), BinaryError> {
+        let first = self.next(PROP);
+        let mut count = 0;
+        for texture in &material.textures {
+            let (semantic, index) = (texture.component as u32, texture.index);
+            let (target, embedded) = match texture.target {
+                TextureTarget::External => (TARGET_EXTERNAL, 0),
+                TextureTarget::Embedded(index) => (TARGET_EMBEDDED, index),
+                TextureTarget::Missing(index) => (TARGET_MISSING, index),
+            };
+            let path = self.buffer(texture.path.as_bytes());
+            let target = self.u32_buffer(vec![target, embedded]);
+            let channel = self.u32_buffer(vec![texture.channel]);
+            let mut properties = vec![
+                ("$tex.file", PROP_STRING, path),
+                ("$tex.target", PROP_U32, target),
+                ("$tex.uvwsrc", PROP_U32, channel),
+            ];
+            if let Some(transform) = texture.uv_transform {
+                let values = vec![
+                    transform.translation.x,
+                    transform.translation.y,
+                    transform.scaling.x,
+                    transform.scaling.y,
+                    transform.rotation,
+                ];
+                properties.push(("$tex.uvtrafo", PROP_F32, self.f32_buffer(values)));
+            }
+            for (key, kind, value) in properties {
+                let key = self.string(key)?;
+                let record = Record::default()
+                    .bytes(&key)
+                    .u32(semantic)
+                    .u32(index)
+                    .u32(kind)
+                    .u32(0)
+                    .bytes(&value);
+                self.push(PROP, record)?;
+                count += 1;
+            }
+        }
+
+        let name = self.string(&material.name)?;
+        self.push(MATL, Record::default().bytes(&name).u32(first).u32(count))
+    }
+
+    fn texture(&mut self, texture: &EmbeddedTextureData) -> Result<(), BinaryError> {
+        let filename = self.string(&texture.filename)?;
+        let format_hint = self.string(&texture.format_hint)?;
+        let data = self.buffer(texture.data.as_deref().unwrap_or_default());
+        let record = Record::default()
+            .bytes(&filename)
+            .bytes(&format_hint)
+            .u32(texture.size.0)
+            .u32(texture.size.1)
+            .u32(texture.data.is_some() as u32)
+            .u32(0)
+            .bytes(&data);
+        self.push(TEXR, record)
+    }
+
+    fn animation(&mut self, animation: &AnimationData) -> Result<(), BinaryError> {
+        let first = self.next(CHAN);
+        for channel in &animation.channels {
+            let name = self.string(&channel.node_name)?;
+            let mut record = Record::default().bytes(&name);
+            let positions = vector_values(&channel.position_keys);
+            record = self.track(record, &channel.position_keys, positions)?;
+            let rotations = channel
+                .rotation_keys
+                .iter()
+                .flat_map(|(_, q)| vec![q.w, q.x, q.y, q.z])
+                .collect();
+            record = self.track(record, &channel.rotation_keys, rotations)?;
+            let scalings = vector_values(&channel.scaling_keys);
+            record = self.track(record, &channel.scaling_keys, scalings)?;
+            self.push(CHAN, record)?;
+        }
+
+        let name = self.string(&animation.name)?;
+        let record = Record::default()
+            .bytes(&name)
+            .f64(animation.duration)
+            .f64(animation.ticks_per_second)
+            .u32(first)
+            .u32(len32("channels", animation.channels.len())?);
+        self.push(ANIM, record)
+    }
+
+    /// Append a track to `record`, baked if the keys are evenly spaced.
+    fn track<T>(
+        &mut self,
+        record: Record,
+        keys: &[(f64, T)],
+        values: Vec<f32>,
+    ) -> Result<Record, BinaryError> {
+        let count = len32("keys", keys.len())?;
+        let values = self.f32_buffer(values);
+        let (start, step) = match keys {
+            [(first, _), (second, _), ..] => (*first, second - first),
+            _ => (0.0, 0.0),
+        };
+        let baked = keys.len() >= 2
+            && keys
+                .iter()
+                .enumerate()
+                .all(|(i, &(time, _))| baked_time(start, step, i).to_bits() == time.to_bits());
+        let (kind, start, step, times) = if baked {
+            (TRACK_BAKED, start, step, self.buffer(&[]))
+        } else {
+            let times: Vec<u8> = keys
+                .iter()
+                .flat_map(|(time, _)| time.to_le_bytes())
+                .collect();
+            (TRACK_KEYED, 0.0, 0.0, self.buffer(&times))
+        };
+        Ok(record
+            .u32(kind)
+            .u32(count)
+            .f64(start)
+            .f64(step)
+            .bytes(&times)
+            .bytes(&values))
+    }
+
+    fn camera(&mut self, camera: &CameraData) -> Result<(), BinaryError> {
+        let name = self.string(&camera.name)?;
+        let record = Record::default()
+            .bytes(&name)
+            .f32s(&vector(camera.position))
+            .f32s(&vector(camera.up))
+            .f32s(&vector(camera.look_at))
+            .f32s(&[
+                camera.horizontal_fov,
+                camera.clip_plane_near,
+                camera.clip_plane_far,
+                camera.aspect,
+            ])
+            .u32(0);
+        self.push(CAMR, record)
+    }
+
+    fn light(&mut self, light: &LightData) -> Result<(), BinaryError> {
+        let name = self.string(&light.name)?;
+        let (constant, linear, quadratic) = light.attenuation;
+        let record = Record::default()
+            .bytes(&name)
+            .u32(light.light_type as u32)
+            .f32s(&vector(light.position))
+            .f32s(&vector(light.direction))
+            .f32s(&vector(light.up))
+            .f32s(&[constant, linear, quadratic])
+            .f32s(&color(light.color_diffuse))
+            .f32s(&color(light.color_specular))
+            .f32s(&color(light.color_ambient))
+            .f32s(&[
+                light.angle_inner_cone,
+                light.angle_outer_cone,
+                light.size.0,
+                light.size.1,
+            ]);
+        self.push(LGHT, record)
+    }
+
+    /// Put the file together: the header, the chunk table, the tables and the payloads.
+    fn finish(self) -> Result<Vec<u8>, BinaryError> {
+        let mut chunks: Vec<([u8; 4], Vec<u8>)> = Vec::new();
+        for (table, &(tag, record_size)) in TABLES.iter().enumerate() {
+            let mut chunk = Vec::with_capacity(TABLE_HEADER_SIZE + self.tables[table].len());
+            chunk.extend_from_slice(&self.counts[table].to_le_bytes());
+            chunk.extend_from_slice(&(record_size as u32).to_le_bytes());
+            chunk.extend_from_slice(&0u64.to_le_bytes());
+            chunk.extend_from_slice(&self.tables[table]);
+            chunks.push((tag, chunk));
+        }
+        chunks.push((STRS, self.strings));
+        chunks.push((DATA, self.data));
+
+        let mut file = Vec::new();
+        file.extend_from_slice(&MAGIC);
+        file.extend_from_slice(&MAJOR_VERSION.to_le_bytes());
+        file.extend_from_slice(&MINOR_VERSION.to_le_bytes());
+        file.extend_from_slice(&(chunks.len() as u32).to_le_bytes());
+        // The size is filled in at the end.
+        file.extend_from_slice(&[0; 16]);
+
+        let mut offset = HEADER_SIZE + chunks.len() * CHUNK_ENTRY_SIZE;
+        for (tag, chunk) in &chunks {
+            offset = align(offset);
+            file.extend_from_slice(tag);
+            file.extend_from_slice(&0u32.to_le_bytes());
+            file.extend_from_slice(&(offset as u64).to_le_bytes());
+            file.extend_from_slice(&(chunk.len() as u64).to_le_bytes());
+            offset += chunk.len();
+        }
+        for (_, chunk) in &chunks {
+            pad(&mut file);
+            file.extend_from_slice(chunk);
+        }
+        let size = (file.len() as u64).to_le_bytes();
+        file[16..24].copy_from_slice(&size);
+        Ok(file)
+    }
+}
+
+fn len32(what: &'static str, len: usize) -> Result<u32, BinaryError> {
+    u32::try_from(len).map_err(|_| BinaryError::TooLarge { what, len })
+}
+
+fn sets(len: usize) -> Result<u32, BinaryError> {
+    match len32("color sets or UV channels", len)? {
+        sets if sets <= MAX_SETS => Ok(sets),
+        _ => Err(BinaryError::TooLarge {
+            what: "color sets or UV channels",
+            len,
+        }),
+    }
+}
+
+fn align(offset: usize) -> usize {
+    (offset + ALIGNMENT - 1) / ALIGNMENT * ALIGNMENT
+}
+
+/// Fill `bytes` with zeros up to the next multiple of `ALIGNMENT`.
+fn pad(bytes: &mut Vec<u8>) {
+    bytes.resize(align(bytes.len()), 0);
+}
+
+fn baked_time(start: f64, step: f64, index: usize) -> f64 {
+    start + index as f64 * step
+}
+
+fn vector(v: Vector3D) -> [f32; 3] {
+    [v.x, v.y, v.z]
+}
+
+fn color(c: Color3D) -> [f32; 3] {
+    [c.r, c.g, c.b]
+}
+
+fn colors(colors: &[Color4D]) -> Vec<f32> {
+    colors
+        .iter()
+        .flat_map(|c| vec![c.r, c.g, c.b, c.a])
+        .collect()
+}
+
+fn vector_values(keys: &[(f64, Vector3D)]) -> Vec<f32> {
+    keys.iter().flat_map(|(_, v)| vector(*v).to_vec()).collect()
+}
+
+/// A record being read. Tables are only read once their record size is checked, so the fields
+/// are always there.
+struct Fields<'a> {
+    bytes: &'a [u8],
+    /// The offset of `bytes` in the file.
+    offset: usize,
+    pos: usize,
+}
+
+impl<'a> Fields<'a> {
+    fn take(&mut self, len: usize) -> &'a [u8] {
+        let field = &self.bytes[self.pos..self.pos + len];
+        self.pos += len;
+        field
+    }
+
+    fn tag(&mut self) -> [u8; 4] {
+        let b = self.take(4);
+        [b[0], b[1], b[2], b[3]]
+    }
+
+    fn u16(&mut self) -> u16 {
+        let b = self.take(2);
+        u16::from_le_bytes([b[0], b[1]])
+    }
+
+    fn u32(&mut self) -> u32 {
+        u32::from_le_bytes(self.tag())
+    }
+
+    fn u64(&mut self) -> u64 {
+        let b = self.take(8);
+        u64::from_le_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]])
+    }
+
+    fn f32(&mut self) -> f32 {
+        f32::from_bits(self.u32())
+    }
+
+    fn f64(&mut self) -> f64 {
+        f64::from_bits(self.u64())
+    }
+
+    fn vector(&mut self) -> Vector3D {
+        Vector3D::new(self.f32(), self.f32(), self.f32())
+    }
+
+    fn color(&mut self) -> Color3D {
+        Color3D::new(self.f32(), self.f32(), self.f32())
+    }
+
+    fn matrix(&mut self) -> Matrix4x4 {
+        let mut rows = [[0.0; 4]; 4];
+        for row in &mut rows {
+            for value in row.iter_mut() {
+                *value = self.f32();
+            }
+        }
+        Matrix4x4::from_rows(rows)
+    }
+
+    /// The offset in the file of the next field.
+    fn here(&self) -> usize {
+        self.offset + self.pos
+    }
+}
+
+/// A table of the file, whose records are all within the file.
+#[derive(Copy, Clone)]
+struct Table<'a> {
+    records: &'a [u8],
+    offset: usize,
+    count: u32,
+    record_size: usize,
+}
+
+impl<'a> Table<'a> {
+    fn record(&self, index: u32) -> Fields<'a> {
+        let start = index as usize * self.record_size;
+        Fields {
+            bytes: &self.records[start..start + self.record_size],
+            offset: self.offset + start,
+            pos: 0,
+        }
+    }
+
+    /// The records of the range at `fields`, checked against the table.
+    fn range(
+        &self,
+        fields: &mut Fields,
+        what: &'static str,
+    ) -> Result<Vec<Fields<'a>>, BinaryError> {
+        let offset = fields.here();
+        let (first, count) = (fields.u32(), fields.u32());
+        match first.checked_add(count) {
+            Some(end) if end <= self.count => Ok((first..end).map(|i| self.record(i)).collect()),
+            _ => Err(BinaryError::Corrupt { what, offset }),
+        }
+    }
+}
+
+struct Decoder<'a> {
+    chunks: Vec<([u8; 4], usize, &'a [u8])>,
+    strings: (usize, &'a [u8]),
+    buffers: (usize, &'a [u8]),
+}
+
+impl<'a> Decoder<'a> {
+    /// Check the header and the chunk table.
+    fn new(data: &'a [u8]) -> Result<Self, BinaryError> {
+        if data.len() < HEADER_SIZE || data[..8] != MAGIC {
+            return Err(BinaryError::NotSnapshot);
+        }
+        let mut header = Fields {
+            bytes: &data[..HEADER_SIZE],
+            offset: 0,
+            pos: 8,
+        };
+        let major = header.u16();
+        let minor = header.u16();
+        if major != MAJOR_VERSION {
+            return Err(BinaryError::UnsupportedVersion { major, minor });
+        }
+        let num_chunks = header.u32() as usize;
+        let size = header.u64();
+        if size != data.len() as u64 {
+            return Err(BinaryError::Corrupt {
+                what: "file size",
+                offset: 16,
+            });
+        }
+
+        let table_end = num_chunks
+            .checked_mul(CHUNK_ENTRY_SIZE)
+            .and_then(|len| len.checked_add(HEADER_SIZE))
+            .filter(|&end| end <= data.len())
+            .ok_or(BinaryError::Corrupt {
+                what: "chunk count",
+                offset: 12,
+            })?;
+        let mut entries = Fields {
+            bytes: &data[..table_end],
+            offset: 0,
+            pos: HEADER_SIZE,
+        };
+        let mut chunks = Vec::with_capacity(num_chunks);
+        for _ in 0..num_chunks {
+            let tag = entries.tag();
+            entries.u32();
+            let at = entries.here();
+            let (offset, len) = (entries.u64(), entries.u64());
+            let chunk = slice(data, offset, len)
+                .filter(|_| offset as usize % ALIGNMENT == 0 && offset as usize >= table_end)
+                .ok_or(BinaryError::Corrupt {
+                    what: "chunk offset",
+                    offset: at,
+                })?;
+            chunks.push((tag, offset as usize, chunk));
+        }
+
+        let mut decoder = Decoder {
+            chunks,
+            strings: (0, &[]),
+            buffers: (0, &[]),
+        };
+        decoder.strings = decoder.chunk(STRS).unwrap_or((0, &[]));
+        decoder.buffers = decoder.chunk(DATA).unwrap_or((0, &[]));
+        Ok(decoder)
+    }
+
+    fn chunk(&self, tag: [u8; 4]) -> Option<(usize, &'a [u8])> {
+        self.chunks
+            .iter()
+            .find(|&&(t, _, _)| t == tag)
+            .map(|&(_, offset, chunk)| (offset, chunk))
+    }
+
+    /// The table `tag`, checked against its chunk. Missing tables are empty.
+    fn table(&self, tag: [u8; 4]) -> Result<Table<'a>, BinaryError> {
+        let min_size = TABLES
+            .iter()
+            .find(|&&(t, _)| t == tag)
+            .map_or(0, |&(_, size)| size);
+        let (offset, chunk) = match self.chunk(tag) {
+            Some(chunk) => chunk,
+            None => {
+                return Ok(Table {
+                    records: &[],
+                    offset: 0,
+                    count: 0,
+                    record_size: min_size,
+                })
+            }
+        };
+        if chunk.len() < TABLE_HEADER_SIZE {
+            return Err(BinaryError::Corrupt {
+                what: "table header",
+                offset,
+            });
+        }
+        let mut header = Fields {
+            bytes: &chunk[..TABLE_HEADER_SIZE],
+            offset,
+            pos: 0,
+        };
+        let count = header.u32();
+        let record_size = header.u32() as usize;
+        if record_size < min_size {
+            return Err(BinaryError::Corrupt {
+                what: "record size",
+                offset: offset + 4,
+            });
+        }
+        let records = &chunk[TABLE_HEADER_SIZE..];
+        match (count as usize).checked_mul(record_size) {
+            Some(len) if len <= records.len() => Ok(Table {
+                records: &records[..len],
+                offset: offset + TABLE_HEADER_SIZE,
+                count,
+                record_size,
+            }),
+            _ => Err(BinaryError::Corrupt {
+                what: "record count",
+                offset,
+            }),
+        }
+    }
+
+    fn string(&self, fields: &mut Fields) -> Result<String, BinaryError> {
+        let offset = fields.here();
+        let (start, len) = (fields.u32(), fields.u32());
+        let bytes = slice(self.strings.1, u64::from(start), u64::from(len)).ok_or(
+            BinaryError::Corrupt {
+                what: "string reference",
+                offset,
+            },
+        )?;
+        let string = std::str::from_utf8(bytes).map_err(|_| BinaryError::Corrupt {
+            what: "string",
+            offset: self.strings.0 + start as usize,
+        })?;
+        Ok(string.to_owned())
+    }
+
+    /// The buffer at `fields`, checked to be aligned and a whole number of `element_size`
+    /// elements, and `count` of them unless that's `None`.
+    fn buffer(
+        &self,
+        fields: &mut Fields,
+        element_size: usize,
+        count: Option<usize>,
+        what: &'static str,
+    ) -> Result<&'a [u8], BinaryError> {
+        let offset = fields.here();
+        let (start, len) = (fields.u64(), fields.u64());
+        slice(self.buffers.1, start, len)
+            .filter(|bytes| start as usize % ALIGNMENT == 0 && bytes.len() % element_size == 0)
+            .filter(|bytes| {
+                count.map_or(true, |count| {
+                    Some(bytes.len()) == count.checked_mul(element_size)
+                })
+            })
+            .ok_or(BinaryError::Corrupt { what, offset })
+    }
+
+    /// A buffer of `f32`s, `width` per element.
+    fn floats(
+        &self,
+        fields: &mut Fields,
+        width: usize,
+        count: Option<usize>,
+        what: &'static str,
+    ) -> Result<Vec<f32>, BinaryError> {
+        let bytes = self.buffer(fields, 4 * width, count, what)?;
+        Ok(bytes
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect())
+    }
+
+    fn u32s(
+        &self,
+        fields: &mut Fields,
+        count: Option<usize>,
+        what: &'static str,
+    ) -> Result<Vec<u32>, BinaryError> {
+        let bytes = self.buffer(fields, 4, count, what)?;
+        Ok(bytes
+            .chunks_exact(4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect())
+    }
+
+    /// A vertex attribute of 3 `f32`s per vertex, which is either there for all `vertices` or
+    /// empty.
+    fn vectors(
+        &self,
+        fields: &mut Fields,
+        vertices: usize,
+        what: &'static str,
+    ) -> Result<Vec<Vector3D>, BinaryError> {
+        let at = fields.here();
+        let floats = self.floats(fields, 3, None, what)?;
+        if !floats.is_empty() && floats.len() / 3 != vertices {
+            return Err(BinaryError::Corrupt { what, offset: at });
+        }
+        Ok(floats
+            .chunks_exact(3)
+            .map(|v| Vector3D::new(v[0], v[1], v[2]))
+            .collect())
+    }
+
+    fn snapshot(&self) -> Result<SceneSnapshot, BinaryError> {
+        let meshes = self.table(MESH)?;
+        let materials = self.table(MATL)?;
+        let textures = self.table(TEXR)?;
+        let animations = self.table(ANIM)?;
+        let cameras = self.table(CAMR)?;
+        let lights = self.table(LGHT)?;
+
+        Ok(SceneSnapshot {
+            root: self.nodes()?,
+            meshes: (0..meshes.count)
+                .map(|i| self.mesh(meshes.record(i)))
+                .collect::<Result<_, _>>()?,
+            materials: (0..materials.count)
+                .map(|i| self.material(materials.record(i)))
+                .collect::<Result<_, _>>()?,
+            textures: (0..textures.count)
+                .map(|i| self.texture(textures.record(i)))
+                .collect::<Result<_, _>>()?,
+            animations: (0..animations.count)
+                .map(|i| self.animation(animations.record(i)))
+                .collect::<Result<_, _>>()?,
+            cameras: (0..cameras.count)
+                .map(|i| self.camera(cameras.record(i)))
+                .collect::<Result<_, _>>()?,
+            lights: (0..lights.count)
+                .map(|i| self.light(lights.record(i)))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// Build the hierarchy from the node records, from the last node to the first, so the
+    /// children of each node are complete before it's built.
+    fn nodes(&self) -> Result<Option<NodeData>, BinaryError> {
+        let table = self.table(NODE)?;
+        let mut nodes: Vec<Option<NodeData>> = Vec::with_capacity(table.count as usize);
+        let mut parents = Vec::with_capacity(table.count as usize);
+        let mut depths: Vec<usize> = Vec::with_capacity(table.count as usize);
+        for index in 0..table.count {
+            let mut fields = table.record(index);
+            let name = self.string(&mut fields)?;
+            let at = fields.here();
+            let parent = fields.u32();
+            fields.u32();
+            // The root has no parent, and every other node comes after its parent.
+            let depth = match (index, parent) {
+                (0, NO_PARENT) => 0,
+                (_, parent) if index > 0 && parent < index => depths[parent as usize] + 1,
+                _ => {
+                    return Err(BinaryError::Corrupt {
+                        what: "node parent",
+                        offset: at,
+                    })
+                }
+            };
+            if depth > MAX_NODE_DEPTH {
+                return Err(BinaryError::Corrupt {
+                    what: "node depth",
+                    offset: at,
+                });
+            }
+            let transform = fields.matrix();
+            let meshes = self.u32s(&mut fields, None, "node meshes")?;
+            nodes.push(Some(NodeData {
+                name,
+                transform,
+                meshes,
+                children: Vec::new(),
+            }));
+            parents.push(parent);
+            depths.push(depth);
+        }
+
+        for index in (1..nodes.len()).rev() {
+            if let Some(node) = nodes[index].take() {
+                if let Some(parent) = &mut nodes[parents[index] as usize] {
+                    parent.children.push(node);
+                }
+            }
+        }
+        let mut root = nodes.into_iter().next().flatten();
+        if let Some(root) = &mut root {
+            root.visit_mut(&mut |node: &mut NodeData| node.children.reverse());
+        }
+        Ok(root)
+    }
+
+    fn mesh(&self, mut fields: Fields) -> Result<MeshData, BinaryError> {
+        let name = self.string(&mut fields)?;
+        let material_index = fields.u32();
+        let vertices = fields.u32() as usize;
+        let faces = fields.u32() as usize;
+        let at = fields.here();
+        let face_size = fields.u32() as usize;
+        let color_sets = fields.u32();
+        let uv_channels = fields.u32();
+        if color_sets > MAX_SETS || uv_channels > MAX_SETS {
+            return Err(BinaryError::Corrupt {
+                what: "set count",
+                offset: at + 4,
+            });
+        }
+        let attrs = self.table(ATTR)?.range(&mut fields, "mesh attributes")?;
+        let bones = self.table(BONE)?.range(&mut fields, "mesh bones")?;
+
+        let positions = self.vectors(&mut fields, vertices, "positions")?;
+        if positions.len() != vertices {
+            return Err(BinaryError::Corrupt {
+                what: "positions",
+                offset: at - 12,
+            });
+        }
+        let normals = self.vectors(&mut fields, vertices, "normals")?;
+        let tangents = self.vectors(&mut fields, vertices, "tangents")?;
+        let bitangents = self.vectors(&mut fields, vertices, "bitangents")?;
+        let indices = self.u32s(&mut fields, None, "face indices")?;
+        let sizes = if face_size == 0 {
+            let sizes = self.u32s(&mut fields, Some(faces), "face sizes")?;
+            sizes.into_iter().map(|size| size as usize).collect()
+        } else {
+            self.buffer(&mut fields, 4, Some(0), "face sizes")?;
+            vec![face_size; faces.min(indices.len() / face_size)]
+        };
+        if sizes.len() != faces
+            || sizes.iter().map(|&size| size as u64).sum::<u64>() != indices.len() as u64
+        {
+            return Err(BinaryError::Corrupt {
+                what: "face indices",
+                offset: at,
+            });
+        }
+        let mut rest = &indices[..];
+        let faces = sizes
+            .into_iter()
+            .map(|size| {
+                let (face, tail) = rest.split_at(size);
+                rest = tail;
+                face.to_vec()
+            })
+            .collect();
+
+        let mut colors = vec![None; color_sets as usize];
+        let mut texture_coords = vec![None; uv_channels as usize];
+        for mut attr in attrs {
+            let at = attr.here();
+            let (kind, set) = (attr.u32(), attr.u32() as usize);
+            match kind {
+                ATTR_COLORS if set < colors.len() => {
+                    let values = self.floats(&mut attr, 4, Some(vertices), "colors")?;
+                    let set_colors = values
+                        .chunks_exact(4)
+                        .map(|c| Color4D::new(c[0], c[1], c[2], c[3]));
+                    colors[set] = Some(set_colors.collect());
+                }
+                ATTR_UVS if set < texture_coords.len() => {
+                    let values =
+                        self.floats(&mut attr, 3, Some(vertices), "texture coordinates")?;
+                    let uvs = values
+                        .chunks_exact(3)
+                        .map(|v| Vector3D::new(v[0], v[1], v[2]));
+                    texture_coords[set] = Some(uvs.collect());
+                }
+                _ => {
+                    return Err(BinaryError::Corrupt {
+                        what: "mesh attribute",
+                        offset: at,
+                    })
+                }
+            }
+        }
+
+        let bones = bones
+            .into_iter()
+            .map(|mut bone| {
+                let name = self.string(&mut bone)?;
+                let pairs = self.buffer(&mut bone, 8, None, "bone weights")?;
+                let weights = pairs
+                    .chunks_exact(8)
+                    .map(|p| {
+                        let vertex = u32::from_le_bytes([p[0], p[1], p[2], p[3]]);
+                        (vertex, f32::from_le_bytes([p[4], p[5], p[6], p[7]]))
+                    })
+                    .collect();
+                Ok(BoneData {
+                    name,
+                    offset_matrix: bone.matrix(),
+                    weights,
+                })
+            })
+            .collect::<Result<_, BinaryError>>()?;
+
+        Ok(MeshData {
+            name,
+            material_index,
+            positions,
+            normals,
+            tangents,
+            bitangents,
+            colors,
+            texture_coords,
+            faces,
+            bones,
+        })
+    }
+
+    fn material(&self, mut fields: Fields) -> Result<MaterialData, BinaryError> {
+        let name = self.string(&mut fields)?;
+        let properties = self
+            .table(PROP)?
+            .range(&mut fields, "material properties")?;
+
+        let mut textures: Vec<MaterialTextureData> = Vec::new();
+        for mut property in properties {
+            let at = property.here();
+            let key = self.string(&mut property)?;
+            let (semantic, index, kind) = (property.u32(), property.u32(), property.u32());
+            property.u32();
+            if key == "$tex.file" && kind == PROP_STRING {
+                let bytes = self.buffer(&mut property, 1, None, "texture path")?;
+                let path = std::str::from_utf8(bytes).map_err(|_| BinaryError::Corrupt {
+                    what: "texture path",
+                    offset: at,
+                })?;
+                let component = MaterialComponentType::try_from(semantic).map_err(|_| {
+                    BinaryError::Corrupt {
+                        what: "texture component",
+                        offset: at,
+                    }
+                })?;
+                textures.push(MaterialTextureData {
+                    component,
+                    index,
+                    path: path.to_owned(),
+                    target: TextureTarget::External,
+                    channel: 0,
+                    uv_transform: None,
+                });
+                continue;
+            }
+            if !key.starts_with("$tex.") {
+                continue;
+            }
+
+            let texture = textures
+                .iter_mut()
+                .rev()
+                .find(|texture| texture.component as u32 == semantic && texture.index == index);
+            match (key.as_str(), kind, texture) {
+                ("$tex.target", PROP_U32, Some(texture)) => {
+                    let values = self.u32s(&mut property, Some(2), "texture target")?;
+                    texture.target = match values[0] {
+                        TARGET_EXTERNAL => TextureTarget::External,
+                        TARGET_EMBEDDED => TextureTarget::Embedded(values[1]),
+                        TARGET_MISSING => TextureTarget::Missing(values[1]),
+                        _ => {
+                            return Err(BinaryError::Corrupt {
+                                what: "texture target",
+                                offset: at,
+                            })
+                        }
+                    };
+                }
+                ("$tex.uvwsrc", PROP_U32, Some(texture)) => {
+                    texture.channel = self.u32s(&mut property, Some(1), "texture channel")?[0];
+                }
+                ("$tex.uvtrafo", PROP_F32, Some(texture)) => {
+                    let values = self.floats(&mut property, 1, Some(5), "UV transform")?;
+                    texture.uv_transform = Some(UvTransform {
+                        translation: Vector2D::new(values[0], values[1]),
+                        scaling: Vector2D::new(values[2], values[3]),
+                        rotation: values[4],
+                    });
+                }
+                ("$tex.target", _, _) | ("$tex.uvwsrc", _, _) | ("$tex.uvtrafo", _, _) => {
+                    return Err(BinaryError::Corrupt {
+                        what: "texture property",
+                        offset: at,
+                    });
+                }
+                // Properties of a newer minor version.
+                _ => {}
+            }
+        }
+
+        Ok(MaterialData { name, textures })
+    }
+
+    fn texture(&self, mut fields: Fields) -> Result<EmbeddedTextureData, BinaryError> {
+        let filename = self.string(&mut fields)?;
+        let format_hint = self.string(&mut fields)?;
+        let size = (fields.u32(), fields.u32());
+        let at = fields.here();
+        let has_data = fields.u32();
+        fields.u32();
+        let bytes = self.buffer(&mut fields, 1, None, "texture data")?;
+        let data = match has_data {
+            0 if bytes.is_empty() => None,
+            1 => Some(bytes.to_vec()),
+            _ => {
+                return Err(BinaryError::Corrupt {
+                    what: "texture data",
+                    offset: at,
+                })
+            }
+        };
+        Ok(EmbeddedTextureData {
+            filename,
+            format_hint,
+            size,
+            data,
+        })
+    }
+
+    fn animation(&self, mut fields: Fields) -> Result<AnimationData, BinaryError> {
+        let name = self.string(&mut fields)?;
+        let duration = fields.f64();
+        let ticks_per_second = fields.f64();
+        let channels = self
+            .table(CHAN)?
+            .range(&mut fields, "animation channels")?
+            .into_iter()
+            .map(|mut channel| {
+                let node_name = self.string(&mut channel)?;
+                let position_keys =
+                    self.track(&mut channel, 3, |v| Vector3D::new(v[0], v[1], v[2]))?;
+                let rotation_keys =
+                    self.track(&mut channel, 4, |q| Quaternion::new(q[0], q[1], q[2], q[3]))?;
+                let scaling_keys =
+                    self.track(&mut channel, 3, |v| Vector3D::new(v[0], v[1], v[2]))?;
+                Ok(ChannelData {
+                    node_name,
+                    position_keys,
+                    rotation_keys,
+                    scaling_keys,
+                })
+            })
+            .collect::<Result<_, BinaryError>>()?;
+        Ok(AnimationData {
+            name,
+            duration,
+            ticks_per_second,
+            channels,
+        })
+    }
+
+    fn track<T, F: Fn(&[f32]) -> T>(
+        &self,
+        fields: &mut Fields,
+        width: usize,
+        value: F,
+    ) -> Result<Vec<(f64, T)>, BinaryError> {
+        let at = fields.here();
+        let (kind, count) = (fields.u32(), fields.u32() as usize);
+        let (start, step) = (fields.f64(), fields.f64());
+        let times: Vec<f64> = match kind {
+            TRACK_KEYED => self
+                .buffer(fields, 8, Some(count), "key times")?
+                .chunks_exact(8)
+                .map(|b| f64::from_le_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]))
+                .collect(),
+            TRACK_BAKED => {
+                self.buffer(fields, 8, Some(0), "key times")?;
+                // The values are checked before the times are made, so `count` is bounded by
+                // the file.
+                let mut values = Fields {
+                    pos: fields.pos,
+                    ..*fields
+                };
+                self.buffer(&mut values, 4 * width, Some(count), "key values")?;
+                (0..count).map(|i| baked_time(start, step, i)).collect()
+            }
+            _ => {
+                return Err(BinaryError::Corrupt {
+                    what: "track kind",
+                    offset: at,
+                })
+            }
+        };
+        let values = self.floats(fields, width, Some(count), "key values")?;
+        Ok(times
+            .into_iter()
+            .zip(values.chunks_exact(width).map(value))
+            .collect())
+    }
+
+    fn camera(&self, mut fields: Fields) -> Result<CameraData, BinaryError> {
+        Ok(CameraData {
+            name: self.string(&mut fields)?,
+            position: fields.vector(),
+            up: fields.vector(),
+            look_at: fields.vector(),
+            horizontal_fov: fields.f32(),
+            clip_plane_near: fields.f32(),
+            clip_plane_far: fields.f32(),
+            aspect: fields.f32(),
+        })
+    }
+
+    fn light(&self, mut fields: Fields) -> Result<LightData, BinaryError> {
+        let name = self.string(&mut fields)?;
+        let at = fields.here();
+        let light_type = LightType::try_from(fields.u32()).map_err(|_| BinaryError::Corrupt {
+            what: "light type",
+            offset: at,
+        })?;
+        Ok(LightData {
+            name,
+            light_type,
+            position: fields.vector(),
+            direction: fields.vector(),
+            up: fields.vector(),
+            attenuation: (fields.f32(), fields.f32(), fields.f32()),
+            color_diffuse: fields.color(),
+            color_specular: fields.color(),
+            color_ambient: fields.color(),
+            angle_inner_cone: fields.f32(),
+            angle_outer_cone: fields.f32(),
+            size: (fields.f32(), fields.f32()),
+        })
+    }
+}
+
+/// The `len` bytes at `start` of `bytes`, if they're all there.
+fn slice(bytes: &[u8], start: u64, len: u64) -> Option<&[u8]> {
+    let start = usize::try_from(start).ok()?;
+    let end = start.checked_add(usize::try_from(len).ok()?)?;
+    bytes.get(start..end)
+}
@@ -0,0 +1,241 @@
+//! Checking bone offset matrices against the node hierarchy, see `Scene::check_bind_pose`.
+
+use std::cmp::Ordering;
+
+use crate::math::{Matrix4x4, Vector3D};
+use crate::Error;
+
+use super::scene::Scene;
+
+/// How far a bone can be from its bind pose before `Scene::check_bind_pose` flags it.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BindPoseThresholds {
+    /// The distance, in the units of the mesh. Default: 0.001
+    pub max_translation: f32,
+    /// The angle, in radians. Default: 0.01
+    pub max_rotation: f32,
+    /// The difference of the scale on any axis from 1. Default: 0.001
+    pub max_scale: f32,
+}
+
+impl Default for BindPoseThresholds {
+    fn default() -> Self {
+        BindPoseThresholds {
+            max_translation: 0.001,
+            max_rotation: 0.01,
+            max_scale: 0.001,
+        }
+    }
+}
+
+/// How far a bone's node is from where its offset matrix says the bone was bound.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoneDeviation {
+    /// The index of the bone in `Mesh::bones`.
+    pub bone_index: u32,
+    pub name: String,
+    /// The index of the bone's node in `Scene::node_arena`.
+    pub node: u32,
+    /// The deviation itself, which is the identity for a consistent bone. See
+    /// `Scene::check_bind_pose`.
+    pub deviation: Matrix4x4,
+    /// The length of the translation of `deviation`.
+    pub translation: f32,
+    /// The angle of the rotation of `deviation`, in radians.
+    pub rotation: f32,
+    /// The largest difference of the scale of `deviation` on any axis from 1.
+    pub scale: f32,
+    /// Whether any of the above is beyond its threshold.
+    pub flagged: bool,
+}
+
+/// A likely fix for the deviations in a `BindPoseReport`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum BindPoseSuggestion {
+    /// The bones are where the offset matrices put them, times `ratio`, which is what
+    /// happens when the nodes and the offsets are in different units. Scaling the scene on
+    /// import, as with `GltfOptions::apply_scene_scale`, or exporting it again with
+    /// consistent units should fix this.
+    ApplyGlobalScale { ratio: f32 },
+    /// The deviations have no common scale, or include rotations, which is typical of FBX
+    /// files with pivots. Importing with `Importer::fbx_preserve_pivots` enabled keeps the
+    /// pivots as extra nodes, which skinning takes into account.
+    PreservePivots,
+}
+
+/// The result of `Scene::check_bind_pose`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BindPoseReport {
+    pub mesh_index: u32,
+    /// One for each bone that has a node, in the order of `Mesh::bones`.
+    pub bones: Vec<BoneDeviation>,
+    /// The indices of the bones that have no node with the same name, which can't be
+    /// skinned at all.
+    pub missing_nodes: Vec<u32>,
+    /// Whether no bone is flagged or missing its node.
+    pub consistent: bool,
+    /// Empty if the bind pose is consistent.
+    pub suggestions: Vec<BindPoseSuggestion>,
+}
+
+impl BindPoseReport {
+    /// The bones that are beyond the thresholds.
+    pub fn flagged(&self) -> impl Iterator<Item = &BoneDeviation> + '_ {
+        self.bones.iter().filter(|bone| bone.flagged)
+    }
+}
+
+impl Scene<'_> {
+    /// Check the bind pose of a mesh with the default `BindPoseThresholds`, see
+    /// `check_bind_pose_with`.
+    pub fn check_bind_pose(&self, mesh_index: u32) -> Result<BindPoseReport, Error> {
+        self.check_bind_pose_with(mesh_index, &BindPoseThresholds::default())
+    }
+
+    /// Check that the offset matrix of each bone of a mesh agrees with the node hierarchy.
+    ///
+    /// A bone's offset matrix takes the mesh from its own space to the bone's space in the
+    /// bind pose, and the world transform of the bone's node takes it back to world space. In
+    /// the bind pose, the two together are the world transform of the node of the mesh, so
+    /// the deviation of a bone is `mesh_world.affine_inverse() * bone_world * offset`, which is
+    /// the identity if they agree. The mesh's node is the first one that references it, and if
+    /// there is none, the mesh is taken to be in world space.
+    ///
+    /// Returns `Error::IndexOutOfRange` if there's no mesh with that index, and
+    /// `Error::MissingData` if the mesh is null or its node's transform is singular.
+    pub fn check_bind_pose_with(
+        &self,
+        mesh_index: u32,
+        thresholds: &BindPoseThresholds,
+    ) -> Result<BindPoseReport, Error> {
+        let mesh = match self.mesh(mesh_index) {
+            Some(mesh) => mesh,
+            None if mesh_index < self.num_meshes() => {
+                return Err(Error::MissingData {
+                    what: format!("mesh {}", mesh_index),
+                })
+            }
+            None => {
+                return Err(Error::IndexOutOfRange {
+                    what: "meshes",
+                    index: mesh_index as usize,
+                    len: self.num_meshes() as usize,
+                })
+            }
+        };
+
+        let arena = self.node_arena();
+        let world = arena.world_transforms();
+        let mesh_world = arena
+            .records()
+            .iter()
+            .position(|record| record.mesh_indices.contains(&mesh_index))
+            .map_or_else(Matrix4x4::identity, |node| world[node]);
+        let mesh_inverse = mesh_world
+            .affine_inverse()
+            .ok_or_else(|| Error::MissingData {
+                what: format!(
+                    "an invertible transform for the node of mesh {}",
+                    mesh_index
+                ),
+            })?;
+
+        let mut bones = Vec::new();
+        let mut missing_nodes = Vec::new();
+        // The position of each flagged bone according to its node and its offset matrix, in
+        // the space of the mesh.
+        let mut positions = Vec::new();
+        for (bone_index, bone) in mesh.bones().enumerate() {
+            let bone_index = bone_index as u32;
+            let name = bone.name_cstr().to_string_lossy().into_owned();
+            let node = match arena.by_name(&name) {
+                Some(node) => node,
+                None => {
+                    missing_nodes.push(bone_index);
+                    continue;
+                }
+            };
+
+            let bone_in_mesh = mesh_inverse * world[node as usize];
+            let offset = bone.offset_matrix();
+            let deviation = bone_in_mesh * offset;
+            let (scaling, rotation, position) = deviation.decompose_assimp();
+            let rotation = rotation.normalize();
+            let translation = position.length();
+            let rotation = 2.0 * rotation.w.abs().min(1.0).acos();
+            let scale = [scaling.x, scaling.y, scaling.z]
+                .iter()
+                .map(|s| (s - 1.0).abs())
+                .fold(0.0, f32::max);
+            let flagged = translation > thresholds.max_translation
+                || rotation > thresholds.max_rotation
+                || scale > thresholds.max_scale;
+
+            if flagged {
+                if let Some(bound) = offset.affine_inverse() {
+                    positions.push((translation_of(&bone_in_mesh), translation_of(&bound)));
+                }
+            }
+            bones.push(BoneDeviation {
+                bone_index,
+                name,
+                node,
+                deviation,
+                translation,
+                rotation,
+                scale,
+                flagged,
+            });
+        }
+
+        let consistent = missing_nodes.is_empty() && bones.iter().all(|bone| !bone.flagged);
+        let mut suggestions = Vec::new();
+        if bones.iter().any(|bone| bone.flagged) {
+            let rotated = bones
+                .iter()
+                .any(|bone| bone.rotation > thresholds.max_rotation);
+            match common_ratio(&positions, thresholds.max_translation) {
+                Some(ratio) if !rotated && (ratio - 1.0).abs() > thresholds.max_scale => {
+                    suggestions.push(BindPoseSuggestion::ApplyGlobalScale { ratio })
+                }
+                _ => suggestions.push(BindPoseSuggestion::PreservePivots),
+            }
+        }
+
+        Ok(BindPoseReport {
+            mesh_index,
+            bones,
+            missing_nodes,
+            consistent,
+            suggestions,
+        })
+    }
+}
+
+fn translation_of(matrix: &Matrix4x4) -> Vector3D {
+    Vector3D::new(matrix.a4, matrix.b4, matrix.c4)
+}
+
+/// The ratio of the node positions to the bound positions in `positions`, if there's one ratio
+/// that takes every bound position to within `tolerance` of its node position, or a thousandth
+/// of the distance of the node from the origin if that's more. Bones bound at the origin say
+/// nothing about the ratio, but must also be at the origin.
+fn common_ratio(positions: &[(Vector3D, Vector3D)], tolerance: f32) -> Option<f32> {
+    let (node, bound) = positions.iter().copied().max_by(|a, b| {
+        a.1.length()
+            .partial_cmp(&b.1.length())
+            .unwrap_or(Ordering::Equal)
+    })?;
+    if bound.length() <= tolerance {
+        return None;
+    }
+    let ratio = node.dot(bound) / bound.dot(bound);
+    let fits = positions.iter().all(|&(node, bound)| {
+        (node - bound * ratio).length() <= tolerance.max(node.length() * 0.001)
+    });
+    if fits && ratio > 0.0 {
+        Some(ratio)
+    } else {
+        None
+    }
+}
@@ -1,8 +1,202 @@
-use ffi::aiCamera;
+use ffi::{aiCamera, aiMatrix4x4};
+
+use crate::math::{Matrix4x4, Vector3D};
+use std::str::Utf8Error;
 
 define_type_and_iterator_indirect! {
-    /// Camera type (not yet implemented)
+    /// A camera in the scene. The camera's position and orientation are relative to the node
+    /// with the same name as the camera, so you need to combine them with that node's transform
+    /// to get the camera's position in world space.
     struct Camera(&aiCamera)
     /// Camera iterator type.
     struct CameraIter
 }
+
+/// The kind of projection that a camera uses, along with the parameters needed to build a
+/// projection matrix from it.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum CameraProjection {
+    /// A perspective projection. `fov_y` is the _full_ vertical field of view in radians.
+    Perspective { fov_y: f32, aspect: f32 },
+    /// An orthographic projection. `width` is the _half_ width of the view volume, so the
+    /// visible area spans `-width..width` horizontally.
+    Orthographic { width: f32, aspect: f32 },
+}
+
+/// The range that depth values are mapped to in clip space. Different graphics APIs expect
+/// different conventions here, so `Camera::build_projection_matrix` lets you pick.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum DepthRange {
+    /// Depth is mapped to `-1..1`, as expected by OpenGL.
+    NegativeOneToOne,
+    /// Depth is mapped to `0..1`, as expected by Direct3D, Vulkan, Metal and wgpu.
+    ZeroToOne,
+}
+
+impl Camera {
+    /// Returns the name of the camera. This is also the name of the node that the camera is
+    /// attached to.
+    ///
+    /// Panics if the camera name isn't valid UTF-8, see `try_name`.
+    pub fn name(&self) -> &str {
+        self.try_name().expect("camera name is not valid UTF-8")
+    }
+
+    /// Like `name`, but returns an error if the camera name isn't valid UTF-8.
+    pub fn try_name(&self) -> Result<&str, Utf8Error> {
+        crate::aistring_to_str(&self.mName)
+    }
+
+    /// The position of the camera relative to its node.
+    pub fn position(&self) -> Vector3D {
+        Vector3D::from_raw(self.mPosition)
+    }
+
+    /// The "up" vector of the camera relative to its node. This is not necessarily normalized.
+    /// It's usually orthogonal to `look_at`, but Assimp doesn't guarantee that for every
+    /// importer, so orthogonalize it before building a view matrix from the two.
+    pub fn up(&self) -> Vector3D {
+        Vector3D::from_raw(self.mUp)
+    }
+
+    /// The direction the camera is looking in, relative to its node. This is not necessarily
+    /// normalized.
+    pub fn look_at(&self) -> Vector3D {
+        Vector3D::from_raw(self.mLookAt)
+    }
+
+    /// _Half_ of the horizontal field of view, in radians. This is how Assimp stores it, see
+    /// `projection` for the full vertical field of view.
+    pub fn horizontal_fov(&self) -> f32 {
+        self.mHorizontalFOV
+    }
+
+    /// The distance of the near clipping plane from the camera.
+    pub fn clip_plane_near(&self) -> f32 {
+        self.mClipPlaneNear
+    }
+
+    /// The distance of the far clipping plane from the camera.
+    pub fn clip_plane_far(&self) -> f32 {
+        self.mClipPlaneFar
+    }
+
+    /// The screen aspect ratio (width / height). Assimp uses `0` to mean that the file didn't
+    /// specify one, in which case this returns `None`.
+    pub fn aspect(&self) -> Option<f32> {
+        if self.mAspect == 0.0 {
+            None
+        } else {
+            Some(self.mAspect)
+        }
+    }
+
+    /// The half width of the orthographic view volume, if this is an orthographic camera.
+    /// Returns `None` for perspective cameras.
+    #[cfg(feature = "assimp-5-1")]
+    pub fn orthographic_width(&self) -> Option<f32> {
+        if self.mOrthographicWidth == 0.0 {
+            None
+        } else {
+            Some(self.mOrthographicWidth)
+        }
+    }
+
+    /// The half width of the orthographic view volume, if this is an orthographic camera.
+    /// Versions of Assimp before 5.1 don't support orthographic cameras, so without the
+    /// `assimp-5-1` feature this always returns `None`.
+    #[cfg(not(feature = "assimp-5-1"))]
+    pub fn orthographic_width(&self) -> Option<f32> {
+        None
+    }
+
+    /// The projection used by this camera. If the file doesn't specify an aspect ratio, `1.0`
+    /// is assumed.
+    pub fn projection(&self) -> CameraProjection {
+        let aspect = self.aspect().unwrap_or(1.0);
+
+        match self.orthographic_width() {
+            Some(width) => CameraProjection::Orthographic { width, aspect },
+            None => CameraProjection::Perspective {
+                fov_y: 2.0 * (self.horizontal_fov().tan() / aspect).atan(),
+                aspect,
+            },
+        }
+    }
+
+    /// Build a projection matrix for this camera, optionally overriding the clipping planes
+    /// stored in the file.
+    ///
+    /// The matrix follows the same conventions as Assimp itself: right-handed, with the camera
+    /// looking down -Z in view space. After the perspective divide, X and Y are mapped to
+    /// `-1..1` and Z is mapped to the range given by `depth_range`.
+    pub fn build_projection_matrix(
+        &self,
+        near_override: Option<f32>,
+        far_override: Option<f32>,
+        depth_range: DepthRange,
+    ) -> Matrix4x4 {
+        let near = near_override.unwrap_or(self.mClipPlaneNear);
+        let far = far_override.unwrap_or(self.mClipPlaneFar);
+
+        let mut out = aiMatrix4x4 {
+            a1: 0.0,
+            a2: 0.0,
+            a3: 0.0,
+            a4: 0.0,
+            b1: 0.0,
+            b2: 0.0,
+            b3: 0.0,
+            b4: 0.0,
+            c1: 0.0,
+            c2: 0.0,
+            c3: 0.0,
+            c4: 0.0,
+            d1: 0.0,
+            d2: 0.0,
+            d3: 0.0,
+            d4: 0.0,
+        };
+
+        match self.projection() {
+            CameraProjection::Perspective { fov_y, aspect } => {
+                let f = 1.0 / (fov_y / 2.0).tan();
+
+                out.a1 = f / aspect;
+                out.b2 = f;
+                out.d3 = -1.0;
+
+                match depth_range {
+                    DepthRange::NegativeOneToOne => {
+                        out.c3 = (far + near) / (near - far);
+                        out.c4 = 2.0 * far * near / (near - far);
+                    }
+                    DepthRange::ZeroToOne => {
+                        out.c3 = far / (near - far);
+                        out.c4 = far * near / (near - far);
+                    }
+                }
+            }
+            CameraProjection::Orthographic { width, aspect } => {
+                let height = width / aspect;
+
+                out.a1 = 1.0 / width;
+                out.b2 = 1.0 / height;
+                out.d4 = 1.0;
+
+                match depth_range {
+                    DepthRange::NegativeOneToOne => {
+                        out.c3 = -2.0 / (far - near);
+                        out.c4 = -(far + near) / (far - near);
+                    }
+                    DepthRange::ZeroToOne => {
+                        out.c3 = -1.0 / (far - near);
+                        out.c4 = -near / (far - near);
+                    }
+                }
+            }
+        }
+
+        Matrix4x4::from_raw(out)
+    }
+}
@@ -0,0 +1,258 @@
+use std::cmp::Ordering;
+
+use super::diff::material_name;
+use super::hash::{hash_material, StableHasher};
+use super::scene::Scene;
+use super::snapshot::{AnimationData, MeshData, NodeData, SceneSnapshot};
+
+/// The epsilon used for the content hashes that break ties, the same as the default for
+/// `Scene::content_hash`.
+const EPSILON: f32 = 1e-5;
+
+/// A stable order for the meshes, materials and animations of a scene, as returned by
+/// `Scene::canonical_order`. Assimp doesn't guarantee the order of these arrays, which can
+/// change between versions, so this is useful for anything that needs reproducible output.
+///
+/// Each list is a permutation: `meshes[i]` is the current index of the mesh that should be
+/// at index `i`, and likewise for the others.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CanonicalOrdering {
+    /// Meshes, sorted by name, then vertex count, then a hash of their contents.
+    pub meshes: Vec<u32>,
+    /// Materials, sorted by name, then a hash of their properties.
+    pub materials: Vec<u32>,
+    /// Animations, sorted by name, then a hash of their contents.
+    pub animations: Vec<u32>,
+}
+
+impl Scene<'_> {
+    /// Compute a stable order for the meshes, materials and animations of the scene, which
+    /// doesn't depend on the order that Assimp produced them in. Use
+    /// `CanonicalOrdering::apply` to reorder a snapshot of the scene.
+    pub fn canonical_order(&self) -> CanonicalOrdering {
+        let meshes: Vec<MeshData> = self.meshes().map(MeshData::from_mesh).collect();
+        let animations: Vec<AnimationData> = self
+            .animations()
+            .map(AnimationData::from_animation)
+            .collect();
+        let materials: Vec<(String, u64)> = self
+            .materials()
+            .map(|material| (material_name(material), hash_material(material, EPSILON)))
+            .collect();
+
+        CanonicalOrdering {
+            meshes: mesh_order(&meshes),
+            materials: sorted_indices(&materials, |a, b| a.cmp(b)),
+            animations: animation_order(&animations),
+        }
+    }
+}
+
+impl SceneSnapshot {
+    /// Like `Scene::canonical_order`, but computed from the snapshot. Snapshots don't include
+    /// the properties of materials, so the material order is left as it is.
+    ///
+    /// The result is the same as for the scene the snapshot was taken from, except for the
+    /// materials.
+    pub fn canonical_order(&self) -> CanonicalOrdering {
+        let num_materials = self
+            .meshes
+            .iter()
+            .map(|mesh| mesh.material_index + 1)
+            .max()
+            .unwrap_or(0)
+            .max(self.materials.len() as u32);
+
+        CanonicalOrdering {
+            meshes: mesh_order(&self.meshes),
+            materials: (0..num_materials).collect(),
+            animations: animation_order(&self.animations),
+        }
+    }
+}
+
+impl CanonicalOrdering {
+    /// For each current mesh index, the index of the mesh once reordered.
+    pub fn mesh_remap(&self) -> Vec<u32> {
+        invert(&self.meshes)
+    }
+
+    /// For each current material index, the index of the material once reordered.
+    pub fn material_remap(&self) -> Vec<u32> {
+        invert(&self.materials)
+    }
+
+    /// For each current animation index, the index of the animation once reordered.
+    pub fn animation_remap(&self) -> Vec<u32> {
+        invert(&self.animations)
+    }
+
+    /// Reorder the meshes, materials and animations of `snapshot`, and rewrite the mesh indices
+    /// of its nodes and the material indices of its meshes to match. The materials are only
+    /// reordered if the snapshot has as many as the ordering, so snapshots without materials
+    /// can still be reordered.
+    ///
+    /// Panics if the snapshot doesn't have the same number of meshes and animations as the
+    /// scene this ordering was computed for.
+    pub fn apply(&self, snapshot: &mut SceneSnapshot) {
+        assert_eq!(
+            snapshot.meshes.len(),
+            self.meshes.len(),
+            "the ordering is for a scene with a different number of meshes"
+        );
+        assert_eq!(
+            snapshot.animations.len(),
+            self.animations.len(),
+            "the ordering is for a scene with a different number of animations"
+        );
+
+        snapshot.meshes = permute(&snapshot.meshes, &self.meshes);
+        snapshot.animations = permute(&snapshot.animations, &self.animations);
+        if snapshot.materials.len() == self.materials.len() {
+            snapshot.materials = permute(&snapshot.materials, &self.materials);
+        }
+
+        let material_remap = self.material_remap();
+        for mesh in &mut snapshot.meshes {
+            mesh.remap_material(&material_remap);
+        }
+
+        if let Some(root) = &mut snapshot.root {
+            root.remap_meshes(&self.mesh_remap());
+        }
+    }
+}
+
+impl NodeData {
+    /// Rewrite the mesh indices of this node and all of its descendants, where `remap[old]`
+    /// is the new index. Indices that `remap` doesn't cover are left unchanged.
+    pub fn remap_meshes(&mut self, remap: &[u32]) {
+        self.visit_mut(&mut |node| {
+            for mesh in &mut node.meshes {
+                *mesh = remap.get(*mesh as usize).cloned().unwrap_or(*mesh);
+            }
+        });
+    }
+}
+
+impl MeshData {
+    /// Rewrite the material index of this mesh, where `remap[old]` is the new index. Indices
+    /// that `remap` doesn't cover are left unchanged.
+    pub fn remap_material(&mut self, remap: &[u32]) {
+        if let Some(&index) = remap.get(self.material_index as usize) {
+            self.material_index = index;
+        }
+    }
+}
+
+fn mesh_order(meshes: &[MeshData]) -> Vec<u32> {
+    let keys: Vec<(&str, usize, u64)> = meshes
+        .iter()
+        .map(|mesh| (mesh.name.as_str(), mesh.positions.len(), hash_mesh(mesh)))
+        .collect();
+
+    sorted_indices(&keys, |a, b| a.cmp(b))
+}
+
+fn animation_order(animations: &[AnimationData]) -> Vec<u32> {
+    let keys: Vec<(&str, u64)> = animations
+        .iter()
+        .map(|animation| (animation.name.as_str(), hash_animation(animation)))
+        .collect();
+
+    sorted_indices(&keys, |a, b| a.cmp(b))
+}
+
+/// The indices of `keys` in sorted order. Equal keys keep their current order, which only
+/// matters for elements that are identical anyway.
+fn sorted_indices<T, F: Fn(&T, &T) -> Ordering>(keys: &[T], compare: F) -> Vec<u32> {
+    let mut indices: Vec<u32> = (0..keys.len() as u32).collect();
+    indices.sort_by(|&a, &b| compare(&keys[a as usize], &keys[b as usize]));
+    indices
+}
+
+fn invert(permutation: &[u32]) -> Vec<u32> {
+    let mut inverse = vec![0; permutation.len()];
+    for (new, &old) in permutation.iter().enumerate() {
+        inverse[old as usize] = new as u32;
+    }
+    inverse
+}
+
+fn permute<T: Clone>(items: &[T], order: &[u32]) -> Vec<T> {
+    order
+        .iter()
+        .map(|&old| items[old as usize].clone())
+        .collect()
+}
+
+/// A hash of everything in a mesh except its material, so that reordering materials doesn't
+/// change the order of meshes.
+fn hash_mesh(mesh: &MeshData) -> u64 {
+    let mut hasher = StableHasher::new(EPSILON);
+
+    hasher.str(&mesh.name);
+    for attribute in &[
+        &mesh.positions,
+        &mesh.normals,
+        &mesh.tangents,
+        &mesh.bitangents,
+    ] {
+        hasher.u64(attribute.len() as u64);
+        attribute.iter().for_each(|&v| hasher.vector(v));
+    }
+
+    for set in &mesh.colors {
+        for color in set.iter().flatten() {
+            hasher.f32(color.r);
+            hasher.f32(color.g);
+            hasher.f32(color.b);
+            hasher.f32(color.a);
+        }
+    }
+    for channel in &mesh.texture_coords {
+        channel.iter().flatten().for_each(|&v| hasher.vector(v));
+    }
+
+    hasher.u64(mesh.faces.len() as u64);
+    for face in &mesh.faces {
+        hasher.u32(face.len() as u32);
+        face.iter().for_each(|&index| hasher.u32(index));
+    }
+
+    for bone in &mesh.bones {
+        hasher.str(&bone.name);
+        hasher.matrix(&bone.offset_matrix);
+        for &(vertex, weight) in &bone.weights {
+            hasher.u32(vertex);
+            hasher.f32(weight);
+        }
+    }
+
+    hasher.finish()
+}
+
+fn hash_animation(animation: &AnimationData) -> u64 {
+    let mut hasher = StableHasher::new(EPSILON);
+
+    hasher.str(&animation.name);
+    hasher.f64(animation.duration);
+    hasher.f64(animation.ticks_per_second);
+
+    for channel in &animation.channels {
+        hasher.str(&channel.node_name);
+        for &(time, value) in channel.position_keys.iter().chain(&channel.scaling_keys) {
+            hasher.f64(time);
+            hasher.vector(value);
+        }
+        for &(time, q) in &channel.rotation_keys {
+            hasher.f64(time);
+            hasher.f32(q.w);
+            hasher.f32(q.x);
+            hasher.f32(q.y);
+            hasher.f32(q.z);
+        }
+    }
+
+    hasher.finish()
+}
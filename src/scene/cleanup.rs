@@ -0,0 +1,172 @@
+//! Detection and removal of degenerate and duplicate faces in owned meshes.
+//!
+//! Assimp's `find_degenerates` step only runs during import. These functions do similar checks
+//! on a `MeshData`, e.g. after processing that collapsed some faces. They never panic, including
+//! on empty meshes and faces with indices out of range, and only depend on the mesh, so the
+//! results are the same on every run.
+
+use std::collections::HashSet;
+
+use crate::math::Vector3D;
+
+use super::snapshot::MeshData;
+
+/// Whether `find_duplicate_faces` considers the winding order of faces.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Winding {
+    /// Faces are duplicates if their indices are the same after rotating them, so
+    /// `[0, 1, 2]` and `[1, 2, 0]` are duplicates but `[0, 1, 2]` and `[2, 1, 0]`, which face
+    /// the other way, aren't.
+    Respect,
+    /// Faces are also duplicates if their indices are the same in reverse.
+    Ignore,
+}
+
+/// What `remove_faces` does with vertices that no face uses any more.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum UnusedVertices {
+    Keep,
+    /// Remove them, along with their normals, colors, UVs and bone weights.
+    Drop,
+}
+
+/// The indices of the faces of `mesh` that are degenerate: faces that use a vertex more than
+/// once, polygons with an area of at most `area_epsilon`, and faces without indices or with
+/// indices out of range. Points and lines have no area, so they only count if they repeat an
+/// index.
+pub fn find_degenerate_faces(mesh: &MeshData, area_epsilon: f32) -> Vec<u32> {
+    let mut degenerate = Vec::new();
+
+    for (index, face) in mesh.faces.iter().enumerate() {
+        let out_of_range = face.iter().any(|&i| i as usize >= mesh.positions.len());
+        let repeated = face
+            .iter()
+            .enumerate()
+            .any(|(k, i)| face[k + 1..].contains(i));
+        let flat = face.len() >= 3 && !out_of_range && area(&mesh.positions, face) <= area_epsilon;
+
+        if face.is_empty() || out_of_range || repeated || flat {
+            degenerate.push(index as u32);
+        }
+    }
+
+    degenerate
+}
+
+/// The indices of the faces of `mesh` that use the same vertices as an earlier face, in the
+/// same cyclic order or, depending on `winding`, in reverse. The first of each set of
+/// duplicates isn't included, so removing the returned faces keeps one of each.
+pub fn find_duplicate_faces(mesh: &MeshData, winding: Winding) -> Vec<u32> {
+    let mut seen = HashSet::new();
+    let mut duplicates = Vec::new();
+
+    for (index, face) in mesh.faces.iter().enumerate() {
+        let mut key = min_rotation(face.clone());
+        if winding == Winding::Ignore {
+            key = key.min(min_rotation(face.iter().rev().copied().collect()));
+        }
+        if !seen.insert(key) {
+            duplicates.push(index as u32);
+        }
+    }
+
+    duplicates
+}
+
+/// Remove the faces at `faces` from `mesh`, keeping the order of the others. Indices that are
+/// out of range or repeated are ignored.
+///
+/// Returns the original index of every vertex that's left, which is all of them unless
+/// `unused` is `UnusedVertices::Drop`. Dropped vertices are removed from every vertex
+/// attribute and bone, and the remaining faces are renumbered; indices that were out of range
+/// become `u32::MAX`.
+pub fn remove_faces(mesh: &mut MeshData, faces: &[u32], unused: UnusedVertices) -> Vec<u32> {
+    let remove: HashSet<u32> = faces.iter().copied().collect();
+    let mut index = 0;
+    mesh.faces.retain(|_| {
+        let keep = !remove.contains(&index);
+        index += 1;
+        keep
+    });
+
+    let num_vertices = mesh.positions.len();
+    if unused == UnusedVertices::Keep {
+        return (0..num_vertices as u32).collect();
+    }
+
+    let mut used = vec![false; num_vertices];
+    for &i in mesh.faces.iter().flatten() {
+        if let Some(used) = used.get_mut(i as usize) {
+            *used = true;
+        }
+    }
+    let kept: Vec<u32> = (0..num_vertices as u32)
+        .filter(|&i| used[i as usize])
+        .collect();
+    let mut new_index = vec![u32::MAX; num_vertices];
+    for (new, &old) in kept.iter().enumerate() {
+        new_index[old as usize] = new as u32;
+    }
+
+    compact(&mut mesh.positions, &kept);
+    compact(&mut mesh.normals, &kept);
+    compact(&mut mesh.tangents, &kept);
+    compact(&mut mesh.bitangents, &kept);
+    for colors in mesh.colors.iter_mut().flatten() {
+        compact(colors, &kept);
+    }
+    for uvs in mesh.texture_coords.iter_mut().flatten() {
+        compact(uvs, &kept);
+    }
+    for bone in &mut mesh.bones {
+        bone.weights = bone
+            .weights
+            .iter()
+            .filter_map(|&(vertex, weight)| {
+                let vertex = *new_index.get(vertex as usize)?;
+                if vertex == u32::MAX {
+                    None
+                } else {
+                    Some((vertex, weight))
+                }
+            })
+            .collect();
+    }
+    for i in mesh.faces.iter_mut().flatten() {
+        *i = new_index.get(*i as usize).copied().unwrap_or(u32::MAX);
+    }
+
+    kept
+}
+
+/// Keep the values at `kept`, in that order. Values past the end are skipped.
+fn compact<T: Copy>(values: &mut Vec<T>, kept: &[u32]) {
+    *values = kept
+        .iter()
+        .filter_map(|&i| values.get(i as usize).copied())
+        .collect();
+}
+
+/// The area of a polygon whose indices are all in range.
+fn area(positions: &[Vector3D], face: &[u32]) -> f32 {
+    let mut normal = Vector3D::new(0.0, 0.0, 0.0);
+    for (k, &a) in face.iter().enumerate() {
+        let b = face[(k + 1) % face.len()];
+        normal = normal + positions[a as usize].cross(positions[b as usize]);
+    }
+    normal.length() / 2.0
+}
+
+/// The lexicographically smallest rotation of a face.
+fn min_rotation(face: Vec<u32>) -> Vec<u32> {
+    (0..face.len())
+        .map(|start| {
+            face[start..]
+                .iter()
+                .chain(&face[..start])
+                .copied()
+                .collect()
+        })
+        .min()
+        .unwrap_or(face)
+}
@@ -0,0 +1,712 @@
+//! Simple collision shapes for imported geometry: convex hulls, bounding spheres and oriented
+//! bounding boxes.
+//!
+//! Every function takes anything that is `AsRef<[Vector3D]>`: a `Mesh`, whose positions are
+//! used, or a slice or `Vec` of points, such as the result of `Mesh::positions_transformed`
+//! for a shape in world space. The calculations are done in `f64` and the results are
+//! converted back to `f32`.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+use crate::math::Vector3D;
+
+/// A convex hull, as returned by `compute_convex_hull`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HullMesh {
+    /// The input points that are corners of the hull, each once.
+    pub positions: Vec<Vector3D>,
+    /// Triangles as indices into `positions`, three per triangle, wound counter-clockwise when
+    /// seen from outside the hull.
+    pub indices: Vec<u32>,
+    /// The outward unit normal of each triangle.
+    pub normals: Vec<Vector3D>,
+}
+
+impl HullMesh {
+    /// The number of triangles of the hull.
+    pub fn num_triangles(&self) -> usize {
+        self.indices.len() / 3
+    }
+}
+
+/// A sphere that contains a set of points, see `compute_bounding_sphere`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BoundingSphere {
+    pub center: Vector3D,
+    pub radius: f32,
+}
+
+/// A box that contains a set of points, see `compute_obb`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct OrientedBox {
+    pub center: Vector3D,
+    /// The unit axes of the box, ordered from the direction the points vary the most in to
+    /// the one they vary the least in. They are orthogonal and right-handed.
+    pub axes: [Vector3D; 3],
+    /// Half the size of the box along each of `axes`. This is zero along an axis if the points
+    /// are flat in that direction.
+    pub half_extents: Vector3D,
+}
+
+/// Why a collision shape couldn't be computed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CollisionError {
+    /// There are no points.
+    Empty,
+    /// A coordinate of the point at `index` is infinite or NaN.
+    NonFinite { index: usize },
+    /// All points are at the same position, so they have no hull.
+    Coincident,
+    /// All points lie on a line, so they have no hull.
+    Collinear,
+    /// All points lie in a plane, so they have no hull. Fewer than 4 distinct points are always
+    /// coplanar or one of the above.
+    Coplanar,
+}
+
+impl fmt::Display for CollisionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CollisionError::Empty => write!(f, "there are no points"),
+            CollisionError::NonFinite { index } => {
+                write!(f, "point {} has a coordinate that isn't finite", index)
+            }
+            CollisionError::Coincident => write!(f, "all points are at the same position"),
+            CollisionError::Collinear => write!(f, "all points lie on a line"),
+            CollisionError::Coplanar => write!(f, "all points lie in a plane"),
+        }
+    }
+}
+
+impl Error for CollisionError {}
+
+type Point = [f64; 3];
+
+/// Compute the convex hull of `points` with the quickhull algorithm.
+///
+/// Points closer to the surface of the hull than a small tolerance, which grows with the size
+/// of the input, are treated as inside it, so points in the middle of a face or an edge aren't
+/// corners. Faces where several corners are coplanar, such as the sides of a box, are split
+/// into triangles.
+///
+/// Returns `CollisionError::Coincident`, `Collinear` or `Coplanar` if the points don't span
+/// a volume, since their hull would have no inside.
+pub fn compute_convex_hull<P>(points: &P) -> Result<HullMesh, CollisionError>
+where
+    P: AsRef<[Vector3D]> + ?Sized,
+{
+    let points = to_f64(points.as_ref())?;
+    let eps = tolerance(&points);
+    let mut hull = Quickhull::new(&points, eps)?;
+    hull.run();
+
+    // A point in the middle of a face or edge becomes a vertex if it's added before the corners
+    // around it. Those are dropped by building the hull again from the real corners only.
+    let corners = hull.corners();
+    if corners.len() < hull.num_vertices() {
+        let corners: Vec<Point> = corners.into_iter().map(|i| points[i]).collect();
+        if let Ok(mut simplified) = Quickhull::new(&corners, eps) {
+            simplified.run();
+            return Ok(simplified.finish());
+        }
+    }
+    Ok(hull.finish())
+}
+
+/// Compute the smallest sphere that contains all `points`, with Welzl's algorithm.
+///
+/// The points are visited in a fixed pseudo-random order, so the result is deterministic.
+pub fn compute_bounding_sphere<P>(points: &P) -> Result<BoundingSphere, CollisionError>
+where
+    P: AsRef<[Vector3D]> + ?Sized,
+{
+    let mut points = to_f64(points.as_ref())?;
+    shuffle(&mut points);
+
+    let sphere = welzl(&points);
+    Ok(BoundingSphere {
+        center: to_vector(sphere.center),
+        radius: sphere.radius() as f32,
+    })
+}
+
+/// Compute an oriented box that contains all `points`, with its axes along the principal
+/// components of the points, i.e. the eigenvectors of their covariance matrix.
+///
+/// This is fast and fits most shapes well, but isn't the smallest possible box. Since every point
+/// contributes to the covariance, a dense cluster of vertices pulls the axes towards itself;
+/// passing the `positions` of `compute_convex_hull` instead avoids that. Points that are flat
+/// or on a line give a box with zero `half_extents` along the missing directions.
+pub fn compute_obb<P>(points: &P) -> Result<OrientedBox, CollisionError>
+where
+    P: AsRef<[Vector3D]> + ?Sized,
+{
+    let points = to_f64(points.as_ref())?;
+    let n = points.len() as f64;
+
+    let mean = scale(points.iter().fold([0.0; 3], |sum, &p| add(sum, p)), 1.0 / n);
+    let mut covariance = [[0.0; 3]; 3];
+    for &p in &points {
+        let d = sub(p, mean);
+        for i in 0..3 {
+            for j in 0..3 {
+                covariance[i][j] += d[i] * d[j] / n;
+            }
+        }
+    }
+
+    let axes = principal_axes(covariance);
+    let mut center = mean;
+    let mut half_extents = [0.0; 3];
+    for (k, &axis) in axes.iter().enumerate() {
+        let (min, max) = points.iter().fold((f64::MAX, f64::MIN), |(min, max), &p| {
+            let t = dot(sub(p, mean), axis);
+            (min.min(t), max.max(t))
+        });
+        center = add(center, scale(axis, (min + max) / 2.0));
+        half_extents[k] = (max - min) / 2.0;
+    }
+
+    Ok(OrientedBox {
+        center: to_vector(center),
+        axes: [to_vector(axes[0]), to_vector(axes[1]), to_vector(axes[2])],
+        half_extents: to_vector(half_extents),
+    })
+}
+
+fn to_f64(points: &[Vector3D]) -> Result<Vec<Point>, CollisionError> {
+    if points.is_empty() {
+        return Err(CollisionError::Empty);
+    }
+
+    points
+        .iter()
+        .enumerate()
+        .map(|(index, p)| {
+            if p.x.is_finite() && p.y.is_finite() && p.z.is_finite() {
+                Ok([p.x as f64, p.y as f64, p.z as f64])
+            } else {
+                Err(CollisionError::NonFinite { index })
+            }
+        })
+        .collect()
+}
+
+fn to_vector(p: Point) -> Vector3D {
+    Vector3D::new(p[0] as f32, p[1] as f32, p[2] as f32)
+}
+
+/// How far a point has to be from a plane or line to count as off it. The input was `f32`, so
+/// this is relative to `f32` precision at the scale of the points.
+fn tolerance(points: &[Point]) -> f64 {
+    let mut max = [0.0f64; 3];
+    for p in points {
+        for k in 0..3 {
+            max[k] = max[k].max(p[k].abs());
+        }
+    }
+    (max[0] + max[1] + max[2]).max(f64::MIN_POSITIVE) * f32::EPSILON as f64 * 4.0
+}
+
+fn add(a: Point, b: Point) -> Point {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+fn sub(a: Point, b: Point) -> Point {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn scale(a: Point, factor: f64) -> Point {
+    [a[0] * factor, a[1] * factor, a[2] * factor]
+}
+
+fn dot(a: Point, b: Point) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: Point, b: Point) -> Point {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn length(a: Point) -> f64 {
+    dot(a, a).sqrt()
+}
+
+fn normalize(a: Point) -> Point {
+    let len = length(a);
+    if len == 0.0 {
+        a
+    } else {
+        scale(a, 1.0 / len)
+    }
+}
+
+/// The distance from `p` to the line through `a` and `b`.
+fn line_distance(a: Point, b: Point, p: Point) -> f64 {
+    let dir = normalize(sub(b, a));
+    length(cross(sub(p, a), dir))
+}
+
+/// Index of the point in `points` that maximises `key`.
+fn max_by(points: &[Point], key: impl Fn(Point) -> f64) -> usize {
+    let mut best = 0;
+    let mut best_key = f64::MIN;
+    for (i, &p) in points.iter().enumerate() {
+        let k = key(p);
+        if k > best_key {
+            best = i;
+            best_key = k;
+        }
+    }
+    best
+}
+
+#[derive(Clone)]
+struct HullFace {
+    vertices: [usize; 3],
+    normal: Point,
+    offset: f64,
+    /// The points above this face that haven't been added to the hull yet.
+    outside: Vec<usize>,
+    alive: bool,
+}
+
+impl HullFace {
+    fn distance(&self, p: Point) -> f64 {
+        dot(self.normal, p) - self.offset
+    }
+}
+
+struct Quickhull<'a> {
+    points: &'a [Point],
+    eps: f64,
+    faces: Vec<HullFace>,
+    /// The face of each directed edge of every live face.
+    edges: HashMap<(usize, usize), usize>,
+}
+
+impl<'a> Quickhull<'a> {
+    /// Start with a tetrahedron of extreme points, or fail if there isn't one.
+    fn new(points: &'a [Point], eps: f64) -> Result<Self, CollisionError> {
+        // The pair of extreme points along an axis that are farthest apart.
+        let mut best = (0, 0, -1.0);
+        for k in 0..3 {
+            let min = max_by(points, |p| -p[k]);
+            let max = max_by(points, |p| p[k]);
+            let dist = length(sub(points[max], points[min]));
+            if dist > best.2 {
+                best = (min, max, dist);
+            }
+        }
+        let (a, b, dist) = best;
+        if dist <= eps {
+            return Err(CollisionError::Coincident);
+        }
+
+        let c = max_by(points, |p| line_distance(points[a], points[b], p));
+        if line_distance(points[a], points[b], points[c]) <= eps {
+            return Err(CollisionError::Collinear);
+        }
+
+        let normal = normalize(cross(sub(points[b], points[a]), sub(points[c], points[a])));
+        let plane = |p: Point| dot(normal, sub(p, points[a]));
+        let d = max_by(points, |p| plane(p).abs());
+        if plane(points[d]).abs() <= eps {
+            return Err(CollisionError::Coplanar);
+        }
+
+        let mut hull = Quickhull {
+            points,
+            eps,
+            faces: Vec::new(),
+            edges: HashMap::new(),
+        };
+        // Wind the base so that `d` is behind it, and the sides around it to match.
+        let (b, c) = if plane(points[d]) > 0.0 {
+            (c, b)
+        } else {
+            (b, c)
+        };
+        for &vertices in &[[a, b, c], [a, d, b], [b, d, c], [c, d, a]] {
+            hull.add_face(vertices);
+        }
+
+        let live: Vec<usize> = (0..hull.faces.len()).collect();
+        let all: Vec<usize> = (0..points.len())
+            .filter(|&i| ![a, b, c, d].contains(&i))
+            .collect();
+        hull.assign(all, &live);
+        Ok(hull)
+    }
+
+    fn add_face(&mut self, vertices: [usize; 3]) -> usize {
+        let [a, b, c] = vertices;
+        let (pa, pb, pc) = (self.points[a], self.points[b], self.points[c]);
+        let normal = normalize(cross(sub(pb, pa), sub(pc, pa)));
+
+        let index = self.faces.len();
+        self.faces.push(HullFace {
+            vertices,
+            normal,
+            offset: dot(normal, pa),
+            outside: Vec::new(),
+            alive: true,
+        });
+        for k in 0..3 {
+            self.edges
+                .insert((vertices[k], vertices[(k + 1) % 3]), index);
+        }
+        index
+    }
+
+    /// Move each of `points` to the outside set of the first of `faces` it's above. The others
+    /// are inside the hull and aren't needed any more.
+    fn assign(&mut self, points: Vec<usize>, faces: &[usize]) {
+        for i in points {
+            let p = self.points[i];
+            if let Some(&face) = faces
+                .iter()
+                .find(|&&face| self.faces[face].distance(p) > self.eps)
+            {
+                self.faces[face].outside.push(i);
+            }
+        }
+    }
+
+    fn run(&mut self) {
+        let mut pending: Vec<usize> = (0..self.faces.len()).collect();
+
+        while let Some(start) = pending.pop() {
+            let face = &self.faces[start];
+            if !face.alive || face.outside.is_empty() {
+                continue;
+            }
+            let eye = *face
+                .outside
+                .iter()
+                .max_by(|&&i, &&j| {
+                    let (di, dj) = (face.distance(self.points[i]), face.distance(self.points[j]));
+                    di.partial_cmp(&dj).unwrap()
+                })
+                .unwrap();
+            let eye_point = self.points[eye];
+
+            // Find the faces that can see the eye, spreading out from `start` so that they stay
+            // connected, and the edges around them.
+            let mut visible = vec![start];
+            let mut seen: HashMap<usize, bool> = HashMap::new();
+            seen.insert(start, true);
+            let mut horizon = Vec::new();
+            let mut next = 0;
+            while next < visible.len() {
+                let vertices = self.faces[visible[next]].vertices;
+                next += 1;
+                for k in 0..3 {
+                    let (a, b) = (vertices[k], vertices[(k + 1) % 3]);
+                    // Every edge of the hull has a face on both sides.
+                    let neighbour = match self.edges.get(&(b, a)) {
+                        Some(&neighbour) => neighbour,
+                        None => continue,
+                    };
+                    let faces = &self.faces;
+                    let eps = self.eps;
+                    let is_visible = *seen
+                        .entry(neighbour)
+                        .or_insert_with(|| faces[neighbour].distance(eye_point) > eps);
+                    if !is_visible {
+                        horizon.push((a, b));
+                    } else if !visible.contains(&neighbour) {
+                        visible.push(neighbour);
+                    }
+                }
+            }
+
+            let mut orphans = Vec::new();
+            for &face in &visible {
+                let face = &mut self.faces[face];
+                face.alive = false;
+                orphans.append(&mut face.outside);
+                let vertices = face.vertices;
+                for k in 0..3 {
+                    self.edges.remove(&(vertices[k], vertices[(k + 1) % 3]));
+                }
+            }
+            orphans.retain(|&i| i != eye);
+
+            let new_faces: Vec<usize> = horizon
+                .into_iter()
+                .map(|(a, b)| self.add_face([a, b, eye]))
+                .collect();
+            self.assign(orphans, &new_faces);
+            pending.extend(new_faces);
+        }
+    }
+
+    fn num_vertices(&self) -> usize {
+        let mut vertices: Vec<usize> = self
+            .live_faces()
+            .flat_map(|face| face.vertices.to_vec())
+            .collect();
+        vertices.sort_unstable();
+        vertices.dedup();
+        vertices.len()
+    }
+
+    /// The vertices whose faces aren't all in one or two planes, in the order of `points`.
+    fn corners(&self) -> Vec<usize> {
+        let mut normals: HashMap<usize, Vec<Point>> = HashMap::new();
+        for face in self.live_faces() {
+            for &vertex in &face.vertices {
+                normals.entry(vertex).or_default().push(face.normal);
+            }
+        }
+
+        // The sine of the angle between planes that are treated as the same.
+        const ANGLE: f64 = 1e-5;
+        let mut corners: Vec<usize> = normals
+            .into_iter()
+            .filter(|(_, normals)| {
+                let first = normals[0];
+                let edge = match normals
+                    .iter()
+                    .map(|&n| cross(first, n))
+                    .find(|&c| length(c) > ANGLE)
+                {
+                    Some(edge) => normalize(edge),
+                    None => return false,
+                };
+                normals.iter().any(|&n| dot(n, edge).abs() > ANGLE)
+            })
+            .map(|(vertex, _)| vertex)
+            .collect();
+        corners.sort_unstable();
+        corners
+    }
+
+    fn live_faces(&self) -> impl Iterator<Item = &HullFace> {
+        self.faces.iter().filter(|face| face.alive)
+    }
+
+    fn finish(self) -> HullMesh {
+        let mut remap = HashMap::new();
+        let mut hull = HullMesh {
+            positions: Vec::new(),
+            indices: Vec::new(),
+            normals: Vec::new(),
+        };
+
+        for face in self.live_faces() {
+            for &vertex in &face.vertices {
+                let points = self.points;
+                let positions = &mut hull.positions;
+                let index = *remap.entry(vertex).or_insert_with(|| {
+                    positions.push(to_vector(points[vertex]));
+                    positions.len() as u32 - 1
+                });
+                hull.indices.push(index);
+            }
+            hull.normals.push(to_vector(face.normal));
+        }
+        hull
+    }
+}
+
+#[derive(Copy, Clone)]
+struct Sphere {
+    center: Point,
+    radius_squared: f64,
+}
+
+impl Sphere {
+    fn radius(&self) -> f64 {
+        self.radius_squared.sqrt()
+    }
+
+    fn contains(&self, p: Point) -> bool {
+        let d = sub(p, self.center);
+        // Allow for the rounding error of the calculated center and radius.
+        let radius = self.radius() * (1.0 + 1e-9) + 1e-12;
+        dot(d, d) <= radius * radius
+    }
+
+    fn point(a: Point) -> Sphere {
+        Sphere {
+            center: a,
+            radius_squared: 0.0,
+        }
+    }
+
+    fn from_center(center: Point, on: Point) -> Sphere {
+        let d = sub(on, center);
+        Sphere {
+            center,
+            radius_squared: dot(d, d),
+        }
+    }
+
+    fn diameter(a: Point, b: Point) -> Sphere {
+        Sphere::from_center(scale(add(a, b), 0.5), a)
+    }
+
+    /// The smallest sphere through `a`, `b` and `c`, or the sphere around the two farthest
+    /// apart if they're on a line.
+    fn circle(a: Point, b: Point, c: Point) -> Sphere {
+        let (ab, ac) = (sub(b, a), sub(c, a));
+        let normal = cross(ab, ac);
+        let denominator = 2.0 * dot(normal, normal);
+        if denominator <= 1e-24 * dot(ab, ab).max(dot(ac, ac)).powi(2) {
+            let candidates = [
+                Sphere::diameter(a, b),
+                Sphere::diameter(a, c),
+                Sphere::diameter(b, c),
+            ];
+            return largest(&candidates);
+        }
+
+        let offset = add(
+            scale(cross(normal, ab), dot(ac, ac)),
+            scale(cross(ac, normal), dot(ab, ab)),
+        );
+        Sphere::from_center(add(a, scale(offset, 1.0 / denominator)), a)
+    }
+
+    /// The sphere through `a`, `b`, `c` and `d`, or the smallest sphere through three of them
+    /// that contains the fourth if they're coplanar.
+    fn tetrahedron(a: Point, b: Point, c: Point, d: Point) -> Sphere {
+        let (u, v, w) = (sub(b, a), sub(c, a), sub(d, a));
+        let determinant = dot(u, cross(v, w));
+        let size = dot(u, u).max(dot(v, v)).max(dot(w, w));
+        if determinant.abs() <= 1e-12 * size.powf(1.5) {
+            let candidates = [
+                Sphere::circle(a, b, c),
+                Sphere::circle(a, b, d),
+                Sphere::circle(a, c, d),
+                Sphere::circle(b, c, d),
+            ];
+            return candidates
+                .iter()
+                .filter(|sphere| [a, b, c, d].iter().all(|&p| sphere.contains(p)))
+                .fold(largest(&candidates), |best, &sphere| {
+                    if sphere.radius_squared < best.radius_squared {
+                        sphere
+                    } else {
+                        best
+                    }
+                });
+        }
+
+        let offset = add(
+            add(scale(cross(v, w), dot(u, u)), scale(cross(w, u), dot(v, v))),
+            scale(cross(u, v), dot(w, w)),
+        );
+        Sphere::from_center(add(a, scale(offset, 0.5 / determinant)), a)
+    }
+}
+
+fn largest(spheres: &[Sphere]) -> Sphere {
+    spheres.iter().fold(spheres[0], |best, &sphere| {
+        if sphere.radius_squared > best.radius_squared {
+            sphere
+        } else {
+            best
+        }
+    })
+}
+
+/// The smallest sphere containing `points`, without recursion: each level of the loops fixes
+/// one more point that has to be on the boundary.
+fn welzl(points: &[Point]) -> Sphere {
+    let mut sphere = Sphere::point(points[0]);
+    for i in 1..points.len() {
+        if sphere.contains(points[i]) {
+            continue;
+        }
+        sphere = Sphere::point(points[i]);
+        for j in 0..i {
+            if sphere.contains(points[j]) {
+                continue;
+            }
+            sphere = Sphere::diameter(points[i], points[j]);
+            for k in 0..j {
+                if sphere.contains(points[k]) {
+                    continue;
+                }
+                sphere = Sphere::circle(points[i], points[j], points[k]);
+                for l in 0..k {
+                    if !sphere.contains(points[l]) {
+                        sphere = Sphere::tetrahedron(points[i], points[j], points[k], points[l]);
+                    }
+                }
+            }
+        }
+    }
+    sphere
+}
+
+/// Fisher-Yates shuffle with a fixed xorshift generator. Welzl's algorithm takes expected
+/// linear time for points in random order, but can take much longer for sorted ones, e.g. the
+/// vertices of a grid.
+fn shuffle(points: &mut [Point]) {
+    let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+    for i in (1..points.len()).rev() {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        points.swap(i, (state % (i as u64 + 1)) as usize);
+    }
+}
+
+/// The eigenvectors of the symmetric matrix `m`, by Jacobi rotations, sorted by decreasing
+/// eigenvalue and made right-handed.
+fn principal_axes(mut m: [[f64; 3]; 3]) -> [Point; 3] {
+    let mut v = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+
+    for _ in 0..50 {
+        let off = m[0][1].abs() + m[0][2].abs() + m[1][2].abs();
+        let diagonal = m[0][0].abs() + m[1][1].abs() + m[2][2].abs();
+        if off <= 1e-15 * diagonal || off == 0.0 {
+            break;
+        }
+
+        for &(p, q) in &[(0, 1), (0, 2), (1, 2)] {
+            if m[p][q] == 0.0 {
+                continue;
+            }
+            let theta = (m[q][q] - m[p][p]) / (2.0 * m[p][q]);
+            let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+            let c = 1.0 / (t * t + 1.0).sqrt();
+            let s = t * c;
+
+            // m = J^T m J, for the rotation J in the (p, q) plane.
+            rotate_columns(&mut m, p, q, c, s);
+            let (row_p, row_q) = (m[p], m[q]);
+            m[p] = sub(scale(row_p, c), scale(row_q, s));
+            m[q] = add(scale(row_p, s), scale(row_q, c));
+            // The columns of v are the eigenvectors.
+            rotate_columns(&mut v, p, q, c, s);
+        }
+    }
+
+    let column = |k: usize| [v[0][k], v[1][k], v[2][k]];
+    let mut order = [0, 1, 2];
+    order.sort_by(|&i, &j| m[j][j].partial_cmp(&m[i][i]).unwrap());
+
+    let first = normalize(column(order[0]));
+    let second = normalize(column(order[1]));
+    [first, second, cross(first, second)]
+}
+
+/// Multiply `m` by the rotation with cosine `c` and sine `s` in the `(p, q)` plane.
+fn rotate_columns(m: &mut [Point; 3], p: usize, q: usize, c: f64, s: f64) {
+    for row in m {
+        let (row_p, row_q) = (row[p], row[q]);
+        row[p] = c * row_p - s * row_q;
+        row[q] = s * row_p + c * row_q;
+    }
+}
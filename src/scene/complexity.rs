@@ -0,0 +1,212 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use super::material::{
+    BlendOp, Mapping, Material, MaterialComponentType, ShadingPath, WrappingMode, TEXTURE_TYPES,
+};
+use super::scene::Scene;
+
+/// How expensive a material is to render, as returned by `Material::complexity`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MaterialComplexity {
+    /// The number of textures of each component that has any, in the order of the
+    /// `MaterialComponentType` variants.
+    pub texture_counts: Vec<(MaterialComponentType, u32)>,
+    /// The blend operations of all textures, in the order they first appear.
+    pub blend_ops: Vec<BlendOp>,
+    /// Whether any texture uses a mapping other than `Mapping::UV`.
+    pub non_uv_mapping: bool,
+    /// Whether any texture wraps with `WrappingMode::Decal` along any axis, which many samplers
+    /// can't express.
+    pub decal_wrapping: bool,
+    /// The number of distinct texture paths, including references to embedded textures.
+    pub distinct_paths: u32,
+    /// Whether the material has physically based properties, see `Material::shading_path_hint`.
+    pub uses_pbr_keys: bool,
+}
+
+impl MaterialComplexity {
+    /// The number of textures of `type_`.
+    pub fn texture_count(&self, type_: MaterialComponentType) -> u32 {
+        self.texture_counts
+            .iter()
+            .find(|&&(component, _)| component == type_)
+            .map_or(0, |&(_, count)| count)
+    }
+
+    /// The number of textures of all components.
+    pub fn total_textures(&self) -> u32 {
+        self.texture_counts.iter().map(|&(_, count)| count).sum()
+    }
+
+    /// Every way in which this material goes beyond `limits`, or an empty `Vec` if it doesn't.
+    pub fn issues(&self, limits: &ComplexityLimits) -> Vec<ComplexityIssue> {
+        let mut issues = Vec::new();
+
+        for &(component, count) in &self.texture_counts {
+            if count > limits.max_textures_per_component {
+                issues.push(ComplexityIssue::StackedTextures { component, count });
+            }
+        }
+        let total = self.total_textures();
+        if total > limits.max_total_textures {
+            issues.push(ComplexityIssue::TooManyTextures(total));
+        }
+        if self.distinct_paths > limits.max_distinct_paths {
+            issues.push(ComplexityIssue::TooManyPaths(self.distinct_paths));
+        }
+        for &op in &self.blend_ops {
+            if !limits.blend_ops.contains(&op) {
+                issues.push(ComplexityIssue::BlendOp(op));
+            }
+        }
+        if self.non_uv_mapping && !limits.allow_non_uv_mapping {
+            issues.push(ComplexityIssue::NonUvMapping);
+        }
+        if self.decal_wrapping && !limits.allow_decal_wrapping {
+            issues.push(ComplexityIssue::DecalWrapping);
+        }
+        if self.uses_pbr_keys && !limits.allow_pbr {
+            issues.push(ComplexityIssue::Pbr);
+        }
+
+        issues
+    }
+}
+
+/// What a renderer can handle, for `Scene::complex_materials`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComplexityLimits {
+    /// The number of textures a single component can have. Default: 1
+    pub max_textures_per_component: u32,
+    /// The number of textures across all components. Default: 8
+    pub max_total_textures: u32,
+    /// The number of distinct texture paths, i.e. of textures that need to be bound.
+    /// Default: 8
+    pub max_distinct_paths: u32,
+    /// The blend operations the renderer supports. Textures without an operation in the file
+    /// report `BlendOp::Multiply`, so that should usually be allowed.
+    /// Default: `Multiply` and `Replace`
+    pub blend_ops: Vec<BlendOp>,
+    /// Default: false
+    pub allow_non_uv_mapping: bool,
+    /// Default: false
+    pub allow_decal_wrapping: bool,
+    /// Default: true
+    pub allow_pbr: bool,
+}
+
+impl Default for ComplexityLimits {
+    fn default() -> Self {
+        ComplexityLimits {
+            max_textures_per_component: 1,
+            max_total_textures: 8,
+            max_distinct_paths: 8,
+            blend_ops: vec![BlendOp::Multiply, BlendOp::Replace],
+            allow_non_uv_mapping: false,
+            allow_decal_wrapping: false,
+            allow_pbr: true,
+        }
+    }
+}
+
+/// A way in which a material goes beyond `ComplexityLimits`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ComplexityIssue {
+    /// A component has more textures than `max_textures_per_component`.
+    StackedTextures {
+        component: MaterialComponentType,
+        count: u32,
+    },
+    /// The material has this many textures, more than `max_total_textures`.
+    TooManyTextures(u32),
+    /// The material has this many distinct texture paths, more than `max_distinct_paths`.
+    TooManyPaths(u32),
+    /// A texture uses a blend operation that isn't allowed.
+    BlendOp(BlendOp),
+    NonUvMapping,
+    DecalWrapping,
+    Pbr,
+}
+
+impl fmt::Display for ComplexityIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ComplexityIssue::StackedTextures { component, count } => {
+                write!(f, "{} {:?} textures", count, component)
+            }
+            ComplexityIssue::TooManyTextures(count) => write!(f, "{} textures", count),
+            ComplexityIssue::TooManyPaths(count) => write!(f, "{} distinct texture paths", count),
+            ComplexityIssue::BlendOp(op) => write!(f, "unsupported blend operation {:?}", op),
+            ComplexityIssue::NonUvMapping => write!(f, "non-UV texture mapping"),
+            ComplexityIssue::DecalWrapping => write!(f, "decal texture wrapping"),
+            ComplexityIssue::Pbr => write!(f, "physically based properties"),
+        }
+    }
+}
+
+impl Material {
+    /// Summarize the textures of every component of this material, to judge how expensive it
+    /// is to render. Textures that Assimp reports but can't read are skipped.
+    pub fn complexity(&self) -> MaterialComplexity {
+        let mut complexity = MaterialComplexity {
+            uses_pbr_keys: self.shading_path_hint() == ShadingPath::Pbr,
+            ..Default::default()
+        };
+        let mut paths = HashSet::new();
+
+        for &type_ in &TEXTURE_TYPES {
+            let mut count = 0;
+            for index in 0..self.num_textures(type_) {
+                let texture = match self.texture(type_, index) {
+                    Some(texture) => texture,
+                    None => continue,
+                };
+                count += 1;
+
+                if !complexity.blend_ops.contains(&texture.blend_op) {
+                    complexity.blend_ops.push(texture.blend_op);
+                }
+                if texture.mapping.is_some() && texture.mapping != Some(Mapping::UV) {
+                    complexity.non_uv_mapping = true;
+                }
+                let wraps = [texture.wrap_u, texture.wrap_v, texture.wrap_w];
+                if wraps.contains(&Some(WrappingMode::Decal)) {
+                    complexity.decal_wrapping = true;
+                }
+                paths.insert(crate::aistring_to_cstr(&texture.path.0).to_bytes().to_vec());
+            }
+            if count > 0 {
+                complexity.texture_counts.push((type_, count));
+            }
+        }
+        complexity.distinct_paths = paths.len() as u32;
+
+        complexity
+    }
+}
+
+impl Scene<'_> {
+    /// The complexity of every material, by material index. Null entries in the materials
+    /// array, which Assimp never produces, get an empty `MaterialComplexity`.
+    pub fn material_complexities(&self) -> Vec<MaterialComplexity> {
+        (0..self.mNumMaterials)
+            .map(|index| {
+                self.material(index)
+                    .map(Material::complexity)
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
+
+    /// The index of every material that goes beyond `limits`, with the reasons, in ascending
+    /// order of index.
+    pub fn complex_materials(&self, limits: &ComplexityLimits) -> Vec<(u32, Vec<ComplexityIssue>)> {
+        self.material_complexities()
+            .iter()
+            .enumerate()
+            .map(|(index, complexity)| (index as u32, complexity.issues(limits)))
+            .filter(|(_, issues)| !issues.is_empty())
+            .collect()
+    }
+}
@@ -0,0 +1,132 @@
+//! The axes and units a file was authored in, as recorded in scene metadata, see
+//! `Scene::source_conventions`.
+
+use super::convert::{Axis, AxisConvention, Handedness};
+use super::node::MetadataValue;
+use super::scene::Scene;
+
+/// The coordinate system and unit of the application that wrote a file, as far as the file
+/// records them. Each field is `None` if the scene's metadata doesn't say.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct SourceConventions {
+    pub up: Option<Axis>,
+    /// The direction a model faces, as for `AxisConvention::forward`.
+    pub front: Option<Axis>,
+    pub handedness: Option<Handedness>,
+    /// The length of one unit of the file in meters, e.g. 0.01 for a file in centimeters.
+    pub unit_scale_to_meters: Option<f32>,
+}
+
+impl SourceConventions {
+    /// The conventions the glTF specification prescribes: +Y up, +Z front, right-handed and
+    /// in meters. The glTF importer doesn't record them in the metadata, since every glTF file
+    /// uses them, so use this with `or` for glTF files.
+    pub const GLTF: SourceConventions = SourceConventions {
+        up: Some(Axis::PosY),
+        front: Some(Axis::PosZ),
+        handedness: Some(Handedness::Right),
+        unit_scale_to_meters: Some(1.0),
+    };
+
+    /// These conventions, with the fields that are `None` taken from `fallback`.
+    pub fn or(self, fallback: SourceConventions) -> SourceConventions {
+        SourceConventions {
+            up: self.up.or(fallback.up),
+            front: self.front.or(fallback.front),
+            handedness: self.handedness.or(fallback.handedness),
+            unit_scale_to_meters: self.unit_scale_to_meters.or(fallback.unit_scale_to_meters),
+        }
+    }
+
+    /// The axis convention, for `convert::axis_convert`, or `None` unless the up axis, the
+    /// front axis and the handedness are all known.
+    pub fn axis_convention(&self) -> Option<AxisConvention> {
+        Some(AxisConvention {
+            up: self.up?,
+            forward: self.front?,
+            handedness: self.handedness?,
+        })
+    }
+}
+
+impl Scene<'_> {
+    /// The axes and unit the file was authored in, read from the scene's metadata. Nothing is
+    /// guessed: a field is `None` unless the metadata records it. Together with
+    /// `convert::axis_convert` and a scale, this brings any file to a single convention, e.g.
+    /// +Y up in meters.
+    ///
+    /// The FBX importer records the file's global settings as `UpAxis`, `FrontAxis` and
+    /// `CoordAxis`, each an axis index with a `...Sign` of 1 or -1, and `UnitScaleFactor`,
+    /// the unit in centimeters. The handedness follows from the right (`CoordAxis`), up and
+    /// front axes. `OriginalUnitScaleFactor` is the unit of the application that wrote the
+    /// file before the exporter converted it, so it isn't used.
+    ///
+    /// The Collada importer doesn't record `<up_axis>` and `<unit>`, but applies them to the
+    /// root node's transform, so the scene is already +Y up in meters unless
+    /// `collada_ignore_up_direction` is set, and this returns `None` for them. glTF files
+    /// always use `SourceConventions::GLTF`, which the importer doesn't record either.
+    pub fn source_conventions(&self) -> SourceConventions {
+        let mut up = (None, 1.0);
+        let mut front = (None, 1.0);
+        let mut right = (None, 1.0);
+        let mut unit = None;
+        for (key, entry) in self.metadata() {
+            let value = match entry.get() {
+                MetadataValue::I32(value) => f64::from(value),
+                MetadataValue::U64(value) => value as f64,
+                MetadataValue::F32(value) => f64::from(value),
+                MetadataValue::F64(value) => value,
+                _ => continue,
+            };
+            match key.to_bytes() {
+                b"UpAxis" => up.0 = Some(value),
+                b"UpAxisSign" => up.1 = value,
+                b"FrontAxis" => front.0 = Some(value),
+                b"FrontAxisSign" => front.1 = value,
+                b"CoordAxis" => right.0 = Some(value),
+                b"CoordAxisSign" => right.1 = value,
+                b"UnitScaleFactor" => unit = Some(value),
+                _ => {}
+            }
+        }
+
+        let up = axis(up);
+        let front = axis(front);
+        let handedness = match (axis(right), up, front) {
+            (Some(right), Some(up), Some(front)) => {
+                let cross = right.unit().cross(up.unit());
+                if cross == front.unit() {
+                    Some(Handedness::Right)
+                } else if cross == -front.unit() {
+                    Some(Handedness::Left)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        };
+
+        SourceConventions {
+            up,
+            front,
+            handedness,
+            unit_scale_to_meters: unit
+                .filter(|&unit| unit.is_finite() && unit > 0.0)
+                .map(|unit| (unit * 0.01) as f32),
+        }
+    }
+}
+
+/// The axis for an FBX axis index and sign. A sign that's missing counts as positive.
+fn axis((index, sign): (Option<f64>, f64)) -> Option<Axis> {
+    let axes = if sign < 0.0 {
+        [Axis::NegX, Axis::NegY, Axis::NegZ]
+    } else {
+        [Axis::PosX, Axis::PosY, Axis::PosZ]
+    };
+    let index = index?;
+    [0.0, 1.0, 2.0]
+        .iter()
+        .position(|&i| i == index)
+        .map(|i| axes[i])
+}
@@ -0,0 +1,295 @@
+//! Conversions between the coordinate system conventions used by different file formats and
+//! engines, applied to an owned `SceneSnapshot`.
+
+use crate::math::{Matrix4x4, Quaternion, Vector3D};
+use crate::Error;
+
+use super::snapshot::SceneSnapshot;
+
+/// A signed coordinate axis.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Axis {
+    PosX,
+    NegX,
+    PosY,
+    NegY,
+    PosZ,
+    NegZ,
+}
+
+impl Axis {
+    pub(crate) fn unit(self) -> Vector3D {
+        match self {
+            Axis::PosX => Vector3D::new(1.0, 0.0, 0.0),
+            Axis::NegX => Vector3D::new(-1.0, 0.0, 0.0),
+            Axis::PosY => Vector3D::new(0.0, 1.0, 0.0),
+            Axis::NegY => Vector3D::new(0.0, -1.0, 0.0),
+            Axis::PosZ => Vector3D::new(0.0, 0.0, 1.0),
+            Axis::NegZ => Vector3D::new(0.0, 0.0, -1.0),
+        }
+    }
+}
+
+/// Whether a coordinate system is left- or right-handed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Handedness {
+    Left,
+    Right,
+}
+
+/// A coordinate system convention: which axis points up, which axis points forward (the
+/// direction a model faces), and the handedness, which decides which way is right.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct AxisConvention {
+    pub up: Axis,
+    pub forward: Axis,
+    pub handedness: Handedness,
+}
+
+impl AxisConvention {
+    /// +Y up, +Z forward and right-handed, as used by glTF. Assimp converts most formats to
+    /// this convention.
+    pub const Y_UP_RIGHT_HANDED: AxisConvention = AxisConvention {
+        up: Axis::PosY,
+        forward: Axis::PosZ,
+        handedness: Handedness::Right,
+    };
+
+    /// +Z up, -Y forward and right-handed, as used by Blender and many CAD tools.
+    pub const Z_UP_RIGHT_HANDED: AxisConvention = AxisConvention {
+        up: Axis::PosZ,
+        forward: Axis::NegY,
+        handedness: Handedness::Right,
+    };
+
+    /// The right, up and forward directions of this convention, or `None` if the up and
+    /// forward axes aren't perpendicular.
+    fn basis(&self) -> Option<[Vector3D; 3]> {
+        let up = self.up.unit();
+        let forward = self.forward.unit();
+
+        if up.dot(forward) != 0.0 {
+            return None;
+        }
+
+        let right = match self.handedness {
+            Handedness::Right => forward.cross(up),
+            Handedness::Left => up.cross(forward),
+        };
+
+        Some([right, up, forward])
+    }
+}
+
+/// The matrix that converts coordinates from the `from` convention to the `to` convention, e.g.
+/// for converting transforms that aren't part of a snapshot. Returns `Error::InvalidConfig`
+/// if either convention's up and forward axes aren't perpendicular.
+///
+/// The result is always a rotation, or a rotation combined with a reflection if the
+/// handedness changes.
+pub fn basis_change(from: AxisConvention, to: AxisConvention) -> Result<Matrix4x4, Error> {
+    let not_perpendicular = || Error::InvalidConfig {
+        message: "up and forward axes must be perpendicular".to_owned(),
+    };
+    let from = from.basis().ok_or_else(not_perpendicular)?;
+    let to = to.basis().ok_or_else(not_perpendicular)?;
+
+    // A vector with components `c` along the `from` basis has the same components along the
+    // `to` basis, so the matrix is `to * transpose(from)`.
+    let mut rows = [[0.0; 4]; 4];
+    for (i, row) in rows.iter_mut().take(3).enumerate() {
+        for (j, value) in row.iter_mut().take(3).enumerate() {
+            *value = (0..3).map(|k| axis(to[k], i) * axis(from[k], j)).sum();
+        }
+    }
+    rows[3][3] = 1.0;
+
+    Ok(Matrix4x4::from_rows(rows))
+}
+
+fn axis(v: Vector3D, i: usize) -> f32 {
+    [v.x, v.y, v.z][i]
+}
+
+/// Convert everything in `scene` from the `from` axis convention to the `to` convention: node
+/// transforms, vertex positions, normals, tangents and bitangents, bone offsets, animation
+/// keys, cameras and lights. If the handedness changes, the winding order of every face is
+/// reversed so that front faces stay front faces.
+///
+/// Returns `Error::InvalidConfig`, without changing anything, if either convention's up and
+/// forward axes aren't perpendicular.
+pub fn axis_convert(
+    scene: &mut SceneSnapshot,
+    from: AxisConvention,
+    to: AxisConvention,
+) -> Result<(), Error> {
+    let m = basis_change(from, to)?;
+    let m_inv = m.transpose();
+    let flip = from.handedness != to.handedness;
+
+    // Conjugating a transform by `m` keeps it mapping between converted spaces.
+    let convert_transform = |t: Matrix4x4| m * t * m_inv;
+
+    if let Some(root) = &mut scene.root {
+        root.visit_mut(&mut |node| node.transform = convert_transform(node.transform));
+    }
+
+    for mesh in &mut scene.meshes {
+        // Normals transform by the inverse transpose, which is `m` itself because `m` is
+        // orthogonal, so they're converted the same way as everything else.
+        let vectors = mesh
+            .normals
+            .iter_mut()
+            .chain(&mut mesh.tangents)
+            .chain(&mut mesh.bitangents);
+        for v in mesh.positions.iter_mut().chain(vectors) {
+            *v = m.transform_vector(*v);
+        }
+        for bone in &mut mesh.bones {
+            bone.offset_matrix = convert_transform(bone.offset_matrix);
+        }
+        if flip {
+            for face in &mut mesh.faces {
+                face.reverse();
+            }
+        }
+    }
+
+    for animation in &mut scene.animations {
+        for channel in &mut animation.channels {
+            for (_, p) in &mut channel.position_keys {
+                *p = m.transform_vector(*p);
+            }
+            for (_, q) in &mut channel.rotation_keys {
+                *q = convert_rotation(&m, flip, *q);
+            }
+            for (_, s) in &mut channel.scaling_keys {
+                *s = convert_scale(&m, *s);
+            }
+        }
+    }
+
+    for camera in &mut scene.cameras {
+        camera.position = m.transform_vector(camera.position);
+        camera.up = m.transform_vector(camera.up);
+        camera.look_at = m.transform_vector(camera.look_at);
+    }
+
+    for light in &mut scene.lights {
+        light.position = m.transform_vector(light.position);
+        light.direction = m.transform_vector(light.direction);
+        light.up = m.transform_vector(light.up);
+    }
+
+    Ok(())
+}
+
+/// Conjugating a rotation by `m` rotates by the same angle around the converted axis. If `m`
+/// includes a reflection, the rotation also changes direction, which is the same as negating
+/// the axis.
+fn convert_rotation(m: &Matrix4x4, flip: bool, q: Quaternion) -> Quaternion {
+    let axis = m.transform_vector(Vector3D::new(q.x, q.y, q.z));
+    let axis = if flip { -axis } else { axis };
+
+    Quaternion::new(q.w, axis.x, axis.y, axis.z)
+}
+
+/// `m` only permutes and negates axes, so a conjugated scale is still a scale, with the
+/// factors permuted.
+fn convert_scale(m: &Matrix4x4, s: Vector3D) -> Vector3D {
+    let rows = m.rows();
+    let scale = |row: [f32; 4]| row[0].abs() * s.x + row[1].abs() * s.y + row[2].abs() * s.z;
+
+    Vector3D::new(scale(rows[0]), scale(rows[1]), scale(rows[2]))
+}
+
+/// Options for `to_left_handed_with`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct LeftHandedOptions {
+    /// Also replace the V coordinate of every UV with `1 - v`, like `Importer::flip_uvs`.
+    /// Default: true
+    pub flip_uvs: bool,
+}
+
+impl Default for LeftHandedOptions {
+    fn default() -> Self {
+        LeftHandedOptions { flip_uvs: true }
+    }
+}
+
+/// Convert `scene` the way importing with `Importer::make_left_handed`,
+/// `Importer::flip_winding_order` and `Importer::flip_uvs` does, for scenes that were imported
+/// without them, e.g. cached snapshots. See `to_left_handed_with`.
+pub fn to_left_handed(scene: &mut SceneSnapshot) {
+    to_left_handed_with(scene, LeftHandedOptions::default())
+}
+
+/// Like `to_left_handed`, but the UVs are only flipped if `opts.flip_uvs` is set.
+///
+/// This does exactly what Assimp's steps do to the data in a snapshot: the Z coordinate of
+/// positions, normals, tangents, bitangents and position keys is negated, and node transforms
+/// and bone offsets are mirrored along Z. Rotation keys get the X and Y components of their
+/// axis negated, which is the mirrored rotation. The winding of every face is reversed. Like
+/// Assimp, this also negates the bitangents as a whole, and mirrors each camera's `look_at`
+/// through its position; lights are left unchanged.
+///
+/// Applying this twice gives back the original scene.
+pub fn to_left_handed_with(scene: &mut SceneSnapshot, opts: LeftHandedOptions) {
+    if let Some(root) = &mut scene.root {
+        root.visit_mut(&mut |node| node.transform = mirror_z(node.transform));
+    }
+
+    for mesh in &mut scene.meshes {
+        let vectors = mesh
+            .positions
+            .iter_mut()
+            .chain(&mut mesh.normals)
+            .chain(&mut mesh.tangents);
+        for v in vectors {
+            *v = Vector3D::new(v.x, v.y, -v.z);
+        }
+        for v in &mut mesh.bitangents {
+            *v = Vector3D::new(-v.x, -v.y, v.z);
+        }
+        for bone in &mut mesh.bones {
+            bone.offset_matrix = mirror_z(bone.offset_matrix);
+        }
+        for face in &mut mesh.faces {
+            face.reverse();
+        }
+        if opts.flip_uvs {
+            for uv in mesh.texture_coords.iter_mut().flatten().flatten() {
+                *uv = Vector3D::new(uv.x, 1.0 - uv.y, uv.z);
+            }
+        }
+    }
+
+    for animation in &mut scene.animations {
+        for channel in &mut animation.channels {
+            for (_, p) in &mut channel.position_keys {
+                *p = Vector3D::new(p.x, p.y, -p.z);
+            }
+            for (_, q) in &mut channel.rotation_keys {
+                *q = Quaternion::new(q.w, -q.x, -q.y, q.z);
+            }
+        }
+    }
+
+    for camera in &mut scene.cameras {
+        camera.look_at = camera.position * 2.0 - camera.look_at;
+    }
+}
+
+/// `m` conjugated by the reflection along Z: the third row and column are negated, except
+/// for the element where they meet.
+fn mirror_z(m: Matrix4x4) -> Matrix4x4 {
+    let mut rows = m.rows();
+    for (i, row) in rows.iter_mut().enumerate() {
+        for (j, value) in row.iter_mut().enumerate() {
+            if (i == 2) != (j == 2) {
+                *value = -*value;
+            }
+        }
+    }
+    Matrix4x4::from_rows(rows)
+}
@@ -0,0 +1,271 @@
+//! Surface areas, UV areas and texel densities of meshes, see `Scene::texel_density_report`.
+
+use crate::math::{Matrix4x4, Vector3D};
+
+use super::mesh::Mesh;
+use super::scene::Scene;
+
+/// Summary statistics of a set of values, e.g. triangle areas.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Distribution {
+    /// The number of values. Everything else is 0 if there are none.
+    pub count: u32,
+    pub min: f32,
+    pub max: f32,
+    pub mean: f32,
+    /// The 10th, 50th and 90th percentile, by the nearest-rank method.
+    pub p10: f32,
+    pub median: f32,
+    pub p90: f32,
+}
+
+impl Distribution {
+    /// The distribution of `values`, which may be in any order. NaNs sort last.
+    pub fn of(mut values: Vec<f32>) -> Distribution {
+        if values.is_empty() {
+            return Distribution::default();
+        }
+        values.sort_by(|a, b| {
+            a.partial_cmp(b)
+                .unwrap_or_else(|| a.is_nan().cmp(&b.is_nan()))
+        });
+        let sum: f64 = values.iter().map(|&value| value as f64).sum();
+        let rank = |percentile: usize| {
+            let rank = (percentile * values.len() + 99) / 100;
+            values[rank.max(1) - 1]
+        };
+        Distribution {
+            count: values.len() as u32,
+            min: values[0],
+            max: values[values.len() - 1],
+            mean: (sum / values.len() as f64) as f32,
+            p10: rank(10),
+            median: rank(50),
+            p90: rank(90),
+        }
+    }
+}
+
+/// The UV statistics of one UV channel of a mesh, in `MeshDensityReport::uv_channels`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct UvChannelDensity {
+    pub channel: u32,
+    /// The area of the triangles in UV space, see `Mesh::uv_area`.
+    pub uv_area: f32,
+    /// The number of triangles with no area in UV space, which are left out of `ratio`.
+    pub zero_uv_area: u32,
+    /// The world area of each triangle divided by its UV area.
+    pub ratio: Distribution,
+    /// The average number of texels per unit of length in world space, for a square texture
+    /// of the target size: `size * sqrt(uv_area / world_area)`. 0 if the mesh has no area.
+    pub texels_per_unit: f32,
+}
+
+/// The surface and UV statistics of a mesh as placed by one node, as returned by
+/// `Scene::texel_density_report`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct MeshDensityReport {
+    pub mesh_index: u32,
+    /// The index of the node in `Scene::node_arena`, or `None` for a mesh that no node
+    /// references, which is measured in its own space.
+    pub node: Option<u32>,
+    /// The surface area in the mesh's own space, see `Mesh::surface_area`.
+    pub local_area: f32,
+    /// The surface area with the world transform of the node applied.
+    pub world_area: f32,
+    /// The world areas of the triangles that were measured.
+    pub triangle_areas: Distribution,
+    /// The number of triangles that were skipped because they have no area or indices out of
+    /// range.
+    pub degenerate: u32,
+    /// The number of points, lines and polygons, which aren't measured. Triangulate the scene
+    /// on import to measure polygons.
+    pub non_triangles: u32,
+    /// One for each UV channel the mesh has, in order.
+    pub uv_channels: Vec<UvChannelDensity>,
+}
+
+impl Mesh {
+    /// The total area of the triangles of the mesh, in its own space. Degenerate triangles,
+    /// points, lines and polygons are skipped, see `Scene::texel_density_report`.
+    pub fn surface_area(&self) -> f32 {
+        let positions = self.position_slice();
+        let (triangles, _, _) = triangles(self, positions);
+        triangles
+            .iter()
+            .map(|&triangle| triangle_area(positions, triangle) as f64)
+            .sum::<f64>() as f32
+    }
+
+    /// The total area of the same triangles as `surface_area` in the UV space of
+    /// `channel`, using the first two UV components. Overlapping triangles are counted
+    /// each time. Returns `None` if the mesh has no such UV channel.
+    pub fn uv_area(&self, channel: u32) -> Option<f32> {
+        let uvs = uv_channel(self, channel)?;
+        let (triangles, _, _) = triangles(self, self.position_slice());
+        Some(
+            triangles
+                .iter()
+                .map(|&triangle| uv_triangle_area(&uvs, triangle) as f64)
+                .sum::<f64>() as f32,
+        )
+    }
+}
+
+impl Scene<'_> {
+    /// Measure the surface and UV areas of every mesh, for auditing texel density. There is
+    /// one report for each time a node references a mesh, in the order of `Scene::node_arena`,
+    /// followed by one for each mesh that no node references.
+    ///
+    /// Only triangles are measured. A triangle is degenerate, and skipped, if it has an index
+    /// out of range or no area in the mesh's own space. The UV statistics of each channel are
+    /// over the same triangles, and `texels_per_unit` is for a square texture of
+    /// `target_texture_size` texels.
+    pub fn texel_density_report(&self, target_texture_size: u32) -> Vec<MeshDensityReport> {
+        let arena = self.node_arena();
+        let world = arena.world_transforms();
+        let mut reports = Vec::new();
+        let mut referenced = vec![false; self.num_meshes() as usize];
+        for (node, record) in arena.records().iter().enumerate() {
+            for &mesh_index in &record.mesh_indices {
+                if let Some(mesh) = self.mesh(mesh_index) {
+                    referenced[mesh_index as usize] = true;
+                    let transform = &world[node];
+                    let node = Some(node as u32);
+                    reports.push(measure(
+                        mesh,
+                        mesh_index,
+                        node,
+                        transform,
+                        target_texture_size,
+                    ));
+                }
+            }
+        }
+        for (mesh_index, mesh) in self.meshes().enumerate() {
+            if !referenced[mesh_index] {
+                let identity = Matrix4x4::identity();
+                let mesh_index = mesh_index as u32;
+                reports.push(measure(
+                    mesh,
+                    mesh_index,
+                    None,
+                    &identity,
+                    target_texture_size,
+                ));
+            }
+        }
+        reports
+    }
+}
+
+fn measure(
+    mesh: &Mesh,
+    mesh_index: u32,
+    node: Option<u32>,
+    transform: &Matrix4x4,
+    target_texture_size: u32,
+) -> MeshDensityReport {
+    let local = mesh.position_slice();
+    let world = mesh.positions_transformed(transform);
+    let (triangles, degenerate, non_triangles) = triangles(mesh, local);
+
+    let local_area: f64 = triangles
+        .iter()
+        .map(|&triangle| triangle_area(local, triangle) as f64)
+        .sum();
+    let world_areas: Vec<f32> = triangles
+        .iter()
+        .map(|&triangle| triangle_area(&world, triangle))
+        .collect();
+    let world_area: f64 = world_areas.iter().map(|&area| area as f64).sum();
+
+    let mut uv_channels = Vec::new();
+    for channel in 0..mesh.mTextureCoords.len() as u32 {
+        let uvs = match uv_channel(mesh, channel) {
+            Some(uvs) => uvs,
+            None => continue,
+        };
+        let mut uv_area = 0.0;
+        let mut zero_uv_area = 0;
+        let mut ratios = Vec::new();
+        for (&triangle, &area) in triangles.iter().zip(&world_areas) {
+            let uv = uv_triangle_area(&uvs, triangle);
+            uv_area += uv as f64;
+            if uv > 0.0 {
+                ratios.push(area / uv);
+            } else {
+                zero_uv_area += 1;
+            }
+        }
+        let texels_per_unit = if world_area > 0.0 {
+            (target_texture_size as f64 * (uv_area / world_area).sqrt()) as f32
+        } else {
+            0.0
+        };
+        uv_channels.push(UvChannelDensity {
+            channel,
+            uv_area: uv_area as f32,
+            zero_uv_area,
+            ratio: Distribution::of(ratios),
+            texels_per_unit,
+        });
+    }
+
+    MeshDensityReport {
+        mesh_index,
+        node,
+        local_area: local_area as f32,
+        world_area: world_area as f32,
+        triangle_areas: Distribution::of(world_areas),
+        degenerate,
+        non_triangles,
+        uv_channels,
+    }
+}
+
+/// The triangles of `mesh` that have an area in `positions`, the number of degenerate
+/// triangles and the number of other faces.
+fn triangles(mesh: &Mesh, positions: &[Vector3D]) -> (Vec<[usize; 3]>, u32, u32) {
+    let mut triangles = Vec::new();
+    let mut degenerate = 0;
+    let mut non_triangles = 0;
+    for face in mesh.faces() {
+        let triangle = match *face.indices() {
+            [a, b, c] => [a as usize, b as usize, c as usize],
+            _ => {
+                non_triangles += 1;
+                continue;
+            }
+        };
+        if triangle.iter().all(|&index| index < positions.len())
+            && triangle_area(positions, triangle) > 0.0
+        {
+            triangles.push(triangle);
+        } else {
+            degenerate += 1;
+        }
+    }
+    (triangles, degenerate, non_triangles)
+}
+
+/// The UVs of `channel`, if the mesh has that channel.
+fn uv_channel(mesh: &Mesh, channel: u32) -> Option<Vec<Vector3D>> {
+    match mesh.mTextureCoords.get(channel as usize) {
+        Some(uvs) if !uvs.is_null() => Some(mesh.texture_coords(channel).collect()),
+        _ => None,
+    }
+}
+
+fn triangle_area(positions: &[Vector3D], [a, b, c]: [usize; 3]) -> f32 {
+    let (a, b, c) = (positions[a], positions[b], positions[c]);
+    (b - a).cross(c - a).length() * 0.5
+}
+
+fn uv_triangle_area(uvs: &[Vector3D], [a, b, c]: [usize; 3]) -> f32 {
+    let (a, b, c) = (uvs[a], uvs[b], uvs[c]);
+    ((b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)).abs() * 0.5
+}
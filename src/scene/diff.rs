@@ -0,0 +1,751 @@
+//! Structured comparison of two imported scenes, e.g. to report what changed when a model is
+//! re-exported.
+//!
+//! Elements of the two scenes are paired up by a `Matcher`, which by default matches by name
+//! and falls back to matching by index. Anything that the matcher doesn't pair up counts as
+//! added or removed.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use super::animation::Animation;
+use super::material::{Material, MaterialKey, MaterialProperty, MaterialValue, PropertyType};
+use super::mesh::Mesh;
+use super::node::Node;
+use super::scene::Scene;
+
+/// How different two values have to be before they're reported as a difference.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct DiffTolerance {
+    /// The maximum distance a vertex can move without being reported. Default: 1e-5
+    pub position: f32,
+    /// The maximum difference in any element of a node's transform. Default: 1e-5
+    pub transform: f32,
+    /// The maximum difference in any float material property. Default: 1e-5
+    pub value: f32,
+    /// The maximum difference in animation durations, in ticks. Default: 1e-5
+    pub time: f64,
+}
+
+impl Default for DiffTolerance {
+    fn default() -> Self {
+        DiffTolerance {
+            position: 1e-5,
+            transform: 1e-5,
+            value: 1e-5,
+            time: 1e-5,
+        }
+    }
+}
+
+/// Decides which elements of the old scene correspond to which elements of the new scene.
+///
+/// Each method returns pairs of `(index in a, index in b)`. Every index should appear at most
+/// once, pairs with out-of-range or repeated indices are ignored. The default implementations
+/// all use `match_by_name_then_index`.
+pub trait Matcher {
+    fn match_meshes(&self, a: &[&Mesh], b: &[&Mesh]) -> Vec<(usize, usize)> {
+        let a: Vec<&str> = a.iter().map(|mesh| mesh.try_name().unwrap_or("")).collect();
+        let b: Vec<&str> = b.iter().map(|mesh| mesh.try_name().unwrap_or("")).collect();
+        match_by_name_then_index(&a, &b)
+    }
+
+    fn match_materials(&self, a: &[&Material], b: &[&Material]) -> Vec<(usize, usize)> {
+        let a: Vec<String> = a.iter().map(|material| material_name(material)).collect();
+        let b: Vec<String> = b.iter().map(|material| material_name(material)).collect();
+        let a: Vec<&str> = a.iter().map(String::as_str).collect();
+        let b: Vec<&str> = b.iter().map(String::as_str).collect();
+        match_by_name_then_index(&a, &b)
+    }
+
+    /// Match the children of two nodes that have already been matched with each other.
+    fn match_nodes(&self, a: &[&Node], b: &[&Node]) -> Vec<(usize, usize)> {
+        let a: Vec<&str> = a.iter().map(|node| node.try_name().unwrap_or("")).collect();
+        let b: Vec<&str> = b.iter().map(|node| node.try_name().unwrap_or("")).collect();
+        match_by_name_then_index(&a, &b)
+    }
+
+    fn match_animations(&self, a: &[&Animation], b: &[&Animation]) -> Vec<(usize, usize)> {
+        let a: Vec<&str> = a
+            .iter()
+            .map(|animation| animation.try_name().unwrap_or(""))
+            .collect();
+        let b: Vec<&str> = b
+            .iter()
+            .map(|animation| animation.try_name().unwrap_or(""))
+            .collect();
+        match_by_name_then_index(&a, &b)
+    }
+}
+
+/// The default `Matcher`.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct NameThenIndex;
+
+impl Matcher for NameThenIndex {}
+
+/// Pair up elements with the same (non-empty) name, then pair up whatever is left at the same
+/// index. If a name appears more than once, the nth occurrence in `a` is paired with the nth
+/// occurrence in `b`.
+pub fn match_by_name_then_index(a: &[&str], b: &[&str]) -> Vec<(usize, usize)> {
+    let mut matched_b = vec![false; b.len()];
+    let mut by_name: Vec<Option<usize>> = vec![None; a.len()];
+
+    for (i, name) in a.iter().enumerate() {
+        if name.is_empty() {
+            continue;
+        }
+
+        let found = b
+            .iter()
+            .enumerate()
+            .position(|(j, other)| !matched_b[j] && other == name);
+
+        if let Some(j) = found {
+            matched_b[j] = true;
+            by_name[i] = Some(j);
+        }
+    }
+
+    let mut matched_a: Vec<bool> = by_name.iter().map(Option::is_some).collect();
+    for i in 0..a.len().min(b.len()) {
+        if !matched_a[i] && !matched_b[i] {
+            matched_a[i] = true;
+            matched_b[i] = true;
+            by_name[i] = Some(i);
+        }
+    }
+
+    by_name
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, j)| Some((i, j?)))
+        .collect()
+}
+
+/// The decoded value of a material property.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub enum PropertyValue {
+    Float(Vec<f32>),
+    Double(Vec<f64>),
+    String(String),
+    Integer(Vec<i32>),
+    Buffer(Vec<u8>),
+}
+
+impl PropertyValue {
+    fn from_property(property: &MaterialProperty) -> Self {
+        let data = property.data();
+
+        match property.property_type() {
+            PropertyType::Float => PropertyValue::Float(
+                data.chunks_exact(4)
+                    .map(|c| f32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+                    .collect(),
+            ),
+            PropertyType::Double => PropertyValue::Double(
+                data.chunks_exact(8)
+                    .map(|c| {
+                        let mut bytes = [0; 8];
+                        bytes.copy_from_slice(c);
+                        f64::from_le_bytes(bytes)
+                    })
+                    .collect(),
+            ),
+            PropertyType::Integer => PropertyValue::Integer(
+                data.chunks_exact(4)
+                    .map(|c| i32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+                    .collect(),
+            ),
+            // Strings are stored as a 32-bit length followed by the (nul-terminated) bytes.
+            PropertyType::String if data.len() >= 4 => {
+                let len = u32::from_le_bytes([data[0], data[1], data[2], data[3]]) as usize;
+                let bytes = &data[4..];
+                let bytes = &bytes[..len.min(bytes.len())];
+                PropertyValue::String(String::from_utf8_lossy(bytes).into_owned())
+            }
+            _ => PropertyValue::Buffer(data.to_vec()),
+        }
+    }
+
+    fn approx_eq(&self, other: &Self, tolerance: f32) -> bool {
+        match (self, other) {
+            (PropertyValue::Float(a), PropertyValue::Float(b)) => {
+                a.len() == b.len() && a.iter().zip(b).all(|(a, b)| (a - b).abs() <= tolerance)
+            }
+            (PropertyValue::Double(a), PropertyValue::Double(b)) => {
+                a.len() == b.len()
+                    && a.iter()
+                        .zip(b)
+                        .all(|(a, b)| (a - b).abs() <= tolerance as f64)
+            }
+            _ => self == other,
+        }
+    }
+}
+
+impl fmt::Display for PropertyValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fn list<T: fmt::Display>(f: &mut fmt::Formatter, values: &[T]) -> fmt::Result {
+            if let [value] = values {
+                return write!(f, "{}", value);
+            }
+
+            write!(f, "[")?;
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{}", value)?;
+            }
+            write!(f, "]")
+        }
+
+        match self {
+            PropertyValue::Float(values) => list(f, values),
+            PropertyValue::Double(values) => list(f, values),
+            PropertyValue::Integer(values) => list(f, values),
+            PropertyValue::String(value) => write!(f, "{:?}", value),
+            PropertyValue::Buffer(bytes) => write!(f, "<{} bytes>", bytes.len()),
+        }
+    }
+}
+
+/// A single difference between two scenes. Meshes and materials are identified by their name,
+/// or by `#index` if they don't have one. Nodes are identified by their path from the root
+/// node in the old scene, with names separated by `/`.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Difference {
+    MeshAdded {
+        index: u32,
+        name: String,
+    },
+    MeshRemoved {
+        index: u32,
+        name: String,
+    },
+    MeshRenamed {
+        old: String,
+        new: String,
+    },
+    VertexCountChanged {
+        mesh: String,
+        old: u32,
+        new: u32,
+    },
+    /// The meshes have the same number of vertices, but their faces are different.
+    TopologyChanged {
+        mesh: String,
+    },
+    /// The meshes have identical topology, but at least one vertex moved by more than the
+    /// tolerance.
+    VerticesMoved {
+        mesh: String,
+        max_deviation: f32,
+    },
+    MaterialAdded {
+        index: u32,
+        name: String,
+    },
+    MaterialRemoved {
+        index: u32,
+        name: String,
+    },
+    /// A material property was added (`old` is `None`), removed (`new` is `None`) or changed.
+    MaterialPropertyChanged {
+        material: String,
+        key: String,
+        semantic: u32,
+        index: u32,
+        old: Option<PropertyValue>,
+        new: Option<PropertyValue>,
+    },
+    NodeAdded {
+        path: String,
+    },
+    NodeRemoved {
+        path: String,
+    },
+    NodeRenamed {
+        path: String,
+        new_name: String,
+    },
+    /// The node's transform changed. `max_delta` is the largest difference in any element of
+    /// the matrix.
+    NodeRetransformed {
+        path: String,
+        max_delta: f32,
+    },
+    AnimationAdded {
+        name: String,
+    },
+    AnimationRemoved {
+        name: String,
+    },
+    AnimationDurationChanged {
+        name: String,
+        old: f64,
+        new: f64,
+    },
+    ChannelAdded {
+        animation: String,
+        node: String,
+    },
+    ChannelRemoved {
+        animation: String,
+        node: String,
+    },
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fn value(f: &mut fmt::Formatter, value: &Option<PropertyValue>) -> fmt::Result {
+            match value {
+                Some(value) => write!(f, "{}", value),
+                None => write!(f, "(none)"),
+            }
+        }
+
+        match self {
+            Difference::MeshAdded { index, name } => {
+                write!(f, "mesh added: `{}` at index {}", name, index)
+            }
+            Difference::MeshRemoved { index, name } => {
+                write!(f, "mesh removed: `{}` at index {}", name, index)
+            }
+            Difference::MeshRenamed { old, new } => {
+                write!(f, "mesh renamed: `{}` -> `{}`", old, new)
+            }
+            Difference::VertexCountChanged { mesh, old, new } => {
+                write!(f, "mesh `{}`: vertex count {} -> {}", mesh, old, new)
+            }
+            Difference::TopologyChanged { mesh } => write!(f, "mesh `{}`: faces changed", mesh),
+            Difference::VerticesMoved {
+                mesh,
+                max_deviation,
+            } => write!(
+                f,
+                "mesh `{}`: vertices moved by up to {}",
+                mesh, max_deviation
+            ),
+            Difference::MaterialAdded { index, name } => {
+                write!(f, "material added: `{}` at index {}", name, index)
+            }
+            Difference::MaterialRemoved { index, name } => {
+                write!(f, "material removed: `{}` at index {}", name, index)
+            }
+            Difference::MaterialPropertyChanged {
+                material,
+                key,
+                semantic,
+                index,
+                old,
+                new,
+            } => {
+                write!(f, "material `{}`: {}", material, key)?;
+                if *semantic != 0 || *index != 0 {
+                    write!(f, "[{}, {}]", semantic, index)?;
+                }
+                write!(f, " ")?;
+                value(f, old)?;
+                write!(f, " -> ")?;
+                value(f, new)
+            }
+            Difference::NodeAdded { path } => write!(f, "node added: `{}`", path),
+            Difference::NodeRemoved { path } => write!(f, "node removed: `{}`", path),
+            Difference::NodeRenamed { path, new_name } => {
+                write!(f, "node renamed: `{}` -> `{}`", path, new_name)
+            }
+            Difference::NodeRetransformed { path, max_delta } => write!(
+                f,
+                "node `{}`: transform changed by up to {}",
+                path, max_delta
+            ),
+            Difference::AnimationAdded { name } => write!(f, "animation added: `{}`", name),
+            Difference::AnimationRemoved { name } => write!(f, "animation removed: `{}`", name),
+            Difference::AnimationDurationChanged { name, old, new } => {
+                write!(f, "animation `{}`: duration {} -> {}", name, old, new)
+            }
+            Difference::ChannelAdded { animation, node } => {
+                write!(f, "animation `{}`: channel added for `{}`", animation, node)
+            }
+            Difference::ChannelRemoved { animation, node } => {
+                write!(
+                    f,
+                    "animation `{}`: channel removed for `{}`",
+                    animation, node
+                )
+            }
+        }
+    }
+}
+
+/// The differences between two scenes, in a stable order: meshes, then materials, then nodes,
+/// then animations.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SceneDiff {
+    pub differences: Vec<Difference>,
+}
+
+impl SceneDiff {
+    /// Returns true if no differences were found.
+    pub fn is_empty(&self) -> bool {
+        self.differences.is_empty()
+    }
+
+    /// The number of differences found.
+    pub fn len(&self) -> usize {
+        self.differences.len()
+    }
+
+    /// Returns an iterator over the differences.
+    pub fn iter(&self) -> std::slice::Iter<'_, Difference> {
+        self.differences.iter()
+    }
+}
+
+impl fmt::Display for SceneDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "no differences");
+        }
+
+        for difference in &self.differences {
+            writeln!(f, "{}", difference)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Compare two scenes using the default `NameThenIndex` matcher.
+pub fn diff(a: &Scene, b: &Scene, tolerance: DiffTolerance) -> SceneDiff {
+    diff_with(a, b, tolerance, &NameThenIndex)
+}
+
+/// Compare two scenes, using `matcher` to decide which elements correspond to each other.
+pub fn diff_with<M: Matcher + ?Sized>(
+    a: &Scene,
+    b: &Scene,
+    tolerance: DiffTolerance,
+    matcher: &M,
+) -> SceneDiff {
+    let mut differ = Differ {
+        tolerance,
+        matcher,
+        out: Vec::new(),
+    };
+
+    differ.meshes(a, b);
+    differ.materials(a, b);
+    differ.nodes(a, b);
+    differ.animations(a, b);
+
+    SceneDiff {
+        differences: differ.out,
+    }
+}
+
+struct Differ<'m, M: ?Sized> {
+    tolerance: DiffTolerance,
+    matcher: &'m M,
+    out: Vec<Difference>,
+}
+
+impl<M: Matcher + ?Sized> Differ<'_, M> {
+    fn meshes(&mut self, a: &Scene, b: &Scene) {
+        let a: Vec<&Mesh> = a.meshes().collect();
+        let b: Vec<&Mesh> = b.meshes().collect();
+        let pairs = sanitize(self.matcher.match_meshes(&a, &b), a.len(), b.len());
+
+        for &(i, j) in &pairs.matched {
+            let (old, new) = (a[i], b[j]);
+            let mesh = label(old.try_name().unwrap_or(""), i);
+
+            if old.try_name().unwrap_or("") != new.try_name().unwrap_or("") {
+                self.out.push(Difference::MeshRenamed {
+                    old: mesh.clone(),
+                    new: label(new.try_name().unwrap_or(""), j),
+                });
+            }
+
+            if old.num_vertices() != new.num_vertices() {
+                self.out.push(Difference::VertexCountChanged {
+                    mesh,
+                    old: old.num_vertices(),
+                    new: new.num_vertices(),
+                });
+            } else if !same_topology(old, new) {
+                self.out.push(Difference::TopologyChanged { mesh });
+            } else {
+                let max_deviation = old
+                    .positions()
+                    .zip(new.positions())
+                    .map(|(p, q)| (p - q).length())
+                    .fold(0.0, f32::max);
+
+                if max_deviation > self.tolerance.position {
+                    self.out.push(Difference::VerticesMoved {
+                        mesh,
+                        max_deviation,
+                    });
+                }
+            }
+        }
+
+        for &i in &pairs.removed {
+            self.out.push(Difference::MeshRemoved {
+                index: i as u32,
+                name: a[i].try_name().unwrap_or("").to_owned(),
+            });
+        }
+        for &j in &pairs.added {
+            self.out.push(Difference::MeshAdded {
+                index: j as u32,
+                name: b[j].try_name().unwrap_or("").to_owned(),
+            });
+        }
+    }
+
+    fn materials(&mut self, a: &Scene, b: &Scene) {
+        let a: Vec<&Material> = a.materials().collect();
+        let b: Vec<&Material> = b.materials().collect();
+        let pairs = sanitize(self.matcher.match_materials(&a, &b), a.len(), b.len());
+
+        for &(i, j) in &pairs.matched {
+            let material = label(&material_name(a[i]), i);
+            let old = property_map(a[i]);
+            let new = property_map(b[j]);
+
+            let keys: BTreeSet<_> = old.keys().chain(new.keys()).collect();
+            for key in keys {
+                let (old, new) = (old.get(key), new.get(key));
+                let same = match (old, new) {
+                    (Some(old), Some(new)) => old.approx_eq(new, self.tolerance.value),
+                    _ => false,
+                };
+
+                if !same {
+                    self.out.push(Difference::MaterialPropertyChanged {
+                        material: material.clone(),
+                        key: key.0.clone(),
+                        semantic: key.1,
+                        index: key.2,
+                        old: old.cloned(),
+                        new: new.cloned(),
+                    });
+                }
+            }
+        }
+
+        for &i in &pairs.removed {
+            self.out.push(Difference::MaterialRemoved {
+                index: i as u32,
+                name: material_name(a[i]),
+            });
+        }
+        for &j in &pairs.added {
+            self.out.push(Difference::MaterialAdded {
+                index: j as u32,
+                name: material_name(b[j]),
+            });
+        }
+    }
+
+    fn nodes(&mut self, a: &Scene, b: &Scene) {
+        match (a.root_node(), b.root_node()) {
+            (Some(a), Some(b)) => self.node(a, b, a.try_name().unwrap_or("").to_owned()),
+            (Some(a), None) => self.out.push(Difference::NodeRemoved {
+                path: a.try_name().unwrap_or("").to_owned(),
+            }),
+            (None, Some(b)) => self.out.push(Difference::NodeAdded {
+                path: b.try_name().unwrap_or("").to_owned(),
+            }),
+            (None, None) => {}
+        }
+    }
+
+    fn node(&mut self, a: &Node, b: &Node, path: String) {
+        if a.try_name().unwrap_or("") != b.try_name().unwrap_or("") {
+            self.out.push(Difference::NodeRenamed {
+                path: path.clone(),
+                new_name: b.try_name().unwrap_or("").to_owned(),
+            });
+        }
+
+        let (ta, tb) = (a.transform(), b.transform());
+        let max_delta = [
+            ta.a1 - tb.a1,
+            ta.a2 - tb.a2,
+            ta.a3 - tb.a3,
+            ta.a4 - tb.a4,
+            ta.b1 - tb.b1,
+            ta.b2 - tb.b2,
+            ta.b3 - tb.b3,
+            ta.b4 - tb.b4,
+            ta.c1 - tb.c1,
+            ta.c2 - tb.c2,
+            ta.c3 - tb.c3,
+            ta.c4 - tb.c4,
+            ta.d1 - tb.d1,
+            ta.d2 - tb.d2,
+            ta.d3 - tb.d3,
+            ta.d4 - tb.d4,
+        ]
+        .iter()
+        .map(|delta| delta.abs())
+        .fold(0.0, f32::max);
+
+        if max_delta > self.tolerance.transform {
+            self.out.push(Difference::NodeRetransformed {
+                path: path.clone(),
+                max_delta,
+            });
+        }
+
+        let a_children: Vec<&Node> = a.children().collect();
+        let b_children: Vec<&Node> = b.children().collect();
+        let pairs = sanitize(
+            self.matcher.match_nodes(&a_children, &b_children),
+            a_children.len(),
+            b_children.len(),
+        );
+
+        for &(i, j) in &pairs.matched {
+            let child_path = format!("{}/{}", path, a_children[i].try_name().unwrap_or(""));
+            self.node(a_children[i], b_children[j], child_path);
+        }
+        for &i in &pairs.removed {
+            self.out.push(Difference::NodeRemoved {
+                path: format!("{}/{}", path, a_children[i].try_name().unwrap_or("")),
+            });
+        }
+        for &j in &pairs.added {
+            self.out.push(Difference::NodeAdded {
+                path: format!("{}/{}", path, b_children[j].try_name().unwrap_or("")),
+            });
+        }
+    }
+
+    fn animations(&mut self, a: &Scene, b: &Scene) {
+        let a: Vec<&Animation> = a.animations().collect();
+        let b: Vec<&Animation> = b.animations().collect();
+        let pairs = sanitize(self.matcher.match_animations(&a, &b), a.len(), b.len());
+
+        for &(i, j) in &pairs.matched {
+            let (old, new) = (a[i], b[j]);
+            let name = label(old.try_name().unwrap_or(""), i);
+
+            if (old.duration() - new.duration()).abs() > self.tolerance.time {
+                self.out.push(Difference::AnimationDurationChanged {
+                    name: name.clone(),
+                    old: old.duration(),
+                    new: new.duration(),
+                });
+            }
+
+            let old_channels: BTreeSet<&str> = old
+                .node_anims()
+                .map(|channel| channel.try_node_name().unwrap_or(""))
+                .collect();
+            let new_channels: BTreeSet<&str> = new
+                .node_anims()
+                .map(|channel| channel.try_node_name().unwrap_or(""))
+                .collect();
+
+            for node in old_channels.difference(&new_channels) {
+                self.out.push(Difference::ChannelRemoved {
+                    animation: name.clone(),
+                    node: (*node).to_owned(),
+                });
+            }
+            for node in new_channels.difference(&old_channels) {
+                self.out.push(Difference::ChannelAdded {
+                    animation: name.clone(),
+                    node: (*node).to_owned(),
+                });
+            }
+        }
+
+        for &i in &pairs.removed {
+            self.out.push(Difference::AnimationRemoved {
+                name: label(a[i].try_name().unwrap_or(""), i),
+            });
+        }
+        for &j in &pairs.added {
+            self.out.push(Difference::AnimationAdded {
+                name: label(b[j].try_name().unwrap_or(""), j),
+            });
+        }
+    }
+}
+
+struct Pairs {
+    matched: Vec<(usize, usize)>,
+    removed: Vec<usize>,
+    added: Vec<usize>,
+}
+
+/// Drop invalid pairs from a matcher's output, sort the rest and work out what's left over.
+fn sanitize(pairs: Vec<(usize, usize)>, len_a: usize, len_b: usize) -> Pairs {
+    let mut used_a = vec![false; len_a];
+    let mut used_b = vec![false; len_b];
+    let mut matched = Vec::with_capacity(pairs.len());
+
+    for (i, j) in pairs {
+        if i < len_a && j < len_b && !used_a[i] && !used_b[j] {
+            used_a[i] = true;
+            used_b[j] = true;
+            matched.push((i, j));
+        }
+    }
+
+    matched.sort_unstable();
+
+    Pairs {
+        matched,
+        removed: (0..len_a).filter(|&i| !used_a[i]).collect(),
+        added: (0..len_b).filter(|&j| !used_b[j]).collect(),
+    }
+}
+
+fn label(name: &str, index: usize) -> String {
+    if name.is_empty() {
+        format!("#{}", index)
+    } else {
+        name.to_owned()
+    }
+}
+
+pub(crate) fn material_name(material: &Material) -> String {
+    match material.get_value(MaterialKey::Name) {
+        Some(MaterialValue::String(name)) => name.to_str().unwrap_or("").to_owned(),
+        _ => String::new(),
+    }
+}
+
+fn same_topology(a: &Mesh, b: &Mesh) -> bool {
+    a.num_faces() == b.num_faces()
+        && a.faces()
+            .zip(b.faces())
+            .all(|(fa, fb)| fa.indices() == fb.indices())
+}
+
+fn property_map(material: &Material) -> BTreeMap<(String, u32, u32), PropertyValue> {
+    material
+        .properties()
+        .map(|property| {
+            (
+                (
+                    property.try_key().unwrap_or("").to_owned(),
+                    property.semantic(),
+                    property.index(),
+                ),
+                PropertyValue::from_property(property),
+            )
+        })
+        .collect()
+}
@@ -0,0 +1,264 @@
+//! Writing the embedded textures of a scene to loose files, see
+//! `Scene::extract_embedded_textures`.
+
+use std::collections::HashSet;
+use std::convert::TryFrom;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::Error;
+
+use super::material::{MaterialKey, MaterialSource, MaterialValue};
+use super::overlay::MaterialOverlay;
+use super::scene::Scene;
+use super::texture::Texture;
+
+/// How `Scene::extract_embedded_textures` names the files it writes. The extension always
+/// comes from the texture's format, see `Scene::extract_embedded_textures`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NamingStrategy {
+    /// `texture_0.png`, `texture_1.jpg` and so on, by the index of the texture in the scene.
+    Index,
+    /// The file name of the texture's original filename, without any directories, if the file
+    /// stores one. Textures without a filename, or whose name is already taken, are named as
+    /// with `Index`.
+    OriginalName,
+    /// Like `Index`, but with the given prefix instead of `texture_`.
+    Prefix(String),
+}
+
+/// A texture written by `Scene::extract_embedded_textures`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtractedTexture {
+    /// The index of the texture in `Scene::textures`.
+    pub index: u32,
+    /// How materials refer to the texture, e.g. `*0`.
+    pub reference: String,
+    /// The original filename of the texture, if the file stores one. Some formats refer to
+    /// embedded textures by this rather than by `reference`.
+    pub filename: Option<String>,
+    /// The path of the written file, relative to the directory it was written to.
+    pub path: PathBuf,
+}
+
+impl Scene<'_> {
+    /// Write every embedded texture of the scene to a file in `dir`, which is created if it
+    /// doesn't exist, and return where each one went. Existing files are overwritten.
+    ///
+    /// Compressed textures, i.e. image files such as PNGs, are written as they are, with the
+    /// extension from `Texture::format_hint`, or `.bin` if there is none. Uncompressed textures
+    /// are encoded as PNG if the `image` feature is enabled. Otherwise they're written as
+    /// `.bgra` files: the width and height as little-endian 32-bit integers, followed by the
+    /// texels row by row, each as 4 bytes in blue, green, red, alpha order.
+    ///
+    /// If writing a texture fails, the textures before it are left on disk. That's returned as
+    /// `Error::Io`, or `Error::ExportFailed` if encoding a texture fails. Compressed textures
+    /// without any data give `Error::MissingData`.
+    pub fn extract_embedded_textures(
+        &self,
+        dir: &Path,
+        naming: NamingStrategy,
+    ) -> Result<Vec<ExtractedTexture>, Error> {
+        fs::create_dir_all(dir)?;
+
+        let names = self.texture_file_names(&naming);
+        let mut extracted = Vec::new();
+        for ((index, texture), name) in self.textures().enumerate().zip(names) {
+            let index = index as u32;
+            let path = dir.join(&name);
+
+            match texture.compressed_data() {
+                Some(data) => fs::write(&path, data)?,
+                None if texture.is_compressed() => return Err(missing_data(index)),
+                None => write_texels(texture, index, &path)?,
+            }
+
+            let filename = texture.filename_cstr().to_str().ok();
+            extracted.push(ExtractedTexture {
+                index,
+                reference: format!("*{}", index),
+                filename: filename.filter(|name| !name.is_empty()).map(str::to_owned),
+                path: PathBuf::from(name),
+            });
+        }
+
+        Ok(extracted)
+    }
+
+    /// The names of the files that `extract_embedded_textures` writes, by texture index.
+    pub(crate) fn texture_file_names(&self, naming: &NamingStrategy) -> Vec<String> {
+        let mut taken = HashSet::new();
+        self.textures()
+            .enumerate()
+            .map(|(index, texture)| file_name(texture, index as u32, naming, &mut taken))
+            .collect()
+    }
+}
+
+impl MaterialOverlay<'_> {
+    /// Point every texture of every material that refers to one of `extracted` at the extracted
+    /// file instead, see `Scene::extract_embedded_textures`. A texture path matches if it's the
+    /// texture's `reference` or its `filename`. Returns the number of paths that were changed.
+    ///
+    /// The new paths are the relative paths from `extracted`, so they're relative to the
+    /// directory the textures were extracted to. Only textures of the components in
+    /// `MaterialComponentType` can be rewritten, since the overlay can't override the others.
+    pub fn rewrite_texture_paths(&mut self, extracted: &[ExtractedTexture]) -> usize {
+        let mut rewrites = Vec::new();
+        for (id, material) in self.materials().enumerate() {
+            for property in material.original().properties() {
+                let component = match property.component() {
+                    Some(component) if property.try_key() == Ok("$tex.file") => component,
+                    _ => continue,
+                };
+                let key = MaterialKey::Texture(component, property.index());
+                let path = match material.get_value(key) {
+                    Some(MaterialValue::String(path)) => path,
+                    _ => continue,
+                };
+                let path = match path.to_str() {
+                    Ok(path) => path,
+                    Err(_) => continue,
+                };
+
+                let texture = extracted.iter().find(|texture| {
+                    texture.reference == path || texture.filename.as_deref() == Some(path)
+                });
+                let new_path = texture
+                    .and_then(|texture| texture.path.to_str())
+                    .and_then(|new_path| crate::InlineString::try_from(new_path).ok());
+                if let Some(new_path) = new_path {
+                    rewrites.push((id as u32, key, new_path));
+                }
+            }
+        }
+
+        let count = rewrites.len();
+        for (id, key, path) in rewrites {
+            self.set(id, key, MaterialValue::String(path));
+        }
+        count
+    }
+}
+
+fn file_name(
+    texture: &Texture,
+    index: u32,
+    naming: &NamingStrategy,
+    taken: &mut HashSet<String>,
+) -> String {
+    let extension = extension(texture);
+    let original = match naming {
+        NamingStrategy::OriginalName => original_stem(texture),
+        _ => None,
+    };
+
+    if let Some(stem) = original {
+        let name = format!("{}.{}", stem, extension);
+        if taken.insert(name.to_lowercase()) {
+            return name;
+        }
+    }
+
+    let prefix = match naming {
+        NamingStrategy::Prefix(prefix) => prefix.as_str(),
+        _ => "texture_",
+    };
+    let mut name = format!("{}{}.{}", prefix, index, extension);
+    // An original name can have taken this name already.
+    let mut suffix = 1;
+    while !taken.insert(name.to_lowercase()) {
+        name = format!("{}{}_{}.{}", prefix, index, suffix, extension);
+        suffix += 1;
+    }
+    name
+}
+
+/// The file name of the texture's filename, without directories or extension, and with any
+/// characters that aren't safe in file names replaced.
+fn original_stem(texture: &Texture) -> Option<String> {
+    let filename = texture.filename_cstr().to_str().ok()?;
+    // Files written on Windows use backslashes, whatever platform this is.
+    let name = filename.rsplit(|c| c == '/' || c == '\\').next()?;
+    let stem = match name.rfind('.') {
+        Some(dot) if dot > 0 => &name[..dot],
+        _ => name,
+    };
+
+    let stem: String = stem
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' || c == '.' || c == ' ' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    if stem.trim_matches('.').is_empty() {
+        None
+    } else {
+        Some(stem)
+    }
+}
+
+fn missing_data(index: u32) -> Error {
+    Error::MissingData {
+        what: format!("data of texture {}", index),
+    }
+}
+
+fn extension(texture: &Texture) -> String {
+    if !texture.is_compressed() {
+        return if cfg!(feature = "image") {
+            "png"
+        } else {
+            "bgra"
+        }
+        .to_owned();
+    }
+
+    let hint = match texture.try_format_hint() {
+        Ok(Some(hint)) if hint.chars().all(|c| c.is_ascii_alphanumeric()) => hint,
+        _ => "bin",
+    };
+    hint.to_ascii_lowercase()
+}
+
+#[cfg(feature = "image")]
+fn write_texels(texture: &Texture, index: u32, path: &Path) -> Result<(), Error> {
+    let data = texture.data().ok_or_else(|| missing_data(index))?;
+
+    let mut rgba = Vec::with_capacity(data.bytes().len());
+    for texel in data.texels() {
+        rgba.extend_from_slice(&[texel.r, texel.g, texel.b, texel.a]);
+    }
+
+    image::save_buffer_with_format(
+        path,
+        &rgba,
+        texture.width(),
+        texture.height(),
+        image::ColorType::Rgba8,
+        image::ImageFormat::Png,
+    )
+    .map_err(|err| match err {
+        image::ImageError::IoError(err) => Error::Io(err),
+        err => Error::ExportFailed {
+            message: format!("couldn't encode texture {}: {}", index, err),
+        },
+    })
+}
+
+#[cfg(not(feature = "image"))]
+fn write_texels(texture: &Texture, index: u32, path: &Path) -> Result<(), Error> {
+    let data = texture.data().ok_or_else(|| missing_data(index))?;
+
+    let mut out = Vec::with_capacity(8 + data.bytes().len());
+    out.extend_from_slice(&texture.width().to_le_bytes());
+    out.extend_from_slice(&texture.height().to_le_bytes());
+    out.extend_from_slice(data.bytes());
+    fs::write(path, out)?;
+    Ok(())
+}
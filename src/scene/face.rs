@@ -9,33 +9,34 @@ use std::{
 use ffi::aiFace;
 
 define_type_and_iterator! {
-    /// Face type (not yet implemented)
+    /// A face of a mesh, as a list of indices into its vertices.
     struct Face(&aiFace)
     /// Face iterator type.
     struct FaceIter
 }
 
 impl Face {
-    /// The "kind" of this face - each mesh contains a bitset of all the primitive types that this mesh
-    /// contains. For most applications you will want to call `Importer::triangulate(true)`, which will
-    /// make Assimp automatically convert all faces to triangles.
-    pub fn primitive_type(&self) -> PrimitiveType {
+    /// The kind of primitive this face is, by its number of indices, or `None` for a face without
+    /// any, which only a corrupt file can produce.
+    ///
+    /// Assimp classifies faces the same way, but it only records the result per mesh, in
+    /// `Mesh::primitive_types`. Use that, or `Mesh::is_pure`, to check a whole mesh instead of
+    /// looking at every face, and `Mesh::faces_of_type` to pick out the faces of one kind. For most
+    /// applications you will want to call `Importer::triangulate(true)`, which will make Assimp
+    /// automatically convert all polygons to triangles.
+    pub fn primitive_type(&self) -> Option<PrimitiveType> {
         match self.indices().len() {
-            0 => unreachable!(),
-            1 => PrimitiveType::Point,
-            2 => PrimitiveType::Line,
-            3 => PrimitiveType::Triangle,
-            _ => PrimitiveType::Polygon,
+            0 => None,
+            1 => Some(PrimitiveType::Point),
+            2 => Some(PrimitiveType::Line),
+            3 => Some(PrimitiveType::Triangle),
+            _ => Some(PrimitiveType::Polygon),
         }
     }
 
     /// The list of indices into the parent mesh's vertex list used by this face.
     pub fn indices(&self) -> &[u32] {
-        if self.mIndices.is_null() {
-            &[]
-        } else {
-            unsafe { std::slice::from_raw_parts(self.mIndices, self.mNumIndices as usize) }
-        }
+        unsafe { crate::array_slice(self.mIndices, self.mNumIndices as usize) }
     }
 }
 
@@ -64,8 +65,6 @@ impl Index<usize> for Face {
     type Output = u32;
 
     fn index(&self, index: usize) -> &u32 {
-        assert!(index < self.mNumIndices as usize);
-
-        unsafe { &*self.mIndices.offset(index as isize) }
+        &self.indices()[index]
     }
 }
@@ -0,0 +1,153 @@
+//! Typed per-node flags read from the metadata conventions of exporters, see
+//! `Node::standard_flags`.
+//!
+//! Exporters store flags such as visibility in node metadata, under keys that differ between
+//! formats and tools. The keys in `STANDARD_FLAG_KEYS` are always recognized, and studios can
+//! add their own with `register_flag_key`. Keys are compared ignoring ASCII case, spaces,
+//! underscores and hyphens, so `Casts Shadows` and `casts_shadows` are the same key.
+
+use std::collections::BTreeMap;
+use std::sync::{Mutex, PoisonError};
+
+use super::node::{MetadataValue, Node};
+
+/// What a metadata key says about a node.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum FlagTarget {
+    Visible,
+    CastsShadows,
+    ReceivesShadows,
+    Static,
+    /// A flag of your own, which ends up in `NodeStandardFlags::custom` under this name.
+    Custom(&'static str),
+}
+
+/// The keys that `Node::standard_flags` always recognizes:
+///
+/// - `Visibility` and `Show`, from FBX files, `$fbx.Show`, and `visible`, which glTF and
+///   Collada exporters commonly use for extras.
+/// - `Casts Shadows` and `Receive Shadows`, from FBX files written by Maya, and `castShadow`
+///   and `receiveShadow`, the names three.js uses, in glTF extras.
+/// - `static` and `isStatic`, which game engine exporters use for geometry that never moves.
+pub const STANDARD_FLAG_KEYS: &[(&str, FlagTarget)] = &[
+    ("Visibility", FlagTarget::Visible),
+    ("Show", FlagTarget::Visible),
+    ("$fbx.Show", FlagTarget::Visible),
+    ("visible", FlagTarget::Visible),
+    ("Casts Shadows", FlagTarget::CastsShadows),
+    ("castShadow", FlagTarget::CastsShadows),
+    ("Receive Shadows", FlagTarget::ReceivesShadows),
+    ("receiveShadow", FlagTarget::ReceivesShadows),
+    ("receives_shadows", FlagTarget::ReceivesShadows),
+    ("static", FlagTarget::Static),
+    ("isStatic", FlagTarget::Static),
+];
+
+/// The keys added with `register_flag_key`, normalized, in order.
+static REGISTERED: Mutex<Vec<(String, FlagTarget)>> = Mutex::new(Vec::new());
+
+/// Recognize `key` as a flag for `target` in every later call to `Node::standard_flags`, on
+/// any thread. A registered key takes precedence over a standard key that compares equal to
+/// it. Returns the target the key was registered for before, if any.
+pub fn register_flag_key(key: &str, target: FlagTarget) -> Option<FlagTarget> {
+    let key = normalize(key);
+    let mut registered = REGISTERED.lock().unwrap_or_else(PoisonError::into_inner);
+    match registered.iter_mut().find(|(other, _)| *other == key) {
+        Some((_, previous)) => Some(std::mem::replace(previous, target)),
+        None => {
+            registered.push((key, target));
+            None
+        }
+    }
+}
+
+/// The flags of a node, as returned by `Node::standard_flags`. Each is `None` if the node has
+/// no metadata for it that could be read.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct NodeStandardFlags {
+    pub visible: Option<bool>,
+    pub casts_shadows: Option<bool>,
+    pub receives_shadows: Option<bool>,
+    pub static_flag: Option<bool>,
+    /// The flags for `FlagTarget::Custom` keys, by name.
+    pub custom: BTreeMap<&'static str, bool>,
+}
+
+impl NodeStandardFlags {
+    fn set(&mut self, target: FlagTarget, value: bool) {
+        let slot = match target {
+            FlagTarget::Visible => &mut self.visible,
+            FlagTarget::CastsShadows => &mut self.casts_shadows,
+            FlagTarget::ReceivesShadows => &mut self.receives_shadows,
+            FlagTarget::Static => &mut self.static_flag,
+            FlagTarget::Custom(name) => {
+                let flag = self.custom.entry(name).or_insert(true);
+                *flag = *flag && value;
+                return;
+            }
+        };
+        *slot = Some(slot.unwrap_or(true) && value);
+    }
+}
+
+impl Node {
+    /// The flags that the node's metadata sets, read from the keys in `STANDARD_FLAG_KEYS` and
+    /// those added with `register_flag_key`.
+    ///
+    /// Booleans are read as they are. Numbers are false if they're 0 and true if they're
+    /// positive, since FBX stores visibility as a fade factor. Strings are true if they're
+    /// `true`, `yes`, `on` or `1`, and false if they're `false`, `no`, `off` or `0`, ignoring
+    /// case. Other values are ignored. If several keys set the same flag, it's only true if
+    /// all of them say so, since files usually only write a key to change its default.
+    pub fn standard_flags(&self) -> NodeStandardFlags {
+        let registered = REGISTERED.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut flags = NodeStandardFlags::default();
+        for (key, entry) in self.metadata() {
+            let key = match key.to_str() {
+                Ok(key) => normalize(key),
+                Err(_) => continue,
+            };
+            let target = registered
+                .iter()
+                .find(|(other, _)| *other == key)
+                .map(|&(_, target)| target)
+                .or_else(|| {
+                    STANDARD_FLAG_KEYS
+                        .iter()
+                        .find(|(other, _)| normalize(other) == key)
+                        .map(|&(_, target)| target)
+                });
+            if let (Some(target), Some(value)) = (target, parse(entry.get())) {
+                flags.set(target, value);
+            }
+        }
+        flags
+    }
+}
+
+/// `key` in lowercase, without spaces, underscores and hyphens.
+fn normalize(key: &str) -> String {
+    key.chars()
+        .filter(|c| !matches!(c, ' ' | '_' | '-'))
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+fn parse(value: MetadataValue) -> Option<bool> {
+    match value {
+        MetadataValue::Bool(value) => Some(value),
+        MetadataValue::I32(value) if value >= 0 => Some(value > 0),
+        MetadataValue::U64(value) => Some(value > 0),
+        MetadataValue::F32(value) if value >= 0.0 => Some(value > 0.0),
+        MetadataValue::F64(value) if value >= 0.0 => Some(value > 0.0),
+        MetadataValue::Str(value) => {
+            let value = value.to_str().ok()?.trim().to_ascii_lowercase();
+            match &*value {
+                "true" | "yes" | "on" | "1" => Some(true),
+                "false" | "no" | "off" | "0" => Some(false),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
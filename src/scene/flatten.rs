@@ -0,0 +1,223 @@
+//! Baking the texture stack of a material component to a single color or image, see
+//! `MaterialComponent::flatten`.
+
+use image::imageops::{self, FilterType};
+use image::{DynamicImage, RgbaImage};
+
+use crate::math::Color3D;
+
+use super::material::{
+    BlendOp, Mapping, MaterialComponent, TextureDefinition, TextureFlags, WrappingMode,
+};
+
+/// Options for `MaterialComponent::flatten_with`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct FlattenOptions {
+    /// The width and height of the baked image, or `None` for the largest width and height of
+    /// the textures in the stack.
+    pub size: Option<(u32, u32)>,
+    /// The filter used to resample textures that aren't already of that size.
+    pub filter: FilterType,
+}
+
+impl Default for FlattenOptions {
+    /// The size of the largest texture, resampled with `FilterType::Triangle`.
+    fn default() -> Self {
+        FlattenOptions {
+            size: None,
+            filter: FilterType::Triangle,
+        }
+    }
+}
+
+/// The result of `MaterialComponent::flatten`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlattenedComponent {
+    pub value: FlattenedValue,
+    /// How the baked image should be wrapped in u and v. This is the mode of every texture in
+    /// the stack, or `WrappingMode::Clamp` if they don't agree or a mode isn't known.
+    pub wrap: (WrappingMode, WrappingMode),
+    /// Everything about the stack that the baked result can't represent.
+    pub warnings: Vec<FlattenWarning>,
+}
+
+/// The color or image that a component's texture stack was flattened to.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FlattenedValue {
+    /// The component's base color, because it has no textures or none of them were resolved.
+    Color(Color3D),
+    Image(RgbaImage),
+}
+
+/// Something about a texture stack that `MaterialComponent::flatten` had to approximate. Indices
+/// are positions in the stack.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FlattenWarning {
+    /// The resolver didn't return an image for this texture, so it was left out.
+    MissingTexture { index: usize, path: String },
+    /// The texture uses a mapping other than UV, so it was applied as if it used UV mapping.
+    /// Textures without a mapping are assumed to use UV mapping without a warning.
+    NotUvMapping { index: usize, mapping: Mapping },
+    /// The texture uses a different UV channel to the first texture, and was applied as if it
+    /// used the same one.
+    DifferentChannel { index: usize, channel: u32 },
+    /// The textures don't all have the same known wrapping modes, so the result is clamped.
+    WrapModeFallback,
+}
+
+impl<I: Iterator<Item = TextureDefinition>> MaterialComponent<I> {
+    /// Bake the component's texture stack into a single color or image, with
+    /// `FlattenOptions::default()`. See `flatten_with`.
+    pub fn flatten<F>(self, resolver: F) -> FlattenedComponent
+    where
+        F: Fn(&TextureDefinition) -> Option<DynamicImage>,
+    {
+        self.flatten_with(resolver, FlattenOptions::default())
+    }
+
+    /// Bake the component's texture stack into a single color or image, for renderers that
+    /// can only bind one texture per component. `resolver` loads the image of each texture,
+    /// e.g. from its `path`, and returns `None` if it can't.
+    ///
+    /// Starting from `color`, each texture is scaled by its `strength` and combined with the
+    /// result so far by its `blend_op`, in the order of the stack, as Assimp describes.
+    /// Textures with the `INVERT` flag are inverted first. The color channels are blended and
+    /// clamped to 0..1 after every step, and the alpha channel is multiplied by each
+    /// texture's alpha, or replaced by it for `BlendOp::Replace`, unless the texture has the
+    /// `IGNORE_ALPHA` flag. Every texture is resampled to the size of the result, so they're
+    /// all assumed to cover the same UV range.
+    ///
+    /// This consumes the component, since its textures are an iterator.
+    pub fn flatten_with<F>(self, resolver: F, opts: FlattenOptions) -> FlattenedComponent
+    where
+        F: Fn(&TextureDefinition) -> Option<DynamicImage>,
+    {
+        let base = self.color;
+        let mut warnings = Vec::new();
+        let mut wrap = None;
+        let mut wrap_fallback = false;
+        let mut channel = None;
+
+        let mut layers = Vec::new();
+        for (index, texture) in self.textures.enumerate() {
+            let image = match resolver(&texture) {
+                Some(image) => image.to_rgba8(),
+                None => {
+                    let path = texture.path.to_str().unwrap_or_default().to_owned();
+                    warnings.push(FlattenWarning::MissingTexture { index, path });
+                    continue;
+                }
+            };
+
+            match texture.mapping {
+                None | Some(Mapping::UV) => {}
+                Some(mapping) => warnings.push(FlattenWarning::NotUvMapping { index, mapping }),
+            }
+            match channel {
+                None => channel = Some(texture.channel),
+                Some(first) if first != texture.channel => {
+                    warnings.push(FlattenWarning::DifferentChannel {
+                        index,
+                        channel: texture.channel,
+                    })
+                }
+                Some(_) => {}
+            }
+            match (texture.wrap_u, texture.wrap_v, wrap) {
+                (Some(u), Some(v), None) => wrap = Some((u, v)),
+                (Some(u), Some(v), Some(modes)) if modes == (u, v) => {}
+                _ => wrap_fallback = true,
+            }
+
+            layers.push((texture, image));
+        }
+
+        if wrap_fallback {
+            warnings.push(FlattenWarning::WrapModeFallback);
+        }
+        let wrap = match wrap {
+            Some(wrap) if !wrap_fallback => wrap,
+            _ => (WrappingMode::Clamp, WrappingMode::Clamp),
+        };
+
+        if layers.is_empty() {
+            return FlattenedComponent {
+                value: FlattenedValue::Color(base),
+                wrap,
+                warnings,
+            };
+        }
+
+        let (width, height) = opts.size.unwrap_or_else(|| {
+            layers.iter().fold((1, 1), |(width, height), (_, image)| {
+                (width.max(image.width()), height.max(image.height()))
+            })
+        });
+
+        let mut out = vec![[base.r, base.g, base.b, 1.0]; width as usize * height as usize];
+        for (texture, image) in layers {
+            let image = if image.dimensions() == (width, height) {
+                image
+            } else {
+                imageops::resize(&image, width, height, opts.filter)
+            };
+
+            for (prev, texel) in out.iter_mut().zip(image.pixels()) {
+                blend(prev, &texture, texel.0);
+            }
+        }
+
+        let mut image = RgbaImage::new(width, height);
+        for (pixel, value) in image.pixels_mut().zip(out) {
+            for (channel, value) in pixel.0.iter_mut().zip(value.iter()) {
+                *channel = (value * 255.0).round() as u8;
+            }
+        }
+
+        FlattenedComponent {
+            value: FlattenedValue::Image(image),
+            wrap,
+            warnings,
+        }
+    }
+}
+
+/// Combine `texel` of `texture` with `prev`, the result so far.
+fn blend(prev: &mut [f32; 4], texture: &TextureDefinition, texel: [u8; 4]) {
+    let invert = texture.flags.contains(TextureFlags::INVERT);
+
+    for (prev, &texel) in prev.iter_mut().zip(texel.iter()).take(3) {
+        let mut cur = texel as f32 / 255.0;
+        if invert {
+            cur = 1.0 - cur;
+        }
+        cur *= texture.strength;
+
+        let value = match texture.blend_op {
+            BlendOp::Multiply => *prev * cur,
+            BlendOp::Add => *prev + cur,
+            BlendOp::Subtract => *prev - cur,
+            // Dividing by black saturates, rather than giving infinity or NaN.
+            BlendOp::Divide if cur == 0.0 => {
+                if *prev > 0.0 {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            BlendOp::Divide => *prev / cur,
+            BlendOp::SmoothAdd => *prev + cur - *prev * cur,
+            BlendOp::SignedAdd => *prev + cur - 0.5,
+            BlendOp::Replace => cur,
+        };
+        *prev = value.max(0.0).min(1.0);
+    }
+
+    if !texture.flags.contains(TextureFlags::IGNORE_ALPHA) {
+        let alpha = texel[3] as f32 / 255.0;
+        prev[3] = match texture.blend_op {
+            BlendOp::Replace => alpha,
+            _ => prev[3] * alpha,
+        };
+    }
+}
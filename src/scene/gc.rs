@@ -0,0 +1,238 @@
+//! Removing the meshes, materials and embedded textures that nothing in a `SceneSnapshot`
+//! refers to any more, see `collect`.
+//!
+//! Dropping nodes, or the mesh references of nodes, e.g. to remove the parts of a model an
+//! application doesn't show, leaves their meshes behind, and with them the materials and
+//! textures that only those meshes used. `collect` finds what is still reachable from the
+//! hierarchy and removes the rest, renumbering the references to what's left.
+
+use std::mem;
+
+use super::snapshot::{MeshData, SceneSnapshot, TextureTarget};
+
+/// What `collect_with` removes. The default removes everything that's unreachable.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct GcOptions {
+    /// Remove the meshes that no node refers to. Default: true
+    pub meshes: bool,
+    /// Remove the materials that no kept mesh uses. Default: true
+    pub materials: bool,
+    /// Remove the embedded textures that no kept material refers to. Default: true
+    pub textures: bool,
+}
+
+impl Default for GcOptions {
+    fn default() -> Self {
+        GcOptions {
+            meshes: true,
+            materials: true,
+            textures: true,
+        }
+    }
+}
+
+/// What `collect` removed. The indices are the ones from before the removal, in increasing
+/// order.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct GcReport {
+    pub meshes: Vec<u32>,
+    pub materials: Vec<u32>,
+    pub textures: Vec<u32>,
+    /// The bytes of vertex attributes, faces, bone weights and texture data that were removed,
+    /// counted like `prune::PruneReport`.
+    pub bytes: u64,
+}
+
+impl GcReport {
+    /// Whether nothing was removed.
+    pub fn is_empty(&self) -> bool {
+        self.meshes.is_empty() && self.materials.is_empty() && self.textures.is_empty()
+    }
+}
+
+/// Remove everything from `snapshot` that's unreachable from its hierarchy, see
+/// `collect_with`.
+pub fn collect(snapshot: &mut SceneSnapshot) -> GcReport {
+    collect_with(snapshot, GcOptions::default())
+}
+
+/// Remove the meshes, materials and embedded textures that `options` selects and nothing
+/// refers to, and renumber the references to the rest: the meshes of nodes, the material of
+/// each mesh, and the targets of material textures, along with their paths of the form `*0`.
+/// Paths that name an embedded texture by its filename don't change.
+///
+/// Nodes refer to meshes, meshes to materials, and materials to embedded textures, both
+/// `TextureTarget::Embedded` and `TextureTarget::Missing` ones. A snapshot without a root
+/// node has no hierarchy to tell which meshes are used, so its meshes are all kept.
+///
+/// References that were out of range, which Assimp doesn't produce, stay out of range by the
+/// same amount, so they don't start resolving to something else.
+pub fn collect_with(snapshot: &mut SceneSnapshot, options: GcOptions) -> GcReport {
+    let mut report = GcReport::default();
+
+    let mut meshes = vec![!options.meshes || snapshot.root.is_none(); snapshot.meshes.len()];
+    if let Some(root) = &mut snapshot.root {
+        root.visit_mut(&mut |node| {
+            for &index in &node.meshes {
+                if let Some(used) = meshes.get_mut(index as usize) {
+                    *used = true;
+                }
+            }
+        });
+    }
+
+    let mut materials = vec![!options.materials; snapshot.materials.len()];
+    for (mesh, _) in snapshot
+        .meshes
+        .iter()
+        .zip(&meshes)
+        .filter(|&(_, &used)| used)
+    {
+        if let Some(used) = materials.get_mut(mesh.material_index as usize) {
+            *used = true;
+        }
+    }
+
+    let mut textures = vec![!options.textures; snapshot.textures.len()];
+    for (material, _) in snapshot
+        .materials
+        .iter()
+        .zip(&materials)
+        .filter(|&(_, &used)| used)
+    {
+        for texture in &material.textures {
+            if let TextureTarget::Embedded(index) | TextureTarget::Missing(index) = texture.target {
+                if let Some(used) = textures.get_mut(index as usize) {
+                    *used = true;
+                }
+            }
+        }
+    }
+
+    let mesh_map = Renumbering::new(&meshes);
+    let material_map = Renumbering::new(&materials);
+    let texture_map = Renumbering::new(&textures);
+
+    report.meshes = mesh_map.removed();
+    report.materials = material_map.removed();
+    report.textures = texture_map.removed();
+
+    let mut index = 0;
+    snapshot.meshes.retain(|mesh| {
+        let used = meshes[index];
+        if !used {
+            report.bytes += mesh_bytes(mesh);
+        }
+        index += 1;
+        used
+    });
+    let mut index = 0;
+    snapshot.materials.retain(|_| {
+        index += 1;
+        materials[index - 1]
+    });
+    let mut index = 0;
+    snapshot.textures.retain(|texture| {
+        let used = textures[index];
+        if !used {
+            report.bytes += texture.data.as_ref().map_or(0, |data| data.len() as u64);
+        }
+        index += 1;
+        used
+    });
+
+    if let Some(root) = &mut snapshot.root {
+        root.visit_mut(&mut |node| {
+            node.meshes = node
+                .meshes
+                .iter()
+                .map(|&index| mesh_map.get(index))
+                .collect();
+        });
+    }
+    for mesh in &mut snapshot.meshes {
+        mesh.material_index = material_map.get(mesh.material_index);
+    }
+    for material in &mut snapshot.materials {
+        for texture in &mut material.textures {
+            let (old, new) = match texture.target {
+                TextureTarget::Embedded(index) => {
+                    let new = texture_map.get(index);
+                    texture.target = TextureTarget::Embedded(new);
+                    (index, new)
+                }
+                TextureTarget::Missing(index) => {
+                    let new = texture_map.get(index);
+                    texture.target = TextureTarget::Missing(new);
+                    (index, new)
+                }
+                TextureTarget::External => continue,
+            };
+            if old != new && texture.path == format!("*{}", old) {
+                texture.path = format!("*{}", new);
+            }
+        }
+    }
+
+    report
+}
+
+/// The new indices of a list after the entries that aren't kept are removed.
+struct Renumbering {
+    /// The new index of each old one, or `None` for the removed ones.
+    indices: Vec<Option<u32>>,
+    len: u32,
+}
+
+impl Renumbering {
+    fn new(keep: &[bool]) -> Renumbering {
+        let mut len = 0;
+        let indices = keep
+            .iter()
+            .map(|&keep| {
+                if !keep {
+                    return None;
+                }
+                len += 1;
+                Some(len - 1)
+            })
+            .collect();
+        Renumbering { indices, len }
+    }
+
+    /// The new index for `index`. Removed entries are unreachable, so nothing kept refers to
+    /// them, and indices past the end are moved by the number of entries removed.
+    fn get(&self, index: u32) -> u32 {
+        match self.indices.get(index as usize) {
+            Some(new) => new.unwrap_or(index),
+            None => index - self.indices.len() as u32 + self.len,
+        }
+    }
+
+    fn removed(&self) -> Vec<u32> {
+        (0..self.indices.len() as u32)
+            .filter(|&index| self.indices[index as usize].is_none())
+            .collect()
+    }
+}
+
+/// The bytes of the arrays of `mesh`.
+fn mesh_bytes(mesh: &MeshData) -> u64 {
+    let mut bytes = mem::size_of_val(&mesh.positions[..])
+        + mem::size_of_val(&mesh.normals[..])
+        + mem::size_of_val(&mesh.tangents[..])
+        + mem::size_of_val(&mesh.bitangents[..]);
+    for colors in mesh.colors.iter().flatten() {
+        bytes += mem::size_of_val(&colors[..]);
+    }
+    for uvs in mesh.texture_coords.iter().flatten() {
+        bytes += mem::size_of_val(&uvs[..]);
+    }
+    for face in &mesh.faces {
+        bytes += mem::size_of_val(&face[..]);
+    }
+    for bone in &mesh.bones {
+        bytes += mem::size_of_val(&bone.weights[..]);
+    }
+    bytes as u64
+}
@@ -0,0 +1,248 @@
+//! Recovering the separate objects and animation takes of a scene, see `Scene::root_groups`
+//! and `Scene::animation_takes`.
+//!
+//! Files such as FBX scenes can hold several unconnected objects and several takes. Assimp
+//! puts the objects under a root node of its own and turns each take into an `Animation`, so
+//! the original groupings are lost. These helpers guess them again. The guesses only depend
+//! on the names, transforms and order of the nodes and animations, so they're the same on
+//! every run, and the options say what they're based on.
+
+use std::collections::{BTreeSet, HashSet};
+
+use crate::math::Matrix4x4;
+
+use super::arena::NodeArena;
+use super::scene::Scene;
+
+/// How `Scene::root_groups_with` recognizes a root node that the importer added.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RootGroupOptions {
+    /// The names importers give the root nodes they add. Default: `["RootNode"]`, which is
+    /// what the FBX importer uses
+    pub artificial_names: Vec<String>,
+    /// Whether an artificial root must also have an identity transform and no meshes of its
+    /// own, so that nothing is lost by leaving it out. Default: true
+    pub require_identity: bool,
+}
+
+impl Default for RootGroupOptions {
+    fn default() -> Self {
+        RootGroupOptions {
+            artificial_names: vec!["RootNode".to_owned()],
+            require_identity: true,
+        }
+    }
+}
+
+/// One of the logical roots of a scene, with statistics of its subtree, as returned by
+/// `Scene::root_groups`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RootGroup {
+    /// The index of the node in `Scene::node_arena`.
+    pub node: u32,
+    pub name: String,
+    /// The number of nodes in the subtree, including this one.
+    pub num_nodes: u32,
+    /// The meshes that nodes of the subtree reference, in order and without duplicates.
+    pub mesh_indices: Vec<u32>,
+    /// The number of vertices of `mesh_indices`, counting each mesh once.
+    pub num_vertices: u64,
+    /// The number of triangles of `mesh_indices` after triangulation, counted like
+    /// `SceneStatistics::num_triangles`.
+    pub num_triangles: u64,
+    /// The indices of the animations with a channel for a node of the subtree.
+    pub animations: Vec<u32>,
+}
+
+/// How `Scene::animation_takes_with` decides which animations animate the same rig.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TakeOptions {
+    /// How similar the sets of animated nodes of two animations must be for them to be in the
+    /// same group, as the size of the intersection over the size of the union. Default: 1.0,
+    /// i.e. the sets must be the same
+    pub min_similarity: f32,
+    /// Whether channels of the helper nodes that the FBX importer adds, such as
+    /// `Arm_$AssimpFbx$_Rotation`, count as channels of the node they belong to, `Arm`.
+    /// Default: true
+    pub merge_fbx_helpers: bool,
+}
+
+impl Default for TakeOptions {
+    fn default() -> Self {
+        TakeOptions {
+            min_similarity: 1.0,
+            merge_fbx_helpers: true,
+        }
+    }
+}
+
+/// Animations that animate the same nodes, as returned by `Scene::animation_takes`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TakeGroup {
+    /// The animated nodes of the first animation of the group, sorted.
+    pub channels: Vec<String>,
+    /// The indices of the animations in `Scene::animations`, in order.
+    pub animations: Vec<u32>,
+}
+
+impl Scene<'_> {
+    /// The logical roots of the scene with the default `RootGroupOptions`, see
+    /// `root_groups_with`.
+    pub fn root_groups(&self) -> Vec<RootGroup> {
+        self.root_groups_with(&RootGroupOptions::default())
+    }
+
+    /// The logical roots of the scene. If the root node is one that the importer added, as
+    /// decided by `options`, these are its children, in order. Otherwise the root node is the
+    /// only one. Returns an empty list if the scene has no root node.
+    pub fn root_groups_with(&self, options: &RootGroupOptions) -> Vec<RootGroup> {
+        let arena = self.node_arena();
+        let root = match arena.get(0) {
+            Some(root) => root,
+            None => return Vec::new(),
+        };
+        let artificial = options
+            .artificial_names
+            .iter()
+            .any(|name| *name == root.name)
+            && (!options.require_identity
+                || (root.mesh_indices.is_empty() && is_identity(&root.local_transform)));
+        let roots = if artificial {
+            root.children.clone()
+        } else {
+            0..1
+        };
+
+        let channels: Vec<HashSet<String>> = self
+            .animations()
+            .map(|animation| {
+                animation
+                    .node_anims()
+                    .map(|channel| {
+                        let name = channel.node_name_cstr().to_string_lossy();
+                        base_name(&name).to_owned()
+                    })
+                    .collect()
+            })
+            .collect();
+        roots
+            .map(|node| self.root_group(&arena, node, &channels))
+            .collect()
+    }
+
+    fn root_group(&self, arena: &NodeArena, node: u32, channels: &[HashSet<String>]) -> RootGroup {
+        let mut num_nodes = 0;
+        let mut mesh_indices = Vec::new();
+        let mut seen = HashSet::new();
+        let mut names = HashSet::new();
+        let mut stack = vec![node];
+        while let Some(index) = stack.pop() {
+            let record = &arena.records()[index as usize];
+            num_nodes += 1;
+            names.insert(base_name(&record.name));
+            for &mesh in &record.mesh_indices {
+                if seen.insert(mesh) {
+                    mesh_indices.push(mesh);
+                }
+            }
+            stack.extend(record.children.clone().rev());
+        }
+        mesh_indices.sort_unstable();
+
+        let mut num_vertices = 0;
+        let mut num_triangles = 0;
+        for mesh in mesh_indices.iter().filter_map(|&index| self.mesh(index)) {
+            num_vertices += mesh.num_vertices() as u64;
+            for face in mesh.faces() {
+                num_triangles += (face.indices().len() as u64).saturating_sub(2);
+            }
+        }
+
+        let animations = channels
+            .iter()
+            .enumerate()
+            .filter(|(_, channels)| channels.iter().any(|name| names.contains(name.as_str())))
+            .map(|(index, _)| index as u32)
+            .collect();
+
+        RootGroup {
+            node,
+            name: arena.records()[node as usize].name.clone(),
+            num_nodes,
+            mesh_indices,
+            num_vertices,
+            num_triangles,
+            animations,
+        }
+    }
+
+    /// The animations grouped by the nodes they animate, with the default `TakeOptions`, see
+    /// `animation_takes_with`.
+    pub fn animation_takes(&self) -> Vec<TakeGroup> {
+        self.animation_takes_with(&TakeOptions::default())
+    }
+
+    /// The animations grouped by the nodes they animate, e.g. to find the takes of each rig.
+    /// Each animation joins the first group, in order of the groups' first animations, whose
+    /// first animation's nodes are at least `min_similarity` similar to its own, or starts a
+    /// new group. Animations without channels are grouped together.
+    pub fn animation_takes_with(&self, options: &TakeOptions) -> Vec<TakeGroup> {
+        let mut groups: Vec<(BTreeSet<String>, TakeGroup)> = Vec::new();
+        for (index, animation) in self.animations().enumerate() {
+            let channels: BTreeSet<String> = animation
+                .node_anims()
+                .map(|channel| {
+                    let name = channel.node_name_cstr().to_string_lossy();
+                    if options.merge_fbx_helpers {
+                        base_name(&name).to_owned()
+                    } else {
+                        name.into_owned()
+                    }
+                })
+                .collect();
+            let group = groups
+                .iter_mut()
+                .find(|(first, _)| similarity(first, &channels) >= options.min_similarity);
+            match group {
+                Some((_, group)) => group.animations.push(index as u32),
+                None => groups.push((
+                    channels.clone(),
+                    TakeGroup {
+                        channels: channels.into_iter().collect(),
+                        animations: vec![index as u32],
+                    },
+                )),
+            }
+        }
+        groups.into_iter().map(|(_, group)| group).collect()
+    }
+}
+
+/// The name of the node that an FBX helper node such as `Arm_$AssimpFbx$_Rotation` belongs
+/// to, or `name` itself for other nodes.
+fn base_name(name: &str) -> &str {
+    match name.find("_$AssimpFbx$_") {
+        Some(end) => &name[..end],
+        None => name,
+    }
+}
+
+fn is_identity(matrix: &Matrix4x4) -> bool {
+    let identity = Matrix4x4::identity().rows();
+    matrix
+        .rows()
+        .iter()
+        .flatten()
+        .zip(identity.iter().flatten())
+        .all(|(a, b)| (a - b).abs() <= 1e-6)
+}
+
+/// The size of the intersection of `a` and `b` over the size of their union, or 1 for two
+/// empty sets.
+fn similarity(a: &BTreeSet<String>, b: &BTreeSet<String>) -> f32 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 1.0;
+    }
+    a.intersection(b).count() as f32 / union as f32
+}
@@ -0,0 +1,354 @@
+use crate::math::{Matrix4x4, Vector3D};
+
+use super::animation::Animation;
+use super::material::{Material, PropertyType};
+use super::mesh::Mesh;
+use super::node::Node;
+use super::scene::Scene;
+use super::texture::Texture;
+
+/// Which parts of the scene contribute to `Scene::content_hash`, and how precisely floats are
+/// compared.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct HashOptions {
+    /// Floats are rounded to the nearest multiple of this before hashing, so that tiny
+    /// differences (including `-0.0` vs `0.0`) don't change the hash. Default: 1e-5
+    pub epsilon: f32,
+    /// Whether to hash mesh vertex data and faces. Default: true
+    pub meshes: bool,
+    /// Whether to hash the node hierarchy. Default: true
+    pub nodes: bool,
+    /// Whether to hash material properties. Default: true
+    pub materials: bool,
+    /// Whether to hash animations. Default: true
+    pub animations: bool,
+    /// Whether to hash the contents of embedded textures. Default: false
+    pub textures: bool,
+}
+
+impl Default for HashOptions {
+    fn default() -> Self {
+        HashOptions {
+            epsilon: 1e-5,
+            meshes: true,
+            nodes: true,
+            materials: true,
+            animations: true,
+            textures: false,
+        }
+    }
+}
+
+/// 64-bit FNV-1a. We can't use `std::collections::hash_map::DefaultHasher` because its output
+/// isn't guaranteed to be stable between Rust versions, which would defeat the point of using
+/// the hash as a cache key.
+pub(crate) struct StableHasher {
+    state: u64,
+    epsilon: f32,
+}
+
+impl StableHasher {
+    pub(crate) fn new(epsilon: f32) -> Self {
+        StableHasher {
+            state: 0xcbf2_9ce4_8422_2325,
+            epsilon,
+        }
+    }
+
+    pub(crate) fn finish(&self) -> u64 {
+        self.state
+    }
+
+    pub(crate) fn bytes(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.state ^= byte as u64;
+            self.state = self.state.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    pub(crate) fn u32(&mut self, value: u32) {
+        self.bytes(&value.to_le_bytes());
+    }
+
+    pub(crate) fn u64(&mut self, value: u64) {
+        self.bytes(&value.to_le_bytes());
+    }
+
+    pub(crate) fn str(&mut self, value: &str) {
+        self.u64(value.len() as u64);
+        self.bytes(value.as_bytes());
+    }
+
+    /// Hash a name straight from an `aiString`, so that names that aren't valid UTF-8 can
+    /// still be hashed.
+    pub(crate) fn name(&mut self, name: &ffi::aiString) {
+        let bytes = crate::aistring_to_cstr(name).to_bytes();
+        self.u64(bytes.len() as u64);
+        self.bytes(bytes);
+    }
+
+    pub(crate) fn f64(&mut self, value: f64) {
+        let epsilon = self.epsilon as f64;
+        let quantized = if epsilon > 0.0 {
+            (value / epsilon).round()
+        } else {
+            value
+        };
+
+        // Adding 0.0 turns -0.0 into 0.0.
+        self.u64((quantized + 0.0).to_bits());
+    }
+
+    pub(crate) fn f32(&mut self, value: f32) {
+        self.f64(value as f64);
+    }
+
+    pub(crate) fn vector(&mut self, v: Vector3D) {
+        self.f32(v.x);
+        self.f32(v.y);
+        self.f32(v.z);
+    }
+
+    pub(crate) fn matrix(&mut self, m: &Matrix4x4) {
+        for &value in &[
+            m.a1, m.a2, m.a3, m.a4, m.b1, m.b2, m.b3, m.b4, m.c1, m.c2, m.c3, m.c4, m.d1, m.d2,
+            m.d3, m.d4,
+        ] {
+            self.f32(value);
+        }
+    }
+
+    /// Hash a list of sub-hashes without regard to their order.
+    fn unordered(&mut self, mut hashes: Vec<u64>) {
+        hashes.sort_unstable();
+        self.u64(hashes.len() as u64);
+        for hash in hashes {
+            self.u64(hash);
+        }
+    }
+}
+
+impl Scene<'_> {
+    /// A hash of the imported content of the scene, which can be used to detect whether a file
+    /// has really changed. The same geometry exported byte-differently (or in a different order)
+    /// will hash the same, as long as the differences are within `options.epsilon`.
+    ///
+    /// The hash is stable across runs and Rust versions, but not across versions of this crate.
+    pub fn content_hash(&self, options: HashOptions) -> u64 {
+        let material_hashes: Vec<u64> = self
+            .materials()
+            .map(|material| hash_material(material, options.epsilon))
+            .collect();
+
+        let mesh_hashes: Vec<u64> = self
+            .meshes()
+            .map(|mesh| {
+                let material = if options.materials {
+                    material_hashes.get(mesh.material_id() as usize).cloned()
+                } else {
+                    None
+                };
+                hash_mesh(mesh, material, options.epsilon)
+            })
+            .collect();
+
+        let mut hasher = StableHasher::new(options.epsilon);
+
+        if options.meshes {
+            hasher.str("meshes");
+            hasher.unordered(mesh_hashes.clone());
+        }
+
+        if options.materials {
+            hasher.str("materials");
+            hasher.unordered(material_hashes);
+        }
+
+        if options.nodes {
+            hasher.str("nodes");
+            if let Some(root) = self.root_node() {
+                let mesh_hashes = if options.meshes {
+                    &mesh_hashes[..]
+                } else {
+                    &[]
+                };
+                hasher.u64(hash_node(root, mesh_hashes, options.epsilon));
+            }
+        }
+
+        if options.animations {
+            hasher.str("animations");
+            hasher.unordered(
+                self.animations()
+                    .map(|animation| hash_animation(animation, options.epsilon))
+                    .collect(),
+            );
+        }
+
+        if options.textures {
+            hasher.str("textures");
+            hasher.unordered(self.textures().map(hash_texture).collect());
+        }
+
+        hasher.finish()
+    }
+}
+
+pub(crate) fn hash_material(material: &Material, epsilon: f32) -> u64 {
+    let mut properties: Vec<_> = material.properties().collect();
+    properties.sort_by_key(|&property| {
+        (
+            crate::aistring_to_cstr(&property.mKey),
+            property.semantic(),
+            property.index(),
+        )
+    });
+
+    let mut hasher = StableHasher::new(epsilon);
+
+    for property in properties {
+        hasher.name(&property.mKey);
+        hasher.u32(property.semantic());
+        hasher.u32(property.index());
+
+        let data = property.data();
+        match property.property_type() {
+            PropertyType::Float => {
+                for chunk in data.chunks_exact(4) {
+                    hasher.f32(f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]));
+                }
+            }
+            PropertyType::Double => {
+                for chunk in data.chunks_exact(8) {
+                    let mut bytes = [0; 8];
+                    bytes.copy_from_slice(chunk);
+                    hasher.f64(f64::from_le_bytes(bytes));
+                }
+            }
+            _ => hasher.bytes(data),
+        }
+    }
+
+    hasher.finish()
+}
+
+fn hash_mesh(mesh: &Mesh, material: Option<u64>, epsilon: f32) -> u64 {
+    let mut hasher = StableHasher::new(epsilon);
+
+    hasher.name(&mesh.mName);
+    hasher.u32(mesh.primitive_types().bits());
+    hasher.u64(material.unwrap_or(0));
+
+    hasher.u32(mesh.num_vertices());
+    mesh.positions().for_each(|v| hasher.vector(v));
+    mesh.normals().for_each(|v| hasher.vector(v));
+    mesh.tangents().for_each(|v| hasher.vector(v));
+    mesh.bitangents().for_each(|v| hasher.vector(v));
+
+    for set in 0..mesh.mColors.len() as u32 {
+        for color in mesh.vertex_colors(set) {
+            hasher.f32(color.r);
+            hasher.f32(color.g);
+            hasher.f32(color.b);
+            hasher.f32(color.a);
+        }
+    }
+
+    for channel in 0..mesh.mTextureCoords.len() as u32 {
+        mesh.texture_coords(channel).for_each(|v| hasher.vector(v));
+    }
+
+    hasher.u32(mesh.num_faces());
+    for face in mesh.faces() {
+        hasher.u32(face.indices().len() as u32);
+        for &index in face.indices() {
+            hasher.u32(index);
+        }
+    }
+
+    let bones = mesh
+        .bones()
+        .map(|bone| {
+            let mut hasher = StableHasher::new(epsilon);
+            hasher.name(&bone.mName);
+            hasher.matrix(&bone.offset_matrix());
+            for weight in bone.weights() {
+                hasher.u32(weight.mVertexId);
+                hasher.f32(weight.mWeight);
+            }
+            hasher.finish()
+        })
+        .collect();
+    hasher.unordered(bones);
+
+    hasher.finish()
+}
+
+fn hash_node(node: &Node, mesh_hashes: &[u64], epsilon: f32) -> u64 {
+    let mut hasher = StableHasher::new(epsilon);
+
+    hasher.name(&node.mName);
+    hasher.matrix(&node.transform());
+    hasher.unordered(
+        node.meshes()
+            .iter()
+            .filter_map(|&id| mesh_hashes.get(id as usize).cloned())
+            .collect(),
+    );
+    hasher.unordered(
+        node.children()
+            .map(|child| hash_node(child, mesh_hashes, epsilon))
+            .collect(),
+    );
+
+    hasher.finish()
+}
+
+fn hash_animation(animation: &Animation, epsilon: f32) -> u64 {
+    let mut hasher = StableHasher::new(epsilon);
+
+    hasher.name(&animation.mName);
+    hasher.f64(animation.duration());
+    hasher.f64(animation.ticks_per_second());
+
+    let channels = animation
+        .node_anims()
+        .map(|channel| {
+            let mut hasher = StableHasher::new(epsilon);
+            hasher.name(&channel.mNodeName);
+            for key in channel.position_keys() {
+                hasher.f64(key.time());
+                hasher.vector(key.value());
+            }
+            for key in channel.rotation_keys() {
+                let q = key.value();
+                hasher.f64(key.time());
+                hasher.f32(q.w);
+                hasher.f32(q.x);
+                hasher.f32(q.y);
+                hasher.f32(q.z);
+            }
+            for key in channel.scaling_keys() {
+                hasher.f64(key.time());
+                hasher.vector(key.value());
+            }
+            hasher.finish()
+        })
+        .collect();
+    hasher.unordered(channels);
+
+    hasher.finish()
+}
+
+fn hash_texture(texture: &Texture) -> u64 {
+    let mut hasher = StableHasher::new(0.0);
+
+    hasher.u32(texture.width());
+    hasher.u32(texture.height());
+    hasher.str(texture.try_format_hint().ok().flatten().unwrap_or(""));
+    if let Some(data) = texture.data() {
+        hasher.bytes(data.bytes());
+    }
+
+    hasher.finish()
+}
@@ -0,0 +1,64 @@
+use ffi::{aiMesh, aiNode};
+
+use super::mesh::Mesh;
+use super::node::Node;
+use super::scene::Scene;
+
+/// Identifies a node of a scene without borrowing it, e.g. to key a map of the engine
+/// entities created for each node, see `Node::id`.
+///
+/// An id is the address of Assimp's node, so it's the same however the node was reached, and
+/// stays valid for as long as the scene is alive and isn't post-processed again. Ids of nodes
+/// of different live scenes never compare equal, but an id kept after its scene was dropped
+/// may be reused by a node of another scene. Node names aren't unique, so unlike names, ids
+/// also tell apart nodes with the same name.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId(usize);
+
+/// Identifies a mesh of a scene without borrowing it, see `Mesh::id` and `NodeId`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct MeshId(usize);
+
+impl NodeId {
+    /// The address the id is made of.
+    pub fn as_usize(self) -> usize {
+        self.0
+    }
+}
+
+impl MeshId {
+    /// The address the id is made of.
+    pub fn as_usize(self) -> usize {
+        self.0
+    }
+}
+
+impl Node {
+    /// The id of this node, which is equal for every borrow of it, see `NodeId`.
+    pub fn id(&self) -> NodeId {
+        NodeId(&**self as *const aiNode as usize)
+    }
+}
+
+impl Mesh {
+    /// The id of this mesh, which is equal for every borrow of it, see `MeshId`.
+    pub fn id(&self) -> MeshId {
+        MeshId(&**self as *const aiMesh as usize)
+    }
+}
+
+impl Scene<'_> {
+    /// The node of this scene with the id `id`, or `None` if it's the id of a node of another
+    /// scene. This searches the hierarchy, so it takes time linear in the number of nodes.
+    pub fn node_by_id(&self, id: NodeId) -> Option<&Node> {
+        self.root_node()?.descendants().find(|node| node.id() == id)
+    }
+
+    /// The mesh of this scene with the id `id` and its index, or `None` if it's the id of a
+    /// mesh of another scene.
+    pub fn mesh_by_id(&self, id: MeshId) -> Option<(u32, &Mesh)> {
+        (0..self.num_meshes())
+            .filter_map(|index| Some((index, self.mesh(index)?)))
+            .find(|(_, mesh)| mesh.id() == id)
+    }
+}
@@ -0,0 +1,105 @@
+use std::collections::{BTreeMap, HashMap};
+
+use ffi::aiNode;
+
+use crate::math::Matrix4x4;
+
+use super::node::Node;
+use super::scene::Scene;
+
+/// Every node that references a mesh, as returned by `Scene::mesh_instances`.
+#[derive(Debug, Clone)]
+pub struct MeshInstances<'a> {
+    /// The index of the mesh in the scene.
+    pub mesh: u32,
+    /// Each node that references the mesh, with the node's world transform: the product of
+    /// the transforms of the node and all of its ancestors. Nodes are in hierarchy order,
+    /// parents before children.
+    pub instances: Vec<(&'a Node, Matrix4x4)>,
+}
+
+impl MeshInstances<'_> {
+    /// The number of times the mesh is referenced.
+    pub fn num_instances(&self) -> usize {
+        self.instances.len()
+    }
+}
+
+/// How much a scene reuses its meshes, see `Scene::instancing_summary`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct InstancingSummary {
+    /// The number of meshes referenced by at least one node.
+    pub num_referenced_meshes: u32,
+    /// The number of meshes referenced by more than one node.
+    pub num_instanced_meshes: u32,
+    /// The total number of mesh references from all nodes.
+    pub num_references: u32,
+}
+
+impl Scene<'_> {
+    /// The nodes that reference each mesh, with their world transforms, computed in a single
+    /// traversal of the node hierarchy. Meshes are in index order, and meshes that no node
+    /// references are left out.
+    ///
+    /// A node that references the same mesh twice counts as two instances.
+    pub fn mesh_instances(&self) -> Vec<MeshInstances<'_>> {
+        let root = match self.root_node() {
+            Some(root) => root,
+            None => return Vec::new(),
+        };
+
+        let mut world_transforms: HashMap<*const aiNode, Matrix4x4> = HashMap::new();
+        let mut meshes: BTreeMap<u32, Vec<(&Node, Matrix4x4)>> = BTreeMap::new();
+
+        for node in root.descendants() {
+            let world = match node
+                .parent()
+                .and_then(|parent| world_transforms.get(&(&**parent as *const aiNode)))
+            {
+                Some(&parent) => parent * node.transform(),
+                None => node.transform(),
+            };
+
+            for &mesh in node.meshes() {
+                meshes.entry(mesh).or_default().push((node, world));
+            }
+
+            if node.num_children() > 0 {
+                world_transforms.insert(&**node as *const aiNode, world);
+            }
+        }
+
+        meshes
+            .into_iter()
+            .map(|(mesh, instances)| MeshInstances { mesh, instances })
+            .collect()
+    }
+
+    /// Count how many meshes are referenced by more than one node, see `mesh_instances`.
+    pub fn instancing_summary(&self) -> InstancingSummary {
+        let mut summary = InstancingSummary::default();
+
+        for mesh in self.mesh_instances() {
+            summary.num_referenced_meshes += 1;
+            summary.num_references += mesh.num_instances() as u32;
+            if mesh.num_instances() > 1 {
+                summary.num_instanced_meshes += 1;
+            }
+        }
+
+        summary
+    }
+}
+
+/// Split the result of `Scene::mesh_instances` into meshes that are worth instancing, with at
+/// least `min_instances` instances, and the rest. Returns `(unique, instanced)`.
+pub fn partition_instances(
+    meshes: Vec<MeshInstances<'_>>,
+    min_instances: usize,
+) -> (Vec<MeshInstances<'_>>, Vec<MeshInstances<'_>>) {
+    let (instanced, unique) = meshes
+        .into_iter()
+        .partition(|mesh| mesh.num_instances() >= min_instances);
+
+    (unique, instanced)
+}
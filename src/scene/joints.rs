@@ -0,0 +1,141 @@
+//! Joint hierarchies for skinning, ordered so that every joint comes after its parent.
+//!
+//! GPU skinning palettes and animation blending compute the transform of each joint from its
+//! parent's in a single pass, so the parent has to be evaluated first. `order_joints` puts the
+//! nodes of a skeleton in that order, with the index of each joint's parent, and `mesh_joints`
+//! does the same for the nodes of a mesh's bones.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+use super::mesh::Mesh;
+use super::node::Node;
+use super::scene::Scene;
+
+/// A joint of a skeleton, as returned by `order_joints`.
+#[derive(Debug, Copy, Clone)]
+pub struct Joint<'a> {
+    pub node: &'a Node,
+    /// The index of the joint's node in the input of `order_joints`. For `mesh_joints`, this
+    /// is the index of the joint's bone in `Mesh::bones`.
+    pub source: usize,
+    /// The index of the parent joint in the same `Vec`, which is always lower than the
+    /// joint's own index, or `None` for the root joint. The parent is the joint of the nearest
+    /// ancestor node that's a joint, so nodes in between that aren't joints are skipped.
+    pub parent_index: Option<usize>,
+}
+
+/// Why `order_joints` or `mesh_joints` failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JointError {
+    /// There's no node with the same name as the bone with this index in `Mesh::bones`.
+    MissingNode { bone: u32 },
+    /// The joints with these indices in the input have the same node.
+    DuplicateNode { first: usize, second: usize },
+    /// Neither of the joints with these indices in the input is a descendant of another
+    /// joint, so the joints don't form a single tree. Some broken exports attach bones to
+    /// unrelated nodes like this.
+    MultipleRoots { first: usize, second: usize },
+}
+
+impl fmt::Display for JointError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            JointError::MissingNode { bone } => write!(f, "bone {} has no node", bone),
+            JointError::DuplicateNode { first, second } => {
+                write!(f, "joints {} and {} have the same node", first, second)
+            }
+            JointError::MultipleRoots { first, second } => write!(
+                f,
+                "joints {} and {} are both roots, so the joints don't form a tree",
+                first, second
+            ),
+        }
+    }
+}
+
+impl Error for JointError {}
+
+/// Order the joints with the nodes `nodes` so that every joint comes after its parent, see
+/// `Joint`.
+///
+/// The joints are returned in the order of `Node::descendants`, so joints with the same
+/// parent keep the order of their nodes among their siblings, whatever the order of `nodes`.
+/// Every node must appear once, and every joint but one must have another joint as an
+/// ancestor, so that the joints form a single tree.
+pub fn order_joints<'a>(nodes: &[&'a Node]) -> Result<Vec<Joint<'a>>, JointError> {
+    let mut sources: HashMap<*const Node, usize> = HashMap::with_capacity(nodes.len());
+    for (source, &node) in nodes.iter().enumerate() {
+        if let Some(first) = sources.insert(node, source) {
+            return Err(JointError::DuplicateNode {
+                first,
+                second: source,
+            });
+        }
+    }
+
+    let mut parents = Vec::with_capacity(nodes.len());
+    let mut root = None;
+    for (source, &node) in nodes.iter().enumerate() {
+        let mut ancestor = node.parent();
+        let parent = loop {
+            match ancestor {
+                Some(node) => match sources.get(&(node as *const Node)) {
+                    Some(&parent) => break Some(parent),
+                    None => ancestor = node.parent(),
+                },
+                None => break None,
+            }
+        };
+
+        if parent.is_none() {
+            if let Some(first) = root {
+                return Err(JointError::MultipleRoots {
+                    first,
+                    second: source,
+                });
+            }
+            root = Some(source);
+        }
+        parents.push(parent);
+    }
+
+    let root = match root {
+        Some(root) => root,
+        None => return Ok(Vec::new()),
+    };
+
+    // Every other joint is a descendant of the root joint, and parents come before their
+    // children in `descendants`.
+    let mut indices = vec![0; nodes.len()];
+    let mut joints = Vec::with_capacity(nodes.len());
+    for node in nodes[root].descendants() {
+        if let Some(&source) = sources.get(&(node as *const Node)) {
+            indices[source] = joints.len();
+            joints.push(Joint {
+                node,
+                source,
+                parent_index: parents[source].map(|parent| indices[parent]),
+            });
+        }
+    }
+
+    Ok(joints)
+}
+
+/// The joints of the bones of `mesh`, ordered by `order_joints`. Each bone's node is the first
+/// node in `scene` with the same name, see `Scene::find_node`.
+pub fn mesh_joints<'a>(scene: &'a Scene, mesh: &Mesh) -> Result<Vec<Joint<'a>>, JointError> {
+    let nodes = mesh
+        .bones()
+        .enumerate()
+        .map(|(i, bone)| {
+            scene
+                .find_node(bone.name_cstr().to_bytes())
+                .ok_or(JointError::MissingNode { bone: i as u32 })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    order_joints(&nodes)
+}
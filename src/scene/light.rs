@@ -1,8 +1,154 @@
 use ffi::aiLight;
 
+use crate::math::{Color3D, Matrix4x4, Vector3D};
+use std::str::Utf8Error;
+
 define_type_and_iterator_indirect! {
-    /// Light type (not yet implemented)
+    /// A light source in the scene. Like cameras, the light's position and orientation are
+    /// relative to the node with the same name as the light.
     struct Light(&aiLight)
     /// Light iterator type.
     struct LightIter
 }
+
+/// The kind of light source.
+#[repr(u32)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum LightType {
+    /// The light type wasn't specified in the file.
+    Undefined = ffi::aiLightSourceType_aiLightSource_UNDEFINED,
+    /// A light infinitely far away that shines in a single direction, like the sun. Only
+    /// `direction` is meaningful, the position is ignored.
+    Directional = ffi::aiLightSourceType_aiLightSource_DIRECTIONAL,
+    /// A light that shines equally in all directions from a single point.
+    Point = ffi::aiLightSourceType_aiLightSource_POINT,
+    /// A light that shines in a cone, defined by `direction`, `angle_inner_cone` and
+    /// `angle_outer_cone`.
+    Spot = ffi::aiLightSourceType_aiLightSource_SPOT,
+    /// Light that affects everything equally, without a position or direction.
+    Ambient = ffi::aiLightSourceType_aiLightSource_AMBIENT,
+    /// A rectangular light. The rectangle is centred on the light's position, faces along
+    /// `direction` and is oriented so that its height runs along `up`. Its dimensions are
+    /// given by `Light::size`.
+    Area = ffi::aiLightSourceType_aiLightSource_AREA,
+}
+
+impl std::convert::TryFrom<u32> for LightType {
+    type Error = crate::Error;
+
+    fn try_from(other: u32) -> Result<Self, Self::Error> {
+        match other {
+            ffi::aiLightSourceType_aiLightSource_UNDEFINED => Ok(Self::Undefined),
+            ffi::aiLightSourceType_aiLightSource_DIRECTIONAL => Ok(Self::Directional),
+            ffi::aiLightSourceType_aiLightSource_POINT => Ok(Self::Point),
+            ffi::aiLightSourceType_aiLightSource_SPOT => Ok(Self::Spot),
+            ffi::aiLightSourceType_aiLightSource_AMBIENT => Ok(Self::Ambient),
+            ffi::aiLightSourceType_aiLightSource_AREA => Ok(Self::Area),
+            _ => Err(crate::Error::unsupported("light source type", other)),
+        }
+    }
+}
+
+impl Light {
+    /// Returns the name of the light. This is also the name of the node that the light is
+    /// attached to.
+    ///
+    /// Panics if the light name isn't valid UTF-8, see `try_name`.
+    pub fn name(&self) -> &str {
+        self.try_name().expect("light name is not valid UTF-8")
+    }
+
+    /// Like `name`, but returns an error if the light name isn't valid UTF-8.
+    pub fn try_name(&self) -> Result<&str, Utf8Error> {
+        crate::aistring_to_str(&self.mName)
+    }
+
+    /// The kind of light source. Unknown values are reported as `LightType::Undefined`.
+    pub fn light_type(&self) -> LightType {
+        use std::convert::TryInto;
+
+        (self.mType as u32)
+            .try_into()
+            .unwrap_or(LightType::Undefined)
+    }
+
+    /// The position of the light relative to its node.
+    pub fn position(&self) -> Vector3D {
+        Vector3D::from_raw(self.mPosition)
+    }
+
+    /// The direction the light is pointing in, relative to its node. For area lights this is
+    /// the normal of the light's rectangle.
+    pub fn direction(&self) -> Vector3D {
+        Vector3D::from_raw(self.mDirection)
+    }
+
+    /// The "up" vector of the light, relative to its node. For area lights this is the axis
+    /// that the rectangle's height runs along.
+    pub fn up(&self) -> Vector3D {
+        Vector3D::from_raw(self.mUp)
+    }
+
+    /// The constant, linear and quadratic attenuation factors, in that order.
+    pub fn attenuation(&self) -> (f32, f32, f32) {
+        (
+            self.mAttenuationConstant,
+            self.mAttenuationLinear,
+            self.mAttenuationQuadratic,
+        )
+    }
+
+    /// The diffuse color of the light.
+    pub fn color_diffuse(&self) -> Color3D {
+        Color3D::from_raw(self.mColorDiffuse)
+    }
+
+    /// The specular color of the light.
+    pub fn color_specular(&self) -> Color3D {
+        Color3D::from_raw(self.mColorSpecular)
+    }
+
+    /// The ambient color of the light.
+    pub fn color_ambient(&self) -> Color3D {
+        Color3D::from_raw(self.mColorAmbient)
+    }
+
+    /// The angle, in radians, of the inner cone of a spot light. Inside this cone the light
+    /// is at full strength.
+    pub fn angle_inner_cone(&self) -> f32 {
+        self.mAngleInnerCone
+    }
+
+    /// The angle, in radians, of the outer cone of a spot light. Outside this cone the light
+    /// has no effect.
+    pub fn angle_outer_cone(&self) -> f32 {
+        self.mAngleOuterCone
+    }
+
+    /// The width and height of an area light. This is only meaningful for
+    /// `LightType::Area`.
+    pub fn size(&self) -> (f32, f32) {
+        (self.mSize.x, self.mSize.y)
+    }
+
+    /// The four corners of an area light's rectangle in world space, given the transform of
+    /// the light's node. The corners are returned in counter-clockwise order when looking
+    /// at the lit side of the rectangle, starting from the bottom-left.
+    pub fn area_corners(&self, world: &Matrix4x4) -> [Vector3D; 4] {
+        let (width, height) = self.size();
+        let normal = self.direction().normalize();
+        let up = self.up().normalize();
+        let right = up.cross(normal).normalize();
+
+        let center = self.position();
+        let half_right = right * (width / 2.0);
+        let half_up = up * (height / 2.0);
+
+        [
+            world.transform_point(center - half_right - half_up),
+            world.transform_point(center + half_right - half_up),
+            world.transform_point(center + half_right + half_up),
+            world.transform_point(center - half_right + half_up),
+        ]
+    }
+}
@@ -0,0 +1,233 @@
+//! Detection of level-of-detail chains from the naming conventions used by exporters.
+//!
+//! Assimp has no concept of LODs, so a chain is usually only visible in the names of the nodes
+//! or meshes that make it up, e.g. `Rock_LOD0`, `Rock_LOD1` and `Rock_LOD2`. `detect_lods`
+//! finds these and groups them by base name, ordered by level.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+use super::mesh::Mesh;
+use super::node::{MetadataValue, Node};
+use super::scene::Scene;
+
+/// How the level of detail of a node or mesh is encoded.
+pub enum LodConvention {
+    /// A `LOD<n>` suffix, matched case-insensitively, optionally preceded by `_`, `-`, `.` or
+    /// a space and optionally followed by `_`: `Rock_LOD0`, `rock-lod1`, `RockLOD_2`. The base
+    /// name is everything before the suffix.
+    Suffix,
+    /// An integer metadata entry on the node with the given key, such as `$lod`. Exporters
+    /// that use metadata put every level of a chain under one parent node, so the base name
+    /// is the name of the parent node.
+    Metadata(String),
+    /// A custom parser, which returns the base name and level for names that are part of a
+    /// LOD chain and `None` for everything else.
+    Custom(Box<dyn Fn(&str) -> Option<(String, u32)>>),
+}
+
+impl LodConvention {
+    fn parse_name(&self, name: &str) -> Option<(String, u32)> {
+        match self {
+            LodConvention::Suffix => parse_suffix(name),
+            LodConvention::Metadata(_) => None,
+            LodConvention::Custom(parse) => parse(name),
+        }
+    }
+
+    fn parse_node(&self, node: &Node) -> Option<(String, u32)> {
+        match self {
+            LodConvention::Metadata(key) => {
+                let level = node
+                    .metadata()
+                    .find(|(entry_key, _)| entry_key.to_bytes() == key.as_bytes())
+                    .and_then(|(_, entry)| match entry.get() {
+                        MetadataValue::I32(level) if level >= 0 => Some(level as u32),
+                        MetadataValue::U64(level) if level <= u32::MAX as u64 => Some(level as u32),
+                        _ => None,
+                    })?;
+                let base = node.parent().unwrap_or(node);
+
+                Some((node_name(base), level))
+            }
+            _ => self.parse_name(&node_name(node)),
+        }
+    }
+}
+
+/// One level of a `LodGroup`.
+#[derive(Debug, Clone)]
+pub struct LodLevel<'a> {
+    pub level: u32,
+    /// The indices of the meshes making up this level.
+    pub meshes: Vec<u32>,
+    /// The node that references the meshes.
+    pub node: &'a Node,
+}
+
+/// The levels of detail of one object, as returned by `detect_lods`.
+#[derive(Debug, Clone)]
+pub struct LodGroup<'a> {
+    /// The name shared by every level, without the LOD suffix.
+    pub base_name: String,
+    /// The levels, ordered from most to least detailed. There can be gaps, e.g. an object
+    /// with only `LOD0` and `LOD2`.
+    pub levels: Vec<LodLevel<'a>>,
+}
+
+/// Find the LOD chains in `scene`. A node whose name matches the convention puts all of its
+/// meshes into one level; otherwise each of the node's meshes whose name matches is added
+/// separately. Base names are compared case-insensitively, and groups are returned in the
+/// order their first level appears in the node hierarchy.
+///
+/// Returns an empty `Vec` if nothing in the scene matches the convention.
+pub fn detect_lods<'a>(scene: &'a Scene, convention: LodConvention) -> Vec<LodGroup<'a>> {
+    let root = match scene.root_node() {
+        Some(root) => root,
+        None => return Vec::new(),
+    };
+
+    let mut groups: Vec<LodGroup<'a>> = Vec::new();
+    let mut by_name: HashMap<String, usize> = HashMap::new();
+    let mut add = |base_name: String, level: LodLevel<'a>| {
+        let index = *by_name.entry(base_name.to_lowercase()).or_insert_with(|| {
+            groups.push(LodGroup {
+                base_name,
+                levels: Vec::new(),
+            });
+            groups.len() - 1
+        });
+        groups[index].levels.push(level);
+    };
+
+    for node in root.descendants() {
+        if let Some((base_name, level)) = convention.parse_node(node) {
+            let meshes = node.meshes().to_vec();
+            add(
+                base_name,
+                LodLevel {
+                    level,
+                    meshes,
+                    node,
+                },
+            );
+            continue;
+        }
+
+        for &index in node.meshes() {
+            let parsed = scene
+                .mesh(index)
+                .and_then(|mesh| convention.parse_name(&mesh_name(mesh)));
+            if let Some((base_name, level)) = parsed {
+                add(
+                    base_name,
+                    LodLevel {
+                        level,
+                        meshes: vec![index],
+                        node,
+                    },
+                );
+            }
+        }
+    }
+
+    for group in &mut groups {
+        group.levels.sort_by_key(|level| level.level);
+    }
+
+    groups
+}
+
+/// A level of a `LodGroup` that isn't simpler than the level before it, see
+/// `validate_lod_group`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LodError {
+    pub base_name: String,
+    pub level: u32,
+    pub triangles: u32,
+    pub previous_level: u32,
+    pub previous_triangles: u32,
+}
+
+impl fmt::Display for LodError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} LOD{} has {} triangles, which isn't fewer than the {} of LOD{}",
+            self.base_name,
+            self.level,
+            self.triangles,
+            self.previous_triangles,
+            self.previous_level
+        )
+    }
+}
+
+impl Error for LodError {}
+
+/// Check that each level of `group` has fewer triangles than the level before it. Polygons
+/// count as the number of triangles they would be split into.
+pub fn validate_lod_group(scene: &Scene, group: &LodGroup) -> Result<(), LodError> {
+    let counts = group.levels.iter().map(|level| {
+        let triangles = level
+            .meshes
+            .iter()
+            .filter_map(|&index| scene.mesh(index))
+            .flat_map(|mesh| mesh.faces())
+            .map(|face| (face.indices().len() as u32).saturating_sub(2))
+            .sum::<u32>();
+        (level.level, triangles)
+    });
+
+    let mut previous: Option<(u32, u32)> = None;
+    for (level, triangles) in counts {
+        if let Some((previous_level, previous_triangles)) = previous {
+            if triangles >= previous_triangles {
+                return Err(LodError {
+                    base_name: group.base_name.clone(),
+                    level,
+                    triangles,
+                    previous_level,
+                    previous_triangles,
+                });
+            }
+        }
+        previous = Some((level, triangles));
+    }
+
+    Ok(())
+}
+
+fn parse_suffix(name: &str) -> Option<(String, u32)> {
+    // Only ASCII characters are matched, so byte offsets into the lowercase copy are also
+    // valid offsets into `name`.
+    let lower = name.to_ascii_lowercase();
+    let without_digits = lower.trim_end_matches(|c: char| c.is_ascii_digit());
+    if without_digits.len() == lower.len() {
+        return None;
+    }
+    let level = lower[without_digits.len()..].parse().ok()?;
+
+    let without_lod = without_digits.trim_end_matches('_').strip_suffix("lod")?;
+    let base = without_lod
+        .strip_suffix(|c: char| c == '_' || c == '-' || c == '.' || c == ' ')
+        .unwrap_or(without_lod);
+    if base.is_empty() {
+        return None;
+    }
+
+    Some((name[..base.len()].to_owned(), level))
+}
+
+fn node_name(node: &Node) -> String {
+    crate::aistring_to_cstr(&node.mName)
+        .to_string_lossy()
+        .into_owned()
+}
+
+fn mesh_name(mesh: &Mesh) -> String {
+    crate::aistring_to_cstr(&mesh.mName)
+        .to_string_lossy()
+        .into_owned()
+}
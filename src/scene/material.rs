@@ -1,35 +1,42 @@
-use crate::math::{Color3D, Color4D, Vector3D};
+use crate::math::{Color3D, Color4D, Vector2D, Vector3D};
 use derive_more::{From, TryInto};
 #[allow(unused_imports)]
 use ffi::{
     aiBlendMode_aiBlendMode_Additive, aiBlendMode_aiBlendMode_Default, aiGetMaterialColor,
-    aiGetMaterialFloatArray, aiGetMaterialIntegerArray, aiGetMaterialString, aiGetMaterialTexture,
-    aiGetMaterialTextureCount, aiGetMaterialUVTransform, aiMaterial, aiMaterialProperty,
-    aiShadingMode_aiShadingMode_Blinn, aiShadingMode_aiShadingMode_CookTorrance,
-    aiShadingMode_aiShadingMode_Flat, aiShadingMode_aiShadingMode_Fresnel,
-    aiShadingMode_aiShadingMode_Gouraud, aiShadingMode_aiShadingMode_Minnaert,
-    aiShadingMode_aiShadingMode_NoShading, aiShadingMode_aiShadingMode_OrenNayar,
-    aiShadingMode_aiShadingMode_Phong, aiShadingMode_aiShadingMode_Toon,
-    aiTextureFlags__aiTextureFlags_Force32Bit, aiTextureFlags_aiTextureFlags_IgnoreAlpha,
-    aiTextureFlags_aiTextureFlags_Invert, aiTextureFlags_aiTextureFlags_UseAlpha,
-    aiTextureMapMode_aiTextureMapMode_Clamp, aiTextureMapMode_aiTextureMapMode_Decal,
-    aiTextureMapMode_aiTextureMapMode_Mirror, aiTextureMapMode_aiTextureMapMode_Wrap,
-    aiTextureMapping_aiTextureMapping_BOX, aiTextureMapping_aiTextureMapping_CYLINDER,
-    aiTextureMapping_aiTextureMapping_PLANE, aiTextureMapping_aiTextureMapping_SPHERE,
-    aiTextureMapping_aiTextureMapping_UV, aiTextureOp_aiTextureOp_Add,
-    aiTextureOp_aiTextureOp_Divide, aiTextureOp_aiTextureOp_Multiply,
+    aiGetMaterialFloatArray, aiGetMaterialIntegerArray, aiGetMaterialProperty, aiGetMaterialString,
+    aiGetMaterialTexture, aiGetMaterialTextureCount, aiGetMaterialUVTransform, aiMaterial,
+    aiMaterialProperty, aiShadingMode_aiShadingMode_Blinn,
+    aiShadingMode_aiShadingMode_CookTorrance, aiShadingMode_aiShadingMode_Flat,
+    aiShadingMode_aiShadingMode_Fresnel, aiShadingMode_aiShadingMode_Gouraud,
+    aiShadingMode_aiShadingMode_Minnaert, aiShadingMode_aiShadingMode_NoShading,
+    aiShadingMode_aiShadingMode_OrenNayar, aiShadingMode_aiShadingMode_Phong,
+    aiShadingMode_aiShadingMode_Toon, aiTextureFlags__aiTextureFlags_Force32Bit,
+    aiTextureFlags_aiTextureFlags_IgnoreAlpha, aiTextureFlags_aiTextureFlags_Invert,
+    aiTextureFlags_aiTextureFlags_UseAlpha, aiTextureMapMode_aiTextureMapMode_Clamp,
+    aiTextureMapMode_aiTextureMapMode_Decal, aiTextureMapMode_aiTextureMapMode_Mirror,
+    aiTextureMapMode_aiTextureMapMode_Wrap, aiTextureMapping_aiTextureMapping_BOX,
+    aiTextureMapping_aiTextureMapping_CYLINDER, aiTextureMapping_aiTextureMapping_PLANE,
+    aiTextureMapping_aiTextureMapping_SPHERE, aiTextureMapping_aiTextureMapping_UV,
+    aiTextureOp_aiTextureOp_Add, aiTextureOp_aiTextureOp_Divide, aiTextureOp_aiTextureOp_Multiply,
     aiTextureOp_aiTextureOp_SignedAdd, aiTextureOp_aiTextureOp_SmoothAdd,
     aiTextureOp_aiTextureOp_Subtract, aiTextureType_aiTextureType_AMBIENT,
-    aiTextureType_aiTextureType_DIFFUSE, aiTextureType_aiTextureType_DISPLACEMENT,
-    aiTextureType_aiTextureType_EMISSIVE, aiTextureType_aiTextureType_LIGHTMAP,
+    aiTextureType_aiTextureType_AMBIENT_OCCLUSION, aiTextureType_aiTextureType_BASE_COLOR,
+    aiTextureType_aiTextureType_DIFFUSE, aiTextureType_aiTextureType_DIFFUSE_ROUGHNESS,
+    aiTextureType_aiTextureType_DISPLACEMENT, aiTextureType_aiTextureType_EMISSION_COLOR,
+    aiTextureType_aiTextureType_EMISSIVE, aiTextureType_aiTextureType_HEIGHT,
+    aiTextureType_aiTextureType_LIGHTMAP, aiTextureType_aiTextureType_METALNESS,
+    aiTextureType_aiTextureType_NORMALS, aiTextureType_aiTextureType_NORMAL_CAMERA,
     aiTextureType_aiTextureType_OPACITY, aiTextureType_aiTextureType_REFLECTION,
-    aiTextureType_aiTextureType_SPECULAR, aiTextureType_aiTextureType_UNKNOWN,
-    _AI_MATKEY_MAPPINGMODE_U_BASE, _AI_MATKEY_MAPPINGMODE_V_BASE, _AI_MATKEY_MAPPING_BASE,
-    _AI_MATKEY_TEXBLEND_BASE, _AI_MATKEY_TEXFLAGS_BASE, _AI_MATKEY_TEXMAP_AXIS_BASE,
-    _AI_MATKEY_TEXOP_BASE, _AI_MATKEY_TEXTURE_BASE, _AI_MATKEY_UVWSRC_BASE,
+    aiTextureType_aiTextureType_SHININESS, aiTextureType_aiTextureType_SPECULAR,
+    aiTextureType_aiTextureType_UNKNOWN, aiUVTransform, _AI_MATKEY_MAPPINGMODE_U_BASE,
+    _AI_MATKEY_MAPPINGMODE_V_BASE, _AI_MATKEY_MAPPING_BASE, _AI_MATKEY_TEXBLEND_BASE,
+    _AI_MATKEY_TEXFLAGS_BASE, _AI_MATKEY_TEXMAP_AXIS_BASE, _AI_MATKEY_TEXOP_BASE,
+    _AI_MATKEY_TEXTURE_BASE, _AI_MATKEY_UVWSRC_BASE,
 };
 use std::convert::{TryFrom, TryInto};
 use std::ffi::CStr;
+use std::fmt;
+use std::str::Utf8Error;
 
 define_type_and_iterator_indirect! {
     /// A single material. This is _not_ the same as a single texture, and in fact a
@@ -41,15 +48,20 @@ define_type_and_iterator_indirect! {
 }
 
 define_type_and_iterator_indirect! {
-    /// Material type (not yet implemented)
+    /// A single raw property of a material.
     struct MaterialProperty(&aiMaterialProperty)
-    /// Material iterator type.
+    /// Material property iterator type.
     struct MaterialPropertyIter
 }
 
 /// A dynamically-typed value of a material property.
-#[derive(TryInto, From, PartialEq, Debug)]
+#[derive(TryInto, From, Clone, PartialEq, Debug)]
 pub enum MaterialValue {
+    /// Colors read from a material are always `Color4D`, with an alpha of 1 if the property
+    /// only has three components. Use `Color3D::from` to drop the alpha.
+    Color4D(Color4D),
+    /// Accepted wherever a color is read, with an alpha of 1, e.g. when set on a
+    /// `MaterialOverlay`.
     Color3D(Color3D),
     String(crate::InlineString),
     Float(f32),
@@ -72,99 +84,284 @@ impl Material {
         &self,
         type_: MaterialComponentType,
     ) -> Option<MaterialComponent<impl ExactSizeIterator<Item = TextureDefinition> + '_>> {
+        MaterialSource::component(self, type_)
+    }
+
+    /// The `index`th texture of the given component, or `None` if there is no such texture.
+    pub fn texture(&self, type_: MaterialComponentType, index: u32) -> Option<TextureDefinition> {
         use std::mem::MaybeUninit;
 
-        let color: Option<Color3D> = self
-            .get_value(MaterialKey::Color(type_))
+        // Assimp only writes the optional outputs if the file actually has the matching
+        // property, so they all need a valid starting value.
+        let mut path = MaybeUninit::uninit();
+        let mut mapping = MaybeUninit::zeroed();
+        let mut uvindex = MaybeUninit::zeroed();
+        let mut blend = MaybeUninit::zeroed();
+        let mut op = MaybeUninit::zeroed();
+        // Assimp writes the U and V modes here, and W where the file has one.
+        let mut mapmode = [0; 3];
+        let mut flags = MaybeUninit::zeroed();
+
+        crate::aireturn_to_result(unsafe {
+            aiGetMaterialTexture(
+                &self.0,
+                type_ as u32,
+                index,
+                path.as_mut_ptr(),
+                mapping.as_mut_ptr(),
+                uvindex.as_mut_ptr(),
+                blend.as_mut_ptr(),
+                op.as_mut_ptr(),
+                mapmode.as_mut_ptr(),
+                flags.as_mut_ptr(),
+            )
+        })
+        .ok()?;
+
+        let mapping = unsafe { Mapping::try_from(mapping.assume_init()) }.ok();
+
+        let axis = if mapping.is_none() || mapping == Some(Mapping::UV) {
+            None
+        } else {
+            self.get_value(MaterialKey::TextureMapAxis(type_, index))
+                .and_then(|val| val.try_into().ok())
+        };
+
+        let wrap_u = self
+            .get_value(MaterialKey::MappingModeU(type_, index))
             .and_then(|val| val.try_into().ok());
 
-        let count = self.num_textures(type_);
-        let color = if count == 0 {
-            color?
+        let wrap_v = self
+            .get_value(MaterialKey::MappingModeV(type_, index))
+            .and_then(|val| val.try_into().ok());
+
+        // Only a few formats have a W mode, so look for the property before asking Assimp for it.
+        let wrap_w = if self.has(MaterialKey::MappingModeW(type_, index)) {
+            self.get_value(MaterialKey::MappingModeW(type_, index))
+                .and_then(|val| val.try_into().ok())
         } else {
-            color.unwrap_or_default()
+            None
         };
 
-        let textures = (0..count).map(move |index| {
-            let mut path = MaybeUninit::uninit();
-            let mut mapping = MaybeUninit::uninit();
-            let mut uvindex = MaybeUninit::zeroed();
-            let mut blend = MaybeUninit::uninit();
-            let mut op = MaybeUninit::uninit();
-            let mut mapmode = MaybeUninit::uninit();
-            let mut flags = MaybeUninit::uninit();
-
-            crate::aireturn_to_result(unsafe {
-                aiGetMaterialTexture(
-                    &self.0,
-                    type_ as u32,
-                    index,
-                    path.as_mut_ptr(),
-                    mapping.as_mut_ptr(),
-                    uvindex.as_mut_ptr(),
-                    blend.as_mut_ptr(),
-                    op.as_mut_ptr(),
-                    mapmode.as_mut_ptr(),
-                    flags.as_mut_ptr(),
-                )
-            })
+        let blend_op = BlendOp::try_from(unsafe { op.assume_init() })
             .ok()
-            .expect(
-                "Somehow getting the texture failed, even though \
-                we used data that should be valid",
-            );
+            .unwrap_or_default();
 
-            let mapping = unsafe { Mapping::try_from(mapping.assume_init()) }.ok();
+        // Assimp leaves `uvindex` alone if the file doesn't specify a channel, so the only way to
+        // tell that apart from an explicit 0 is to look for the property.
+        let (channel, channel_source) = if self.has(MaterialKey::UVWSource(type_, index)) {
+            let channel = (unsafe { uvindex.assume_init() } as i32)
+                .try_into()
+                .ok()
+                .unwrap_or_default();
+            (channel, ChannelSource::Explicit)
+        } else {
+            (self.inferred_channel(type_, index), ChannelSource::Inferred)
+        };
 
-            let axis = if mapping.is_none() || mapping == Some(Mapping::UV) {
-                None
-            } else {
-                self.get_value(MaterialKey::TextureMapAxis(type_, index))
-                    .and_then(|val| val.try_into().ok())
-            };
+        unsafe {
+            Some(TextureDefinition {
+                path: crate::InlineString(path.assume_init()),
+                strength: if blend_op == BlendOp::Replace {
+                    // This value isn't set when the blend operation is undefined.
+                    1.0
+                } else {
+                    blend.assume_init()
+                },
+                blend_op,
+                mapping,
+                axis,
+                channel,
+                channel_source,
+                wrap_u,
+                wrap_v,
+                wrap_w,
+                flags: TextureFlags::from_bits(flags.assume_init()).unwrap_or_default(),
+            })
+        }
+    }
 
-            let wrap_u = self
-                .get_value(MaterialKey::MappingModeU(type_, index))
-                .and_then(|val| val.try_into().ok());
+    /// The UV transform of the `index`th texture of the given component, or `None` if the file
+    /// doesn't give it one.
+    pub fn uv_transform(&self, type_: MaterialComponentType, index: u32) -> Option<UvTransform> {
+        let mut transform = std::mem::MaybeUninit::<aiUVTransform>::uninit();
+        crate::aireturn_to_result(unsafe {
+            aiGetMaterialUVTransform(&self.0, type_ as u32, index, transform.as_mut_ptr())
+        })
+        .ok()?;
 
-            let wrap_v = self
-                .get_value(MaterialKey::MappingModeV(type_, index))
-                .and_then(|val| val.try_into().ok());
+        let transform = unsafe { transform.assume_init() };
+        Some(UvTransform {
+            translation: Vector2D::new(transform.mTranslation.x, transform.mTranslation.y),
+            scaling: Vector2D::new(transform.mScaling.x, transform.mScaling.y),
+            rotation: transform.mRotation,
+        })
+    }
 
-            let blend_op = BlendOp::try_from(unsafe { op.assume_init() })
-                .ok()
-                .unwrap_or_default();
+    /// The channel of the `index`th texture of a component when the file doesn't specify one.
+    /// As Assimp recommends, the textures of the stack without a channel get the lowest channels
+    /// that no other texture of the stack uses explicitly, in ascending order. This gives channel
+    /// 0 to a texture that's alone on its stack.
+    fn inferred_channel(&self, type_: MaterialComponentType, index: u32) -> u32 {
+        let explicit: Vec<Option<u32>> = (0..self.num_textures(type_))
+            .map(|index| self.explicit_channel(type_, index))
+            .collect();
+        let inferred_before = explicit
+            .iter()
+            .take(index as usize)
+            .filter(|channel| channel.is_none())
+            .count();
 
-            unsafe {
-                TextureDefinition {
-                    path: crate::InlineString(path.assume_init()),
-                    strength: if blend_op == BlendOp::Replace {
-                        // This value isn't set when the blend operation is undefined.
-                        1.0
-                    } else {
-                        blend.assume_init()
-                    },
-                    blend_op,
-                    mapping,
-                    axis,
-                    channel: (uvindex.assume_init() as i32)
-                        .try_into()
-                        .ok()
-                        .unwrap_or_default(),
-                    wrap_u,
-                    wrap_v,
-                    flags: TextureFlags::from_bits(flags.assume_init()).unwrap_or_default(),
-                }
-            }
-        });
+        (0..)
+            .filter(|channel| !explicit.contains(&Some(*channel)))
+            .nth(inferred_before)
+            .unwrap()
+    }
+
+    fn explicit_channel(&self, type_: MaterialComponentType, index: u32) -> Option<u32> {
+        let key = MaterialKey::UVWSource(type_, index);
+        if self.has(key) {
+            self.get_value(key).and_then(|val| val.try_into().ok())
+        } else {
+            None
+        }
+    }
 
-        Some(MaterialComponent { color, textures })
+    /// The name of the material as a `CStr`, without checking that it's valid UTF-8. This is
+    /// empty if the material doesn't have a name.
+    pub fn name_cstr(&self) -> &CStr {
+        let (key, _, _) = MaterialKey::Name.triple().unwrap();
+        let name = self
+            .properties()
+            .find(|property| crate::aistring_to_cstr(&property.mKey) == key)
+            .map(|property| property.data());
+
+        // String properties are stored as a 32-bit length followed by a nul-terminated string.
+        name.and_then(|data| data.get(4..))
+            .and_then(|data| {
+                let end = data.iter().position(|&byte| byte == 0)?;
+                CStr::from_bytes_with_nul(&data[..=end]).ok()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Whether the material is named `name`, compared byte for byte.
+    pub fn eq_name(&self, name: &[u8]) -> bool {
+        self.name_cstr().to_bytes() == name
+    }
+
+    /// The number of raw properties in this material.
+    pub fn num_properties(&self) -> u32 {
+        self.mNumProperties
+    }
+
+    /// Iterator over the raw properties of this material, in the order Assimp stores them. Most
+    /// applications should use `get_value` or the component methods instead.
+    pub fn properties(&self) -> MaterialPropertyIter {
+        unsafe {
+            MaterialPropertyIter::new(
+                std::ptr::NonNull::new(self.mProperties as *mut *const aiMaterialProperty),
+                self.mNumProperties as usize,
+            )
+        }
     }
 
     pub fn num_textures(&self, type_: MaterialComponentType) -> u32 {
         unsafe { aiGetMaterialTextureCount(&self.0, type_ as u32) }
     }
 
+    /// Whether this material has a property for `key`. Unlike `get_value`, this doesn't copy or
+    /// convert the value, so it's cheap enough to probe for many keys.
+    pub fn has(&self, key: MaterialKey) -> bool {
+        match key.triple() {
+            Some((base, type_, index)) => self.has_raw(base, type_, index),
+            None => false,
+        }
+    }
+
+    fn has_raw(&self, key: &CStr, type_: u32, index: u32) -> bool {
+        let mut property = std::ptr::null();
+        crate::aireturn_to_result(unsafe {
+            aiGetMaterialProperty(&self.0, key.as_ptr(), type_, index, &mut property)
+        })
+        .is_ok()
+            && !property.is_null()
+    }
+
+    /// The float stored under `key`, for the properties that `MaterialKey` doesn't cover, such
+    /// as the PBR factors.
+    pub(crate) fn raw_float(&self, key: &CStr) -> Option<f32> {
+        let mut out = 0.0;
+        crate::aireturn_to_result(unsafe {
+            aiGetMaterialFloatArray(&self.0, key.as_ptr(), 0, 0, &mut out, std::ptr::null_mut())
+        })
+        .ok()?;
+        Some(out)
+    }
+
+    /// The color stored under `key`, see `raw_float`.
+    pub(crate) fn raw_color(&self, key: &CStr) -> Option<Color4D> {
+        use std::mem::MaybeUninit;
+
+        let mut out = MaybeUninit::uninit();
+        crate::aireturn_to_result(unsafe {
+            aiGetMaterialColor(&self.0, key.as_ptr(), 0, 0, out.as_mut_ptr())
+        })
+        .ok()?;
+        Some(Color4D(unsafe { out.assume_init() }))
+    }
+
+    /// The key, component and texture index of every property of this material, in the order
+    /// Assimp stores them, see `MaterialProperty::component`. Keys that aren't valid UTF-8 are
+    /// converted lossily.
+    pub fn keys(&self) -> impl Iterator<Item = (String, Option<MaterialComponentType>, u32)> + '_ {
+        self.properties().map(|property| {
+            (
+                crate::aistring_to_cstr(&property.mKey)
+                    .to_string_lossy()
+                    .into_owned(),
+                property.component(),
+                property.index(),
+            )
+        })
+    }
+
+    /// Whether this material is set up for physically based rendering or for one of the older
+    /// shading models, judging by which properties it has. Importers for PBR formats usually
+    /// fill in the legacy properties too, so the PBR properties win.
+    pub fn shading_path_hint(&self) -> ShadingPath {
+        const PBR_KEYS: &[&[u8]] = &[
+            b"$clr.base\0",
+            b"$mat.metallicFactor\0",
+            b"$mat.roughnessFactor\0",
+            b"$mat.glossinessFactor\0",
+            // The keys used by the glTF importer before Assimp 5.1.
+            b"$mat.gltf.pbrMetallicRoughness.baseColorFactor\0",
+            b"$mat.gltf.pbrMetallicRoughness.metallicFactor\0",
+            b"$mat.gltf.pbrMetallicRoughness.roughnessFactor\0",
+            b"$mat.gltf.pbrSpecularGlossiness\0",
+        ];
+        const LEGACY_KEYS: &[MaterialKey] = &[
+            MaterialKey::Color(MaterialComponentType::Diffuse),
+            MaterialKey::Color(MaterialComponentType::Specular),
+            MaterialKey::Color(MaterialComponentType::Ambient),
+            MaterialKey::Shininess,
+            MaterialKey::ShadingModel,
+        ];
+
+        let has_pbr = PBR_KEYS
+            .iter()
+            .any(|key| self.has_raw(CStr::from_bytes_with_nul(key).unwrap(), 0, 0));
+
+        if has_pbr {
+            ShadingPath::Pbr
+        } else if LEGACY_KEYS.iter().any(|key| self.has(*key)) {
+            ShadingPath::Legacy
+        } else {
+            ShadingPath::Unknown
+        }
+    }
+
     pub fn get_value(&self, key: MaterialKey) -> Option<MaterialValue> {
         use std::mem::MaybeUninit;
 
@@ -174,7 +371,7 @@ impl Material {
 
         Some(match value_type {
             // These are the types that Assimp understands natively
-            ValueType::Color3D => {
+            ValueType::Color4D => {
                 let mut out = MaybeUninit::uninit();
 
                 crate::aireturn_to_result(unsafe {
@@ -182,7 +379,7 @@ impl Material {
                 })
                 .ok()?;
 
-                MaterialValue::Color3D(Color4D(unsafe { out.assume_init() }).into())
+                MaterialValue::Color4D(Color4D(unsafe { out.assume_init() }))
             }
             ValueType::String => {
                 let mut out = MaybeUninit::uninit();
@@ -251,13 +448,30 @@ impl Material {
                     _ => unreachable!(),
                 }
             }
-            ValueType::Vector3D => todo!(
-                "Getting vector properties from materials currently unimplemented: \
-                    The documentation has some pretty weird stuff here, it confusingly says that \
-                    we should use the `pMax` parameter to specify the size in floats, but the \
-                    example code (NOT tests, this is only in the documentation and therefore \
-                    may be wrong or out of date) passes the requested size in _bytes_."
-            ),
+            ValueType::Vector3D => {
+                // The documentation is inconsistent about whether `pMax` is in bytes or floats,
+                // but the implementation treats it as a number of floats.
+                let mut out = [0.0f32; 3];
+                let mut max = out.len() as u32;
+
+                crate::aireturn_to_result(unsafe {
+                    aiGetMaterialFloatArray(
+                        &self.0,
+                        base.as_ptr(),
+                        type_,
+                        index,
+                        out.as_mut_ptr(),
+                        &mut max,
+                    )
+                })
+                .ok()?;
+
+                if max as usize != out.len() {
+                    return None;
+                }
+
+                MaterialValue::Vector3D(Vector3D::new(out[0], out[1], out[2]))
+            }
         })
     }
 
@@ -300,11 +514,212 @@ impl Material {
     }
 }
 
+/// Something that material properties can be read from. This is implemented by `Material` and
+/// by `OverlaidMaterial`, so that code which reads materials can accept either.
+pub trait MaterialSource {
+    /// Get a single property of the material.
+    fn get_value(&self, key: MaterialKey) -> Option<MaterialValue>;
+
+    /// The number of textures in the given component.
+    fn num_textures(&self, type_: MaterialComponentType) -> u32;
+
+    /// The `index`th texture of the given component, or `None` if there is no such texture.
+    fn texture(&self, type_: MaterialComponentType, index: u32) -> Option<TextureDefinition>;
+
+    /// A single component of this material, see the documentation for `MaterialComponent` for
+    /// more information.
+    fn component(&self, type_: MaterialComponentType) -> Option<MaterialComponent<Textures<Self>>> {
+        let color = match self.get_value(MaterialKey::Color(type_)) {
+            Some(MaterialValue::Color4D(color)) => Some(color),
+            Some(MaterialValue::Color3D(color)) => Some(color.into()),
+            _ => None,
+        };
+
+        let count = self.num_textures(type_);
+        let color = match color {
+            Some(color) => color,
+            None if count == 0 => return None,
+            None => Color4D::new(0.0, 0.0, 0.0, 1.0),
+        };
+
+        Some(MaterialComponent {
+            color: color.into(),
+            color_alpha: color.a,
+            textures: Textures {
+                source: self,
+                type_,
+                index: 0,
+                count,
+            },
+        })
+    }
+
+    /// The "diffuse" component of the material.
+    fn diffuse(&self) -> Option<MaterialComponent<Textures<Self>>> {
+        self.component(MaterialComponentType::Diffuse)
+    }
+
+    /// The "specular" component of the material.
+    fn specular(&self) -> Option<MaterialComponent<Textures<Self>>> {
+        self.component(MaterialComponentType::Specular)
+    }
+
+    /// The "ambient" component of the material.
+    fn ambient(&self) -> Option<MaterialComponent<Textures<Self>>> {
+        self.component(MaterialComponentType::Ambient)
+    }
+
+    /// The "emissive" component of the material.
+    fn emissive(&self) -> Option<MaterialComponent<Textures<Self>>> {
+        self.component(MaterialComponentType::Emissive)
+    }
+
+    /// The transparency of the material.
+    fn opacity(&self) -> Option<MaterialComponent<Textures<Self>>> {
+        self.component(MaterialComponentType::Opacity)
+    }
+}
+
+impl MaterialSource for Material {
+    fn get_value(&self, key: MaterialKey) -> Option<MaterialValue> {
+        Material::get_value(self, key)
+    }
+
+    fn num_textures(&self, type_: MaterialComponentType) -> u32 {
+        Material::num_textures(self, type_)
+    }
+
+    fn texture(&self, type_: MaterialComponentType, index: u32) -> Option<TextureDefinition> {
+        Material::texture(self, type_, index)
+    }
+}
+
+/// Prints the material's name and the components that have textures, but not its properties.
+impl fmt::Debug for Material {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let textured = TEXTURE_TYPES
+            .iter()
+            .filter(|&&type_| self.num_textures(type_) > 0);
+
+        f.debug_struct("Material")
+            .field("name", &self.name_cstr().to_string_lossy())
+            .field("textures", &crate::BoundedList(textured))
+            .finish()
+    }
+}
+
+/// Iterator over the textures of a single material component.
+pub struct Textures<'a, M: ?Sized> {
+    source: &'a M,
+    type_: MaterialComponentType,
+    index: u32,
+    count: u32,
+}
+
+impl<M: MaterialSource + ?Sized> Iterator for Textures<'_, M> {
+    type Item = TextureDefinition;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index < self.count {
+            match self.source.texture(self.type_, self.index) {
+                Some(texture) => {
+                    self.index += 1;
+
+                    Some(texture)
+                }
+                // Assimp reported more textures than it can actually read, which can happen
+                // with malformed files. Stop here rather than panicking.
+                None => {
+                    self.index = self.count;
+
+                    None
+                }
+            }
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.count - self.index) as usize;
+
+        (len, Some(len))
+    }
+}
+
+impl<M: MaterialSource + ?Sized> ExactSizeIterator for Textures<'_, M> {}
+
+/// The type of the data stored in a raw `MaterialProperty`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PropertyType {
+    /// An array of `f32`s.
+    Float,
+    /// An array of `f64`s.
+    Double,
+    /// An `aiString`.
+    String,
+    /// An array of `i32`s.
+    Integer,
+    /// Some other binary data.
+    Buffer,
+}
+
+impl MaterialProperty {
+    /// The key of this property, e.g. `$clr.diffuse`.
+    ///
+    /// Panics if the property key isn't valid UTF-8, see `try_key`.
+    pub fn key(&self) -> &str {
+        self.try_key().expect("property key is not valid UTF-8")
+    }
+
+    /// Like `key`, but returns an error if the property key isn't valid UTF-8.
+    pub fn try_key(&self) -> Result<&str, Utf8Error> {
+        crate::aistring_to_str(&self.mKey)
+    }
+
+    /// For texture properties, the type of texture this property applies to. For all other
+    /// properties this is 0.
+    pub fn semantic(&self) -> u32 {
+        self.mSemantic
+    }
+
+    /// For texture properties, the index of the texture this property applies to. For all
+    /// other properties this is 0.
+    pub fn index(&self) -> u32 {
+        self.mIndex
+    }
+
+    /// The component that `semantic` refers to, or `None` for properties that aren't about
+    /// textures and for texture types that `MaterialComponentType` doesn't cover.
+    pub fn component(&self) -> Option<MaterialComponentType> {
+        MaterialComponentType::try_from(self.mSemantic).ok()
+    }
+
+    /// The type of the data stored in this property.
+    pub fn property_type(&self) -> PropertyType {
+        match self.mType {
+            ffi::aiPropertyTypeInfo_aiPTI_Float => PropertyType::Float,
+            ffi::aiPropertyTypeInfo_aiPTI_Double => PropertyType::Double,
+            ffi::aiPropertyTypeInfo_aiPTI_String => PropertyType::String,
+            ffi::aiPropertyTypeInfo_aiPTI_Integer => PropertyType::Integer,
+            _ => PropertyType::Buffer,
+        }
+    }
+
+    /// The raw data of this property.
+    pub fn data(&self) -> &[u8] {
+        unsafe { crate::array_slice(self.mData as *const u8, self.mDataLength as usize) }
+    }
+}
+
 /// A component of a material - see `MaterialComponentType` for what the different components can be.
 /// For each component, the resultant texture is created by combining many textures together,
 pub struct MaterialComponent<I> {
     /// The base color of this material component, which the rest of the "stack" will be based on
     pub color: Color3D,
+    /// The alpha of the base color, which some formats use for opacity, e.g. the alpha of
+    /// `baseColorFactor` in glTF. 1 if the color has no alpha.
+    pub color_alpha: f32,
     /// The iterator of textures, each of which may be `None` (which represents an invalid texture)
     pub textures: I,
 }
@@ -312,6 +727,7 @@ pub struct MaterialComponent<I> {
 /// The component of this material - these affect how the supplied textures interact with light.
 #[repr(u32)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MaterialComponentType {
     /// The "diffuse" component of the material - this is likely to be rendered using gourard shading.
     Diffuse = aiTextureType_aiTextureType_DIFFUSE,
@@ -334,10 +750,93 @@ pub enum MaterialComponentType {
     /// Reflectivity map - this is likely application-dependent, and real-time applications probably
     /// don't need to worry about it.
     Reflection = aiTextureType_aiTextureType_REFLECTION,
+    /// A height map. OBJ's `bump` and FBX's `Bump` end up here, and files often store normal
+    /// maps this way.
+    Height = aiTextureType_aiTextureType_HEIGHT,
+    /// A tangent space normal map.
+    Normals = aiTextureType_aiTextureType_NORMALS,
+    /// The per-pixel shininess, i.e. the exponent for phong shading.
+    Shininess = aiTextureType_aiTextureType_SHININESS,
+    /// The base color of a PBR material.
+    BaseColor = aiTextureType_aiTextureType_BASE_COLOR,
+    /// A normal map for PBR materials, which Assimp distinguishes from `Normals` even though
+    /// most importers use the latter.
+    NormalCamera = aiTextureType_aiTextureType_NORMAL_CAMERA,
+    /// The emission color of a PBR material.
+    EmissionColor = aiTextureType_aiTextureType_EMISSION_COLOR,
+    /// The metalness of a PBR material.
+    Metalness = aiTextureType_aiTextureType_METALNESS,
+    /// The roughness of a PBR material.
+    DiffuseRoughness = aiTextureType_aiTextureType_DIFFUSE_ROUGHNESS,
+    /// The ambient occlusion of a PBR material. Some importers use `Lightmap` instead.
+    AmbientOcclusion = aiTextureType_aiTextureType_AMBIENT_OCCLUSION,
     /// Unknown material component - accessible but not processed in any way by Assimp.
     Unknown = aiTextureType_aiTextureType_UNKNOWN,
 }
 
+/// Every texture type that a material can reference.
+/// New variants of `MaterialComponentType` need to be added here too, so that e.g.
+/// `Material::complexity` and `Scene::statistics` count their textures.
+pub(crate) const TEXTURE_TYPES: [MaterialComponentType; 18] = [
+    MaterialComponentType::Diffuse,
+    MaterialComponentType::Specular,
+    MaterialComponentType::Ambient,
+    MaterialComponentType::Emissive,
+    MaterialComponentType::Opacity,
+    MaterialComponentType::Displacement,
+    MaterialComponentType::Lightmap,
+    MaterialComponentType::Reflection,
+    MaterialComponentType::Height,
+    MaterialComponentType::Normals,
+    MaterialComponentType::Shininess,
+    MaterialComponentType::BaseColor,
+    MaterialComponentType::NormalCamera,
+    MaterialComponentType::EmissionColor,
+    MaterialComponentType::Metalness,
+    MaterialComponentType::DiffuseRoughness,
+    MaterialComponentType::AmbientOcclusion,
+    MaterialComponentType::Unknown,
+];
+
+impl TryFrom<u32> for MaterialComponentType {
+    type Error = crate::Error;
+
+    fn try_from(other: u32) -> Result<Self, Self::Error> {
+        match other {
+            aiTextureType_aiTextureType_DIFFUSE => Ok(Self::Diffuse),
+            aiTextureType_aiTextureType_SPECULAR => Ok(Self::Specular),
+            aiTextureType_aiTextureType_AMBIENT => Ok(Self::Ambient),
+            aiTextureType_aiTextureType_EMISSIVE => Ok(Self::Emissive),
+            aiTextureType_aiTextureType_OPACITY => Ok(Self::Opacity),
+            aiTextureType_aiTextureType_DISPLACEMENT => Ok(Self::Displacement),
+            aiTextureType_aiTextureType_LIGHTMAP => Ok(Self::Lightmap),
+            aiTextureType_aiTextureType_REFLECTION => Ok(Self::Reflection),
+            aiTextureType_aiTextureType_HEIGHT => Ok(Self::Height),
+            aiTextureType_aiTextureType_NORMALS => Ok(Self::Normals),
+            aiTextureType_aiTextureType_SHININESS => Ok(Self::Shininess),
+            aiTextureType_aiTextureType_BASE_COLOR => Ok(Self::BaseColor),
+            aiTextureType_aiTextureType_NORMAL_CAMERA => Ok(Self::NormalCamera),
+            aiTextureType_aiTextureType_EMISSION_COLOR => Ok(Self::EmissionColor),
+            aiTextureType_aiTextureType_METALNESS => Ok(Self::Metalness),
+            aiTextureType_aiTextureType_DIFFUSE_ROUGHNESS => Ok(Self::DiffuseRoughness),
+            aiTextureType_aiTextureType_AMBIENT_OCCLUSION => Ok(Self::AmbientOcclusion),
+            aiTextureType_aiTextureType_UNKNOWN => Ok(Self::Unknown),
+            _ => Err(crate::Error::unsupported("texture type", other)),
+        }
+    }
+}
+
+/// The kind of renderer a material was made for, see `Material::shading_path_hint`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ShadingPath {
+    /// The material has physically based properties, e.g. a metallic factor.
+    Pbr,
+    /// The material only has the classic properties, e.g. a diffuse color and shininess.
+    Legacy,
+    /// The material has neither, e.g. because it only has textures.
+    Unknown,
+}
+
 /// The shading model that meshes with this material applied will use - this is just a hint. The shading
 /// models here map roughly to shading models in Blender, and are only meant as a way to roughly
 /// approximate the intended shading method. Most applications can ignore this.
@@ -346,7 +845,7 @@ pub enum MaterialComponentType {
 /// will use a combination of these models depending on the material component), it should give a rough
 /// idea of the original intent.
 #[repr(u32)]
-#[derive(Debug, PartialEq, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum ShadingModel {
     /// Flat shading.
     ///
@@ -380,7 +879,7 @@ pub enum ShadingModel {
 }
 
 impl TryFrom<u32> for ShadingModel {
-    type Error = ();
+    type Error = crate::Error;
 
     fn try_from(other: u32) -> Result<Self, Self::Error> {
         match other {
@@ -394,14 +893,14 @@ impl TryFrom<u32> for ShadingModel {
             ffi::aiShadingMode_aiShadingMode_CookTorrance => Ok(Self::CookTorrance),
             ffi::aiShadingMode_aiShadingMode_NoShading => Ok(Self::NoShading),
             ffi::aiShadingMode_aiShadingMode_Fresnel => Ok(Self::Fresnel),
-            _ => Err(()),
+            _ => Err(crate::Error::unsupported("shading mode", other)),
         }
     }
 }
 
 /// The "key" for each field of the material, which can be used to extract single fields of the material.
 /// However, it is likely easier to use the helper methods on the `Material` struct.
-#[derive(Debug, PartialEq, Eq, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum MaterialKey {
     /// The material's name, if applicable
     Name,
@@ -436,13 +935,20 @@ pub enum MaterialKey {
     UVWSource(MaterialComponentType, u32),
     MappingModeU(MaterialComponentType, u32),
     MappingModeV(MaterialComponentType, u32),
+    /// The wrapping mode along the W axis, for 3D textures.
+    MappingModeW(MaterialComponentType, u32),
     TextureMapAxis(MaterialComponentType, u32),
     Flags(MaterialComponentType, u32),
+    /// How the glTF importers say the alpha of the base color is used: `OPAQUE`, `MASK` or
+    /// `BLEND`. Other importers don't set this.
+    GltfAlphaMode,
+    /// The alpha below which a glTF `MASK` material is fully transparent.
+    GltfAlphaCutoff,
 }
 
 enum ValueType {
     // These are the types that Assimp understands natively
-    Color3D,
+    Color4D,
     String,
     Float,
     Int,
@@ -501,10 +1007,14 @@ impl MaterialKey {
             MaterialKey::MappingModeV(comp, index) => {
                 (_AI_MATKEY_MAPPINGMODE_V_BASE, *comp as u32, *index)
             }
+            // Assimp's headers only define keys for U and V, so this follows the same naming.
+            MaterialKey::MappingModeW(comp, index) => (b"$tex.mapmodew\0", *comp as u32, *index),
             MaterialKey::TextureMapAxis(comp, index) => {
                 (_AI_MATKEY_TEXMAP_AXIS_BASE, *comp as u32, *index)
             }
             MaterialKey::Flags(comp, index) => (_AI_MATKEY_TEXFLAGS_BASE, *comp as u32, *index),
+            MaterialKey::GltfAlphaMode => (b"$mat.gltf.alphaMode\0", 0, 0),
+            MaterialKey::GltfAlphaCutoff => (b"$mat.gltf.alphaCutoff\0", 0, 0),
         };
 
         Some((CStr::from_bytes_with_nul(name).unwrap(), type_, index))
@@ -513,7 +1023,7 @@ impl MaterialKey {
     fn type_(&self) -> ValueType {
         match self {
             MaterialKey::Name => ValueType::String,
-            MaterialKey::Color(..) => ValueType::Color3D,
+            MaterialKey::Color(..) => ValueType::Color4D,
             MaterialKey::Wireframe => ValueType::Bool,
             MaterialKey::TwoSided => ValueType::Bool,
             MaterialKey::ShadingModel => ValueType::ShadingModel,
@@ -529,8 +1039,11 @@ impl MaterialKey {
             MaterialKey::UVWSource(..) => ValueType::Int,
             MaterialKey::MappingModeU(..) => ValueType::WrappingMode,
             MaterialKey::MappingModeV(..) => ValueType::WrappingMode,
+            MaterialKey::MappingModeW(..) => ValueType::WrappingMode,
             MaterialKey::TextureMapAxis(..) => ValueType::Vector3D,
             MaterialKey::Flags(..) => ValueType::TextureFlags,
+            MaterialKey::GltfAlphaMode => ValueType::String,
+            MaterialKey::GltfAlphaCutoff => ValueType::Float,
         }
     }
 }
@@ -563,7 +1076,7 @@ impl Default for BlendOp {
 }
 
 impl TryFrom<u32> for BlendOp {
-    type Error = ();
+    type Error = crate::Error;
 
     fn try_from(other: u32) -> Result<Self, Self::Error> {
         match other {
@@ -573,7 +1086,7 @@ impl TryFrom<u32> for BlendOp {
             ffi::aiTextureOp_aiTextureOp_Divide => Ok(Self::Divide),
             ffi::aiTextureOp_aiTextureOp_SmoothAdd => Ok(Self::SmoothAdd),
             ffi::aiTextureOp_aiTextureOp_SignedAdd => Ok(Self::SignedAdd),
-            _ => Err(()),
+            _ => Err(crate::Error::unsupported("texture op", other)),
         }
     }
 }
@@ -586,13 +1099,13 @@ pub enum MaterialBlendOp {
 }
 
 impl TryFrom<u32> for MaterialBlendOp {
-    type Error = ();
+    type Error = crate::Error;
 
     fn try_from(other: u32) -> Result<Self, Self::Error> {
         match other {
             ffi::aiBlendMode_aiBlendMode_Additive => Ok(Self::Additive),
             ffi::aiBlendMode_aiBlendMode_Default => Ok(Self::Default),
-            _ => Err(()),
+            _ => Err(crate::Error::unsupported("blend mode", other)),
         }
     }
 }
@@ -633,7 +1146,7 @@ pub enum Mapping {
 }
 
 impl TryFrom<u32> for Mapping {
-    type Error = ();
+    type Error = crate::Error;
 
     fn try_from(other: u32) -> Result<Self, Self::Error> {
         match other {
@@ -642,12 +1155,12 @@ impl TryFrom<u32> for Mapping {
             ffi::aiTextureMapping_aiTextureMapping_CYLINDER => Ok(Self::Cylinder),
             ffi::aiTextureMapping_aiTextureMapping_BOX => Ok(Self::Box),
             ffi::aiTextureMapping_aiTextureMapping_PLANE => Ok(Self::Plane),
-            _ => Err(()),
+            _ => Err(crate::Error::unsupported("texture mapping", other)),
         }
     }
 }
 
-/// Behavior when a texture's U or V coordinates are outside the bounds of 0..1. These (mostly)
+/// Behavior when a texture's U, V or W coordinates are outside the bounds of 0..1. These (mostly)
 /// map directly to sampler methods in most hardware graphics libraries.
 #[repr(u32)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
@@ -669,7 +1182,7 @@ pub enum WrappingMode {
 }
 
 impl TryFrom<u32> for WrappingMode {
-    type Error = ();
+    type Error = crate::Error;
 
     fn try_from(other: u32) -> Result<Self, Self::Error> {
         match other {
@@ -677,7 +1190,7 @@ impl TryFrom<u32> for WrappingMode {
             ffi::aiTextureMapMode_aiTextureMapMode_Clamp => Ok(Self::Clamp),
             ffi::aiTextureMapMode_aiTextureMapMode_Decal => Ok(Self::Decal),
             ffi::aiTextureMapMode_aiTextureMapMode_Mirror => Ok(Self::MirrorRepeat),
-            _ => Err(()),
+            _ => Err(crate::Error::unsupported("texture map mode", other)),
         }
     }
 }
@@ -697,6 +1210,66 @@ bitflags::bitflags! {
     }
 }
 
+/// Where the UV channel of a `TextureDefinition` came from.
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub enum ChannelSource {
+    /// The file specifies the channel.
+    Explicit,
+    /// The file doesn't specify the channel, so it was allocated following Assimp's
+    /// recommendation, see `TextureDefinition::channel`.
+    Inferred,
+}
+
+/// How the UVs of a texture are transformed before it's sampled, e.g. from glTF's
+/// `KHR_texture_transform`, see `Material::uv_transform`. The UVs are scaled by `scaling`, then
+/// rotated by `rotation` radians counter-clockwise around (0.5, 0.5), then moved by
+/// `translation`. `Importer::transform_uv_coords` can apply it at import time.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct UvTransform {
+    pub translation: Vector2D,
+    pub scaling: Vector2D,
+    pub rotation: f32,
+}
+
+impl Default for UvTransform {
+    /// The identity, which leaves UVs as they are.
+    fn default() -> Self {
+        UvTransform {
+            translation: Vector2D::new(0.0, 0.0),
+            scaling: Vector2D::new(1.0, 1.0),
+            rotation: 0.0,
+        }
+    }
+}
+
+impl UvTransform {
+    /// Whether this transform leaves UVs as they are.
+    pub fn is_identity(&self) -> bool {
+        *self == UvTransform::default()
+    }
+
+    /// Transform `uv`, which is a `Vector3D` like Assimp's UVs, leaving `z` as it is.
+    pub fn apply(&self, uv: Vector3D) -> Vector3D {
+        if self.rotation == 0.0 {
+            // The same, without rounding errors from moving to the center and back.
+            return Vector3D::new(
+                uv.x * self.scaling.x + self.translation.x,
+                uv.y * self.scaling.y + self.translation.y,
+                uv.z,
+            );
+        }
+
+        let (sin, cos) = self.rotation.sin_cos();
+        let x = uv.x * self.scaling.x - 0.5;
+        let y = uv.y * self.scaling.y - 0.5;
+        Vector3D::new(
+            x * cos - y * sin + 0.5 + self.translation.x,
+            x * sin + y * cos + 0.5 + self.translation.y,
+            uv.z,
+        )
+    }
+}
+
 /// A definition of a single texture within a material, this does not fully describe the material
 /// as a material may include many individual textures combined with a specified blend mode.
 #[derive(Clone, Debug, PartialEq)]
@@ -723,12 +1296,20 @@ pub struct TextureDefinition {
     /// The UV channel that this texture uses - each mesh can have up to 8 UV channels, so up to 8
     /// sets of UV coordinates per-vertex. Assimp recommends in [their documentation on this property](http://assimp.sourceforge.net/lib_html/materials.html#uvwsrc),
     /// there called `MatKey_UVWSRC`, that if a texture does not define a channel, it should be
-    /// either 0 if there is only a single channel or allocated in ascending order otherwise.
+    /// either 0 if there is only a single channel or allocated in ascending order otherwise. This
+    /// crate follows that recommendation, see `channel_source`.
     pub channel: u32,
+    /// Whether `channel` comes from the file or was inferred because the file doesn't specify it.
+    /// Within each component's stack, inferred channels are allocated in ascending order, skipping
+    /// any channels the other textures of the stack use explicitly.
+    pub channel_source: ChannelSource,
     /// This texture's u-space wrapping mode - i.e. the behavior when u > 1 or u < 0
     pub wrap_u: Option<WrappingMode>,
     /// This texture's v-space wrapping mode - i.e. the behavior when v > 1 or v < 0
     pub wrap_v: Option<WrappingMode>,
+    /// This texture's w-space wrapping mode, for 3D textures. This is `None` for almost all
+    /// files, in which case it's usually best to use `wrap_u`.
+    pub wrap_w: Option<WrappingMode>,
     /// Any flags for this texture - this is going to be 0 in most cases and is usually unlikely to badly
     /// affect rendering if ignored.
     pub flags: TextureFlags,
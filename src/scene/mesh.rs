@@ -1,12 +1,16 @@
-use ffi::{aiBone, aiColor4D, aiMesh, aiVector3D, aiVertexWeight};
+use ffi::{aiAnimMesh, aiBone, aiColor4D, aiMesh, aiVector3D, aiVertexWeight};
 
 // use arrayvec::ArrayVec;
 
+use std::ffi::CStr;
+use std::fmt;
 use std::ptr::NonNull;
+use std::str::Utf8Error;
 
 use super::face::{Face, FaceIter};
-use crate::import::structs::PrimitiveTypes;
+use crate::import::structs::{PrimitiveType, PrimitiveTypes};
 use crate::math::color4::{Color4D, Color4DIter};
+use crate::math::transform::{transform_normals, transform_points};
 use crate::math::vector3::{Vector3D, Vector3DIter};
 use crate::math::Matrix4x4;
 
@@ -24,6 +28,13 @@ define_type_and_iterator_indirect! {
     struct BoneIter
 }
 
+define_type_and_iterator_indirect! {
+    /// A morph target of a mesh, also known as a blend shape
+    struct AnimMesh(&aiAnimMesh)
+    /// Anim mesh iterator type.
+    struct AnimMeshIter
+}
+
 define_type_and_iterator! {
     /// Vertex weight type
     struct VertexWeight(&aiVertexWeight)
@@ -49,15 +60,37 @@ pub struct Vertex {
 
 impl Mesh {
     /// This mesh's name (may be empty)
+    ///
+    /// Panics if the mesh name isn't valid UTF-8, see `try_name`.
     pub fn name(&self) -> &str {
-        unsafe { crate::aistring_to_cstr(&self.mName) }
-            .to_str()
-            .unwrap()
+        self.try_name().expect("mesh name is not valid UTF-8")
+    }
+
+    /// Like `name`, but returns an error if the mesh name isn't valid UTF-8.
+    pub fn try_name(&self) -> Result<&str, Utf8Error> {
+        crate::aistring_to_str(&self.mName)
+    }
+
+    /// The name of the mesh as a `CStr`, without checking that it's valid UTF-8.
+    pub fn name_cstr(&self) -> &CStr {
+        crate::aistring_to_cstr(&self.mName)
+    }
+
+    /// Whether the mesh is named `name`, compared byte for byte.
+    pub fn eq_name(&self, name: &[u8]) -> bool {
+        self.name_cstr().to_bytes() == name
     }
 
-    /// Returns a bitset of all the primitive types in use in this mesh.
+    /// Returns a bitset of all the primitive types in use in this mesh. Unknown bits are
+    /// ignored.
     pub fn primitive_types(&self) -> PrimitiveTypes {
-        PrimitiveTypes::from_bits(self.mPrimitiveTypes).unwrap()
+        PrimitiveTypes::from_bits_truncate(self.mPrimitiveTypes)
+    }
+
+    /// Whether all faces of this mesh are of `type_`, according to `primitive_types`. Meshes
+    /// imported with `sort_by_primitive_type` are always pure.
+    pub fn is_pure(&self, type_: PrimitiveType) -> bool {
+        self.primitive_types() == type_.into()
     }
 
     /// The index of this mesh's material in the parent `Model`'s `materials` array.
@@ -101,7 +134,7 @@ impl Mesh {
     pub fn positions(&self) -> Vector3DIter {
         // Every format should at least provide vertex positions, so we `unwrap` here instead
         // of returning `None`.
-        Vector3DIter::new(NonNull::new(self.mVertices), self.mNumVertices as usize)
+        unsafe { Vector3DIter::new(NonNull::new(self.mVertices), self.mNumVertices as usize) }
     }
 
     /// Get the position of the nth unique vertex .
@@ -109,9 +142,30 @@ impl Mesh {
         self.vertex_data(self.mVertices, id)
     }
 
+    /// The vertex positions as a slice, without copying them.
+    pub(crate) fn position_slice(&self) -> &[Vector3D] {
+        self.vertex_slice(self.mVertices)
+    }
+
+    /// The vertex positions transformed by `matrix`, e.g. to bake a node's transform into the
+    /// mesh. See `math::transform_points`.
+    pub fn positions_transformed(&self, matrix: &Matrix4x4) -> Vec<Vector3D> {
+        let mut out = Vec::new();
+        transform_points(matrix, self.vertex_slice(self.mVertices), &mut out);
+        out
+    }
+
+    /// The vertex normals transformed by the normal matrix of `matrix` and renormalized. See
+    /// `math::transform_normals`.
+    pub fn normals_transformed(&self, matrix: &Matrix4x4) -> Vec<Vector3D> {
+        let mut out = Vec::new();
+        transform_normals(matrix, self.vertex_slice(self.mNormals), &mut out);
+        out
+    }
+
     /// Iterator over the vertex normals.
     pub fn normals(&self) -> Vector3DIter {
-        Vector3DIter::new(NonNull::new(self.mNormals), self.mNumVertices as usize)
+        unsafe { Vector3DIter::new(NonNull::new(self.mNormals), self.mNumVertices as usize) }
     }
 
     /// Get the normal of the nth unique vertex .
@@ -121,7 +175,7 @@ impl Mesh {
 
     /// Iterator over the vertex tangents, if available. Not all formats provide tangents,
     pub fn tangents(&self) -> Vector3DIter {
-        Vector3DIter::new(NonNull::new(self.mTangents), self.mNumVertices as usize)
+        unsafe { Vector3DIter::new(NonNull::new(self.mTangents), self.mNumVertices as usize) }
     }
 
     /// Get the tangent of the nth unique vertex.
@@ -131,7 +185,7 @@ impl Mesh {
 
     /// Iterator over the vertex bitangents, if available. Not all formats provide bitangents,
     pub fn bitangents(&self) -> Vector3DIter {
-        Vector3DIter::new(NonNull::new(self.mBitangents), self.mNumVertices as usize)
+        unsafe { Vector3DIter::new(NonNull::new(self.mBitangents), self.mNumVertices as usize) }
     }
 
     /// Get the bitangent of the nth unique vertex.
@@ -141,10 +195,12 @@ impl Mesh {
 
     /// Iterator over the vertex colors, if available. Not all formats provide colors,
     pub fn vertex_colors(&self, set_id: u32) -> Color4DIter {
-        Color4DIter::new(
-            NonNull::new(self.mColors[set_id as usize]),
-            self.mNumVertices as usize,
-        )
+        unsafe {
+            Color4DIter::new(
+                NonNull::new(self.mColors[set_id as usize]),
+                self.mNumVertices as usize,
+            )
+        }
     }
 
     /// Get the color of the nth unique vertex .
@@ -152,13 +208,44 @@ impl Mesh {
         self.color_data(self.mColors[set_id as usize], id)
     }
 
+    /// Convert every color in the given set with `f`, or return `None` if the mesh doesn't have
+    /// that color set.
+    pub fn map_colors<T, F: FnMut(Color4D) -> T>(&self, set_id: u32, f: F) -> Option<Vec<T>> {
+        let colors = *self.mColors.get(set_id as usize)?;
+
+        if colors.is_null() {
+            None
+        } else {
+            Some(self.vertex_colors(set_id).map(f).collect())
+        }
+    }
+
+    /// The colors in the given set quantized to 8 bits per component, without any color space
+    /// conversion. See `Color4D::to_rgba8`.
+    pub fn vertex_colors_rgba8(&self, set_id: u32) -> Option<Vec<[u8; 4]>> {
+        self.map_colors(set_id, Color4D::to_rgba8)
+    }
+
+    /// The colors in the given set, converted from sRGB to linear. Most formats store vertex
+    /// colors in sRGB, but don't say so. See `Color4D::to_linear`.
+    pub fn vertex_colors_srgb_to_linear(&self, set_id: u32) -> Option<Vec<Color4D>> {
+        self.map_colors(set_id, Color4D::to_linear)
+    }
+
+    /// The colors in the given set, converted from linear to sRGB. See `Color4D::to_srgb`.
+    pub fn vertex_colors_linear_to_srgb(&self, set_id: u32) -> Option<Vec<Color4D>> {
+        self.map_colors(set_id, Color4D::to_srgb)
+    }
+
     /// Iterator over the vertex UVs, if available. Not all formats provide UVs, and even if this
     /// mesh has a material it may be mapped in a way that doesn't require UVs,
     pub fn texture_coords(&self, channel_id: u32) -> Vector3DIter {
-        Vector3DIter::new(
-            NonNull::new(self.mTextureCoords[channel_id as usize]),
-            self.mNumVertices as usize,
-        )
+        unsafe {
+            Vector3DIter::new(
+                NonNull::new(self.mTextureCoords[channel_id as usize]),
+                self.mNumVertices as usize,
+            )
+        }
     }
 
     /// Get the UV of the nth unique vertex
@@ -166,6 +253,55 @@ impl Mesh {
         self.vertex_data(self.mTextureCoords[channel_id as usize], id)
     }
 
+    /// The original name of the given UV channel, e.g. `map1` or `lightmapUV`, if the file
+    /// names its UV channels. Empty names are returned as `None`.
+    ///
+    /// Panics if the name isn't valid UTF-8, see `try_texture_coords_name`.
+    pub fn texture_coords_name(&self, channel_id: u32) -> Option<&str> {
+        self.try_texture_coords_name(channel_id)
+            .expect("texture coordinate name is not valid UTF-8")
+    }
+
+    /// Like `texture_coords_name`, but returns an error if the name isn't valid UTF-8.
+    pub fn try_texture_coords_name(&self, channel_id: u32) -> Result<Option<&str>, Utf8Error> {
+        self.raw_texture_coords_name(channel_id)
+            .map(crate::aistring_to_str)
+            .transpose()
+    }
+
+    /// The names of all UV channels, indexed by channel, see `texture_coords_name`.
+    ///
+    /// Panics if any name isn't valid UTF-8.
+    pub fn texture_coords_names(&self) -> Vec<Option<&str>> {
+        (0..self.mTextureCoords.len() as u32)
+            .map(|channel_id| self.texture_coords_name(channel_id))
+            .collect()
+    }
+
+    /// Both the array of names and each entry in it can be null.
+    #[cfg(feature = "assimp-5-1")]
+    fn raw_texture_coords_name(&self, channel_id: u32) -> Option<&ffi::aiString> {
+        if channel_id as usize >= self.mTextureCoords.len() {
+            return None;
+        }
+
+        let names = NonNull::new(self.mTextureCoordsNames)?;
+        let name = unsafe { (*names.as_ptr().add(channel_id as usize)).as_ref()? };
+
+        if name.length == 0 {
+            None
+        } else {
+            Some(name)
+        }
+    }
+
+    /// Versions of Assimp before 5.1 don't record UV channel names, so without the
+    /// `assimp-5-1` feature there are none.
+    #[cfg(not(feature = "assimp-5-1"))]
+    fn raw_texture_coords_name(&self, _channel_id: u32) -> Option<&ffi::aiString> {
+        None
+    }
+
     /// The number of faces in this mesh
     pub fn num_faces(&self) -> u32 {
         self.mNumFaces
@@ -174,39 +310,128 @@ impl Mesh {
     /// Iterator over the faces in this mesh. Each face is described as a list of indices into the vertex
     /// array
     pub fn faces(&self) -> FaceIter {
-        FaceIter::new(NonNull::new(self.mFaces), self.mNumFaces as usize)
+        unsafe { FaceIter::new(NonNull::new(self.mFaces), self.mNumFaces as usize) }
+    }
+
+    /// The faces of `type_`, as classified by `Face::primitive_type`, with their indices in
+    /// `faces`. This is empty if `primitive_types` doesn't contain `type_`, without looking at
+    /// every face.
+    pub fn faces_of_type(&self, type_: PrimitiveType) -> impl Iterator<Item = (u32, &Face)> {
+        let faces = if self.primitive_types().contains(type_.into()) {
+            self.faces()
+        } else {
+            unsafe { FaceIter::new(None, 0) }
+        };
+        faces
+            .enumerate()
+            .filter(move |(_, face)| face.primitive_type() == Some(type_))
+            .map(|(index, face)| (index as u32, face))
     }
 
     /// Get the nth face.
     pub fn face(&self, id: u32) -> Option<&Face> {
         if id < self.mNumFaces {
             unsafe {
-                Some(Face::from_raw(NonNull::new(
-                    NonNull::new(self.mFaces)?.as_ptr().offset(id as isize),
-                )?))
+                Some(Face::from_raw(NonNull::new(crate::array_element(
+                    self.mFaces,
+                    id as usize,
+                )?)?))
             }
         } else {
             None
         }
     }
 
+    /// Returns true if every index in this mesh fits in a `u16`, i.e. if the mesh can be
+    /// rendered with a 16-bit index buffer.
+    pub fn fits_u16_indices(&self) -> bool {
+        self.mNumVertices as usize <= u16::MAX as usize + 1
+            || self
+                .faces()
+                .all(|face| face.indices().iter().all(|&i| i <= u16::MAX as u32))
+    }
+
+    /// The indices of all faces in this mesh, concatenated in order. This is only really
+    /// useful if all faces have the same primitive type, e.g. after `Importer::triangulate`.
+    pub fn indices_u32(&self) -> Vec<u32> {
+        let mut out = Vec::with_capacity(self.mNumFaces as usize * 3);
+
+        for face in self.faces() {
+            out.extend_from_slice(face.indices());
+        }
+
+        out
+    }
+
+    /// Like `indices_u32`, but as 16-bit indices. Returns `None` if any index doesn't fit in a
+    /// `u16` instead of silently truncating it.
+    pub fn indices_u16(&self) -> Option<Vec<u16>> {
+        use std::convert::TryFrom;
+
+        let mut out = Vec::with_capacity(self.mNumFaces as usize * 3);
+
+        for face in self.faces() {
+            for &index in face.indices() {
+                out.push(u16::try_from(index).ok()?);
+            }
+        }
+
+        Some(out)
+    }
+
     pub fn num_bones(&self) -> u32 {
         self.mNumBones
     }
 
     pub fn bones(&self) -> BoneIter {
-        BoneIter::new(
-            NonNull::new(self.mBones as *mut *const aiBone),
-            self.mNumBones as usize,
-        )
+        unsafe {
+            BoneIter::new(
+                NonNull::new(self.mBones as *mut *const aiBone),
+                self.mNumBones as usize,
+            )
+        }
     }
 
     pub fn bone(&self, id: u32) -> Option<&Bone> {
         if id < self.mNumBones {
             unsafe {
-                Some(Bone::from_raw(NonNull::new(
-                    *(NonNull::new(self.mBones)?.as_ptr().offset(id as isize)),
-                )?))
+                Some(Bone::from_raw(NonNull::new(*crate::array_element(
+                    self.mBones,
+                    id as usize,
+                )?)?))
+            }
+        } else {
+            None
+        }
+    }
+
+    /// The index of the first bone named `name`, for use with `bone`. Names are compared byte
+    /// for byte, so this doesn't check them for valid UTF-8.
+    pub fn bone_index_by_name(&self, name: &[u8]) -> Option<u32> {
+        (0..self.mNumBones).find(|&id| self.bone(id).map_or(false, |bone| bone.eq_name(name)))
+    }
+
+    /// Returns the number of morph targets of this mesh.
+    pub fn num_anim_meshes(&self) -> u32 {
+        self.mNumAnimMeshes
+    }
+
+    pub fn anim_meshes(&self) -> AnimMeshIter {
+        unsafe {
+            AnimMeshIter::new(
+                NonNull::new(self.mAnimMeshes as *mut *const aiAnimMesh),
+                self.mNumAnimMeshes as usize,
+            )
+        }
+    }
+
+    pub fn anim_mesh(&self, id: u32) -> Option<&AnimMesh> {
+        if id < self.mNumAnimMeshes {
+            unsafe {
+                Some(AnimMesh::from_raw(NonNull::new(*crate::array_element(
+                    self.mAnimMeshes,
+                    id as usize,
+                )?)?))
             }
         } else {
             None
@@ -217,31 +442,88 @@ impl Mesh {
     fn vertex_data(&self, array: *mut aiVector3D, id: u32) -> Option<Vector3D> {
         if id < self.mNumVertices {
             unsafe {
-                Some(Vector3D::from_raw(
-                    *NonNull::new(array)?.as_ptr().offset(id as isize),
-                ))
+                Some(Vector3D::from_raw(*crate::array_element(
+                    array,
+                    id as usize,
+                )?))
             }
         } else {
             None
         }
     }
 
+    /// View a per-vertex array as a slice, which is empty if the mesh doesn't have it.
+    fn vertex_slice(&self, array: *mut aiVector3D) -> &[Vector3D] {
+        // `Vector3D` is a transparent wrapper around `aiVector3D`.
+        unsafe { crate::array_slice(array as *const Vector3D, self.mNumVertices as usize) }
+    }
+
     #[inline]
     fn color_data(&self, array: *mut aiColor4D, id: u32) -> Option<Color4D> {
         if id < self.mNumVertices {
-            unsafe { Some(Color4D::from_raw(*array.offset(id as isize))) }
+            unsafe {
+                Some(Color4D::from_raw(*crate::array_element(
+                    array,
+                    id as usize,
+                )?))
+            }
         } else {
             None
         }
     }
 }
 
+/// Prints the mesh's name, counts, which vertex attributes it has and its material, but never
+/// its vertex data. `color_sets` and `uv_channels` are the indices of the sets that are present.
+impl fmt::Debug for Mesh {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let color_sets = (0..self.mColors.len()).filter(|&set| !self.mColors[set].is_null());
+        let uv_channels = (0..self.mTextureCoords.len())
+            .filter(|&channel| !self.mTextureCoords[channel].is_null());
+
+        f.debug_struct("Mesh")
+            .field("name", &self.name_cstr().to_string_lossy())
+            .field("num_vertices", &self.num_vertices())
+            .field("num_faces", &self.num_faces())
+            .field("normals", &!self.mNormals.is_null())
+            .field("tangents", &!self.mTangents.is_null())
+            .field("bitangents", &!self.mBitangents.is_null())
+            .field("color_sets", &crate::BoundedList(color_sets))
+            .field("uv_channels", &crate::BoundedList(uv_channels))
+            .field("num_bones", &self.num_bones())
+            .field("material_id", &self.material_id())
+            .finish()
+    }
+}
+
+/// The positions of the mesh's vertices, e.g. for the functions in `collision`.
+impl AsRef<[Vector3D]> for Mesh {
+    fn as_ref(&self) -> &[Vector3D] {
+        self.vertex_slice(self.mVertices)
+    }
+}
+
 impl Bone {
     /// Returns the name of the bone.
+    ///
+    /// Panics if the bone name isn't valid UTF-8, see `try_name`.
     pub fn name(&self) -> &str {
-        unsafe { crate::aistring_to_cstr(&self.mName) }
-            .to_str()
-            .unwrap()
+        self.try_name().expect("bone name is not valid UTF-8")
+    }
+
+    /// Like `name`, but returns an error if the bone name isn't valid UTF-8.
+    pub fn try_name(&self) -> Result<&str, Utf8Error> {
+        crate::aistring_to_str(&self.mName)
+    }
+
+    /// The name of the bone as a `CStr`, without checking that it's valid UTF-8.
+    pub fn name_cstr(&self) -> &CStr {
+        crate::aistring_to_cstr(&self.mName)
+    }
+
+    /// Whether the bone is named `name`, compared byte for byte.
+    pub fn eq_name(&self, name: &[u8]) -> bool {
+        self.name_cstr().to_bytes() == name
     }
 
     /// Returns the bones's offset transformation matrix.
@@ -256,18 +538,88 @@ impl Bone {
 
     /// Get an iterator over the vertex weights for this bone
     pub fn weights(&self) -> VertexWeightIter {
-        VertexWeightIter::new(
-            NonNull::new(self.mWeights),
-            self.mNumWeights as usize,
-        )
+        unsafe { VertexWeightIter::new(NonNull::new(self.mWeights), self.mNumWeights as usize) }
     }
 
     /// Get the nth vertex weight
     pub fn weight(&self, id: u32) -> Option<&VertexWeight> {
         if id < self.mNumWeights {
             unsafe {
-                Some(VertexWeight::from_raw(NonNull::new(
-                    NonNull::new(self.mWeights)?.as_ptr().offset(id as isize),
+                Some(VertexWeight::from_raw(NonNull::new(crate::array_element(
+                    self.mWeights,
+                    id as usize,
+                )?)?))
+            }
+        } else {
+            None
+        }
+    }
+}
+
+/// Prints the bone's name and number of weights, but not the weights themselves.
+impl fmt::Debug for Bone {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Bone")
+            .field("name", &self.name_cstr().to_string_lossy())
+            .field("num_weights", &self.num_weights())
+            .finish()
+    }
+}
+
+impl AnimMesh {
+    /// Returns the name of the morph target (may be empty).
+    ///
+    /// Panics if the name isn't valid UTF-8, see `try_name`.
+    pub fn name(&self) -> &str {
+        self.try_name().expect("anim mesh name is not valid UTF-8")
+    }
+
+    /// Like `name`, but returns an error if the name isn't valid UTF-8.
+    pub fn try_name(&self) -> Result<&str, Utf8Error> {
+        crate::aistring_to_str(&self.mName)
+    }
+
+    /// The name of the morph target as a `CStr`, without checking that it's valid UTF-8.
+    pub fn name_cstr(&self) -> &CStr {
+        crate::aistring_to_cstr(&self.mName)
+    }
+
+    /// The default weight of the morph target.
+    pub fn weight(&self) -> f32 {
+        self.mWeight
+    }
+
+    /// Returns the number of vertices, which is the same as for the mesh it belongs to.
+    pub fn num_vertices(&self) -> u32 {
+        self.mNumVertices
+    }
+
+    /// Iterator over the vertex positions of the morph target, if it replaces them. These are
+    /// the positions with the target fully applied, not offsets from those of the mesh.
+    pub fn positions(&self) -> Vector3DIter {
+        unsafe { Vector3DIter::new(NonNull::new(self.mVertices), self.mNumVertices as usize) }
+    }
+
+    pub fn position(&self, id: u32) -> Option<Vector3D> {
+        self.vertex_data(self.mVertices, id)
+    }
+
+    /// Iterator over the vertex normals of the morph target, if it replaces them.
+    pub fn normals(&self) -> Vector3DIter {
+        unsafe { Vector3DIter::new(NonNull::new(self.mNormals), self.mNumVertices as usize) }
+    }
+
+    pub fn normal(&self, id: u32) -> Option<Vector3D> {
+        self.vertex_data(self.mNormals, id)
+    }
+
+    #[inline]
+    fn vertex_data(&self, array: *mut aiVector3D, id: u32) -> Option<Vector3D> {
+        if id < self.mNumVertices {
+            unsafe {
+                Some(Vector3D::from_raw(*crate::array_element(
+                    array,
+                    id as usize,
                 )?))
             }
         } else {
@@ -275,3 +627,13 @@ impl Bone {
         }
     }
 }
+
+/// Prints the name and weight of the morph target, but not its vertices.
+impl fmt::Debug for AnimMesh {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("AnimMesh")
+            .field("name", &self.name_cstr().to_string_lossy())
+            .field("weight", &self.weight())
+            .finish()
+    }
+}
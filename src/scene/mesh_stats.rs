@@ -0,0 +1,378 @@
+//! Statistics of how well meshes will render, to decide whether
+//! `Importer::improve_cache_locality` is worth running, and to check that it helped.
+//!
+//! `vertex_cache_stats` simulates a GPU's post-transform vertex cache as a FIFO, and gives the
+//! average cache miss ratio (ACMR), the number of vertices transformed per triangle, and the
+//! average transform to vertex ratio (ATVR), the number of times each vertex is transformed.
+//! An ACMR of 0.5 to 0.7 is good for a regular mesh, and 3 is the worst.
+//! `Scene::vertex_cache_stats` gives them for every mesh of a scene, and
+//! `Importer::apply_postprocessing_with_cache_report` compares them before and after
+//! post-processing.
+//!
+//! `overdraw_estimate` is a proxy for overdraw: the average number of front-facing triangles
+//! that cover each cell of a coarse grid, looking at the mesh from a few directions. It ignores
+//! the draw order, so it's the depth complexity of the mesh rather than the overdraw of a
+//! particular renderer, which depends on the order the triangles are drawn in and on the
+//! depth test.
+//!
+//! Both take the triangles of the faces with 3 or more vertices, split into fans, and ignore
+//! points and lines.
+
+use super::mesh::Mesh;
+use super::scene::Scene;
+use super::snapshot::MeshData;
+use crate::math::Vector3D;
+
+/// The result of `vertex_cache_stats`.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct VertexCacheStats {
+    pub triangles: u64,
+    /// The number of distinct vertices the triangles use.
+    pub vertices: u64,
+    /// The number of vertices that weren't in the cache, and so were transformed.
+    pub cache_misses: u64,
+    /// The average cache miss ratio, `cache_misses / triangles`, or 0 without triangles.
+    pub acmr: f32,
+    /// The average transform to vertex ratio, `cache_misses / vertices`, or 0 without
+    /// triangles. 1 is the best possible.
+    pub atvr: f32,
+}
+
+impl VertexCacheStats {
+    fn new(triangles: u64, vertices: u64, cache_misses: u64) -> Self {
+        let ratio = |count: u64| {
+            if count == 0 {
+                0.0
+            } else {
+                (cache_misses as f64 / count as f64) as f32
+            }
+        };
+        VertexCacheStats {
+            triangles,
+            vertices,
+            cache_misses,
+            acmr: ratio(triangles),
+            atvr: ratio(vertices),
+        }
+    }
+
+    /// The statistics of several meshes drawn one after the other, each starting with an empty
+    /// cache.
+    pub fn sum<'a, I: IntoIterator<Item = &'a VertexCacheStats>>(stats: I) -> Self {
+        let (mut triangles, mut vertices, mut cache_misses) = (0, 0, 0);
+        for stats in stats {
+            triangles += stats.triangles;
+            vertices += stats.vertices;
+            cache_misses += stats.cache_misses;
+        }
+        VertexCacheStats::new(triangles, vertices, cache_misses)
+    }
+}
+
+/// Simulate drawing the triangle list `indices` with a FIFO vertex cache of `cache_size`
+/// entries, which starts empty. Like most GPUs, a vertex that's already in the cache doesn't
+/// move when it's used again. Indices after the last whole triangle are ignored.
+pub fn vertex_cache_stats(indices: &[u32], cache_size: u32) -> VertexCacheStats {
+    let indices = &indices[..indices.len() / 3 * 3];
+    let max = indices
+        .iter()
+        .copied()
+        .max()
+        .map_or(0, |max| max as usize + 1);
+    // The miss count when each vertex was last added to the cache. A vertex is still in the
+    // cache if fewer than `cache_size` vertices were added after it.
+    let mut added: Vec<Option<u64>> = vec![None; max];
+    let mut misses = 0u64;
+    let mut vertices = 0u64;
+
+    for &index in indices {
+        let slot = &mut added[index as usize];
+        match *slot {
+            Some(at) if misses - at < u64::from(cache_size) => {}
+            previous => {
+                if previous.is_none() {
+                    vertices += 1;
+                }
+                *slot = Some(misses);
+                misses += 1;
+            }
+        }
+    }
+
+    VertexCacheStats::new((indices.len() / 3) as u64, vertices, misses)
+}
+
+/// The triangles of `faces`, as a triangle list, with polygons split into fans.
+fn triangle_list<'a, I: IntoIterator<Item = &'a [u32]>>(faces: I) -> Vec<u32> {
+    let mut indices = Vec::new();
+    for face in faces {
+        for k in 2..face.len() {
+            indices.extend_from_slice(&[face[0], face[k - 1], face[k]]);
+        }
+    }
+    indices
+}
+
+impl Mesh {
+    /// `vertex_cache_stats` of the triangles of the mesh, in the order they're stored.
+    pub fn vertex_cache_stats(&self, cache_size: u32) -> VertexCacheStats {
+        let indices = triangle_list(self.faces().map(|face| face.indices()));
+        vertex_cache_stats(&indices, cache_size)
+    }
+}
+
+/// The vertex cache statistics of the meshes of a scene, as returned by
+/// `Scene::vertex_cache_stats`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SceneVertexCacheStats {
+    pub cache_size: u32,
+    /// The statistics of each mesh, in the order of `Scene::meshes`.
+    pub meshes: Vec<VertexCacheStats>,
+    /// The statistics of all meshes together, see `VertexCacheStats::sum`.
+    pub total: VertexCacheStats,
+}
+
+impl Scene<'_> {
+    /// `Mesh::vertex_cache_stats` of every mesh.
+    pub fn vertex_cache_stats(&self, cache_size: u32) -> SceneVertexCacheStats {
+        let meshes: Vec<VertexCacheStats> = self
+            .meshes()
+            .map(|mesh| mesh.vertex_cache_stats(cache_size))
+            .collect();
+        SceneVertexCacheStats {
+            cache_size,
+            total: VertexCacheStats::sum(&meshes),
+            meshes,
+        }
+    }
+}
+
+/// The vertex cache statistics of a scene before and after post-processing, as returned by
+/// `Importer::apply_postprocessing_with_cache_report`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CacheLocalityReport {
+    pub before: SceneVertexCacheStats,
+    pub after: SceneVertexCacheStats,
+}
+
+impl CacheLocalityReport {
+    /// How much the ACMR of the whole scene went down, as a fraction of what it was, e.g. 0.25
+    /// if it went from 1.0 to 0.75. This is negative if it went up, and 0 without triangles.
+    pub fn acmr_improvement(&self) -> f32 {
+        let (before, after) = (self.before.total.acmr, self.after.total.acmr);
+        if before == 0.0 {
+            0.0
+        } else {
+            (before - after) / before
+        }
+    }
+}
+
+/// A direction `overdraw_estimate` looks at a mesh from.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct OverdrawView {
+    /// The direction the viewer looks in. It doesn't need to be normalized.
+    pub direction: Vector3D,
+    /// The number of cells of the grid along each side. The grid covers the bounds of the mesh
+    /// as seen from the view.
+    pub resolution: u32,
+}
+
+impl OverdrawView {
+    /// The six views along the axes, with a grid of `resolution` by `resolution` cells.
+    pub fn canonical(resolution: u32) -> [OverdrawView; 6] {
+        let view = |x, y, z| OverdrawView {
+            direction: Vector3D::new(x, y, z),
+            resolution,
+        };
+        [
+            view(1.0, 0.0, 0.0),
+            view(-1.0, 0.0, 0.0),
+            view(0.0, 1.0, 0.0),
+            view(0.0, -1.0, 0.0),
+            view(0.0, 0.0, 1.0),
+            view(0.0, 0.0, -1.0),
+        ]
+    }
+}
+
+/// The overdraw of a mesh from one `OverdrawView`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ViewOverdraw {
+    pub view: OverdrawView,
+    /// The number of cells that at least one front-facing triangle covers.
+    pub covered_cells: u64,
+    /// The number of times a front-facing triangle covers a cell, summed over all cells.
+    pub fragments: u64,
+    /// `fragments / covered_cells`, or 0 if no cell is covered.
+    pub overdraw: f32,
+}
+
+/// The result of `overdraw_estimate`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct OverdrawEstimate {
+    pub views: Vec<ViewOverdraw>,
+    /// The average `ViewOverdraw::overdraw` of the views that cover any cell, or 0 if none
+    /// does.
+    pub average: f32,
+}
+
+/// Estimate the overdraw of `mesh` from each of `views`, see the module documentation.
+///
+/// Each view projects the mesh orthographically onto a plane facing it, and covers the bounds
+/// of the projection with a grid. A triangle covers a cell if it faces the viewer, with
+/// Assimp's counter-clockwise winding, and contains the center of the cell. Centers on an
+/// edge shared by two triangles count for exactly one of them. Faces with indices past the
+/// vertices are skipped, and so are views with a zero direction or resolution.
+///
+/// The result is the same on every run and every platform, as it only depends on the order of
+/// the faces and f32 arithmetic.
+pub fn overdraw_estimate(mesh: &MeshData, views: &[OverdrawView]) -> OverdrawEstimate {
+    let positions = &mesh.positions;
+    let indices: Vec<u32> = triangle_list(mesh.faces.iter().map(Vec::as_slice))
+        .chunks_exact(3)
+        .filter(|triangle| triangle.iter().all(|&i| (i as usize) < positions.len()))
+        .flatten()
+        .copied()
+        .collect();
+
+    let views: Vec<ViewOverdraw> = views
+        .iter()
+        .map(|&view| view_overdraw(positions, &indices, view))
+        .collect();
+    let covered: Vec<f32> = views
+        .iter()
+        .filter(|view| view.covered_cells > 0)
+        .map(|view| view.overdraw)
+        .collect();
+    let average = if covered.is_empty() {
+        0.0
+    } else {
+        covered.iter().sum::<f32>() / covered.len() as f32
+    };
+    OverdrawEstimate { views, average }
+}
+
+fn view_overdraw(positions: &[Vector3D], indices: &[u32], view: OverdrawView) -> ViewOverdraw {
+    let mut result = ViewOverdraw {
+        view,
+        covered_cells: 0,
+        fragments: 0,
+        overdraw: 0.0,
+    };
+    let d = view.direction;
+    let length = (d.x * d.x + d.y * d.y + d.z * d.z).sqrt();
+    let resolution = view.resolution as usize;
+    if length.is_nan() || length <= 0.0 || resolution == 0 || indices.is_empty() {
+        return result;
+    }
+    let d = Vector3D::new(d.x / length, d.y / length, d.z / length);
+
+    // A basis of the plane facing the viewer, with `u` to the right and `v` up, so that
+    // `u x v = -d` and triangles that are counter-clockwise seen from the viewer stay
+    // counter-clockwise in (u, v).
+    let helper = if d.y.abs() < 0.9 {
+        Vector3D::new(0.0, 1.0, 0.0)
+    } else {
+        Vector3D::new(0.0, 0.0, 1.0)
+    };
+    let u = normalize(cross(d, helper));
+    let v = cross(u, d);
+    let projected: Vec<(f32, f32)> = positions.iter().map(|&p| (dot(p, u), dot(p, v))).collect();
+
+    let used = indices.iter().map(|&i| projected[i as usize]);
+    let (mut min, mut max) = (
+        (f32::INFINITY, f32::INFINITY),
+        (f32::NEG_INFINITY, f32::NEG_INFINITY),
+    );
+    for (x, y) in used {
+        min = (min.0.min(x), min.1.min(y));
+        max = (max.0.max(x), max.1.max(y));
+    }
+    // The cells are square, over the larger side of the bounds.
+    let size = (max.0 - min.0).max(max.1 - min.1);
+    let cell = if size > 0.0 {
+        size / resolution as f32
+    } else {
+        1.0
+    };
+
+    let mut counts = vec![0u32; resolution * resolution];
+    for triangle in indices.chunks_exact(3) {
+        let to_grid = |i: u32| {
+            let (x, y) = projected[i as usize];
+            ((x - min.0) / cell, (y - min.1) / cell)
+        };
+        let (a, b, c) = (
+            to_grid(triangle[0]),
+            to_grid(triangle[1]),
+            to_grid(triangle[2]),
+        );
+        // Looking along `d`, the triangle faces the viewer if it's counter-clockwise in (u, v).
+        let area = (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0);
+        if area.is_nan() || area <= 0.0 {
+            continue;
+        }
+        rasterize([a, b, c], resolution, &mut counts);
+    }
+
+    for &count in &counts {
+        if count > 0 {
+            result.covered_cells += 1;
+            result.fragments += u64::from(count);
+        }
+    }
+    if result.covered_cells > 0 {
+        result.overdraw = (result.fragments as f64 / result.covered_cells as f64) as f32;
+    }
+    result
+}
+
+/// Add 1 to each cell of the `resolution` by `resolution` grid whose center is in the
+/// counter-clockwise triangle `corners`, in grid coordinates.
+fn rasterize(corners: [(f32, f32); 3], resolution: usize, counts: &mut [u32]) {
+    let low = |values: [f32; 3]| {
+        let min = values[0].min(values[1]).min(values[2]);
+        (min - 0.5).ceil().max(0.0) as usize
+    };
+    let high = |values: [f32; 3]| {
+        let max = values[0].max(values[1]).max(values[2]);
+        ((max - 0.5).floor().max(-1.0) + 1.0).min(resolution as f32) as usize
+    };
+    let xs = [corners[0].0, corners[1].0, corners[2].0];
+    let ys = [corners[0].1, corners[1].1, corners[2].1];
+
+    for y in low(ys)..high(ys) {
+        for x in low(xs)..high(xs) {
+            let center = (x as f32 + 0.5, y as f32 + 0.5);
+            let inside = (0..3).all(|k| {
+                let (from, to) = (corners[k], corners[(k + 1) % 3]);
+                let edge = (to.0 - from.0, to.1 - from.1);
+                let side = edge.0 * (center.1 - from.1) - edge.1 * (center.0 - from.0);
+                // A center on an edge belongs to the triangle on one side of it only: the two
+                // triangles sharing the edge run along it in opposite directions.
+                side > 0.0 || (side == 0.0 && (edge.1 > 0.0 || (edge.1 == 0.0 && edge.0 > 0.0)))
+            });
+            if inside {
+                counts[y * resolution + x] += 1;
+            }
+        }
+    }
+}
+
+fn dot(a: Vector3D, b: Vector3D) -> f32 {
+    a.x * b.x + a.y * b.y + a.z * b.z
+}
+
+fn cross(a: Vector3D, b: Vector3D) -> Vector3D {
+    Vector3D::new(
+        a.y * b.z - a.z * b.y,
+        a.z * b.x - a.x * b.z,
+        a.x * b.y - a.y * b.x,
+    )
+}
+
+fn normalize(v: Vector3D) -> Vector3D {
+    let length = dot(v, v).sqrt();
+    Vector3D::new(v.x / length, v.y / length, v.z / length)
+}
@@ -1,21 +1,110 @@
 //! The `scene` module contains definitions of imported scene data.
 
 pub use self::animation::*;
+pub use self::arena::{NodeArena, NodeRecord};
+pub use self::bind_pose::{BindPoseReport, BindPoseSuggestion, BindPoseThresholds, BoneDeviation};
 pub use self::camera::*;
+pub use self::canonical::CanonicalOrdering;
+pub use self::complexity::{ComplexityIssue, ComplexityLimits, MaterialComplexity};
+pub use self::conventions::SourceConventions;
+pub use self::density::{Distribution, MeshDensityReport, UvChannelDensity};
+pub use self::extract::{ExtractedTexture, NamingStrategy};
 pub use self::face::*;
+#[cfg(feature = "image")]
+pub use self::flatten::{FlattenOptions, FlattenWarning, FlattenedComponent, FlattenedValue};
+pub use self::groups::{RootGroup, RootGroupOptions, TakeGroup, TakeOptions};
+pub use self::hash::HashOptions;
+pub(crate) use self::hash::StableHasher;
+pub use self::identity::{MeshId, NodeId};
+pub use self::instancing::{partition_instances, InstancingSummary, MeshInstances};
 pub use self::light::*;
 pub use self::material::*;
 pub use self::mesh::*;
+pub use self::morph::{apply_morphs, MorphTargetDeltas};
 pub use self::node::*;
-pub use self::scene::Scene;
+#[cfg(feature = "image")]
+pub use self::normal_map::{height_to_normal, NormalMapImage};
+pub use self::overlay::*;
+pub use self::placement::Placed;
+pub use self::render_state::{BlendMode, CullMode, RenderStateHint};
+pub use self::requirements::{MaterialRequirementReport, MeshRequirementReport, RequiredAttribute};
+pub use self::sample::{AttributeSelector, SampleError, SampledValue, SampledVertex};
+#[cfg(feature = "test-fixtures")]
+pub(crate) use self::scene::is_owned_scene;
+pub use self::scene::{ImportDiagnostics, MeshGroup, OptimizeMeshesReport, Ownership, Scene};
+#[cfg(feature = "assimp-5-2")]
+pub use self::skeleton::*;
+pub use self::smoothing::count_regions;
+pub use self::snapshot::*;
+pub use self::statistics::{FaceCounts, SceneStatistics};
 pub use self::texture::*;
+pub(crate) use self::validation::validation_findings;
+pub use self::validation::{NullEntries, ValidationFinding, ValidationSeverity};
+pub use self::visit::{SceneVisitor, VisitControl, VisitOptions};
 
+pub mod animation_bake;
+pub mod animation_compress;
+pub mod animation_process;
+pub mod bake;
+pub mod binary;
+pub mod cleanup;
+pub mod collision;
+pub mod convert;
+pub mod diff;
+pub mod flags;
+pub mod gc;
+pub mod joints;
+pub mod lod;
+pub mod mesh_stats;
+pub mod naming;
+pub mod profile;
+pub mod prune;
+pub mod quantize;
+pub mod raycast;
+pub mod relocate;
+pub mod simplify;
+pub mod skinning;
+pub mod strips;
+pub mod uv;
+pub mod uv_transform;
+pub mod vertex_buffer;
+pub mod weld;
+
+mod adjacency;
 mod animation;
+mod arena;
+mod bind_pose;
 mod camera;
+mod canonical;
+mod complexity;
+mod conventions;
+mod density;
+mod extract;
 mod face;
+#[cfg(feature = "image")]
+mod flatten;
+mod groups;
+mod hash;
+mod identity;
+mod instancing;
 mod light;
 mod material;
 mod mesh;
+mod morph;
 mod node;
+#[cfg(feature = "image")]
+mod normal_map;
+mod overlay;
+mod placement;
+mod render_state;
+mod requirements;
+mod sample;
 mod scene;
+#[cfg(feature = "assimp-5-2")]
+mod skeleton;
+mod smoothing;
+mod snapshot;
+mod statistics;
 mod texture;
+mod validation;
+mod visit;
@@ -0,0 +1,88 @@
+//! Sparse morph target deltas, see `Mesh::morph_target_deltas`.
+
+use crate::math::Vector3D;
+
+use super::mesh::Mesh;
+
+/// The vertices that a morph target moves, as offsets from the mesh it belongs to. The three
+/// arrays have the same length, with one entry per moved vertex in ascending order of index.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MorphTargetDeltas {
+    pub name: String,
+    /// The default weight of the target, see `AnimMesh::weight`.
+    pub weight: f32,
+    pub indices: Vec<u32>,
+    pub position_deltas: Vec<Vector3D>,
+    /// Zero for every vertex if the target or the mesh doesn't have normals.
+    pub normal_deltas: Vec<Vector3D>,
+}
+
+impl MorphTargetDeltas {
+    /// The number of vertices the target moves.
+    pub fn len(&self) -> usize {
+        self.indices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+}
+
+impl Mesh {
+    /// The deltas of every morph target of the mesh, in the order of `anim_meshes`. A vertex
+    /// is left out if neither its position nor its normal moves by more than `epsilon`.
+    ///
+    /// Targets store absolute positions, and most formats store every vertex even though a
+    /// typical blend shape only moves a small part of the mesh, so this is usually much
+    /// smaller. Targets that don't replace positions or normals move none.
+    pub fn morph_target_deltas(&self, epsilon: f32) -> Vec<MorphTargetDeltas> {
+        let positions: Vec<Vector3D> = self.positions().collect();
+        let normals: Vec<Vector3D> = self.normals().collect();
+        self.anim_meshes()
+            .map(|target| {
+                let mut deltas = MorphTargetDeltas {
+                    name: target.name_cstr().to_string_lossy().into_owned(),
+                    weight: target.weight(),
+                    ..Default::default()
+                };
+                let target_positions: Vec<Vector3D> = target.positions().collect();
+                let target_normals: Vec<Vector3D> = target.normals().collect();
+                for vertex in 0..positions.len() {
+                    let position = delta(&positions, &target_positions, vertex);
+                    let normal = delta(&normals, &target_normals, vertex);
+                    if position.length() > epsilon || normal.length() > epsilon {
+                        deltas.indices.push(vertex as u32);
+                        deltas.position_deltas.push(position);
+                        deltas.normal_deltas.push(normal);
+                    }
+                }
+                deltas
+            })
+            .collect()
+    }
+}
+
+/// The offset of `vertex` from `base` to `moved`, or zero if either doesn't have it.
+fn delta(base: &[Vector3D], moved: &[Vector3D], vertex: usize) -> Vector3D {
+    match (base.get(vertex), moved.get(vertex)) {
+        (Some(&base), Some(&moved)) => moved - base,
+        _ => Vector3D::new(0.0, 0.0, 0.0),
+    }
+}
+
+/// Add the position deltas of the morph targets in `targets` to `base`, each scaled by its
+/// weight. `targets` has pairs of an index into `deltas` and a weight, so
+/// `&[(0, 1.0)]` gives the positions of the first target of `Mesh::morph_target_deltas`
+/// when `base` holds the positions of the mesh.
+///
+/// Panics if an index in `targets` is out of range for `deltas`, or a delta is for a vertex
+/// past the end of `base`.
+pub fn apply_morphs(deltas: &[MorphTargetDeltas], base: &mut [Vector3D], targets: &[(usize, f32)]) {
+    for &(target, weight) in targets {
+        let target = &deltas[target];
+        for (&vertex, &delta) in target.indices.iter().zip(&target.position_deltas) {
+            let position = &mut base[vertex as usize];
+            *position = *position + delta * weight;
+        }
+    }
+}
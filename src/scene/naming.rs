@@ -0,0 +1,301 @@
+//! Sanitized, unique identifiers for the names in a scene.
+//!
+//! Engines often restrict the characters and length of asset identifiers, and imported names
+//! are frequently duplicated. `sanitize` maps every node, mesh, material, bone and animation
+//! name to an identifier that follows a `NamingRules`, and the resulting `NameMap` can be
+//! queried in both directions. The mapping only depends on the names and their order, so it's
+//! the same on every run and platform.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::Error;
+
+use super::scene::Scene;
+
+/// The kinds of named things in a scene.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NameCategory {
+    /// Nodes, indexed in the order of `Node::descendants` of the root node.
+    Node,
+    /// Meshes, indexed as in `Scene::meshes`.
+    Mesh,
+    /// Materials, indexed as in `Scene::materials`.
+    Material,
+    /// Bones, by name: bones of different meshes with the same name are the same bone. They
+    /// are indexed in the order they first appear in, going through the bones of each mesh.
+    Bone,
+    /// Animations, indexed as in `Scene::animations`.
+    Animation,
+}
+
+/// The characters that an identifier can contain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AllowedChars {
+    /// ASCII letters and digits, and the characters in the string.
+    AsciiAlphanumeric(String),
+    /// Letters and digits in any script, see `char::is_alphanumeric`, and the characters in
+    /// the string.
+    Alphanumeric(String),
+    /// Only the characters in the string.
+    Only(String),
+}
+
+impl AllowedChars {
+    fn allows(&self, c: char) -> bool {
+        match self {
+            AllowedChars::AsciiAlphanumeric(extra) => {
+                c.is_ascii_alphanumeric() || extra.contains(c)
+            }
+            AllowedChars::Alphanumeric(extra) => c.is_alphanumeric() || extra.contains(c),
+            AllowedChars::Only(chars) => chars.contains(c),
+        }
+    }
+}
+
+/// How `sanitize` changes the case of names.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Case {
+    Preserve,
+    Lower,
+    Upper,
+}
+
+/// Options for `sanitize`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamingRules {
+    /// Default: `AllowedChars::AsciiAlphanumeric("_".to_owned())`
+    pub allowed: AllowedChars,
+    /// Replaces each run of characters that aren't allowed, or `None` to remove them.
+    /// Default: `Some('_')`
+    pub replacement: Option<char>,
+    /// The maximum length of an identifier in bytes, including the suffix of duplicates.
+    /// Names are shortened without splitting characters. Default: 64
+    pub max_len: usize,
+    /// Default: `Case::Preserve`
+    pub case: Case,
+    /// Treat identifiers that only differ in case as duplicates. Default: false
+    pub case_insensitive: bool,
+    /// Duplicates get this and a number appended, starting with 1 for the second use of a
+    /// name, so `_` gives `name`, `name_1`, `name_2`. Default: `_`
+    pub suffix_separator: String,
+    /// Make identifiers unique across all categories, rather than only within each category.
+    /// Default: false
+    pub global_namespace: bool,
+    /// The identifier for names that are empty after sanitizing. Default: `unnamed`
+    pub fallback: String,
+}
+
+impl Default for NamingRules {
+    fn default() -> Self {
+        NamingRules {
+            allowed: AllowedChars::AsciiAlphanumeric("_".to_owned()),
+            replacement: Some('_'),
+            max_len: 64,
+            case: Case::Preserve,
+            case_insensitive: false,
+            suffix_separator: "_".to_owned(),
+            global_namespace: false,
+            fallback: "unnamed".to_owned(),
+        }
+    }
+}
+
+/// A name and its identifier in a `NameMap`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NamedItem {
+    pub category: NameCategory,
+    /// The index of the item within its category, see `NameCategory`.
+    pub index: u32,
+    pub original: String,
+    pub sanitized: String,
+}
+
+/// The identifiers for the names of a scene, as returned by `sanitize`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NameMap {
+    /// Sorted by category, then index.
+    entries: Vec<NamedItem>,
+}
+
+impl NameMap {
+    /// Every name, sorted by category and then index.
+    pub fn entries(&self) -> &[NamedItem] {
+        &self.entries
+    }
+
+    /// The identifier of the `index`th item of `category`.
+    pub fn get(&self, category: NameCategory, index: u32) -> Option<&str> {
+        self.entries
+            .binary_search_by_key(&(category, index), |item| (item.category, item.index))
+            .ok()
+            .map(|i| &*self.entries[i].sanitized)
+    }
+
+    /// The identifier of the first item of `category` named `original`.
+    pub fn sanitized(&self, category: NameCategory, original: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|item| item.category == category && item.original == original)
+            .map(|item| &*item.sanitized)
+    }
+
+    /// The item of `category` with the identifier `sanitized`.
+    pub fn original(&self, category: NameCategory, sanitized: &str) -> Option<&NamedItem> {
+        self.entries
+            .iter()
+            .find(|item| item.category == category && item.sanitized == sanitized)
+    }
+}
+
+/// Map the names of every node, mesh, material, bone and animation in `scene` to identifiers
+/// that follow `rules`. See `sanitize_names` for how the identifiers are chosen, and when
+/// this fails.
+pub fn sanitize(scene: &Scene, rules: NamingRules) -> Result<NameMap, Error> {
+    let mut names = Vec::new();
+    if let Some(root) = scene.root_node() {
+        for node in root.descendants() {
+            names.push((NameCategory::Node, node.name_cstr().to_string_lossy()));
+        }
+    }
+    for mesh in scene.meshes() {
+        names.push((NameCategory::Mesh, mesh.name_cstr().to_string_lossy()));
+    }
+    for material in scene.materials() {
+        names.push((
+            NameCategory::Material,
+            material.name_cstr().to_string_lossy(),
+        ));
+    }
+    let mut bones = HashSet::new();
+    for mesh in scene.meshes() {
+        for bone in mesh.bones() {
+            let name = bone.name_cstr().to_string_lossy();
+            if bones.insert(name.clone()) {
+                names.push((NameCategory::Bone, name));
+            }
+        }
+    }
+    for animation in scene.animations() {
+        names.push((
+            NameCategory::Animation,
+            animation.name_cstr().to_string_lossy(),
+        ));
+    }
+
+    sanitize_names(
+        names.iter().map(|(category, name)| (*category, &**name)),
+        rules,
+    )
+}
+
+/// Map arbitrary names to identifiers that follow `rules`. Each item's index is its position
+/// among the items of the same category.
+///
+/// The case is changed first, then each run of characters that aren't allowed is replaced,
+/// and the result is shortened to `NamingRules::max_len`. When an identifier is already
+/// taken, the earlier name keeps it and the later one gets the lowest free numbered suffix.
+///
+/// Returns `Error::InvalidConfig` if a suffix doesn't fit in `NamingRules::max_len` on its
+/// own, since the identifier would then be too long.
+pub fn sanitize_names<'a, I>(names: I, rules: NamingRules) -> Result<NameMap, Error>
+where
+    I: IntoIterator<Item = (NameCategory, &'a str)>,
+{
+    let mut counts: HashMap<NameCategory, u32> = HashMap::new();
+    let mut taken = HashSet::new();
+    let mut next_suffix: HashMap<(Option<NameCategory>, String), u32> = HashMap::new();
+    let mut entries = Vec::new();
+
+    for (category, original) in names {
+        let index = counts.entry(category).or_insert(0);
+        let namespace = if rules.global_namespace {
+            None
+        } else {
+            Some(category)
+        };
+        let key = |name: &str| {
+            if rules.case_insensitive {
+                name.to_lowercase()
+            } else {
+                name.to_owned()
+            }
+        };
+
+        let base = clean(original, &rules);
+        let mut sanitized = truncate(&base, rules.max_len).to_owned();
+        if taken.contains(&(namespace, key(&sanitized))) {
+            let suffix = next_suffix.entry((namespace, key(&base))).or_insert(1);
+            loop {
+                let number = format!("{}{}", rules.suffix_separator, suffix);
+                *suffix += 1;
+                if number.len() > rules.max_len {
+                    return Err(Error::InvalidConfig {
+                        message: format!(
+                            "the suffix {:?} of a duplicate of {:?} is longer than max_len {}",
+                            number, base, rules.max_len
+                        ),
+                    });
+                }
+                let room = rules.max_len - number.len();
+                sanitized = format!("{}{}", truncate(&base, room), number);
+                if !taken.contains(&(namespace, key(&sanitized))) {
+                    break;
+                }
+            }
+        }
+        taken.insert((namespace, key(&sanitized)));
+
+        entries.push(NamedItem {
+            category,
+            index: *index,
+            original: original.to_owned(),
+            sanitized,
+        });
+        *index += 1;
+    }
+
+    entries.sort_by_key(|item| (item.category, item.index));
+    Ok(NameMap { entries })
+}
+
+/// `name` with the case changed and characters that aren't allowed replaced.
+fn clean(name: &str, rules: &NamingRules) -> String {
+    let name = match rules.case {
+        Case::Preserve => name.to_owned(),
+        Case::Lower => name.to_lowercase(),
+        Case::Upper => name.to_uppercase(),
+    };
+
+    let mut cleaned = String::with_capacity(name.len());
+    let mut replacing = false;
+    for c in name.chars() {
+        if rules.allowed.allows(c) {
+            cleaned.push(c);
+            replacing = false;
+        } else if !replacing {
+            cleaned.extend(rules.replacement);
+            replacing = true;
+        }
+    }
+
+    if cleaned.is_empty() {
+        rules.fallback.clone()
+    } else {
+        cleaned
+    }
+}
+
+/// The longest prefix of `s` that is at most `max_len` bytes and ends on a character boundary.
+fn truncate(s: &str, max_len: usize) -> &str {
+    if s.len() <= max_len {
+        return s;
+    }
+    let mut end = max_len;
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    &s[..end]
+}
@@ -1,4 +1,9 @@
-use std::{ffi::CStr, ptr::NonNull, slice::from_raw_parts};
+use std::{
+    ffi::{CStr, CString},
+    fmt,
+    ptr::NonNull,
+    str::Utf8Error,
+};
 
 use ffi::{aiMetadata, aiMetadataEntry, aiNode, aiString, aiVector3D};
 
@@ -13,10 +18,25 @@ define_type_and_iterator_indirect! {
 
 impl Node {
     /// Returns the name of the node.
+    ///
+    /// Panics if the node name isn't valid UTF-8, see `try_name`.
     pub fn name(&self) -> &str {
-        unsafe { crate::aistring_to_cstr(&self.mName) }
-            .to_str()
-            .unwrap()
+        self.try_name().expect("node name is not valid UTF-8")
+    }
+
+    /// Like `name`, but returns an error if the node name isn't valid UTF-8.
+    pub fn try_name(&self) -> Result<&str, Utf8Error> {
+        crate::aistring_to_str(&self.mName)
+    }
+
+    /// The name of the node as a `CStr`, without checking that it's valid UTF-8.
+    pub fn name_cstr(&self) -> &CStr {
+        crate::aistring_to_cstr(&self.mName)
+    }
+
+    /// Whether the node is named `name`, compared byte for byte.
+    pub fn eq_name(&self, name: &[u8]) -> bool {
+        self.name_cstr().to_bytes() == name
     }
 
     /// Returns the node's transformation matrix.
@@ -24,6 +44,34 @@ impl Node {
         Matrix4x4::from_raw(self.mTransformation)
     }
 
+    /// The transform from this node's space to the space of the root node's parent, i.e. world
+    /// space: the product of the transforms of all of this node's ancestors and its own.
+    pub fn world_transform(&self) -> Matrix4x4 {
+        let mut world = self.transform();
+        let mut parent = self.parent();
+        while let Some(node) = parent {
+            world = node.transform() * world;
+            parent = node.parent();
+        }
+        world
+    }
+
+    /// The first node named `name` among this node and its descendants, in the order of
+    /// `descendants`. Names are compared byte for byte, so this also works for names that
+    /// aren't valid UTF-8.
+    pub fn find(&self, name: &[u8]) -> Option<&Node> {
+        self.find_all(name).next()
+    }
+
+    /// Every node named `name` among this node and its descendants, see `find`. Assimp
+    /// doesn't require node names to be unique.
+    pub fn find_all<'a, 'b>(&'a self, name: &'b [u8]) -> impl Iterator<Item = &'a Node> + 'b
+    where
+        'a: 'b,
+    {
+        self.descendants().filter(move |node| node.eq_name(name))
+    }
+
     /// Return the parent of this node. Returns `None` if this node is the root node.
     pub fn parent(&self) -> Option<&Node> {
         unsafe { Some(Node::from_raw(NonNull::new(self.mParent)?)) }
@@ -36,10 +84,19 @@ impl Node {
 
     /// Returns a vector containing all of the child nodes under this node.
     pub fn children(&self) -> NodeIter {
-        NodeIter::new(
-            NonNull::new(self.mChildren as *mut *const aiNode),
-            self.mNumChildren as usize,
-        )
+        unsafe {
+            NodeIter::new(
+                NonNull::new(self.mChildren as *mut *const aiNode),
+                self.mNumChildren as usize,
+            )
+        }
+    }
+
+    /// Returns an iterator over this node and all of its descendants, depth first with parents
+    /// before their children. This doesn't recurse, so it's safe to use on very deep
+    /// hierarchies.
+    pub fn descendants(&self) -> Descendants<'_> {
+        Descendants { stack: vec![self] }
     }
 
     /// Returns the number of meshes under this node.
@@ -51,7 +108,7 @@ impl Node {
     /// the meshes contained in the `Scene` struct.
     pub fn meshes(&self) -> &[u32] {
         let len = self.mNumMeshes as usize;
-        unsafe { from_raw_parts(self.mMeshes, len) }
+        unsafe { crate::array_slice(self.mMeshes, len) }
     }
 
     /// Any custom metadata for this node - for example, the importer for HL1 `.mdl` files
@@ -61,6 +118,36 @@ impl Node {
     }
 }
 
+/// Prints the node's name, number of children, mesh indices and transform, but not its
+/// descendants.
+impl fmt::Debug for Node {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Node")
+            .field("name", &self.name_cstr().to_string_lossy())
+            .field("num_children", &self.num_children())
+            .field("meshes", &crate::BoundedList(self.meshes().iter()))
+            .field("transform", &self.transform())
+            .finish()
+    }
+}
+
+/// Iterator over a node and its descendants, see `Node::descendants`.
+pub struct Descendants<'a> {
+    stack: Vec<&'a Node>,
+}
+
+impl<'a> Iterator for Descendants<'a> {
+    type Item = &'a Node;
+
+    fn next(&mut self) -> Option<&'a Node> {
+        let node = self.stack.pop()?;
+        let len = self.stack.len();
+        self.stack.extend(node.children());
+        self.stack[len..].reverse();
+        Some(node)
+    }
+}
+
 /// Metadata for a specific node. If you want this as a `HashMap`, you can easily just
 /// do `let map: HashMap<_, _> = node.metadata().collect()`.
 pub struct Metadata<'a> {
@@ -76,11 +163,24 @@ impl std::ops::Deref for Metadata<'_> {
     }
 }
 
-impl Metadata<'_> {
-    /// Create a metadata iterator from a raw pointer.
-    pub unsafe fn from_raw(meta: *const aiMetadata) -> Self {
+impl<'a> Metadata<'a> {
+    /// Create a metadata iterator from a raw pointer. Most nodes don't have any metadata,
+    /// so a null pointer is treated as an empty set of metadata.
+    ///
+    /// # Safety
+    ///
+    /// A non-null `meta` must point to valid metadata which isn't modified or freed for the
+    /// lifetime `'a`, which the keys and values the iterator returns borrow from. Choose `'a`
+    /// explicitly, e.g. as the lifetime of a borrow of the `Scene` that owns the metadata.
+    pub unsafe fn from_raw(meta: *const aiMetadata) -> Metadata<'a> {
+        const EMPTY: aiMetadata = aiMetadata {
+            mNumProperties: 0,
+            mKeys: std::ptr::null_mut(),
+            mValues: std::ptr::null_mut(),
+        };
+
         Metadata {
-            meta: &*meta,
+            meta: meta.as_ref().unwrap_or(&EMPTY),
             index: 0,
         }
     }
@@ -97,22 +197,14 @@ impl<'a> Iterator for Metadata<'a> {
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.len() > 0 {
-            let key = unsafe {
-                crate::aistring_to_cstr(
-                    &*NonNull::new(
-                        NonNull::new(self.meta.mKeys)?
-                            .as_ptr()
-                            .offset(self.index as isize),
-                    )?
-                    .as_ptr(),
-                )
-            };
+            let key = crate::aistring_to_cstr(unsafe {
+                &*crate::array_element(self.meta.mKeys, self.index)?
+            });
             let value = unsafe {
-                MetadataEntry::from_raw(NonNull::new(
-                    NonNull::new(self.meta.mValues)?
-                        .as_ptr()
-                        .offset(self.index as isize),
-                )?)
+                MetadataEntry::from_raw(NonNull::new(crate::array_element(
+                    self.meta.mValues,
+                    self.index,
+                )?)?)
             };
 
             self.index += 1;
@@ -145,11 +237,54 @@ pub enum MetadataValue<'a> {
     Str(&'a CStr),
     /// A vector
     Vector3D(Vector3D),
+    /// A value of a type that this crate doesn't know how to read, or a value with no data.
+    /// The `u32` is the raw `aiMetadataType`.
+    Unknown(u32),
+}
+
+/// An owned copy of a `MetadataValue`, e.g. for `MetadataOverlay`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MetadataOwnedValue {
+    /// A boolean
+    Bool(bool),
+    /// A signed int
+    I32(i32),
+    /// An unsigned int
+    U64(u64),
+    /// A single-precision float
+    F32(f32),
+    /// A double-precision float
+    F64(f64),
+    /// A string
+    Str(CString),
+    /// A vector
+    Vector3D(Vector3D),
+    /// See `MetadataValue::Unknown`.
+    Unknown(u32),
+}
+
+impl From<MetadataValue<'_>> for MetadataOwnedValue {
+    fn from(value: MetadataValue<'_>) -> Self {
+        match value {
+            MetadataValue::Bool(value) => MetadataOwnedValue::Bool(value),
+            MetadataValue::I32(value) => MetadataOwnedValue::I32(value),
+            MetadataValue::U64(value) => MetadataOwnedValue::U64(value),
+            MetadataValue::F32(value) => MetadataOwnedValue::F32(value),
+            MetadataValue::F64(value) => MetadataOwnedValue::F64(value),
+            MetadataValue::Str(value) => MetadataOwnedValue::Str(value.to_owned()),
+            MetadataValue::Vector3D(value) => MetadataOwnedValue::Vector3D(value),
+            MetadataValue::Unknown(type_) => MetadataOwnedValue::Unknown(type_),
+        }
+    }
 }
 
 impl MetadataEntry {
     /// Get the value of this entry
     pub fn get(&self) -> MetadataValue<'_> {
+        if self.mData.is_null() {
+            return MetadataValue::Unknown(self.mType as u32);
+        }
+
         unsafe {
             match self.mType {
                 ffi::aiMetadataType_AI_BOOL => MetadataValue::Bool(*(self.mData as *const bool)),
@@ -163,7 +298,7 @@ impl MetadataEntry {
                 ffi::aiMetadataType_AI_AIVECTOR3D => {
                     MetadataValue::Vector3D(Vector3D::from_raw(*(self.mData as *const aiVector3D)))
                 }
-                _ => unreachable!(),
+                other => MetadataValue::Unknown(other as u32),
             }
         }
     }
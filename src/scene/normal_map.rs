@@ -0,0 +1,92 @@
+//! Converting height maps to normal maps, see `height_to_normal`.
+
+use image::{DynamicImage, GrayImage, Rgba, RgbaImage};
+
+use crate::math::Vector3D;
+
+use super::material::{TextureDefinition, WrappingMode};
+use super::profile::ResolvedMaterial;
+
+/// Convert a height map to a tangent space normal map, with the gradient of `height` from a
+/// Sobel filter. Heights go from 0 for black to 1 for white, and `strength` scales the
+/// gradient, in heights per pixel, so 0 gives a flat map. `wrap` says what's past the edges:
+/// the other side of the image for `WrappingMode::Repeat`, its mirror image for
+/// `MirrorRepeat`, and the edge pixels themselves otherwise.
+///
+/// Each normal is encoded as `(n + 1) / 2` in the color channels, so a flat pixel is
+/// `(128, 128, 255)`, and green points up the image, as in OpenGL. Alpha is 255.
+pub fn height_to_normal(height: &GrayImage, strength: f32, wrap: WrappingMode) -> RgbaImage {
+    let (width, rows) = height.dimensions();
+    let sample = |x: i64, y: i64| {
+        let x = wrap_coord(x, width, wrap);
+        let y = wrap_coord(y, rows, wrap);
+        height.get_pixel(x, y).0[0] as f32 / 255.0
+    };
+
+    RgbaImage::from_fn(width, rows, |x, y| {
+        let (x, y) = (i64::from(x), i64::from(y));
+        let h = |dx, dy| sample(x + dx, y + dy);
+        // The kernels weigh 8 pixels in total, so this is the change in height per pixel.
+        let dx = (h(1, -1) + 2.0 * h(1, 0) + h(1, 1) - h(-1, -1) - 2.0 * h(-1, 0) - h(-1, 1)) / 8.0;
+        let dy = (h(-1, 1) + 2.0 * h(0, 1) + h(1, 1) - h(-1, -1) - 2.0 * h(0, -1) - h(1, -1)) / 8.0;
+        // Rows go down the image, and the normal's y axis up.
+        let normal = Vector3D::new(-strength * dx, strength * dy, 1.0).normalize();
+        let encode = |value: f32| ((value * 0.5 + 0.5) * 255.0).round() as u8;
+        Rgba([encode(normal.x), encode(normal.y), encode(normal.z), 255])
+    })
+}
+
+/// The pixel that coordinate `coord` samples in an image `len` pixels across.
+fn wrap_coord(coord: i64, len: u32, wrap: WrappingMode) -> u32 {
+    let len = i64::from(len);
+    let coord = match wrap {
+        WrappingMode::Repeat => coord.rem_euclid(len),
+        WrappingMode::MirrorRepeat => {
+            let coord = coord.rem_euclid(2 * len);
+            if coord < len {
+                coord
+            } else {
+                2 * len - 1 - coord
+            }
+        }
+        WrappingMode::Clamp | WrappingMode::Decal => coord.clamp(0, len - 1),
+    };
+    coord as u32
+}
+
+/// The image of the normal slot of a `ResolvedMaterial`, see `ResolvedMaterial::normal_map`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NormalMapImage {
+    pub image: RgbaImage,
+    /// Whether the texture was a height map that was converted with `height_to_normal`.
+    pub converted_from_height: bool,
+}
+
+impl ResolvedMaterial {
+    /// The normal map of the material, with its image loaded by `resolver` as in
+    /// `MaterialComponent::flatten`. If the rule that filled the slot says the texture is a
+    /// height map, see `ProfileRule::height_map`, it's converted with `height_to_normal`,
+    /// wrapping like the texture does in u, or repeating if that isn't known.
+    ///
+    /// `None` if the normal slot is empty or `resolver` doesn't return an image.
+    pub fn normal_map<F>(&self, resolver: F) -> Option<NormalMapImage>
+    where
+        F: Fn(&TextureDefinition) -> Option<DynamicImage>,
+    {
+        let normal = self.normal.as_ref()?;
+        let image = resolver(&normal.texture)?;
+        Some(match normal.height_map {
+            Some(strength) => {
+                let wrap = normal.texture.wrap_u.unwrap_or(WrappingMode::Repeat);
+                NormalMapImage {
+                    image: height_to_normal(&image.to_luma8(), strength, wrap),
+                    converted_from_height: true,
+                }
+            }
+            None => NormalMapImage {
+                image: image.to_rgba8(),
+                converted_from_height: false,
+            },
+        })
+    }
+}
@@ -0,0 +1,262 @@
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::ffi::CString;
+
+use ffi::aiNode;
+
+use super::material::*;
+use super::node::{MetadataOwnedValue, Node};
+use super::scene::Scene;
+
+/// A set of overrides for the materials of a scene. Assimp's materials are read-only, so this
+/// lets you patch individual properties after import without going through an exporter.
+///
+/// An overlay with no overrides is a pass-through view of the scene's materials. Overrides are
+/// keyed by material index and `MaterialKey`, and are consulted before the original material
+/// whenever a property is read through an `OverlaidMaterial`.
+///
+/// Texture properties can only be overridden for textures that already exist in the original
+/// material - overriding e.g. `MaterialKey::Texture(Diffuse, 3)` on a material with a single
+/// diffuse texture has no effect.
+pub struct MaterialOverlay<'a> {
+    scene: &'a Scene<'a>,
+    overrides: HashMap<(u32, MaterialKey), MaterialValue>,
+}
+
+impl<'a> MaterialOverlay<'a> {
+    /// Create a pass-through overlay for the materials of `scene`.
+    pub fn new(scene: &'a Scene<'a>) -> Self {
+        MaterialOverlay {
+            scene,
+            overrides: HashMap::new(),
+        }
+    }
+
+    /// Override a single property of the material with index `material_id`. Returns the previous
+    /// override for this property, if any.
+    pub fn set(
+        &mut self,
+        material_id: u32,
+        key: MaterialKey,
+        value: MaterialValue,
+    ) -> Option<MaterialValue> {
+        self.overrides.insert((material_id, key), value)
+    }
+
+    /// Remove an override, so that the property is read from the original material again.
+    pub fn unset(&mut self, material_id: u32, key: MaterialKey) -> Option<MaterialValue> {
+        self.overrides.remove(&(material_id, key))
+    }
+
+    /// The number of overrides across all materials.
+    pub fn num_overrides(&self) -> usize {
+        self.overrides.len()
+    }
+
+    /// Get the nth material of the scene, with any overrides applied.
+    pub fn material(&self, id: u32) -> Option<OverlaidMaterial<'_>> {
+        Some(OverlaidMaterial {
+            material: self.scene.material(id)?,
+            material_id: id,
+            overrides: &self.overrides,
+        })
+    }
+
+    /// Returns an iterator over all the materials of the scene, with any overrides applied.
+    pub fn materials(&self) -> impl ExactSizeIterator<Item = OverlaidMaterial<'_>> + '_ {
+        self.scene
+            .materials()
+            .enumerate()
+            .map(move |(id, material)| OverlaidMaterial {
+                material,
+                material_id: id as u32,
+                overrides: &self.overrides,
+            })
+    }
+}
+
+/// A single material viewed through a `MaterialOverlay`.
+pub struct OverlaidMaterial<'a> {
+    material: &'a Material,
+    material_id: u32,
+    overrides: &'a HashMap<(u32, MaterialKey), MaterialValue>,
+}
+
+impl OverlaidMaterial<'_> {
+    /// The original material, without any overrides applied.
+    pub fn original(&self) -> &Material {
+        self.material
+    }
+
+    fn get_override(&self, key: MaterialKey) -> Option<&MaterialValue> {
+        self.overrides.get(&(self.material_id, key))
+    }
+
+    fn override_as<T>(&self, key: MaterialKey) -> Option<T>
+    where
+        MaterialValue: TryInto<T>,
+    {
+        self.get_override(key)?.clone().try_into().ok()
+    }
+}
+
+impl MaterialSource for OverlaidMaterial<'_> {
+    fn get_value(&self, key: MaterialKey) -> Option<MaterialValue> {
+        match self.get_override(key) {
+            Some(value) => Some(value.clone()),
+            None => self.material.get_value(key),
+        }
+    }
+
+    fn num_textures(&self, type_: MaterialComponentType) -> u32 {
+        self.material.num_textures(type_)
+    }
+
+    fn texture(&self, type_: MaterialComponentType, index: u32) -> Option<TextureDefinition> {
+        let mut texture = self.material.texture(type_, index)?;
+
+        if let Some(path) = self.override_as(MaterialKey::Texture(type_, index)) {
+            texture.path = path;
+        }
+        if let Some(strength) = self.override_as(MaterialKey::TextureBlend(type_, index)) {
+            texture.strength = strength;
+        }
+        if let Some(blend_op) = self.override_as(MaterialKey::TextureOp(type_, index)) {
+            texture.blend_op = blend_op;
+        }
+        if let Some(mapping) = self.override_as(MaterialKey::Mapping(type_, index)) {
+            texture.mapping = Some(mapping);
+        }
+        if let Some(channel) = self.override_as(MaterialKey::UVWSource(type_, index)) {
+            texture.channel = channel;
+            texture.channel_source = ChannelSource::Explicit;
+        }
+        if let Some(wrap_u) = self.override_as(MaterialKey::MappingModeU(type_, index)) {
+            texture.wrap_u = Some(wrap_u);
+        }
+        if let Some(wrap_v) = self.override_as(MaterialKey::MappingModeV(type_, index)) {
+            texture.wrap_v = Some(wrap_v);
+        }
+        if let Some(wrap_w) = self.override_as(MaterialKey::MappingModeW(type_, index)) {
+            texture.wrap_w = Some(wrap_w);
+        }
+        if let Some(axis) = self.override_as(MaterialKey::TextureMapAxis(type_, index)) {
+            texture.axis = Some(axis);
+        }
+        if let Some(flags) = self.override_as(MaterialKey::Flags(type_, index)) {
+            texture.flags = flags;
+        }
+
+        Some(texture)
+    }
+}
+
+/// A set of changes to the metadata of a scene's nodes, the metadata equivalent of
+/// `MaterialOverlay`. Entries can be added, replaced or removed, and `effective_metadata`
+/// merges them with the node's original metadata, e.g. for your own export code. The scene
+/// itself, and so `Node::metadata`, is never modified.
+///
+/// Nodes are identified by reference, so the nodes passed to an overlay must come from the
+/// scene it was created for.
+pub struct MetadataOverlay<'a> {
+    scene: &'a Scene<'a>,
+    /// `None` masks an entry of the original metadata.
+    overrides: HashMap<(*const aiNode, CString), Option<MetadataOwnedValue>>,
+}
+
+impl<'a> MetadataOverlay<'a> {
+    /// Create a pass-through overlay for the node metadata of `scene`.
+    pub fn new(scene: &'a Scene<'a>) -> Self {
+        MetadataOverlay {
+            scene,
+            overrides: HashMap::new(),
+        }
+    }
+
+    /// The scene this overlay was created for.
+    pub fn scene(&self) -> &'a Scene<'a> {
+        self.scene
+    }
+
+    /// Add or replace the entry `key` in the metadata of `node`. Replaces any previous change to
+    /// this entry, including a `remove`.
+    ///
+    /// Panics if `key` contains a nul byte.
+    pub fn set(&mut self, node: &Node, key: &str, value: MetadataOwnedValue) {
+        self.overrides
+            .insert(Self::override_key(node, key), Some(value));
+    }
+
+    /// Hide the entry `key` in the metadata of `node`, whether it's an original entry or one
+    /// added with `set`.
+    ///
+    /// Panics if `key` contains a nul byte.
+    pub fn remove(&mut self, node: &Node, key: &str) {
+        self.overrides.insert(Self::override_key(node, key), None);
+    }
+
+    /// Undo any `set` or `remove` of the entry `key` on `node`, so that the original entry (if
+    /// any) is visible again.
+    ///
+    /// Panics if `key` contains a nul byte.
+    pub fn unset(&mut self, node: &Node, key: &str) {
+        self.overrides.remove(&Self::override_key(node, key));
+    }
+
+    /// The number of added, replaced and removed entries across all nodes.
+    pub fn num_overrides(&self) -> usize {
+        self.overrides.len()
+    }
+
+    /// The value of the entry `key` in the metadata of `node`, with any changes applied.
+    pub fn get(&self, node: &Node, key: &str) -> Option<MetadataOwnedValue> {
+        let key = CString::new(key).ok()?;
+
+        if let Some(value) = self.overrides.get(&(&**node as *const aiNode, key.clone())) {
+            return value.clone();
+        }
+
+        node.metadata()
+            .find(|(name, _)| *name == key.as_c_str())
+            .map(|(_, entry)| entry.get().into())
+    }
+
+    /// The metadata of `node` with all changes applied. This has the original entries in their
+    /// original order, followed by any added entries sorted by key.
+    pub fn effective_metadata(&self, node: &Node) -> Vec<(CString, MetadataOwnedValue)> {
+        let ptr = &**node as *const aiNode;
+        let mut out = Vec::new();
+
+        for (key, entry) in node.metadata() {
+            let key = key.to_owned();
+
+            match self.overrides.get(&(ptr, key.clone())) {
+                Some(Some(value)) => out.push((key, value.clone())),
+                Some(None) => {}
+                None => out.push((key, entry.get().into())),
+            }
+        }
+
+        let mut added: Vec<_> = self
+            .overrides
+            .iter()
+            .filter(|((node_ptr, key), _)| {
+                *node_ptr == ptr
+                    && !node
+                        .metadata()
+                        .any(|(original, _)| original == key.as_c_str())
+            })
+            .filter_map(|((_, key), value)| Some((key.clone(), value.clone()?)))
+            .collect();
+        added.sort_by(|(a, _), (b, _)| a.cmp(b));
+        out.extend(added);
+
+        out
+    }
+
+    fn override_key(node: &Node, key: &str) -> (*const aiNode, CString) {
+        let key = CString::new(key).expect("metadata key contains a nul byte");
+
+        (&**node as *const aiNode, key)
+    }
+}
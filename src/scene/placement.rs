@@ -0,0 +1,93 @@
+use ffi::aiString;
+
+use crate::math::Matrix4x4;
+
+use super::camera::Camera;
+use super::light::Light;
+use super::node::Node;
+use super::scene::Scene;
+
+/// A camera or light together with the node it's attached to, as returned by
+/// `Scene::placed_cameras` and `Scene::placed_lights`.
+#[derive(Debug, Clone)]
+pub struct Placed<'a, T> {
+    pub item: &'a T,
+    pub node: &'a Node,
+    /// The world transform of `node`, see `Node::world_transform`. The position and
+    /// directions of `item` are relative to this.
+    pub world: Matrix4x4,
+}
+
+impl Scene<'_> {
+    /// The first node named `name` in the scene, see `Node::find`.
+    pub fn find_node(&self, name: &[u8]) -> Option<&Node> {
+        self.root_node()?.find(name)
+    }
+
+    /// The node that `camera` is attached to, which is the node with the same name, and that
+    /// node's world transform. Returns `None` if there's no such node. If more than one node
+    /// has the camera's name, the first in the order of `Node::descendants` is used; see
+    /// `resolve_camera_all` for all of them.
+    pub fn resolve_camera(&self, camera: &Camera) -> Option<(&Node, Matrix4x4)> {
+        self.resolve_all(&camera.mName).into_iter().next()
+    }
+
+    /// Every node with the same name as `camera`, each with its world transform, see
+    /// `resolve_camera`.
+    pub fn resolve_camera_all(&self, camera: &Camera) -> Vec<(&Node, Matrix4x4)> {
+        self.resolve_all(&camera.mName)
+    }
+
+    /// The node that `light` is attached to and its world transform, see `resolve_camera`.
+    pub fn resolve_light(&self, light: &Light) -> Option<(&Node, Matrix4x4)> {
+        self.resolve_all(&light.mName).into_iter().next()
+    }
+
+    /// Every node with the same name as `light`, each with its world transform, see
+    /// `resolve_camera`.
+    pub fn resolve_light_all(&self, light: &Light) -> Vec<(&Node, Matrix4x4)> {
+        self.resolve_all(&light.mName)
+    }
+
+    /// Every camera in the scene that has a node, with that node, see `resolve_camera`.
+    /// Cameras without a node are left out.
+    pub fn placed_cameras(&self) -> Vec<Placed<'_, Camera>> {
+        self.camera()
+            .filter_map(|camera| {
+                let (node, world) = self.resolve_camera(camera)?;
+                Some(Placed {
+                    item: camera,
+                    node,
+                    world,
+                })
+            })
+            .collect()
+    }
+
+    /// Every light in the scene that has a node, with that node, see `resolve_light`. Lights
+    /// without a node are left out.
+    pub fn placed_lights(&self) -> Vec<Placed<'_, Light>> {
+        self.light()
+            .filter_map(|light| {
+                let (node, world) = self.resolve_light(light)?;
+                Some(Placed {
+                    item: light,
+                    node,
+                    world,
+                })
+            })
+            .collect()
+    }
+
+    fn resolve_all(&self, name: &aiString) -> Vec<(&Node, Matrix4x4)> {
+        let name = crate::aistring_to_cstr(name).to_bytes();
+
+        match self.root_node() {
+            Some(root) => root
+                .find_all(name)
+                .map(|node| (node, node.world_transform()))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,373 @@
+//! Canonical texture slots for the materials of a scene.
+//!
+//! Formats, and Assimp's importers for them, don't agree on where a texture goes: an OBJ
+//! normal map is usually a `bump` map and ends up in `MaterialComponentType::Height`, FBX
+//! puts it in `Normals`, and glTF's occlusion texture is a `Lightmap`. An `ImportProfile` is
+//! a list of rules saying which texture fills each `CanonicalSlot`, and
+//! `Scene::apply_import_profile` resolves every material with it. Profiles can be loaded with
+//! serde, so they can be tweaked without rebuilding.
+
+use std::ffi::CStr;
+
+use crate::math::{Color3D, Color4D};
+
+use super::material::{
+    Material, MaterialComponentType, MaterialKey, MaterialValue, TextureDefinition,
+};
+use super::scene::Scene;
+
+/// The texture slots of an engine material.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CanonicalSlot {
+    Albedo,
+    Normal,
+    Metallic,
+    Roughness,
+    Occlusion,
+    Emissive,
+    Opacity,
+}
+
+impl CanonicalSlot {
+    /// Every slot, in the order of the fields of `ResolvedMaterial`.
+    pub const ALL: [CanonicalSlot; 7] = [
+        CanonicalSlot::Albedo,
+        CanonicalSlot::Normal,
+        CanonicalSlot::Metallic,
+        CanonicalSlot::Roughness,
+        CanonicalSlot::Occlusion,
+        CanonicalSlot::Emissive,
+        CanonicalSlot::Opacity,
+    ];
+}
+
+/// A color channel of a texture.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ColorChannel {
+    R,
+    G,
+    B,
+    A,
+}
+
+/// A test of the path of the texture that a `ProfileRule` would use. Comparisons ignore
+/// ASCII case.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TextureCondition {
+    /// Every texture.
+    Any,
+    /// The path contains the string.
+    PathContains(String),
+    /// The file name has the extension, with or without the leading `.`.
+    Extension(String),
+    /// At least one of the conditions holds.
+    AnyOf(Vec<TextureCondition>),
+    /// All of the conditions hold.
+    AllOf(Vec<TextureCondition>),
+}
+
+impl Default for TextureCondition {
+    fn default() -> Self {
+        TextureCondition::Any
+    }
+}
+
+impl TextureCondition {
+    /// Whether a texture with this path meets the condition.
+    pub fn matches(&self, path: &str) -> bool {
+        match self {
+            TextureCondition::Any => true,
+            TextureCondition::PathContains(needle) => path
+                .to_ascii_lowercase()
+                .contains(&needle.to_ascii_lowercase()),
+            TextureCondition::Extension(extension) => {
+                let name = path
+                    .rsplit(|c| c == '/' || c == '\\')
+                    .next()
+                    .unwrap_or(path);
+                match name.rfind('.') {
+                    Some(dot) => {
+                        name[dot + 1..].eq_ignore_ascii_case(extension.trim_start_matches('.'))
+                    }
+                    None => false,
+                }
+            }
+            TextureCondition::AnyOf(conditions) => {
+                conditions.iter().any(|condition| condition.matches(path))
+            }
+            TextureCondition::AllOf(conditions) => {
+                conditions.iter().all(|condition| condition.matches(path))
+            }
+        }
+    }
+}
+
+/// Fill `slot` with the `index`th texture of the `source` stack, if the material has it and
+/// its path meets `condition`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProfileRule {
+    pub slot: CanonicalSlot,
+    pub source: MaterialComponentType,
+    /// Default: 0
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub index: u32,
+    /// Default: `TextureCondition::Any`
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub condition: TextureCondition,
+    /// The channel that holds the value, for textures that pack several values, such as
+    /// glTF's metallic-roughness texture. Default: `None`
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub color_channel: Option<ColorChannel>,
+    /// Treat the texture as a height map, to be converted to a normal map with this strength,
+    /// see `ResolvedMaterial::normal_map`. Default: `None`
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub height_map: Option<f32>,
+}
+
+impl ProfileRule {
+    /// A rule that takes the first texture of `source` as it is.
+    pub fn new(slot: CanonicalSlot, source: MaterialComponentType) -> Self {
+        ProfileRule {
+            slot,
+            source,
+            index: 0,
+            condition: TextureCondition::Any,
+            color_channel: None,
+            height_map: None,
+        }
+    }
+
+    pub fn with_condition(self, condition: TextureCondition) -> Self {
+        ProfileRule { condition, ..self }
+    }
+
+    pub fn with_color_channel(self, color_channel: ColorChannel) -> Self {
+        ProfileRule {
+            color_channel: Some(color_channel),
+            ..self
+        }
+    }
+
+    pub fn with_height_map(self, strength: f32) -> Self {
+        ProfileRule {
+            height_map: Some(strength),
+            ..self
+        }
+    }
+}
+
+/// Rules for `Scene::apply_import_profile`. For each slot, the rules for it are tried in
+/// order and the first one that finds a texture fills it, so a rule only applies if the
+/// slot is still empty.
+///
+/// The default profile follows the conventions of Assimp's OBJ, FBX and glTF importers. It
+/// prefers the PBR texture types, treats a `Height` texture as the normal map if its path
+/// contains "normal" or it's a PNG, reads glTF's metallic-roughness texture from `Unknown`
+/// and its occlusion texture from `Lightmap`. Metalness, roughness and occlusion are read
+/// from the blue, green and red channels, as glTF packs them.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ImportProfile {
+    pub rules: Vec<ProfileRule>,
+}
+
+impl ImportProfile {
+    /// A profile without rules, which leaves every slot empty.
+    pub fn empty() -> Self {
+        ImportProfile { rules: Vec::new() }
+    }
+
+    /// The rules for `slot`, in the order they're tried.
+    pub fn rules_for(&self, slot: CanonicalSlot) -> impl Iterator<Item = &ProfileRule> + '_ {
+        self.rules.iter().filter(move |rule| rule.slot == slot)
+    }
+}
+
+impl Default for ImportProfile {
+    fn default() -> Self {
+        use self::CanonicalSlot as S;
+        use self::MaterialComponentType as T;
+
+        let normal_map = TextureCondition::AnyOf(vec![
+            TextureCondition::PathContains("normal".to_owned()),
+            TextureCondition::Extension("png".to_owned()),
+        ]);
+
+        ImportProfile {
+            rules: vec![
+                ProfileRule::new(S::Albedo, T::BaseColor),
+                ProfileRule::new(S::Albedo, T::Diffuse),
+                ProfileRule::new(S::Normal, T::Normals),
+                ProfileRule::new(S::Normal, T::NormalCamera),
+                ProfileRule::new(S::Normal, T::Height).with_condition(normal_map),
+                ProfileRule::new(S::Metallic, T::Metalness).with_color_channel(ColorChannel::B),
+                // The glTF importer stores the metallic-roughness texture as `Unknown`.
+                ProfileRule::new(S::Metallic, T::Unknown).with_color_channel(ColorChannel::B),
+                ProfileRule::new(S::Roughness, T::DiffuseRoughness)
+                    .with_color_channel(ColorChannel::G),
+                ProfileRule::new(S::Roughness, T::Unknown).with_color_channel(ColorChannel::G),
+                ProfileRule::new(S::Occlusion, T::AmbientOcclusion)
+                    .with_color_channel(ColorChannel::R),
+                ProfileRule::new(S::Occlusion, T::Lightmap).with_color_channel(ColorChannel::R),
+                ProfileRule::new(S::Emissive, T::EmissionColor),
+                ProfileRule::new(S::Emissive, T::Emissive),
+                ProfileRule::new(S::Opacity, T::Opacity),
+            ],
+        }
+    }
+}
+
+/// The texture that fills a slot of a `ResolvedMaterial`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedTextureRef {
+    /// The stack the texture came from, and its index in it.
+    pub source: MaterialComponentType,
+    pub index: u32,
+    /// `texture.path`, converted lossily if it isn't valid UTF-8.
+    pub path: String,
+    /// See `ProfileRule::color_channel`.
+    pub color_channel: Option<ColorChannel>,
+    /// See `ProfileRule::height_map`.
+    pub height_map: Option<f32>,
+    pub texture: TextureDefinition,
+}
+
+/// A material of the scene with its textures in canonical slots, as returned by
+/// `Scene::apply_import_profile`. The scalars are what the material says for the slots,
+/// to use where a slot has no texture, and are `None` if it doesn't have the property.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedMaterial {
+    /// The index of the material in `Scene::materials`.
+    pub material_index: u32,
+    pub name: String,
+    pub albedo: Option<ResolvedTextureRef>,
+    pub normal: Option<ResolvedTextureRef>,
+    pub metallic: Option<ResolvedTextureRef>,
+    pub roughness: Option<ResolvedTextureRef>,
+    pub occlusion: Option<ResolvedTextureRef>,
+    pub emissive: Option<ResolvedTextureRef>,
+    pub opacity: Option<ResolvedTextureRef>,
+    /// The PBR base color, or the diffuse color of other materials.
+    pub base_color: Option<Color4D>,
+    pub metallic_factor: Option<f32>,
+    pub roughness_factor: Option<f32>,
+    pub emissive_color: Option<Color3D>,
+    pub opacity_factor: Option<f32>,
+}
+
+impl ResolvedMaterial {
+    pub fn slot(&self, slot: CanonicalSlot) -> Option<&ResolvedTextureRef> {
+        match slot {
+            CanonicalSlot::Albedo => self.albedo.as_ref(),
+            CanonicalSlot::Normal => self.normal.as_ref(),
+            CanonicalSlot::Metallic => self.metallic.as_ref(),
+            CanonicalSlot::Roughness => self.roughness.as_ref(),
+            CanonicalSlot::Occlusion => self.occlusion.as_ref(),
+            CanonicalSlot::Emissive => self.emissive.as_ref(),
+            CanonicalSlot::Opacity => self.opacity.as_ref(),
+        }
+    }
+
+    fn slot_mut(&mut self, slot: CanonicalSlot) -> &mut Option<ResolvedTextureRef> {
+        match slot {
+            CanonicalSlot::Albedo => &mut self.albedo,
+            CanonicalSlot::Normal => &mut self.normal,
+            CanonicalSlot::Metallic => &mut self.metallic,
+            CanonicalSlot::Roughness => &mut self.roughness,
+            CanonicalSlot::Occlusion => &mut self.occlusion,
+            CanonicalSlot::Emissive => &mut self.emissive,
+            CanonicalSlot::Opacity => &mut self.opacity,
+        }
+    }
+}
+
+impl Scene {
+    /// Resolve the textures of every material into canonical slots with `profile`, in the
+    /// order of `Scene::materials`.
+    pub fn apply_import_profile(&self, profile: &ImportProfile) -> Vec<ResolvedMaterial> {
+        self.materials()
+            .enumerate()
+            .map(|(index, material)| resolve(index as u32, material, profile))
+            .collect()
+    }
+}
+
+fn resolve(material_index: u32, material: &Material, profile: &ImportProfile) -> ResolvedMaterial {
+    let mut resolved = ResolvedMaterial {
+        material_index,
+        name: material.name_cstr().to_string_lossy().into_owned(),
+        albedo: None,
+        normal: None,
+        metallic: None,
+        roughness: None,
+        occlusion: None,
+        emissive: None,
+        opacity: None,
+        base_color: raw_color(material, b"$clr.base\0")
+            .or_else(|| {
+                raw_color(
+                    material,
+                    b"$mat.gltf.pbrMetallicRoughness.baseColorFactor\0",
+                )
+            })
+            .or_else(|| color(material, MaterialComponentType::Diffuse)),
+        metallic_factor: raw_float(material, b"$mat.metallicFactor\0")
+            .or_else(|| raw_float(material, b"$mat.gltf.pbrMetallicRoughness.metallicFactor\0")),
+        roughness_factor: raw_float(material, b"$mat.roughnessFactor\0").or_else(|| {
+            raw_float(
+                material,
+                b"$mat.gltf.pbrMetallicRoughness.roughnessFactor\0",
+            )
+        }),
+        emissive_color: color(material, MaterialComponentType::Emissive).map(Color3D::from),
+        opacity_factor: match material.get_value(MaterialKey::Opacity) {
+            Some(MaterialValue::Float(opacity)) => Some(opacity),
+            _ => None,
+        },
+    };
+
+    for rule in &profile.rules {
+        let slot = resolved.slot_mut(rule.slot);
+        if slot.is_some() {
+            continue;
+        }
+        let texture = match material.texture(rule.source, rule.index) {
+            Some(texture) => texture,
+            None => continue,
+        };
+        let path = crate::aistring_to_cstr(&texture.path.0)
+            .to_string_lossy()
+            .into_owned();
+        if rule.condition.matches(&path) {
+            *slot = Some(ResolvedTextureRef {
+                source: rule.source,
+                index: rule.index,
+                path,
+                color_channel: rule.color_channel,
+                height_map: rule.height_map,
+                texture,
+            });
+        }
+    }
+
+    resolved
+}
+
+fn raw_float(material: &Material, key: &[u8]) -> Option<f32> {
+    material.raw_float(CStr::from_bytes_with_nul(key).unwrap())
+}
+
+fn raw_color(material: &Material, key: &[u8]) -> Option<Color4D> {
+    material.raw_color(CStr::from_bytes_with_nul(key).unwrap())
+}
+
+fn color(material: &Material, type_: MaterialComponentType) -> Option<Color4D> {
+    match material.get_value(MaterialKey::Color(type_)) {
+        Some(MaterialValue::Color4D(color)) => Some(color),
+        _ => None,
+    }
+}
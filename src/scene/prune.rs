@@ -0,0 +1,108 @@
+//! Dropping data that an application doesn't need from a `SceneSnapshot`, to save memory.
+//!
+//! `Importer::remove_component` can leave out whole categories of data at import time. `prune`
+//! is finer grained, e.g. keeping the metadata of embedded textures while dropping their
+//! contents, and works after the import, so the decision can depend on what the scene
+//! contains.
+
+use std::mem;
+
+use super::snapshot::{SceneSnapshot, TextureTarget};
+
+/// What `prune` drops. The default keeps everything.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PruneFilter {
+    /// Drop the data of embedded textures, keeping their filenames, format hints and sizes.
+    /// Material textures that refer to them become `TextureTarget::Missing`. Default: false
+    pub texture_data: bool,
+    /// Keep only the animations with one of these names. Default: `None`, which keeps every
+    /// animation
+    pub keep_animations: Option<Vec<String>>,
+    /// Drop the UV channels of each mesh from this index on. Default: `None`, which keeps every
+    /// channel
+    pub max_uv_channels: Option<usize>,
+    /// Drop the vertex colors of every mesh. Default: false
+    pub colors: bool,
+    /// Drop the tangents and bitangents of every mesh. Default: false
+    pub tangents: bool,
+}
+
+/// The number of bytes of data that `prune` dropped in each category. These count the arrays of
+/// vertex attributes, keys and texture data, not the smaller fixed overhead of each item.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct PruneReport {
+    pub texture_data: u64,
+    /// The keys of the dropped animations.
+    pub animations: u64,
+    pub uv_channels: u64,
+    pub colors: u64,
+    /// Tangents and bitangents.
+    pub tangents: u64,
+}
+
+impl PruneReport {
+    /// The bytes dropped across all categories.
+    pub fn total(&self) -> u64 {
+        self.texture_data + self.animations + self.uv_channels + self.colors + self.tangents
+    }
+}
+
+/// Drop the data that `filter` selects from `snapshot`, and update the references to it.
+pub fn prune(snapshot: &mut SceneSnapshot, filter: PruneFilter) -> PruneReport {
+    let mut report = PruneReport::default();
+
+    if filter.texture_data {
+        for texture in &mut snapshot.textures {
+            if let Some(data) = texture.data.take() {
+                report.texture_data += data.len() as u64;
+            }
+        }
+        for material in &mut snapshot.materials {
+            for texture in &mut material.textures {
+                if let TextureTarget::Embedded(index) = texture.target {
+                    texture.target = TextureTarget::Missing(index);
+                }
+            }
+        }
+    }
+
+    if let Some(names) = &filter.keep_animations {
+        snapshot.animations.retain(|animation| {
+            let keep = names.contains(&animation.name);
+            if !keep {
+                for channel in &animation.channels {
+                    report.animations += (mem::size_of_val(&channel.position_keys[..])
+                        + mem::size_of_val(&channel.rotation_keys[..])
+                        + mem::size_of_val(&channel.scaling_keys[..]))
+                        as u64;
+                }
+            }
+            keep
+        });
+    }
+
+    for mesh in &mut snapshot.meshes {
+        if let Some(max) = filter.max_uv_channels {
+            for channel in mesh.texture_coords.iter_mut().skip(max) {
+                if let Some(uvs) = channel.take() {
+                    report.uv_channels += mem::size_of_val(&uvs[..]) as u64;
+                }
+            }
+        }
+        if filter.colors {
+            for set in &mut mesh.colors {
+                if let Some(colors) = set.take() {
+                    report.colors += mem::size_of_val(&colors[..]) as u64;
+                }
+            }
+        }
+        if filter.tangents {
+            let tangents = mem::take(&mut mesh.tangents);
+            let bitangents = mem::take(&mut mesh.bitangents);
+            report.tangents +=
+                (mem::size_of_val(&tangents[..]) + mem::size_of_val(&bitangents[..])) as u64;
+        }
+    }
+
+    report
+}
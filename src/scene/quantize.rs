@@ -0,0 +1,231 @@
+//! Quantized vertex attributes, for targets where vertex bandwidth matters more than
+//! precision, e.g. mobile and the web.
+//!
+//! Normals are octahedral-encoded as two `i16`s, UVs are `u16`s over a known range and
+//! positions are `u16`s relative to the mesh's bounds, with a `DequantizeTransform` to get
+//! back to the mesh's space. `vertex_buffer::Format` writes these in an interleaved buffer.
+
+use crate::import::Aabb;
+use crate::math::{Matrix4x4, Vector3D};
+
+/// The largest value of an `i16` component, which stands for 1.
+const SNORM16_MAX: f32 = i16::MAX as f32;
+/// The largest value of a `u16` component, which stands for 1.
+const UNORM16_MAX: f32 = u16::MAX as f32;
+
+/// The largest angle between a unit vector and the result of encoding and decoding it with
+/// `encode_octahedral_normals` and `decode_octahedral_normals`, in degrees.
+pub const MAX_OCTAHEDRAL_ERROR_DEGREES: f32 = 0.005;
+
+/// Encode unit vectors, e.g. normals or tangents, in the octahedral mapping with two signed
+/// normalized 16 bit components each.
+///
+/// The vectors don't have to be normalized. After a round trip through
+/// `decode_octahedral_normals` the direction of every vector is within
+/// `MAX_OCTAHEDRAL_ERROR_DEGREES` of the original. Vectors of zero length or with components
+/// that aren't finite are encoded as `[0, 0]`, which decodes to `(0, 0, 1)`.
+pub fn encode_octahedral_normals(normals: &[Vector3D]) -> Vec<[i16; 2]> {
+    normals
+        .iter()
+        .map(|&normal| encode_octahedral(normal))
+        .collect()
+}
+
+/// Decode vectors encoded by `encode_octahedral_normals`, as unit vectors.
+pub fn decode_octahedral_normals(encoded: &[[i16; 2]]) -> Vec<Vector3D> {
+    encoded
+        .iter()
+        .map(|&encoded| decode_octahedral(encoded))
+        .collect()
+}
+
+pub(crate) fn encode_octahedral(normal: Vector3D) -> [i16; 2] {
+    let sum = normal.x.abs() + normal.y.abs() + normal.z.abs();
+    if !(sum > 0.0 && sum.is_finite()) {
+        return [0, 0];
+    }
+
+    let (x, y, z) = (normal.x / sum, normal.y / sum, normal.z / sum);
+    // Fold the lower half of the octahedron over the upper half.
+    let (x, y) = if z < 0.0 {
+        (
+            (1.0 - y.abs()) * sign_not_zero(x),
+            (1.0 - x.abs()) * sign_not_zero(y),
+        )
+    } else {
+        (x, y)
+    };
+
+    let snorm = |value: f32| (value.max(-1.0).min(1.0) * SNORM16_MAX).round() as i16;
+    [snorm(x), snorm(y)]
+}
+
+pub(crate) fn decode_octahedral(encoded: [i16; 2]) -> Vector3D {
+    let x = (encoded[0] as f32 / SNORM16_MAX).max(-1.0);
+    let y = (encoded[1] as f32 / SNORM16_MAX).max(-1.0);
+    let z = 1.0 - x.abs() - y.abs();
+    let (x, y) = if z < 0.0 {
+        (
+            (1.0 - y.abs()) * sign_not_zero(x),
+            (1.0 - x.abs()) * sign_not_zero(y),
+        )
+    } else {
+        (x, y)
+    };
+
+    Vector3D::new(x, y, z).normalize()
+}
+
+fn sign_not_zero(value: f32) -> f32 {
+    if value < 0.0 {
+        -1.0
+    } else {
+        1.0
+    }
+}
+
+/// The range of UVs that `quantize_uvs` maps to `0..=65535`. Default: 0 to 1 in U and V
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct UvRange {
+    pub min: [f32; 2],
+    pub max: [f32; 2],
+}
+
+impl Default for UvRange {
+    fn default() -> Self {
+        UvRange {
+            min: [0.0, 0.0],
+            max: [1.0, 1.0],
+        }
+    }
+}
+
+impl UvRange {
+    /// The smallest range that contains every UV, or `None` if there aren't any finite ones.
+    pub fn of_uvs(uvs: &[[f32; 2]]) -> Option<UvRange> {
+        let mut finite = uvs.iter().filter(|uv| uv.iter().all(|c| c.is_finite()));
+        let first = *finite.next()?;
+        Some(finite.fold(
+            UvRange {
+                min: first,
+                max: first,
+            },
+            |range, uv| UvRange {
+                min: [range.min[0].min(uv[0]), range.min[1].min(uv[1])],
+                max: [range.max[0].max(uv[0]), range.max[1].max(uv[1])],
+            },
+        ))
+    }
+
+    /// Quantize one UV, see `quantize_uvs`.
+    pub fn quantize(&self, uv: [f32; 2]) -> [u16; 2] {
+        [
+            unorm16(uv[0], self.min[0], self.max[0]),
+            unorm16(uv[1], self.min[1], self.max[1]),
+        ]
+    }
+
+    /// The UV that a quantized UV stands for.
+    pub fn dequantize(&self, quantized: [u16; 2]) -> [f32; 2] {
+        let axis = |q: u16, min: f32, max: f32| min + q as f32 / UNORM16_MAX * (max - min);
+        [
+            axis(quantized[0], self.min[0], self.max[0]),
+            axis(quantized[1], self.min[1], self.max[1]),
+        ]
+    }
+}
+
+/// Quantize UVs to two unsigned normalized 16 bit components each, where 0 is `range.min`
+/// and 65535 is `range.max`.
+///
+/// UVs outside of the range are clamped to it, and NaNs become `range.min`. Within the range,
+/// each component is within `(max - min) / 131070`, half a step, of the original after
+/// `UvRange::dequantize`.
+pub fn quantize_uvs(uvs: &[[f32; 2]], range: UvRange) -> Vec<[u16; 2]> {
+    uvs.iter().map(|&uv| range.quantize(uv)).collect()
+}
+
+/// `value` from `min..=max` mapped to `0..=65535`.
+fn unorm16(value: f32, min: f32, max: f32) -> u16 {
+    let extent = max - min;
+    if !(extent > 0.0) {
+        return 0;
+    }
+
+    let normalized = ((value - min) / extent).max(0.0).min(1.0);
+    (normalized * UNORM16_MAX).round() as u16
+}
+
+/// Turns positions quantized by `quantize_positions` back into the mesh's space: each
+/// position is `offset + quantized * scale`, componentwise.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct DequantizeTransform {
+    pub scale: Vector3D,
+    pub offset: Vector3D,
+}
+
+impl DequantizeTransform {
+    /// The transform for positions quantized relative to `aabb`.
+    pub fn of_aabb(aabb: Aabb) -> DequantizeTransform {
+        let scale = |min: f32, max: f32| {
+            if max > min {
+                (max - min) / UNORM16_MAX
+            } else {
+                0.0
+            }
+        };
+        DequantizeTransform {
+            scale: Vector3D::new(
+                scale(aabb.min.x, aabb.max.x),
+                scale(aabb.min.y, aabb.max.y),
+                scale(aabb.min.z, aabb.max.z),
+            ),
+            offset: aabb.min,
+        }
+    }
+
+    pub fn apply(&self, quantized: [u16; 3]) -> Vector3D {
+        Vector3D::new(
+            self.offset.x + quantized[0] as f32 * self.scale.x,
+            self.offset.y + quantized[1] as f32 * self.scale.y,
+            self.offset.z + quantized[2] as f32 * self.scale.z,
+        )
+    }
+
+    /// The transform as a matrix, to multiply the node's transform by in a vertex shader
+    /// that reads the quantized positions as unnormalized integers.
+    pub fn to_matrix(&self) -> Matrix4x4 {
+        Matrix4x4::from_rows([
+            [self.scale.x, 0.0, 0.0, self.offset.x],
+            [0.0, self.scale.y, 0.0, self.offset.y],
+            [0.0, 0.0, self.scale.z, self.offset.z],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+}
+
+/// Quantize positions to three unsigned 16 bit components each, relative to `aabb`, which is
+/// usually `Aabb::of_mesh`.
+///
+/// Positions outside of `aabb` are clamped to it. Within it, each component is within half a
+/// step, `extent / 131070` where `extent` is the size of `aabb` along that axis, of the
+/// original after `DequantizeTransform::apply`, give or take the rounding of `f32`s. An axis
+/// along which `aabb` is flat has a scale of 0.
+pub fn quantize_positions(
+    positions: &[Vector3D],
+    aabb: Aabb,
+) -> (Vec<[u16; 3]>, DequantizeTransform) {
+    let quantized = positions
+        .iter()
+        .map(|&p| quantize_position(p, aabb))
+        .collect();
+    (quantized, DequantizeTransform::of_aabb(aabb))
+}
+
+pub(crate) fn quantize_position(p: Vector3D, aabb: Aabb) -> [u16; 3] {
+    [
+        unorm16(p.x, aabb.min.x, aabb.max.x),
+        unorm16(p.y, aabb.min.y, aabb.max.y),
+        unorm16(p.z, aabb.min.z, aabb.max.z),
+    ]
+}
@@ -0,0 +1,500 @@
+//! Ray intersection queries against the geometry of a whole scene, e.g. for picking objects.
+//!
+//! `build_bvh` places every mesh in world space using the node graph, the same way as
+//! `bake::vertex_ao`, and builds a bounding volume hierarchy over the triangles. The resulting
+//! `SceneBvh` doesn't borrow the scene, and `SceneBvh::refit` moves the meshes whose transforms
+//! changed without building it again.
+
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+
+use crate::math::{Matrix4x4, Vector3D};
+
+use super::bake::placements;
+use super::scene::Scene;
+
+/// What `build_bvh` does with faces that have more than three indices.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Polygons {
+    /// Split them into triangle fans around their first index, which is only correct for
+    /// convex polygons.
+    Fan,
+    /// Fail with `RaycastError::Polygon`, for scenes that should have been imported with
+    /// `triangulate`.
+    Reject,
+}
+
+/// Options for `build_bvh`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RaycastOptions {
+    /// Default: `Polygons::Fan`
+    pub polygons: Polygons,
+    /// The tolerance of the intersection tests. Hits closer to the origin of the ray than this
+    /// are ignored, and hits up to this far outside a triangle, in barycentric coordinates,
+    /// still count, so that rays through an edge don't slip between its triangles.
+    /// Default: 1e-6
+    pub epsilon: f32,
+}
+
+impl Default for RaycastOptions {
+    fn default() -> Self {
+        RaycastOptions {
+            polygons: Polygons::Fan,
+            epsilon: 1e-6,
+        }
+    }
+}
+
+/// Where a ray hits a triangle, as returned by `SceneBvh::intersect`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Hit {
+    pub mesh_index: u32,
+    /// Which of the nodes that reference the mesh placed it, in the order of
+    /// `Node::descendants`. This indexes `SceneBvh::transforms`.
+    pub instance: u32,
+    pub face_index: u32,
+    /// The vertices of the triangle that was hit. For a face that was split into a fan, these
+    /// are its first index and two consecutive ones.
+    pub indices: [u32; 3],
+    /// The distance along the ray, in multiples of the length of its direction.
+    pub t: f32,
+    /// The weights of the vertices in `indices` at the hit, which add up to 1.
+    pub barycentric: [f32; 3],
+    pub world_point: Vector3D,
+}
+
+/// Why a `SceneBvh` couldn't be built or refitted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RaycastError {
+    /// A face has more than three indices, with `Polygons::Reject`.
+    Polygon { mesh: u32, face: u32 },
+    /// A face refers to a vertex that the mesh doesn't have.
+    IndexOutOfRange { mesh: u32, face: u32, index: u32 },
+    /// `refit` was called for a mesh that the scene doesn't have.
+    NoSuchMesh(u32),
+    /// `refit` was given a different number of transforms than the mesh has instances.
+    TransformCount {
+        mesh: u32,
+        expected: usize,
+        actual: usize,
+    },
+}
+
+impl fmt::Display for RaycastError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RaycastError::Polygon { mesh, face } => {
+                write!(f, "face {} of mesh {} isn't a triangle", face, mesh)
+            }
+            RaycastError::IndexOutOfRange { mesh, face, index } => write!(
+                f,
+                "face {} of mesh {} refers to vertex {}, which doesn't exist",
+                face, mesh, index
+            ),
+            RaycastError::NoSuchMesh(mesh) => write!(f, "there is no mesh {}", mesh),
+            RaycastError::TransformCount {
+                mesh,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "mesh {} has {} instances, but {} transforms were given",
+                mesh, expected, actual
+            ),
+        }
+    }
+}
+
+impl Error for RaycastError {}
+
+/// A bounding volume hierarchy over the triangles of a scene in world space, see `build_bvh`.
+pub struct SceneBvh {
+    meshes: Vec<MeshEntry>,
+    nodes: Vec<BvhNode>,
+    triangles: Vec<Triangle>,
+    epsilon: f32,
+}
+
+/// The positions of a mesh in its own space, and the world transform of each instance.
+struct MeshEntry {
+    positions: Vec<Vector3D>,
+    transforms: Vec<Matrix4x4>,
+}
+
+/// Build a `SceneBvh` over the faces of every mesh in `scene`.
+///
+/// Meshes are placed by the world transforms of the nodes that reference them, once for every
+/// such node. Meshes that no node references are used as they are, in their own space. Points
+/// and lines can't be hit and are left out.
+pub fn build_bvh(scene: &Scene, opts: RaycastOptions) -> Result<SceneBvh, RaycastError> {
+    let mut meshes = Vec::new();
+    let mut triangles = Vec::new();
+    for (index, transforms) in placements(scene).into_iter().enumerate() {
+        let mesh_index = index as u32;
+        let mesh = match scene.mesh(mesh_index) {
+            Some(mesh) => mesh,
+            None => {
+                meshes.push(MeshEntry {
+                    positions: Vec::new(),
+                    transforms,
+                });
+                continue;
+            }
+        };
+        let entry = MeshEntry {
+            positions: mesh.positions().collect(),
+            transforms,
+        };
+
+        for (face_index, face) in mesh.faces().enumerate() {
+            let face_index = face_index as u32;
+            let indices = face.indices();
+            if indices.len() < 3 {
+                continue;
+            }
+            if indices.len() > 3 && opts.polygons == Polygons::Reject {
+                return Err(RaycastError::Polygon {
+                    mesh: mesh_index,
+                    face: face_index,
+                });
+            }
+            if let Some(&index) = indices.iter().find(|&&i| i >= mesh.num_vertices()) {
+                return Err(RaycastError::IndexOutOfRange {
+                    mesh: mesh_index,
+                    face: face_index,
+                    index,
+                });
+            }
+            for pair in indices[1..].windows(2) {
+                for instance in 0..entry.transforms.len() {
+                    let mut triangle = Triangle {
+                        mesh: mesh_index,
+                        instance: instance as u32,
+                        face: face_index,
+                        indices: [indices[0], pair[0], pair[1]],
+                        a: Vector3D::new(0.0, 0.0, 0.0),
+                        edge1: Vector3D::new(0.0, 0.0, 0.0),
+                        edge2: Vector3D::new(0.0, 0.0, 0.0),
+                    };
+                    triangle.place(&entry);
+                    triangles.push(triangle);
+                }
+            }
+        }
+        meshes.push(entry);
+    }
+
+    let mut bvh = SceneBvh {
+        meshes,
+        nodes: Vec::new(),
+        triangles,
+        epsilon: opts.epsilon,
+    };
+    if !bvh.triangles.is_empty() {
+        bvh.build(0, bvh.triangles.len());
+    }
+    Ok(bvh)
+}
+
+impl SceneBvh {
+    /// The number of triangles, counting each instance of a mesh separately.
+    pub fn num_triangles(&self) -> usize {
+        self.triangles.len()
+    }
+
+    /// The world transform of each instance of the mesh `mesh_index`.
+    pub fn transforms(&self, mesh_index: u32) -> Option<&[Matrix4x4]> {
+        self.meshes
+            .get(mesh_index as usize)
+            .map(|mesh| &mesh.transforms[..])
+    }
+
+    /// The closest hit of the ray from `ray_origin` along `ray_dir`, which doesn't have to be
+    /// normalized. Triangles are hit from either side.
+    pub fn intersect(&self, ray_origin: Vector3D, ray_dir: Vector3D) -> Option<Hit> {
+        let mut closest = None;
+        self.traverse(ray_origin, ray_dir, |hit| {
+            closest = Some(hit);
+            hit.t
+        });
+        closest
+    }
+
+    /// Every hit of the ray from `ray_origin` along `ray_dir`, in ascending order of `t`.
+    ///
+    /// A ray through an edge can hit the triangles on both sides of it. That's reported once
+    /// for the triangles of a face that was split into a fan, but twice for an edge between
+    /// two faces.
+    pub fn intersect_all(&self, ray_origin: Vector3D, ray_dir: Vector3D) -> Vec<Hit> {
+        let mut hits = Vec::new();
+        self.traverse(ray_origin, ray_dir, |hit| {
+            hits.push(hit);
+            f32::INFINITY
+        });
+        hits.sort_by(|a, b| a.t.partial_cmp(&b.t).unwrap_or(Ordering::Equal));
+
+        let mut unique: Vec<Hit> = Vec::with_capacity(hits.len());
+        for hit in hits {
+            let duplicate = unique
+                .iter()
+                .rev()
+                .take_while(|other| hit.t - other.t <= self.epsilon * other.t.max(1.0))
+                .any(|other| {
+                    (hit.mesh_index, hit.instance, hit.face_index)
+                        == (other.mesh_index, other.instance, other.face_index)
+                });
+            if !duplicate {
+                unique.push(hit);
+            }
+        }
+        unique
+    }
+
+    /// Move the instances of the mesh `mesh_index` to `transforms`, one per instance, and update
+    /// the bounds of the parts of the hierarchy that contain them.
+    ///
+    /// The structure of the hierarchy stays the same, so queries get slower the further the
+    /// mesh moves from where it was when the `SceneBvh` was built. Build it again after large
+    /// changes.
+    pub fn refit(&mut self, mesh_index: u32, transforms: &[Matrix4x4]) -> Result<(), RaycastError> {
+        let mesh = self
+            .meshes
+            .get_mut(mesh_index as usize)
+            .ok_or(RaycastError::NoSuchMesh(mesh_index))?;
+        if transforms.len() != mesh.transforms.len() {
+            return Err(RaycastError::TransformCount {
+                mesh: mesh_index,
+                expected: mesh.transforms.len(),
+                actual: transforms.len(),
+            });
+        }
+        mesh.transforms.copy_from_slice(transforms);
+        let mesh = &self.meshes[mesh_index as usize];
+
+        // Children come after their parents, so going backwards updates them first.
+        let mut moved = vec![false; self.nodes.len()];
+        for index in (0..self.nodes.len()).rev() {
+            let (start, count) = (self.nodes[index].start, self.nodes[index].count);
+            let (lower, upper) = if count > 0 {
+                let triangles = &mut self.triangles[start..start + count];
+                for triangle in triangles.iter_mut() {
+                    if triangle.mesh == mesh_index {
+                        triangle.place(mesh);
+                        moved[index] = true;
+                    }
+                }
+                if !moved[index] {
+                    continue;
+                }
+                bounds(triangles)
+            } else {
+                if !moved[index + 1] && !moved[start] {
+                    continue;
+                }
+                moved[index] = true;
+                let (first, second) = (&self.nodes[index + 1], &self.nodes[start]);
+                (min(first.min, second.min), max(first.max, second.max))
+            };
+            self.nodes[index].min = lower;
+            self.nodes[index].max = upper;
+        }
+        Ok(())
+    }
+
+    fn build(&mut self, start: usize, end: usize) -> usize {
+        let triangles = &mut self.triangles[start..end];
+        let (min, max) = bounds(triangles);
+        let (mut centroid_min, mut centroid_max) = ([f32::INFINITY; 3], [f32::NEG_INFINITY; 3]);
+        for triangle in triangles.iter() {
+            for axis in 0..3 {
+                let centroid = triangle.centroid(axis);
+                centroid_min[axis] = centroid_min[axis].min(centroid);
+                centroid_max[axis] = centroid_max[axis].max(centroid);
+            }
+        }
+
+        let index = self.nodes.len();
+        self.nodes.push(BvhNode {
+            min,
+            max,
+            start,
+            count: end - start,
+        });
+        if end - start <= LEAF_SIZE {
+            return index;
+        }
+
+        let axis = (0..3)
+            .max_by(|&a, &b| {
+                let extent = |axis: usize| centroid_max[axis] - centroid_min[axis];
+                extent(a).partial_cmp(&extent(b)).unwrap_or(Ordering::Equal)
+            })
+            .unwrap_or(0);
+        triangles.sort_by(|a, b| {
+            a.centroid(axis)
+                .partial_cmp(&b.centroid(axis))
+                .unwrap_or(Ordering::Equal)
+        });
+
+        let middle = (start + end) / 2;
+        self.build(start, middle);
+        let second = self.build(middle, end);
+        self.nodes[index].start = second;
+        self.nodes[index].count = 0;
+        index
+    }
+
+    /// Call `on_hit` for hits of the ray closer than the distance returned by the previous
+    /// call, or for all hits at first.
+    fn traverse<F>(&self, origin: Vector3D, direction: Vector3D, mut on_hit: F)
+    where
+        F: FnMut(Hit) -> f32,
+    {
+        if self.nodes.is_empty() {
+            return;
+        }
+        let inverse = Vector3D::new(1.0 / direction.x, 1.0 / direction.y, 1.0 / direction.z);
+
+        let mut max_distance = f32::INFINITY;
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if !slab_test(node, origin, inverse, max_distance) {
+                continue;
+            }
+            if node.count > 0 {
+                for triangle in &self.triangles[node.start..node.start + node.count] {
+                    if let Some(hit) = triangle.hit(origin, direction, max_distance, self.epsilon) {
+                        max_distance = on_hit(hit);
+                    }
+                }
+            } else {
+                stack.push(node.start);
+                stack.push(index + 1);
+            }
+        }
+    }
+}
+
+/// A triangle of an instance of a mesh, in world space.
+struct Triangle {
+    mesh: u32,
+    instance: u32,
+    face: u32,
+    indices: [u32; 3],
+    a: Vector3D,
+    edge1: Vector3D,
+    edge2: Vector3D,
+}
+
+impl Triangle {
+    /// Place the triangle by the transform of its instance of `mesh`.
+    fn place(&mut self, mesh: &MeshEntry) {
+        let transform = &mesh.transforms[self.instance as usize];
+        let corner = |i: usize| transform.transform_point(mesh.positions[self.indices[i] as usize]);
+        let (a, b, c) = (corner(0), corner(1), corner(2));
+        self.a = a;
+        self.edge1 = b - a;
+        self.edge2 = c - a;
+    }
+
+    fn centroid(&self, axis: usize) -> f32 {
+        component(self.a, axis) + (component(self.edge1, axis) + component(self.edge2, axis)) / 3.0
+    }
+
+    /// Where the ray from `origin` along `direction` hits the triangle, from either side,
+    /// between `epsilon` and `max_distance` along it.
+    fn hit(
+        &self,
+        origin: Vector3D,
+        direction: Vector3D,
+        max_distance: f32,
+        epsilon: f32,
+    ) -> Option<Hit> {
+        let p = direction.cross(self.edge2);
+        let det = self.edge1.dot(p);
+        if det.abs() <= f32::MIN_POSITIVE {
+            return None;
+        }
+        let inv = 1.0 / det;
+        let s = origin - self.a;
+        let u = s.dot(p) * inv;
+        let q = s.cross(self.edge1);
+        let v = direction.dot(q) * inv;
+        if u < -epsilon || v < -epsilon || u + v > 1.0 + epsilon {
+            return None;
+        }
+        let t = self.edge2.dot(q) * inv;
+        if !(t > epsilon && t < max_distance) {
+            return None;
+        }
+        Some(Hit {
+            mesh_index: self.mesh,
+            instance: self.instance,
+            face_index: self.face,
+            indices: self.indices,
+            t,
+            barycentric: [1.0 - u - v, u, v],
+            world_point: origin + direction * t,
+        })
+    }
+}
+
+/// The maximum number of triangles in a leaf of a `SceneBvh`.
+const LEAF_SIZE: usize = 4;
+
+/// A node of a `SceneBvh`. Leaves have `count` triangles starting at `start`. Other nodes have
+/// their first child right after them, and their second child at `start`.
+struct BvhNode {
+    min: Vector3D,
+    max: Vector3D,
+    start: usize,
+    count: usize,
+}
+
+/// The bounds of the corners of `triangles`, which mustn't be empty.
+fn bounds(triangles: &[Triangle]) -> (Vector3D, Vector3D) {
+    let (mut lower, mut upper) = (triangles[0].a, triangles[0].a);
+    for triangle in triangles {
+        for &p in &[
+            triangle.a,
+            triangle.a + triangle.edge1,
+            triangle.a + triangle.edge2,
+        ] {
+            lower = min(lower, p);
+            upper = max(upper, p);
+        }
+    }
+    (lower, upper)
+}
+
+fn min(a: Vector3D, b: Vector3D) -> Vector3D {
+    Vector3D::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z))
+}
+
+fn max(a: Vector3D, b: Vector3D) -> Vector3D {
+    Vector3D::new(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z))
+}
+
+/// Whether the ray from `origin` with the inverse direction `inverse` enters the bounds of
+/// `node` closer than `max_distance`.
+fn slab_test(node: &BvhNode, origin: Vector3D, inverse: Vector3D, max_distance: f32) -> bool {
+    let (mut near, mut far) = (0.0f32, max_distance);
+    for axis in 0..3 {
+        let (o, inv) = (component(origin, axis), component(inverse, axis));
+        let t0 = (component(node.min, axis) - o) * inv;
+        let t1 = (component(node.max, axis) - o) * inv;
+        near = near.max(t0.min(t1));
+        far = far.min(t0.max(t1));
+    }
+    near <= far
+}
+
+fn component(v: Vector3D, axis: usize) -> f32 {
+    match axis {
+        0 => v.x,
+        1 => v.y,
+        _ => v.z,
+    }
+}
@@ -0,0 +1,216 @@
+//! Rewriting the texture paths of materials, e.g. after copying a model to another directory.
+//!
+//! Texture paths are usually relative to the model file, so they stop working when the model
+//! moves without its textures, such as into a content-addressed store. `rewrite_texture_paths`
+//! passes every texture path of every material through a function and keeps a log of what it
+//! changed. It works on a `MaterialOverlay`, which changes what the overlay's materials
+//! return, and on a `SceneSnapshot`, which changes what's serialized or exported from it.
+
+use std::convert::TryFrom;
+
+use crate::InlineString;
+
+use super::material::{
+    MaterialComponentType, MaterialKey, MaterialSource, MaterialValue, TEXTURE_TYPES,
+};
+use super::overlay::MaterialOverlay;
+use super::snapshot::{SceneSnapshot, TextureTarget};
+
+/// Something whose material texture paths `rewrite_texture_paths` can change.
+pub trait TexturePaths {
+    /// Every texture path as `(material, component, index, path)`, by material, then by
+    /// component in the order of the `MaterialComponentType` variants, then by index. Paths
+    /// that aren't valid UTF-8 are left out.
+    fn texture_paths(&self) -> Vec<(u32, MaterialComponentType, u32, String)>;
+
+    /// Change the path of the `index`th texture of `component` of material `material`.
+    /// Returns false if there's no such texture or the path can't be stored.
+    fn set_texture_path(
+        &mut self,
+        material: u32,
+        component: MaterialComponentType,
+        index: u32,
+        path: &str,
+    ) -> bool;
+}
+
+/// A texture path changed by `rewrite_texture_paths`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathChange {
+    /// The index of the material in the scene.
+    pub material: u32,
+    pub component: MaterialComponentType,
+    /// The index of the texture within its component.
+    pub index: u32,
+    pub old: String,
+    pub new: String,
+}
+
+/// What `rewrite_texture_paths_with` passes to the rewriter. The default is what
+/// `rewrite_texture_paths` does.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct RewriteOptions {
+    /// Whether to also pass references to embedded textures, i.e. `*0`, `*1` and so on, which
+    /// don't depend on where the model is. Default: false
+    pub embedded: bool,
+}
+
+/// Pass every texture path in `target`, except references to embedded textures, to `rewriter`
+/// and replace it with what that returns. See `rewrite_texture_paths_with`.
+pub fn rewrite_texture_paths<T, F>(target: &mut T, rewriter: F) -> Vec<PathChange>
+where
+    T: TexturePaths + ?Sized,
+    F: Fn(&str) -> Option<String>,
+{
+    rewrite_texture_paths_with(target, RewriteOptions::default(), rewriter)
+}
+
+/// Pass the texture paths in `target` to `rewriter`, as chosen by `options`, and replace each
+/// path with what that returns. `None` leaves the path as it is. Backslashes in the new paths
+/// are replaced with forward slashes.
+///
+/// Returns a change for each path that's now different, in the order of
+/// `TexturePaths::texture_paths`. If a new path can't be stored, e.g. because it's longer than
+/// the 1024 bytes an overlay can hold, the path is left as it is and isn't in the log.
+pub fn rewrite_texture_paths_with<T, F>(
+    target: &mut T,
+    options: RewriteOptions,
+    rewriter: F,
+) -> Vec<PathChange>
+where
+    T: TexturePaths + ?Sized,
+    F: Fn(&str) -> Option<String>,
+{
+    let mut changes = Vec::new();
+    for (material, component, index, old) in target.texture_paths() {
+        if !options.embedded && is_embedded_reference(&old) {
+            continue;
+        }
+        let new = match rewriter(&old) {
+            Some(new) => new.replace('\\', "/"),
+            None => continue,
+        };
+        if new != old && target.set_texture_path(material, component, index, &new) {
+            changes.push(PathChange {
+                material,
+                component,
+                index,
+                old,
+                new,
+            });
+        }
+    }
+    changes
+}
+
+/// Whether `path` is of the form `*N`, which is how Assimp refers to embedded textures.
+fn is_embedded_reference(path: &str) -> bool {
+    path.starts_with('*') && path.len() > 1 && path[1..].bytes().all(|b| b.is_ascii_digit())
+}
+
+/// The overrides are set with `MaterialKey::Texture`, so rewriting the same overlay again sees
+/// the rewritten paths, and `MaterialOverlay::unset` restores the original.
+impl TexturePaths for MaterialOverlay<'_> {
+    fn texture_paths(&self) -> Vec<(u32, MaterialComponentType, u32, String)> {
+        let mut paths = Vec::new();
+        for (id, material) in self.materials().enumerate() {
+            for &component in &TEXTURE_TYPES {
+                for index in 0..material.num_textures(component) {
+                    let texture = match material.texture(component, index) {
+                        Some(texture) => texture,
+                        None => continue,
+                    };
+                    if let Ok(path) = texture.path.to_str() {
+                        paths.push((id as u32, component, index, path.to_owned()));
+                    }
+                }
+            }
+        }
+        paths
+    }
+
+    fn set_texture_path(
+        &mut self,
+        material: u32,
+        component: MaterialComponentType,
+        index: u32,
+        path: &str,
+    ) -> bool {
+        let exists = self
+            .material(material)
+            .map_or(false, |material| index < material.num_textures(component));
+        match InlineString::try_from(path) {
+            Ok(path) if exists => {
+                let key = MaterialKey::Texture(component, index);
+                self.set(material, key, MaterialValue::String(path));
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// The target of each rewritten texture is updated to match its new path.
+impl TexturePaths for SceneSnapshot {
+    fn texture_paths(&self) -> Vec<(u32, MaterialComponentType, u32, String)> {
+        let mut paths = Vec::new();
+        for (id, material) in self.materials.iter().enumerate() {
+            for texture in &material.textures {
+                let path = texture.path.clone();
+                paths.push((id as u32, texture.component, texture.index, path));
+            }
+        }
+        paths
+    }
+
+    fn set_texture_path(
+        &mut self,
+        material: u32,
+        component: MaterialComponentType,
+        index: u32,
+        path: &str,
+    ) -> bool {
+        let target = snapshot_target(self, path);
+        let texture = self
+            .materials
+            .get_mut(material as usize)
+            .and_then(|material| {
+                material
+                    .textures
+                    .iter_mut()
+                    .find(|texture| texture.component == component && texture.index == index)
+            });
+        match texture {
+            Some(texture) => {
+                texture.path = path.to_owned();
+                texture.target = target;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// What `path` refers to among the embedded textures of `snapshot`, like
+/// `MaterialData::from_material` decides it for a scene.
+fn snapshot_target(snapshot: &SceneSnapshot, path: &str) -> TextureTarget {
+    let index = if is_embedded_reference(path) {
+        path[1..]
+            .parse()
+            .ok()
+            .filter(|&index| (index as usize) < snapshot.textures.len())
+    } else {
+        snapshot
+            .textures
+            .iter()
+            .position(|texture| !texture.filename.is_empty() && texture.filename == path)
+            .map(|index| index as u32)
+    };
+    match index {
+        Some(index) if snapshot.textures[index as usize].data.is_some() => {
+            TextureTarget::Embedded(index)
+        }
+        Some(index) => TextureTarget::Missing(index),
+        None => TextureTarget::External,
+    }
+}
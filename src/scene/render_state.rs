@@ -0,0 +1,141 @@
+//! The pipeline state a renderer needs for a material, see `Material::render_state_hint`.
+
+use super::material::{
+    Material, MaterialBlendOp, MaterialComponentType, MaterialKey, MaterialSource, MaterialValue,
+    TextureFlags,
+};
+use super::overlay::OverlaidMaterial;
+
+/// Which faces a renderer should cull.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum CullMode {
+    /// Cull back faces, the default for materials that aren't two-sided.
+    Back,
+    /// Draw both sides of each face.
+    None,
+}
+
+/// How a renderer should combine a material's color with what's behind it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum BlendMode {
+    /// Ignore alpha.
+    Opaque,
+    /// Discard fragments whose alpha is below `RenderStateHint::alpha_cutoff`, and draw the others
+    /// as opaque.
+    AlphaTest,
+    /// Blend by alpha, `out = src * alpha + dst * (1 - alpha)`.
+    AlphaBlend,
+    /// Add to what's behind, `out = src * alpha + dst`.
+    Additive,
+}
+
+/// The pipeline state for a material, as returned by `Material::render_state_hint`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RenderStateHint {
+    pub cull: CullMode,
+    pub blend: BlendMode,
+    /// The alpha cutoff for `BlendMode::AlphaTest`, `None` for the other modes.
+    pub alpha_cutoff: Option<f32>,
+    /// Whether the material should write depth. False for the blended modes, which are usually
+    /// drawn after the opaque geometry and sorted back to front.
+    pub depth_write: bool,
+}
+
+/// The alpha cutoff that glTF uses for `MASK` materials that don't specify one.
+const DEFAULT_ALPHA_CUTOFF: f32 = 0.5;
+
+impl Material {
+    /// How a renderer should set up culling, blending and depth writes for this material. The
+    /// hint is derived from the material's properties as follows:
+    ///
+    /// 1. The material is two-sided, with `CullMode::None`, if `MaterialKey::TwoSided` is true,
+    ///    and culls back faces otherwise.
+    /// 2. If the file is glTF and says how alpha is used, with `MaterialKey::GltfAlphaMode`,
+    ///    that decides: `MASK` is `BlendMode::AlphaTest` with the `MaterialKey::GltfAlphaCutoff`
+    ///    of the material, or 0.5, `BLEND` is `BlendMode::AlphaBlend` and anything else is
+    ///    opaque.
+    /// 3. Otherwise the material is `BlendMode::Additive` if `MaterialKey::BlendFunc` is
+    ///    `MaterialBlendOp::Additive`.
+    /// 4. Otherwise it's `BlendMode::AlphaBlend` if its opacity is below 1, if it has an opacity
+    ///    texture without `TextureFlags::IGNORE_ALPHA`, or if it has a diffuse texture with
+    ///    `TextureFlags::FORCE_ALPHA`.
+    /// 5. Otherwise it's opaque.
+    ///
+    /// Only the blended modes turn depth writes off.
+    pub fn render_state_hint(&self) -> RenderStateHint {
+        render_state_hint(self)
+    }
+}
+
+impl OverlaidMaterial<'_> {
+    /// Like `Material::render_state_hint`, but with the overrides applied.
+    pub fn render_state_hint(&self) -> RenderStateHint {
+        render_state_hint(self)
+    }
+}
+
+fn render_state_hint<M: MaterialSource + ?Sized>(material: &M) -> RenderStateHint {
+    let cull = match material.get_value(MaterialKey::TwoSided) {
+        Some(MaterialValue::Bool(true)) => CullMode::None,
+        _ => CullMode::Back,
+    };
+    let blend = blend_mode(material);
+    let alpha_cutoff = match blend {
+        BlendMode::AlphaTest => match material.get_value(MaterialKey::GltfAlphaCutoff) {
+            Some(MaterialValue::Float(cutoff)) => Some(cutoff),
+            _ => Some(DEFAULT_ALPHA_CUTOFF),
+        },
+        _ => None,
+    };
+    let depth_write = match blend {
+        BlendMode::Opaque | BlendMode::AlphaTest => true,
+        BlendMode::AlphaBlend | BlendMode::Additive => false,
+    };
+
+    RenderStateHint {
+        cull,
+        blend,
+        alpha_cutoff,
+        depth_write,
+    }
+}
+
+fn blend_mode<M: MaterialSource + ?Sized>(material: &M) -> BlendMode {
+    if let Some(MaterialValue::String(mode)) = material.get_value(MaterialKey::GltfAlphaMode) {
+        return match mode.to_str() {
+            Ok("MASK") => BlendMode::AlphaTest,
+            Ok("BLEND") => BlendMode::AlphaBlend,
+            _ => BlendMode::Opaque,
+        };
+    }
+
+    if let Some(MaterialValue::MaterialBlendOp(MaterialBlendOp::Additive)) =
+        material.get_value(MaterialKey::BlendFunc)
+    {
+        return BlendMode::Additive;
+    }
+
+    if let Some(MaterialValue::Float(opacity)) = material.get_value(MaterialKey::Opacity) {
+        if opacity < 1.0 {
+            return BlendMode::AlphaBlend;
+        }
+    }
+
+    let any_texture = |type_, blends: fn(TextureFlags) -> bool| {
+        (0..material.num_textures(type_))
+            .filter_map(|index| material.texture(type_, index))
+            .any(|texture| blends(texture.flags))
+    };
+    if any_texture(MaterialComponentType::Opacity, |flags| {
+        !flags.contains(TextureFlags::IGNORE_ALPHA)
+    }) || any_texture(MaterialComponentType::Diffuse, |flags| {
+        flags.contains(TextureFlags::FORCE_ALPHA)
+    }) {
+        return BlendMode::AlphaBlend;
+    }
+
+    BlendMode::Opaque
+}
@@ -0,0 +1,124 @@
+//! Whether meshes have the vertex attributes their materials need, see
+//! `Scene::attribute_requirements`.
+
+use super::material::{Mapping, Material, MaterialComponentType, TEXTURE_TYPES};
+use super::mesh::Mesh;
+use super::scene::Scene;
+
+/// A vertex attribute that a material needs the meshes using it to have.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RequiredAttribute {
+    /// The UV channel of a texture with UV mapping.
+    TextureCoords(u32),
+    /// Tangents and bitangents, for a normal map.
+    Tangents,
+}
+
+/// Whether a mesh has the attributes its material needs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MeshRequirementReport {
+    /// The index of the mesh in `Scene::meshes`.
+    pub mesh_index: u32,
+    pub satisfied: bool,
+    /// The required attributes that the mesh doesn't have, in the order of
+    /// `MaterialRequirementReport::required`.
+    pub missing: Vec<RequiredAttribute>,
+}
+
+/// The vertex attributes that a material needs, and whether the meshes using it have them,
+/// as returned by `Scene::attribute_requirements`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MaterialRequirementReport {
+    /// The index of the material in `Scene::materials`.
+    pub material_index: u32,
+    pub name: String,
+    /// Sorted, without duplicates.
+    pub required: Vec<RequiredAttribute>,
+    /// A report for each mesh using the material, in the order of `Scene::meshes`.
+    pub meshes: Vec<MeshRequirementReport>,
+    /// Whether every mesh using the material has every required attribute, which is also
+    /// the case if no mesh uses it.
+    pub satisfied: bool,
+}
+
+impl MaterialRequirementReport {
+    /// The meshes that are missing attributes, with the attributes they're missing.
+    pub fn unsatisfied(&self) -> impl Iterator<Item = &MeshRequirementReport> + '_ {
+        self.meshes.iter().filter(|mesh| !mesh.satisfied)
+    }
+}
+
+impl Scene<'_> {
+    /// For each material, in the order of `Scene::materials`, the vertex attributes that its
+    /// textures need, and whether every mesh that uses it has them. This helps to choose
+    /// shader permutations, or catch assets that will render wrongly.
+    ///
+    /// Every texture with UV mapping, or without a mapping, needs its UV channel, which is
+    /// `TextureDefinition::channel`, so channels that the file doesn't specify are inferred
+    /// as Assimp recommends. A `Normals` or `NormalCamera` texture needs tangents. Assimp
+    /// doesn't say whether a material uses vertex colors, so they're never required.
+    pub fn attribute_requirements(&self) -> Vec<MaterialRequirementReport> {
+        self.materials()
+            .enumerate()
+            .map(|(material_index, material)| {
+                let material_index = material_index as u32;
+                let required = required_attributes(material);
+                let meshes: Vec<_> = self
+                    .meshes()
+                    .enumerate()
+                    .filter(|(_, mesh)| mesh.material_id() == material_index)
+                    .map(|(mesh_index, mesh)| {
+                        let missing: Vec<_> = required
+                            .iter()
+                            .copied()
+                            .filter(|&attribute| !has_attribute(mesh, attribute))
+                            .collect();
+                        MeshRequirementReport {
+                            mesh_index: mesh_index as u32,
+                            satisfied: missing.is_empty(),
+                            missing,
+                        }
+                    })
+                    .collect();
+                MaterialRequirementReport {
+                    material_index,
+                    name: material.name_cstr().to_string_lossy().into_owned(),
+                    required,
+                    satisfied: meshes.iter().all(|mesh| mesh.satisfied),
+                    meshes,
+                }
+            })
+            .collect()
+    }
+}
+
+fn required_attributes(material: &Material) -> Vec<RequiredAttribute> {
+    let mut required = Vec::new();
+    for &type_ in &TEXTURE_TYPES {
+        for index in 0..material.num_textures(type_) {
+            let texture = match material.texture(type_, index) {
+                Some(texture) => texture,
+                None => continue,
+            };
+            if let None | Some(Mapping::UV) = texture.mapping {
+                required.push(RequiredAttribute::TextureCoords(texture.channel));
+            }
+            if let MaterialComponentType::Normals | MaterialComponentType::NormalCamera = type_ {
+                required.push(RequiredAttribute::Tangents);
+            }
+        }
+    }
+    required.sort();
+    required.dedup();
+    required
+}
+
+fn has_attribute(mesh: &Mesh, attribute: RequiredAttribute) -> bool {
+    match attribute {
+        RequiredAttribute::TextureCoords(channel) => mesh
+            .mTextureCoords
+            .get(channel as usize)
+            .map_or(false, |coords| !coords.is_null()),
+        RequiredAttribute::Tangents => !mesh.mTangents.is_null() && !mesh.mBitangents.is_null(),
+    }
+}
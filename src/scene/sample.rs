@@ -0,0 +1,212 @@
+//! Interpolating vertex attributes inside triangles, see `Mesh::sample_attribute`.
+
+use std::error::Error;
+use std::fmt;
+
+use crate::math::{Color4D, Vector3D};
+
+use super::mesh::Mesh;
+
+/// A vertex attribute of a mesh, for `Mesh::sample_attribute`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum AttributeSelector {
+    Position,
+    Normal,
+    Tangent,
+    Bitangent,
+    /// The UV channel with the given index.
+    TextureCoords(u32),
+    /// The color set with the given index.
+    Color(u32),
+}
+
+/// An interpolated vertex attribute, as returned by `Mesh::sample_attribute`. Colors are
+/// `Color`, and every other attribute is `Vector`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SampledValue {
+    Vector(Vector3D),
+    Color(Color4D),
+}
+
+impl SampledValue {
+    /// The vector, or `None` for a color.
+    pub fn vector(self) -> Option<Vector3D> {
+        match self {
+            SampledValue::Vector(vector) => Some(vector),
+            SampledValue::Color(_) => None,
+        }
+    }
+
+    /// The color, or `None` for a vector.
+    pub fn color(self) -> Option<Color4D> {
+        match self {
+            SampledValue::Color(color) => Some(color),
+            SampledValue::Vector(_) => None,
+        }
+    }
+}
+
+/// Every vertex attribute of a mesh interpolated at one point, as returned by
+/// `Mesh::sample_all`. Attributes that the mesh doesn't have are `None`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SampledVertex {
+    pub position: Vector3D,
+    pub normal: Option<Vector3D>,
+    pub tangent: Option<Vector3D>,
+    pub bitangent: Option<Vector3D>,
+    /// One entry for each of Assimp's UV channels.
+    pub texture_coords: Vec<Option<Vector3D>>,
+    /// One entry for each of Assimp's color sets.
+    pub colors: Vec<Option<Color4D>>,
+}
+
+/// Why a point on a face couldn't be sampled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SampleError {
+    /// The mesh has no face with this index.
+    NoSuchFace(u32),
+    /// The face doesn't have exactly three indices.
+    NotATriangle { face: u32, num_indices: u32 },
+    /// The face refers to a vertex that doesn't exist.
+    IndexOutOfRange { face: u32, index: u32 },
+}
+
+impl fmt::Display for SampleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SampleError::NoSuchFace(face) => write!(f, "there is no face {}", face),
+            SampleError::NotATriangle { face, num_indices } => write!(
+                f,
+                "face {} has {} indices, so it isn't a triangle",
+                face, num_indices
+            ),
+            SampleError::IndexOutOfRange { face, index } => write!(
+                f,
+                "face {} refers to vertex {}, which doesn't exist",
+                face, index
+            ),
+        }
+    }
+}
+
+impl Error for SampleError {}
+
+impl Mesh {
+    /// Interpolate `attr` at the point of triangle `face_index` with the barycentric
+    /// coordinates `bary`, where `bary[k]` is the weight of the `k`th index of the face. Returns
+    /// `Ok(None)` if the mesh doesn't have the attribute.
+    ///
+    /// Normals, tangents and bitangents are renormalized after interpolating. The weights are
+    /// used as they are, so they should add up to 1, like the barycentric coordinates of a
+    /// `raycast::Hit`. Polygons aren't supported, since the same point has different
+    /// coordinates depending on how the polygon is triangulated; import with
+    /// `Importer::triangulate` to sample them.
+    pub fn sample_attribute(
+        &self,
+        face_index: u32,
+        bary: [f32; 3],
+        attr: AttributeSelector,
+    ) -> Result<Option<SampledValue>, SampleError> {
+        let indices = self.triangle(face_index)?;
+        Ok(self.interpolate(indices, bary, attr))
+    }
+
+    /// Interpolate every vertex attribute at a point of a triangle, like `sample_attribute`.
+    pub fn sample_all(
+        &self,
+        face_index: u32,
+        bary: [f32; 3],
+    ) -> Result<SampledVertex, SampleError> {
+        let indices = self.triangle(face_index)?;
+        let vector = |attr| {
+            self.interpolate(indices, bary, attr)
+                .and_then(SampledValue::vector)
+        };
+        Ok(SampledVertex {
+            position: vector(AttributeSelector::Position).unwrap_or(Vector3D::new(0.0, 0.0, 0.0)),
+            normal: vector(AttributeSelector::Normal),
+            tangent: vector(AttributeSelector::Tangent),
+            bitangent: vector(AttributeSelector::Bitangent),
+            texture_coords: (0..self.mTextureCoords.len() as u32)
+                .map(|channel| vector(AttributeSelector::TextureCoords(channel)))
+                .collect(),
+            colors: (0..self.mColors.len() as u32)
+                .map(|set| {
+                    self.interpolate(indices, bary, AttributeSelector::Color(set))
+                        .and_then(SampledValue::color)
+                })
+                .collect(),
+        })
+    }
+
+    /// The indices of triangle `face_index`, checked against the number of vertices.
+    fn triangle(&self, face_index: u32) -> Result<[u32; 3], SampleError> {
+        let face = self
+            .face(face_index)
+            .ok_or(SampleError::NoSuchFace(face_index))?;
+        let indices = match *face.indices() {
+            [a, b, c] => [a, b, c],
+            ref other => {
+                return Err(SampleError::NotATriangle {
+                    face: face_index,
+                    num_indices: other.len() as u32,
+                })
+            }
+        };
+        match indices.iter().find(|&&index| index >= self.num_vertices()) {
+            Some(&index) => Err(SampleError::IndexOutOfRange {
+                face: face_index,
+                index,
+            }),
+            None => Ok(indices),
+        }
+    }
+
+    fn interpolate(
+        &self,
+        indices: [u32; 3],
+        bary: [f32; 3],
+        attr: AttributeSelector,
+    ) -> Option<SampledValue> {
+        let vector = match attr {
+            AttributeSelector::Position => weighted(indices, bary, |index| self.position(index))?,
+            AttributeSelector::Normal => {
+                weighted(indices, bary, |index| self.normal(index))?.normalize()
+            }
+            AttributeSelector::Tangent => {
+                weighted(indices, bary, |index| self.tangent(index))?.normalize()
+            }
+            AttributeSelector::Bitangent => {
+                weighted(indices, bary, |index| self.bitangent(index))?.normalize()
+            }
+            AttributeSelector::TextureCoords(channel) => {
+                self.mTextureCoords.get(channel as usize)?;
+                weighted(indices, bary, |index| self.texture_coord(channel, index))?
+            }
+            AttributeSelector::Color(set) => {
+                self.mColors.get(set as usize)?;
+                let mut sum = [0.0; 4];
+                for (&index, &weight) in indices.iter().zip(&bary) {
+                    let color: [f32; 4] = self.vertex_color(set, index)?.into();
+                    for (sum, component) in sum.iter_mut().zip(&color) {
+                        *sum += component * weight;
+                    }
+                }
+                return Some(SampledValue::Color(sum.into()));
+            }
+        };
+        Some(SampledValue::Vector(vector))
+    }
+}
+
+/// The sum of `value` at each of `indices`, weighted by `bary`, or `None` if any is missing.
+fn weighted<F>(indices: [u32; 3], bary: [f32; 3], value: F) -> Option<Vector3D>
+where
+    F: Fn(u32) -> Option<Vector3D>,
+{
+    let mut sum = Vector3D::new(0.0, 0.0, 0.0);
+    for (&index, &weight) in indices.iter().zip(&bary) {
+        sum = sum + value(index)? * weight;
+    }
+    Some(sum)
+}
@@ -1,5 +1,8 @@
 use ffi::*;
 
+use std::convert::TryFrom;
+use std::fmt;
+use std::mem;
 use std::ptr::NonNull;
 
 // Import all types
@@ -9,18 +12,139 @@ use super::light::*;
 use super::material::*;
 use super::mesh::*;
 use super::node::*;
+use super::overlay::{MaterialOverlay, MetadataOverlay};
+#[cfg(feature = "assimp-5-2")]
+use super::skeleton::*;
 use super::texture::*;
+use super::validation::ValidationFinding;
+use crate::import::ImportTimings;
+use crate::Error;
 
 /// The top-level scene type. This contains all the data in the imported file, such as
 /// individual meshes, bones for skeletal animation, cameras, lights, and a node
 /// heirarchy to organize all of these elements.
-pub struct Scene<'a>(&'a aiScene);
+///
+/// Everything borrowed from a scene, e.g. a `Mesh`, its name or the indices of one of its
+/// faces, borrows the `Scene` itself, so it can't be kept after the scene is dropped. Only the
+/// `unsafe` constructors, such as `Scene::from_raw` or the `from_raw` of the wrapper types,
+/// let the caller choose a lifetime instead.
+pub struct Scene<'a> {
+    raw: &'a aiScene,
+    ownership: Ownership,
+    deallocator: Deallocator,
+    diagnostics: ImportDiagnostics,
+}
+
+/// How an owned scene has to be freed, which depends on how it was created.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Deallocator {
+    /// Scenes from the `aiImportFile*` functions.
+    ReleaseImport,
+    /// Scenes from `aiCopyScene`.
+    FreeScene,
+}
+
+/// Whether a `Scene` is responsible for freeing the underlying `aiScene`, see
+/// `Scene::from_raw`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Ownership {
+    /// The scene is freed when the `Scene` is dropped, with `aiReleaseImport`, or with
+    /// `aiFreeScene` for copies made by `Scene::try_clone`.
+    Owned,
+    /// The scene is owned by someone else, e.g. a C++ `Assimp::Importer`, and is never freed
+    /// by this crate.
+    Borrowed,
+}
 
 impl Scene<'_> {
     /// Create a scene from a raw pointer to an original `aiScene` struct from the
     /// source library.
-    pub unsafe fn from_raw(inner: NonNull<aiScene>) -> Self {
-        Self(&*inner.as_ptr())
+    ///
+    /// # Safety
+    ///
+    /// `inner` must point to a valid, fully constructed `aiScene` which isn't modified while
+    /// the returned `Scene` exists, and which lives for the lifetime `'s` that the caller
+    /// chooses. Choose `'s` explicitly rather than letting it be inferred, e.g. as the lifetime
+    /// of a borrow of whatever owns a borrowed scene.
+    ///
+    /// With `Ownership::Owned`, the scene must have come from one of the `aiImportFile*`
+    /// functions (or `aiApplyPostProcessing`) and nothing else may free it, including another
+    /// owned `Scene`. Scenes created or copied by any other means must use
+    /// `Ownership::Borrowed`, because `aiReleaseImport` is the only way this crate frees them.
+    pub unsafe fn from_raw<'s>(inner: NonNull<aiScene>, ownership: Ownership) -> Scene<'s> {
+        if ownership == Ownership::Owned {
+            debug_tracking::acquire(inner.as_ptr());
+        }
+
+        Scene {
+            raw: &*inner.as_ptr(),
+            ownership,
+            deallocator: Deallocator::ReleaseImport,
+            diagnostics: ImportDiagnostics::default(),
+        }
+    }
+
+    /// Make an independent copy of the scene with `aiCopyScene`, e.g. to keep the original
+    /// around while applying destructive post-processing. The copy is always owned, even if
+    /// this scene is borrowed, and can outlive this scene.
+    ///
+    /// Assimp doesn't keep track of copies, so `Importer::apply_postprocessing` fails for
+    /// them. Post-process the imported scene and keep the copy as the original instead.
+    ///
+    /// Returns `Error::MissingData` if Assimp didn't return a copy.
+    pub fn try_clone<'b>(&self) -> Result<Scene<'b>, Error> {
+        let mut copy = std::ptr::null_mut();
+        unsafe { aiCopyScene(self.raw, &mut copy) };
+
+        let copy = NonNull::new(copy).ok_or_else(|| Error::MissingData {
+            what: "copy of the scene from aiCopyScene".to_owned(),
+        })?;
+        debug_tracking::acquire(copy.as_ptr());
+
+        Ok(Scene {
+            raw: unsafe { &*copy.as_ptr() },
+            ownership: Ownership::Owned,
+            deallocator: Deallocator::FreeScene,
+            diagnostics: self.diagnostics.clone(),
+        })
+    }
+
+    /// Whether this scene is a copy made by `try_clone`.
+    pub fn is_copy(&self) -> bool {
+        self.deallocator == Deallocator::FreeScene
+    }
+
+    /// Give up ownership of the scene and return the raw pointer, which will no longer be freed
+    /// when this `Scene` would have been dropped. For an owned scene, the caller is now
+    /// responsible for freeing it with `aiReleaseImport`, or with `aiFreeScene` if it's a copy
+    /// (see `is_copy`).
+    pub fn into_raw(mut self) -> *const aiScene {
+        let raw = self.raw as *const aiScene;
+
+        if self.ownership == Ownership::Owned {
+            debug_tracking::release(raw);
+        }
+        // Only the `aiScene` is handed over; the diagnostics are dropped as usual.
+        drop(mem::take(&mut self.diagnostics));
+        mem::forget(self);
+
+        raw
+    }
+
+    /// Whether this scene will be freed when it's dropped.
+    pub fn ownership(&self) -> Ownership {
+        self.ownership
+    }
+
+    /// What the importer recorded while importing this scene. Scenes that weren't imported by
+    /// an `Importer`, e.g. ones created with `from_raw`, have no diagnostics. Copies made with
+    /// `try_clone` have the same diagnostics as the original.
+    pub fn import_diagnostics(&self) -> &ImportDiagnostics {
+        &self.diagnostics
+    }
+
+    pub(crate) fn set_import_diagnostics(&mut self, diagnostics: ImportDiagnostics) {
+        self.diagnostics = diagnostics;
     }
 }
 
@@ -28,7 +152,7 @@ impl std::ops::Deref for Scene<'_> {
     type Target = aiScene;
 
     fn deref(&self) -> &Self::Target {
-        self.0
+        self.raw
     }
 }
 
@@ -45,11 +169,22 @@ impl Scene<'_> {
     }
 
     /// Returns true if any warnings were generated by the `validate_data_structure`
-    /// post-process step. The details of the warnings are written to the output log.
+    /// post-process step. The details of the warnings are written to the output log, and are
+    /// available from `validation_findings`.
     pub fn has_validation_warning(&self) -> bool {
         self.mFlags & AI_SCENE_FLAGS_VALIDATION_WARNING != 0
     }
 
+    /// The warnings of the `validate_data_structure` post-process step, in the order Assimp
+    /// logged them. This is empty if the step wasn't enabled when the scene was imported.
+    ///
+    /// The findings are parsed from the messages Assimp logs on the importing thread. Up to
+    /// 4096 messages are kept, so a file that logs more only gets the findings among the last
+    /// of them.
+    pub fn validation_findings(&self) -> &[ValidationFinding] {
+        &self.diagnostics.validation
+    }
+
     /// Returns true if the `join_identical_vertices` post-process step was run.
     pub fn is_non_verbose_format(&self) -> bool {
         self.mFlags & AI_SCENE_FLAGS_NON_VERBOSE_FORMAT != 0
@@ -65,6 +200,12 @@ impl Scene<'_> {
         unsafe { Some(Node::from_raw(NonNull::new(self.mRootNode)?)) }
     }
 
+    /// Metadata for the whole scene. The FBX importer, for example, stores the file's axes
+    /// and unit scale here as `UpAxis`, `UnitScaleFactor` and so on.
+    pub fn metadata(&self) -> Metadata<'_> {
+        unsafe { Metadata::from_raw(self.mMetaData) }
+    }
+
     /// Returns the number of meshes in the scene.
     pub fn num_meshes(&self) -> u32 {
         self.mNumMeshes
@@ -72,19 +213,22 @@ impl Scene<'_> {
 
     /// Returns an iterator over all the meshes in the scene.
     pub fn meshes(&self) -> MeshIter {
-        MeshIter::new(
-            NonNull::new(self.mMeshes as *mut *const aiMesh),
-            self.mNumMeshes as usize,
-        )
+        unsafe {
+            MeshIter::new(
+                NonNull::new(self.mMeshes as *mut *const aiMesh),
+                self.mNumMeshes as usize,
+            )
+        }
     }
 
     /// Return an individual mesh from the scene.
     pub fn mesh(&self, id: u32) -> Option<&Mesh> {
         if id < self.mNumMeshes {
             unsafe {
-                Some(Mesh::from_raw(NonNull::new(
-                    *(NonNull::new(self.mMeshes)?.as_ptr().offset(id as isize)),
-                )?))
+                Some(Mesh::from_raw(NonNull::new(*crate::array_element(
+                    self.mMeshes,
+                    id as usize,
+                )?)?))
             }
         } else {
             None
@@ -98,25 +242,40 @@ impl Scene<'_> {
 
     /// Returns an iterator over all the materials in the scene.
     pub fn materials(&self) -> MaterialIter {
-        MaterialIter::new(
-            NonNull::new(self.mMaterials as *mut *const aiMaterial),
-            self.mNumMaterials as usize,
-        )
+        unsafe {
+            MaterialIter::new(
+                NonNull::new(self.mMaterials as *mut *const aiMaterial),
+                self.mNumMaterials as usize,
+            )
+        }
     }
 
     /// Get the nth material definition in the scene
     pub fn material(&self, id: u32) -> Option<&Material> {
         if id < self.mNumMaterials {
             unsafe {
-                Some(Material::from_raw(NonNull::new(
-                    *(NonNull::new(self.mMaterials)?.as_ptr().offset(id as isize)),
-                )?))
+                Some(Material::from_raw(NonNull::new(*crate::array_element(
+                    self.mMaterials,
+                    id as usize,
+                )?)?))
             }
         } else {
             None
         }
     }
 
+    /// Returns a pass-through overlay of the scene's materials, which can be used to override
+    /// material properties without modifying the scene.
+    pub fn material_overlay(&self) -> MaterialOverlay<'_> {
+        MaterialOverlay::new(self)
+    }
+
+    /// Returns a pass-through overlay of the node metadata, which can be used to add, change or
+    /// remove metadata entries without modifying the scene.
+    pub fn metadata_overlay(&self) -> MetadataOverlay<'_> {
+        MetadataOverlay::new(self)
+    }
+
     /// Returns the number of animations in the scene.
     pub fn num_animations(&self) -> u32 {
         self.mNumAnimations
@@ -124,19 +283,22 @@ impl Scene<'_> {
 
     /// Returns an iterator over all the animations in the scene.
     pub fn animations(&self) -> AnimationIter {
-        AnimationIter::new(
-            NonNull::new(self.mAnimations as *mut *const aiAnimation),
-            self.mNumAnimations as usize,
-        )
+        unsafe {
+            AnimationIter::new(
+                NonNull::new(self.mAnimations as *mut *const aiAnimation),
+                self.mNumAnimations as usize,
+            )
+        }
     }
 
     /// Return an individual animation from the scene.
     pub fn animation(&self, id: u32) -> Option<&Animation> {
         if id < self.mNumAnimations {
             unsafe {
-                Some(Animation::from_raw(NonNull::new(
-                    *(NonNull::new(self.mAnimations)?.as_ptr().offset(id as isize)),
-                )?))
+                Some(Animation::from_raw(NonNull::new(*crate::array_element(
+                    self.mAnimations,
+                    id as usize,
+                )?)?))
             }
         } else {
             None
@@ -150,10 +312,59 @@ impl Scene<'_> {
 
     /// Returns an iterator over all the textures in the scene, if any.
     pub fn textures(&self) -> TextureIter {
-        TextureIter::new(
-            NonNull::new(self.mTextures as *mut *const aiTexture),
-            self.mNumTextures as usize,
-        )
+        unsafe {
+            TextureIter::new(
+                NonNull::new(self.mTextures as *mut *const aiTexture),
+                self.mNumTextures as usize,
+            )
+        }
+    }
+
+    /// Return an individual texture from the scene.
+    pub fn texture(&self, id: u32) -> Option<&Texture> {
+        if id < self.mNumTextures {
+            unsafe {
+                Some(Texture::from_raw(NonNull::new(*crate::array_element(
+                    self.mTextures,
+                    id as usize,
+                )?)?))
+            }
+        } else {
+            None
+        }
+    }
+
+    /// The embedded texture that a material's texture path refers to, or `None` if the path
+    /// refers to a file instead. Paths of the form `*0`, `*1` and so on refer to textures by
+    /// their index, and some formats refer to them by `Texture::filename` instead.
+    ///
+    /// Returns `Error::IndexOutOfRange` if the path refers to an index past the end of
+    /// `textures`, or `Error::MissingData` if the texture with that index is null, which only
+    /// happens for broken files.
+    pub fn embedded_texture(&self, path: &str) -> Result<Option<&Texture>, Error> {
+        let index = if path.starts_with('*') {
+            path[1..].parse().ok()
+        } else {
+            None
+        };
+        if let Some(index) = index {
+            let len = self.mNumTextures as usize;
+            return match u32::try_from(index).ok().and_then(|id| self.texture(id)) {
+                Some(texture) => Ok(Some(texture)),
+                None if index < len => Err(Error::MissingData {
+                    what: format!("texture {}", index),
+                }),
+                None => Err(Error::IndexOutOfRange {
+                    what: "textures",
+                    index,
+                    len,
+                }),
+            };
+        }
+
+        Ok(self
+            .textures()
+            .find(|texture| texture.eq_filename(path.as_bytes())))
     }
 
     /// Returns the number of lights in the scene.
@@ -163,10 +374,56 @@ impl Scene<'_> {
 
     /// Returns an iterator over all the lights in the scene.
     pub fn light(&self) -> LightIter {
-        LightIter::new(
-            NonNull::new(self.mLights as *mut *const aiLight),
-            self.mNumLights as usize,
-        )
+        unsafe {
+            LightIter::new(
+                NonNull::new(self.mLights as *mut *const aiLight),
+                self.mNumLights as usize,
+            )
+        }
+    }
+
+    /// Group the meshes in the scene that appear to have come from the same original mesh,
+    /// for example after `split_large_meshes` has split one mesh into several pieces. Assimp
+    /// doesn't record this directly, so the grouping is a heuristic: meshes are grouped if
+    /// they have the same material and the same name once any trailing `-N`, `_N` or `.N`
+    /// numeric suffix has been stripped.
+    ///
+    /// This can group meshes that were always separate, such as `wheel_1` and `wheel_2`
+    /// sharing a material. Use `mesh_groups_with` to supply your own grouping key.
+    pub fn mesh_groups(&self) -> Vec<MeshGroup> {
+        self.mesh_groups_with(|mesh| {
+            (
+                strip_numeric_suffix(mesh.name()).to_owned(),
+                mesh.material_id(),
+            )
+        })
+    }
+
+    /// Like `mesh_groups`, but meshes are grouped according to the `(name, material)` key
+    /// returned by `key`. Groups are returned in order of the first mesh in each group.
+    pub fn mesh_groups_with<F>(&self, key: F) -> Vec<MeshGroup>
+    where
+        F: Fn(&Mesh) -> (String, u32),
+    {
+        let mut groups: Vec<MeshGroup> = Vec::new();
+
+        for (index, mesh) in self.meshes().enumerate() {
+            let (name, material_id) = key(mesh);
+
+            match groups
+                .iter_mut()
+                .find(|group| group.material_id == material_id && group.name == name)
+            {
+                Some(group) => group.meshes.push(index as u32),
+                None => groups.push(MeshGroup {
+                    name,
+                    material_id,
+                    meshes: vec![index as u32],
+                }),
+            }
+        }
+
+        groups
     }
 
     /// Returns the number of cameras in the scene.
@@ -176,19 +433,169 @@ impl Scene<'_> {
 
     /// Returns an iterator over all the cameras in the scene.
     pub fn camera(&self) -> CameraIter {
-        CameraIter::new(
-            NonNull::new(self.mCameras as *mut *const aiCamera),
-            self.mNumCameras as usize,
-        )
+        unsafe {
+            CameraIter::new(
+                NonNull::new(self.mCameras as *mut *const aiCamera),
+                self.mNumCameras as usize,
+            )
+        }
+    }
+
+    /// Returns the number of skeletons in the scene.
+    #[cfg(feature = "assimp-5-2")]
+    pub fn num_skeletons(&self) -> u32 {
+        self.mNumSkeletons
+    }
+
+    /// Returns an iterator over all the skeletons in the scene. Only some importers, such as
+    /// glTF and FBX, create skeletons.
+    #[cfg(feature = "assimp-5-2")]
+    pub fn skeletons(&self) -> SkeletonIter {
+        unsafe {
+            SkeletonIter::new(
+                NonNull::new(self.mSkeletons as *mut *const aiSkeleton),
+                self.mNumSkeletons as usize,
+            )
+        }
+    }
+}
+
+/// A set of meshes that appear to have come from the same original mesh, see
+/// `Scene::mesh_groups`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MeshGroup {
+    /// The name shared by the meshes in this group, without any split suffix.
+    pub name: String,
+    /// The material shared by the meshes in this group.
+    pub material_id: u32,
+    /// Indices of the meshes in this group, in the order they appear in the scene.
+    pub meshes: Vec<u32>,
+}
+
+/// Information recorded while importing a scene, see `Scene::import_diagnostics`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ImportDiagnostics {
+    /// The effect of `Importer::optimize_meshes`, if it ran.
+    pub optimize_meshes: Option<OptimizeMeshesReport>,
+    /// What `Importer::validate_data_structure` found, see `Scene::validation_findings`.
+    pub validation: Vec<ValidationFinding>,
+    /// How long the import took, if the importer was `Importer::instrumented`.
+    pub timings: Option<ImportTimings>,
+}
+
+/// The number of meshes in a scene before and after `Importer::optimize_meshes`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct OptimizeMeshesReport {
+    pub meshes_before: u32,
+    pub meshes_after: u32,
+}
+
+fn strip_numeric_suffix(name: &str) -> &str {
+    let trimmed = name.trim_end_matches(|c: char| c.is_ascii_digit());
+
+    if trimmed.len() == name.len() {
+        return name;
+    }
+
+    match trimmed.chars().last() {
+        Some('-') | Some('_') | Some('.') => &trimmed[..trimmed.len() - 1],
+        _ => name,
+    }
+}
+
+/// Copies with `try_clone`, which only fails if Assimp runs out of memory.
+impl Clone for Scene<'_> {
+    fn clone(&self) -> Self {
+        self.try_clone().expect("couldn't copy the scene")
+    }
+}
+
+/// Prints the number of each kind of top-level item in the scene, but not the items.
+impl fmt::Debug for Scene<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut scene = f.debug_struct("Scene");
+        scene
+            .field("num_meshes", &self.num_meshes())
+            .field("num_materials", &self.num_materials())
+            .field("num_animations", &self.num_animations())
+            .field("num_textures", &self.num_textures())
+            .field("num_lights", &self.num_lights())
+            .field("num_cameras", &self.num_cameras());
+        #[cfg(feature = "assimp-5-2")]
+        scene.field("num_skeletons", &self.num_skeletons());
+        scene.finish()
     }
 }
 
 // Drop implementation for a scene owned by Assimp.
-// Scenes returned by aiImportFile* methods must be freed with aiReleaseImport.
+// Scenes returned by aiImportFile* methods must be freed with aiReleaseImport, and copies
+// made with aiCopyScene with aiFreeScene. A `Scene` always has a scene to release: failed
+// imports return an error without making one, including when Assimp frees the scene because
+// post-processing failed, and `raw` can't be null.
 impl Drop for Scene<'_> {
     fn drop(&mut self) {
-        unsafe {
-            aiReleaseImport(self.0);
+        if self.ownership == Ownership::Owned {
+            debug_tracking::release(self.raw);
+            unsafe {
+                match self.deallocator {
+                    Deallocator::ReleaseImport => aiReleaseImport(self.raw),
+                    Deallocator::FreeScene => aiFreeScene(self.raw),
+                }
+            }
         }
     }
 }
+
+/// In debug builds, keep track of which scenes are owned so that freeing one twice, or owning
+/// one from two `Scene`s, triggers an assertion instead of undefined behaviour. `Scene` isn't
+/// `Send`, so a scene is always released on the thread that acquired it.
+#[cfg(debug_assertions)]
+mod debug_tracking {
+    use ffi::aiScene;
+    use std::cell::RefCell;
+    use std::collections::HashSet;
+
+    thread_local! {
+        static OWNED: RefCell<HashSet<usize>> = RefCell::new(HashSet::new());
+    }
+
+    pub fn acquire(scene: *const aiScene) {
+        let inserted = OWNED.with(|owned| owned.borrow_mut().insert(scene as usize));
+        assert!(
+            inserted,
+            "scene {:p} is already owned by another `Scene`",
+            scene
+        );
+    }
+
+    pub fn release(scene: *const aiScene) {
+        let removed = OWNED.with(|owned| owned.borrow_mut().remove(&(scene as usize)));
+        assert!(removed, "scene {:p} was released twice", scene);
+    }
+
+    #[cfg(feature = "test-fixtures")]
+    pub fn is_owned(scene: *const aiScene) -> bool {
+        OWNED.with(|owned| owned.borrow().contains(&(scene as usize)))
+    }
+}
+
+#[cfg(not(debug_assertions))]
+mod debug_tracking {
+    use ffi::aiScene;
+
+    pub fn acquire(_: *const aiScene) {}
+
+    pub fn release(_: *const aiScene) {}
+
+    #[cfg(feature = "test-fixtures")]
+    pub fn is_owned(_: *const aiScene) -> bool {
+        false
+    }
+}
+
+/// Whether `scene` is currently owned by a `Scene` on this thread, for
+/// `fixtures::is_owned_scene`.
+#[cfg(feature = "test-fixtures")]
+pub(crate) fn is_owned_scene(scene: *const aiScene) -> bool {
+    debug_tracking::is_owned(scene)
+}
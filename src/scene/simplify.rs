@@ -0,0 +1,684 @@
+//! Mesh simplification by edge collapse, e.g. for generating levels of detail.
+//!
+//! `simplify` repeatedly collapses the edge whose removal changes the shape the least, as
+//! measured by the quadric error metric of Garland and Heckbert, until the target is reached.
+//! It works on the owned `MeshData` of a `SceneSnapshot`, so the imported scene is left as
+//! it is, and returns a new mesh.
+//!
+//! Only triangles are simplified. The vertices of points, lines and polygons are locked, so
+//! those faces are kept as they are; import with `triangulate` to simplify polygons too.
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashSet};
+
+use crate::math::{Color4D, Vector3D};
+
+use super::snapshot::{BoneData, MeshData};
+
+/// When `simplify` stops collapsing edges.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SimplifyTarget {
+    /// Keep this fraction of the triangles, e.g. `0.25` for a quarter. The result can have
+    /// more triangles if collapsing any more edges would damage the mesh.
+    Ratio(f32),
+    /// Collapse edges while the error stays below this distance, in the units of the mesh.
+    /// The error of a vertex is the square root of the sum of the squared distances to the
+    /// planes of the original triangles around it.
+    MaxError(f32),
+}
+
+/// Options for `simplify_with`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct SimplifyOptions {
+    /// Never move vertices on the boundary of the mesh, i.e. on edges with only one triangle.
+    /// Vertices are split along UV and normal seams, so this also keeps seams from being
+    /// smeared or pulled apart, at the cost of simplifying the mesh less.
+    pub lock_boundary: bool,
+}
+
+/// Simplify the triangles of `mesh` until `target` is reached, with
+/// `SimplifyOptions::default()`. See `simplify_with`.
+pub fn simplify(mesh: &MeshData, target: SimplifyTarget) -> MeshData {
+    simplify_with(mesh, target, SimplifyOptions::default())
+}
+
+/// Simplify the triangles of `mesh` until `target` is reached.
+///
+/// Each collapse merges the two vertices of an edge into one, at the position with the least
+/// error, and interpolates the normals, tangents, bitangents, colors and UVs of the vertices
+/// along the edge to that position. The merged vertex takes the bone weights of the vertex it's
+/// closest to. Collapses that would flip a triangle, or make the mesh non-manifold, are
+/// skipped, so the input doesn't have to be manifold. The result only depends on the input.
+///
+/// The vertices of the result are the remaining vertices in their original order. Faces with
+/// indices out of range, and triangles with a repeated index, are removed. Vertex attributes
+/// that don't have one entry per vertex are removed too, since they can't be interpolated.
+pub fn simplify_with(mesh: &MeshData, target: SimplifyTarget, opts: SimplifyOptions) -> MeshData {
+    let mut simplifier = Simplifier::new(mesh, opts);
+    simplifier.run(target);
+    simplifier.finish()
+}
+
+type Point = [f64; 3];
+
+/// The symmetric 4x4 matrix of a quadric, as the upper triangle in row order.
+#[derive(Copy, Clone, Default)]
+struct Quadric([f64; 10]);
+
+impl Quadric {
+    /// The squared distance to the plane through `p` with unit normal `n`.
+    fn plane(n: Point, p: Point) -> Quadric {
+        let [a, b, c] = n;
+        let d = -dot(n, p);
+        Quadric([
+            a * a,
+            a * b,
+            a * c,
+            a * d,
+            b * b,
+            b * c,
+            b * d,
+            c * c,
+            c * d,
+            d * d,
+        ])
+    }
+
+    fn add(&mut self, other: &Quadric) {
+        for (a, b) in self.0.iter_mut().zip(other.0.iter()) {
+            *a += b;
+        }
+    }
+
+    fn error(&self, p: Point) -> f64 {
+        let q = &self.0;
+        let [x, y, z] = p;
+        let error = q[0] * x * x
+            + 2.0 * q[1] * x * y
+            + 2.0 * q[2] * x * z
+            + 2.0 * q[3] * x
+            + q[4] * y * y
+            + 2.0 * q[5] * y * z
+            + 2.0 * q[6] * y
+            + q[7] * z * z
+            + 2.0 * q[8] * z
+            + q[9];
+        // Rounding can make the error slightly negative.
+        error.max(0.0)
+    }
+
+    /// The position with the least error, if the quadric isn't close to singular.
+    fn minimum(&self) -> Option<Point> {
+        let q = &self.0;
+        let m = [[q[0], q[1], q[2]], [q[1], q[4], q[5]], [q[2], q[5], q[7]]];
+        let rhs = [-q[3], -q[6], -q[8]];
+
+        let det = dot(m[0], cross(m[1], m[2]));
+        let trace = q[0] + q[4] + q[7];
+        if !det.is_finite() || det.abs() <= 1e-6 * trace * trace * trace {
+            return None;
+        }
+
+        // Cramer's rule, with the columns of the symmetric matrix as its rows.
+        let solve = |k: usize| {
+            let mut columns = m;
+            columns[k] = rhs;
+            dot(columns[0], cross(columns[1], columns[2])) / det
+        };
+        Some([solve(0), solve(1), solve(2)])
+    }
+}
+
+/// An edge collapse in the queue, ordered so that the cheapest is popped first.
+struct Candidate {
+    cost: f64,
+    /// The vertex that is kept, and the one that is merged into it.
+    keep: u32,
+    remove: u32,
+    position: Point,
+    /// The versions of the two vertices when this was queued. If either has changed since,
+    /// the candidate is out of date.
+    versions: (u32, u32),
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed, since `BinaryHeap` pops the largest. Ties are broken by the vertices so
+        // that the order doesn't depend on how the heap is laid out.
+        other
+            .cost
+            .partial_cmp(&self.cost)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| (other.keep, other.remove).cmp(&(self.keep, self.remove)))
+    }
+}
+
+struct Simplifier<'a> {
+    mesh: &'a MeshData,
+    positions: Vec<Point>,
+    normals: Option<Vec<Vector3D>>,
+    tangents: Option<Vec<Vector3D>>,
+    bitangents: Option<Vec<Vector3D>>,
+    colors: Vec<Option<Vec<Color4D>>>,
+    texture_coords: Vec<Option<Vec<Vector3D>>>,
+    /// The `(bone, weight)` pairs of each vertex.
+    weights: Vec<Vec<(usize, f32)>>,
+
+    quadrics: Vec<Quadric>,
+    locked: Vec<bool>,
+    alive: Vec<bool>,
+    versions: Vec<u32>,
+    /// The triangles around each vertex, including ones that have been removed.
+    vertex_triangles: Vec<Vec<usize>>,
+    triangles: Vec<[u32; 3]>,
+    triangle_alive: Vec<bool>,
+    num_triangles: usize,
+    queue: BinaryHeap<Candidate>,
+}
+
+impl<'a> Simplifier<'a> {
+    fn new(mesh: &'a MeshData, opts: SimplifyOptions) -> Self {
+        let num_vertices = mesh.positions.len();
+        let attribute = |values: &Vec<Vector3D>| {
+            if values.len() == num_vertices {
+                Some(values.clone())
+            } else {
+                None
+            }
+        };
+
+        let mut weights = vec![Vec::new(); num_vertices];
+        for (bone, data) in mesh.bones.iter().enumerate() {
+            for &(vertex, weight) in &data.weights {
+                if let Some(weights) = weights.get_mut(vertex as usize) {
+                    weights.push((bone, weight));
+                }
+            }
+        }
+
+        let mut simplifier = Simplifier {
+            mesh,
+            positions: mesh.positions.iter().map(|&p| to_point(p)).collect(),
+            normals: attribute(&mesh.normals),
+            tangents: attribute(&mesh.tangents),
+            bitangents: attribute(&mesh.bitangents),
+            colors: mesh
+                .colors
+                .iter()
+                .map(|set| set.clone().filter(|set| set.len() == num_vertices))
+                .collect(),
+            texture_coords: mesh
+                .texture_coords
+                .iter()
+                .map(|channel| channel.as_ref().and_then(&attribute))
+                .collect(),
+            weights,
+            quadrics: vec![Quadric::default(); num_vertices],
+            locked: vec![false; num_vertices],
+            alive: vec![true; num_vertices],
+            versions: vec![0; num_vertices],
+            vertex_triangles: vec![Vec::new(); num_vertices],
+            triangles: Vec::new(),
+            triangle_alive: Vec::new(),
+            num_triangles: 0,
+            queue: BinaryHeap::new(),
+        };
+
+        for face in &mesh.faces {
+            if face.iter().any(|&index| index as usize >= num_vertices) {
+                continue;
+            }
+            if face.len() != 3 {
+                for &index in face {
+                    simplifier.locked[index as usize] = true;
+                }
+                continue;
+            }
+            let triangle = [face[0], face[1], face[2]];
+            if triangle[0] == triangle[1]
+                || triangle[1] == triangle[2]
+                || triangle[2] == triangle[0]
+            {
+                continue;
+            }
+
+            let index = simplifier.triangles.len();
+            simplifier.triangles.push(triangle);
+            simplifier.triangle_alive.push(true);
+            for &vertex in &triangle {
+                simplifier.vertex_triangles[vertex as usize].push(index);
+            }
+        }
+        simplifier.num_triangles = simplifier.triangles.len();
+
+        simplifier.init_quadrics(opts);
+        for vertex in 0..num_vertices as u32 {
+            simplifier.queue_edges(vertex);
+        }
+        simplifier
+    }
+
+    fn init_quadrics(&mut self, opts: SimplifyOptions) {
+        for &[a, b, c] in &self.triangles {
+            let (pa, pb, pc) = (
+                self.positions[a as usize],
+                self.positions[b as usize],
+                self.positions[c as usize],
+            );
+            let normal = cross(sub(pb, pa), sub(pc, pa));
+            if length(normal) == 0.0 {
+                continue;
+            }
+            let quadric = Quadric::plane(normalize(normal), pa);
+            for &vertex in &[a, b, c] {
+                self.quadrics[vertex as usize].add(&quadric);
+            }
+        }
+
+        // Boundary edges have only one triangle, so their direction is unknown.
+        let mut edges: HashSet<(u32, u32)> = HashSet::new();
+        for &[a, b, c] in &self.triangles {
+            edges.extend(&[(a, b), (b, c), (c, a)]);
+        }
+        for &[a, b, c] in &self.triangles {
+            for &(from, to, opposite) in &[(a, b, c), (b, c, a), (c, a, b)] {
+                if edges.contains(&(to, from)) {
+                    continue;
+                }
+                if opts.lock_boundary {
+                    self.locked[from as usize] = true;
+                    self.locked[to as usize] = true;
+                    continue;
+                }
+
+                // Otherwise keep the boundary in place with a plane through the edge, at right
+                // angles to the triangle.
+                let (pa, pb, pc) = (
+                    self.positions[from as usize],
+                    self.positions[to as usize],
+                    self.positions[opposite as usize],
+                );
+                let edge = sub(pb, pa);
+                let normal = cross(edge, cross(sub(pc, pa), edge));
+                if length(normal) == 0.0 {
+                    continue;
+                }
+                let quadric = Quadric::plane(normalize(normal), pa);
+                self.quadrics[from as usize].add(&quadric);
+                self.quadrics[to as usize].add(&quadric);
+            }
+        }
+    }
+
+    /// The live vertices that share a live triangle with `vertex`, in ascending order.
+    fn neighbours(&self, vertex: u32) -> Vec<u32> {
+        let mut neighbours: Vec<u32> = self.vertex_triangles[vertex as usize]
+            .iter()
+            .filter(|&&t| self.triangle_alive[t])
+            .flat_map(|&t| self.triangles[t].to_vec())
+            .filter(|&other| other != vertex)
+            .collect();
+        neighbours.sort_unstable();
+        neighbours.dedup();
+        neighbours
+    }
+
+    fn queue_edges(&mut self, vertex: u32) {
+        for other in self.neighbours(vertex) {
+            if let Some(candidate) = self.candidate(vertex, other) {
+                self.queue.push(candidate);
+            }
+        }
+    }
+
+    /// The cheapest collapse of the edge between `a` and `b`, unless both are locked.
+    fn candidate(&self, a: u32, b: u32) -> Option<Candidate> {
+        let (a, b) = (a.min(b), a.max(b));
+        let (pa, pb) = (self.positions[a as usize], self.positions[b as usize]);
+        let mut quadric = self.quadrics[a as usize];
+        quadric.add(&self.quadrics[b as usize]);
+
+        let (keep, remove, position) = match (self.locked[a as usize], self.locked[b as usize]) {
+            (true, true) => return None,
+            (true, false) => (a, b, pa),
+            (false, true) => (b, a, pb),
+            (false, false) => {
+                let midpoint = scale(add(pa, pb), 0.5);
+                // The minimum of a nearly flat quadric can be far away, so it's only used
+                // if it's close to the edge.
+                let minimum = quadric
+                    .minimum()
+                    .filter(|&p| length(sub(p, midpoint)) <= length(sub(pb, pa)));
+                let position = minimum.unwrap_or_else(|| {
+                    let mut best = pa;
+                    for &p in &[midpoint, pb] {
+                        if quadric.error(p) < quadric.error(best) {
+                            best = p;
+                        }
+                    }
+                    best
+                });
+                (a, b, position)
+            }
+        };
+
+        let cost = quadric.error(position);
+        if !cost.is_finite() {
+            return None;
+        }
+        Some(Candidate {
+            cost,
+            keep,
+            remove,
+            position,
+            versions: (self.versions[keep as usize], self.versions[remove as usize]),
+        })
+    }
+
+    fn run(&mut self, target: SimplifyTarget) {
+        let (min_triangles, max_cost) = match target {
+            SimplifyTarget::Ratio(ratio) => {
+                let ratio = ratio.clamp(0.0, 1.0) as f64;
+                (
+                    (self.num_triangles as f64 * ratio).round() as usize,
+                    f64::INFINITY,
+                )
+            }
+            SimplifyTarget::MaxError(error) => (0, error as f64 * error as f64),
+        };
+
+        while self.num_triangles > min_triangles {
+            let candidate = match self.queue.pop() {
+                Some(candidate) => candidate,
+                None => break,
+            };
+            if candidate.cost > max_cost {
+                break;
+            }
+
+            let (keep, remove) = (candidate.keep as usize, candidate.remove as usize);
+            let current = (self.versions[keep], self.versions[remove]);
+            if !self.alive[keep] || !self.alive[remove] || candidate.versions != current {
+                continue;
+            }
+            if self.can_collapse(&candidate) {
+                self.collapse(&candidate);
+            }
+        }
+    }
+
+    /// Whether collapsing keeps the mesh manifold and doesn't flip any triangles.
+    fn can_collapse(&self, candidate: &Candidate) -> bool {
+        let (keep, remove) = (candidate.keep, candidate.remove);
+
+        // The edge must have one or two triangles, and the only vertices next to both ends
+        // must be the opposite corners of those triangles. Otherwise the collapse would pinch
+        // the surface.
+        let mut shared = 0;
+        let mut opposite: Vec<u32> = Vec::new();
+        for &t in &self.vertex_triangles[remove as usize] {
+            let triangle = self.triangles[t];
+            if self.triangle_alive[t] && triangle.contains(&keep) {
+                shared += 1;
+                opposite.extend(triangle.iter().filter(|&&v| v != keep && v != remove));
+            }
+        }
+        if shared == 0 || shared > 2 {
+            return false;
+        }
+        let keep_neighbours = self.neighbours(keep);
+        let common = self
+            .neighbours(remove)
+            .into_iter()
+            .filter(|v| keep_neighbours.binary_search(v).is_ok())
+            .count();
+        if common != opposite.len() {
+            return false;
+        }
+
+        for &vertex in &[keep, remove] {
+            for &t in &self.vertex_triangles[vertex as usize] {
+                let triangle = self.triangles[t];
+                if !self.triangle_alive[t]
+                    || (triangle.contains(&keep) && triangle.contains(&remove))
+                {
+                    continue;
+                }
+
+                let corners = |moved: Option<Point>| {
+                    let mut corners = [[0.0; 3]; 3];
+                    for (corner, &v) in corners.iter_mut().zip(&triangle) {
+                        *corner = match moved {
+                            Some(p) if v == vertex => p,
+                            _ => self.positions[v as usize],
+                        };
+                    }
+                    corners
+                };
+                let before = triangle_normal(corners(None));
+                let after = triangle_normal(corners(Some(candidate.position)));
+                // Reject flipped triangles, and ones that would have no area.
+                if length(after) == 0.0 || dot(normalize(before), normalize(after)) < 0.2 {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    fn collapse(&mut self, candidate: &Candidate) {
+        let (keep, remove) = (candidate.keep as usize, candidate.remove as usize);
+        let (pa, pb) = (self.positions[keep], self.positions[remove]);
+        let edge = sub(pb, pa);
+        let t = if dot(edge, edge) > 0.0 {
+            (dot(sub(candidate.position, pa), edge) / dot(edge, edge)).clamp(0.0, 1.0) as f32
+        } else {
+            0.0
+        };
+
+        self.positions[keep] = candidate.position;
+        let directions = self
+            .normals
+            .iter_mut()
+            .chain(&mut self.tangents)
+            .chain(&mut self.bitangents);
+        for values in directions {
+            values[keep] = values[keep].lerp(values[remove], t).normalize();
+        }
+        for values in self.texture_coords.iter_mut().flatten() {
+            values[keep] = values[keep].lerp(values[remove], t);
+        }
+        for values in self.colors.iter_mut().flatten() {
+            values[keep] = values[keep].lerp(values[remove], t);
+        }
+        if t > 0.5 {
+            self.weights[keep] = std::mem::take(&mut self.weights[remove]);
+        }
+
+        let quadric = self.quadrics[remove];
+        self.quadrics[keep].add(&quadric);
+        self.alive[remove] = false;
+        self.versions[keep] += 1;
+        self.versions[remove] += 1;
+
+        let triangles = std::mem::take(&mut self.vertex_triangles[remove]);
+        for t in triangles {
+            if !self.triangle_alive[t] {
+                continue;
+            }
+            let triangle = &mut self.triangles[t];
+            if triangle.contains(&(keep as u32)) {
+                self.triangle_alive[t] = false;
+                self.num_triangles -= 1;
+            } else {
+                for vertex in triangle.iter_mut().filter(|v| **v == remove as u32) {
+                    *vertex = keep as u32;
+                }
+                self.vertex_triangles[keep].push(t);
+            }
+        }
+        let triangle_alive = &self.triangle_alive;
+        self.vertex_triangles[keep].retain(|&t| triangle_alive[t]);
+
+        self.queue_edges(keep as u32);
+    }
+
+    fn finish(self) -> MeshData {
+        let mesh = self.mesh;
+        let mut remap = vec![None; self.positions.len()];
+        let mut next = 0;
+        for (vertex, &alive) in self.alive.iter().enumerate() {
+            if alive {
+                remap[vertex] = Some(next);
+                next += 1;
+            }
+        }
+        let alive = &self.alive;
+
+        // Keep the faces in their original order, with the simplified triangles in place of
+        // the triangles they came from.
+        let mut triangles = self
+            .triangles
+            .iter()
+            .zip(&self.triangle_alive)
+            .map(|(triangle, &alive)| Some(*triangle).filter(|_| alive));
+        let mut faces = Vec::new();
+        for face in &mesh.faces {
+            if face.iter().any(|&index| index as usize >= remap.len()) {
+                continue;
+            }
+            if face.len() == 3 {
+                if face[0] == face[1] || face[1] == face[2] || face[2] == face[0] {
+                    continue;
+                }
+                if let Some(Some(triangle)) = triangles.next() {
+                    faces.push(triangle.iter().filter_map(|&v| remap[v as usize]).collect());
+                }
+            } else {
+                faces.push(face.iter().filter_map(|&v| remap[v as usize]).collect());
+            }
+        }
+
+        let mut bones: Vec<BoneData> = mesh
+            .bones
+            .iter()
+            .map(|bone| BoneData {
+                weights: Vec::new(),
+                ..bone.clone()
+            })
+            .collect();
+        for (vertex, weights) in self.weights.iter().enumerate() {
+            if let Some(index) = remap[vertex] {
+                for &(bone, weight) in weights {
+                    bones[bone].weights.push((index, weight));
+                }
+            }
+        }
+
+        MeshData {
+            name: mesh.name.clone(),
+            material_index: mesh.material_index,
+            positions: compact(
+                self.positions.iter().map(|&p| to_vector(p)).collect(),
+                alive,
+            ),
+            normals: self
+                .normals
+                .map(|values| compact(values, alive))
+                .unwrap_or_default(),
+            tangents: self
+                .tangents
+                .map(|values| compact(values, alive))
+                .unwrap_or_default(),
+            bitangents: self
+                .bitangents
+                .map(|values| compact(values, alive))
+                .unwrap_or_default(),
+            colors: self
+                .colors
+                .into_iter()
+                .map(|set| set.map(|values| compact(values, alive)))
+                .collect(),
+            texture_coords: self
+                .texture_coords
+                .into_iter()
+                .map(|channel| channel.map(|values| compact(values, alive)))
+                .collect(),
+            faces,
+            bones,
+        }
+    }
+}
+
+/// The values of the vertices that are `alive`.
+fn compact<T>(values: Vec<T>, alive: &[bool]) -> Vec<T> {
+    values
+        .into_iter()
+        .zip(alive)
+        .filter(|&(_, &alive)| alive)
+        .map(|(value, _)| value)
+        .collect()
+}
+
+fn to_point(v: Vector3D) -> Point {
+    [v.x as f64, v.y as f64, v.z as f64]
+}
+
+fn to_vector(p: Point) -> Vector3D {
+    Vector3D::new(p[0] as f32, p[1] as f32, p[2] as f32)
+}
+
+fn add(a: Point, b: Point) -> Point {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+fn sub(a: Point, b: Point) -> Point {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn scale(a: Point, factor: f64) -> Point {
+    [a[0] * factor, a[1] * factor, a[2] * factor]
+}
+
+fn dot(a: Point, b: Point) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: Point, b: Point) -> Point {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn length(a: Point) -> f64 {
+    dot(a, a).sqrt()
+}
+
+fn normalize(a: Point) -> Point {
+    let len = length(a);
+    if len == 0.0 {
+        a
+    } else {
+        scale(a, 1.0 / len)
+    }
+}
+
+fn triangle_normal([a, b, c]: [Point; 3]) -> Point {
+    cross(sub(b, a), sub(c, a))
+}
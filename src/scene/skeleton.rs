@@ -0,0 +1,126 @@
+use ffi::{aiSkeleton, aiSkeletonBone};
+
+use std::ptr::NonNull;
+use std::str::Utf8Error;
+
+use super::mesh::{Mesh, VertexWeight, VertexWeightIter};
+use super::node::Node;
+use crate::math::Matrix4x4;
+
+define_type_and_iterator_indirect! {
+    /// Skeleton type. Assimp 5.2 added skeletons as an alternative to matching bones to nodes
+    /// by name.
+    struct Skeleton(&aiSkeleton)
+    /// Skeleton iterator type.
+    struct SkeletonIter
+}
+
+define_type_and_iterator_indirect! {
+    /// Skeleton bone type
+    struct SkeletonBone(&aiSkeletonBone)
+    /// Skeleton bone iterator type.
+    struct SkeletonBoneIter
+}
+
+impl Skeleton {
+    /// Returns the name of the skeleton.
+    ///
+    /// Panics if the skeleton name isn't valid UTF-8, see `try_name`.
+    pub fn name(&self) -> &str {
+        self.try_name().expect("skeleton name is not valid UTF-8")
+    }
+
+    /// Like `name`, but returns an error if the skeleton name isn't valid UTF-8.
+    pub fn try_name(&self) -> Result<&str, Utf8Error> {
+        crate::aistring_to_str(&self.mName)
+    }
+
+    /// Returns the number of bones in the skeleton.
+    pub fn num_bones(&self) -> u32 {
+        self.mNumBones
+    }
+
+    /// Returns an iterator over the bones in the skeleton.
+    pub fn bones(&self) -> SkeletonBoneIter {
+        unsafe {
+            SkeletonBoneIter::new(
+                NonNull::new(self.mBones as *mut *const aiSkeletonBone),
+                self.mNumBones as usize,
+            )
+        }
+    }
+
+    /// Returns an individual bone of the skeleton.
+    pub fn bone(&self, id: u32) -> Option<&SkeletonBone> {
+        if id < self.mNumBones {
+            unsafe {
+                Some(SkeletonBone::from_raw(NonNull::new(
+                    *crate::array_element(self.mBones, id as usize)?,
+                )?))
+            }
+        } else {
+            None
+        }
+    }
+}
+
+impl SkeletonBone {
+    /// The index of the parent bone in `Skeleton::bones`, or `None` for a root bone.
+    pub fn parent(&self) -> Option<u32> {
+        if self.mParent < 0 {
+            None
+        } else {
+            Some(self.mParent as u32)
+        }
+    }
+
+    /// The node at the root of the armature that this bone belongs to.
+    pub fn armature(&self) -> Option<&Node> {
+        unsafe { Some(Node::from_raw(NonNull::new(self.mArmature)?)) }
+    }
+
+    /// The node that this bone animates.
+    pub fn node(&self) -> Option<&Node> {
+        unsafe { Some(Node::from_raw(NonNull::new(self.mNode)?)) }
+    }
+
+    /// The mesh that the weights of this bone refer to.
+    pub fn mesh(&self) -> Option<&Mesh> {
+        unsafe { Some(Mesh::from_raw(NonNull::new(self.mMeshId)?)) }
+    }
+
+    /// Returns the matrix that transforms from mesh space to bone space in the bind pose.
+    pub fn offset_matrix(&self) -> Matrix4x4 {
+        Matrix4x4::from_raw(self.mOffsetMatrix)
+    }
+
+    /// Returns the transform of the bone relative to its parent.
+    pub fn local_matrix(&self) -> Matrix4x4 {
+        Matrix4x4::from_raw(self.mLocalMatrix)
+    }
+
+    /// Get the number of vertex weights
+    pub fn num_weights(&self) -> u32 {
+        // Assimp's field name has a typo.
+        self.mNumnWeights
+    }
+
+    /// Get an iterator over the vertex weights for this bone
+    pub fn weights(&self) -> VertexWeightIter {
+        unsafe { VertexWeightIter::new(NonNull::new(self.mWeights), self.mNumnWeights as usize) }
+    }
+
+    /// Get the nth vertex weight
+    pub fn weight(&self, id: u32) -> Option<&VertexWeight> {
+        if id < self.mNumnWeights {
+            unsafe {
+                Some(VertexWeight::from_raw(NonNull::new(crate::array_element(
+                    self.mWeights,
+                    id as usize,
+                )?)?))
+            }
+        } else {
+            None
+        }
+    }
+}
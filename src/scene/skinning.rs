@@ -0,0 +1,317 @@
+//! Packing of bone weights into the fixed-size per-vertex joint and weight attributes used for
+//! GPU skinning.
+//!
+//! Assimp stores skinning data per bone, as a list of `(vertex, weight)` pairs. `pack` inverts
+//! this into a fixed number of influences per vertex, keeping the strongest ones, and writes
+//! them in the requested formats.
+
+use std::error::Error;
+use std::fmt;
+
+use super::mesh::Mesh;
+
+/// The format of the joint indices in `SkinBuffers::joints`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum JointFormat {
+    /// One byte per index, for up to 256 bones.
+    U8,
+    /// Two little-endian bytes per index, for up to 65536 bones.
+    U16,
+}
+
+impl JointFormat {
+    /// The size of one joint index in bytes.
+    pub fn size(self) -> usize {
+        match self {
+            JointFormat::U8 => 1,
+            JointFormat::U16 => 2,
+        }
+    }
+
+    /// The number of bones that can be indexed.
+    pub fn max_bones(self) -> usize {
+        match self {
+            JointFormat::U8 => 1 << 8,
+            JointFormat::U16 => 1 << 16,
+        }
+    }
+}
+
+/// The format of the weights in `SkinBuffers::weights`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum WeightFormat {
+    /// One byte per weight, where 255 is a weight of 1. The weights of each vertex sum to
+    /// exactly 255.
+    Unorm8,
+    /// Four little-endian bytes per weight.
+    F32,
+}
+
+impl WeightFormat {
+    /// The size of one weight in bytes.
+    pub fn size(self) -> usize {
+        match self {
+            WeightFormat::Unorm8 => 1,
+            WeightFormat::F32 => 4,
+        }
+    }
+}
+
+/// Options for `pack`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PackOptions {
+    /// Default: `JointFormat::U8`
+    pub joints: JointFormat,
+    /// Default: `WeightFormat::F32`
+    pub weights: WeightFormat,
+    /// The number of influences stored for each vertex, which must be 2, 4 or 8. Vertices
+    /// with more influences keep the strongest ones. Default: 4
+    pub influences: u32,
+    /// Leave out bones that don't influence any vertex, so that they don't use up joint
+    /// indices. Default: false
+    pub drop_unused_bones: bool,
+}
+
+impl Default for PackOptions {
+    fn default() -> Self {
+        PackOptions {
+            joints: JointFormat::U8,
+            weights: WeightFormat::F32,
+            influences: 4,
+            drop_unused_bones: false,
+        }
+    }
+}
+
+/// The packed skinning data of a mesh, as returned by `pack`.
+///
+/// Both buffers have one element of `influences` values for each vertex of the mesh.
+/// Unused influences have a joint index and weight of 0, and vertices that aren't influenced
+/// by any bone have all weights set to 0.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkinBuffers {
+    pub joints: Vec<u8>,
+    pub joint_format: JointFormat,
+    /// The size of the joint indices of one vertex in bytes.
+    pub joint_stride: usize,
+    pub weights: Vec<u8>,
+    pub weight_format: WeightFormat,
+    /// The size of the weights of one vertex in bytes.
+    pub weight_stride: usize,
+    pub influences: u32,
+    pub num_vertices: u32,
+    /// The index in `Mesh::bones` of the bone that each joint index refers to. This is the
+    /// identity unless `PackOptions::drop_unused_bones` was set.
+    pub bones: Vec<u32>,
+}
+
+impl SkinBuffers {
+    /// The joint index of the `slot`th influence of a vertex.
+    pub fn joint(&self, vertex: u32, slot: u32) -> Option<u32> {
+        let size = self.joint_format.size();
+        let bytes = self.element(&self.joints, self.joint_stride, size, vertex, slot)?;
+
+        Some(match self.joint_format {
+            JointFormat::U8 => bytes[0] as u32,
+            JointFormat::U16 => u16::from_le_bytes([bytes[0], bytes[1]]) as u32,
+        })
+    }
+
+    /// The weight of the `slot`th influence of a vertex, converted back to a float.
+    pub fn weight(&self, vertex: u32, slot: u32) -> Option<f32> {
+        let size = self.weight_format.size();
+        let bytes = self.element(&self.weights, self.weight_stride, size, vertex, slot)?;
+
+        Some(match self.weight_format {
+            WeightFormat::Unorm8 => bytes[0] as f32 / 255.0,
+            WeightFormat::F32 => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+        })
+    }
+
+    fn element<'a>(
+        &self,
+        buffer: &'a [u8],
+        stride: usize,
+        size: usize,
+        vertex: u32,
+        slot: u32,
+    ) -> Option<&'a [u8]> {
+        if vertex >= self.num_vertices || slot >= self.influences {
+            return None;
+        }
+
+        let start = vertex as usize * stride + slot as usize * size;
+        buffer.get(start..start + size)
+    }
+}
+
+/// The reasons `pack` can fail.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SkinError {
+    /// `PackOptions::influences` isn't 2, 4 or 8.
+    InvalidInfluenceCount(u32),
+    /// There are more bones than the joint format can index.
+    TooManyBones { bones: usize, max: usize },
+    /// A bone has a weight for a vertex that the mesh doesn't have.
+    VertexOutOfRange { bone: u32, vertex: u32 },
+}
+
+impl fmt::Display for SkinError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SkinError::InvalidInfluenceCount(count) => {
+                write!(
+                    f,
+                    "{} influences per vertex requested, expected 2, 4 or 8",
+                    count
+                )
+            }
+            SkinError::TooManyBones { bones, max } => {
+                write!(
+                    f,
+                    "{} bones don't fit in a joint format for {} bones",
+                    bones, max
+                )
+            }
+            SkinError::VertexOutOfRange { bone, vertex } => {
+                write!(
+                    f,
+                    "bone {} has a weight for missing vertex {}",
+                    bone, vertex
+                )
+            }
+        }
+    }
+}
+
+impl Error for SkinError {}
+
+/// Pack the bone weights of `mesh` into `SkinBuffers`.
+///
+/// Weights that are zero, negative, infinite or NaN are ignored. The remaining influences of
+/// each vertex are sorted from strongest to weakest, limited to `PackOptions::influences`, and
+/// renormalized to sum to 1. `WeightFormat::Unorm8` weights are rounded so that they still
+/// sum to exactly 255, with each weight within 1/255 of its float value.
+pub fn pack(mesh: &Mesh, opts: PackOptions) -> Result<SkinBuffers, SkinError> {
+    if ![2, 4, 8].contains(&opts.influences) {
+        return Err(SkinError::InvalidInfluenceCount(opts.influences));
+    }
+
+    let num_vertices = mesh.num_vertices();
+    let mut influences: Vec<Vec<(u32, f32)>> = vec![Vec::new(); num_vertices as usize];
+    let mut bones = Vec::new();
+
+    for (bone_index, bone) in mesh.bones().enumerate() {
+        let bone_index = bone_index as u32;
+        let joint = bones.len() as u32;
+        let mut used = false;
+
+        for weight in bone.weights() {
+            let vertex = influences.get_mut(weight.mVertexId as usize).ok_or(
+                SkinError::VertexOutOfRange {
+                    bone: bone_index,
+                    vertex: weight.mVertexId,
+                },
+            )?;
+
+            if weight.mWeight > 0.0 && weight.mWeight.is_finite() {
+                vertex.push((joint, weight.mWeight));
+                used = true;
+            }
+        }
+
+        if used || !opts.drop_unused_bones {
+            bones.push(bone_index);
+        }
+    }
+
+    let max = opts.joints.max_bones();
+    if bones.len() > max {
+        return Err(SkinError::TooManyBones {
+            bones: bones.len(),
+            max,
+        });
+    }
+
+    let count = opts.influences as usize;
+    let joint_stride = count * opts.joints.size();
+    let weight_stride = count * opts.weights.size();
+    let mut joints = Vec::with_capacity(joint_stride * num_vertices as usize);
+    let mut weights = Vec::with_capacity(weight_stride * num_vertices as usize);
+
+    for mut vertex in influences {
+        // Strongest first, ties broken by joint index so the output is deterministic.
+        vertex.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap().then(a.0.cmp(&b.0)));
+        vertex.truncate(count);
+
+        let total: f32 = vertex.iter().map(|&(_, weight)| weight).sum();
+        let mut normalized = [0.0; 8];
+        for (slot, &(_, weight)) in vertex.iter().enumerate() {
+            normalized[slot] = weight / total;
+        }
+
+        for slot in 0..count {
+            let joint = vertex.get(slot).map_or(0, |&(joint, _)| joint);
+            match opts.joints {
+                JointFormat::U8 => joints.push(joint as u8),
+                JointFormat::U16 => joints.extend_from_slice(&(joint as u16).to_le_bytes()),
+            }
+        }
+
+        match opts.weights {
+            WeightFormat::Unorm8 => {
+                weights.extend_from_slice(&quantize_unorm8(&normalized[..count], vertex.len()))
+            }
+            WeightFormat::F32 => {
+                for weight in &normalized[..count] {
+                    weights.extend_from_slice(&weight.to_le_bytes());
+                }
+            }
+        }
+    }
+
+    Ok(SkinBuffers {
+        joints,
+        joint_format: opts.joints,
+        joint_stride,
+        weights,
+        weight_format: opts.weights,
+        weight_stride,
+        influences: opts.influences,
+        num_vertices,
+        bones,
+    })
+}
+
+/// Quantize weights that sum to 1 so that they sum to exactly 255, by rounding down and then
+/// rounding up the weights with the largest remainders. Only the first `used` weights are
+/// rounded up, and if there are none the weights are left at 0.
+fn quantize_unorm8(weights: &[f32], used: usize) -> Vec<u8> {
+    let mut quantized: Vec<u8> = weights
+        .iter()
+        .map(|weight| (weight * 255.0).floor().max(0.0).min(255.0) as u8)
+        .collect();
+
+    if used == 0 {
+        return quantized;
+    }
+
+    let sum: u32 = quantized.iter().map(|&q| q as u32).sum();
+    let mut by_remainder: Vec<usize> = (0..used).collect();
+    by_remainder.sort_by(|&a, &b| {
+        let remainder = |i: usize| weights[i] * 255.0 - quantized[i] as f32;
+        remainder(b)
+            .partial_cmp(&remainder(a))
+            .unwrap()
+            .then(a.cmp(&b))
+    });
+
+    for &i in by_remainder
+        .iter()
+        .take(255u32.saturating_sub(sum) as usize)
+    {
+        quantized[i] += 1;
+    }
+
+    quantized
+}
@@ -0,0 +1,91 @@
+use std::collections::VecDeque;
+
+use crate::math::Vector3D;
+
+use super::adjacency::EdgeAdjacency;
+use super::mesh::Mesh;
+
+impl Mesh {
+    /// Split the faces into regions that would be shaded smoothly, like the smoothing groups
+    /// of some formats, and return the region of each face. Regions are numbered from 0 in
+    /// the order of their first face, see `count_regions`.
+    ///
+    /// A region grows across every edge where the normals of the two faces differ by less
+    /// than `angle_threshold_deg` degrees. Edges are compared by position, so vertices that
+    /// were split for hard normals or UV seams don't separate regions. Edges shared by more
+    /// than two faces, and points, lines and faces with zero area, are always region borders.
+    /// The normals are the geometric normals of the faces, so the result doesn't depend on
+    /// the vertex normals.
+    pub fn smoothing_regions(&self, angle_threshold_deg: f32) -> Vec<u32> {
+        let positions: Vec<Vector3D> = self.positions().collect();
+        let faces: Vec<&[u32]> = self.faces().map(|face| face.indices()).collect();
+        let normals: Vec<Vector3D> = faces
+            .iter()
+            .map(|face| face_normal(&positions, face))
+            .collect();
+        let adjacency = EdgeAdjacency::new(&positions, faces.iter().cloned());
+
+        let min_cos = angle_threshold_deg.to_radians().cos();
+        let unassigned = u32::MAX;
+        let mut regions = vec![unassigned; faces.len()];
+        let mut num_regions = 0;
+        let mut queue = VecDeque::new();
+        for seed in 0..faces.len() {
+            if regions[seed] != unassigned {
+                continue;
+            }
+            regions[seed] = num_regions;
+            queue.push_back(seed);
+
+            while let Some(face) = queue.pop_front() {
+                let normal = normals[face];
+                if normal.length() == 0.0 {
+                    continue;
+                }
+                for around in adjacency.around(faces[face]) {
+                    let neighbour = match *around {
+                        [a, b] if a == face => b,
+                        [a, b] if b == face => a,
+                        _ => continue,
+                    };
+                    if regions[neighbour] == unassigned
+                        && normals[neighbour].length() != 0.0
+                        && normals[neighbour].dot(normal) > min_cos
+                    {
+                        regions[neighbour] = num_regions;
+                        queue.push_back(neighbour);
+                    }
+                }
+            }
+            num_regions += 1;
+        }
+        regions
+    }
+
+    /// The indices of the faces in each region of `regions`, as returned by
+    /// `smoothing_regions`, concatenated in order like `indices_u32`.
+    pub fn region_indices(&self, regions: &[u32]) -> Vec<Vec<u32>> {
+        let mut out = vec![Vec::new(); count_regions(regions) as usize];
+
+        for (face, &region) in self.faces().zip(regions) {
+            out[region as usize].extend_from_slice(face.indices());
+        }
+
+        out
+    }
+}
+
+/// The number of regions in `regions`, as returned by `Mesh::smoothing_regions`.
+pub fn count_regions(regions: &[u32]) -> u32 {
+    regions.iter().max().map_or(0, |&region| region + 1)
+}
+
+/// The unit normal of a polygon by Newell's method, or zero if it has no area.
+fn face_normal(positions: &[Vector3D], face: &[u32]) -> Vector3D {
+    let mut normal = Vector3D::new(0.0, 0.0, 0.0);
+    for (k, &a) in face.iter().enumerate() {
+        let b = face[(k + 1) % face.len()];
+        normal = normal + positions[a as usize].cross(positions[b as usize]);
+    }
+    normal.normalize()
+}
@@ -0,0 +1,409 @@
+use ffi::aiString;
+
+use crate::math::{Color3D, Color4D, Matrix4x4, Quaternion, Vector3D};
+
+use super::animation::Animation;
+use super::camera::Camera;
+use super::light::{Light, LightType};
+use super::material::{Material, MaterialComponentType, UvTransform, TEXTURE_TYPES};
+use super::mesh::Mesh;
+use super::node::Node;
+use super::scene::Scene;
+use super::texture::Texture;
+
+/// An owned, editable copy of the geometry, hierarchy, animations, cameras and lights of a
+/// scene, as returned by `Scene::snapshot`. Assimp's data is read-only, so this is the starting
+/// point for processing that needs to change the scene, such as `convert::axis_convert`.
+///
+/// Only the names and texture references of materials are copied, along with the embedded
+/// textures - `MeshData::material_index` refers to `materials`, which are in the same order as
+/// in the original scene, so the other properties can be read from there.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SceneSnapshot {
+    pub root: Option<NodeData>,
+    pub meshes: Vec<MeshData>,
+    /// The materials, in the same order as in the scene. Null entries, which Assimp never
+    /// produces, are copied as empty materials, and likewise for textures.
+    pub materials: Vec<MaterialData>,
+    /// The embedded textures, in the same order as in the scene, so that references like `*0`
+    /// keep working.
+    pub textures: Vec<EmbeddedTextureData>,
+    pub animations: Vec<AnimationData>,
+    pub cameras: Vec<CameraData>,
+    pub lights: Vec<LightData>,
+}
+
+/// An owned copy of a `Node` and its children.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeData {
+    pub name: String,
+    pub transform: Matrix4x4,
+    /// Indices into `SceneSnapshot::meshes`.
+    pub meshes: Vec<u32>,
+    pub children: Vec<NodeData>,
+}
+
+/// An owned copy of a `Mesh`. Optional vertex attributes that the mesh doesn't have are
+/// empty, or `None` for the color sets and UV channels.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MeshData {
+    pub name: String,
+    pub material_index: u32,
+    pub positions: Vec<Vector3D>,
+    pub normals: Vec<Vector3D>,
+    pub tangents: Vec<Vector3D>,
+    pub bitangents: Vec<Vector3D>,
+    /// One entry for each of Assimp's color sets.
+    pub colors: Vec<Option<Vec<Color4D>>>,
+    /// One entry for each of Assimp's UV channels.
+    pub texture_coords: Vec<Option<Vec<Vector3D>>>,
+    /// The vertex indices of each face.
+    pub faces: Vec<Vec<u32>>,
+    pub bones: Vec<BoneData>,
+}
+
+/// The name and texture references of a `Material`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MaterialData {
+    pub name: String,
+    /// Every texture of every component, by component in the order of the
+    /// `MaterialComponentType` variants, then by index.
+    pub textures: Vec<MaterialTextureData>,
+}
+
+/// A texture of a `MaterialData`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MaterialTextureData {
+    pub component: MaterialComponentType,
+    /// The index of the texture within its component.
+    pub index: u32,
+    /// The path stored in the material, e.g. a file name or `*0` for an embedded texture.
+    pub path: String,
+    pub target: TextureTarget,
+    /// The UV channel the texture uses, see `TextureDefinition::channel`.
+    pub channel: u32,
+    /// See `Material::uv_transform`.
+    pub uv_transform: Option<UvTransform>,
+}
+
+/// What a `MaterialTextureData` refers to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum TextureTarget {
+    /// A file outside the scene.
+    External,
+    /// The embedded texture at this index of `SceneSnapshot::textures`.
+    Embedded(u32),
+    /// The embedded texture at this index of `SceneSnapshot::textures`, whose data was
+    /// dropped, e.g. by `prune::prune`.
+    Missing(u32),
+}
+
+/// An owned copy of an embedded `Texture`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct EmbeddedTextureData {
+    /// See `Texture::filename`. Empty if the file doesn't store one.
+    pub filename: String,
+    /// See `Texture::format_hint`. Empty for uncompressed textures.
+    pub format_hint: String,
+    /// The width and height in texels, or `(0, 0)` for compressed textures.
+    pub size: (u32, u32),
+    /// The contents of a compressed texture, or the BGRA texels of an uncompressed one, see
+    /// `Texture::compressed_data` and `TextureData::bytes`. `None` once the data has been
+    /// dropped, e.g. by `prune::prune`.
+    pub data: Option<Vec<u8>>,
+}
+
+/// An owned copy of a `Bone`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoneData {
+    pub name: String,
+    pub offset_matrix: Matrix4x4,
+    /// `(vertex index, weight)` pairs.
+    pub weights: Vec<(u32, f32)>,
+}
+
+/// An owned copy of an `Animation`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct AnimationData {
+    pub name: String,
+    /// The duration in ticks.
+    pub duration: f64,
+    /// Ticks per second, which is 0 if the file didn't specify it.
+    pub ticks_per_second: f64,
+    pub channels: Vec<ChannelData>,
+}
+
+/// An owned copy of a `NodeAnim`. Each key is a `(time in ticks, value)` pair.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ChannelData {
+    pub node_name: String,
+    pub position_keys: Vec<(f64, Vector3D)>,
+    pub rotation_keys: Vec<(f64, Quaternion)>,
+    pub scaling_keys: Vec<(f64, Vector3D)>,
+}
+
+/// An owned copy of a `Camera`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CameraData {
+    pub name: String,
+    pub position: Vector3D,
+    pub up: Vector3D,
+    pub look_at: Vector3D,
+    pub horizontal_fov: f32,
+    pub clip_plane_near: f32,
+    pub clip_plane_far: f32,
+    /// The aspect ratio, which is 0 if the file didn't specify it.
+    pub aspect: f32,
+}
+
+/// An owned copy of a `Light`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LightData {
+    pub name: String,
+    pub light_type: LightType,
+    pub position: Vector3D,
+    pub direction: Vector3D,
+    pub up: Vector3D,
+    /// Constant, linear and quadratic attenuation.
+    pub attenuation: (f32, f32, f32),
+    pub color_diffuse: Color3D,
+    pub color_specular: Color3D,
+    pub color_ambient: Color3D,
+    pub angle_inner_cone: f32,
+    pub angle_outer_cone: f32,
+    pub size: (f32, f32),
+}
+
+impl Scene<'_> {
+    /// Copy the scene into an owned `SceneSnapshot`.
+    pub fn snapshot(&self) -> SceneSnapshot {
+        SceneSnapshot {
+            root: self.root_node().map(NodeData::from_node),
+            meshes: self.meshes().map(MeshData::from_mesh).collect(),
+            materials: (0..self.num_materials())
+                .map(|index| {
+                    self.material(index)
+                        .map(|material| MaterialData::from_material(material, self))
+                        .unwrap_or_default()
+                })
+                .collect(),
+            textures: (0..self.num_textures())
+                .map(|index| {
+                    self.texture(index)
+                        .map(EmbeddedTextureData::from_texture)
+                        .unwrap_or_default()
+                })
+                .collect(),
+            animations: self
+                .animations()
+                .map(AnimationData::from_animation)
+                .collect(),
+            cameras: self.camera().map(CameraData::from_camera).collect(),
+            lights: self.light().map(LightData::from_light).collect(),
+        }
+    }
+}
+
+impl NodeData {
+    /// Copy `node` and all of its children.
+    pub fn from_node(node: &Node) -> Self {
+        NodeData {
+            name: lossy_name(&node.mName),
+            transform: node.transform(),
+            meshes: node.meshes().to_vec(),
+            children: node.children().map(NodeData::from_node).collect(),
+        }
+    }
+
+    /// Call `f` on this node and all of its descendants, parents before children.
+    pub fn visit_mut<F: FnMut(&mut NodeData)>(&mut self, f: &mut F) {
+        f(self);
+        for child in &mut self.children {
+            child.visit_mut(f);
+        }
+    }
+}
+
+impl MeshData {
+    /// Copy `mesh`.
+    pub fn from_mesh(mesh: &Mesh) -> Self {
+        let num_sets = mesh.mColors.len() as u32;
+        let num_channels = mesh.mTextureCoords.len() as u32;
+
+        MeshData {
+            name: lossy_name(&mesh.mName),
+            material_index: mesh.material_id(),
+            positions: mesh.positions().collect(),
+            normals: mesh.normals().collect(),
+            tangents: mesh.tangents().collect(),
+            bitangents: mesh.bitangents().collect(),
+            colors: (0..num_sets)
+                .map(|set| mesh.map_colors(set, |color| color))
+                .collect(),
+            texture_coords: (0..num_channels)
+                .map(|channel| {
+                    if mesh.mTextureCoords[channel as usize].is_null() {
+                        None
+                    } else {
+                        Some(mesh.texture_coords(channel).collect())
+                    }
+                })
+                .collect(),
+            faces: mesh.faces().map(|face| face.indices().to_vec()).collect(),
+            bones: mesh
+                .bones()
+                .map(|bone| BoneData {
+                    name: lossy_name(&bone.mName),
+                    offset_matrix: bone.offset_matrix(),
+                    weights: bone
+                        .weights()
+                        .map(|weight| (weight.mVertexId, weight.mWeight))
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl MaterialData {
+    /// Copy the name and texture references of `material`, which belongs to `scene`.
+    pub fn from_material(material: &Material, scene: &Scene) -> Self {
+        let mut textures = Vec::new();
+        for &component in &TEXTURE_TYPES {
+            for index in 0..material.num_textures(component) {
+                let texture = match material.texture(component, index) {
+                    Some(texture) => texture,
+                    None => continue,
+                };
+                let path = lossy_name(&texture.path.0);
+                let target = match embedded_index(scene, &path) {
+                    Some(index) => TextureTarget::Embedded(index),
+                    None => TextureTarget::External,
+                };
+                textures.push(MaterialTextureData {
+                    component,
+                    index,
+                    path,
+                    target,
+                    channel: texture.channel,
+                    uv_transform: material.uv_transform(component, index),
+                });
+            }
+        }
+
+        MaterialData {
+            name: material.name_cstr().to_string_lossy().into_owned(),
+            textures,
+        }
+    }
+}
+
+/// The index of the embedded texture that `path` refers to, see `Scene::embedded_texture`.
+fn embedded_index(scene: &Scene, path: &str) -> Option<u32> {
+    if path.starts_with('*') {
+        return path[1..]
+            .parse()
+            .ok()
+            .filter(|&index| index < scene.num_textures());
+    }
+
+    (0..scene.num_textures()).find(|&index| {
+        scene
+            .texture(index)
+            .map_or(false, |texture| texture.eq_filename(path.as_bytes()))
+    })
+}
+
+impl EmbeddedTextureData {
+    /// Copy `texture`, including its data.
+    pub fn from_texture(texture: &Texture) -> Self {
+        let (size, data) = if texture.is_compressed() {
+            ((0, 0), texture.compressed_data().map(<[u8]>::to_vec))
+        } else {
+            let data = texture.data().map(|data| data.bytes().to_vec());
+            (texture.size(), data)
+        };
+
+        EmbeddedTextureData {
+            filename: lossy_name(&texture.mFilename),
+            format_hint: texture
+                .try_format_hint()
+                .ok()
+                .flatten()
+                .unwrap_or_default()
+                .to_owned(),
+            size,
+            data: Some(data.unwrap_or_default()),
+        }
+    }
+}
+
+impl AnimationData {
+    /// Copy `animation`.
+    pub fn from_animation(animation: &Animation) -> Self {
+        AnimationData {
+            name: lossy_name(&animation.mName),
+            duration: animation.duration(),
+            ticks_per_second: animation.ticks_per_second(),
+            channels: animation
+                .node_anims()
+                .map(|channel| ChannelData {
+                    node_name: lossy_name(&channel.mNodeName),
+                    position_keys: channel
+                        .position_keys()
+                        .map(|key| (key.time(), key.value()))
+                        .collect(),
+                    rotation_keys: channel
+                        .rotation_keys()
+                        .map(|key| (key.time(), key.value()))
+                        .collect(),
+                    scaling_keys: channel
+                        .scaling_keys()
+                        .map(|key| (key.time(), key.value()))
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl CameraData {
+    /// Copy `camera`.
+    pub fn from_camera(camera: &Camera) -> Self {
+        CameraData {
+            name: lossy_name(&camera.mName),
+            position: camera.position(),
+            up: camera.up(),
+            look_at: camera.look_at(),
+            horizontal_fov: camera.horizontal_fov(),
+            clip_plane_near: camera.clip_plane_near(),
+            clip_plane_far: camera.clip_plane_far(),
+            aspect: camera.aspect().unwrap_or(0.0),
+        }
+    }
+}
+
+impl LightData {
+    /// Copy `light`.
+    pub fn from_light(light: &Light) -> Self {
+        LightData {
+            name: lossy_name(&light.mName),
+            light_type: light.light_type(),
+            position: light.position(),
+            direction: light.direction(),
+            up: light.up(),
+            attenuation: light.attenuation(),
+            color_diffuse: light.color_diffuse(),
+            color_specular: light.color_specular(),
+            color_ambient: light.color_ambient(),
+            angle_inner_cone: light.angle_inner_cone(),
+            angle_outer_cone: light.angle_outer_cone(),
+            size: light.size(),
+        }
+    }
+}
+
+/// Copy a name, replacing any invalid UTF-8 so that snapshots can be taken of any scene.
+fn lossy_name(name: &aiString) -> String {
+    crate::aistring_to_cstr(name).to_string_lossy().into_owned()
+}
@@ -0,0 +1,184 @@
+use std::collections::{BTreeSet, HashSet};
+use std::fmt;
+
+use super::material::TEXTURE_TYPES;
+use super::node::Node;
+use super::scene::Scene;
+
+/// The number of faces of each primitive type.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct FaceCounts {
+    pub points: u64,
+    pub lines: u64,
+    pub triangles: u64,
+    pub polygons: u64,
+}
+
+/// A summary of the contents of a scene, as returned by `Scene::statistics`.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SceneStatistics {
+    pub num_meshes: u32,
+    /// The total number of vertices across all meshes.
+    pub num_vertices: u64,
+    /// The number of triangles the scene would have after triangulation: triangles count as
+    /// one, polygons with `n` vertices count as `n - 2`, and points and lines don't count.
+    pub num_triangles: u64,
+    pub faces: FaceCounts,
+    pub num_materials: u32,
+    /// The total number of bones across all meshes. A bone that affects several meshes is
+    /// counted once for each of them.
+    pub num_bones: u64,
+    /// The largest number of bones that affect any single vertex. This is the number of
+    /// weights per vertex a renderer needs to support to skin the scene without losing
+    /// influences.
+    pub max_bone_influences: u32,
+    pub num_animations: u32,
+    /// The sum of the durations of all animations, in seconds, see
+    /// `Animation::duration_seconds`.
+    pub total_animation_duration: f64,
+    /// The number of textures embedded in the file.
+    pub num_embedded_textures: u32,
+    /// The number of distinct external texture paths referenced by materials.
+    pub num_external_textures: u32,
+    pub num_nodes: u32,
+    /// The depth of the node hierarchy, where a scene with only a root node has a depth of 1.
+    pub max_depth: u32,
+    /// The distinct numbers of UV channels used by meshes, e.g. `{1, 2}` if some meshes have
+    /// one UV channel and some have two.
+    pub uv_channel_counts: BTreeSet<u32>,
+}
+
+impl Scene<'_> {
+    /// Summarize the contents of the scene. This walks every mesh, material, animation and
+    /// node once.
+    pub fn statistics(&self) -> SceneStatistics {
+        let mut stats = SceneStatistics {
+            num_meshes: self.num_meshes(),
+            num_materials: self.mNumMaterials,
+            num_animations: self.mNumAnimations,
+            num_embedded_textures: self.mNumTextures,
+            ..SceneStatistics::default()
+        };
+
+        for mesh in self.meshes() {
+            stats.num_vertices += mesh.num_vertices() as u64;
+
+            for face in mesh.faces() {
+                match face.indices().len() {
+                    0 => {}
+                    1 => stats.faces.points += 1,
+                    2 => stats.faces.lines += 1,
+                    3 => {
+                        stats.faces.triangles += 1;
+                        stats.num_triangles += 1;
+                    }
+                    n => {
+                        stats.faces.polygons += 1;
+                        stats.num_triangles += n as u64 - 2;
+                    }
+                }
+            }
+
+            if mesh.num_bones() > 0 {
+                stats.num_bones += mesh.num_bones() as u64;
+
+                let mut influences = vec![0u32; mesh.num_vertices() as usize];
+                for bone in mesh.bones() {
+                    for weight in bone.weights() {
+                        if let Some(count) = influences.get_mut(weight.mVertexId as usize) {
+                            *count += 1;
+                        }
+                    }
+                }
+
+                let max = influences.into_iter().max().unwrap_or(0);
+                stats.max_bone_influences = stats.max_bone_influences.max(max);
+            }
+
+            let uv_channels = mesh
+                .mTextureCoords
+                .iter()
+                .filter(|channel| !channel.is_null())
+                .count();
+            stats.uv_channel_counts.insert(uv_channels as u32);
+        }
+
+        let mut external = HashSet::new();
+        for material in self.materials() {
+            for &type_ in &TEXTURE_TYPES {
+                for index in 0..material.num_textures(type_) {
+                    if let Some(texture) = material.texture(type_, index) {
+                        let path = crate::aistring_to_cstr(&texture.path.0).to_bytes();
+
+                        // Embedded textures are referenced as `*0`, `*1` etc.
+                        if !path.is_empty() && !path.starts_with(b"*") {
+                            external.insert(path.to_vec());
+                        }
+                    }
+                }
+            }
+        }
+        stats.num_external_textures = external.len() as u32;
+
+        for animation in self.animations() {
+            stats.total_animation_duration += animation.duration_seconds();
+        }
+
+        if let Some(root) = self.root_node() {
+            count_nodes(root, 1, &mut stats);
+        }
+
+        stats
+    }
+}
+
+fn count_nodes(node: &Node, depth: u32, stats: &mut SceneStatistics) {
+    stats.num_nodes += 1;
+    stats.max_depth = stats.max_depth.max(depth);
+
+    for child in node.children() {
+        count_nodes(child, depth + 1, stats);
+    }
+}
+
+impl fmt::Display for SceneStatistics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let uv_channels: Vec<String> = self
+            .uv_channel_counts
+            .iter()
+            .map(|count| count.to_string())
+            .collect();
+
+        let rows: [(&str, String); 17] = [
+            ("Meshes", self.num_meshes.to_string()),
+            ("Vertices", self.num_vertices.to_string()),
+            ("Triangles", self.num_triangles.to_string()),
+            ("  Point faces", self.faces.points.to_string()),
+            ("  Line faces", self.faces.lines.to_string()),
+            ("  Triangle faces", self.faces.triangles.to_string()),
+            ("  Polygon faces", self.faces.polygons.to_string()),
+            ("Materials", self.num_materials.to_string()),
+            ("Bones", self.num_bones.to_string()),
+            ("Max bone influences", self.max_bone_influences.to_string()),
+            ("Animations", self.num_animations.to_string()),
+            (
+                "Animation duration",
+                format!("{:.3}s", self.total_animation_duration),
+            ),
+            ("Embedded textures", self.num_embedded_textures.to_string()),
+            ("External textures", self.num_external_textures.to_string()),
+            ("Nodes", self.num_nodes.to_string()),
+            ("Max depth", self.max_depth.to_string()),
+            ("UV channels", uv_channels.join(", ")),
+        ];
+
+        let width = rows.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+        for (name, value) in &rows {
+            writeln!(f, "{:<width$}  {}", name, value, width = width)?;
+        }
+
+        Ok(())
+    }
+}
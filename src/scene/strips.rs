@@ -0,0 +1,377 @@
+//! Conversion of triangle lists to triangle strips, for GPUs that draw strips faster.
+//!
+//! `stripify` takes the indices of a triangulated mesh, e.g. from `Mesh::indices_u32` after
+//! importing with `triangulate`, and returns strips that draw the same triangles with the same
+//! winding. As usual for strips, the winding of every other triangle is flipped: triangle `k`
+//! of a strip `v` is `(v[k], v[k + 1], v[k + 2])` for even `k` and `(v[k + 1], v[k], v[k + 2])`
+//! for odd `k`.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+/// The index used to restart strips in a single index stream, see `StripOutput::Restart`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum RestartIndex {
+    /// `u32::MAX`, for 32-bit index buffers.
+    U32,
+    /// `u16::MAX`, for 16-bit index buffers. Every vertex index must then be below `u16::MAX`.
+    U16,
+}
+
+impl RestartIndex {
+    /// The value of the restart index.
+    pub fn value(self) -> u32 {
+        match self {
+            RestartIndex::U32 => u32::MAX,
+            RestartIndex::U16 => u16::MAX as u32,
+        }
+    }
+}
+
+/// How the strips are returned by `stripify`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum StripOutput {
+    /// Each strip separately, see `StripIndices::Separate`.
+    Separate,
+    /// All strips in a single index stream, separated by a primitive restart index, see
+    /// `StripIndices::Restart`.
+    Restart(RestartIndex),
+}
+
+/// The tradeoff between the length of the strips and the time it takes to make them.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum StripQuality {
+    /// Start each strip at the first triangle that isn't in a strip yet, and extend it with
+    /// the first neighbour found.
+    Fast,
+    /// Start each strip at the triangle with the fewest neighbours that aren't in a strip yet,
+    /// try each of its edges, and keep the longest strip. Strips are extended with the
+    /// neighbour that has the fewest free neighbours itself, which avoids leaving isolated
+    /// triangles behind. This is the greedy algorithm used by SGI's `tomesh`.
+    Best,
+}
+
+/// Options for `stripify`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct StripOptions {
+    pub output: StripOutput,
+    pub quality: StripQuality,
+}
+
+impl Default for StripOptions {
+    /// Separate strips with `StripQuality::Best`.
+    fn default() -> Self {
+        StripOptions {
+            output: StripOutput::Separate,
+            quality: StripQuality::Best,
+        }
+    }
+}
+
+/// The index data returned by `stripify`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StripIndices {
+    /// One `Vec` of indices per strip.
+    Separate(Vec<Vec<u32>>),
+    /// Every strip in one stream, with `restart_index` between consecutive strips.
+    Restart {
+        indices: Vec<u32>,
+        restart_index: u32,
+    },
+}
+
+/// The size of the index data before and after `stripify`.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct StripStatistics {
+    pub strip_count: usize,
+    /// The average number of triangles per strip, or 0 if there are no strips.
+    pub average_strip_length: f32,
+    pub indices_before: usize,
+    /// The total number of indices in the strips, including any restart indices.
+    pub indices_after: usize,
+}
+
+/// The result of `stripify`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StripifiedMesh {
+    pub indices: StripIndices,
+    pub statistics: StripStatistics,
+}
+
+/// Why `stripify` failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StripError {
+    /// The number of indices isn't a multiple of 3, so they aren't a triangle list.
+    NotTriangles { len: usize },
+    /// A vertex index is the restart index, or doesn't fit below it.
+    IndexTooLarge { index: u32, restart_index: u32 },
+}
+
+impl fmt::Display for StripError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StripError::NotTriangles { len } => {
+                write!(f, "{} indices aren't a whole number of triangles", len)
+            }
+            StripError::IndexTooLarge {
+                index,
+                restart_index,
+            } => write!(
+                f,
+                "vertex index {} doesn't fit below the restart index {}",
+                index, restart_index
+            ),
+        }
+    }
+}
+
+impl Error for StripError {}
+
+/// Convert the triangle list `indices` to triangle strips, see the module documentation.
+///
+/// Every triangle of the input appears in exactly one strip with the same winding, except for
+/// degenerate triangles, which use a vertex more than once. Those don't draw anything, so
+/// they're left out.
+pub fn stripify(indices: &[u32], opts: StripOptions) -> Result<StripifiedMesh, StripError> {
+    if indices.len() % 3 != 0 {
+        return Err(StripError::NotTriangles { len: indices.len() });
+    }
+    if let StripOutput::Restart(restart) = opts.output {
+        let restart_index = restart.value();
+        if let Some(&index) = indices.iter().find(|&&index| index >= restart_index) {
+            return Err(StripError::IndexTooLarge {
+                index,
+                restart_index,
+            });
+        }
+    }
+
+    let triangles: Vec<[u32; 3]> = indices
+        .chunks_exact(3)
+        .map(|t| [t[0], t[1], t[2]])
+        .filter(|t| t[0] != t[1] && t[1] != t[2] && t[2] != t[0])
+        .collect();
+    let strips = Stripifier::new(&triangles, opts.quality).run();
+
+    let num_triangles: usize = strips.iter().map(|strip| strip.len() - 2).sum();
+    let mut statistics = StripStatistics {
+        strip_count: strips.len(),
+        indices_before: indices.len(),
+        ..Default::default()
+    };
+    if !strips.is_empty() {
+        statistics.average_strip_length = num_triangles as f32 / strips.len() as f32;
+    }
+
+    let indices = match opts.output {
+        StripOutput::Separate => {
+            statistics.indices_after = strips.iter().map(Vec::len).sum();
+            StripIndices::Separate(strips)
+        }
+        StripOutput::Restart(restart) => {
+            let restart_index = restart.value();
+            let mut indices = Vec::new();
+            for (i, strip) in strips.iter().enumerate() {
+                if i > 0 {
+                    indices.push(restart_index);
+                }
+                indices.extend_from_slice(strip);
+            }
+            statistics.indices_after = indices.len();
+            StripIndices::Restart {
+                indices,
+                restart_index,
+            }
+        }
+    };
+
+    Ok(StripifiedMesh {
+        indices,
+        statistics,
+    })
+}
+
+struct Stripifier<'a> {
+    triangles: &'a [[u32; 3]],
+    quality: StripQuality,
+    /// The triangles that contain each directed edge.
+    edges: HashMap<(u32, u32), Vec<usize>>,
+    used: Vec<bool>,
+    /// Triangles tentatively added to the strip being tried, marked with the number of the
+    /// attempt so that nothing has to be cleared between attempts.
+    trial: Vec<usize>,
+    trial_number: usize,
+}
+
+impl<'a> Stripifier<'a> {
+    fn new(triangles: &'a [[u32; 3]], quality: StripQuality) -> Self {
+        let mut edges: HashMap<(u32, u32), Vec<usize>> = HashMap::new();
+        for (i, t) in triangles.iter().enumerate() {
+            for k in 0..3 {
+                edges.entry((t[k], t[(k + 1) % 3])).or_default().push(i);
+            }
+        }
+
+        Stripifier {
+            triangles,
+            quality,
+            edges,
+            used: vec![false; triangles.len()],
+            trial: vec![0; triangles.len()],
+            trial_number: 0,
+        }
+    }
+
+    fn run(mut self) -> Vec<Vec<u32>> {
+        let mut strips = Vec::new();
+
+        match self.quality {
+            StripQuality::Fast => {
+                for start in 0..self.triangles.len() {
+                    if !self.used[start] {
+                        let strip = self.try_strip(start, 0);
+                        strips.push(self.commit(strip));
+                    }
+                }
+            }
+            StripQuality::Best => {
+                // Buckets of triangles by their number of free neighbours. Counts only go down,
+                // so entries are checked when popped rather than removed when they change.
+                let mut buckets: [Vec<usize>; 4] = Default::default();
+                for start in (0..self.triangles.len()).rev() {
+                    let count = self.free_neighbours(start).min(3);
+                    buckets[count].push(start);
+                }
+
+                while let Some(start) = self.pop_fewest_neighbours(&mut buckets) {
+                    // Keep the first of the longest attempts, so that a triangle on its own
+                    // isn't rotated.
+                    let mut strip = self.try_strip(start, 0);
+                    for rotation in 1..3 {
+                        let attempt = self.try_strip(start, rotation);
+                        if attempt.vertices.len() > strip.vertices.len() {
+                            strip = attempt;
+                        }
+                    }
+                    let triangles = strip.triangles.clone();
+                    strips.push(self.commit(strip));
+
+                    for t in triangles {
+                        for neighbour in self.neighbours(t) {
+                            if !self.used[neighbour] {
+                                let count = self.free_neighbours(neighbour).min(3);
+                                buckets[count].push(neighbour);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        strips
+    }
+
+    fn pop_fewest_neighbours(&self, buckets: &mut [Vec<usize>; 4]) -> Option<usize> {
+        for (count, bucket) in buckets.iter_mut().enumerate() {
+            while let Some(t) = bucket.pop() {
+                if !self.used[t] && self.free_neighbours(t).min(3) == count {
+                    return Some(t);
+                }
+            }
+        }
+        None
+    }
+
+    /// Build a strip starting with triangle `start`, rotated so that it begins at vertex
+    /// `rotation`, without marking its triangles as used.
+    fn try_strip(&mut self, start: usize, rotation: usize) -> Strip {
+        self.trial_number += 1;
+        let t = self.triangles[start];
+        let mut strip = Strip {
+            vertices: vec![t[rotation], t[(rotation + 1) % 3], t[(rotation + 2) % 3]],
+            triangles: vec![start],
+        };
+        self.trial[start] = self.trial_number;
+
+        loop {
+            let n = strip.vertices.len();
+            let (a, b) = (strip.vertices[n - 2], strip.vertices[n - 1]);
+            // The next triangle is `(a, b, c)` if it's even and `(b, a, c)` if it's odd, so it
+            // has to contain that directed edge.
+            let edge = if strip.triangles.len() % 2 == 0 {
+                (a, b)
+            } else {
+                (b, a)
+            };
+
+            let next = match self.next_triangle(edge) {
+                Some(next) => next,
+                None => break,
+            };
+            let c = self.triangles[next]
+                .iter()
+                .copied()
+                .find(|&v| v != a && v != b)
+                .unwrap();
+
+            self.trial[next] = self.trial_number;
+            strip.vertices.push(c);
+            strip.triangles.push(next);
+        }
+
+        strip
+    }
+
+    fn next_triangle(&self, edge: (u32, u32)) -> Option<usize> {
+        let mut candidates = self
+            .edges
+            .get(&edge)?
+            .iter()
+            .copied()
+            .filter(|&t| self.is_free(t));
+
+        match self.quality {
+            StripQuality::Fast => candidates.next(),
+            StripQuality::Best => candidates.min_by_key(|&t| self.free_neighbours(t)),
+        }
+    }
+
+    fn commit(&mut self, strip: Strip) -> Vec<u32> {
+        for &t in &strip.triangles {
+            self.used[t] = true;
+        }
+        // End the last attempt, so that its triangles count as free again if they weren't
+        // committed.
+        self.trial_number += 1;
+        strip.vertices
+    }
+
+    fn is_free(&self, t: usize) -> bool {
+        !self.used[t] && self.trial[t] != self.trial_number
+    }
+
+    /// The triangles that share an edge with `t` in the opposite direction, i.e. the ones
+    /// that can follow it in a strip.
+    fn neighbours(&self, t: usize) -> Vec<usize> {
+        let [a, b, c] = self.triangles[t];
+        [(b, a), (c, b), (a, c)]
+            .iter()
+            .filter_map(|edge| self.edges.get(edge))
+            .flatten()
+            .copied()
+            .collect()
+    }
+
+    fn free_neighbours(&self, t: usize) -> usize {
+        self.neighbours(t)
+            .into_iter()
+            .filter(|&neighbour| self.is_free(neighbour))
+            .count()
+    }
+}
+
+struct Strip {
+    vertices: Vec<u32>,
+    triangles: Vec<usize>,
+}
@@ -1,5 +1,5 @@
 use ffi::{aiTexel, aiTexture};
-use std::{ffi::CStr, mem, slice};
+use std::{ffi::CStr, mem, slice, str::Utf8Error};
 
 define_type_and_iterator_indirect! {
     /// Texture type.
@@ -49,21 +49,69 @@ impl Texture {
     /// A "format hint" intended to give an idea of how to interpret the texture data,
     /// which will either be `None` for raw image data or otherwise the file extension
     /// of the image.
+    ///
+    /// Panics if the hint isn't valid UTF-8, see `try_format_hint`.
     pub fn format_hint(&self) -> Option<&str> {
-        let out =
-            unsafe { CStr::from_bytes_with_nul_unchecked(mem::transmute(&self.achFormatHint[..])) }
-                .to_str()
-                .unwrap();
+        self.try_format_hint()
+            .expect("texture format hint is not valid UTF-8")
+    }
+
+    /// Like `format_hint`, but returns an error if the hint isn't valid UTF-8.
+    pub fn try_format_hint(&self) -> Result<Option<&str>, Utf8Error> {
+        let bytes: &[u8] = unsafe { mem::transmute(&self.achFormatHint[..]) };
+
+        // The hint should always be nul-terminated, but treat a missing terminator the same
+        // as an empty hint rather than reading past the end.
+        let out = match bytes.iter().position(|&byte| byte == 0) {
+            Some(end) => unsafe { CStr::from_bytes_with_nul_unchecked(&bytes[..=end]) }.to_str()?,
+            None => "",
+        };
 
         if out.len() == 0 {
-            None
+            Ok(None)
         } else {
-            Some(out)
+            Ok(Some(out))
         }
     }
 
+    /// The original path of an embedded texture, if the file format stores one.
+    ///
+    /// Panics if the filename isn't valid UTF-8, see `try_filename`.
     pub fn filename(&self) -> &str {
-        unsafe { crate::aistring_to_cstr(&self.mFilename).to_str().unwrap() }
+        self.try_filename()
+            .expect("texture filename is not valid UTF-8")
+    }
+
+    /// Like `filename`, but returns an error if the filename isn't valid UTF-8.
+    pub fn try_filename(&self) -> Result<&str, Utf8Error> {
+        crate::aistring_to_str(&self.mFilename)
+    }
+
+    /// The filename as a `CStr`, without checking that it's valid UTF-8.
+    pub fn filename_cstr(&self) -> &CStr {
+        crate::aistring_to_cstr(&self.mFilename)
+    }
+
+    /// Whether the filename is `filename`, compared byte for byte.
+    pub fn eq_filename(&self, filename: &[u8]) -> bool {
+        self.filename_cstr().to_bytes() == filename
+    }
+
+    /// Whether this is a compressed texture, i.e. the contents of an image file such as a PNG,
+    /// see `compressed_data`. `format_hint` is usually the file extension in that case.
+    pub fn is_compressed(&self) -> bool {
+        self.mHeight == 0
+    }
+
+    /// The contents of a compressed texture, or `None` if the texture isn't compressed or has
+    /// no data. Uncompressed textures are read with `data` instead.
+    pub fn compressed_data(&self) -> Option<&[u8]> {
+        if !self.is_compressed() || self.pcData.is_null() {
+            return None;
+        }
+
+        // Assimp stores the size in bytes in `mWidth`.
+        Some(unsafe { crate::array_slice(self.pcData as *const u8, self.mWidth as usize) })
     }
 
     pub fn data(&self) -> Option<&TextureData> {
@@ -73,8 +121,9 @@ impl Texture {
             return None;
         }
 
-        let count = self.mWidth * self.mHeight;
+        // This could overflow a `u32` for hostile files, so do the maths in `usize`.
+        let count = (self.mWidth as usize).checked_mul(self.mHeight as usize)?;
 
-        Some(unsafe { mem::transmute(slice::from_raw_parts(data, count as usize)) })
+        Some(unsafe { mem::transmute(crate::array_slice(data, count)) })
     }
 }
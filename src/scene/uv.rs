@@ -0,0 +1,345 @@
+//! Generation of lightmap UVs: a UV channel where no two triangles share texels.
+//!
+//! `generate_lightmap_uvs` splits a mesh into charts of connected triangles that face in
+//! similar directions, projects each chart onto the plane of its first triangle, and packs the
+//! charts into a square atlas with empty texels between them. Vertices on the border between
+//! charts get one UV per chart, so the result has its own vertices and index buffer.
+
+use std::cmp::Ordering;
+use std::collections::{HashMap, VecDeque};
+use std::error::Error;
+use std::fmt;
+
+use crate::math::Vector3D;
+
+use super::adjacency::EdgeAdjacency;
+use super::snapshot::MeshData;
+
+/// Options for `generate_lightmap_uvs`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AtlasOptions {
+    /// The width and height of the lightmap in texels. Default: 1024
+    pub resolution: u32,
+    /// The number of empty texels between charts, and between charts and the edges of the
+    /// atlas. Default: 2
+    pub padding: u32,
+    /// The largest angle in degrees between the normal of a triangle and the normal of the
+    /// first triangle of its chart. Must be below 90 for the projection to keep every
+    /// triangle's orientation. Default: 60
+    pub max_chart_angle: f32,
+}
+
+impl Default for AtlasOptions {
+    fn default() -> Self {
+        AtlasOptions {
+            resolution: 1024,
+            padding: 2,
+            max_chart_angle: 60.0,
+        }
+    }
+}
+
+/// The lightmap UVs of a mesh, as returned by `generate_lightmap_uvs`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LightmapUvs {
+    /// One UV per new vertex, within `[0, 1]`.
+    pub uvs: Vec<[f32; 2]>,
+    /// The index of the original vertex of each new vertex, so other attributes can be copied
+    /// with `remap.iter().map(|&i| positions[i as usize])`.
+    pub remap: Vec<u32>,
+    /// Three indices into `uvs` for each triangle. The triangles follow the order of the faces
+    /// of the mesh, with polygons split into triangle fans.
+    pub indices: Vec<u32>,
+    /// The chart of each triangle.
+    pub charts: Vec<u32>,
+    /// The number of charts.
+    pub num_charts: u32,
+}
+
+/// The reasons `generate_lightmap_uvs` can fail.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AtlasError {
+    /// The mesh has no faces with three or more indices.
+    NoTriangles,
+    /// A face has an index past the end of the positions.
+    IndexOutOfRange { face: u32, index: u32 },
+    /// A vertex used by a face has a position that is infinite or NaN.
+    NonFinite { vertex: u32 },
+    /// The charts don't fit into the atlas, even at one texel each.
+    DoesNotFit { charts: u32 },
+}
+
+impl fmt::Display for AtlasError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AtlasError::NoTriangles => write!(f, "the mesh has no triangles"),
+            AtlasError::IndexOutOfRange { face, index } => {
+                write!(f, "face {} has index {} past the last vertex", face, index)
+            }
+            AtlasError::NonFinite { vertex } => {
+                write!(f, "vertex {} has a position that isn't finite", vertex)
+            }
+            AtlasError::DoesNotFit { charts } => {
+                write!(f, "{} charts don't fit into the atlas", charts)
+            }
+        }
+    }
+}
+
+impl Error for AtlasError {}
+
+/// Generate a lightmap UV channel for `mesh`.
+///
+/// Triangles are in the same chart if they are connected by edges, compared by position so
+/// that existing UV seams don't split charts, and their normal is within
+/// `AtlasOptions::max_chart_angle` of the chart's first triangle. Triangles with zero area
+/// join the chart of any neighbour. All charts use the same number of texels per unit of
+/// length, chosen as large as the packing allows. Points and lines are left out.
+pub fn generate_lightmap_uvs(
+    mesh: &MeshData,
+    opts: AtlasOptions,
+) -> Result<LightmapUvs, AtlasError> {
+    let triangles = triangulate(mesh)?;
+    let positions = &mesh.positions;
+    let normals: Vec<Vector3D> = triangles
+        .iter()
+        .map(|t| {
+            let [a, b, c] = corners(positions, t);
+            (b - a).cross(c - a)
+        })
+        .collect();
+
+    let (charts, chart_normals) = segment(positions, &triangles, &normals, opts);
+    let projections: Vec<Projection> = chart_normals
+        .iter()
+        .map(|&normal| Projection::new(normal))
+        .collect();
+
+    // The bounds of each chart in its plane.
+    let mut min = vec![[f32::INFINITY; 2]; projections.len()];
+    let mut max = vec![[f32::NEG_INFINITY; 2]; projections.len()];
+    for (triangle, &chart) in triangles.iter().zip(&charts) {
+        let chart = chart as usize;
+        for &vertex in triangle {
+            let [u, v] = projections[chart].project(positions[vertex as usize]);
+            min[chart] = [min[chart][0].min(u), min[chart][1].min(v)];
+            max[chart] = [max[chart][0].max(u), max[chart][1].max(v)];
+        }
+    }
+    let sizes: Vec<[f32; 2]> = min
+        .iter()
+        .zip(&max)
+        .map(|(min, max)| [max[0] - min[0], max[1] - min[1]])
+        .collect();
+    let (scale, origins) = pack_charts(&sizes, opts)?;
+
+    let resolution = opts.resolution as f32;
+    let mut new_vertices = HashMap::new();
+    let mut uvs = Vec::new();
+    let mut remap = Vec::new();
+    let mut indices = Vec::with_capacity(triangles.len() * 3);
+    for (triangle, &chart) in triangles.iter().zip(&charts) {
+        for &vertex in triangle {
+            let index = *new_vertices.entry((chart, vertex)).or_insert_with(|| {
+                let c = chart as usize;
+                let [u, v] = projections[c].project(positions[vertex as usize]);
+                let x = origins[c][0] as f32 + (u - min[c][0]) * scale;
+                let y = origins[c][1] as f32 + (v - min[c][1]) * scale;
+                uvs.push([
+                    (x / resolution).clamp(0.0, 1.0),
+                    (y / resolution).clamp(0.0, 1.0),
+                ]);
+                remap.push(vertex);
+                remap.len() as u32 - 1
+            });
+            indices.push(index);
+        }
+    }
+
+    Ok(LightmapUvs {
+        uvs,
+        remap,
+        indices,
+        num_charts: projections.len() as u32,
+        charts,
+    })
+}
+
+/// The triangles of the faces of `mesh`, with polygons split into triangle fans.
+fn triangulate(mesh: &MeshData) -> Result<Vec<[u32; 3]>, AtlasError> {
+    let mut triangles = Vec::new();
+    for (face, indices) in mesh.faces.iter().enumerate() {
+        if indices.len() < 3 {
+            continue;
+        }
+        for &index in indices {
+            let out_of_range = AtlasError::IndexOutOfRange {
+                face: face as u32,
+                index,
+            };
+            let position = mesh.positions.get(index as usize).ok_or(out_of_range)?;
+            if !(position.x.is_finite() && position.y.is_finite() && position.z.is_finite()) {
+                return Err(AtlasError::NonFinite { vertex: index });
+            }
+        }
+        for pair in indices[1..].windows(2) {
+            triangles.push([indices[0], pair[0], pair[1]]);
+        }
+    }
+    if triangles.is_empty() {
+        return Err(AtlasError::NoTriangles);
+    }
+    Ok(triangles)
+}
+
+fn corners(positions: &[Vector3D], triangle: &[u32; 3]) -> [Vector3D; 3] {
+    [
+        positions[triangle[0] as usize],
+        positions[triangle[1] as usize],
+        positions[triangle[2] as usize],
+    ]
+}
+
+/// Grow charts from the triangles in order, returning the chart of each triangle and the unit
+/// normal of each chart.
+fn segment(
+    positions: &[Vector3D],
+    triangles: &[[u32; 3]],
+    normals: &[Vector3D],
+    opts: AtlasOptions,
+) -> (Vec<u32>, Vec<Vector3D>) {
+    let edges = EdgeAdjacency::new(positions, triangles.iter().map(|t| &t[..]));
+
+    let min_cos = opts.max_chart_angle.to_radians().cos();
+    let unassigned = u32::MAX;
+    let mut charts = vec![unassigned; triangles.len()];
+    let mut chart_normals = Vec::new();
+    let mut queue = VecDeque::new();
+    for seed in 0..triangles.len() {
+        if charts[seed] != unassigned {
+            continue;
+        }
+        let chart = chart_normals.len() as u32;
+        let mut normal = normals[seed].normalize();
+        charts[seed] = chart;
+        queue.push_back(seed);
+
+        while let Some(triangle) = queue.pop_front() {
+            for around in edges.around(&triangles[triangle]) {
+                for &neighbour in around {
+                    if charts[neighbour] != unassigned {
+                        continue;
+                    }
+                    let other = normals[neighbour].normalize();
+                    let accept = if normal.length() == 0.0 {
+                        // The chart so far has zero area, so it takes the first real normal.
+                        normal = other;
+                        true
+                    } else {
+                        other.length() == 0.0 || other.dot(normal) >= min_cos
+                    };
+                    if accept {
+                        charts[neighbour] = chart;
+                        queue.push_back(neighbour);
+                    }
+                }
+            }
+        }
+        chart_normals.push(normal);
+    }
+    (charts, chart_normals)
+}
+
+/// A projection onto the plane through the origin with a given normal.
+struct Projection {
+    u: Vector3D,
+    v: Vector3D,
+}
+
+impl Projection {
+    fn new(normal: Vector3D) -> Self {
+        if normal.length() == 0.0 {
+            return Projection {
+                u: Vector3D::new(1.0, 0.0, 0.0),
+                v: Vector3D::new(0.0, 1.0, 0.0),
+            };
+        }
+        let axis = if normal.x.abs() < 0.9 {
+            Vector3D::new(1.0, 0.0, 0.0)
+        } else {
+            Vector3D::new(0.0, 1.0, 0.0)
+        };
+        let u = (axis - normal * normal.dot(axis)).normalize();
+        Projection {
+            v: normal.cross(u),
+            u,
+        }
+    }
+
+    fn project(&self, p: Vector3D) -> [f32; 2] {
+        [p.dot(self.u), p.dot(self.v)]
+    }
+}
+
+/// Find the largest scale from chart units to texels at which the charts of the given sizes
+/// fit into the atlas, and the origin of each chart in texels.
+fn pack_charts(sizes: &[[f32; 2]], opts: AtlasOptions) -> Result<(f32, Vec<[u32; 2]>), AtlasError> {
+    let largest = sizes.iter().fold(0.0f32, |m, s| m.max(s[0]).max(s[1]));
+    let area: f32 = sizes.iter().map(|s| s[0] * s[1]).sum();
+    let usable = opts.resolution as f32 - 2.0 * opts.padding as f32;
+
+    let mut scale = if largest > 0.0 {
+        (usable.max(1.0) / largest).min(opts.resolution as f32 / area.sqrt())
+    } else {
+        1.0
+    };
+
+    // Sorting by height makes the shelves tight.
+    let mut order: Vec<usize> = (0..sizes.len()).collect();
+    order.sort_by(|&a, &b| {
+        sizes[b][1]
+            .partial_cmp(&sizes[a][1])
+            .unwrap_or(Ordering::Equal)
+    });
+
+    loop {
+        if let Some(origins) = pack_shelves(sizes, &order, scale, opts) {
+            return Ok((scale, origins));
+        }
+        if largest * scale <= 1.0 {
+            return Err(AtlasError::DoesNotFit {
+                charts: sizes.len() as u32,
+            });
+        }
+        scale *= 0.9;
+    }
+}
+
+/// Place the charts on shelves in the given order, or return `None` if they don't fit.
+fn pack_shelves(
+    sizes: &[[f32; 2]],
+    order: &[usize],
+    scale: f32,
+    opts: AtlasOptions,
+) -> Option<Vec<[u32; 2]>> {
+    let texels = |size: f32| ((size * scale).ceil() as u64).max(1);
+    let (resolution, padding) = (u64::from(opts.resolution), u64::from(opts.padding));
+
+    let mut origins = vec![[0, 0]; sizes.len()];
+    let (mut x, mut y, mut shelf) = (padding, padding, 0);
+    for &chart in order {
+        let (width, height) = (texels(sizes[chart][0]), texels(sizes[chart][1]));
+        if x > padding && x + width + padding > resolution {
+            x = padding;
+            y += shelf + padding;
+            shelf = 0;
+        }
+        if x + width + padding > resolution || y + height + padding > resolution {
+            return None;
+        }
+        origins[chart] = [x as u32, y as u32];
+        x += width + padding;
+        shelf = shelf.max(height);
+    }
+    Some(origins)
+}
@@ -0,0 +1,126 @@
+//! Baking the UV transforms of materials into the UVs of meshes, for renderers that can't
+//! transform the UVs of each texture, see `bake_uv_transforms`.
+//!
+//! `Importer::transform_uv_coords` does the same at import time, but it transforms every
+//! channel it can, and gives no account of the channels it can't. This works on a
+//! `SceneSnapshot`, and reports both.
+
+use super::material::{MaterialComponentType, UvTransform};
+use super::snapshot::SceneSnapshot;
+
+/// What `bake_uv_transforms` did.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct UvBakeReport {
+    /// The channels whose transform was baked.
+    pub baked: Vec<BakedUvChannel>,
+    /// The channels that were left as they were, because their textures disagree.
+    pub conflicts: Vec<UvTransformConflict>,
+}
+
+/// A UV channel of the meshes of a material that had a transform baked into it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BakedUvChannel {
+    /// The index of the material in `SceneSnapshot::materials`.
+    pub material: u32,
+    pub channel: u32,
+    pub transform: UvTransform,
+    /// The meshes whose UVs were transformed, which are the meshes of the material that have
+    /// the channel.
+    pub meshes: Vec<u32>,
+}
+
+/// A UV channel that textures of a material use with different transforms.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UvTransformConflict {
+    /// The index of the material in `SceneSnapshot::materials`.
+    pub material: u32,
+    pub channel: u32,
+    /// The component, index and transform of each texture that uses the channel, with `None`
+    /// for textures without a transform.
+    pub textures: Vec<(MaterialComponentType, u32, Option<UvTransform>)>,
+}
+
+/// Bake the UV transform of each texture into the UV channel it uses, and remove it from the
+/// material, so the textures can be sampled with the UVs as they are.
+///
+/// A channel is baked if every texture of the material that uses it has the same transform,
+/// and that transform isn't the identity. Since a channel can only be transformed once, a
+/// channel that textures use with different transforms, including textures without one, is
+/// left as it is and reported as a conflict. The UVs of every mesh of the material are
+/// transformed, so a mesh's channel is only ever transformed by its own material. Materials
+/// that no mesh uses are left as they are.
+pub fn bake_uv_transforms(snapshot: &mut SceneSnapshot) -> UvBakeReport {
+    let mut report = UvBakeReport::default();
+    let SceneSnapshot {
+        materials, meshes, ..
+    } = snapshot;
+
+    for (material_index, material) in materials.iter_mut().enumerate() {
+        let material_index = material_index as u32;
+        let users: Vec<u32> = (0..meshes.len() as u32)
+            .filter(|&mesh| meshes[mesh as usize].material_index == material_index)
+            .collect();
+        if users.is_empty() {
+            continue;
+        }
+
+        let mut channels: Vec<u32> = material
+            .textures
+            .iter()
+            .map(|texture| texture.channel)
+            .collect();
+        channels.sort_unstable();
+        channels.dedup();
+
+        for channel in channels {
+            let textures: Vec<(MaterialComponentType, u32, Option<UvTransform>)> = material
+                .textures
+                .iter()
+                .filter(|texture| texture.channel == channel)
+                .map(|texture| (texture.component, texture.index, texture.uv_transform))
+                .collect();
+            // Textures without a transform use the identity, which is only a conflict if
+            // another texture of the channel has a transform.
+            let transforms: Vec<UvTransform> = textures
+                .iter()
+                .map(|&(_, _, transform)| transform.unwrap_or_default())
+                .collect();
+            let transform = transforms[0];
+            if transforms.iter().any(|&other| other != transform) {
+                report.conflicts.push(UvTransformConflict {
+                    material: material_index,
+                    channel,
+                    textures,
+                });
+                continue;
+            }
+            if transform.is_identity() {
+                continue;
+            }
+
+            let mut baked = Vec::new();
+            for &mesh_index in &users {
+                let mesh = &mut meshes[mesh_index as usize];
+                if let Some(Some(uvs)) = mesh.texture_coords.get_mut(channel as usize) {
+                    for uv in uvs.iter_mut() {
+                        *uv = transform.apply(*uv);
+                    }
+                    baked.push(mesh_index);
+                }
+            }
+            for texture in &mut material.textures {
+                if texture.channel == channel {
+                    texture.uv_transform = None;
+                }
+            }
+            report.baked.push(BakedUvChannel {
+                material: material_index,
+                channel,
+                transform,
+                meshes: baked,
+            });
+        }
+    }
+
+    report
+}
@@ -0,0 +1,168 @@
+use std::fmt;
+
+use crate::log::{LogLevel, LogRecord};
+
+use super::scene::Scene;
+
+/// The prefixes of the messages of Assimp's `ValidateDataStructure` step.
+const ERROR_PREFIX: &str = "Validation failed: ";
+const WARNING_PREFIX: &str = "Validation warning: ";
+
+/// The indices of the null entries in one of the pointer arrays of a scene, as returned by
+/// `Scene::null_entries`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NullEntries {
+    /// Which array the entries are in, e.g. `meshes` or `children of node "Root"`.
+    pub array: String,
+    pub indices: Vec<usize>,
+}
+
+impl fmt::Display for NullEntries {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "null entries in {} at indices {:?}",
+            self.array, self.indices
+        )
+    }
+}
+
+impl Scene<'_> {
+    /// Find null entries in the scene's arrays of meshes, materials, animations, textures,
+    /// lights and cameras, in the children of every node, in the bones of every mesh and in
+    /// the channels of every animation. Assimp never produces these, but a scene built or
+    /// modified by other code might contain them.
+    ///
+    /// The iterators over these arrays skip null entries, so this is a way to check that
+    /// nothing was skipped. Returns an empty `Vec` if there are no null entries.
+    pub fn null_entries(&self) -> Vec<NullEntries> {
+        let mut found = Vec::new();
+        let mut check = |array: String, indices: Vec<usize>| {
+            if !indices.is_empty() {
+                found.push(NullEntries { array, indices });
+            }
+        };
+
+        check("meshes".to_owned(), self.meshes().null_indices());
+        check("materials".to_owned(), self.materials().null_indices());
+        check("animations".to_owned(), self.animations().null_indices());
+        check("textures".to_owned(), self.textures().null_indices());
+        check("lights".to_owned(), self.light().null_indices());
+        check("cameras".to_owned(), self.camera().null_indices());
+
+        if let Some(root) = self.root_node() {
+            for node in root.descendants() {
+                check(
+                    format!(
+                        "children of node {:?}",
+                        crate::aistring_to_cstr(&node.mName)
+                    ),
+                    node.children().null_indices(),
+                );
+            }
+        }
+
+        for index in 0..self.num_meshes() {
+            if let Some(mesh) = self.mesh(index) {
+                check(
+                    format!("bones of mesh {}", index),
+                    mesh.bones().null_indices(),
+                );
+            }
+        }
+
+        for index in 0..self.num_animations() {
+            if let Some(animation) = self.animation(index) {
+                check(
+                    format!("channels of animation {}", index),
+                    animation.node_anims().null_indices(),
+                );
+            }
+        }
+
+        found
+    }
+}
+
+/// How bad a `ValidationFinding` is.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ValidationSeverity {
+    /// The scene can still be used, e.g. it has vertices that no face refers to.
+    Warning,
+    /// The scene is broken, so the import fails.
+    Error,
+}
+
+/// A problem that `Importer::validate_data_structure` found, see `Scene::validation_findings`
+/// and `Error::ValidationFailed`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationFinding {
+    pub severity: ValidationSeverity,
+    /// Assimp's message, without the `Validation failed:` or `Validation warning:` prefix.
+    pub message: String,
+    /// What the message is about, if it names it, e.g. `aiMesh::mMaterialIndex` or
+    /// `aiScene::mMeshes[2]`. This is the first word of the message that looks like the name
+    /// of a field of one of Assimp's structs.
+    pub subject: Option<String>,
+}
+
+impl ValidationFinding {
+    fn new(severity: ValidationSeverity, message: &str) -> ValidationFinding {
+        let subject = message
+            .split_whitespace()
+            .map(|word| word.trim_end_matches(&[':', ',', '.', ';'][..]))
+            .find(|word| {
+                let mut chars = word.chars();
+                chars.next() == Some('a')
+                    && chars.next() == Some('i')
+                    && chars.next().map_or(false, |c| c.is_ascii_uppercase())
+                    && word.contains("::")
+            })
+            .map(str::to_owned);
+        ValidationFinding {
+            severity,
+            message: message.to_owned(),
+            subject,
+        }
+    }
+}
+
+impl fmt::Display for ValidationFinding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let severity = match self.severity {
+            ValidationSeverity::Warning => "warning",
+            ValidationSeverity::Error => "error",
+        };
+        write!(f, "validation {}: {}", severity, self.message)
+    }
+}
+
+/// The findings of the validation step among the log records of an import, and in the error
+/// message of the import if it failed. Assimp logs the error that fails an import as well as
+/// returning it, so errors are only counted once.
+pub(crate) fn validation_findings(
+    records: &[LogRecord],
+    error: Option<&str>,
+) -> Vec<ValidationFinding> {
+    let mut findings: Vec<ValidationFinding> = Vec::new();
+    let messages = records
+        .iter()
+        .filter_map(|record| record.level.map(|level| (level, &record.message[..])))
+        .chain(error.map(|error| (LogLevel::Error, error)));
+    for (level, message) in messages {
+        let finding = match level {
+            LogLevel::Warn if message.starts_with(WARNING_PREFIX) => ValidationFinding::new(
+                ValidationSeverity::Warning,
+                &message[WARNING_PREFIX.len()..],
+            ),
+            LogLevel::Error if message.starts_with(ERROR_PREFIX) => {
+                ValidationFinding::new(ValidationSeverity::Error, &message[ERROR_PREFIX.len()..])
+            }
+            _ => continue,
+        };
+        if finding.severity == ValidationSeverity::Warning || !findings.contains(&finding) {
+            findings.push(finding);
+        }
+    }
+    findings
+}
@@ -0,0 +1,468 @@
+//! Interleaving the vertex attributes of a mesh into a single buffer for upload to the GPU.
+//!
+//! The attributes are listed in a layout, in the order they're written for each vertex. UV
+//! channels can be picked by index, or by what they're for: the channel that a material's
+//! texture uses, or the channel's name in the file. Those are resolved against the mesh when
+//! the buffer is built, because exporters don't agree on which channel is which, e.g. some put
+//! a lightmap UV in channel 0 and the base UV in channel 1.
+//!
+//! Attributes are written as `f32`s by `build`, or in a quantized `Format` by
+//! `build_formatted`, see `quantize` for the encodings and their accuracy.
+
+use std::error::Error;
+use std::fmt;
+
+use crate::import::Aabb;
+use crate::math::Vector3D;
+
+use super::material::MaterialComponentType;
+use super::mesh::Mesh;
+use super::quantize::{self, DequantizeTransform, UvRange};
+use super::scene::Scene;
+
+/// A vertex attribute in a layout for `build`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Attribute {
+    /// 3 components.
+    Position,
+    /// 3 components.
+    Normal,
+    /// 3 components.
+    Tangent,
+    /// 3 components.
+    Bitangent,
+    /// The U and V of the UV channel with the given index.
+    Uv(u32),
+    /// The U and V of the UV channel that the first texture of the given component of the
+    /// mesh's material uses, see `TextureDefinition::channel`. That's the channel the file
+    /// declares, or the inferred one if it doesn't declare any.
+    UvForComponent(MaterialComponentType),
+    /// The U and V of the UV channel with the given name, see `Mesh::texture_coords_name`.
+    /// Files only name their channels with the `assimp-5-1` feature, so use `Uv` without it.
+    NamedUv(String),
+    /// 4 components, the RGBA of the color set with the given index.
+    Color(u32),
+}
+
+impl Attribute {
+    /// The number of components of the attribute, before any quantization.
+    pub fn components(&self) -> usize {
+        match self {
+            Attribute::Position | Attribute::Normal | Attribute::Tangent | Attribute::Bitangent => {
+                3
+            }
+            Attribute::Uv(_) | Attribute::UvForComponent(_) | Attribute::NamedUv(_) => 2,
+            Attribute::Color(_) => 4,
+        }
+    }
+
+    /// The size of the attribute in bytes as `f32`s.
+    pub fn size(&self) -> usize {
+        self.components() * 4
+    }
+}
+
+/// How the components of an attribute are written, for `build_formatted`. All of the formats
+/// are little-endian.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Format {
+    /// An `f32` for each component. This works for every attribute.
+    F32,
+    /// For normals, tangents and bitangents: two `i16`s in the octahedral mapping, see
+    /// `quantize::encode_octahedral_normals`.
+    Octahedral16,
+    /// For UVs: a `u16` for U and V, mapping the range to `0..=65535`, see
+    /// `quantize::quantize_uvs`.
+    Unorm16(UvRange),
+    /// For positions: a `u16` for each component relative to the bounds of the mesh, see
+    /// `quantize::quantize_positions`, and 2 bytes of padding to keep the next attribute
+    /// aligned to 4 bytes. `VertexBuffer::position_transform` turns them back into positions.
+    Position16,
+}
+
+impl Format {
+    /// Whether `attribute` can be written in this format.
+    pub fn supports(self, attribute: &Attribute) -> bool {
+        match self {
+            Format::F32 => true,
+            Format::Octahedral16 => {
+                matches!(
+                    attribute,
+                    Attribute::Normal | Attribute::Tangent | Attribute::Bitangent
+                )
+            }
+            Format::Unorm16(_) => attribute.components() == 2,
+            Format::Position16 => *attribute == Attribute::Position,
+        }
+    }
+
+    /// The size in bytes of an attribute in this format, which must support it.
+    pub fn size(self, attribute: &Attribute) -> usize {
+        match self {
+            Format::F32 => attribute.size(),
+            Format::Octahedral16 | Format::Unorm16(_) => 4,
+            Format::Position16 => 8,
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Format::F32 => "f32",
+            Format::Octahedral16 => "octahedral 16 bit",
+            Format::Unorm16(_) => "unorm 16 bit",
+            Format::Position16 => "16 bit position",
+        })
+    }
+}
+
+impl fmt::Display for Attribute {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Attribute::Position => f.write_str("positions"),
+            Attribute::Normal => f.write_str("normals"),
+            Attribute::Tangent => f.write_str("tangents"),
+            Attribute::Bitangent => f.write_str("bitangents"),
+            Attribute::Uv(channel) => write!(f, "UV channel {}", channel),
+            Attribute::UvForComponent(component) => {
+                write!(f, "the UV channel of the {:?} texture", component)
+            }
+            Attribute::NamedUv(name) => write!(f, "the UV channel named `{}`", name),
+            Attribute::Color(set) => write!(f, "color set {}", set),
+        }
+    }
+}
+
+/// An attribute of a `VertexBuffer`, and where it is in each vertex.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BufferAttribute {
+    /// The attribute as it was given in the layout.
+    pub attribute: Attribute,
+    pub format: Format,
+    /// The offset of the attribute from the start of each vertex in bytes.
+    pub offset: usize,
+    /// The size of the attribute in bytes, including any padding.
+    pub size: usize,
+    /// The UV channel or color set that the attribute was resolved to, or `None` for the
+    /// attributes that there's only one of.
+    pub channel: Option<u32>,
+}
+
+/// The interleaved vertex data of a mesh, as returned by `build`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VertexBuffer {
+    pub data: Vec<u8>,
+    /// The size of one vertex in bytes.
+    pub stride: usize,
+    pub num_vertices: u32,
+    /// The attributes in the order of the layout.
+    pub attributes: Vec<BufferAttribute>,
+    /// Turns positions written as `Format::Position16` back into the mesh's space, or `None`
+    /// if the layout doesn't have any.
+    pub position_transform: Option<DequantizeTransform>,
+}
+
+impl VertexBuffer {
+    /// The components of the `index`th attribute of the layout of a vertex, converted back to
+    /// `f32`s if the attribute is quantized.
+    pub fn get(&self, vertex: u32, index: usize) -> Option<Vec<f32>> {
+        let attribute = self.attributes.get(index)?;
+        if vertex >= self.num_vertices {
+            return None;
+        }
+
+        let start = vertex as usize * self.stride + attribute.offset;
+        let bytes = self.data.get(start..start + attribute.size)?;
+        let u16_at = |i: usize| u16::from_le_bytes([bytes[i * 2], bytes[i * 2 + 1]]);
+        Some(match attribute.format {
+            Format::F32 => bytes
+                .chunks_exact(4)
+                .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+                .collect(),
+            Format::Octahedral16 => {
+                let encoded = [u16_at(0) as i16, u16_at(1) as i16];
+                <[f32; 3]>::from(quantize::decode_octahedral(encoded)).to_vec()
+            }
+            Format::Unorm16(range) => range.dequantize([u16_at(0), u16_at(1)]).to_vec(),
+            Format::Position16 => {
+                let position = self
+                    .position_transform?
+                    .apply([u16_at(0), u16_at(1), u16_at(2)]);
+                <[f32; 3]>::from(position).to_vec()
+            }
+        })
+    }
+}
+
+/// The reasons `build` can fail. Each says which attribute of the layout it's about.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BufferError {
+    /// The scene doesn't have a mesh with the given index.
+    MeshOutOfRange { mesh: u32, len: u32 },
+    /// An `Attribute::UvForComponent` can't be resolved because the mesh's material index
+    /// is out of range.
+    MissingMaterial { attribute: Attribute, material: u32 },
+    /// An `Attribute::UvForComponent` can't be resolved because the mesh's material has no
+    /// texture of that component.
+    NoTexture { attribute: Attribute, material: u32 },
+    /// An `Attribute::NamedUv` can't be resolved because none of the mesh's UV channels has
+    /// that name.
+    NoNamedChannel { attribute: Attribute },
+    /// The mesh doesn't have the attribute. `channel` is the UV channel or color set that it
+    /// was resolved to, if any.
+    MissingAttribute {
+        attribute: Attribute,
+        channel: Option<u32>,
+    },
+    /// The attribute can't be written in the format, e.g. a color as `Format::Octahedral16`.
+    UnsupportedFormat {
+        attribute: Attribute,
+        format: Format,
+    },
+}
+
+impl fmt::Display for BufferError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BufferError::MeshOutOfRange { mesh, len } => {
+                write!(f, "mesh {} is out of range for {} meshes", mesh, len)
+            }
+            BufferError::MissingMaterial {
+                attribute,
+                material,
+            } => write!(
+                f,
+                "can't resolve {}: the mesh's material {} doesn't exist",
+                attribute, material
+            ),
+            BufferError::NoTexture {
+                attribute,
+                material,
+            } => write!(
+                f,
+                "can't resolve {}: material {} has no such texture",
+                attribute, material
+            ),
+            BufferError::NoNamedChannel { attribute } => {
+                write!(
+                    f,
+                    "can't resolve {}: no UV channel of the mesh has that name",
+                    attribute
+                )?;
+                if cfg!(not(feature = "assimp-5-1")) {
+                    write!(f, " (UV channel names need the `assimp-5-1` feature)")?;
+                }
+                Ok(())
+            }
+            BufferError::MissingAttribute { attribute, channel } => match (attribute, channel) {
+                (Attribute::UvForComponent(_), Some(channel))
+                | (Attribute::NamedUv(_), Some(channel)) => {
+                    write!(
+                        f,
+                        "the mesh has no UV channel {}, which is {}",
+                        channel, attribute
+                    )
+                }
+                _ => write!(f, "the mesh has no {}", attribute),
+            },
+            BufferError::UnsupportedFormat { attribute, format } => {
+                write!(f, "{} can't be written as {}", attribute, format)
+            }
+        }
+    }
+}
+
+impl Error for BufferError {}
+
+/// Interleave the attributes of the `mesh_index`th mesh of `scene` in the order of `layout`.
+///
+/// `Attribute::UvForComponent` needs the mesh's material, which is why this takes the scene.
+/// Every attribute must be present in the mesh, so check `Scene::attribute_requirements`
+/// first or fall back to a layout with fewer attributes.
+///
+/// Every attribute is written as `Format::F32`, see `build_formatted` for the others.
+pub fn build(
+    scene: &Scene,
+    mesh_index: u32,
+    layout: &[Attribute],
+) -> Result<VertexBuffer, BufferError> {
+    let layout: Vec<_> = layout
+        .iter()
+        .map(|attribute| (attribute.clone(), Format::F32))
+        .collect();
+    build_formatted(scene, mesh_index, &layout)
+}
+
+/// Like `build`, but writes each attribute in the format it's paired with, e.g. normals as
+/// `Format::Octahedral16` to halve their size.
+pub fn build_formatted(
+    scene: &Scene,
+    mesh_index: u32,
+    layout: &[(Attribute, Format)],
+) -> Result<VertexBuffer, BufferError> {
+    let mesh = scene.mesh(mesh_index).ok_or(BufferError::MeshOutOfRange {
+        mesh: mesh_index,
+        len: scene.num_meshes(),
+    })?;
+
+    let mut attributes = Vec::with_capacity(layout.len());
+    let mut stride = 0;
+    for (attribute, format) in layout {
+        if !format.supports(attribute) {
+            let (attribute, format) = (attribute.clone(), *format);
+            return Err(BufferError::UnsupportedFormat { attribute, format });
+        }
+        let channel = resolve(scene, mesh, attribute)?;
+        let size = format.size(attribute);
+        attributes.push(BufferAttribute {
+            attribute: attribute.clone(),
+            format: *format,
+            offset: stride,
+            size,
+            channel,
+        });
+        stride += size;
+    }
+
+    let bounds = if layout
+        .iter()
+        .any(|&(_, format)| format == Format::Position16)
+    {
+        Aabb::of_mesh(mesh)
+    } else {
+        None
+    };
+
+    let num_vertices = mesh.num_vertices();
+    let mut data = Vec::with_capacity(stride * num_vertices as usize);
+    for vertex in 0..num_vertices {
+        for attribute in &attributes {
+            let missing = || BufferError::MissingAttribute {
+                attribute: attribute.attribute.clone(),
+                channel: attribute.channel,
+            };
+            let components: Vec<f32> = match (&attribute.attribute, attribute.channel) {
+                (Attribute::Position, _) => components(mesh.position(vertex)),
+                (Attribute::Normal, _) => components(mesh.normal(vertex)),
+                (Attribute::Tangent, _) => components(mesh.tangent(vertex)),
+                (Attribute::Bitangent, _) => components(mesh.bitangent(vertex)),
+                (Attribute::Color(_), Some(set)) => mesh
+                    .vertex_color(set, vertex)
+                    .map(|color| <[f32; 4]>::from(color).to_vec()),
+                (_, Some(channel)) => mesh.texture_coord(channel, vertex).map(|uv| {
+                    let uv: [f32; 3] = uv.into();
+                    uv[..2].to_vec()
+                }),
+                (_, None) => None,
+            }
+            .ok_or_else(missing)?;
+            write(&mut data, attribute.format, &components, bounds);
+        }
+    }
+
+    let position_transform = bounds.map(DequantizeTransform::of_aabb);
+    Ok(VertexBuffer {
+        data,
+        stride,
+        num_vertices,
+        attributes,
+        position_transform,
+    })
+}
+
+/// Write the components of an attribute in `format`. `bounds` are the bounds of the mesh for
+/// `Format::Position16`, which only has no bounds if it has no vertices.
+fn write(data: &mut Vec<u8>, format: Format, components: &[f32], bounds: Option<Aabb>) {
+    match format {
+        Format::F32 => {
+            for component in components {
+                data.extend_from_slice(&component.to_le_bytes());
+            }
+        }
+        Format::Octahedral16 => {
+            let normal = Vector3D::new(components[0], components[1], components[2]);
+            for component in &quantize::encode_octahedral(normal) {
+                data.extend_from_slice(&component.to_le_bytes());
+            }
+        }
+        Format::Unorm16(range) => {
+            for component in &range.quantize([components[0], components[1]]) {
+                data.extend_from_slice(&component.to_le_bytes());
+            }
+        }
+        Format::Position16 => {
+            let position = Vector3D::new(components[0], components[1], components[2]);
+            let quantized = bounds.map_or([0; 3], |bounds| {
+                quantize::quantize_position(position, bounds)
+            });
+            for component in &quantized {
+                data.extend_from_slice(&component.to_le_bytes());
+            }
+            data.extend_from_slice(&[0, 0]);
+        }
+    }
+}
+
+fn components(vector: Option<Vector3D>) -> Option<Vec<f32>> {
+    vector.map(|vector| <[f32; 3]>::from(vector).to_vec())
+}
+
+/// The UV channel or color set of `attribute` in `mesh`, checking that the mesh has it.
+fn resolve(scene: &Scene, mesh: &Mesh, attribute: &Attribute) -> Result<Option<u32>, BufferError> {
+    let channel = match attribute {
+        Attribute::Position | Attribute::Normal | Attribute::Tangent | Attribute::Bitangent => None,
+        Attribute::Uv(channel) => Some(*channel),
+        Attribute::UvForComponent(component) => {
+            let material = mesh.material_id();
+            let texture = scene
+                .material(material)
+                .ok_or_else(|| BufferError::MissingMaterial {
+                    attribute: attribute.clone(),
+                    material,
+                })?
+                .texture(*component, 0)
+                .ok_or_else(|| BufferError::NoTexture {
+                    attribute: attribute.clone(),
+                    material,
+                })?;
+            Some(texture.channel)
+        }
+        Attribute::NamedUv(name) => {
+            let channel = (0..mesh.mTextureCoords.len() as u32)
+                .find(|&channel| {
+                    mesh.try_texture_coords_name(channel).ok().flatten() == Some(name.as_str())
+                })
+                .ok_or_else(|| BufferError::NoNamedChannel {
+                    attribute: attribute.clone(),
+                })?;
+            Some(channel)
+        }
+        Attribute::Color(set) => Some(*set),
+    };
+
+    let present = match (attribute, channel) {
+        (Attribute::Position, _) => !mesh.mVertices.is_null(),
+        (Attribute::Normal, _) => !mesh.mNormals.is_null(),
+        (Attribute::Tangent, _) => !mesh.mTangents.is_null(),
+        (Attribute::Bitangent, _) => !mesh.mBitangents.is_null(),
+        (Attribute::Color(_), Some(set)) => mesh
+            .mColors
+            .get(set as usize)
+            .map_or(false, |colors| !colors.is_null()),
+        (_, Some(channel)) => mesh
+            .mTextureCoords
+            .get(channel as usize)
+            .map_or(false, |uvs| !uvs.is_null()),
+        (_, None) => false,
+    };
+
+    if present {
+        Ok(channel)
+    } else {
+        Err(BufferError::MissingAttribute {
+            attribute: attribute.clone(),
+            channel,
+        })
+    }
+}
@@ -0,0 +1,160 @@
+//! Streaming a scene through a visitor, see `Scene::visit`.
+
+use crate::math::{Matrix4x4, Vector3D};
+
+use super::material::Material;
+use super::mesh::Mesh;
+use super::node::Node;
+use super::scene::Scene;
+
+/// Whether `Scene::visit` goes on after a callback of a `SceneVisitor`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum VisitControl {
+    Continue,
+    /// Return from `Scene::visit` without any further callbacks.
+    Stop,
+}
+
+/// The callbacks of `Scene::visit`. Every callback does nothing by default, so a visitor only
+/// has to implement those it needs.
+pub trait SceneVisitor {
+    /// A node, with its depth below the root node, which has a depth of 0, and its transform
+    /// relative to the root node.
+    fn on_node(&mut self, _node: &Node, _depth: u32, _world_transform: &Matrix4x4) -> VisitControl {
+        VisitControl::Continue
+    }
+
+    /// The start of mesh `index`. The positions and indices of the mesh follow, and then
+    /// `on_mesh_end`.
+    fn on_mesh_begin(&mut self, _index: u32, _mesh: &Mesh) -> VisitControl {
+        VisitControl::Continue
+    }
+
+    /// The next vertex positions of the current mesh.
+    fn on_positions_chunk(&mut self, _positions: &[Vector3D]) -> VisitControl {
+        VisitControl::Continue
+    }
+
+    /// The next face indices of the current mesh, like `Mesh::indices_u32`. The indices of a
+    /// face are never split across chunks, so for a triangulated mesh every chunk holds whole
+    /// triangles.
+    fn on_indices_chunk(&mut self, _indices: &[u32]) -> VisitControl {
+        VisitControl::Continue
+    }
+
+    /// The end of mesh `index`.
+    fn on_mesh_end(&mut self, _index: u32) -> VisitControl {
+        VisitControl::Continue
+    }
+
+    fn on_material(&mut self, _index: u32, _material: &Material) -> VisitControl {
+        VisitControl::Continue
+    }
+}
+
+/// How `Scene::visit` splits meshes into chunks.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct VisitOptions {
+    /// The largest number of positions passed to `SceneVisitor::on_positions_chunk` at once.
+    /// Default: 65536
+    pub positions_chunk_len: usize,
+    /// The largest number of indices passed to `SceneVisitor::on_indices_chunk` at once, unless
+    /// a single face has more. Default: 65536
+    pub indices_chunk_len: usize,
+}
+
+impl Default for VisitOptions {
+    fn default() -> Self {
+        VisitOptions {
+            positions_chunk_len: 65536,
+            indices_chunk_len: 65536,
+        }
+    }
+}
+
+macro_rules! visit_try {
+    ($control:expr) => {
+        if $control == VisitControl::Stop {
+            return VisitControl::Stop;
+        }
+    };
+}
+
+impl Scene<'_> {
+    /// Pass the contents of the scene to `visitor` piece by piece, so that it can be converted
+    /// without building a copy of every mesh, e.g. to write it to a file as it goes.
+    ///
+    /// The nodes come first, depth first with parents before their children like in
+    /// `Node::descendants`, then the meshes in order, then the materials. Positions are passed
+    /// straight from the mesh, while indices are gathered into a buffer of up to
+    /// `opts.indices_chunk_len`, which is reused for every chunk. Chunk lengths of 0 count
+    /// as 1.
+    ///
+    /// Returns `VisitControl::Stop` if a callback stopped the visit, or `Continue` otherwise.
+    pub fn visit<V: SceneVisitor>(&self, visitor: &mut V, opts: VisitOptions) -> VisitControl {
+        if let Some(root) = self.root_node() {
+            visit_try!(visit_nodes(root, visitor));
+        }
+
+        let mut indices = Vec::new();
+        for index in 0..self.num_meshes() {
+            if let Some(mesh) = self.mesh(index) {
+                visit_try!(visit_mesh(index, mesh, visitor, &opts, &mut indices));
+            }
+        }
+
+        for index in 0..self.num_materials() {
+            if let Some(material) = self.material(index) {
+                visit_try!(visitor.on_material(index, material));
+            }
+        }
+        VisitControl::Continue
+    }
+}
+
+/// Visit `root` and its descendants with an explicit stack, so that deep hierarchies can't
+/// overflow the call stack.
+fn visit_nodes<V: SceneVisitor>(root: &Node, visitor: &mut V) -> VisitControl {
+    let mut stack = vec![(root, 0, root.transform())];
+    while let Some((node, depth, world)) = stack.pop() {
+        visit_try!(visitor.on_node(node, depth, &world));
+        let len = stack.len();
+        stack.extend(
+            node.children()
+                .map(|child| (child, depth + 1, world * child.transform())),
+        );
+        stack[len..].reverse();
+    }
+    VisitControl::Continue
+}
+
+fn visit_mesh<V: SceneVisitor>(
+    index: u32,
+    mesh: &Mesh,
+    visitor: &mut V,
+    opts: &VisitOptions,
+    indices: &mut Vec<u32>,
+) -> VisitControl {
+    visit_try!(visitor.on_mesh_begin(index, mesh));
+
+    let positions = mesh.position_slice();
+    for chunk in positions.chunks(opts.positions_chunk_len.max(1)) {
+        visit_try!(visitor.on_positions_chunk(chunk));
+    }
+
+    let limit = opts.indices_chunk_len.max(1);
+    indices.clear();
+    for face in mesh.faces() {
+        let face = face.indices();
+        if !indices.is_empty() && indices.len() + face.len() > limit {
+            visit_try!(visitor.on_indices_chunk(indices));
+            indices.clear();
+        }
+        indices.extend_from_slice(face);
+    }
+    if !indices.is_empty() {
+        visit_try!(visitor.on_indices_chunk(indices));
+    }
+
+    visitor.on_mesh_end(index)
+}
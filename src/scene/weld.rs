@@ -0,0 +1,273 @@
+//! Finding the vertices that different meshes share along their seams.
+//!
+//! Importers and the `split_large_meshes` and `sort_by_primitive_type` steps split models into
+//! several meshes, e.g. one per material. The vertices on either side of a seam are at the same
+//! position, but nothing connects them, so algorithms that work on one mesh at a time leave
+//! cracks there. `cross_mesh_weld` finds them, and `smooth_normals` uses that to compute
+//! normals that are continuous across the seams.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::math::{Matrix4x4, Vector3D};
+
+use super::adjacency::{position_key, EdgeAdjacency};
+use super::bake::placements;
+use super::mesh::Mesh;
+use super::scene::Scene;
+
+/// A vertex of a mesh of a scene.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MeshVertex {
+    pub mesh: u32,
+    pub vertex: u32,
+}
+
+/// The vertices on the open boundary of each mesh that coincide with boundary vertices of
+/// other meshes, as returned by `cross_mesh_weld`. Welds go both ways: if `a` is welded to `b`,
+/// `b` is welded to `a`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct WeldMap {
+    welds: BTreeMap<MeshVertex, Vec<MeshVertex>>,
+}
+
+impl WeldMap {
+    /// The vertices of other meshes that `vertex` of `mesh` is welded to, in ascending order.
+    pub fn get(&self, mesh: u32, vertex: u32) -> &[MeshVertex] {
+        self.welds
+            .get(&MeshVertex { mesh, vertex })
+            .map_or(&[][..], |welded| &welded[..])
+    }
+
+    /// Every welded vertex with the vertices it's welded to, in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = (MeshVertex, &[MeshVertex])> {
+        self.welds
+            .iter()
+            .map(|(&vertex, welded)| (vertex, &welded[..]))
+    }
+
+    /// The number of vertices that are welded to any other.
+    pub fn len(&self) -> usize {
+        self.welds.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.welds.is_empty()
+    }
+}
+
+/// Weld the vertices on the open boundaries of the meshes of `scene` that are no more than
+/// `epsilon` apart in world space, to vertices of other meshes. The vertices within one mesh
+/// aren't welded to each other.
+///
+/// The open boundary is made of the edges that only one face has, where edges are compared by
+/// the positions of their ends like in `Mesh::smoothing_regions`. Meshes are placed in world
+/// space by the first node that references them in the order of `Node::descendants`, or used
+/// as they are if no node does. An `epsilon` of 0 welds only vertices at exactly the same
+/// position.
+///
+/// The boundary vertices are put into a spatial hash with cells of size `epsilon`, so that
+/// only those in neighbouring cells have to be compared.
+pub fn cross_mesh_weld(scene: &Scene, epsilon: f32) -> WeldMap {
+    let placements = placements(scene);
+    let mut boundary = Vec::new();
+    for (index, placement) in placements.iter().enumerate() {
+        let mesh = match scene.mesh(index as u32) {
+            Some(mesh) => mesh,
+            None => continue,
+        };
+        let positions: Vec<Vector3D> = mesh.positions().collect();
+        let faces = valid_faces(mesh);
+        let adjacency = EdgeAdjacency::new(&positions, faces.iter().cloned());
+
+        let mut open = vec![false; positions.len()];
+        for face in &faces {
+            for [a, b] in adjacency.open_edges(face) {
+                open[a as usize] = true;
+                open[b as usize] = true;
+            }
+        }
+        let world = mesh.positions_transformed(&placement[0]);
+        for (vertex, _) in open.iter().enumerate().filter(|&(_, &open)| open) {
+            let id = MeshVertex {
+                mesh: index as u32,
+                vertex: vertex as u32,
+            };
+            boundary.push((id, world[vertex]));
+        }
+    }
+
+    let reach = if epsilon > 0.0 { 1 } else { 0 };
+    let cell = |p: Vector3D| -> [i64; 3] {
+        if epsilon > 0.0 {
+            let c = |x: f32| (x / epsilon).floor() as i64;
+            [c(p.x), c(p.y), c(p.z)]
+        } else {
+            let key = position_key(p);
+            [key[0].into(), key[1].into(), key[2].into()]
+        }
+    };
+    let mut grid: HashMap<[i64; 3], Vec<usize>> = HashMap::new();
+    for (index, &(_, p)) in boundary.iter().enumerate() {
+        grid.entry(cell(p)).or_default().push(index);
+    }
+
+    let mut welds = BTreeMap::new();
+    for &(id, p) in &boundary {
+        let [x, y, z] = cell(p);
+        let mut welded = Vec::new();
+        for dx in -reach..=reach {
+            for dy in -reach..=reach {
+                for dz in -reach..=reach {
+                    let candidates = match grid.get(&[x + dx, y + dy, z + dz]) {
+                        Some(candidates) => candidates,
+                        None => continue,
+                    };
+                    for &(other, q) in candidates.iter().map(|&i| &boundary[i]) {
+                        if other.mesh != id.mesh && (q - p).length() <= epsilon {
+                            welded.push(other);
+                        }
+                    }
+                }
+            }
+        }
+        if !welded.is_empty() {
+            welded.sort();
+            welds.insert(id, welded);
+        }
+    }
+    WeldMap { welds }
+}
+
+/// Smooth normals for the vertices of every mesh of `scene`, with one `Vec` per mesh and one
+/// normal per vertex, in the space of the mesh. Assign them to `MeshData::normals` to use them.
+///
+/// The normal of a vertex is the area-weighted average of the normals of the faces around it,
+/// where vertices at the same position within a mesh, and vertices of different meshes that
+/// are welded in `welds`, count as one. Meshes are placed in world space the same way as for
+/// `cross_mesh_weld`. Vertices without a face with an area around them get a zero normal.
+pub fn smooth_normals(scene: &Scene, welds: &WeldMap) -> Vec<Vec<Vector3D>> {
+    let placements = placements(scene);
+    let meshes: Vec<Option<&Mesh>> = (0..scene.num_meshes()).map(|i| scene.mesh(i)).collect();
+    let mut offsets = Vec::with_capacity(meshes.len());
+    let mut total = 0;
+    for mesh in &meshes {
+        offsets.push(total);
+        total += mesh.map_or(0, |mesh| mesh.num_vertices() as usize);
+    }
+
+    // Join the vertices that count as one.
+    let mut groups = Groups::new(total);
+    for (mesh, &offset) in meshes.iter().zip(&offsets) {
+        let mut first = HashMap::new();
+        for (vertex, p) in mesh.iter().flat_map(|mesh| mesh.positions()).enumerate() {
+            let other = *first.entry(position_key(p)).or_insert(vertex);
+            groups.join(offset + vertex, offset + other);
+        }
+    }
+    // A `WeldMap` of another scene could refer to vertices that don't exist.
+    let global = |id: MeshVertex| match meshes.get(id.mesh as usize) {
+        Some(Some(mesh)) if id.vertex < mesh.num_vertices() => {
+            Some(offsets[id.mesh as usize] + id.vertex as usize)
+        }
+        _ => None,
+    };
+    for (a, welded) in welds.iter() {
+        for &b in welded {
+            if let (Some(a), Some(b)) = (global(a), global(b)) {
+                groups.join(a, b);
+            }
+        }
+    }
+    let roots: Vec<usize> = (0..total).map(|vertex| groups.find(vertex)).collect();
+
+    let mut sums = vec![Vector3D::new(0.0, 0.0, 0.0); total];
+    for (index, (mesh, &offset)) in meshes.iter().zip(&offsets).enumerate() {
+        let mesh = match mesh {
+            Some(mesh) => mesh,
+            None => continue,
+        };
+        let world = mesh.positions_transformed(&placements[index][0]);
+        for face in valid_faces(mesh) {
+            let normal = area_normal(&world, face);
+            for &vertex in face {
+                let root = roots[offset + vertex as usize];
+                sums[root] = sums[root] + normal;
+            }
+        }
+    }
+
+    meshes
+        .iter()
+        .zip(&offsets)
+        .enumerate()
+        .map(|(index, (mesh, &offset))| {
+            let count = mesh.map_or(0, |mesh| mesh.num_vertices() as usize);
+            let world = &placements[index][0];
+            (offset..offset + count)
+                .map(|vertex| to_local(world, sums[roots[vertex]]))
+                .collect()
+        })
+        .collect()
+}
+
+/// The faces of `mesh` with at least three indices, all of which are in range.
+fn valid_faces(mesh: &Mesh) -> Vec<&[u32]> {
+    mesh.faces()
+        .map(|face| face.indices())
+        .filter(|face| face.len() >= 3 && face.iter().all(|&i| i < mesh.num_vertices()))
+        .collect()
+}
+
+/// The normal of a polygon by Newell's method, with a length of twice its area.
+fn area_normal(positions: &[Vector3D], face: &[u32]) -> Vector3D {
+    let mut normal = Vector3D::new(0.0, 0.0, 0.0);
+    for (k, &a) in face.iter().enumerate() {
+        let b = face[(k + 1) % face.len()];
+        normal = normal + positions[a as usize].cross(positions[b as usize]);
+    }
+    normal
+}
+
+/// The unit normal in the space that `world` transforms from, for the normal `normal` in world
+/// space. Normals are transformed by the inverse transpose, so this is the transpose of
+/// `world`, flipped back for transforms that mirror.
+fn to_local(world: &Matrix4x4, normal: Vector3D) -> Vector3D {
+    let local = world.transpose().transform_vector(normal).normalize();
+    let [a, b, c] = [
+        Vector3D::new(world.a1, world.a2, world.a3),
+        Vector3D::new(world.b1, world.b2, world.b3),
+        Vector3D::new(world.c1, world.c2, world.c3),
+    ];
+    if a.dot(b.cross(c)) < 0.0 {
+        -local
+    } else {
+        local
+    }
+}
+
+/// Disjoint sets of vertices, by union-find.
+struct Groups {
+    parents: Vec<usize>,
+}
+
+impl Groups {
+    fn new(len: usize) -> Self {
+        Groups {
+            parents: (0..len).collect(),
+        }
+    }
+
+    /// The representative of the group of `index`.
+    fn find(&mut self, mut index: usize) -> usize {
+        while self.parents[index] != index {
+            self.parents[index] = self.parents[self.parents[index]];
+            index = self.parents[index];
+        }
+        index
+    }
+
+    fn join(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        self.parents[a.max(b)] = a.min(b);
+    }
+}
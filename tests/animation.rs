@@ -0,0 +1,115 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::scene::animation_process::resample;
+use assimp::scene::{Interpolation, DEFAULT_TICKS_PER_SECOND};
+use assimp::Importer;
+
+#[test]
+fn test_unspecified_ticks_per_second() {
+    // Two seconds at the default of 25 ticks per second.
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/anim_no_fps.assbin").unwrap();
+    let animation = scene.animations().next().unwrap();
+
+    assert_eq!(animation.ticks_per_second(), 0.0);
+    assert_eq!(animation.effective_fps(None), DEFAULT_TICKS_PER_SECOND);
+    assert_eq!(animation.effective_fps(Some(24.0)), 24.0);
+    assert_eq!(animation.duration(), 50.0);
+    assert_eq!(animation.duration_seconds(), 2.0);
+    assert_eq!(animation.ticks_to_seconds(25.0), 1.0);
+    assert_eq!(animation.seconds_to_ticks(0.5), 12.5);
+
+    let mut data = scene.snapshot().animations.remove(0);
+    assert_eq!(data.effective_fps(None), DEFAULT_TICKS_PER_SECOND);
+    assert_eq!(data.duration_seconds(), 2.0);
+
+    // Resampling at 5 frames per second gives a key every 5 ticks.
+    resample(&mut data, 5.0);
+    let times: Vec<f64> = data.channels[0]
+        .position_keys
+        .iter()
+        .map(|&(time, _)| time)
+        .collect();
+    let expected: Vec<f64> = (0..=10).map(|frame| frame as f64 * 5.0).collect();
+    assert_eq!(times, expected);
+}
+
+#[test]
+fn test_specified_ticks_per_second() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/anim_30fps.assbin").unwrap();
+    let animation = scene.animations().next().unwrap();
+
+    assert_eq!(animation.ticks_per_second(), 30.0);
+    assert_eq!(animation.effective_fps(None), 30.0);
+    // The default only applies to animations that don't specify their rate.
+    assert_eq!(animation.effective_fps(Some(24.0)), 30.0);
+    assert_eq!(animation.duration(), 60.0);
+    assert_eq!(animation.duration_seconds(), 2.0);
+    assert_eq!(animation.seconds_to_ticks(1.5), 45.0);
+    assert_eq!(scene.statistics().total_animation_duration, 2.0);
+
+    let data = scene.snapshot().animations.remove(0);
+    assert_eq!(data.ticks_to_seconds(data.seconds_to_ticks(1.25)), 1.25);
+    let middle = data.channels[0].position_at(data.seconds_to_ticks(1.0));
+    assert_eq!(middle.map(|position| position.x), Some(0.5));
+}
+
+#[test]
+fn test_gltf_linear_interpolation() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/step_animation.gltf").unwrap();
+    let animation = scene.animations().next().unwrap();
+    let ramped = animation.channel_for(b"Ramped").unwrap();
+    assert!(ramped
+        .position_keys()
+        .all(|key| key.interpolation() == Interpolation::Linear));
+
+    let x_at = |seconds: f64| {
+        ramped
+            .position_at(animation.seconds_to_ticks(seconds))
+            .unwrap()
+            .x
+    };
+    assert!((x_at(0.5) - 0.5).abs() < 1e-5);
+    assert!((x_at(1.25) - 1.25).abs() < 1e-5);
+    assert_eq!(x_at(-1.0), 0.0);
+    assert_eq!(x_at(3.0), 2.0);
+    assert_eq!(ramped.rotation_at(0.0), None);
+}
+
+#[cfg(feature = "assimp-5-1")]
+#[test]
+fn test_gltf_step_interpolation() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/step_animation.gltf").unwrap();
+    let animation = scene.animations().next().unwrap();
+    let stepped = animation.channel_for(b"Stepped").unwrap();
+    assert!(stepped
+        .position_keys()
+        .all(|key| key.interpolation() == Interpolation::Step));
+
+    // The value holds until the next key instead of ramping towards it.
+    let x_at = |seconds: f64| {
+        stepped
+            .position_at(animation.seconds_to_ticks(seconds))
+            .unwrap()
+            .x
+    };
+    for &seconds in &[0.0, 0.25, 0.5, 0.99] {
+        assert_eq!(x_at(seconds), 0.0);
+    }
+    assert_eq!(x_at(1.0), 1.0);
+    assert_eq!(x_at(1.5), 1.0);
+    assert_eq!(x_at(2.0), 2.0);
+
+    // Snapshots don't keep the interpolation, so they ramp.
+    let data = scene.snapshot().animations.remove(0);
+    let channel = data
+        .channels
+        .iter()
+        .find(|channel| channel.node_name == "Stepped")
+        .unwrap();
+    let middle = channel.position_at(data.seconds_to_ticks(0.5)).unwrap();
+    assert!((middle.x - 0.5).abs() < 1e-5);
+}
@@ -0,0 +1,74 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::fixtures;
+use assimp::math::{Matrix4x4, Quaternion, Vector3D};
+use assimp::scene::animation_bake::{bake_to_table, inverse_bind_matrices};
+use assimp::scene::joints::mesh_joints;
+use assimp::Importer;
+
+fn close(a: Vector3D, b: Vector3D) -> bool {
+    (a - b).length() < 1e-4
+}
+
+fn close_matrix(a: &Matrix4x4, b: &Matrix4x4) -> bool {
+    let (a, b) = (a.rows(), b.rows());
+    (0..4).all(|i| (0..4).all(|j| (a[i][j] - b[i][j]).abs() < 1e-4))
+}
+
+fn rotate(q: Quaternion, v: Vector3D) -> Vector3D {
+    let u = Vector3D::new(q.x, q.y, q.z);
+    u * (2.0 * u.dot(v)) + v * (q.w * q.w - u.dot(u)) + u.cross(v) * (2.0 * q.w)
+}
+
+#[test]
+fn test_bake_to_table() {
+    // `Bend` rotates `Tip`, one unit above `Root`, by 90 degrees about z over a second.
+    let importer = Importer::new();
+    let scene = fixtures::skinned_quad_with(&importer);
+    let mesh = scene.mesh(0).unwrap();
+    let joints = mesh_joints(&scene, mesh).unwrap();
+    let animation = scene.animation(0).unwrap();
+    let offsets = inverse_bind_matrices(mesh, &joints);
+
+    let baked = bake_to_table(animation, &joints, 4.0, Some(&offsets));
+    assert_eq!(baked.joint_names, vec!["Root", "Tip"]);
+    assert!(baked.duration > 0.5);
+    assert_eq!(
+        baked.frame_count,
+        (baked.duration * 4.0 + 1e-6).floor() as u32 + 1
+    );
+    assert_eq!(baked.matrices.len(), baked.frame_count as usize * 2);
+    assert_eq!(baked.as_floats().len(), baked.matrices.len() * 16);
+    assert!(baked.frame(baked.frame_count).is_none());
+
+    // The animation starts in the bind pose, so nothing moves.
+    for matrix in baked.frame(0).unwrap() {
+        assert!(close_matrix(matrix, &Matrix4x4::identity()), "{:?}", matrix);
+    }
+
+    // Halfway through, the top of the quad swings around `Tip`.
+    let time = animation.seconds_to_ticks(0.5);
+    let rotation = animation
+        .channel_for(b"Tip")
+        .unwrap()
+        .rotation_at(time)
+        .unwrap();
+    let frame = baked.frame(2).unwrap();
+    assert!(close_matrix(&frame[0], &Matrix4x4::identity()));
+    let tip = Vector3D::new(0.0, 1.0, 0.0);
+    let corner = Vector3D::new(1.0, 1.0, 0.0);
+    assert!(close(
+        frame[1].transform_point(corner),
+        tip + rotate(rotation, corner - tip)
+    ));
+
+    // Without the inverse bind matrices, the table holds the joint transforms.
+    let joint_table = bake_to_table(animation, &joints, 4.0, None);
+    let frame = joint_table.frame(2).unwrap();
+    assert!(close(
+        frame[1].transform_point(Vector3D::new(0.0, 0.0, 0.0)),
+        tip
+    ));
+    let x = Vector3D::new(1.0, 0.0, 0.0);
+    assert!(close(frame[1].transform_vector(x), rotate(rotation, x)));
+}
@@ -0,0 +1,143 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::math::{Quaternion, Vector3D};
+use assimp::scene::animation_compress::{
+    measure_error, reduce_animation, reduce_keys, ReduceTolerance,
+};
+use assimp::scene::{AnimationData, ChannelData};
+
+/// A rotation of `angle` radians around `axis`, which must be a unit vector.
+fn rotation(axis: Vector3D, angle: f32) -> Quaternion {
+    let (sin, cos) = (angle / 2.0).sin_cos();
+    Quaternion::new(cos, axis.x * sin, axis.y * sin, axis.z * sin)
+}
+
+/// The angle between two rotations in degrees, computed independently of the crate.
+fn angle(a: Quaternion, b: Quaternion) -> f64 {
+    let dot = a.w as f64 * b.w as f64
+        + a.x as f64 * b.x as f64
+        + a.y as f64 * b.y as f64
+        + a.z as f64 * b.z as f64;
+    (2.0 * dot.abs().min(1.0).acos()).to_degrees()
+}
+
+/// A channel with a key every frame of a second at 60 Hz, one tick per frame.
+fn channel_60hz(
+    position: impl Fn(f32) -> Vector3D,
+    rotation: impl Fn(f32) -> Quaternion,
+    scale: impl Fn(f32) -> Vector3D,
+) -> ChannelData {
+    let frames = || (0..=60).map(|frame| (frame as f64, frame as f32 / 60.0));
+    ChannelData {
+        node_name: "Node".to_owned(),
+        position_keys: frames().map(|(time, s)| (time, position(s))).collect(),
+        rotation_keys: frames().map(|(time, s)| (time, rotation(s))).collect(),
+        scaling_keys: frames().map(|(time, s)| (time, scale(s))).collect(),
+    }
+}
+
+#[test]
+fn test_linear_ramp() {
+    let channel = channel_60hz(
+        |s| Vector3D::new(0.0, 2.0 * s, -s),
+        |s| rotation(Vector3D::new(0.0, 1.0, 0.0), s),
+        |s| Vector3D::new(1.0 + s, 1.0, 1.0),
+    );
+    let mut anim = AnimationData {
+        name: "Ramp".to_owned(),
+        duration: 60.0,
+        ticks_per_second: 60.0,
+        channels: vec![channel.clone()],
+    };
+
+    let stats = reduce_animation(&mut anim, ReduceTolerance::default());
+
+    let reduced = &anim.channels[0];
+    assert_eq!(reduced.position_keys.len(), 2);
+    assert_eq!(reduced.rotation_keys.len(), 2);
+    assert_eq!(reduced.scaling_keys.len(), 2);
+    assert_eq!(reduced.position_keys[0], channel.position_keys[0]);
+    assert_eq!(reduced.position_keys[1], channel.position_keys[60]);
+    assert_eq!(reduced.rotation_keys[1], channel.rotation_keys[60]);
+
+    assert_eq!(stats.len(), 1);
+    assert_eq!(stats[0].node_name, "Node");
+    assert_eq!((stats[0].keys_before, stats[0].keys_after), (183, 6));
+    // Zero, give or take the rounding of `f32`s.
+    assert!(stats[0].max_error.position < 1e-5);
+    assert!(stats[0].max_error.rotation_degrees < 1e-2);
+    assert!(stats[0].max_error.scale < 1e-5);
+}
+
+#[test]
+fn test_curved_path() {
+    let tolerance = ReduceTolerance {
+        position: 0.01,
+        rotation_degrees: 0.5,
+        scale: 0.005,
+    };
+    // A circle, a rotation that speeds up and a pulsing scale.
+    let channel = channel_60hz(
+        |s| {
+            let angle = s * std::f32::consts::PI * 2.0;
+            Vector3D::new(angle.cos(), angle.sin(), 0.0)
+        },
+        |s| rotation(Vector3D::new(0.0, 0.0, 1.0), 3.0 * s * s),
+        |s| {
+            let scale = 1.0 + 0.1 * (s * std::f32::consts::PI * 4.0).sin();
+            Vector3D::new(scale, scale, scale)
+        },
+    );
+
+    let reduced = reduce_keys(&channel, tolerance);
+    assert!(reduced.position_keys.len() > 2 && reduced.position_keys.len() < 61);
+    assert!(reduced.rotation_keys.len() > 2 && reduced.rotation_keys.len() < 61);
+    assert!(reduced.scaling_keys.len() > 2 && reduced.scaling_keys.len() < 61);
+    assert_eq!(reduced.position_keys.last(), channel.position_keys.last());
+    // Deterministic.
+    assert_eq!(reduce_keys(&channel, tolerance), reduced);
+
+    // Resampled much more densely than `measure_error` does.
+    let (mut position, mut rotation, mut scale) = (0.0f32, 0.0f64, 0.0f32);
+    for sample in 0..=6000 {
+        let time = sample as f64 / 100.0;
+        let original_position = channel.position_at(time).unwrap();
+        position = position.max((reduced.position_at(time).unwrap() - original_position).length());
+        let original_rotation = channel.rotation_at(time).unwrap();
+        rotation = rotation.max(angle(reduced.rotation_at(time).unwrap(), original_rotation));
+        let original_scale = channel.scaling_at(time).unwrap();
+        scale = scale.max((reduced.scaling_at(time).unwrap() - original_scale).length());
+    }
+    assert!(position <= tolerance.position + 1e-6, "{}", position);
+    assert!(
+        rotation <= tolerance.rotation_degrees as f64 * 1.01,
+        "{}",
+        rotation
+    );
+    assert!(scale <= tolerance.scale + 1e-6, "{}", scale);
+
+    // The reported error is measured, so it's within the tolerance but not just the tolerance.
+    let measured = measure_error(&channel, &reduced);
+    assert!(measured.position > 0.0 && measured.position < tolerance.position);
+    assert!(measured.rotation_degrees > 0.0);
+    assert!(measured.rotation_degrees <= tolerance.rotation_degrees * 1.001);
+    assert!(measured.scale > 0.0 && measured.scale <= tolerance.scale);
+    assert!((measured.position - position).abs() < 1e-3);
+}
+
+#[test]
+fn test_short_and_empty_tracks() {
+    let channel = ChannelData {
+        node_name: "Node".to_owned(),
+        position_keys: vec![(0.0, Vector3D::new(1.0, 2.0, 3.0))],
+        rotation_keys: Vec::new(),
+        scaling_keys: vec![
+            (0.0, Vector3D::new(1.0, 1.0, 1.0)),
+            (1.0, Vector3D::new(1.0, 1.0, 1.0)),
+        ],
+    };
+
+    let reduced = reduce_keys(&channel, ReduceTolerance::default());
+    assert_eq!(reduced, channel);
+    assert_eq!(measure_error(&channel, &reduced), Default::default());
+}
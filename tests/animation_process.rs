@@ -0,0 +1,128 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::math::{Quaternion, Vector3D};
+use assimp::scene::animation_process::{resample, retain_channels, strip_constant_channels};
+use assimp::scene::{AnimationData, ChannelData};
+use assimp::Importer;
+
+/// The animation of `examples/rigged.dae`, which moves `Bone2` from (0, 1, 0) to (0, 2, 0)
+/// without rotating or scaling it.
+fn rigged_animation() -> AnimationData {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/rigged.dae").unwrap();
+    scene.snapshot().animations.remove(0)
+}
+
+fn close(a: Vector3D, b: Vector3D) -> bool {
+    (a - b).length() < 1e-4
+}
+
+#[test]
+fn test_strip_constant_channels() {
+    let mut anim = rigged_animation();
+    let channel = &anim.channels[0];
+    assert!(channel.scaling_keys.len() > 1);
+    assert!(channel.rotation_keys.len() > 1);
+
+    let stats = strip_constant_channels(&mut anim, 1e-4);
+
+    let channel = &anim.channels[0];
+    assert!(channel.scaling_keys.is_empty());
+    assert!(channel.rotation_keys.is_empty());
+    assert!(channel.position_keys.len() > 1);
+    assert_eq!(stats.channels_before, stats.channels_after);
+    assert!(stats.keys_removed() > 0);
+}
+
+#[test]
+fn test_strip_constant_non_identity_track() {
+    let scale = Vector3D::new(2.0, 2.0, 2.0);
+    let mut anim = AnimationData {
+        name: String::new(),
+        duration: 2.0,
+        ticks_per_second: 1.0,
+        channels: vec![ChannelData {
+            node_name: "Node".to_owned(),
+            position_keys: Vec::new(),
+            rotation_keys: vec![(0.0, Quaternion::identity()), (2.0, Quaternion::identity())],
+            scaling_keys: vec![(0.0, scale), (1.0, scale), (2.0, scale)],
+        }],
+    };
+
+    let stats = strip_constant_channels(&mut anim, 1e-4);
+
+    assert_eq!(anim.channels[0].scaling_keys, vec![(0.0, scale)]);
+    assert!(anim.channels[0].rotation_keys.is_empty());
+    assert_eq!((stats.keys_before, stats.keys_after), (5, 1));
+}
+
+#[test]
+fn test_retain_channels() {
+    let mut anim = rigged_animation();
+    let stats = retain_channels(&mut anim, |name| name == "Bone1");
+
+    assert!(anim.channels.is_empty());
+    assert_eq!(stats.channels_before, 1);
+    assert_eq!(stats.channels_after, 0);
+    assert_eq!(stats.keys_after, 0);
+}
+
+#[test]
+fn test_resample_linear_track() {
+    let mut anim = rigged_animation();
+    let original = anim.channels[0].clone();
+    let duration = anim.duration;
+    let (start, end) = (
+        original.position_keys[0].0,
+        original.position_keys.last().unwrap().0,
+    );
+
+    let ticks_per_second = if anim.ticks_per_second > 0.0 {
+        anim.ticks_per_second
+    } else {
+        25.0
+    };
+    let fps = 10.0 * ticks_per_second / (end - start);
+    let stats = resample(&mut anim, fps);
+
+    let keys = &anim.channels[0].position_keys;
+    assert_eq!(keys.len(), 11);
+    assert_eq!(keys[0], original.position_keys[0]);
+    assert_eq!(keys[10], *original.position_keys.last().unwrap());
+    assert_eq!(anim.duration, duration);
+    assert!(stats.keys_after > stats.keys_before);
+
+    for &(time, value) in keys {
+        let t = ((time - start) / (end - start)) as f32;
+        assert!(close(value, Vector3D::new(0.0, 1.0 + t, 0.0)));
+    }
+}
+
+#[test]
+fn test_resample_keeps_last_key() {
+    let mut anim = AnimationData {
+        name: String::new(),
+        duration: 1.0,
+        ticks_per_second: 1.0,
+        channels: vec![ChannelData {
+            node_name: "Node".to_owned(),
+            position_keys: vec![
+                (0.0, Vector3D::new(0.0, 0.0, 0.0)),
+                (1.0, Vector3D::new(1.0, 0.0, 0.0)),
+            ],
+            rotation_keys: vec![(0.0, Quaternion::identity())],
+            scaling_keys: Vec::new(),
+        }],
+    };
+
+    resample(&mut anim, 3.0 / 0.9);
+
+    let times: Vec<f64> = anim.channels[0]
+        .position_keys
+        .iter()
+        .map(|&(time, _)| time)
+        .collect();
+    assert_eq!(times.len(), 5);
+    assert_eq!(times[4], 1.0);
+    assert_eq!(anim.channels[0].rotation_keys.len(), 1);
+}
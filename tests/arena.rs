@@ -0,0 +1,98 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::scene::NodeArena;
+use assimp::Importer;
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+/// Check the links of `arena` against each other and the layout described on `NodeArena`.
+fn check_links(arena: &NodeArena) {
+    let records = arena.records();
+    assert_eq!(records[0].parent, None);
+
+    for (index, record) in records.iter().enumerate() {
+        for child in record.children.clone() {
+            assert_eq!(records[child as usize].parent, Some(index as u32));
+        }
+        if let Some(parent) = record.parent {
+            assert!(records[parent as usize].children.contains(&(index as u32)));
+        }
+    }
+
+    // Visiting the blocks of children depth-first gives every node but the root in order.
+    let mut expected_start = 1;
+    let mut stack = vec![0];
+    while let Some(index) = stack.pop() {
+        let children = records[index as usize].children.clone();
+        assert_eq!(children.start, expected_start);
+        expected_start = children.end;
+        stack.extend(children.rev());
+    }
+    assert_eq!(expected_start as usize, records.len());
+}
+
+#[test]
+fn test_node_arena() {
+    assert_send_sync::<NodeArena>();
+
+    for file in &["examples/rigged.dae", "examples/instanced.dae"] {
+        let importer = Importer::new();
+        let scene = importer.read_file(file).unwrap();
+        let arena = scene.node_arena();
+        let root = scene.root_node().unwrap();
+
+        assert_eq!(arena.len(), root.descendants().count());
+        check_links(&arena);
+
+        for node in root.descendants() {
+            let index = arena.index_of(node).unwrap();
+            let record = arena.get(index).unwrap();
+            assert_eq!(record.name, node.name());
+            assert_eq!(record.local_transform, node.transform());
+            assert_eq!(record.mesh_indices, node.meshes());
+            assert_eq!(
+                record.parent,
+                node.parent().map(|parent| arena.index_of(parent).unwrap())
+            );
+
+            let children: Vec<_> = node.children().map(|child| child.name()).collect();
+            let records: Vec<_> = arena
+                .children(index)
+                .iter()
+                .map(|child| &*child.name)
+                .collect();
+            assert_eq!(records, children);
+        }
+
+        let world = arena.world_transforms();
+        assert_eq!(world.len(), arena.len());
+        for node in root.descendants() {
+            let index = arena.index_of(node).unwrap() as usize;
+            let expected = node.world_transform().rows();
+            for (row, expected) in world[index].rows().iter().zip(expected.iter()) {
+                for (value, expected) in row.iter().zip(expected) {
+                    assert!((value - expected).abs() < 1e-4);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_node_arena_lookup() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/rigged.dae").unwrap();
+    let arena = scene.node_arena();
+
+    let bone = arena.by_name("Bone2").unwrap();
+    let node = scene.find_node(b"Bone2").unwrap();
+    assert_eq!(arena.index_of(node), Some(bone));
+    let parent = arena.get(bone).unwrap().parent.unwrap();
+    assert_eq!(arena.get(parent).unwrap().name, "Bone1");
+    assert_eq!(arena.by_name("missing"), None);
+    assert!(arena.children(arena.len() as u32).is_empty());
+
+    // Nodes of another scene aren't in the arena, even if they have the same names.
+    let other = importer.read_file("examples/rigged.dae").unwrap();
+    assert_eq!(arena.index_of(other.find_node(b"Bone2").unwrap()), None);
+}
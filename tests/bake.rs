@@ -0,0 +1,198 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::math::{Matrix4x4, Vector3D};
+use assimp::scene::bake::{pretransform_subtree, vertex_ao, AoOptions, NodeSelector, Occluders};
+use assimp::scene::{NodeData, SceneSnapshot};
+use assimp::{Error, Importer, Scene};
+
+fn mesh_index(scene: &Scene, name: &str) -> usize {
+    scene.meshes().position(|mesh| mesh.name() == name).unwrap()
+}
+
+/// The occlusion of every vertex of the mesh named `name` at the origin.
+fn at_origin(scene: &Scene, ao: &[Vec<f32>], name: &str) -> Vec<f32> {
+    let index = mesh_index(scene, name);
+    let mesh = scene.mesh(index as u32).unwrap();
+    mesh.positions()
+        .zip(&ao[index])
+        .filter(|(p, _)| p.length() < 1e-6)
+        .map(|(_, &occlusion)| occlusion)
+        .collect()
+}
+
+#[test]
+fn test_vertex_ao_plane() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/ao_walled.obj").unwrap();
+    let floor = mesh_index(&scene, "floor");
+
+    // Nothing above a flat plane blocks the sky.
+    let opts = AoOptions {
+        rays: 1024,
+        occluders: Occluders::Meshes(vec![floor as u32]),
+        ..Default::default()
+    };
+    let ao = vertex_ao(&scene, opts);
+    assert_eq!(ao.len(), scene.num_meshes() as usize);
+    assert_eq!(ao[floor].len(), 4 * 4);
+    let open = at_origin(&scene, &ao, "floor");
+    assert!(!open.is_empty());
+    assert!(open.iter().all(|&occlusion| occlusion == 0.0));
+
+    // Beside the wall, half of it is hidden.
+    let opts = AoOptions {
+        rays: 1024,
+        ..Default::default()
+    };
+    let ao = vertex_ao(&scene, opts.clone());
+    for occlusion in at_origin(&scene, &ao, "floor") {
+        assert!((occlusion - 0.5).abs() < 0.06, "{}", occlusion);
+    }
+
+    // The result only depends on the seed.
+    assert_eq!(vertex_ao(&scene, opts.clone()), ao);
+    let reseeded = vertex_ao(&scene, AoOptions { seed: 1, ..opts });
+    assert_ne!(reseeded, ao);
+}
+
+#[test]
+fn test_vertex_ao_box() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/ao_box.obj").unwrap();
+
+    let ao = vertex_ao(&scene, AoOptions::default());
+    let inside = &ao[mesh_index(&scene, "inside")];
+    assert_eq!(inside.len(), 3);
+    for &occlusion in inside {
+        assert!(occlusion > 0.99, "{}", occlusion);
+    }
+
+    // The box is further away than `max_distance`.
+    let opts = AoOptions {
+        max_distance: 0.5,
+        ..Default::default()
+    };
+    let ao = vertex_ao(&scene, opts);
+    for &occlusion in &ao[mesh_index(&scene, "inside")] {
+        assert_eq!(occlusion, 0.0);
+    }
+}
+
+fn close(a: Vector3D, b: Vector3D) -> bool {
+    (a - b).length() < 1e-5
+}
+
+/// The world-space bounds of every mesh reference in `snapshot`.
+fn world_bounds(snapshot: &SceneSnapshot) -> (Vector3D, Vector3D) {
+    fn visit(
+        snapshot: &SceneSnapshot,
+        node: &NodeData,
+        parent: Matrix4x4,
+        bounds: &mut (Vector3D, Vector3D),
+    ) {
+        let world = parent * node.transform;
+        for &mesh in &node.meshes {
+            for &p in &snapshot.meshes[mesh as usize].positions {
+                let p = world.transform_point(p);
+                let (min, max) = bounds;
+                *min = Vector3D::new(min.x.min(p.x), min.y.min(p.y), min.z.min(p.z));
+                *max = Vector3D::new(max.x.max(p.x), max.y.max(p.y), max.z.max(p.z));
+            }
+        }
+        for child in &node.children {
+            visit(snapshot, child, world, bounds);
+        }
+    }
+
+    let mut bounds = (
+        Vector3D::new(f32::INFINITY, f32::INFINITY, f32::INFINITY),
+        Vector3D::new(f32::NEG_INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY),
+    );
+    let root = snapshot.root.as_ref().unwrap();
+    visit(snapshot, root, Matrix4x4::identity(), &mut bounds);
+    bounds
+}
+
+fn find<'a>(node: &'a NodeData, name: &str) -> Option<&'a NodeData> {
+    if node.name == name {
+        return Some(node);
+    }
+    node.children.iter().find_map(|child| find(child, name))
+}
+
+#[test]
+fn test_pretransform_subtree() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/environment.gltf").unwrap();
+    let original = scene.snapshot();
+    let rock = original
+        .meshes
+        .iter()
+        .position(|m| m.name == "Rock")
+        .unwrap() as u32;
+    let crate_ = original
+        .meshes
+        .iter()
+        .position(|m| m.name == "Crate")
+        .unwrap() as u32;
+    let bounds = world_bounds(&original);
+
+    // `Environment` moves by 5 along x, and `Rock` another 2 along y.
+    let mut snapshot = original.clone();
+    let selector = NodeSelector::Name("Environment".to_owned());
+    let report = pretransform_subtree(&mut snapshot, selector, true).unwrap();
+    for (baked, unbaked) in snapshot.meshes[rock as usize]
+        .positions
+        .iter()
+        .zip(&original.meshes[rock as usize].positions)
+    {
+        assert!(close(*baked, *unbaked + Vector3D::new(5.0, 2.0, 0.0)));
+    }
+    assert_eq!(
+        snapshot.meshes[rock as usize].normals,
+        original.meshes[rock as usize].normals
+    );
+
+    // `Prop` outside the subtree still sees the original crate, and `Crate` a moved copy.
+    assert_eq!(report.duplicated, vec![(crate_, 2)]);
+    assert_eq!(report.baked.len(), 2);
+    assert_eq!(snapshot.meshes.len(), 3);
+    assert_eq!(
+        snapshot.meshes[crate_ as usize],
+        original.meshes[crate_ as usize]
+    );
+    let root = snapshot.root.as_ref().unwrap();
+    assert_eq!(find(root, "Prop").unwrap().meshes, vec![crate_]);
+    assert_eq!(find(root, "Crate").unwrap().meshes, vec![2]);
+    for name in &["Environment", "Rock", "Crate"] {
+        assert_eq!(find(root, name).unwrap().transform, Matrix4x4::identity());
+    }
+    assert_ne!(
+        find(root, "Character").unwrap().transform,
+        Matrix4x4::identity()
+    );
+
+    let (min, max) = world_bounds(&snapshot);
+    assert!(close(min, bounds.0) && close(max, bounds.1));
+
+    // The same node by its arena index, collapsed into one node.
+    let index = scene.node_arena().by_name("Environment").unwrap();
+    let mut collapsed = original.clone();
+    let report = pretransform_subtree(&mut collapsed, NodeSelector::Index(index), false).unwrap();
+    assert_eq!(report.removed_nodes, 2);
+    let root = collapsed.root.as_ref().unwrap();
+    let environment = find(root, "Environment").unwrap();
+    assert!(environment.children.is_empty());
+    assert_eq!(environment.meshes, vec![rock, 2]);
+    assert_eq!(collapsed.meshes, snapshot.meshes);
+    let (min, max) = world_bounds(&collapsed);
+    assert!(close(min, bounds.0) && close(max, bounds.1));
+
+    let mut unchanged = original.clone();
+    let missing = NodeSelector::Name("Nowhere".to_owned());
+    match pretransform_subtree(&mut unchanged, missing, true) {
+        Err(Error::MissingData { what }) => assert_eq!(what, "node named `Nowhere`"),
+        other => panic!("{:?}", other),
+    }
+    assert_eq!(unchanged, original);
+}
@@ -0,0 +1,183 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::fixtures;
+use assimp::scene::binary::{self, BinaryError};
+use assimp::scene::SceneSnapshot;
+use assimp::Importer;
+
+const EXAMPLES: &[&str] = &[
+    "examples/triangle.ply",
+    "examples/cameras.dae",
+    "examples/nested_light.dae",
+    "examples/rigged.gltf",
+    "examples/morph.gltf",
+    "examples/texture_transform.gltf",
+    "examples/multi_take.fbx",
+    "examples/metadata.assbin",
+    "examples/raw_texture.assbin",
+];
+
+fn triangulating() -> Importer {
+    let mut importer = Importer::new();
+    importer.triangulate(true);
+    importer
+}
+
+fn snapshot(path: &str) -> SceneSnapshot {
+    triangulating().read_file(path).unwrap().snapshot()
+}
+
+fn cube_snapshot() -> SceneSnapshot {
+    fixtures::unit_cube_with(&triangulating()).snapshot()
+}
+
+/// The fixtures and the examples, by name.
+fn snapshots() -> Vec<(&'static str, SceneSnapshot)> {
+    let importer = triangulating();
+    let mut out = vec![
+        ("unit_cube", cube_snapshot()),
+        (
+            "two_material_obj",
+            fixtures::two_material_obj_with(&importer).snapshot(),
+        ),
+        (
+            "skinned_quad",
+            fixtures::skinned_quad_with(&importer).snapshot(),
+        ),
+        (
+            "embedded_texture_glb",
+            fixtures::embedded_texture_glb_with(&importer).snapshot(),
+        ),
+    ];
+    out.extend(EXAMPLES.iter().map(|&path| (path, snapshot(path))));
+    out
+}
+
+fn encode(snapshot: &SceneSnapshot) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    binary::write(snapshot, &mut bytes).unwrap();
+    bytes
+}
+
+/// The offset of the first record of the table `tag`, read from the chunk table.
+fn table_offset(bytes: &[u8], tag: &[u8; 4]) -> usize {
+    let chunks = u32::from_le_bytes([bytes[12], bytes[13], bytes[14], bytes[15]]) as usize;
+    let entry = (0..chunks)
+        .map(|i| &bytes[32 + 24 * i..56 + 24 * i])
+        .find(|entry| &entry[..4] == tag)
+        .unwrap();
+    let mut offset = [0; 8];
+    offset.copy_from_slice(&entry[8..16]);
+    u64::from_le_bytes(offset) as usize + 16
+}
+
+#[test]
+fn test_fixtures_round_trip() {
+    for (name, snapshot) in snapshots() {
+        let bytes = encode(&snapshot);
+        assert_eq!(&bytes[..8], &binary::MAGIC);
+        assert_eq!(bytes.len() % 16, 0, "{}", name);
+
+        let read = binary::read(&bytes[..]).unwrap();
+        assert!(read == snapshot, "{} doesn't round-trip", name);
+        // Writing what was read gives the same file.
+        assert_eq!(encode(&read), bytes, "{}", name);
+    }
+}
+
+#[test]
+fn test_buffers_are_aligned() {
+    let snapshot = cube_snapshot();
+    let bytes = encode(&snapshot);
+    let mesh = table_offset(&bytes, b"MESH");
+    let data = table_offset(&bytes, b"DATA") - 16;
+    assert_eq!(data % 16, 0);
+
+    // The positions are the first buffer of the mesh, after the name and ten `u32`s.
+    let reference = &bytes[mesh + 48..mesh + 64];
+    let mut offset = [0; 8];
+    offset.copy_from_slice(&reference[..8]);
+    let start = data + u64::from_le_bytes(offset) as usize;
+    assert_eq!(start % 16, 0);
+    let x = f32::from_le_bytes([
+        bytes[start],
+        bytes[start + 1],
+        bytes[start + 2],
+        bytes[start + 3],
+    ]);
+    assert_eq!(x, snapshot.meshes[0].positions[0].x);
+}
+
+#[test]
+fn test_corrupted_offset_is_an_error() {
+    let bytes = encode(&cube_snapshot());
+    let mesh = table_offset(&bytes, b"MESH");
+
+    // Point the positions past the end of the file.
+    let mut corrupted = bytes.clone();
+    corrupted[mesh + 48..mesh + 56].copy_from_slice(&u64::MAX.to_le_bytes());
+    match binary::read(&corrupted[..]) {
+        Err(BinaryError::Corrupt { what, offset }) => {
+            assert_eq!((what, offset), ("positions", mesh + 48));
+        }
+        other => panic!("expected Corrupt, got {:?}", other),
+    }
+
+    // A misaligned buffer, a string past the end of the strings and too many records.
+    let mut corrupted = bytes.clone();
+    corrupted[mesh + 48] += 4;
+    assert!(matches!(
+        binary::read(&corrupted[..]),
+        Err(BinaryError::Corrupt { .. })
+    ));
+    let mut corrupted = bytes.clone();
+    corrupted[mesh + 4..mesh + 8].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(matches!(
+        binary::read(&corrupted[..]),
+        Err(BinaryError::Corrupt { .. })
+    ));
+    let mut corrupted = bytes.clone();
+    corrupted[mesh - 16..mesh - 12].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(matches!(
+        binary::read(&corrupted[..]),
+        Err(BinaryError::Corrupt { .. })
+    ));
+
+    // Every truncation is an error, and so is every corrupted byte that isn't plain data.
+    for len in 0..bytes.len() {
+        assert!(binary::from_bytes(&bytes[..len]).is_err());
+    }
+    for index in 0..bytes.len() {
+        let mut corrupted = bytes.clone();
+        corrupted[index] ^= 0xa5;
+        let _ = binary::from_bytes(&corrupted);
+    }
+}
+
+#[test]
+fn test_versions() {
+    let bytes = encode(&cube_snapshot());
+
+    let mut newer = bytes.clone();
+    newer[8..10].copy_from_slice(&(binary::MAJOR_VERSION + 1).to_le_bytes());
+    newer[10..12].copy_from_slice(&3u16.to_le_bytes());
+    match binary::read(&newer[..]) {
+        Err(error @ BinaryError::UnsupportedVersion { .. }) => {
+            assert_eq!(
+                error.to_string(),
+                "binary snapshot version 2.3 isn't supported, only 1.x"
+            );
+        }
+        other => panic!("expected UnsupportedVersion, got {:?}", other),
+    }
+
+    // Newer minor versions are read.
+    let mut minor = bytes.clone();
+    minor[10..12].copy_from_slice(&(binary::MINOR_VERSION + 1).to_le_bytes());
+    assert!(binary::read(&minor[..]).is_ok());
+
+    match binary::read(&b"solid ascii stl"[..]) {
+        Err(BinaryError::NotSnapshot) => {}
+        other => panic!("expected NotSnapshot, got {:?}", other),
+    }
+}
@@ -0,0 +1,74 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::scene::{BindPoseSuggestion, BindPoseThresholds};
+use assimp::{Error, Importer};
+
+#[test]
+fn test_consistent_bind_pose() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/rigged.gltf").unwrap();
+    let report = scene.check_bind_pose(0).unwrap();
+
+    assert!(report.consistent);
+    assert_eq!(report.mesh_index, 0);
+    assert_eq!(report.bones.len(), 2);
+    assert!(report.missing_nodes.is_empty());
+    assert!(report.suggestions.is_empty());
+    for bone in &report.bones {
+        assert!(!bone.flagged, "{:?}", bone);
+        assert!(bone.translation < 1e-5);
+        assert!(bone.rotation < 1e-3);
+        assert!(bone.scale < 1e-5);
+    }
+    assert_eq!(report.flagged().count(), 0);
+}
+
+#[test]
+fn test_bind_pose_in_other_units() {
+    // The same rig, with inverse bind matrices a hundred times too large, as if they were in
+    // centimetres and the nodes in metres.
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/rigged_units.gltf").unwrap();
+    let report = scene.check_bind_pose(0).unwrap();
+
+    assert!(!report.consistent);
+    let flagged: Vec<_> = report.flagged().collect();
+    assert_eq!(flagged.len(), 2);
+    let names: Vec<_> = flagged.iter().map(|bone| &*bone.name).collect();
+    assert_eq!(names, ["Bone1", "Bone2"]);
+    // Bone1 is 1 unit from the origin and Bone2 2 units, so they're 99 and 198 units off.
+    assert!((flagged[0].translation - 99.0).abs() < 1e-3);
+    assert!((flagged[1].translation - 198.0).abs() < 1e-3);
+    for bone in &flagged {
+        assert!(bone.rotation < 1e-3);
+        assert!(bone.scale < 1e-5);
+    }
+
+    assert_eq!(report.suggestions.len(), 1);
+    match report.suggestions[0] {
+        BindPoseSuggestion::ApplyGlobalScale { ratio } => assert!((ratio - 0.01).abs() < 1e-6),
+        ref other => panic!("unexpected suggestion {:?}", other),
+    }
+
+    // Loose enough thresholds accept it.
+    let thresholds = BindPoseThresholds {
+        max_translation: 200.0,
+        ..BindPoseThresholds::default()
+    };
+    let report = scene.check_bind_pose_with(0, &thresholds).unwrap();
+    assert!(report.consistent);
+    assert!(report.suggestions.is_empty());
+}
+
+#[test]
+fn test_bind_pose_of_missing_mesh() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/rigged.gltf").unwrap();
+    match scene.check_bind_pose(scene.num_meshes()) {
+        Err(Error::IndexOutOfRange { what, index, len }) => {
+            assert_eq!(what, "meshes");
+            assert_eq!(index, len);
+        }
+        other => panic!("unexpected result {:?}", other),
+    }
+}
@@ -0,0 +1,100 @@
+extern crate open_asset_importer as assimp;
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use assimp::fixtures;
+use assimp::io::{ByteBudget, ByteBudgetFileIO, IoEvent, MemoryFileIO, ObservedFileIO};
+use assimp::{Error, Importer};
+
+const SPIDER: &[u8] = include_bytes!("../examples/spider.obj");
+
+fn memory_io() -> MemoryFileIO {
+    fixtures::file_io().with_file("spider.obj", SPIDER)
+}
+
+fn small_budget() -> ByteBudget {
+    ByteBudget {
+        max_total_bytes: 4096,
+        max_file_size: 1024,
+        max_files: 2,
+    }
+}
+
+#[test]
+fn test_within_budget() {
+    // Count the bytes the files serve, under the budget.
+    let served = Arc::new(AtomicU64::new(0));
+    let counter = served.clone();
+    let memory_io = ObservedFileIO::new(memory_io(), move |event| {
+        if let IoEvent::FileRead { bytes, .. } = event {
+            counter.fetch_add(bytes as u64, Ordering::SeqCst);
+        }
+    });
+    let io = ByteBudgetFileIO::new(memory_io, small_budget());
+    let importer = Importer::new();
+    let scene = importer
+        .read_file_with_io("two_materials.obj", &io)
+        .unwrap();
+    assert!(scene.num_materials() >= 2);
+
+    // Assimp may read parts of a file more than once, e.g. to detect the format.
+    assert_eq!(io.bytes_read(), served.load(Ordering::SeqCst));
+    assert!(
+        io.bytes_read()
+            >= (fixtures::TWO_MATERIAL_OBJ.len() + fixtures::TWO_MATERIAL_MTL.len()) as u64
+    );
+    assert_eq!(io.files_opened(), 2);
+}
+
+#[test]
+fn test_total_bytes_exceeded() {
+    let budget = ByteBudget {
+        max_file_size: 1 << 20,
+        ..small_budget()
+    };
+    let io = ByteBudgetFileIO::new(memory_io(), budget);
+    let importer = Importer::new();
+    match importer.read_file_with_io("spider.obj", &io) {
+        Err(Error::BudgetExceeded {
+            what,
+            limit,
+            attempted,
+        }) => {
+            assert_eq!((what, limit), ("bytes read", 4096));
+            assert!(attempted > limit);
+        }
+        other => panic!("expected BudgetExceeded, got {:?}", other.err()),
+    }
+    assert!(io.bytes_read() <= 4096);
+}
+
+#[test]
+fn test_file_size_exceeded() {
+    let io = ByteBudgetFileIO::new(memory_io(), small_budget());
+    let importer = Importer::new();
+    match importer.read_file_with_io("spider.obj", &io) {
+        Err(error @ Error::BudgetExceeded { .. }) => {
+            let message = format!("file size exceeded the budget: {} > 1024", SPIDER.len());
+            assert_eq!(error.to_string(), message);
+        }
+        other => panic!("expected BudgetExceeded, got {:?}", other.err()),
+    }
+    // The file was refused before anything was read.
+    assert_eq!(io.bytes_read(), 0);
+    assert_eq!(io.files_opened(), 0);
+}
+
+#[test]
+fn test_files_exceeded() {
+    let budget = ByteBudget {
+        max_files: 1,
+        ..small_budget()
+    };
+    let io = ByteBudgetFileIO::new(memory_io(), budget);
+    let importer = Importer::new();
+    // The OBJ importer carries on without the materials.
+    let _ = importer.read_file_with_io("two_materials.obj", &io);
+    assert_eq!(io.files_opened(), 1);
+    assert!(io.bytes_read() >= fixtures::TWO_MATERIAL_OBJ.len() as u64);
+}
@@ -0,0 +1,183 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::math::{Matrix3x3, Matrix4x4, Quaternion, Vector3D};
+use assimp::Importer;
+
+fn close(a: f32, b: f32) -> bool {
+    (a - b).abs() < 1e-5
+}
+
+fn close_vec(a: Vector3D, b: Vector3D) -> bool {
+    (a - b).length() < 1e-5
+}
+
+fn close_mat(a: &Matrix4x4, b: &Matrix4x4) -> bool {
+    let (a, b) = (a.rows(), b.rows());
+    (0..4).all(|i| (0..4).all(|j| close(a[i][j], b[i][j])))
+}
+
+/// Quaternions `q` and `-q` are the same rotation.
+fn same_rotation(a: Quaternion, b: Quaternion) -> bool {
+    let dot = a.w * b.w + a.x * b.x + a.y * b.y + a.z * b.z;
+    close(dot.abs(), 1.0)
+}
+
+fn translation(x: f32, y: f32, z: f32) -> Matrix4x4 {
+    Matrix4x4::from_rows([
+        [1.0, 0.0, 0.0, x],
+        [0.0, 1.0, 0.0, y],
+        [0.0, 0.0, 1.0, z],
+        [0.0, 0.0, 0.0, 1.0],
+    ])
+}
+
+fn scaling(x: f32, y: f32, z: f32) -> Matrix4x4 {
+    Matrix4x4::from_rows([
+        [x, 0.0, 0.0, 0.0],
+        [0.0, y, 0.0, 0.0],
+        [0.0, 0.0, z, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ])
+}
+
+/// A rotation of 90 degrees around +Z.
+fn rotation_z() -> Matrix4x4 {
+    Matrix4x4::from_rows([
+        [0.0, -1.0, 0.0, 0.0],
+        [1.0, 0.0, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ])
+}
+
+fn arbitrary() -> Matrix4x4 {
+    Matrix4x4::from_rows([
+        [1.0, 2.0, 3.0, 4.0],
+        [-5.0, 6.0, -7.0, 8.0],
+        [9.0, 0.5, 0.25, -1.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ])
+}
+
+#[test]
+fn test_identity_matches_rust() {
+    assert_eq!(Matrix4x4::identity_assimp(), Matrix4x4::identity());
+    assert_eq!(
+        Matrix3x3::identity_assimp(),
+        Matrix3x3::new(1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0)
+    );
+}
+
+#[test]
+fn test_multiply_matches_rust() {
+    let a = arbitrary();
+    let b = translation(1.0, 2.0, 3.0) * rotation_z();
+
+    let mut product = a;
+    product.multiply_assimp(&b);
+    assert!(close_mat(&product, &(a * b)));
+
+    let mut product = b;
+    product.multiply_assimp(&a);
+    assert!(close_mat(&product, &(b * a)));
+}
+
+#[test]
+fn test_multiply_matrix3() {
+    let mut rotation = Matrix3x3::new(0.0, -1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0);
+    let copy = rotation;
+    rotation.multiply_assimp(&copy);
+
+    // Two quarter turns around Z.
+    assert_eq!(
+        rotation,
+        Matrix3x3::new(-1.0, 0.0, 0.0, 0.0, -1.0, 0.0, 0.0, 0.0, 1.0)
+    );
+}
+
+#[test]
+fn test_transpose_matches_rust() {
+    let mut mat = arbitrary();
+    mat.transpose_assimp();
+    assert_eq!(mat, arbitrary().transpose());
+
+    let mut mat3 = Matrix3x3::new(1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0);
+    mat3.transpose_assimp();
+    assert_eq!(
+        mat3,
+        Matrix3x3::new(1.0, 4.0, 7.0, 2.0, 5.0, 8.0, 3.0, 6.0, 9.0)
+    );
+}
+
+#[test]
+fn test_transform_matches_rust() {
+    let mat = arbitrary();
+    let p = Vector3D::new(0.5, -2.0, 3.0);
+
+    let mut transformed = p;
+    transformed.transform_assimp(&mat);
+    assert!(close_vec(transformed, mat.transform_point(p)));
+
+    let mut rotated = Vector3D::new(1.0, 0.0, 0.0);
+    rotated.transform_by_matrix3_assimp(&Matrix3x3::new(
+        0.0, -1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0,
+    ));
+    assert!(close_vec(rotated, Vector3D::new(0.0, 1.0, 0.0)));
+}
+
+#[test]
+fn test_quaternion_from_matrix() {
+    let half = std::f32::consts::FRAC_1_SQRT_2;
+
+    let quat = Quaternion::from_matrix_assimp(&Matrix3x3::new(
+        0.0, -1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0,
+    ));
+    assert!(same_rotation(quat, Quaternion::new(half, 0.0, 0.0, half)));
+
+    let quat = Quaternion::from_matrix_assimp(&Matrix3x3::identity_assimp());
+    assert!(same_rotation(quat, Quaternion::new(1.0, 0.0, 0.0, 0.0)));
+}
+
+#[test]
+fn test_decompose() {
+    let half = std::f32::consts::FRAC_1_SQRT_2;
+    let mat = translation(1.0, 2.0, 3.0) * rotation_z() * scaling(2.0, 3.0, 4.0);
+
+    let (scale, rotation, position) = mat.decompose_assimp();
+    assert!(close_vec(scale, Vector3D::new(2.0, 3.0, 4.0)));
+    assert!(same_rotation(
+        rotation,
+        Quaternion::new(half, 0.0, 0.0, half)
+    ));
+    assert!(close_vec(position, Vector3D::new(1.0, 2.0, 3.0)));
+}
+
+#[test]
+fn test_decompose_identity() {
+    let (scale, rotation, position) = Matrix4x4::identity().decompose_assimp();
+    assert!(close_vec(scale, Vector3D::new(1.0, 1.0, 1.0)));
+    assert!(same_rotation(rotation, Quaternion::new(1.0, 0.0, 0.0, 0.0)));
+    assert!(close_vec(position, Vector3D::new(0.0, 0.0, 0.0)));
+}
+
+#[test]
+fn test_world_transforms_match_rust() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/instanced.dae").unwrap();
+    let root = scene.root_node().unwrap();
+
+    for node in root.descendants() {
+        let mut world = node.transform();
+        let mut rust_world = node.transform();
+        let mut parent = node.parent();
+        while let Some(ancestor) = parent {
+            let mut assimp_world = ancestor.transform();
+            assimp_world.multiply_assimp(&world);
+            world = assimp_world;
+            rust_world = ancestor.transform() * rust_world;
+            parent = ancestor.parent();
+        }
+
+        assert!(close_mat(&world, &rust_world));
+    }
+}
@@ -0,0 +1,39 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::scene::{CameraProjection, DepthRange};
+use assimp::Importer;
+
+#[test]
+fn test_perspective_camera() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/cameras.dae").unwrap();
+    let camera = scene.camera().find(|c| c.name() == "persp").unwrap();
+
+    assert_eq!(camera.orthographic_width(), None);
+    match camera.projection() {
+        CameraProjection::Perspective { aspect, .. } => assert_eq!(aspect, 2.0),
+        other => panic!("Expected a perspective projection, got {:?}", other),
+    }
+
+    let proj = camera.build_projection_matrix(Some(1.0), Some(10.0), DepthRange::ZeroToOne);
+    assert_eq!(proj.d3, -1.0);
+    assert_eq!(proj.d4, 0.0);
+}
+
+#[test]
+#[cfg(feature = "assimp-5-1")]
+fn test_orthographic_camera() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/cameras.dae").unwrap();
+    let camera = scene.camera().find(|c| c.name() == "ortho").unwrap();
+
+    assert!(camera.orthographic_width().is_some());
+    match camera.projection() {
+        CameraProjection::Orthographic { aspect, .. } => assert_eq!(aspect, 2.0),
+        other => panic!("Expected an orthographic projection, got {:?}", other),
+    }
+
+    let proj = camera.build_projection_matrix(Some(1.0), Some(11.0), DepthRange::ZeroToOne);
+    assert_eq!(proj.d4, 1.0);
+    assert_eq!(proj.c3, -0.1);
+}
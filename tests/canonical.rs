@@ -0,0 +1,112 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::fixtures;
+use assimp::scene::{CanonicalOrdering, NodeData, SceneSnapshot};
+use assimp::{Importer, Scene};
+
+type Read = fn(&Importer) -> Scene<'static>;
+
+fn import(read: Read) -> (SceneSnapshot, CanonicalOrdering) {
+    let mut importer = Importer::new();
+    importer.obj_options(|o| o.separate_by_material = true);
+    let scene = read(&importer);
+    (scene.snapshot(), scene.canonical_order())
+}
+
+fn lods(importer: &Importer) -> Scene<'static> {
+    importer.read_file("examples/lods.dae").unwrap()
+}
+
+fn rigged(importer: &Importer) -> Scene<'static> {
+    importer.read_file("examples/rigged.dae").unwrap()
+}
+
+/// The names of the meshes referenced by each node, which shouldn't change when reordering.
+fn node_meshes(snapshot: &SceneSnapshot) -> Vec<(String, Vec<String>)> {
+    fn visit(node: &NodeData, snapshot: &SceneSnapshot, out: &mut Vec<(String, Vec<String>)>) {
+        let meshes = node
+            .meshes
+            .iter()
+            .map(|&index| snapshot.meshes[index as usize].name.clone())
+            .collect();
+        out.push((node.name.clone(), meshes));
+        for child in &node.children {
+            visit(child, snapshot, out);
+        }
+    }
+
+    let mut out = Vec::new();
+    if let Some(root) = &snapshot.root {
+        visit(root, snapshot, &mut out);
+    }
+    out
+}
+
+#[test]
+fn test_canonical_order_is_reproducible() {
+    let reads: [Read; 3] = [lods, fixtures::two_material_obj_with, rigged];
+    for &read in &reads {
+        let (mut a, order_a) = import(read);
+        let (mut b, order_b) = import(read);
+        let references = node_meshes(&a);
+
+        assert_eq!(order_a, order_b);
+        order_a.apply(&mut a);
+        order_b.apply(&mut b);
+        assert_eq!(a, b);
+        assert_eq!(node_meshes(&a), references);
+
+        // Applying the order sorts the meshes by name.
+        let names: Vec<_> = a.meshes.iter().map(|mesh| mesh.name.clone()).collect();
+        let mut sorted = names.clone();
+        sorted.sort();
+        assert_eq!(names, sorted);
+    }
+}
+
+#[test]
+fn test_shuffled_snapshot_is_restored() {
+    let (mut canonical, order) = import(lods);
+    order.apply(&mut canonical);
+    assert_eq!(canonical.canonical_order().meshes, vec![0, 1, 2, 3]);
+
+    let shuffles = [vec![3, 2, 1, 0], vec![1, 3, 0, 2], vec![2, 0, 3, 1]];
+    for shuffle in &shuffles {
+        let mut shuffled = canonical.clone();
+        CanonicalOrdering {
+            meshes: shuffle.clone(),
+            ..Default::default()
+        }
+        .apply(&mut shuffled);
+        assert_ne!(shuffled.meshes, canonical.meshes);
+        assert_eq!(node_meshes(&shuffled), node_meshes(&canonical));
+
+        shuffled.canonical_order().apply(&mut shuffled);
+        assert_eq!(shuffled, canonical);
+    }
+}
+
+#[test]
+fn test_material_remap() {
+    let (mut snapshot, order) = import(fixtures::two_material_obj_with);
+    assert_eq!(snapshot.meshes.len(), 2);
+    let materials: Vec<u32> = snapshot
+        .meshes
+        .iter()
+        .map(|mesh| mesh.material_index)
+        .collect();
+    assert_ne!(materials[0], materials[1]);
+
+    order.apply(&mut snapshot);
+    let remap = order.material_remap();
+    for (new, &old) in order.meshes.iter().enumerate() {
+        assert_eq!(
+            snapshot.meshes[new].material_index,
+            remap[materials[old as usize] as usize]
+        );
+    }
+
+    for (new, &old) in order.materials.iter().enumerate() {
+        assert_eq!(remap[old as usize], new as u32);
+    }
+}
@@ -0,0 +1,112 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::scene::cleanup::{
+    find_degenerate_faces, find_duplicate_faces, remove_faces, UnusedVertices, Winding,
+};
+use assimp::scene::{BoneData, MeshData};
+use assimp::{Matrix4x4, Vector3D};
+
+/// Two triangles of a square, a triangle whose corners are on a line, and a copy of the first
+/// triangle starting at another corner. Vertex 4 is only used by the flat triangle.
+fn mesh() -> MeshData {
+    let positions = vec![
+        Vector3D::new(0.0, 0.0, 0.0),
+        Vector3D::new(1.0, 0.0, 0.0),
+        Vector3D::new(1.0, 1.0, 0.0),
+        Vector3D::new(0.0, 1.0, 0.0),
+        Vector3D::new(2.0, 0.0, 0.0),
+    ];
+    let uvs = positions
+        .iter()
+        .map(|p| Vector3D::new(p.x, p.y, 0.0))
+        .collect();
+    MeshData {
+        normals: vec![Vector3D::new(0.0, 0.0, 1.0); positions.len()],
+        texture_coords: vec![Some(uvs)],
+        positions,
+        faces: vec![vec![0, 1, 2], vec![0, 2, 3], vec![0, 1, 4], vec![2, 0, 1]],
+        bones: vec![BoneData {
+            name: "Bone".to_owned(),
+            offset_matrix: Matrix4x4::identity(),
+            weights: vec![(1, 1.0), (4, 0.5), (3, 0.25)],
+        }],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_find_and_remove() {
+    let mut mesh = mesh();
+    let degenerate = find_degenerate_faces(&mesh, 1e-6);
+    let duplicates = find_duplicate_faces(&mesh, Winding::Respect);
+    assert_eq!(degenerate, [2]);
+    assert_eq!(duplicates, [3]);
+
+    let original = mesh.clone();
+    let faces: Vec<u32> = degenerate.into_iter().chain(duplicates).collect();
+    let kept = remove_faces(&mut mesh, &faces, UnusedVertices::Keep);
+    assert_eq!(kept, [0, 1, 2, 3, 4]);
+    assert_eq!(mesh.faces, [vec![0, 1, 2], vec![0, 2, 3]]);
+    assert_eq!(
+        MeshData {
+            faces: original.faces.clone(),
+            ..mesh.clone()
+        },
+        original
+    );
+
+    // Dropping vertex 4 leaves the others where they were.
+    let mut dropped = original.clone();
+    let kept = remove_faces(&mut dropped, &faces, UnusedVertices::Drop);
+    assert_eq!(kept, [0, 1, 2, 3]);
+    assert_eq!(dropped.faces, mesh.faces);
+    assert_eq!(dropped.positions, &original.positions[..4]);
+    assert_eq!(dropped.normals, &original.normals[..4]);
+    assert_eq!(
+        dropped.texture_coords[0].as_deref(),
+        Some(&original.texture_coords[0].as_ref().unwrap()[..4])
+    );
+    assert_eq!(dropped.bones[0].weights, [(1, 1.0), (3, 0.25)]);
+}
+
+#[test]
+fn test_drop_renumbers_vertices() {
+    let mut mesh = mesh();
+    // Without the first two faces, vertex 3 isn't used and vertex 4 takes its place.
+    let kept = remove_faces(&mut mesh, &[0, 1, 1, 7], UnusedVertices::Drop);
+    assert_eq!(kept, [0, 1, 2, 4]);
+    assert_eq!(mesh.faces, [vec![0, 1, 3], vec![2, 0, 1]]);
+    assert_eq!(mesh.positions[3], Vector3D::new(2.0, 0.0, 0.0));
+    assert_eq!(mesh.bones[0].weights, [(1, 1.0), (3, 0.5)]);
+}
+
+#[test]
+fn test_duplicate_winding() {
+    let mut mesh = mesh();
+    mesh.faces = vec![vec![0, 1, 2], vec![1, 0, 2], vec![1, 2, 0], vec![0, 2, 3]];
+    assert_eq!(find_duplicate_faces(&mesh, Winding::Respect), [2]);
+    assert_eq!(find_duplicate_faces(&mesh, Winding::Ignore), [1, 2]);
+}
+
+#[test]
+fn test_degenerate_faces() {
+    let mut mesh = mesh();
+    mesh.faces = vec![
+        vec![],
+        vec![3],
+        vec![3, 3],
+        vec![0, 2],
+        vec![0, 1, 1],
+        vec![0, 1, 2, 3],
+        vec![0, 1, 9],
+        vec![0, 1, 2, 4],
+    ];
+    // The last quad isn't flat until the epsilon is as large as its area of 0.5.
+    assert_eq!(find_degenerate_faces(&mesh, 1e-6), [0, 2, 4, 6]);
+    assert_eq!(find_degenerate_faces(&mesh, 0.5), [0, 2, 4, 6, 7]);
+
+    let mut empty = MeshData::default();
+    assert!(find_degenerate_faces(&empty, 1e-6).is_empty());
+    assert!(find_duplicate_faces(&empty, Winding::Ignore).is_empty());
+    assert!(remove_faces(&mut empty, &[0], UnusedVertices::Drop).is_empty());
+}
@@ -0,0 +1,199 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::fixtures;
+use assimp::scene::collision::{
+    compute_bounding_sphere, compute_convex_hull, compute_obb, CollisionError,
+};
+use assimp::{Importer, Vector3D};
+
+const EPSILON: f32 = 1e-5;
+
+fn corners(min: [f32; 3], max: [f32; 3]) -> Vec<Vector3D> {
+    let mut corners = Vec::new();
+    for &x in &[min[0], max[0]] {
+        for &y in &[min[1], max[1]] {
+            for &z in &[min[2], max[2]] {
+                corners.push(Vector3D::new(x, y, z));
+            }
+        }
+    }
+    corners
+}
+
+fn assert_close(a: Vector3D, b: Vector3D) {
+    assert!((a - b).length() < EPSILON, "{:?} != {:?}", a, b);
+}
+
+#[test]
+fn test_cube_hull() {
+    let mut points = corners([-1.0, -1.0, -1.0], [1.0, 1.0, 1.0]);
+    // Points inside the cube, or in the middle of its faces and edges, aren't corners.
+    points.push(Vector3D::new(0.0, 0.0, 0.0));
+    points.push(Vector3D::new(0.25, -0.5, 0.75));
+    points.push(Vector3D::new(1.0, 0.0, 0.0));
+    points.push(Vector3D::new(1.0, 1.0, 0.0));
+
+    let hull = compute_convex_hull(&points).unwrap();
+    assert_eq!(hull.positions.len(), 8);
+    assert_eq!(hull.num_triangles(), 12);
+    assert_eq!(hull.normals.len(), 12);
+
+    for (triangle, &normal) in hull.indices.chunks(3).zip(&hull.normals) {
+        let [a, b, c] = [
+            hull.positions[triangle[0] as usize],
+            hull.positions[triangle[1] as usize],
+            hull.positions[triangle[2] as usize],
+        ];
+        // The normals are the outward axes, and match the winding.
+        assert!((normal.length() - 1.0).abs() < EPSILON);
+        assert!(normal.dot(a) > 0.0);
+        assert_close((b - a).cross(c - a).normalize(), normal);
+        // Every point is inside or on the hull.
+        for &p in &points {
+            assert!(normal.dot(p - a) < EPSILON);
+        }
+    }
+}
+
+#[test]
+fn test_mesh_hull() {
+    let importer = Importer::new();
+    let scene = fixtures::unit_cube_with(&importer);
+    let mesh = scene.mesh(0).unwrap();
+
+    // The 24 vertices of the cube are 8 distinct positions.
+    let hull = compute_convex_hull(mesh).unwrap();
+    assert_eq!(hull.positions.len(), 8);
+    assert_eq!(hull.num_triangles(), 12);
+
+    let sphere = compute_bounding_sphere(mesh).unwrap();
+    assert_close(sphere.center, Vector3D::new(0.0, 0.0, 0.0));
+    assert!((sphere.radius - 0.75f32.sqrt()).abs() < EPSILON);
+}
+
+#[test]
+fn test_degenerate_hull() {
+    let empty: Vec<Vector3D> = Vec::new();
+    let point = Vector3D::new(1.0, 2.0, 3.0);
+    assert_eq!(
+        compute_convex_hull(&empty).unwrap_err(),
+        CollisionError::Empty
+    );
+    assert_eq!(
+        compute_convex_hull(&[point, point, point, point][..]).unwrap_err(),
+        CollisionError::Coincident
+    );
+
+    let line: Vec<_> = (0..5)
+        .map(|i| Vector3D::new(i as f32, 2.0 * i as f32, 0.0))
+        .collect();
+    assert_eq!(
+        compute_convex_hull(&line).unwrap_err(),
+        CollisionError::Collinear
+    );
+
+    let square = corners([0.0, 0.0, 1.0], [1.0, 1.0, 1.0]);
+    assert_eq!(
+        compute_convex_hull(&square).unwrap_err(),
+        CollisionError::Coplanar
+    );
+    // Fewer than 4 distinct points.
+    assert_eq!(
+        compute_convex_hull(&[square[0], square[2], square[4]][..]).unwrap_err(),
+        CollisionError::Coplanar
+    );
+
+    let mut points = corners([0.0; 3], [1.0; 3]);
+    points[5].0.y = f32::NAN;
+    assert_eq!(
+        compute_convex_hull(&points).unwrap_err(),
+        CollisionError::NonFinite { index: 5 }
+    );
+}
+
+#[test]
+fn test_bounding_sphere() {
+    // The corners of an octahedron around `center`, plus points inside it.
+    let center = Vector3D::new(1.0, -2.0, 3.0);
+    let mut points = Vec::new();
+    for &axis in &[
+        Vector3D::new(1.0, 0.0, 0.0),
+        Vector3D::new(0.0, 1.0, 0.0),
+        Vector3D::new(0.0, 0.0, 1.0),
+    ] {
+        points.push(center + axis * 2.5);
+        points.push(center - axis * 2.5);
+        points.push(center + axis * 0.5);
+    }
+    let sphere = compute_bounding_sphere(&points).unwrap();
+    assert_close(sphere.center, center);
+    assert!((sphere.radius - 2.5).abs() < EPSILON);
+
+    // The smallest sphere around an obtuse triangle has its longest side as a diameter.
+    let triangle = [
+        Vector3D::new(-2.0, 0.0, 0.0),
+        Vector3D::new(2.0, 0.0, 0.0),
+        Vector3D::new(0.0, 0.5, 0.0),
+    ];
+    let sphere = compute_bounding_sphere(&triangle[..]).unwrap();
+    assert_close(sphere.center, Vector3D::new(0.0, 0.0, 0.0));
+    assert!((sphere.radius - 2.0).abs() < EPSILON);
+
+    // An equilateral triangle's sphere is its circumcircle.
+    let triangle = [
+        Vector3D::new(1.0, 0.0, 0.0),
+        Vector3D::new(-0.5, 0.75f32.sqrt(), 0.0),
+        Vector3D::new(-0.5, -(0.75f32.sqrt()), 0.0),
+    ];
+    let sphere = compute_bounding_sphere(&triangle[..]).unwrap();
+    assert_close(sphere.center, Vector3D::new(0.0, 0.0, 0.0));
+    assert!((sphere.radius - 1.0).abs() < EPSILON);
+
+    let single = compute_bounding_sphere(&[center][..]).unwrap();
+    assert_eq!((single.center, single.radius), (center, 0.0));
+    assert_eq!(
+        compute_bounding_sphere(&Vec::<Vector3D>::new()).unwrap_err(),
+        CollisionError::Empty
+    );
+}
+
+#[test]
+fn test_axis_aligned_obb() {
+    let points = corners([-1.0, 0.0, 2.0], [1.0, 4.0, 8.0]);
+    let obb = compute_obb(&points).unwrap();
+
+    assert_close(obb.center, Vector3D::new(0.0, 2.0, 5.0));
+    // Sorted from the longest side to the shortest.
+    assert_close(obb.half_extents, Vector3D::new(3.0, 2.0, 1.0));
+    let expected = [
+        Vector3D::new(0.0, 0.0, 1.0),
+        Vector3D::new(0.0, 1.0, 0.0),
+        Vector3D::new(1.0, 0.0, 0.0),
+    ];
+    for (&axis, &expected) in obb.axes.iter().zip(&expected) {
+        assert!((axis.dot(expected).abs() - 1.0).abs() < EPSILON);
+    }
+    assert_close(obb.axes[0].cross(obb.axes[1]), obb.axes[2]);
+
+    // A flat square has no thickness.
+    let square = corners([0.0, 0.0, 1.0], [2.0, 1.0, 1.0]);
+    let obb = compute_obb(&square).unwrap();
+    assert_close(obb.half_extents, Vector3D::new(1.0, 0.5, 0.0));
+    assert_close(obb.center, Vector3D::new(1.0, 0.5, 1.0));
+}
+
+#[test]
+fn test_rotated_obb() {
+    // A 4x2x1 box rotated by 45 degrees around z.
+    let (c, s) = (0.5f32.sqrt(), 0.5f32.sqrt());
+    let points: Vec<_> = corners([-2.0, -1.0, -0.5], [2.0, 1.0, 0.5])
+        .into_iter()
+        .map(|p| Vector3D::new(c * p.x - s * p.y, s * p.x + c * p.y, p.z))
+        .collect();
+    let obb = compute_obb(&points).unwrap();
+
+    assert_close(obb.center, Vector3D::new(0.0, 0.0, 0.0));
+    assert_close(obb.half_extents, Vector3D::new(2.0, 1.0, 0.5));
+    assert!((obb.axes[0].dot(Vector3D::new(c, s, 0.0)).abs() - 1.0).abs() < EPSILON);
+    assert!((obb.axes[2].z.abs() - 1.0).abs() < EPSILON);
+}
@@ -0,0 +1,114 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::{Color4D, Importer};
+
+const PLY: &str = "ply
+format ascii 1.0
+element vertex 3
+property float x
+property float y
+property float z
+property uchar red
+property uchar green
+property uchar blue
+property uchar alpha
+element face 1
+property list uchar int vertex_indices
+end_header
+0 0 0 255 0 0 255
+1 0 0 0 188 0 128
+0 1 0 0 0 1 0
+3 0 1 2
+";
+
+fn close(a: f32, b: f32) -> bool {
+    (a - b).abs() < 1e-4
+}
+
+#[test]
+fn test_srgb_reference_values() {
+    assert_eq!(
+        Color4D::new(0.5, 0.5, 0.5, 0.5).to_srgb8(),
+        [188, 188, 188, 128]
+    );
+    assert_eq!(
+        Color4D::new(0.0, 1.0, 0.2, 1.0).to_srgb8(),
+        [0, 255, 124, 255]
+    );
+
+    let linear = Color4D::new(188.0 / 255.0, 0.0, 1.0, 0.25).to_linear();
+    assert!(close(linear.r, 0.5029));
+    assert_eq!(linear.g, 0.0);
+    assert!(close(linear.b, 1.0));
+    assert_eq!(linear.a, 0.25);
+
+    // The linear segment near black.
+    assert!(close(
+        Color4D::new(0.02, 0.0, 0.0, 1.0).to_linear().r,
+        0.02 / 12.92
+    ));
+    assert!(close(
+        Color4D::new(0.002, 0.0, 0.0, 1.0).to_srgb().r,
+        0.002 * 12.92
+    ));
+}
+
+#[test]
+fn test_srgb_round_trip() {
+    for i in 0..=255u8 {
+        let value = i as f32 / 255.0;
+        let color = Color4D::new(value, value, value, value);
+        assert_eq!(color.to_linear().to_srgb8(), [i, i, i, i]);
+    }
+}
+
+#[test]
+fn test_out_of_range_and_nan() {
+    let color = Color4D::new(-0.5, 1.5, std::f32::NAN, 2.0);
+    assert_eq!(color.to_rgba8(), [0, 255, 0, 255]);
+    assert_eq!(color.to_srgb8(), [0, 255, 0, 255]);
+
+    let linear = color.to_linear();
+    assert!(linear.b.is_nan());
+    assert_eq!(linear.a, 2.0);
+}
+
+#[test]
+fn test_luminance_and_lerp() {
+    assert!(close(Color4D::new(1.0, 1.0, 1.0, 0.0).luminance(), 1.0));
+    assert!(close(Color4D::new(0.0, 1.0, 0.0, 1.0).luminance(), 0.7152));
+
+    let a = Color4D::new(0.0, 0.0, 0.0, 0.0);
+    let b = Color4D::new(1.0, 0.5, 0.25, 1.0);
+    assert_eq!(a.lerp(b, 0.0), a);
+    assert_eq!(a.lerp(b, 1.0), b);
+    assert_eq!(a.lerp(b, 0.5), Color4D::new(0.5, 0.25, 0.125, 0.5));
+}
+
+#[test]
+fn test_mesh_vertex_colors() {
+    let importer = Importer::new();
+    let scene = importer
+        .read_memory_with_hint(PLY.as_bytes(), "ply")
+        .unwrap();
+    let mesh = scene.mesh(0).unwrap();
+
+    assert_eq!(
+        mesh.vertex_colors_rgba8(0).unwrap(),
+        vec![[255, 0, 0, 255], [0, 188, 0, 128], [0, 0, 1, 0]]
+    );
+
+    let linear = mesh.vertex_colors_srgb_to_linear(0).unwrap();
+    assert!(close(linear[1].g, 0.5029));
+    assert!(close(linear[1].a, 128.0 / 255.0));
+
+    let srgb = mesh.vertex_colors_linear_to_srgb(0).unwrap();
+    assert!(close(srgb[0].r, 1.0));
+    assert_eq!(srgb[0].g, 0.0);
+
+    let luminance = mesh.map_colors(0, Color4D::luminance).unwrap();
+    assert!(close(luminance[0], 0.2126));
+
+    assert!(mesh.vertex_colors_rgba8(1).is_none());
+    assert!(mesh.map_colors(8, |c| c).is_none());
+}
@@ -0,0 +1,13 @@
+//! Borrows of a scene's data, e.g. names and index slices, can't outlive the `Scene`, and the
+//! raw constructors that pick their own lifetime are `unsafe`.
+
+/// The `.stderr` files hold the errors of stable rustc, whose wording changes between
+/// versions, so this only runs when asked for, as CI does on stable:
+/// `cargo test --test compile_fail -- --ignored`. Run it with `TRYBUILD=overwrite` to update
+/// the files after a new stable release.
+#[test]
+#[ignore]
+fn test_compile_fail() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/compile_fail/*.rs");
+}
@@ -0,0 +1,10 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::fixtures;
+
+fn main() {
+    let scene = fixtures::unit_cube();
+    let indices = scene.mesh(0).unwrap().faces().next().unwrap().indices();
+    drop(scene);
+    println!("{:?}", indices);
+}
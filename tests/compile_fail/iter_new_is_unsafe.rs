@@ -0,0 +1,8 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::math::Vector3DIter;
+
+fn main() {
+    let iter: Vector3DIter<'static> = Vector3DIter::new(None, 0);
+    println!("{}", iter.len());
+}
@@ -0,0 +1,10 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::fixtures;
+
+fn main() {
+    let scene = fixtures::unit_cube();
+    let name = scene.mesh(0).unwrap().name();
+    drop(scene);
+    println!("{}", name);
+}
@@ -0,0 +1,167 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::import::structs::{
+    CalcTangentSpace, ComponentTypes, FbxOptions, ImporterConfig, PreTransformVertices,
+    RemoveComponent,
+};
+use assimp::{Importer, Matrix4x4};
+
+fn translation() -> Matrix4x4 {
+    Matrix4x4::from_rows([
+        [1.0, 0.0, 0.0, 2.0],
+        [0.0, 1.0, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ])
+}
+
+/// The configuration that the tests below build in other ways.
+fn closure_built() -> Importer {
+    let mut importer = Importer::new();
+    importer.triangulate(true);
+    importer.calc_tangent_space(|x| {
+        x.enable = true;
+        x.max_smoothing_angle = 30.0;
+    });
+    importer.remove_component(|x| {
+        x.enable = true;
+        x.components = ComponentTypes::COLORS | ComponentTypes::LIGHTS;
+    });
+    importer.pre_transform_vertices(|x| {
+        x.enable = true;
+        x.add_root_transformation = true;
+        x.root_transformation = translation();
+    });
+    importer.fbx_options(|x| x.preserve_pivots = false);
+    importer
+}
+
+fn assert_same(importer: &Importer, expected: &Importer) {
+    assert_eq!(importer.effective_flags(), expected.effective_flags());
+    assert_eq!(
+        importer.properties().collect::<Vec<_>>(),
+        expected.properties().collect::<Vec<_>>()
+    );
+    assert_eq!(importer.fingerprint(), expected.fingerprint());
+}
+
+#[test]
+fn test_setters_match_closures() {
+    let mut importer = Importer::new();
+    importer.triangulate(true);
+    importer.set_calc_tangent_space(CalcTangentSpace {
+        enable: true,
+        max_smoothing_angle: 30.0,
+        ..Default::default()
+    });
+    importer.set_remove_component(RemoveComponent {
+        enable: true,
+        components: ComponentTypes::COLORS | ComponentTypes::LIGHTS,
+    });
+    importer.set_pre_transform_vertices(PreTransformVertices {
+        enable: true,
+        add_root_transformation: true,
+        root_transformation: translation(),
+        ..Default::default()
+    });
+    let mut fbx = FbxOptions::default();
+    fbx.preserve_pivots = false;
+    importer.set_fbx_options(fbx);
+
+    assert_same(&importer, &closure_built());
+}
+
+#[test]
+fn test_apply_config() {
+    let mut fbx = FbxOptions::default();
+    fbx.preserve_pivots = false;
+    let config = ImporterConfig {
+        triangulate: true,
+        calc_tangent_space: CalcTangentSpace {
+            enable: true,
+            max_smoothing_angle: 30.0,
+            ..Default::default()
+        },
+        remove_component: RemoveComponent {
+            enable: true,
+            components: ComponentTypes::COLORS | ComponentTypes::LIGHTS,
+        },
+        pre_transform_vertices: PreTransformVertices {
+            enable: true,
+            add_root_transformation: true,
+            root_transformation: translation(),
+            ..Default::default()
+        },
+        fbx_options: Some(fbx),
+        ..Default::default()
+    };
+
+    // Steps that the config doesn't enable are disabled.
+    let mut importer = Importer::new();
+    importer.flip_uvs(true);
+    importer.apply_config(config);
+    assert_same(&importer, &closure_built());
+
+    // An empty config enables nothing and sets no properties.
+    let mut importer = Importer::new();
+    importer.apply_config(ImporterConfig::default());
+    assert_same(&importer, &Importer::new());
+}
+
+#[test]
+fn test_fingerprint() {
+    assert_eq!(Importer::new().fingerprint(), Importer::new().fingerprint());
+    let importer = closure_built();
+    assert_eq!(importer.clone().fingerprint(), importer.fingerprint());
+    assert_ne!(closure_built().fingerprint(), Importer::new().fingerprint());
+
+    let mut importer = closure_built();
+    importer.calc_tangent_space(|x| {
+        x.enable = true;
+        x.max_smoothing_angle = 31.0;
+    });
+    assert_ne!(importer.fingerprint(), closure_built().fingerprint());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_config_from_toml() {
+    let components = (ComponentTypes::COLORS | ComponentTypes::LIGHTS).bits();
+    let settings = format!(
+        r#"
+            triangulate = true
+
+            [calc_tangent_space]
+            enable = true
+            max_smoothing_angle = 30.0
+
+            [remove_component]
+            enable = true
+            components = {}
+
+            [pre_transform_vertices]
+            enable = true
+            add_root_transformation = true
+            root_transformation = [
+                [1.0, 0.0, 0.0, 2.0],
+                [0.0, 1.0, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0],
+                [0.0, 0.0, 0.0, 1.0],
+            ]
+
+            [fbx_options]
+            preserve_pivots = false
+        "#,
+        components
+    );
+    let config: ImporterConfig = toml::from_str(&settings).unwrap();
+    assert!(config.obj_options.is_none());
+
+    let mut importer = Importer::new();
+    importer.apply_config(config);
+    assert_same(&importer, &closure_built());
+
+    // Bits that aren't components are rejected.
+    let invalid = "[remove_component]\ncomponents = 1\n";
+    assert!(toml::from_str::<ImporterConfig>(invalid).is_err());
+}
@@ -0,0 +1,68 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::scene::convert::{Axis, AxisConvention, Handedness};
+use assimp::scene::SourceConventions;
+use assimp::{Importer, Vector3D};
+
+#[test]
+fn test_z_up_centimeters_fbx() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/z_up_cm.fbx").unwrap();
+
+    // The unit is the file's, not the inches it was authored in.
+    let conventions = scene.source_conventions();
+    assert_eq!(
+        conventions,
+        SourceConventions {
+            up: Some(Axis::PosZ),
+            front: Some(Axis::NegY),
+            handedness: Some(Handedness::Right),
+            unit_scale_to_meters: Some(0.01),
+        }
+    );
+    assert_eq!(
+        conventions.axis_convention(),
+        Some(AxisConvention::Z_UP_RIGHT_HANDED)
+    );
+    assert_eq!(conventions.or(SourceConventions::GLTF), conventions);
+
+    let probe = importer.probe("examples/z_up_cm.fbx").unwrap();
+    assert_eq!(probe.up_axis, Some(Vector3D::new(0.0, 0.0, 1.0)));
+
+    // Other FBX files use the defaults of the format.
+    let scene = importer.read_file("examples/uv_sets.fbx").unwrap();
+    let conventions = scene.source_conventions();
+    assert_eq!(
+        conventions.axis_convention(),
+        Some(AxisConvention::Y_UP_RIGHT_HANDED)
+    );
+    assert_eq!(conventions.unit_scale_to_meters, Some(0.01));
+}
+
+#[test]
+fn test_y_up_meters_gltf() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/pbr.gltf").unwrap();
+
+    // The glTF importer records nothing, since the format fixes the conventions.
+    let conventions = scene.source_conventions();
+    assert_eq!(conventions, SourceConventions::default());
+    assert_eq!(conventions.axis_convention(), None);
+
+    let conventions = conventions.or(SourceConventions::GLTF);
+    assert_eq!(conventions.up, Some(Axis::PosY));
+    assert_eq!(conventions.unit_scale_to_meters, Some(1.0));
+    assert_eq!(
+        conventions.axis_convention(),
+        Some(AxisConvention::Y_UP_RIGHT_HANDED)
+    );
+}
+
+#[test]
+fn test_collada_applies_its_conventions() {
+    // The Collada importer turns the file to +Y up in meters instead of recording its axes.
+    let scene = Importer::new().read_file("examples/z_up.dae").unwrap();
+    let conventions = scene.source_conventions();
+    assert_eq!(conventions.up, None);
+    assert_eq!(conventions.unit_scale_to_meters, None);
+}
@@ -0,0 +1,279 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::math::{Quaternion, Vector3D};
+use assimp::scene::convert::{
+    axis_convert, basis_change, to_left_handed, to_left_handed_with, Axis, AxisConvention,
+    Handedness, LeftHandedOptions,
+};
+use assimp::scene::{AnimationData, ChannelData, MeshData, NodeData, SceneSnapshot};
+use assimp::Importer;
+
+const Z_UP: AxisConvention = AxisConvention::Z_UP_RIGHT_HANDED;
+const Y_UP: AxisConvention = AxisConvention::Y_UP_RIGHT_HANDED;
+
+fn close(a: Vector3D, b: Vector3D) -> bool {
+    (a - b).length() < 1e-5
+}
+
+fn rotate(q: Quaternion, v: Vector3D) -> Vector3D {
+    let u = Vector3D::new(q.x, q.y, q.z);
+    u * (2.0 * u.dot(v)) + v * (q.w * q.w - u.dot(u)) + u.cross(v) * (2.0 * q.w)
+}
+
+fn dot(a: Quaternion, b: Quaternion) -> f32 {
+    a.w * b.w + a.x * b.x + a.y * b.y + a.z * b.z
+}
+
+fn triangle() -> SceneSnapshot {
+    SceneSnapshot {
+        meshes: vec![MeshData {
+            positions: vec![
+                Vector3D::new(0.0, 0.0, 1.0),
+                Vector3D::new(1.0, 0.0, 0.0),
+                Vector3D::new(0.0, -1.0, 0.0),
+            ],
+            normals: vec![Vector3D::new(0.0, 0.0, 1.0); 3],
+            faces: vec![vec![0, 1, 2]],
+            ..Default::default()
+        }],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_z_up_to_y_up() {
+    let mut scene = triangle();
+    axis_convert(&mut scene, Z_UP, Y_UP).unwrap();
+
+    let mesh = &scene.meshes[0];
+    assert!(close(mesh.positions[0], Vector3D::new(0.0, 1.0, 0.0)));
+    // -Y is forward in the Z-up convention, and +Z in the Y-up one.
+    assert!(close(mesh.positions[2], Vector3D::new(0.0, 0.0, 1.0)));
+    assert!(close(mesh.positions[1], Vector3D::new(1.0, 0.0, 0.0)));
+    assert!(close(mesh.normals[0], Vector3D::new(0.0, 1.0, 0.0)));
+    assert_eq!(mesh.faces[0], vec![0, 1, 2]);
+
+    axis_convert(&mut scene, Y_UP, Z_UP).unwrap();
+    assert!(close(
+        scene.meshes[0].positions[0],
+        Vector3D::new(0.0, 0.0, 1.0)
+    ));
+    assert!(close(
+        scene.meshes[0].positions[2],
+        Vector3D::new(0.0, -1.0, 0.0)
+    ));
+}
+
+#[test]
+fn test_handedness_flips_winding() {
+    let left = AxisConvention {
+        handedness: Handedness::Left,
+        ..Y_UP
+    };
+    let m = basis_change(Y_UP, left).unwrap();
+    assert!(close(
+        m.transform_vector(Vector3D::new(1.0, 0.0, 0.0)),
+        Vector3D::new(-1.0, 0.0, 0.0)
+    ));
+
+    let mut scene = triangle();
+    axis_convert(&mut scene, Y_UP, left).unwrap();
+    assert_eq!(scene.meshes[0].faces[0], vec![2, 1, 0]);
+    assert!(close(
+        scene.meshes[0].positions[1],
+        Vector3D::new(-1.0, 0.0, 0.0)
+    ));
+}
+
+#[test]
+fn test_invalid_convention() {
+    let invalid = AxisConvention {
+        up: Axis::PosY,
+        forward: Axis::NegY,
+        handedness: Handedness::Right,
+    };
+    let mut scene = triangle();
+    assert!(axis_convert(&mut scene, invalid, Y_UP).is_err());
+    assert_eq!(scene, triangle());
+}
+
+#[test]
+fn test_animation_continuity() {
+    // A quarter turn around Z, in small steps.
+    let rotation_keys: Vec<_> = (0..=8)
+        .map(|i| {
+            let half_angle = i as f32 * std::f32::consts::FRAC_PI_4 / 8.0;
+            (
+                i as f64,
+                Quaternion::new(half_angle.cos(), 0.0, 0.0, half_angle.sin()),
+            )
+        })
+        .collect();
+    let mut scene = SceneSnapshot {
+        animations: vec![AnimationData {
+            channels: vec![ChannelData {
+                rotation_keys: rotation_keys.clone(),
+                scaling_keys: vec![(0.0, Vector3D::new(1.0, 2.0, 3.0))],
+                ..Default::default()
+            }],
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+
+    let right_left = AxisConvention {
+        handedness: Handedness::Left,
+        ..Y_UP
+    };
+    for &to in &[Y_UP, right_left] {
+        let mut converted = scene.clone();
+        axis_convert(&mut converted, Z_UP, to).unwrap();
+        let m = basis_change(Z_UP, to).unwrap();
+        let channel = &converted.animations[0].channels[0];
+
+        for (i, (time, q)) in channel.rotation_keys.iter().enumerate() {
+            assert_eq!(*time, rotation_keys[i].0);
+            let original = rotation_keys[i].1;
+
+            // Rotating a converted vector gives the converted rotated vector.
+            let v = Vector3D::new(1.0, 0.5, -0.25);
+            assert!(close(
+                rotate(*q, m.transform_vector(v)),
+                m.transform_vector(rotate(original, v))
+            ));
+
+            // No key jumps to the other hemisphere, so interpolation is unchanged.
+            if i > 0 {
+                let previous = channel.rotation_keys[i - 1].1;
+                let expected = dot(rotation_keys[i - 1].1, original);
+                assert!((dot(previous, *q) - expected).abs() < 1e-5);
+            }
+        }
+
+        assert!(close(
+            channel.scaling_keys[0].1,
+            Vector3D::new(1.0, 3.0, 2.0)
+        ));
+    }
+
+    axis_convert(&mut scene, Z_UP, Y_UP).unwrap();
+    axis_convert(&mut scene, Y_UP, Z_UP).unwrap();
+    for ((_, a), (_, b)) in scene.animations[0].channels[0]
+        .rotation_keys
+        .iter()
+        .zip(&rotation_keys)
+    {
+        assert!((dot(*a, *b) - 1.0).abs() < 1e-5);
+    }
+}
+
+#[test]
+fn test_convert_imported_scene() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/rigged.dae").unwrap();
+    let original = scene.snapshot();
+    assert_eq!(original.meshes[0].bones.len(), 2);
+    assert_eq!(original.animations.len(), 1);
+
+    let mut converted = original.clone();
+    axis_convert(&mut converted, Z_UP, Y_UP).unwrap();
+    let m = basis_change(Z_UP, Y_UP).unwrap();
+
+    for (a, b) in original.meshes[0]
+        .positions
+        .iter()
+        .zip(&converted.meshes[0].positions)
+    {
+        assert!(close(m.transform_vector(*a), *b));
+    }
+
+    // Node transforms still line up with the converted geometry.
+    let original_root = original.root.as_ref().unwrap();
+    let converted_root = converted.root.as_ref().unwrap();
+    let p = Vector3D::new(1.0, 2.0, 3.0);
+    assert!(close(
+        converted_root
+            .transform
+            .transform_point(m.transform_vector(p)),
+        m.transform_vector(original_root.transform.transform_point(p))
+    ));
+
+    let original_keys = &original.animations[0].channels[0].position_keys;
+    let converted_keys = &converted.animations[0].channels[0].position_keys;
+    assert_eq!(original_keys.len(), converted_keys.len());
+    for ((t0, a), (t1, b)) in original_keys.iter().zip(converted_keys) {
+        assert_eq!(t0, t1);
+        assert!(close(m.transform_vector(*a), *b));
+    }
+}
+
+fn assert_nodes_close(a: &NodeData, b: &NodeData) {
+    assert_eq!(a.name, b.name);
+    for (row_a, row_b) in a.transform.rows().iter().zip(&b.transform.rows()) {
+        for (x, y) in row_a.iter().zip(row_b) {
+            assert!((x - y).abs() < 1e-5, "{:?} {:?}", a.transform, b.transform);
+        }
+    }
+    assert_eq!(a.children.len(), b.children.len());
+    for (a, b) in a.children.iter().zip(&b.children) {
+        assert_nodes_close(a, b);
+    }
+}
+
+#[test]
+fn test_to_left_handed_matches_import() {
+    let file = "examples/handedness.gltf";
+    let plain = Importer::new().read_file(file).unwrap().snapshot();
+    let mut importer = Importer::new();
+    importer.make_left_handed(true);
+    importer.flip_winding_order(true);
+    importer.flip_uvs(true);
+    let expected = importer.read_file(file).unwrap().snapshot();
+
+    let mut converted = plain.clone();
+    to_left_handed(&mut converted);
+
+    let (mesh, expected_mesh) = (&converted.meshes[0], &expected.meshes[0]);
+    assert_eq!(mesh.positions.len(), expected_mesh.positions.len());
+    let vectors = mesh.positions.iter().chain(&mesh.normals);
+    let expected_vectors = expected_mesh.positions.iter().chain(&expected_mesh.normals);
+    for (a, b) in vectors.zip(expected_vectors) {
+        assert!(close(*a, *b), "{:?} {:?}", a, b);
+    }
+    assert_eq!(mesh.faces, expected_mesh.faces);
+    assert_ne!(mesh.faces, plain.meshes[0].faces);
+    let uvs = mesh.texture_coords[0].as_ref().unwrap();
+    let expected_uvs = expected_mesh.texture_coords[0].as_ref().unwrap();
+    for (a, b) in uvs.iter().zip(expected_uvs) {
+        assert!(close(*a, *b), "{:?} {:?}", a, b);
+    }
+
+    assert_nodes_close(
+        converted.root.as_ref().unwrap(),
+        expected.root.as_ref().unwrap(),
+    );
+
+    // The pose halfway through the animation.
+    let (animation, expected_animation) = (&converted.animations[0], &expected.animations[0]);
+    let time = animation.duration / 2.0;
+    for (channel, expected_channel) in animation.channels.iter().zip(&expected_animation.channels) {
+        assert_eq!(channel.node_name, expected_channel.node_name);
+        if let Some(position) = channel.position_at(time) {
+            assert!(close(position, expected_channel.position_at(time).unwrap()));
+        }
+        if let Some(rotation) = channel.rotation_at(time) {
+            let expected_rotation = expected_channel.rotation_at(time).unwrap();
+            assert!((dot(rotation, expected_rotation).abs() - 1.0).abs() < 1e-5);
+        }
+    }
+
+    // Without flipping UVs, and back again.
+    let mut unflipped = plain.clone();
+    to_left_handed_with(&mut unflipped, LeftHandedOptions { flip_uvs: false });
+    assert_eq!(
+        unflipped.meshes[0].texture_coords,
+        plain.meshes[0].texture_coords
+    );
+    to_left_handed_with(&mut unflipped, LeftHandedOptions { flip_uvs: false });
+    assert_eq!(unflipped, plain);
+}
@@ -0,0 +1,175 @@
+extern crate open_asset_importer as assimp;
+
+use std::fs;
+use std::path::PathBuf;
+
+use assimp::convert::{convert, ConvertOptions, ConvertWarning, OverwritePolicy};
+use assimp::export::ExportWarning;
+use assimp::import::structs::ImporterConfig;
+use assimp::scene::NamingStrategy;
+use assimp::{Error, Importer};
+
+fn temp_dir(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("assimp-rs-{}-{}", test, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn triangulating() -> ConvertOptions {
+    ConvertOptions {
+        config: ImporterConfig {
+            triangulate: true,
+            validate_data_structure: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_convert_obj_to_ply() {
+    let dir = temp_dir("convert-ply");
+    let output = dir.join("cube.ply");
+
+    let report = convert("examples/unit_cube.obj", &output, triangulating()).unwrap();
+    assert_eq!(report.format, "ply");
+    assert_eq!(report.files, vec![output.clone()]);
+    assert!(!report.dry_run);
+    assert_eq!((report.meshes, report.faces, report.materials), (1, 12, 1));
+    assert!(report.vertices >= 8);
+    assert_eq!(report.output_bytes, fs::metadata(&output).unwrap().len());
+    assert!(report.timings.import.import_wall_time <= report.timings.total());
+    assert_eq!(report.pruned.total(), 0);
+    // PLY files have no materials.
+    assert!(report
+        .warnings
+        .contains(&ConvertWarning::Export(ExportWarning::Dropped {
+            what: "materials",
+            count: 1
+        })));
+    // Nothing staged is left behind.
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+    // The output is the same cube.
+    let scene = Importer::new().read_file(&output).unwrap();
+    assert_eq!(scene.num_meshes(), 1);
+    let mesh = scene.mesh(0).unwrap();
+    assert_eq!(mesh.num_faces(), 12);
+    assert!(mesh
+        .positions()
+        .all(|v| v.x.abs() == 0.5 && v.y.abs() == 0.5 && v.z.abs() == 0.5));
+
+    // Existing files are only replaced if the options say so.
+    let written = fs::read(&output).unwrap();
+    match convert("examples/unit_cube.obj", &output, triangulating()) {
+        Err(Error::Io(err)) => assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists),
+        other => panic!("{:?}", other),
+    }
+    let options = ConvertOptions {
+        overwrite: OverwritePolicy::Overwrite,
+        scale: 2.0,
+        ..triangulating()
+    };
+    convert("examples/unit_cube.obj", &output, options).unwrap();
+    assert_ne!(fs::read(&output).unwrap(), written);
+    let scene = Importer::new().read_file(&output).unwrap();
+    assert!(scene.mesh(0).unwrap().positions().all(|v| v.x.abs() == 1.0));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_convert_dry_run() {
+    let dir = temp_dir("convert-dry-run");
+    let output = dir.join("cube.obj");
+
+    let options = ConvertOptions {
+        dry_run: true,
+        ..triangulating()
+    };
+    let dry = convert("examples/unit_cube.obj", &output, options).unwrap();
+    assert!(dry.dry_run);
+    assert_eq!(dry.files, vec![output.clone()]);
+    assert!(!output.exists());
+
+    let report = convert("examples/unit_cube.obj", &output, triangulating()).unwrap();
+    assert_eq!(report.output_bytes, dry.output_bytes);
+    assert_eq!(report.files, dry.files);
+    assert_eq!(report.warnings, dry.warnings);
+
+    // The textures that would be extracted are listed, but not written.
+    let options = ConvertOptions {
+        dry_run: true,
+        extract_textures: Some(NamingStrategy::Index),
+        ..Default::default()
+    };
+    let output = dir.join("textured.ply");
+    let report = convert("examples/embedded_texture.glb", &output, options).unwrap();
+    let texture = dir.join("textured_textures").join("texture_0.png");
+    assert_eq!(report.files, vec![output.clone(), texture.clone()]);
+    assert!(!output.exists() && !texture.exists());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_convert_extracts_textures() {
+    let dir = temp_dir("convert-textures");
+    let output = dir.join("textured.obj");
+    let options = ConvertOptions {
+        extract_textures: Some(NamingStrategy::Index),
+        ..Default::default()
+    };
+
+    let report = convert("examples/embedded_texture.glb", &output, options).unwrap();
+    assert_eq!(report.textures, 1);
+    assert_eq!(report.files.len(), 2);
+    for file in &report.files {
+        assert!(file.is_file(), "{}", file.display());
+    }
+    let mut names: Vec<_> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|e| e.unwrap().file_name())
+        .collect();
+    names.sort();
+    assert_eq!(names, vec!["textured.obj", "textured_textures"]);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_convert_failures_write_nothing() {
+    let dir = temp_dir("convert-failures");
+
+    match convert(
+        "examples/unit_cube.obj",
+        dir.join("cube.xyz"),
+        ConvertOptions::default(),
+    ) {
+        Err(Error::Unsupported { .. }) => {}
+        other => panic!("{:?}", other),
+    }
+    let options = ConvertOptions {
+        scale: 0.0,
+        ..Default::default()
+    };
+    match convert("examples/unit_cube.obj", dir.join("cube.ply"), options) {
+        Err(Error::InvalidConfig { .. }) => {}
+        other => panic!("{:?}", other),
+    }
+    match convert(
+        "examples/missing.obj",
+        dir.join("cube.ply"),
+        ConvertOptions::default(),
+    ) {
+        Err(Error::ImportFailed { .. }) => {}
+        other => panic!("{:?}", other),
+    }
+    // Writing into a directory that doesn't exist fails, and leaves nothing behind.
+    let output = dir.join("missing").join("cube.ply");
+    assert!(convert("examples/unit_cube.obj", &output, ConvertOptions::default()).is_err());
+
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+    fs::remove_dir_all(&dir).unwrap();
+}
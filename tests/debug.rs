@@ -0,0 +1,85 @@
+extern crate autogenerated_assimp_sys as ffi;
+extern crate open_asset_importer as assimp;
+
+use std::ptr::NonNull;
+
+use assimp::fixtures;
+use assimp::{Importer, Node};
+
+#[test]
+fn test_unit_cube_debug() {
+    let importer = Importer::new();
+    let scene = fixtures::unit_cube_with(&importer);
+
+    // With `assimp-5-2`, the number of skeletons follows.
+    assert!(format!("{:?}", scene).starts_with(
+        "Scene { num_meshes: 1, num_materials: 1, num_animations: 0, num_textures: 0, \
+         num_lights: 0, num_cameras: 0"
+    ));
+
+    let mesh = scene.mesh(0).unwrap();
+    assert_eq!(
+        format!("{:?}", mesh),
+        "Mesh { name: \"Cube\", num_vertices: 24, num_faces: 6, normals: true, tangents: false, \
+         bitangents: false, color_sets: [], uv_channels: [0], num_bones: 0, material_id: 0 }"
+    );
+
+    let material = scene.material(0).unwrap();
+    assert_eq!(
+        format!("{:?}", material),
+        "Material { name: \"DefaultMaterial\", textures: [] }"
+    );
+
+    let root = scene.root_node().unwrap();
+    let child = root.children().next().unwrap();
+    assert_eq!(
+        format!("{:?}", child),
+        format!(
+            "Node {{ name: \"Cube\", num_children: 0, meshes: [0], transform: {:?} }}",
+            child.transform()
+        )
+    );
+}
+
+#[test]
+fn test_rigged_debug() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/rigged.dae").unwrap();
+
+    let bone = scene.mesh(0).unwrap().bone(0).unwrap();
+    assert_eq!(
+        format!("{:?}", bone),
+        format!(
+            "Bone {{ name: {:?}, num_weights: {} }}",
+            bone.name(),
+            bone.num_weights()
+        )
+    );
+
+    let animation = scene.animations().next().unwrap();
+    let debug = format!("{:?}", animation);
+    assert!(debug.starts_with("Animation { name: "));
+    assert!(debug.ends_with(&format!(
+        "duration: {:?}, ticks_per_second: {:?}, num_channels: {} }}",
+        animation.duration(),
+        animation.ticks_per_second(),
+        animation.node_anims().count()
+    )));
+}
+
+#[test]
+fn test_debug_bounded() {
+    let meshes: Vec<u32> = (0..20).collect();
+    let mut raw: ffi::aiNode = unsafe { std::mem::zeroed() };
+    raw.mMeshes = meshes.as_ptr() as *mut _;
+    raw.mNumMeshes = meshes.len() as u32;
+    // Names that aren't valid UTF-8 are printed lossily rather than panicking.
+    raw.mName.data[0] = 0xffu8 as _;
+    raw.mName.length = 1;
+    let node = unsafe { Node::from_raw(NonNull::from(&raw)) };
+
+    let debug = format!("{:?}", node);
+    assert!(debug.starts_with(
+        "Node { name: \"\u{fffd}\", num_children: 0, meshes: [0, 1, 2, 3, 4, 5, 6, 7, ...], "
+    ));
+}
@@ -0,0 +1,92 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::fixtures;
+use assimp::Importer;
+
+fn close(a: f32, b: f32) -> bool {
+    (a - b).abs() < 1e-4
+}
+
+#[test]
+fn test_unit_cube_area() {
+    let mut importer = Importer::new();
+    importer.triangulate(true);
+    let scene = fixtures::unit_cube_with(&importer);
+    let mesh = scene.mesh(0).unwrap();
+    assert!(close(mesh.surface_area(), 6.0));
+    // Each side covers the whole UV square.
+    assert!(close(mesh.uv_area(0).unwrap(), 6.0));
+    assert_eq!(mesh.uv_area(1), None);
+
+    let reports = scene.texel_density_report(1024);
+    assert_eq!(reports.len(), 1);
+    let report = &reports[0];
+    assert!(close(report.local_area, 6.0));
+    assert!(close(report.world_area, 6.0));
+    assert_eq!(report.triangle_areas.count, 12);
+    assert!(close(report.triangle_areas.mean, 0.5));
+    assert!(close(report.triangle_areas.median, 0.5));
+    assert_eq!(report.degenerate, 0);
+    assert_eq!(report.non_triangles, 0);
+    assert_eq!(report.uv_channels.len(), 1);
+    assert!(close(report.uv_channels[0].uv_area, 6.0));
+    assert_eq!(report.uv_channels[0].zero_uv_area, 0);
+    assert!(close(report.uv_channels[0].ratio.median, 1.0));
+    assert!(close(report.uv_channels[0].texels_per_unit, 1024.0));
+}
+
+#[test]
+fn test_untriangulated_faces_are_skipped() {
+    let importer = Importer::new();
+    let scene = fixtures::unit_cube_with(&importer);
+    assert_eq!(scene.mesh(0).unwrap().surface_area(), 0.0);
+
+    let report = &scene.texel_density_report(1024)[0];
+    assert_eq!(report.non_triangles, 6);
+    assert_eq!(report.triangle_areas.count, 0);
+    assert_eq!(report.world_area, 0.0);
+}
+
+#[test]
+fn test_uv_area_and_world_scale() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/density.gltf").unwrap();
+    let mesh = scene.mesh(0).unwrap();
+    assert!(close(mesh.surface_area(), 1.0));
+    assert!(close(mesh.uv_area(0).unwrap(), 1.0));
+    assert!(close(mesh.uv_area(1).unwrap(), 0.25));
+    assert_eq!(mesh.uv_area(2), None);
+    assert_eq!(mesh.uv_area(100), None);
+
+    let reports = scene.texel_density_report(1024);
+    assert_eq!(reports.len(), 2);
+    let arena = scene.node_arena();
+    let unit = arena.by_name("Unit");
+    let doubled = arena.by_name("Doubled");
+    let unit = reports.iter().find(|report| report.node == unit).unwrap();
+    let doubled = reports
+        .iter()
+        .find(|report| report.node == doubled)
+        .unwrap();
+
+    for report in &[unit, doubled] {
+        assert_eq!(report.mesh_index, 0);
+        assert!(close(report.local_area, 1.0));
+        assert_eq!(report.triangle_areas.count, 2);
+        assert_eq!(report.degenerate, 1);
+        assert_eq!(report.non_triangles, 0);
+        assert_eq!(report.uv_channels.len(), 2);
+        assert!(close(report.uv_channels[0].uv_area, 1.0));
+        assert!(close(report.uv_channels[1].uv_area, 0.25));
+        assert_eq!(report.uv_channels[0].zero_uv_area, 0);
+    }
+
+    // Scaling the node by 2 quadruples the area and halves the texel density.
+    assert!(close(unit.world_area, 1.0));
+    assert!(close(doubled.world_area, 4.0));
+    assert!(close(unit.uv_channels[0].texels_per_unit, 1024.0));
+    assert!(close(doubled.uv_channels[0].texels_per_unit, 512.0));
+    assert!(close(unit.uv_channels[1].texels_per_unit, 512.0));
+    assert!(close(doubled.uv_channels[0].ratio.median, 4.0));
+    assert!(close(doubled.uv_channels[1].ratio.max, 16.0));
+}
@@ -0,0 +1,125 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::fixtures;
+use assimp::scene::diff::{self, DiffTolerance, Difference, Matcher};
+use assimp::{Importer, Mesh, Scene};
+
+/// The text of `fixtures::UNIT_CUBE_OBJ`, to edit.
+fn unit_cube() -> &'static str {
+    std::str::from_utf8(fixtures::UNIT_CUBE_OBJ).unwrap()
+}
+
+/// Import from memory so that the root node gets the same name for both files.
+fn import<'a>(importer: &'a Importer, obj: &str) -> Scene<'a> {
+    importer
+        .read_memory_with_hint(obj.as_bytes(), "obj")
+        .unwrap()
+}
+
+#[test]
+fn test_diff_identical() {
+    let obj = unit_cube();
+    let importer = Importer::new();
+    let a = import(&importer, obj);
+    let b = import(&importer, obj);
+
+    let diff = diff::diff(&a, &b, DiffTolerance::default());
+
+    assert!(diff.is_empty(), "{}", diff);
+    assert_eq!(diff.to_string(), "no differences\n");
+}
+
+#[test]
+fn test_diff_moved_vertex() {
+    let original = unit_cube();
+    let modified = original.replace("v 0.5 0.5 0.5", "v 0.5 0.6 0.5");
+    let importer = Importer::new();
+    let a = import(&importer, original);
+    let b = import(&importer, &modified);
+
+    let diff = diff::diff(&a, &b, DiffTolerance::default());
+
+    assert_eq!(diff.len(), 1, "{}", diff);
+    match &diff.differences[0] {
+        Difference::VerticesMoved {
+            mesh,
+            max_deviation,
+        } => {
+            assert_eq!(mesh, "Cube");
+            assert!((max_deviation - 0.1).abs() < 1e-5);
+        }
+        other => panic!("unexpected difference: {}", other),
+    }
+
+    let loose = DiffTolerance {
+        position: 0.2,
+        ..DiffTolerance::default()
+    };
+    assert!(diff::diff(&a, &b, loose).is_empty());
+}
+
+#[test]
+fn test_diff_renamed_object() {
+    let original = unit_cube();
+    let renamed = original.replace("o Cube", "o Box");
+    let importer = Importer::new();
+    let a = import(&importer, original);
+    let b = import(&importer, &renamed);
+    let root = a.root_node().unwrap().name().to_owned();
+
+    let diff = diff::diff(&a, &b, DiffTolerance::default());
+
+    assert_eq!(
+        diff.differences,
+        vec![
+            Difference::MeshRenamed {
+                old: "Cube".into(),
+                new: "Box".into(),
+            },
+            Difference::NodeRenamed {
+                path: format!("{}/Cube", root),
+                new_name: "Box".into(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_diff_custom_matcher() {
+    /// Only match meshes with exactly the same name.
+    struct StrictNames;
+
+    impl Matcher for StrictNames {
+        fn match_meshes(&self, a: &[&Mesh], b: &[&Mesh]) -> Vec<(usize, usize)> {
+            let mut out = Vec::new();
+            for (i, mesh) in a.iter().enumerate() {
+                if let Some(j) = b.iter().position(|other| other.name() == mesh.name()) {
+                    out.push((i, j));
+                }
+            }
+            out
+        }
+    }
+
+    let original = unit_cube();
+    let renamed = original.replace("o Cube", "o Box");
+    let importer = Importer::new();
+    let a = import(&importer, original);
+    let b = import(&importer, &renamed);
+
+    let diff = diff::diff_with(&a, &b, DiffTolerance::default(), &StrictNames);
+
+    assert_eq!(
+        &diff.differences[..2],
+        &[
+            Difference::MeshRemoved {
+                index: 0,
+                name: "Cube".into(),
+            },
+            Difference::MeshAdded {
+                index: 0,
+                name: "Box".into(),
+            },
+        ]
+    );
+}
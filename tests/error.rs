@@ -0,0 +1,252 @@
+extern crate autogenerated_assimp_sys as ffi;
+extern crate open_asset_importer as assimp;
+
+use std::convert::TryFrom;
+use std::error::Error as _;
+use std::io::{self, SeekFrom};
+use std::ptr::{self, NonNull};
+
+use assimp::fixtures;
+use assimp::io::{FileError, FileOperation};
+use assimp::scene::convert::{basis_change, Axis, AxisConvention, Handedness};
+use assimp::scene::Ownership;
+use assimp::{Error, File, FileIO, Importer, LightType, Node, Scene};
+
+/// Opens every file, but fails every read with `PermissionDenied`.
+struct DeniedIO;
+
+struct DeniedFile;
+
+impl FileIO for DeniedIO {
+    fn open(&self, _file_path: &str, _mode: &str) -> Option<Box<dyn File>> {
+        Some(Box::new(DeniedFile))
+    }
+}
+
+impl File for DeniedFile {
+    fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+        Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "not allowed",
+        ))
+    }
+
+    fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+        Err(io::ErrorKind::PermissionDenied.into())
+    }
+
+    fn tell(&mut self) -> u64 {
+        0
+    }
+
+    fn size(&mut self) -> u64 {
+        64
+    }
+
+    fn seek(&mut self, _seek_from: SeekFrom) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn flush(&mut self) {}
+
+    fn close(&mut self) {}
+}
+
+/// A scene built in memory with a single texture.
+struct RawScene {
+    scene: ffi::aiScene,
+    _texture: Box<ffi::aiTexture>,
+    _texture_ptrs: Vec<*mut ffi::aiTexture>,
+}
+
+impl RawScene {
+    fn new(texture: ffi::aiTexture, null: bool) -> RawScene {
+        let mut texture = Box::new(texture);
+        let mut texture_ptrs = vec![if null {
+            ptr::null_mut()
+        } else {
+            &mut *texture as *mut _
+        }];
+
+        let mut scene: ffi::aiScene = unsafe { std::mem::zeroed() };
+        scene.mNumTextures = 1;
+        scene.mTextures = texture_ptrs.as_mut_ptr();
+
+        RawScene {
+            scene,
+            _texture: texture,
+            _texture_ptrs: texture_ptrs,
+        }
+    }
+
+    fn scene(&self) -> Scene {
+        unsafe { Scene::from_raw(NonNull::from(&self.scene), Ownership::Borrowed) }
+    }
+}
+
+#[test]
+fn test_import_failed() {
+    let importer = Importer::new();
+    match importer.read_file("examples/non_existent_file.obj") {
+        Err(Error::ImportFailed { message }) => assert!(!message.is_empty()),
+        Err(other) => panic!("expected ImportFailed, got {:?}", other),
+        Ok(_) => panic!("imported a file that doesn't exist"),
+    }
+
+    match importer.read_memory(b"") {
+        Err(error @ Error::ImportFailed { .. }) => {
+            assert!(!error.to_string().is_empty());
+            assert!(error.source().is_none());
+        }
+        other => panic!("expected ImportFailed, got {:?}", other.err()),
+    }
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn test_export_failed() {
+    // An uncompressed texture with no width can't be encoded as PNG.
+    let mut texel: ffi::aiTexel = unsafe { std::mem::zeroed() };
+    let mut texture: ffi::aiTexture = unsafe { std::mem::zeroed() };
+    texture.mWidth = 0;
+    texture.mHeight = 1;
+    texture.pcData = &mut texel;
+    let raw = RawScene::new(texture, false);
+
+    let dir = std::env::temp_dir().join(format!("assimp-rs-error-{}", std::process::id()));
+    let result = raw
+        .scene()
+        .extract_embedded_textures(&dir, assimp::scene::NamingStrategy::Index);
+    let _ = std::fs::remove_dir_all(&dir);
+    match result {
+        Err(Error::ExportFailed { message }) => assert!(message.contains("texture 0")),
+        other => panic!("expected ExportFailed, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_invalid_config() {
+    let importer = Importer::new();
+    match importer.read_file("examples/box\0.obj") {
+        Err(Error::InvalidConfig { message }) => {
+            assert_eq!(message, "File path contains a nul byte")
+        }
+        other => panic!("expected InvalidConfig, got {:?}", other.err()),
+    }
+    assert!(matches!(
+        importer.read_memory_with_hint(b"", "o\0bj"),
+        Err(Error::InvalidConfig { .. })
+    ));
+
+    let invalid = AxisConvention {
+        up: Axis::PosY,
+        forward: Axis::NegY,
+        handedness: Handedness::Right,
+    };
+    assert!(matches!(
+        basis_change(invalid, AxisConvention::Y_UP_RIGHT_HANDED),
+        Err(Error::InvalidConfig { .. })
+    ));
+}
+
+#[test]
+fn test_io() {
+    let importer = Importer::new();
+    let error = importer
+        .read_file_with_io("box.obj", &DeniedIO)
+        .err()
+        .unwrap();
+    match &error {
+        Error::Io(err) => assert_eq!(err.kind(), io::ErrorKind::PermissionDenied),
+        other => panic!("expected Io, got {:?}", other),
+    }
+
+    // The source is the file and the operation, and its source the original error.
+    let source = error.source().unwrap();
+    let file_error = source.downcast_ref::<FileError>().unwrap();
+    assert_eq!(file_error.path(), "box.obj");
+    assert_eq!(file_error.operation(), FileOperation::Read);
+    let source = file_error.source().unwrap();
+    let source = source.downcast_ref::<io::Error>().unwrap();
+    assert_eq!(source.to_string(), "not allowed");
+
+    // The error doesn't carry over to the next import.
+    assert!(matches!(
+        importer.read_file("examples/non_existent_file.obj"),
+        Err(Error::ImportFailed { .. })
+    ));
+
+    assert!(matches!(
+        Importer::identify("examples/non_existent_file.obj"),
+        Err(Error::Io(_))
+    ));
+}
+
+#[test]
+fn test_utf8() {
+    fn name(node: &Node) -> Result<&str, Error> {
+        Ok(node.try_name()?)
+    }
+
+    let mut raw: ffi::aiNode = unsafe { std::mem::zeroed() };
+    raw.mName.data[0] = 0xffu8 as _;
+    raw.mName.length = 1;
+    let node = unsafe { Node::from_raw(NonNull::from(&raw)) };
+
+    let error = name(node).unwrap_err();
+    assert!(matches!(error, Error::Utf8(_)));
+    assert!(error.source().unwrap().is::<std::str::Utf8Error>());
+}
+
+#[test]
+fn test_index_out_of_range() {
+    let importer = Importer::new();
+    let scene = fixtures::embedded_texture_glb_with(&importer);
+    assert_eq!(scene.num_textures(), 1);
+
+    assert!(scene.embedded_texture("*0").unwrap().is_some());
+    assert!(scene
+        .embedded_texture("no_such_file.png")
+        .unwrap()
+        .is_none());
+    match scene.embedded_texture("*5") {
+        Err(Error::IndexOutOfRange { what, index, len }) => {
+            assert_eq!((what, index, len), ("textures", 5, 1))
+        }
+        other => panic!("expected IndexOutOfRange, got {:?}", other.err()),
+    }
+}
+
+#[test]
+fn test_missing_data() {
+    let raw = RawScene::new(unsafe { std::mem::zeroed() }, true);
+    match raw.scene().embedded_texture("*0") {
+        Err(Error::MissingData { what }) => assert_eq!(what, "texture 0"),
+        other => panic!("expected MissingData, got {:?}", other.err()),
+    }
+
+    // A compressed texture without any data.
+    let raw = RawScene::new(unsafe { std::mem::zeroed() }, false);
+    let dir = std::env::temp_dir().join(format!("assimp-rs-error-missing-{}", std::process::id()));
+    let result = raw
+        .scene()
+        .extract_embedded_textures(&dir, assimp::scene::NamingStrategy::Index);
+    let _ = std::fs::remove_dir_all(&dir);
+    assert!(matches!(result, Err(Error::MissingData { .. })));
+}
+
+#[test]
+fn test_unsupported() {
+    match LightType::try_from(1234) {
+        Err(Error::Unsupported { what }) => assert_eq!(what, "light source type 1234"),
+        other => panic!("expected Unsupported, got {:?}", other),
+    }
+
+    let importer = Importer::new();
+    let scene = fixtures::unit_cube_with(&importer);
+    let copy = scene.try_clone().unwrap();
+    assert!(matches!(
+        importer.apply_postprocessing(copy),
+        Err(Error::Unsupported { .. })
+    ));
+}
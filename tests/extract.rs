@@ -0,0 +1,131 @@
+extern crate open_asset_importer as assimp;
+
+use std::fs;
+
+use assimp::fixtures;
+use assimp::scene::{MaterialSource, NamingStrategy};
+use assimp::Importer;
+
+fn temp_dir(test: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("assimp-rs-{}-{}", test, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+#[test]
+fn test_extract_glb_texture() {
+    let importer = Importer::new();
+    let scene = fixtures::embedded_texture_glb_with(&importer);
+    assert_eq!(scene.num_textures(), 1);
+
+    let dir = temp_dir("extract-glb");
+    let extracted = scene
+        .extract_embedded_textures(&dir, NamingStrategy::Index)
+        .unwrap();
+    assert_eq!(extracted.len(), 1);
+    assert_eq!(extracted[0].index, 0);
+    assert_eq!(extracted[0].reference, "*0");
+    assert_eq!(extracted[0].path, std::path::Path::new("texture_0.png"));
+
+    // Compressed textures are written exactly as they were embedded.
+    let written = fs::read(dir.join(&extracted[0].path)).unwrap();
+    assert!(written.starts_with(PNG_SIGNATURE));
+    let texture = scene.textures().next().unwrap();
+    assert_eq!(Some(&written[..]), texture.compressed_data());
+
+    let mut overlay = scene.material_overlay();
+    let painted = overlay
+        .materials()
+        .position(|material| material.original().eq_name(b"Painted"))
+        .unwrap() as u32;
+    let original = overlay.material(painted).unwrap();
+    let diffuse: Vec<_> = original.diffuse().unwrap().textures.collect();
+    assert_eq!(&*diffuse[0].path, "*0");
+
+    assert!(overlay.rewrite_texture_paths(&extracted) >= 1);
+    let rewritten = overlay.material(painted).unwrap();
+    let diffuse: Vec<_> = rewritten.diffuse().unwrap().textures.collect();
+    assert_eq!(&*diffuse[0].path, "texture_0.png");
+
+    // Every rewritten path points at a file that was written.
+    for material in overlay.materials() {
+        if let Some(component) = material.diffuse() {
+            for texture in component.textures {
+                assert!(dir.join(&*texture.path).is_file());
+            }
+        }
+    }
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_extract_naming() {
+    let importer = Importer::new();
+    let scene = fixtures::embedded_texture_glb_with(&importer);
+
+    let dir = temp_dir("extract-naming");
+    let extracted = scene
+        .extract_embedded_textures(&dir, NamingStrategy::Prefix("albedo_".to_owned()))
+        .unwrap();
+    assert_eq!(extracted[0].path, std::path::Path::new("albedo_0.png"));
+    assert!(dir.join("albedo_0.png").is_file());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_extract_uncompressed_texture() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/raw_texture.assbin").unwrap();
+
+    let dir = temp_dir("extract-raw");
+    let extracted = scene
+        .extract_embedded_textures(&dir, NamingStrategy::OriginalName)
+        .unwrap();
+    assert_eq!(extracted.len(), 1);
+    let written = fs::read(dir.join(&extracted[0].path)).unwrap();
+
+    if cfg!(feature = "image") {
+        assert_eq!(extracted[0].path, std::path::Path::new("texture_0.png"));
+        assert!(written.starts_with(PNG_SIGNATURE));
+    } else {
+        // The width and height, then a red and a blue texel.
+        assert_eq!(extracted[0].path, std::path::Path::new("texture_0.bgra"));
+        assert_eq!(
+            written,
+            [2, 0, 0, 0, 1, 0, 0, 0, 0, 0, 255, 255, 255, 0, 0, 255]
+        );
+    }
+
+    let mut overlay = scene.material_overlay();
+    assert_eq!(overlay.rewrite_texture_paths(&extracted), 1);
+    let material = overlay.material(0).unwrap();
+    let diffuse: Vec<_> = material.diffuse().unwrap().textures.collect();
+    assert_eq!(std::path::Path::new(&*diffuse[0].path), extracted[0].path);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn test_extracted_png_decodes() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/raw_texture.assbin").unwrap();
+
+    let dir = temp_dir("extract-decode");
+    let extracted = scene
+        .extract_embedded_textures(&dir, NamingStrategy::Index)
+        .unwrap();
+
+    let image = image::open(dir.join(&extracted[0].path))
+        .unwrap()
+        .to_rgba8();
+    assert_eq!(image.dimensions(), (2, 1));
+    assert_eq!(image.get_pixel(0, 0).0, [255, 0, 0, 255]);
+    assert_eq!(image.get_pixel(1, 0).0, [0, 0, 255, 255]);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
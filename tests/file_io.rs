@@ -0,0 +1,277 @@
+extern crate open_asset_importer as assimp;
+
+use std::error::Error as _;
+use std::io::{self, SeekFrom};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Mutex};
+
+use assimp::fixtures;
+use assimp::io::{FileError, FileOperation, MemoryFileIO};
+use assimp::{Error, File, FileIO, Importer};
+
+/// What a `TrackingIO` saw.
+#[derive(Debug, Default)]
+struct Events {
+    opened: Vec<String>,
+    closed: Vec<String>,
+    unclosed: Vec<String>,
+}
+
+/// Serves files from memory by name, recording every open and close, panics when reading
+/// the file named `panic_on_read` and fails reads of the file named `deny_read`.
+struct TrackingIO {
+    files: MemoryFileIO,
+    panic_on_read: Option<&'static str>,
+    deny_read: Option<&'static str>,
+    events: Arc<Mutex<Events>>,
+}
+
+struct TrackingFile {
+    name: String,
+    file: Box<dyn File>,
+    panic_on_read: bool,
+    deny_read: bool,
+    events: Arc<Mutex<Events>>,
+}
+
+impl TrackingIO {
+    fn new(files: &[(&'static str, &'static [u8])]) -> TrackingIO {
+        let mut memory = MemoryFileIO::new();
+        for &(name, data) in files {
+            memory.insert(name, data);
+        }
+        TrackingIO {
+            files: memory,
+            panic_on_read: None,
+            deny_read: None,
+            events: Arc::default(),
+        }
+    }
+
+    /// Assert that every file that was opened was closed once, and return the events.
+    fn assert_balanced(&self) -> std::sync::MutexGuard<Events> {
+        let events = self.events.lock().unwrap();
+        assert!(!events.opened.is_empty());
+        let mut opened = events.opened.clone();
+        let mut closed = events.closed.clone();
+        opened.sort();
+        closed.sort();
+        assert_eq!(opened, closed);
+        // Files that Assimp left open are reported before the crate closes them.
+        assert!(events.unclosed.len() <= closed.len());
+        events
+    }
+}
+
+impl FileIO for TrackingIO {
+    fn open(&self, file_path: &str, mode: &str) -> Option<Box<dyn File>> {
+        let name = file_path.rsplit(&['/', '\\'][..]).next()?;
+        let file = self.files.open(file_path, mode)?;
+        self.events.lock().unwrap().opened.push(name.to_owned());
+        Some(Box::new(TrackingFile {
+            name: name.to_owned(),
+            file,
+            panic_on_read: self.panic_on_read == Some(name),
+            deny_read: self.deny_read == Some(name),
+            events: self.events.clone(),
+        }))
+    }
+
+    fn on_unclosed(&self, file_path: &str) {
+        self.events
+            .lock()
+            .unwrap()
+            .unclosed
+            .push(file_path.to_owned());
+    }
+}
+
+impl File for TrackingFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.panic_on_read {
+            panic!("read of {} failed", self.name);
+        }
+        if self.deny_read {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "access denied",
+            ));
+        }
+        self.file.read(buf)
+    }
+
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.file.write(buf)
+    }
+
+    fn tell(&mut self) -> u64 {
+        self.file.tell()
+    }
+
+    fn size(&mut self) -> u64 {
+        self.file.size()
+    }
+
+    fn seek(&mut self, seek_from: SeekFrom) -> io::Result<()> {
+        self.file.seek(seek_from)
+    }
+
+    fn flush(&mut self) {
+        self.file.flush()
+    }
+
+    fn close(&mut self) {
+        self.file.close();
+        self.events.lock().unwrap().closed.push(self.name.clone());
+    }
+}
+
+const OBJ: &[u8] = fixtures::TWO_MATERIAL_OBJ;
+const MTL: &[u8] = fixtures::TWO_MATERIAL_MTL;
+
+/// A glTF file whose buffer is in a file that doesn't exist.
+const MISSING_BUFFER: &[u8] = br#"{
+    "asset": {"version": "2.0"},
+    "scene": 0,
+    "scenes": [{"nodes": [0]}],
+    "nodes": [{"mesh": 0}],
+    "meshes": [{"primitives": [{"attributes": {"POSITION": 0}}]}],
+    "accessors": [{"bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3",
+        "min": [0, 0, 0], "max": [1, 1, 0]}],
+    "bufferViews": [{"buffer": 0, "byteLength": 36}],
+    "buffers": [{"byteLength": 36, "uri": "missing.bin"}]
+}"#;
+
+#[test]
+fn test_files_closed_on_success() {
+    let io = TrackingIO::new(&[("two_materials.obj", OBJ), ("two_materials.mtl", MTL)]);
+    let importer = Importer::new();
+    let scene = importer
+        .read_file_with_io("two_materials.obj", &io)
+        .unwrap();
+    assert!(scene.num_materials() >= 2);
+
+    let events = io.assert_balanced();
+    assert!(events.opened.iter().any(|name| name == "two_materials.mtl"));
+}
+
+#[test]
+fn test_files_closed_on_failure() {
+    let io = TrackingIO::new(&[("missing_buffer.gltf", MISSING_BUFFER)]);
+    let importer = Importer::new();
+    assert!(importer
+        .read_file_with_io("missing_buffer.gltf", &io)
+        .is_err());
+
+    let events = io.assert_balanced();
+    assert!(events
+        .opened
+        .iter()
+        .all(|name| name == "missing_buffer.gltf"));
+}
+
+#[test]
+fn test_files_closed_on_panic() {
+    let mut io = TrackingIO::new(&[("two_materials.obj", OBJ), ("two_materials.mtl", MTL)]);
+    io.panic_on_read = Some("two_materials.mtl");
+    let importer = Importer::new();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        importer.read_file_with_io("two_materials.obj", &io)
+    }));
+    let payload = result.err().unwrap();
+    let message = payload.downcast_ref::<String>().unwrap();
+    assert_eq!(message, "read of two_materials.mtl failed");
+    drop(io.assert_balanced());
+
+    // The panic doesn't carry over to the next import.
+    io.panic_on_read = None;
+    assert!(importer.read_file_with_io("two_materials.obj", &io).is_ok());
+    io.assert_balanced();
+}
+
+#[test]
+fn test_read_error_context() {
+    let mut io = TrackingIO::new(&[("two_materials.obj", OBJ), ("two_materials.mtl", MTL)]);
+    io.deny_read = Some("two_materials.obj");
+    let importer = Importer::new();
+    let error = importer
+        .read_file_with_io("two_materials.obj", &io)
+        .err()
+        .unwrap();
+    match &error {
+        Error::Io(err) => assert_eq!(err.kind(), io::ErrorKind::PermissionDenied),
+        other => panic!("expected Io, got {:?}", other),
+    }
+    assert!(error.to_string().contains("two_materials.obj"));
+
+    let file_error = error.source().unwrap().downcast_ref::<FileError>().unwrap();
+    assert!(file_error.path().ends_with("two_materials.obj"));
+    assert_eq!(file_error.operation(), FileOperation::Read);
+    let original = file_error
+        .source()
+        .unwrap()
+        .downcast_ref::<io::Error>()
+        .unwrap();
+    assert_eq!(original.kind(), io::ErrorKind::PermissionDenied);
+    assert_eq!(original.to_string(), "access denied");
+    drop(io.assert_balanced());
+
+    // The context doesn't carry over to the next import.
+    io.deny_read = None;
+    assert!(importer.read_file_with_io("two_materials.obj", &io).is_ok());
+}
+
+#[test]
+fn test_read_error_context_not_kept_on_success() {
+    // The OBJ importer carries on without its materials.
+    let mut io = TrackingIO::new(&[("two_materials.obj", OBJ), ("two_materials.mtl", MTL)]);
+    io.deny_read = Some("two_materials.mtl");
+    let importer = Importer::new();
+    assert!(importer.read_file_with_io("two_materials.obj", &io).is_ok());
+
+    // A failure of the next import isn't blamed on the earlier read.
+    let io = TrackingIO::new(&[("missing_buffer.gltf", MISSING_BUFFER)]);
+    match importer.read_file_with_io("missing_buffer.gltf", &io) {
+        Err(Error::ImportFailed { .. }) => {}
+        other => panic!("expected ImportFailed, got {:?}", other.err()),
+    }
+}
+
+/// Imports `inner` with a `TrackingIO` whose files all fail to read while opening any file,
+/// then serves the outer files like a `TrackingIO`.
+struct NestingIO {
+    outer: TrackingIO,
+    inner_error: Mutex<Option<Error>>,
+}
+
+impl FileIO for NestingIO {
+    fn open(&self, file_path: &str, mode: &str) -> Option<Box<dyn File>> {
+        let mut inner = TrackingIO::new(&[("missing_buffer.gltf", MISSING_BUFFER)]);
+        inner.deny_read = Some("missing_buffer.gltf");
+        let result = Importer::new().read_file_with_io("missing_buffer.gltf", &inner);
+        *self.inner_error.lock().unwrap() = result.err();
+        self.outer.open(file_path, mode)
+    }
+}
+
+#[test]
+fn test_read_error_context_nested() {
+    let mut outer = TrackingIO::new(&[("two_materials.obj", OBJ), ("two_materials.mtl", MTL)]);
+    outer.deny_read = Some("two_materials.mtl");
+    let io = NestingIO {
+        outer,
+        inner_error: Mutex::new(None),
+    };
+    let importer = Importer::new();
+
+    // The outer import succeeds without its materials, and the inner import's error stays
+    // with the inner import.
+    assert!(importer.read_file_with_io("two_materials.obj", &io).is_ok());
+    let inner_error = io.inner_error.lock().unwrap().take().unwrap();
+    let file_error = inner_error
+        .source()
+        .unwrap()
+        .downcast_ref::<FileError>()
+        .unwrap();
+    assert!(file_error.path().ends_with("missing_buffer.gltf"));
+}
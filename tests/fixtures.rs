@@ -0,0 +1,91 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::fixtures::{self, FixtureCounts};
+use assimp::MaterialComponentType;
+
+#[test]
+fn test_unit_cube() {
+    let scene = fixtures::unit_cube();
+    assert_eq!(FixtureCounts::of(&scene), fixtures::UNIT_CUBE);
+
+    let mesh = scene.mesh(0).unwrap();
+    assert!((mesh.surface_area() - 6.0).abs() < 1e-4);
+    assert_eq!(mesh.normals().len(), 24);
+    for normal in mesh.normals() {
+        assert!((normal.length() - 1.0).abs() < 1e-4);
+    }
+    assert!((mesh.uv_area(0).unwrap() - 6.0).abs() < 1e-4);
+}
+
+#[test]
+fn test_skinned_quad() {
+    let scene = fixtures::skinned_quad();
+    assert_eq!(FixtureCounts::of(&scene), fixtures::SKINNED_QUAD);
+
+    let mesh = scene.mesh(0).unwrap();
+    let mut bones: Vec<_> = mesh.bones().map(|bone| bone.name().to_owned()).collect();
+    bones.sort();
+    assert_eq!(bones, ["Root", "Tip"]);
+    for bone in mesh.bones() {
+        let weighted: Vec<u32> = bone
+            .weights()
+            .filter(|weight| weight.mWeight > 0.0)
+            .map(|weight| weight.mVertexId)
+            .collect();
+        assert_eq!(weighted.len(), 2);
+    }
+
+    let animation = scene.animations().next().unwrap();
+    assert_eq!(animation.name(), "Bend");
+    assert!(animation.channel_for(b"Tip").is_some());
+    assert!(scene.root_node().unwrap().find(b"Root").is_some());
+}
+
+#[test]
+fn test_two_material_obj() {
+    let scene = fixtures::two_material_obj();
+    assert_eq!(FixtureCounts::of(&scene), fixtures::TWO_MATERIAL);
+
+    let mut names: Vec<String> = scene
+        .meshes()
+        .map(|mesh| {
+            let material = scene.material(mesh.material_id()).unwrap();
+            material.name_cstr().to_string_lossy().into_owned()
+        })
+        .collect();
+    names.sort();
+    assert_eq!(names, ["Blue", "Red"]);
+}
+
+#[test]
+fn test_embedded_texture_glb() {
+    let scene = fixtures::embedded_texture_glb();
+    assert_eq!(FixtureCounts::of(&scene), fixtures::EMBEDDED_TEXTURE);
+
+    let texture = scene.textures().next().unwrap();
+    let png = texture.compressed_data().unwrap();
+    assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+    let painted = scene
+        .materials()
+        .find(|material| material.eq_name(b"Painted"))
+        .unwrap();
+    let diffuse = painted.texture(MaterialComponentType::Diffuse, 0).unwrap();
+    assert_eq!(&*diffuse.path, "*0");
+}
+
+/// Replay the fuzz target's seeds and regression inputs, see `fuzz/README.md`.
+#[test]
+fn test_fuzz_corpus() {
+    for dir in &[
+        "fuzz/seeds/read_from_memory",
+        "fuzz/regressions/read_from_memory",
+    ] {
+        let mut entries = 0;
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let input = std::fs::read(entry.unwrap().path()).unwrap();
+            fixtures::fuzz_read_from_memory(&input);
+            entries += 1;
+        }
+        assert!(entries > 0, "{} is empty", dir);
+    }
+}
@@ -0,0 +1,338 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::import::structs::{
+    ComponentTypes, ImportProperty, PostProcessSteps, PrimitiveTypes, UVTransformFlags,
+};
+use assimp::{Importer, Matrix4x4};
+
+use ImportProperty::{Float, Int, Matrix, String as Str};
+
+/// How to configure the importer, the steps that should then be enabled and the properties
+/// that should then be set.
+type Case = (
+    fn(&mut Importer),
+    PostProcessSteps,
+    Vec<(&'static str, ImportProperty)>,
+);
+
+fn case(
+    configure: fn(&mut Importer),
+    steps: PostProcessSteps,
+    properties: Vec<(&'static str, ImportProperty)>,
+) -> Case {
+    (configure, steps, properties)
+}
+
+fn cases() -> Vec<Case> {
+    let identity = Matrix4x4::new(
+        1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0,
+    );
+
+    vec![
+        case(
+            |i| {
+                i.calc_tangent_space(|x| {
+                    x.enable = true;
+                    x.max_smoothing_angle = 30.0;
+                    x.texture_channel = 1;
+                })
+            },
+            PostProcessSteps::CALC_TANGENT_SPACE,
+            vec![
+                ("AI_CONFIG_PP_CT_TEXTURE_CHANNEL_INDEX", Int(1)),
+                ("PP_CT_MAX_SMOOTHING_ANGLE", Float(30.0)),
+            ],
+        ),
+        case(
+            |i| i.join_identical_vertices(true),
+            PostProcessSteps::JOIN_IDENTICAL_VERTICES,
+            vec![],
+        ),
+        case(
+            |i| i.make_left_handed(true),
+            PostProcessSteps::MAKE_LEFT_HANDED,
+            vec![],
+        ),
+        case(
+            |i| i.triangulate(true),
+            PostProcessSteps::TRIANGULATE,
+            vec![],
+        ),
+        case(
+            |i| {
+                i.remove_component(|x| {
+                    x.enable = true;
+                    x.components = ComponentTypes::NORMALS | ComponentTypes::COLORS;
+                })
+            },
+            PostProcessSteps::REMOVE_COMPONENT,
+            vec![(
+                "PP_RVC_FLAGS",
+                Int((ComponentTypes::NORMALS | ComponentTypes::COLORS).bits() as i32),
+            )],
+        ),
+        case(
+            |i| i.generate_normals(|x| x.enable = true),
+            PostProcessSteps::GEN_NORMALS,
+            vec![],
+        ),
+        case(
+            |i| {
+                i.generate_normals(|x| {
+                    x.enable = true;
+                    x.smooth = true;
+                    x.max_smoothing_angle = 80.0;
+                })
+            },
+            PostProcessSteps::GEN_SMOOTH_NORMALS,
+            vec![("PP_GSN_MAX_SMOOTHING_ANGLE", Float(80.0))],
+        ),
+        case(
+            |i| {
+                i.split_large_meshes(|x| {
+                    x.enable = true;
+                    x.vertex_limit = 100;
+                    x.triangle_limit = 200;
+                })
+            },
+            PostProcessSteps::SPLIT_LARGE_MESHES,
+            vec![
+                ("PP_SLM_TRIANGLE_LIMIT", Int(200)),
+                ("PP_SLM_VERTEX_LIMIT", Int(100)),
+            ],
+        ),
+        case(
+            |i| {
+                i.pre_transform_vertices(|x| {
+                    x.enable = true;
+                    x.normalize = true;
+                })
+            },
+            PostProcessSteps::PRE_TRANSFORM_VERTICES,
+            vec![
+                ("PP_PTV_ADD_ROOT_TRANSFORMATION", Int(0)),
+                ("PP_PTV_KEEP_HIERARCHY", Int(0)),
+                ("PP_PTV_NORMALIZE", Int(1)),
+                ("PP_PTV_ROOT_TRANSFORMATION", Matrix(identity)),
+            ],
+        ),
+        case(
+            |i| {
+                i.limit_bone_weights(|x| {
+                    x.enable = true;
+                    x.max_weights = 2;
+                })
+            },
+            PostProcessSteps::LIMIT_BONE_WEIGHTS,
+            vec![("PP_LBW_MAX_WEIGHTS", Int(2))],
+        ),
+        case(
+            |i| i.validate_data_structure(true),
+            PostProcessSteps::VALIDATE_DATA_STRUCTURE,
+            vec![],
+        ),
+        case(
+            |i| {
+                i.improve_cache_locality(|x| {
+                    x.enable = true;
+                    x.cache_size = 24;
+                })
+            },
+            PostProcessSteps::IMPROVE_CACHE_LOCALITY,
+            vec![("PP_ICL_PTCACHE_SIZE", Int(24))],
+        ),
+        case(
+            |i| {
+                i.remove_redudant_materials(|x| {
+                    x.enable = true;
+                    x.exclude_list = "keep 'keep me'".to_owned();
+                })
+            },
+            PostProcessSteps::REMOVE_REDUNDANT_MATERIALS,
+            vec![("PP_RRM_EXCLUDE_LIST", Str("keep 'keep me'".to_owned()))],
+        ),
+        case(
+            |i| i.fix_infacing_normals(true),
+            PostProcessSteps::FIX_INFACING_NORMALS,
+            vec![],
+        ),
+        case(
+            |i| {
+                i.sort_by_primitive_type(|x| {
+                    x.enable = true;
+                    x.remove = PrimitiveTypes::POINT | PrimitiveTypes::LINE;
+                })
+            },
+            PostProcessSteps::SORT_BY_PTYPE,
+            vec![(
+                "PP_SBP_REMOVE",
+                Int((PrimitiveTypes::POINT | PrimitiveTypes::LINE).bits() as i32),
+            )],
+        ),
+        case(
+            |i| {
+                i.find_degenerates(|x| {
+                    x.enable = true;
+                    x.remove = true;
+                })
+            },
+            PostProcessSteps::FIND_DEGENERATES,
+            vec![("PP_FD_REMOVE", Int(1))],
+        ),
+        case(
+            |i| {
+                i.find_invalid_data(|x| {
+                    x.enable = true;
+                    x.accuracy = 0.5;
+                })
+            },
+            PostProcessSteps::FIND_INVALID_DATA,
+            vec![("PP_FID_ANIM_ACCURACY", Float(0.5))],
+        ),
+        case(
+            |i| i.gen_uv_coords(true),
+            PostProcessSteps::GEN_UV_COORDS,
+            vec![],
+        ),
+        case(
+            |i| {
+                i.transform_uv_coords(|x| {
+                    x.enable = true;
+                    x.flags = UVTransformFlags::SCALING;
+                })
+            },
+            PostProcessSteps::TRANSFORM_UV_COORDS,
+            vec![(
+                "PP_TUV_EVALUATE",
+                Int(UVTransformFlags::SCALING.bits() as i32),
+            )],
+        ),
+        case(
+            |i| i.find_instances(true),
+            PostProcessSteps::FIND_INSTANCES,
+            vec![],
+        ),
+        case(
+            |i| {
+                i.optimize_meshes(|x| {
+                    x.enable = true;
+                    x.exclude_list = "root".to_owned();
+                    x.size_limits = Some((500, 1000));
+                })
+            },
+            PostProcessSteps::OPTIMIZE_MESHES,
+            vec![
+                ("PP_OG_EXCLUDE_LIST", Str("root".to_owned())),
+                ("PP_SLM_TRIANGLE_LIMIT", Int(1000)),
+                ("PP_SLM_VERTEX_LIMIT", Int(500)),
+            ],
+        ),
+        case(
+            |i| {
+                i.optimize_graph(|x| {
+                    x.enable = true;
+                    x.exclude_list = "root".to_owned();
+                })
+            },
+            PostProcessSteps::OPTIMIZE_GRAPH,
+            vec![("PP_OG_EXCLUDE_LIST", Str("root".to_owned()))],
+        ),
+        case(|i| i.flip_uvs(true), PostProcessSteps::FLIP_UVS, vec![]),
+        case(
+            |i| i.flip_winding_order(true),
+            PostProcessSteps::FLIP_WINDING_ORDER,
+            vec![],
+        ),
+        case(
+            |i| {
+                i.split_by_bone_count(|x| {
+                    x.enable = true;
+                    x.max_bones = 16;
+                })
+            },
+            PostProcessSteps::SPLIT_BY_BONE_COUNT,
+            vec![("PP_SBBC_MAX_BONES", Int(16))],
+        ),
+        case(
+            |i| {
+                i.debone(|x| {
+                    x.enable = true;
+                    x.threshold = 0.5;
+                    x.all_or_none = true;
+                })
+            },
+            PostProcessSteps::DEBONE,
+            vec![
+                ("PP_DB_ALL_OR_NONE", Int(1)),
+                ("PP_DB_THRESHOLD", Float(0.5)),
+            ],
+        ),
+    ]
+}
+
+#[test]
+fn test_effective_flags_for_each_step() {
+    for (configure, steps, properties) in cases() {
+        let mut importer = Importer::new();
+        configure(&mut importer);
+
+        assert_eq!(importer.effective_flags(), steps);
+        assert_eq!(
+            importer.properties().collect::<Vec<_>>(),
+            properties
+                .iter()
+                .map(|(name, value)| (*name, value))
+                .collect::<Vec<_>>(),
+            "properties for {}",
+            steps
+        );
+    }
+}
+
+#[test]
+fn test_effective_flags_disable() {
+    let mut importer = Importer::new();
+    assert!(importer.effective_flags().is_empty());
+
+    importer.triangulate(true);
+    importer.flip_uvs(true);
+    importer.generate_normals(|x| {
+        x.enable = true;
+        x.smooth = true;
+    });
+    assert_eq!(
+        importer.effective_flags(),
+        PostProcessSteps::TRIANGULATE
+            | PostProcessSteps::FLIP_UVS
+            | PostProcessSteps::GEN_SMOOTH_NORMALS
+    );
+
+    importer.triangulate(false);
+    importer.generate_normals(|x| x.enable = false);
+    assert_eq!(importer.effective_flags(), PostProcessSteps::FLIP_UVS);
+}
+
+#[test]
+fn test_disabled_step_sets_no_properties() {
+    let mut importer = Importer::new();
+    importer.find_degenerates(|x| x.remove = true);
+
+    assert!(importer.effective_flags().is_empty());
+    assert_eq!(importer.property("PP_FD_REMOVE"), None);
+}
+
+#[test]
+fn test_post_process_steps_display() {
+    assert_eq!(PostProcessSteps::empty().to_string(), "(none)");
+    assert_eq!(
+        (PostProcessSteps::FLIP_UVS | PostProcessSteps::TRIANGULATE).to_string(),
+        "aiProcess_Triangulate | aiProcess_FlipUVs"
+    );
+    assert_eq!(
+        format!(
+            "{:?}",
+            PostProcessSteps::TRIANGULATE | PostProcessSteps::FLIP_UVS
+        ),
+        "TRIANGULATE | FLIP_UVS"
+    );
+}
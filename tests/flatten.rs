@@ -0,0 +1,171 @@
+#![cfg(feature = "image")]
+
+extern crate open_asset_importer as assimp;
+
+use std::convert::TryFrom;
+
+use assimp::scene::{
+    BlendOp, ChannelSource, FlattenOptions, FlattenWarning, FlattenedValue, Mapping,
+    MaterialComponent, TextureDefinition, TextureFlags, WrappingMode,
+};
+use assimp::{Color3D, InlineString};
+use image::{DynamicImage, Rgba, RgbaImage};
+
+fn texture(path: &str, blend_op: BlendOp, strength: f32) -> TextureDefinition {
+    TextureDefinition {
+        path: InlineString::try_from(path).ok().unwrap(),
+        strength,
+        blend_op,
+        mapping: Some(Mapping::UV),
+        axis: None,
+        channel: 0,
+        channel_source: ChannelSource::Inferred,
+        wrap_u: Some(WrappingMode::Repeat),
+        wrap_v: Some(WrappingMode::Repeat),
+        wrap_w: None,
+        flags: TextureFlags::empty(),
+    }
+}
+
+fn red(
+    textures: Vec<TextureDefinition>,
+) -> MaterialComponent<std::vec::IntoIter<TextureDefinition>> {
+    MaterialComponent {
+        color: Color3D::new(1.0, 0.0, 0.0),
+        color_alpha: 1.0,
+        textures: textures.into_iter(),
+    }
+}
+
+/// Resolves `white.png` to a 2x2 white image and `gradient.png` to a 4x1 gray gradient.
+fn resolve(texture: &TextureDefinition) -> Option<DynamicImage> {
+    match &*texture.path {
+        "white.png" => Some(DynamicImage::ImageRgba8(RgbaImage::from_pixel(
+            2,
+            2,
+            Rgba([255, 255, 255, 255]),
+        ))),
+        "gradient.png" => Some(DynamicImage::ImageRgba8(RgbaImage::from_fn(
+            4,
+            1,
+            |x, _| {
+                let value = (x * 85) as u8;
+                Rgba([value, value, value, 255])
+            },
+        ))),
+        _ => None,
+    }
+}
+
+/// Resampling can be off by one.
+fn assert_close(pixel: [u8; 4], expected: [u8; 4]) {
+    for (&channel, &expected) in pixel.iter().zip(expected.iter()) {
+        assert!(
+            (channel as i32 - expected as i32).abs() <= 1,
+            "{:?} != {:?}",
+            pixel,
+            expected
+        );
+    }
+}
+
+fn baked(value: FlattenedValue) -> RgbaImage {
+    match value {
+        FlattenedValue::Image(image) => image,
+        other => panic!("expected an image, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_flatten_multiply() {
+    let flattened = red(vec![texture("white.png", BlendOp::Multiply, 0.5)]).flatten(resolve);
+    assert!(flattened.warnings.is_empty());
+    assert_eq!(flattened.wrap, (WrappingMode::Repeat, WrappingMode::Repeat));
+
+    let image = baked(flattened.value);
+    assert_eq!(image.dimensions(), (2, 2));
+    for pixel in image.pixels() {
+        assert_eq!(pixel.0, [128, 0, 0, 255]);
+    }
+}
+
+#[test]
+fn test_flatten_missing_texture() {
+    let flattened = red(vec![texture("missing.png", BlendOp::Multiply, 1.0)]).flatten(resolve);
+    assert_eq!(
+        flattened.value,
+        FlattenedValue::Color(Color3D::new(1.0, 0.0, 0.0))
+    );
+    assert_eq!(
+        flattened.warnings,
+        vec![FlattenWarning::MissingTexture {
+            index: 0,
+            path: "missing.png".to_owned()
+        }]
+    );
+
+    let flattened = red(vec![]).flatten(resolve);
+    assert_eq!(
+        flattened.value,
+        FlattenedValue::Color(Color3D::new(1.0, 0.0, 0.0))
+    );
+    assert!(flattened.warnings.is_empty());
+}
+
+#[test]
+fn test_flatten_stack() {
+    // Replace with the gradient, then add white at a quarter strength. The white texture is
+    // resampled to the size of the gradient.
+    let flattened = red(vec![
+        texture("gradient.png", BlendOp::Replace, 1.0),
+        texture("white.png", BlendOp::Add, 0.25),
+    ])
+    .flatten(resolve);
+
+    let image = baked(flattened.value);
+    assert_eq!(image.dimensions(), (4, 2));
+    for (x, &expected) in [64, 149, 234, 255].iter().enumerate() {
+        for y in 0..2 {
+            let pixel = image.get_pixel(x as u32, y).0;
+            assert_close(pixel, [expected, expected, expected, 255]);
+        }
+    }
+
+    let opts = FlattenOptions {
+        size: Some((8, 8)),
+        ..Default::default()
+    };
+    let flattened =
+        red(vec![texture("white.png", BlendOp::Multiply, 1.0)]).flatten_with(resolve, opts);
+    let image = baked(flattened.value);
+    assert_eq!(image.dimensions(), (8, 8));
+    assert_close(image.get_pixel(3, 5).0, [255, 0, 0, 255]);
+}
+
+#[test]
+fn test_flatten_fallbacks() {
+    let mut sphere = texture("white.png", BlendOp::Multiply, 1.0);
+    sphere.mapping = Some(Mapping::Sphere);
+    sphere.wrap_u = None;
+    let mut second = texture("white.png", BlendOp::Multiply, 1.0);
+    second.channel = 1;
+
+    let flattened = red(vec![sphere, second]).flatten(resolve);
+    assert_eq!(flattened.wrap, (WrappingMode::Clamp, WrappingMode::Clamp));
+    assert_eq!(
+        flattened.warnings,
+        vec![
+            FlattenWarning::NotUvMapping {
+                index: 0,
+                mapping: Mapping::Sphere
+            },
+            FlattenWarning::DifferentChannel {
+                index: 1,
+                channel: 1
+            },
+            FlattenWarning::WrapModeFallback,
+        ]
+    );
+    // The textures are still applied.
+    assert_eq!(baked(flattened.value).get_pixel(0, 0).0, [255, 0, 0, 255]);
+}
@@ -0,0 +1,90 @@
+extern crate open_asset_importer as assimp;
+
+use std::sync::{Arc, Mutex};
+
+use assimp::fixtures;
+use assimp::io::{IoEvent, ObservedFileIO};
+use assimp::{Error, Importer};
+
+const OBJ: &[u8] = fixtures::UNIT_CUBE_OBJ;
+const PLY: &[u8] = include_bytes!("../examples/triangle.ply");
+const GLB: &[u8] = fixtures::EMBEDDED_TEXTURE_GLB;
+
+fn is_refused(result: Result<assimp::Scene, Error>, expected: Option<&str>) -> bool {
+    match result {
+        Err(Error::FormatNotAllowed { format }) => format.as_deref() == expected,
+        _ => false,
+    }
+}
+
+#[test]
+fn test_allowed_formats() {
+    let mut importer = Importer::new();
+    importer.allowed_formats(&["obj"]);
+    assert!(importer.read_file("examples/unit_cube.obj").is_ok());
+    assert!(is_refused(
+        importer.read_file("examples/triangle.ply"),
+        Some("ply")
+    ));
+
+    // The refused file is never opened, let alone read.
+    let reads = Arc::new(Mutex::new(Vec::new()));
+    let recorded = reads.clone();
+    let io = ObservedFileIO::new(
+        fixtures::file_io().with_file("triangle.ply", PLY),
+        move |event| {
+            let name = match event {
+                IoEvent::FileOpened { path, .. } | IoEvent::FileRead { path, .. } => path,
+                _ => return,
+            };
+            recorded.lock().unwrap().push(name.to_owned());
+        },
+    );
+    assert!(is_refused(
+        importer.read_file_with_io("triangle.ply", &io),
+        Some("ply")
+    ));
+    assert!(reads.lock().unwrap().is_empty());
+    assert!(importer.read_file_with_io("unit_cube.obj", &io).is_ok());
+    assert!(!reads.lock().unwrap().is_empty());
+
+    // Binary files are also checked by their magic bytes.
+    assert!(is_refused(
+        importer.read_memory_with_hint(GLB, ".OBJ"),
+        Some("glb")
+    ));
+    // Without a hint, the format of a text file can't be told.
+    assert!(is_refused(importer.read_memory(OBJ), None));
+    assert!(importer.read_memory_with_hint(OBJ, "obj").is_ok());
+
+    // Clones keep the filter, and an empty list allows everything again.
+    assert!(is_refused(
+        importer.clone().read_file("examples/triangle.ply"),
+        Some("ply")
+    ));
+    importer.allowed_formats(&[]);
+    assert!(importer.read_file("examples/triangle.ply").is_ok());
+}
+
+#[test]
+fn test_denied_formats() {
+    let mut importer = Importer::new();
+    importer.denied_formats(&["*.PLY", "glb"]);
+    assert!(is_refused(
+        importer.read_file("examples/triangle.ply"),
+        Some("ply")
+    ));
+    assert!(is_refused(importer.read_memory(GLB), Some("glb")));
+    assert!(importer.read_memory_with_hint(OBJ, "obj").is_ok());
+    assert!(importer.read_file("examples/unit_cube.obj").is_ok());
+
+    // Denying wins over allowing.
+    importer.allowed_formats(&["obj", "ply"]);
+    assert!(is_refused(
+        importer.read_file("examples/triangle.ply"),
+        Some("ply")
+    ));
+
+    let error = importer.read_file("examples/triangle.ply").err().unwrap();
+    assert_eq!(error.to_string(), "importing .ply files isn't allowed");
+}
@@ -0,0 +1,186 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::fixtures;
+use assimp::scene::diff::{self, DiffTolerance};
+use assimp::scene::HashOptions;
+use assimp::{Importer, Node, Scene};
+
+/// A tiny deterministic PRNG, so the corpus is the same on every run.
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self) -> u8 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 33) as u8
+    }
+
+    fn bytes(&mut self, len: usize) -> Vec<u8> {
+        (0..len).map(|_| self.next()).collect()
+    }
+}
+
+/// Build the corpus of broken files, as `(hint, data)` pairs.
+fn corpus() -> Vec<(&'static str, Vec<u8>)> {
+    let mut rng = Lcg(0x5eed);
+    let mut out = Vec::new();
+
+    let mut valid = vec![("obj", fixtures::UNIT_CUBE_OBJ.to_vec())];
+    for &(hint, path) in &[
+        ("obj", "examples/spider.obj"),
+        ("dae", "examples/cameras.dae"),
+        ("dae", "examples/z_up.dae"),
+    ] {
+        valid.push((hint, std::fs::read(path).unwrap()));
+    }
+
+    for (hint, data) in valid {
+        // Truncated at various points.
+        for &fraction in &[1, 2, 3, 5, 7] {
+            out.push((hint, data[..data.len() * fraction / 8].to_vec()));
+        }
+
+        // Valid data with the wrong extension.
+        for &wrong in &["fbx", "ply", "stl", "gltf", "3ds", "md2"] {
+            out.push((wrong, data.clone()));
+        }
+
+        // Random bytes overwritten in the middle of the file.
+        let mut corrupted = data.clone();
+        for _ in 0..corrupted.len() / 16 {
+            let index = (rng.next() as usize * 251 + rng.next() as usize) % corrupted.len();
+            corrupted[index] = rng.next();
+        }
+        out.push((hint, corrupted));
+    }
+
+    // Garbage with valid magic numbers.
+    let magics: &[(&str, &[u8])] = &[
+        ("fbx", b"Kaydara FBX Binary  \x00\x1a\x00"),
+        ("glb", b"glTF\x02\x00\x00\x00"),
+        (
+            "ply",
+            b"ply\nformat binary_little_endian 1.0\nelement vertex 4294967295\n",
+        ),
+        ("stl", b"solid garbage\n"),
+        ("3ds", b"\x4d\x4d"),
+        ("md2", b"IDP2"),
+        ("md3", b"IDP3"),
+        ("mdl", b"IDPO"),
+        ("blend", b"BLENDER-v279"),
+        ("x", b"xof 0303txt 0032\n"),
+    ];
+
+    for &(hint, magic) in magics {
+        for &len in &[0, 4, 64, 1024] {
+            let mut data = magic.to_vec();
+            data.extend(rng.bytes(len));
+            out.push((hint, data));
+        }
+    }
+
+    // Names that aren't valid UTF-8.
+    let mut obj = b"o ".to_vec();
+    obj.extend_from_slice(b"\xff\xfe\xfd");
+    obj.extend_from_slice(b"\nv 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n");
+    out.push(("obj", obj));
+
+    out
+}
+
+fn walk_node(node: &Node) {
+    let _ = node.try_name();
+    let _ = node.transform();
+    let _ = node.meshes();
+    for (key, value) in node.metadata() {
+        let _ = key.to_str();
+        let _ = value.get();
+    }
+    for child in node.children() {
+        walk_node(child);
+    }
+}
+
+/// Touch everything in the scene through the non-panicking accessors.
+fn walk(scene: &Scene) {
+    if let Some(root) = scene.root_node() {
+        walk_node(root);
+    }
+
+    for mesh in scene.meshes() {
+        let _ = mesh.try_name();
+        let _ = mesh.primitive_types();
+        let _ = mesh.vertices().count();
+        for set in 0..8 {
+            let _ = mesh.vertex_colors(set).count();
+            let _ = mesh.texture_coords(set).count();
+        }
+        for face in mesh.faces() {
+            let _ = face.indices();
+        }
+        for bone in mesh.bones() {
+            let _ = bone.try_name();
+            let _ = bone.weights().count();
+        }
+    }
+
+    for material in scene.materials() {
+        for property in material.properties() {
+            let _ = property.try_key();
+            let _ = property.data();
+        }
+        if let Some(diffuse) = material.diffuse() {
+            for texture in diffuse.textures {
+                let _ = texture.path.to_str();
+            }
+        }
+    }
+
+    for animation in scene.animations() {
+        let _ = animation.try_name();
+        for channel in animation.node_anims() {
+            let _ = channel.try_node_name();
+            let _ = channel.position_keys().count();
+            let _ = channel.rotation_keys().count();
+            let _ = channel.scaling_keys().count();
+        }
+    }
+
+    for texture in scene.textures() {
+        let _ = texture.try_format_hint();
+        let _ = texture.try_filename();
+        let _ = texture.data().map(|data| data.bytes().len());
+    }
+
+    for camera in scene.camera() {
+        let _ = camera.try_name();
+    }
+
+    for light in scene.light() {
+        let _ = light.try_name();
+    }
+
+    let _ = scene.content_hash(HashOptions::default());
+    let _ = diff::diff(scene, scene, DiffTolerance::default());
+}
+
+#[test]
+fn test_broken_files_do_not_panic() {
+    let importer = Importer::new();
+
+    for (hint, data) in corpus() {
+        if let Ok(scene) = importer.read_memory_with_hint(&data, hint) {
+            walk(&scene);
+        }
+    }
+}
+
+#[test]
+fn test_nul_in_path_is_an_error() {
+    let importer = Importer::new();
+
+    assert!(importer.read_file("examples/box\0.obj").is_err());
+    assert!(importer.read_memory_with_hint(b"", "o\0bj").is_err());
+}
@@ -0,0 +1,208 @@
+extern crate open_asset_importer as assimp;
+
+use std::mem;
+
+use assimp::fixtures;
+use assimp::math::Matrix4x4;
+use assimp::scene::gc::{collect, collect_with, GcOptions, GcReport};
+use assimp::scene::{NodeData, SceneSnapshot, TextureTarget};
+use assimp::Importer;
+
+/// The triangle of `embedded_texture.glb` twice, each copy with its own material and embedded
+/// texture, under a node of its own, and a third material that nothing uses.
+fn two_triangles() -> SceneSnapshot {
+    let importer = Importer::new();
+    let scene = fixtures::embedded_texture_glb_with(&importer);
+    let mut snapshot = scene.snapshot();
+    assert_eq!(
+        (
+            snapshot.meshes.len(),
+            snapshot.materials.len(),
+            snapshot.textures.len()
+        ),
+        (1, 1, 1)
+    );
+
+    let mut mesh = snapshot.meshes[0].clone();
+    mesh.name = "Second".to_owned();
+    mesh.material_index = 1;
+    let mut material = snapshot.materials[0].clone();
+    material.name = "Second".to_owned();
+    for texture in &mut material.textures {
+        assert_eq!(texture.target, TextureTarget::Embedded(0));
+        assert_eq!(texture.path, "*0");
+        texture.target = TextureTarget::Embedded(1);
+        texture.path = "*1".to_owned();
+    }
+    let mut texture = snapshot.textures[0].clone();
+    texture.filename = "second".to_owned();
+    let mut unused = snapshot.materials[0].clone();
+    unused.name = "Unused".to_owned();
+
+    snapshot.meshes.push(mesh);
+    snapshot.materials.push(material);
+    snapshot.materials.push(unused);
+    snapshot.textures.push(texture);
+
+    let root = snapshot.root.as_mut().unwrap();
+    let first = NodeData {
+        name: "First".to_owned(),
+        transform: Matrix4x4::identity(),
+        meshes: vec![0],
+        children: Vec::new(),
+    };
+    let second = NodeData {
+        name: "Second".to_owned(),
+        transform: Matrix4x4::identity(),
+        meshes: vec![1],
+        children: Vec::new(),
+    };
+    root.meshes.clear();
+    root.children = vec![first, second];
+    snapshot
+}
+
+fn mesh_references(snapshot: &mut SceneSnapshot) -> Vec<u32> {
+    let mut meshes = Vec::new();
+    if let Some(root) = &mut snapshot.root {
+        root.visit_mut(&mut |node| meshes.extend_from_slice(&node.meshes));
+    }
+    meshes
+}
+
+/// Check that every reference in `snapshot` resolves.
+fn assert_resolves(snapshot: &mut SceneSnapshot) {
+    let num_meshes = snapshot.meshes.len() as u32;
+    assert!(mesh_references(snapshot)
+        .iter()
+        .all(|&index| index < num_meshes));
+    for mesh in &snapshot.meshes {
+        assert!((mesh.material_index as usize) < snapshot.materials.len());
+    }
+    for material in &snapshot.materials {
+        for texture in &material.textures {
+            if let TextureTarget::Embedded(index) = texture.target {
+                assert!((index as usize) < snapshot.textures.len());
+                assert_eq!(texture.path, format!("*{}", index));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_collect_after_pruning_meshes() {
+    let mut snapshot = two_triangles();
+    assert_resolves(&mut snapshot);
+
+    // Nothing's unreachable but the unused material.
+    let mut collected = snapshot.clone();
+    let report = collect(&mut collected);
+    assert_eq!(report.materials, vec![2]);
+    assert!(report.meshes.is_empty() && report.textures.is_empty());
+    assert_eq!(report.bytes, 0);
+    assert_eq!(collected.materials.len(), 2);
+
+    // Drop the node of the first triangle, leaving its mesh, material and texture behind.
+    let root = snapshot.root.as_mut().unwrap();
+    root.children.remove(0);
+    let first_mesh = snapshot.meshes[0].clone();
+    let texture_size = snapshot.textures[0].data.as_ref().unwrap().len();
+
+    let report = collect(&mut snapshot);
+
+    let mesh_bytes = mem::size_of_val(&first_mesh.positions[..])
+        + mem::size_of_val(&first_mesh.normals[..])
+        + first_mesh
+            .texture_coords
+            .iter()
+            .flatten()
+            .map(|uvs| mem::size_of_val(&uvs[..]))
+            .sum::<usize>()
+        + first_mesh
+            .faces
+            .iter()
+            .map(|face| face.len() * 4)
+            .sum::<usize>();
+    assert_eq!(
+        report,
+        GcReport {
+            meshes: vec![0],
+            materials: vec![0, 2],
+            textures: vec![0],
+            bytes: (mesh_bytes + texture_size) as u64,
+        }
+    );
+
+    // What's left is the second triangle, renumbered.
+    assert_eq!(snapshot.meshes.len(), 1);
+    assert_eq!(snapshot.meshes[0].name, "Second");
+    assert_eq!(snapshot.meshes[0].material_index, 0);
+    assert_eq!(snapshot.materials.len(), 1);
+    assert_eq!(snapshot.materials[0].name, "Second");
+    assert!(!snapshot.materials[0].textures.is_empty());
+    for texture in &snapshot.materials[0].textures {
+        assert_eq!(texture.target, TextureTarget::Embedded(0));
+        assert_eq!(texture.path, "*0");
+    }
+    assert_eq!(snapshot.textures.len(), 1);
+    assert_eq!(snapshot.textures[0].filename, "second");
+    assert_eq!(mesh_references(&mut snapshot), vec![0]);
+    assert_resolves(&mut snapshot);
+
+    // Collecting again finds nothing.
+    let collected = snapshot.clone();
+    assert!(collect(&mut snapshot).is_empty());
+    assert_eq!(snapshot, collected);
+}
+
+#[test]
+fn test_collect_options() {
+    let mut original = two_triangles();
+    original.root.as_mut().unwrap().children.remove(0);
+
+    // Keeping the unreferenced mesh keeps its material and texture.
+    let mut snapshot = original.clone();
+    let options = GcOptions {
+        meshes: false,
+        ..Default::default()
+    };
+    let report = collect_with(&mut snapshot, options);
+    assert_eq!(report.materials, vec![2]);
+    assert!(report.meshes.is_empty() && report.textures.is_empty());
+    assert_resolves(&mut snapshot);
+
+    // Keeping the textures leaves their indices as they were.
+    let mut snapshot = original.clone();
+    let options = GcOptions {
+        textures: false,
+        ..Default::default()
+    };
+    let report = collect_with(&mut snapshot, options);
+    assert_eq!((report.meshes, report.materials), (vec![0], vec![0, 2]));
+    assert!(report.textures.is_empty());
+    assert_eq!(snapshot.textures, original.textures);
+    assert!(snapshot.materials[0]
+        .textures
+        .iter()
+        .all(|texture| texture.target == TextureTarget::Embedded(1) && texture.path == "*1"));
+    assert_eq!(snapshot.meshes[0].material_index, 0);
+
+    // Keeping the materials keeps the textures they use.
+    let mut snapshot = original.clone();
+    let options = GcOptions {
+        materials: false,
+        ..Default::default()
+    };
+    let report = collect_with(&mut snapshot, options);
+    assert_eq!(report.meshes, vec![0]);
+    assert!(report.materials.is_empty() && report.textures.is_empty());
+    assert_eq!(snapshot.meshes[0].material_index, 1);
+    assert_resolves(&mut snapshot);
+
+    // A snapshot without a hierarchy keeps its meshes.
+    let mut snapshot = original.clone();
+    snapshot.root = None;
+    let report = collect(&mut snapshot);
+    assert!(report.meshes.is_empty());
+    assert_eq!(report.materials, vec![2]);
+}
@@ -0,0 +1,79 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::fixtures;
+use assimp::scene::{RootGroupOptions, TakeOptions};
+use assimp::Importer;
+
+#[test]
+fn test_fbx_root_groups() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/multi_take.fbx").unwrap();
+    assert_eq!(scene.root_node().unwrap().name(), "RootNode");
+
+    let groups = scene.root_groups();
+    let names: Vec<_> = groups.iter().map(|group| &*group.name).collect();
+    assert_eq!(names, ["Robot", "Crate"]);
+
+    let robot = &groups[0];
+    assert!(robot.num_nodes >= 2);
+    assert!(robot.mesh_indices.is_empty());
+    assert_eq!(robot.num_triangles, 0);
+    let crate_ = &groups[1];
+    assert_eq!(crate_.mesh_indices.len(), 1);
+    assert_eq!(crate_.num_vertices, 3);
+    assert_eq!(crate_.num_triangles, 1);
+
+    // Walk and Wave animate the robot, Spin the crate.
+    let animation_name = |index: &u32| {
+        let animation = scene.animations().nth(*index as usize).unwrap();
+        animation.name().rsplit("::").next().unwrap().to_owned()
+    };
+    let robot_animations: Vec<_> = robot.animations.iter().map(animation_name).collect();
+    assert_eq!(robot_animations, ["Walk", "Wave"]);
+    let crate_animations: Vec<_> = crate_.animations.iter().map(animation_name).collect();
+    assert_eq!(crate_animations, ["Spin"]);
+
+    // Without the artificial root, the whole scene is a single group.
+    let options = RootGroupOptions {
+        artificial_names: Vec::new(),
+        ..RootGroupOptions::default()
+    };
+    let groups = scene.root_groups_with(&options);
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0].node, 0);
+    assert_eq!(groups[0].name, "RootNode");
+    assert_eq!(groups[0].animations.len(), 3);
+}
+
+#[test]
+fn test_fbx_animation_takes() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/multi_take.fbx").unwrap();
+    assert_eq!(scene.num_animations(), 3);
+
+    let takes = scene.animation_takes();
+    assert_eq!(takes.len(), 2);
+    assert_eq!(takes[0].channels, ["Arm", "Robot"]);
+    assert_eq!(takes[0].animations.len(), 2);
+    assert_eq!(takes[1].channels, ["Crate"]);
+    assert_eq!(takes[1].animations.len(), 1);
+
+    // Animations of unrelated nodes are never similar.
+    let options = TakeOptions {
+        min_similarity: 0.1,
+        ..TakeOptions::default()
+    };
+    assert_eq!(scene.animation_takes_with(&options), takes);
+}
+
+#[test]
+fn test_single_root() {
+    let importer = Importer::new();
+    let scene = fixtures::unit_cube_with(&importer);
+    let groups = scene.root_groups();
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0].node, 0);
+    assert_eq!(groups[0].num_nodes as usize, scene.node_arena().len());
+    assert_eq!(groups[0].num_triangles, 12);
+    assert!(scene.animation_takes().is_empty());
+}
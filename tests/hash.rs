@@ -0,0 +1,111 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::fixtures;
+use assimp::scene::HashOptions;
+use assimp::Importer;
+
+const TRIANGLE_OBJ: &str = "\
+v 0.0 0.0 0.0
+v 1.0 0.0 0.0
+v 0.0 1.0 0.0
+f 1 2 3
+";
+
+const MOVED_TRIANGLE_OBJ: &str = "\
+v 0.0 0.0 0.0
+v 1.5 0.0 0.0
+v 0.0 1.0 0.0
+f 1 2 3
+";
+
+fn bvh(second_frame_y: f32) -> String {
+    format!(
+        "\
+HIERARCHY
+ROOT Hips
+{{
+  OFFSET 0.0 0.0 0.0
+  CHANNELS 6 Xposition Yposition Zposition Zrotation Xrotation Yrotation
+  JOINT Chest
+  {{
+    OFFSET 0.0 5.0 0.0
+    CHANNELS 3 Zrotation Xrotation Yrotation
+    End Site
+    {{
+      OFFSET 0.0 5.0 0.0
+    }}
+  }}
+}}
+MOTION
+Frames: 2
+Frame Time: 0.033333
+0.0 0.0 0.0 0.0 0.0 0.0 0.0 0.0 0.0
+0.0 {} 0.0 0.0 0.0 0.0 0.0 0.0 0.0
+",
+        second_frame_y
+    )
+}
+
+#[test]
+fn test_hash_is_deterministic() {
+    let importer = Importer::new();
+    let first = fixtures::unit_cube_with(&importer);
+    let second = fixtures::unit_cube_with(&importer);
+
+    assert_eq!(
+        first.content_hash(HashOptions::default()),
+        second.content_hash(HashOptions::default())
+    );
+}
+
+#[test]
+fn test_hash_changes_with_geometry() {
+    let importer = Importer::new();
+    let original = importer
+        .read_memory_with_hint(TRIANGLE_OBJ.as_bytes(), "obj")
+        .unwrap();
+    let moved = importer
+        .read_memory_with_hint(MOVED_TRIANGLE_OBJ.as_bytes(), "obj")
+        .unwrap();
+
+    assert_ne!(
+        original.content_hash(HashOptions::default()),
+        moved.content_hash(HashOptions::default())
+    );
+
+    let without_meshes = HashOptions {
+        meshes: false,
+        ..HashOptions::default()
+    };
+    assert_eq!(
+        original.content_hash(without_meshes),
+        moved.content_hash(without_meshes)
+    );
+}
+
+#[test]
+fn test_hash_ignores_animations_when_disabled() {
+    let importer = Importer::new();
+    let still = bvh(0.0);
+    let moving = bvh(1.0);
+    let still = importer
+        .read_memory_with_hint(still.as_bytes(), "bvh")
+        .unwrap();
+    let moving = importer
+        .read_memory_with_hint(moving.as_bytes(), "bvh")
+        .unwrap();
+
+    assert_ne!(
+        still.content_hash(HashOptions::default()),
+        moving.content_hash(HashOptions::default())
+    );
+
+    let without_animations = HashOptions {
+        animations: false,
+        ..HashOptions::default()
+    };
+    assert_eq!(
+        still.content_hash(without_animations),
+        moving.content_hash(without_animations)
+    );
+}
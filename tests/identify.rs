@@ -0,0 +1,72 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::fixtures;
+use assimp::import::{Confidence, IdentifyResult};
+use assimp::Importer;
+use std::fs;
+
+#[test]
+fn test_identify_obj() {
+    let result = Importer::identify("examples/unit_cube.obj").unwrap();
+    assert_eq!(
+        result,
+        IdentifyResult {
+            likely_format: Some("obj".to_owned()),
+            supported: true,
+            confidence: Confidence::Extension,
+        }
+    );
+}
+
+#[test]
+fn test_identify_unsupported_extension() {
+    let result = Importer::identify_bytes(b"PK\x03\x04 not a model", Some("report.docx"));
+    assert_eq!(result.likely_format.as_deref(), Some("docx"));
+    assert!(!result.supported);
+    assert_eq!(result.confidence, Confidence::Extension);
+}
+
+#[test]
+fn test_identify_glb_by_magic() {
+    let data = fixtures::EMBEDDED_TEXTURE_GLB;
+    for name in &[
+        Some("embedded_texture.glb"),
+        Some("wrong.obj"),
+        Some("docx"),
+        None,
+    ] {
+        let result = Importer::identify_bytes(data, *name);
+        assert_eq!(result.likely_format.as_deref(), Some("glb"));
+        assert!(result.supported);
+        assert_eq!(result.confidence, Confidence::Magic);
+    }
+}
+
+#[test]
+fn test_identify_empty_file() {
+    let path = std::env::temp_dir().join(format!("assimp-rs-empty-{}.obj", std::process::id()));
+    fs::write(&path, b"").unwrap();
+    let result = Importer::identify(&path);
+    fs::remove_file(&path).unwrap();
+
+    let result = result.unwrap();
+    assert_eq!(result.likely_format, None);
+    assert!(!result.supported);
+    assert_eq!(result.confidence, Confidence::Unknown);
+    assert_eq!(Importer::identify_bytes(&[], None), result);
+}
+
+#[test]
+fn test_identify_missing_file() {
+    assert!(Importer::identify("examples/non_existent_file.obj").is_err());
+}
+
+#[test]
+fn test_supports_extension() {
+    assert!(Importer::supports_extension("obj"));
+    assert!(Importer::supports_extension(".OBJ"));
+    assert!(Importer::supports_extension("*.gltf"));
+    assert!(!Importer::supports_extension("docx"));
+    assert!(!Importer::supports_extension(""));
+    assert!(!Importer::supports_extension("ob\0j"));
+}
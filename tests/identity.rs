@@ -0,0 +1,81 @@
+extern crate open_asset_importer as assimp;
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use assimp::scene::{Node, NodeId};
+use assimp::Importer;
+
+fn hash<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[test]
+fn test_node_identity() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/instanced.dae").unwrap();
+    let root = scene.root_node().unwrap();
+
+    // The same node, reached two different ways.
+    let first = root.find(b"CubeB").unwrap();
+    let second = root.find(b"CubeC").unwrap().parent().unwrap();
+    assert_eq!(first, second);
+    assert_eq!(hash(first), hash(second));
+    assert_eq!(first.id(), second.id());
+    assert_eq!(first.parent(), root.find(b"Instances"));
+
+    // Different nodes differ, including by id.
+    let other = root.find(b"CubeC").unwrap();
+    assert_ne!(first, other);
+    assert_ne!(first.id(), other.id());
+    assert_ne!(root, first);
+
+    // Borrows can be map keys, and so can ids, which don't borrow the scene.
+    let mut by_node: HashMap<&Node, usize> = HashMap::new();
+    let mut by_id: HashMap<NodeId, String> = HashMap::new();
+    for node in root.descendants() {
+        by_node.insert(node, by_node.len());
+        by_id.insert(node.id(), node.name().to_owned());
+    }
+    // Every node is a distinct key.
+    let count = root.descendants().count();
+    assert!(count > 5);
+    assert_eq!(by_node.len(), count);
+    assert_eq!(by_id.len(), count);
+
+    // Ids stay valid for fresh traversals of the same scene.
+    for node in scene.root_node().unwrap().descendants() {
+        assert_eq!(by_id[&node.id()], node.name());
+        assert!(by_node.contains_key(node));
+        assert_eq!(scene.node_by_id(node.id()), Some(node));
+    }
+    let arena = scene.node_arena();
+    assert_eq!(arena.index_of_id(first.id()), arena.index_of(first));
+    assert!(arena.index_of_id(first.id()).is_some());
+}
+
+#[test]
+fn test_mesh_identity() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/instanced.dae").unwrap();
+    let other_scene = importer.read_file("examples/instanced.dae").unwrap();
+
+    let meshes: Vec<_> = scene.meshes().collect();
+    assert!(meshes.len() >= 2);
+    assert_eq!(scene.mesh(0), Some(meshes[0]));
+    assert_eq!(hash(scene.mesh(1).unwrap()), hash(meshes[1]));
+    assert_ne!(meshes[0], meshes[1]);
+
+    let id = meshes[1].id();
+    assert_eq!(scene.mesh_by_id(id), Some((1, meshes[1])));
+    assert_eq!(scene.meshes().nth(1).unwrap().id(), id);
+
+    // The same file imported twice gives different structs, with different ids.
+    assert_ne!(other_scene.mesh(1).unwrap(), meshes[1]);
+    assert_eq!(other_scene.mesh_by_id(id), None);
+    let root_id = scene.root_node().unwrap().id();
+    assert_eq!(other_scene.node_by_id(root_id), None);
+}
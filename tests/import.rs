@@ -1,5 +1,7 @@
 extern crate open_asset_importer as assimp;
 
+use assimp::fixtures;
+use assimp::import::structs::{ImportProperty, PostProcessSteps};
 use assimp::Importer;
 
 #[test]
@@ -12,7 +14,7 @@ fn test_get_extension_list() {
 #[test]
 fn test_import_from_file_success() {
     let importer = Importer::new();
-    let scene = importer.read_file("examples/box.obj");
+    let scene = importer.read_file("examples/unit_cube.obj");
     assert!(scene.is_ok());
 }
 
@@ -26,7 +28,7 @@ fn test_import_from_file_failure() {
 #[test]
 fn test_apply_postprocessing_success() {
     let mut importer = Importer::new();
-    let scene = importer.read_file("examples/box.obj").unwrap();
+    let scene = fixtures::unit_cube_with(&importer);
     importer.triangulate(true);
     let _new_scene = importer.apply_postprocessing(scene).unwrap();
 }
@@ -45,3 +47,357 @@ fn test_sort_by_primitive_type_panic() {
         x.remove = all;
     });
 }
+
+#[test]
+fn test_obj_separate_by_material() {
+    let importer = Importer::new();
+    let combined = fixtures::two_material_obj_with(&importer);
+
+    let mut importer = Importer::new();
+    importer.obj_options(|o| o.separate_by_material = true);
+    let separated = fixtures::two_material_obj_with(&importer);
+
+    assert_eq!(separated.num_meshes(), 2);
+    assert!(combined.num_meshes() < separated.num_meshes());
+}
+
+#[test]
+fn test_collada_ignore_up_direction() {
+    let mut importer = Importer::new();
+    importer.collada_options(|c| c.ignore_up_direction = false);
+    let converted = importer.read_file("examples/z_up.dae").unwrap();
+
+    let mut importer = Importer::new();
+    importer.collada_options(|c| c.ignore_up_direction = true);
+    let ignored = importer.read_file("examples/z_up.dae").unwrap();
+
+    let converted = converted.root_node().unwrap().transform();
+    let ignored = ignored.root_node().unwrap().transform();
+    assert_ne!(converted, ignored);
+}
+
+#[test]
+fn test_property_store_reused_across_imports() {
+    let mut importer = Importer::new();
+    importer.collada_options(|c| c.ignore_up_direction = false);
+    let converted = importer.read_file("examples/z_up.dae").unwrap();
+
+    importer.collada_options(|c| c.ignore_up_direction = true);
+    let ignored = importer.read_file("examples/z_up.dae").unwrap();
+
+    let mut fresh = Importer::new();
+    fresh.collada_options(|c| c.ignore_up_direction = true);
+    let expected = fresh.read_file("examples/z_up.dae").unwrap();
+
+    let converted = converted.root_node().unwrap().transform();
+    let ignored = ignored.root_node().unwrap().transform();
+    assert_ne!(converted, ignored);
+    assert_eq!(ignored, expected.root_node().unwrap().transform());
+}
+
+#[test]
+fn test_reset_properties() {
+    let mut importer = Importer::new();
+    importer.collada_options(|c| c.ignore_up_direction = true);
+    importer.triangulate(true);
+    importer.reset_properties();
+
+    assert_eq!(importer.properties().count(), 0);
+    assert!(!importer.effective_flags().is_empty());
+
+    let reset = importer.read_file("examples/z_up.dae").unwrap();
+    let default = Importer::new().read_file("examples/z_up.dae").unwrap();
+    assert_eq!(
+        reset.root_node().unwrap().transform(),
+        default.root_node().unwrap().transform()
+    );
+}
+
+#[test]
+fn test_clone_has_own_property_store() {
+    let mut importer = Importer::new();
+    importer.collada_options(|c| c.ignore_up_direction = true);
+
+    let mut clone = importer.clone();
+    assert_eq!(
+        clone.properties().collect::<Vec<_>>(),
+        importer.properties().collect::<Vec<_>>()
+    );
+
+    clone.reset_properties();
+    assert!(importer.properties().count() > 0);
+
+    let original = importer.read_file("examples/z_up.dae").unwrap();
+    let reset = clone.read_file("examples/z_up.dae").unwrap();
+    assert_ne!(
+        original.root_node().unwrap().transform(),
+        reset.root_node().unwrap().transform()
+    );
+}
+
+#[test]
+fn test_drop_after_failed_import() {
+    let mut importer = Importer::new();
+    importer.collada_options(|c| c.ignore_up_direction = true);
+    assert!(importer
+        .read_file("examples/non_existent_file.dae")
+        .is_err());
+    assert!(importer
+        .read_file("examples/non_existent_file.dae")
+        .is_err());
+    drop(importer);
+
+    let unconfigured = Importer::new();
+    assert!(unconfigured
+        .read_file("examples/non_existent_file.dae")
+        .is_err());
+    drop(unconfigured);
+}
+
+/// A new empty directory for test files, which is different for each test.
+fn temp_dir(test: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("assimp-rs-{}-{}", test, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn test_import_path_with_spaces_and_unicode() {
+    let dir = temp_dir("unicode").join("with spaces");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("cübe ✓.obj");
+    std::fs::write(&path, fixtures::UNIT_CUBE_OBJ).unwrap();
+
+    let importer = Importer::new();
+    let scene = importer.read_file(&path);
+    assert!(scene.is_ok());
+
+    std::fs::remove_dir_all(dir.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn test_import_path_types() {
+    let importer = Importer::new();
+    assert!(importer
+        .read_file(std::path::Path::new("examples/unit_cube.obj"))
+        .is_ok());
+    assert!(importer
+        .read_file(String::from("examples/unit_cube.obj"))
+        .is_ok());
+    assert!(importer
+        .read_file(std::ffi::OsStr::new("examples/unit_cube.obj"))
+        .is_ok());
+}
+
+#[test]
+fn test_import_path_with_nul() {
+    let importer = Importer::new();
+    assert!(importer.read_file("examples/box\0.obj").is_err());
+}
+
+// macOS file systems don't allow names that aren't valid UTF-8.
+#[test]
+#[cfg(all(unix, not(target_os = "macos")))]
+fn test_import_non_utf8_path() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let dir = temp_dir("non-utf8");
+    let path = dir.join(OsStr::from_bytes(b"cube-\xff\xfe.obj"));
+    assert!(path.to_str().is_none());
+    std::fs::write(&path, fixtures::UNIT_CUBE_OBJ).unwrap();
+
+    let importer = Importer::new();
+    let scene = importer.read_file(&path);
+    assert!(scene.is_ok());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_optimize_meshes_diagnostics() {
+    let plain = Importer::new()
+        .read_file("examples/ten_meshes.gltf")
+        .unwrap();
+    assert_eq!(plain.num_meshes(), 10);
+    assert_eq!(plain.import_diagnostics().optimize_meshes, None);
+
+    let mut importer = Importer::new();
+    importer.triangulate(true);
+    importer.optimize_meshes(|x| x.enable = true);
+    let scene = importer.read_file("examples/ten_meshes.gltf").unwrap();
+
+    let report = scene.import_diagnostics().optimize_meshes.unwrap();
+    assert_eq!(report.meshes_before, 10);
+    assert_eq!(report.meshes_after, scene.num_meshes());
+    assert!(scene.num_meshes() < 10);
+
+    // Nothing is lost in the merge.
+    let faces: u32 = scene.meshes().map(|mesh| mesh.num_faces()).sum();
+    assert_eq!(faces, 10);
+
+    // Copies keep the diagnostics, and reading from memory records them too.
+    assert_eq!(
+        scene.try_clone().unwrap().import_diagnostics(),
+        scene.import_diagnostics()
+    );
+    let data = std::fs::read("examples/ten_meshes.gltf").unwrap();
+    let from_memory = importer.read_memory_with_hint(&data, "gltf").unwrap();
+    assert_eq!(from_memory.import_diagnostics(), scene.import_diagnostics());
+}
+
+/// Reads from a buffer without implementing `Seek`, like a network stream.
+struct ReadOnly<'a>(&'a [u8]);
+
+impl std::io::Read for ReadOnly<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.0.read(buf)
+    }
+}
+
+#[test]
+fn test_read_from_reader_memory() {
+    use assimp::import::SpoolPolicy;
+    use assimp::Error;
+
+    let data = fixtures::UNIT_CUBE_OBJ;
+    let importer = Importer::new();
+    let spool = SpoolPolicy::Memory { max_size: None };
+    let scene = importer
+        .read_from_reader(ReadOnly(data), Some("obj"), spool)
+        .unwrap();
+    assert_eq!(scene.num_meshes(), 1);
+    assert_eq!(scene.mesh(0).unwrap().num_faces(), 6);
+
+    let spool = SpoolPolicy::Memory {
+        max_size: Some(100),
+    };
+    match importer.read_from_reader(ReadOnly(data), Some("obj"), spool) {
+        Err(Error::BudgetExceeded {
+            what,
+            limit,
+            attempted,
+        }) => {
+            assert_eq!((what, limit), ("stream size", 100));
+            assert!(attempted > 100 && attempted <= data.len() as u64);
+        }
+        other => panic!("expected BudgetExceeded, got {:?}", other.err()),
+    }
+}
+
+#[test]
+fn test_read_from_reader_temp_file() {
+    use assimp::import::SpoolPolicy;
+    use assimp::Error;
+
+    let data = fixtures::UNIT_CUBE_OBJ;
+    let dir = temp_dir("spool");
+    let is_empty = |dir: &std::path::Path| std::fs::read_dir(dir).unwrap().next().is_none();
+    let spool = |max_size| SpoolPolicy::TempFile {
+        threshold: 64,
+        max_size,
+        dir: Some(dir.clone()),
+    };
+
+    let importer = Importer::new();
+    let scene = importer
+        .read_from_reader(ReadOnly(data), Some("obj"), spool(None))
+        .unwrap();
+    assert_eq!(scene.mesh(0).unwrap().num_faces(), 6);
+    assert!(is_empty(&dir));
+
+    // The file is deleted when the import fails too.
+    let result = importer.read_from_reader(ReadOnly(data), Some("glb"), spool(None));
+    assert!(result.is_err());
+    assert!(is_empty(&dir));
+
+    // And when the stream turns out to be too long after spooling started.
+    match importer.read_from_reader(ReadOnly(data), Some("obj"), spool(Some(200))) {
+        Err(Error::BudgetExceeded { what, limit, .. }) => {
+            assert_eq!((what, limit), ("stream size", 200));
+        }
+        other => panic!("expected BudgetExceeded, got {:?}", other.err()),
+    }
+    assert!(is_empty(&dir));
+
+    // Short streams stay in memory.
+    let short = b"v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n";
+    let scene = importer
+        .read_from_reader(ReadOnly(short), Some("obj"), spool(None))
+        .unwrap();
+    assert_eq!(scene.mesh(0).unwrap().num_faces(), 1);
+
+    let result = importer.read_from_reader(ReadOnly(data), Some("../obj"), spool(None));
+    assert!(matches!(result, Err(Error::InvalidConfig { .. })));
+    assert!(is_empty(&dir));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_read_from_reader_opens_no_other_files() {
+    use assimp::import::SpoolPolicy;
+
+    // The material library is next to the temporary file, and at the path the OBJ names
+    // relative to the working directory, but neither is opened.
+    let data = std::str::from_utf8(fixtures::TWO_MATERIAL_OBJ)
+        .unwrap()
+        .replace(
+            "mtllib two_materials.mtl",
+            "mtllib examples/two_materials.mtl",
+        );
+    let dir = temp_dir("spool_side_files");
+    std::fs::write(dir.join("two_materials.mtl"), fixtures::TWO_MATERIAL_MTL).unwrap();
+    std::fs::create_dir(dir.join("examples")).unwrap();
+    std::fs::write(
+        dir.join("examples/two_materials.mtl"),
+        fixtures::TWO_MATERIAL_MTL,
+    )
+    .unwrap();
+
+    let importer = Importer::new();
+    let policies = vec![
+        SpoolPolicy::Memory { max_size: None },
+        SpoolPolicy::TempFile {
+            threshold: 16,
+            max_size: None,
+            dir: Some(dir.clone()),
+        },
+    ];
+    for spool in policies {
+        let scene = importer
+            .read_from_reader(ReadOnly(data.as_bytes()), Some("obj"), spool)
+            .unwrap();
+        assert_eq!(scene.num_meshes(), 2);
+        assert_eq!(scene.num_materials(), 1);
+    }
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_gltf_options_keep_shared_settings() {
+    let mut importer = Importer::new();
+    importer.global_keyframe(3);
+    importer.gltf_options(|g| {
+        g.apply_scene_scale = true;
+        g.scale_factor = 0.5;
+    });
+    let configured = importer.clone();
+
+    // Options left at their defaults don't reset the keyframe or the global scale step.
+    importer.gltf_options(|_| {});
+    assert_eq!(importer.effective_flags(), configured.effective_flags());
+    assert!(importer
+        .effective_flags()
+        .contains(PostProcessSteps::GLOBAL_SCALE));
+    assert_eq!(
+        importer.property("IMPORT_GLOBAL_KEYFRAME"),
+        Some(&ImportProperty::Int(3))
+    );
+    assert_eq!(
+        importer.property("GLOBAL_SCALE_FACTOR"),
+        Some(&ImportProperty::Float(0.5))
+    );
+}
@@ -0,0 +1,93 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::fixtures;
+use assimp::scene::{partition_instances, InstancingSummary};
+use assimp::{Importer, Matrix4x4, Node, Vector3D};
+
+fn manual_world_transform(node: &Node) -> Matrix4x4 {
+    let mut transform = node.transform();
+    let mut parent = node.parent();
+    while let Some(node) = parent {
+        transform = node.transform() * transform;
+        parent = node.parent();
+    }
+    transform
+}
+
+fn close(a: &Matrix4x4, b: &Matrix4x4) -> bool {
+    a.rows()
+        .iter()
+        .flatten()
+        .zip(b.rows().iter().flatten())
+        .all(|(a, b)| (a - b).abs() < 1e-5)
+}
+
+#[test]
+fn test_mesh_instances() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/instanced.dae").unwrap();
+    assert_eq!(scene.num_meshes(), 2);
+
+    let instances = scene.mesh_instances();
+    assert_eq!(instances.len(), 2);
+
+    let cube = instances
+        .iter()
+        .find(|mesh| scene.mesh(mesh.mesh).unwrap().num_vertices() == 8)
+        .unwrap();
+    let names: Vec<_> = cube.instances.iter().map(|(node, _)| node.name()).collect();
+    assert_eq!(names, vec!["CubeA", "CubeB", "CubeC"]);
+
+    for (node, transform) in &cube.instances {
+        assert!(close(transform, &manual_world_transform(node)));
+    }
+
+    // The transforms really are different.
+    let origins: Vec<_> = cube
+        .instances
+        .iter()
+        .map(|(_, transform)| transform.transform_point(Vector3D::new(0.0, 0.0, 0.0)))
+        .collect();
+    assert_ne!(origins[0], origins[1]);
+    assert_ne!(origins[1], origins[2]);
+
+    assert_eq!(
+        scene.instancing_summary(),
+        InstancingSummary {
+            num_referenced_meshes: 2,
+            num_instanced_meshes: 1,
+            num_references: 4,
+        }
+    );
+
+    let (unique, instanced) = partition_instances(scene.mesh_instances(), 2);
+    assert_eq!(unique.len(), 1);
+    assert_eq!(unique[0].instances[0].0.name(), "Floor");
+    assert_eq!(instanced.len(), 1);
+    assert_eq!(instanced[0].mesh, cube.mesh);
+
+    let (unique, instanced) = partition_instances(scene.mesh_instances(), 4);
+    assert_eq!((unique.len(), instanced.len()), (2, 0));
+}
+
+#[test]
+fn test_descendants() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/instanced.dae").unwrap();
+    let root = scene.root_node().unwrap();
+
+    let names: Vec<_> = root.descendants().skip(1).map(Node::name).collect();
+    assert_eq!(names, vec!["Instances", "CubeA", "CubeB", "CubeC", "Floor"]);
+    assert_eq!(root.descendants().next().unwrap().name(), root.name());
+}
+
+#[test]
+fn test_no_instancing() {
+    let importer = Importer::new();
+    let scene = fixtures::unit_cube_with(&importer);
+
+    let summary = scene.instancing_summary();
+    assert_eq!(summary.num_referenced_meshes, 1);
+    assert_eq!(summary.num_instanced_meshes, 0);
+    assert_eq!(summary.num_references, 1);
+}
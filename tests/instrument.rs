@@ -0,0 +1,113 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::fixtures;
+use assimp::import::structs::PostProcessSteps;
+use assimp::scene::HashOptions;
+use assimp::Importer;
+
+fn configured_importer() -> Importer {
+    let mut importer = Importer::new();
+    importer.triangulate(true);
+    importer.generate_normals(|x| x.enable = true);
+    importer.join_identical_vertices(true);
+    importer.sort_by_primitive_type(|x| x.enable = true);
+    importer.improve_cache_locality(|x| x.enable = true);
+    importer.optimize_meshes(|x| x.enable = true);
+    importer.validate_data_structure(true);
+    importer
+}
+
+#[test]
+fn test_instrumented_read_file() {
+    let mut importer = configured_importer();
+    let plain = importer.read_file("examples/unit_cube.obj").unwrap();
+    assert_eq!(plain.import_diagnostics().timings, None);
+
+    importer.instrumented(true);
+    let scene = importer.read_file("examples/unit_cube.obj").unwrap();
+    let timings = scene.import_diagnostics().timings.clone().unwrap();
+
+    // Assimp read the file itself.
+    assert_eq!(timings.io_read_time, None);
+    assert_eq!(timings.io_bytes, None);
+    assert!(timings.import_wall_time > Default::default());
+
+    // One entry per step but validation, in the order Assimp runs them.
+    let steps: Vec<PostProcessSteps> = timings.per_step.iter().map(|&(step, _)| step).collect();
+    assert_eq!(
+        steps,
+        vec![
+            PostProcessSteps::OPTIMIZE_MESHES,
+            PostProcessSteps::TRIANGULATE,
+            PostProcessSteps::SORT_BY_PTYPE,
+            PostProcessSteps::GEN_NORMALS,
+            PostProcessSteps::JOIN_IDENTICAL_VERTICES,
+            PostProcessSteps::IMPROVE_CACHE_LOCALITY,
+        ]
+    );
+    assert!(timings.post_process_time() <= timings.total());
+    assert!(timings.import_wall_time <= timings.total());
+    assert!(timings.wrapper_time <= timings.total());
+
+    // The same scene, and the same diagnostics but for the timings.
+    assert_eq!(scene.snapshot(), plain.snapshot());
+    assert_eq!(
+        scene.content_hash(HashOptions::default()),
+        plain.content_hash(HashOptions::default())
+    );
+    let mut diagnostics = scene.import_diagnostics().clone();
+    diagnostics.timings = None;
+    assert_eq!(&diagnostics, plain.import_diagnostics());
+    assert!(diagnostics.optimize_meshes.is_some());
+}
+
+#[test]
+fn test_instrumented_read_file_with_io() {
+    let mut importer = configured_importer();
+    importer.instrumented(true);
+    let scene = importer
+        .read_file_with_io("unit_cube.obj", &fixtures::file_io())
+        .unwrap();
+    let timings = scene.import_diagnostics().timings.clone().unwrap();
+
+    assert!(timings.io_bytes.unwrap() >= fixtures::UNIT_CUBE_OBJ.len() as u64);
+    let io_read_time = timings.io_read_time.unwrap();
+    assert!(io_read_time <= timings.import_wall_time);
+    assert_eq!(timings.per_step.len(), 6);
+
+    let plain = configured_importer()
+        .read_file("examples/unit_cube.obj")
+        .unwrap();
+    assert_eq!(scene.snapshot(), plain.snapshot());
+
+    // Clones are instrumented too.
+    let scene = fixtures::unit_cube_with(&importer.clone());
+    assert!(scene.import_diagnostics().timings.is_some());
+}
+
+#[test]
+fn test_split_large_meshes_runs_with_the_steps_in_between() {
+    let mut importer = Importer::new();
+    importer.split_large_meshes(|x| x.enable = true);
+    importer.generate_normals(|x| x.enable = true);
+    importer.join_identical_vertices(true);
+    importer.flip_uvs(true);
+    importer.instrumented(true);
+    let scene = fixtures::unit_cube_with(&importer);
+    let timings = scene.import_diagnostics().timings.clone().unwrap();
+
+    let steps: Vec<PostProcessSteps> = timings.per_step.iter().map(|&(step, _)| step).collect();
+    assert_eq!(
+        steps,
+        vec![
+            PostProcessSteps::FLIP_UVS,
+            PostProcessSteps::SPLIT_LARGE_MESHES
+                | PostProcessSteps::GEN_NORMALS
+                | PostProcessSteps::JOIN_IDENTICAL_VERTICES,
+        ]
+    );
+
+    importer.instrumented(false);
+    let plain = fixtures::unit_cube_with(&importer);
+    assert_eq!(scene.snapshot(), plain.snapshot());
+}
@@ -0,0 +1,218 @@
+extern crate autogenerated_assimp_sys as ffi;
+extern crate open_asset_importer as assimp;
+
+use assimp::scene::joints::{mesh_joints, order_joints, Joint, JointError};
+use assimp::{Importer, Node};
+use std::ptr::{self, NonNull};
+
+fn aistring(name: &str) -> ffi::aiString {
+    let mut string: ffi::aiString = unsafe { std::mem::zeroed() };
+    for (dst, &src) in string.data.iter_mut().zip(name.as_bytes()) {
+        *dst = src as _;
+    }
+    string.length = name.len() as _;
+    string
+}
+
+/// A node hierarchy built in memory, where node `i` is named `names[i]` and is a child of
+/// `parents[i]`. Node 0 is the root.
+struct Tree {
+    nodes: Vec<Box<ffi::aiNode>>,
+    _children: Vec<Vec<*mut ffi::aiNode>>,
+}
+
+impl Tree {
+    fn new(nodes: &[(&str, Option<usize>)]) -> Tree {
+        let mut boxes: Vec<Box<ffi::aiNode>> = nodes
+            .iter()
+            .map(|&(name, _)| {
+                let mut node: Box<ffi::aiNode> = Box::new(unsafe { std::mem::zeroed() });
+                node.mName = aistring(name);
+                node
+            })
+            .collect();
+        let pointers: Vec<*mut ffi::aiNode> =
+            boxes.iter_mut().map(|node| &mut **node as *mut _).collect();
+
+        let mut children = vec![Vec::new(); nodes.len()];
+        for (i, &(_, parent)) in nodes.iter().enumerate() {
+            if let Some(parent) = parent {
+                boxes[i].mParent = pointers[parent];
+                children[parent].push(pointers[i]);
+            }
+        }
+        for (node, children) in boxes.iter_mut().zip(children.iter_mut()) {
+            node.mNumChildren = children.len() as u32;
+            node.mChildren = if children.is_empty() {
+                ptr::null_mut()
+            } else {
+                children.as_mut_ptr()
+            };
+        }
+
+        Tree {
+            nodes: boxes,
+            _children: children,
+        }
+    }
+
+    fn node(&self, i: usize) -> &Node {
+        unsafe { Node::from_raw(NonNull::from(&*self.nodes[i])) }
+    }
+}
+
+/// Every joint comes after its parent, and its parent is the joint of its nearest ancestor
+/// that's a joint.
+fn assert_parents_first(joints: &[Joint]) {
+    for (i, joint) in joints.iter().enumerate() {
+        let mut ancestor = joint.node.parent();
+        let expected = loop {
+            match ancestor {
+                Some(node) => match joints.iter().position(|j| ptr::eq(j.node, node)) {
+                    Some(parent) => break Some(parent),
+                    None => ancestor = node.parent(),
+                },
+                None => break None,
+            }
+        };
+        assert_eq!(joint.parent_index, expected, "joint {}", i);
+        if let Some(parent) = joint.parent_index {
+            assert!(parent < i);
+        }
+    }
+    assert!(joints.iter().filter(|j| j.parent_index.is_none()).count() <= 1);
+}
+
+fn names(joints: &[Joint]) -> Vec<String> {
+    joints.iter().map(|j| j.node.name().to_owned()).collect()
+}
+
+/// A skeleton under a node that isn't a joint.
+///
+/// ```text
+/// Armature
+/// └── Hips
+///     ├── Spine
+///     │   ├── Helper (not a joint)
+///     │   │   └── Neck
+///     │   └── Chest
+///     ├── LeftLeg
+///     └── RightLeg
+/// ```
+fn skeleton() -> Tree {
+    Tree::new(&[
+        ("Armature", None),
+        ("Hips", Some(0)),
+        ("Spine", Some(1)),
+        ("Helper", Some(2)),
+        ("Neck", Some(3)),
+        ("Chest", Some(2)),
+        ("LeftLeg", Some(1)),
+        ("RightLeg", Some(1)),
+    ])
+}
+
+#[test]
+fn test_order_joints() {
+    let tree = skeleton();
+    let nodes: Vec<_> = [1, 2, 4, 5, 6, 7].iter().map(|&i| tree.node(i)).collect();
+    let joints = order_joints(&nodes).unwrap();
+
+    assert_parents_first(&joints);
+    assert_eq!(
+        names(&joints),
+        vec!["Hips", "Spine", "Neck", "Chest", "LeftLeg", "RightLeg"]
+    );
+    let parents: Vec<_> = joints.iter().map(|j| j.parent_index).collect();
+    assert_eq!(
+        parents,
+        vec![None, Some(0), Some(1), Some(1), Some(0), Some(0)]
+    );
+    let sources: Vec<_> = joints.iter().map(|j| j.source).collect();
+    assert_eq!(sources, vec![0, 1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn test_order_joints_shuffled() {
+    let tree = skeleton();
+    let expected = vec!["Hips", "Spine", "Neck", "Chest", "LeftLeg", "RightLeg"];
+
+    // Children before parents, and siblings out of order.
+    let shuffled = [7, 4, 6, 5, 2, 1];
+    let nodes: Vec<_> = shuffled.iter().map(|&i| tree.node(i)).collect();
+    let joints = order_joints(&nodes).unwrap();
+    assert_parents_first(&joints);
+    assert_eq!(names(&joints), expected);
+    for joint in &joints {
+        assert!(ptr::eq(joint.node, nodes[joint.source]));
+    }
+
+    // Every rotation of the input gives the same order.
+    let mut nodes: Vec<_> = [1, 2, 4, 5, 6, 7].iter().map(|&i| tree.node(i)).collect();
+    for _ in 0..nodes.len() {
+        nodes.rotate_left(1);
+        let joints = order_joints(&nodes).unwrap();
+        assert_parents_first(&joints);
+        assert_eq!(names(&joints), expected);
+    }
+}
+
+#[test]
+fn test_order_joints_errors() {
+    let tree = skeleton();
+
+    // The legs are siblings, so neither is an ancestor of the other.
+    let nodes = [tree.node(6), tree.node(7)];
+    assert_eq!(
+        order_joints(&nodes).unwrap_err(),
+        JointError::MultipleRoots {
+            first: 0,
+            second: 1
+        }
+    );
+
+    let nodes = [tree.node(1), tree.node(2), tree.node(1)];
+    assert_eq!(
+        order_joints(&nodes).unwrap_err(),
+        JointError::DuplicateNode {
+            first: 0,
+            second: 2
+        }
+    );
+
+    assert!(order_joints(&[]).unwrap().is_empty());
+}
+
+#[test]
+fn test_mesh_joints_for_every_fixture() {
+    let importer = Importer::new();
+    let mut skinned = 0;
+    for entry in std::fs::read_dir("examples").unwrap() {
+        let path = entry.unwrap().path();
+        let scene = match importer.read_file(&path) {
+            Ok(scene) => scene,
+            Err(_) => continue,
+        };
+
+        for mesh in scene.meshes().filter(|mesh| mesh.num_bones() > 0) {
+            let joints = mesh_joints(&scene, mesh).unwrap();
+            assert_eq!(joints.len(), mesh.num_bones() as usize, "{:?}", path);
+            assert_parents_first(&joints);
+            for joint in &joints {
+                let bone = mesh.bone(joint.source as u32).unwrap();
+                assert_eq!(joint.node.name(), bone.name());
+            }
+            skinned += 1;
+        }
+    }
+    assert!(skinned >= 2);
+}
+
+#[test]
+fn test_mesh_joints_rigged() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/rigged.gltf").unwrap();
+    let joints = mesh_joints(&scene, scene.mesh(0).unwrap()).unwrap();
+    assert_eq!(names(&joints), vec!["Bone1", "Bone2"]);
+    assert_eq!(joints[1].parent_index, Some(0));
+}
@@ -0,0 +1,41 @@
+extern crate autogenerated_assimp_sys as ffi;
+extern crate open_asset_importer as assimp;
+
+use assimp::scene::LightType;
+use assimp::{Light, Matrix4x4, Vector3D};
+use std::ptr::NonNull;
+
+fn rect_light() -> ffi::aiLight {
+    let mut raw: ffi::aiLight = unsafe { std::mem::zeroed() };
+    raw.mType = ffi::aiLightSourceType_aiLightSource_AREA;
+    raw.mPosition = Vector3D::new(0.0, 1.0, 0.0).to_raw();
+    raw.mDirection = Vector3D::new(0.0, 0.0, 1.0).to_raw();
+    raw.mUp = Vector3D::new(0.0, 1.0, 0.0).to_raw();
+    raw.mSize = ffi::aiVector2D { x: 2.0, y: 4.0 };
+    raw
+}
+
+#[test]
+fn test_area_light_size() {
+    let mut raw = rect_light();
+    let light = unsafe { Light::from_raw(NonNull::new(&mut raw).unwrap()) };
+
+    assert_eq!(light.light_type(), LightType::Area);
+    assert_eq!(light.size(), (2.0, 4.0));
+}
+
+#[test]
+fn test_area_light_corners() {
+    let mut raw = rect_light();
+    let light = unsafe { Light::from_raw(NonNull::new(&mut raw).unwrap()) };
+
+    // Translate the light's node 10 units along X.
+    let mut world = Matrix4x4::identity();
+    world.0.a4 = 10.0;
+
+    let corners = light.area_corners(&world);
+    assert_eq!(corners[0], Vector3D::new(9.0, -1.0, 0.0));
+    assert_eq!(corners[1], Vector3D::new(11.0, -1.0, 0.0));
+    assert_eq!(corners[2], Vector3D::new(11.0, 3.0, 0.0));
+    assert_eq!(corners[3], Vector3D::new(9.0, 3.0, 0.0));
+}
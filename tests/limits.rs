@@ -0,0 +1,151 @@
+extern crate open_asset_importer as assimp;
+
+use std::cell::Cell;
+
+use assimp::fixtures;
+use assimp::import::ImportLimits;
+use assimp::io::{ByteBudget, MemoryFileIO};
+use assimp::{Error, File, FileIO, Importer};
+
+/// Serves the fixtures, `two_materials.mtl` among them, and counts the files it's asked for.
+struct MtlIO {
+    files: MemoryFileIO,
+    requests: Cell<u32>,
+}
+
+impl MtlIO {
+    fn new() -> MtlIO {
+        MtlIO {
+            files: fixtures::file_io(),
+            requests: Cell::new(0),
+        }
+    }
+}
+
+impl FileIO for MtlIO {
+    fn open(&self, file_path: &str, mode: &str) -> Option<Box<dyn File>> {
+        self.requests.set(self.requests.get() + 1);
+        self.files.open(file_path, mode)
+    }
+}
+
+fn limit_exceeded(importer: &Importer, path: &str) -> (&'static str, u64, u64) {
+    match importer.read_file(path) {
+        Err(Error::LimitExceeded { what, limit, found }) => (what, limit, found),
+        other => panic!("expected LimitExceeded, got {:?}", other.err()),
+    }
+}
+
+#[test]
+fn test_limits_are_enforced() {
+    let mut importer = Importer::new();
+    importer.limits(|limits| limits.max_meshes = Some(5));
+    assert_eq!(
+        limit_exceeded(&importer, "examples/ten_meshes.gltf"),
+        ("meshes", 5, 10)
+    );
+    assert!(importer.read_file("examples/unit_cube.obj").is_ok());
+
+    importer.limits(|limits| limits.max_vertices = Some(7));
+    let (what, limit, found) = limit_exceeded(&importer, "examples/unit_cube.obj");
+    assert_eq!((what, limit), ("vertices", 7));
+    assert!(found > 7);
+
+    // Counting stops at the first node over the limit.
+    importer.limits(|limits| limits.max_nodes = Some(0));
+    assert_eq!(
+        limit_exceeded(&importer, "examples/unit_cube.obj"),
+        ("nodes", 0, 1)
+    );
+
+    importer.limits(|limits| limits.max_texture_bytes = Some(16));
+    let (what, _, found) = limit_exceeded(&importer, "examples/embedded_texture.glb");
+    assert_eq!(what, "texture bytes");
+    assert!(found > 16);
+
+    // The untrusted limits allow every example, and are kept by clones.
+    importer.set_limits(ImportLimits::untrusted());
+    let scene = importer
+        .clone()
+        .read_file("examples/ten_meshes.gltf")
+        .unwrap();
+    assert_eq!(scene.num_meshes(), 10);
+
+    let error = Error::LimitExceeded {
+        what: "meshes",
+        limit: 5,
+        found: 10,
+    };
+    assert_eq!(
+        error.to_string(),
+        "the scene has more meshes than the limit: 10 > 5"
+    );
+}
+
+#[test]
+fn test_read_from_memory() {
+    let importer = Importer::new();
+    let scene = importer
+        .read_from_memory(fixtures::UNIT_CUBE_OBJ, "obj")
+        .unwrap();
+    assert_eq!(scene.num_meshes(), 1);
+    let scene = importer
+        .read_from_memory(fixtures::EMBEDDED_TEXTURE_GLB, "")
+        .unwrap();
+    assert_eq!(scene.num_textures(), 1);
+
+    match importer.read_from_memory(fixtures::UNIT_CUBE_OBJ, "../obj") {
+        Err(Error::InvalidConfig { .. }) => {}
+        other => panic!("expected InvalidConfig, got {:?}", other.err()),
+    }
+}
+
+#[test]
+fn test_read_from_memory_refuses_other_files() {
+    let importer = Importer::new();
+
+    // The OBJ importer carries on without its material library, so only the default material
+    // is left.
+    let scene = importer
+        .read_from_memory(fixtures::TWO_MATERIAL_OBJ, "obj")
+        .unwrap();
+    assert_eq!(scene.num_meshes(), 2);
+    assert_eq!(scene.num_materials(), 1);
+
+    let io = MtlIO::new();
+    let scene = importer
+        .read_from_memory_with_io(fixtures::TWO_MATERIAL_OBJ, "obj", &io)
+        .unwrap();
+    assert_eq!(scene.num_materials(), 3);
+    assert!(io.requests.get() >= 1);
+}
+
+#[test]
+fn test_read_from_memory_budget() {
+    let mut importer = Importer::new();
+    importer.limits(|limits| {
+        limits.io_budget = ByteBudget {
+            max_total_bytes: 1 << 20,
+            max_file_size: 64,
+            max_files: 4,
+        }
+    });
+    match importer.read_from_memory(fixtures::UNIT_CUBE_OBJ, "obj") {
+        Err(Error::BudgetExceeded { what, .. }) => assert_eq!(what, "file size"),
+        other => panic!("expected BudgetExceeded, got {:?}", other.err()),
+    }
+
+    // The side files count towards the same budget.
+    importer.limits(|limits| {
+        limits.io_budget = ByteBudget {
+            max_files: 1,
+            ..ByteBudget::default()
+        }
+    });
+    let io = MtlIO::new();
+    let scene = importer
+        .read_from_memory_with_io(fixtures::TWO_MATERIAL_OBJ, "obj", &io)
+        .unwrap();
+    assert_eq!(scene.num_materials(), 1);
+    assert_eq!(io.requests.get(), 0);
+}
@@ -0,0 +1,101 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::fixtures;
+use assimp::scene::lod::{detect_lods, validate_lod_group, LodConvention, LodGroup};
+use assimp::Importer;
+
+fn summary(group: &LodGroup) -> (String, Vec<(u32, String)>) {
+    let levels = group
+        .levels
+        .iter()
+        .map(|level| (level.level, level.node.name().to_owned()))
+        .collect();
+    (group.base_name.clone(), levels)
+}
+
+fn owned(base: &str, levels: &[(u32, &str)]) -> (String, Vec<(u32, String)>) {
+    let levels = levels
+        .iter()
+        .map(|&(level, name)| (level, name.to_owned()))
+        .collect();
+    (base.to_owned(), levels)
+}
+
+#[test]
+fn test_detect_lods_by_suffix() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/lods.dae").unwrap();
+    let groups = detect_lods(&scene, LodConvention::Suffix);
+
+    let summaries: Vec<_> = groups.iter().map(summary).collect();
+    assert_eq!(
+        summaries,
+        vec![
+            // A gap, and a lowercase suffix.
+            owned("Crate", &[(0, "Crate_LOD0"), (2, "crate_lod2")]),
+            owned("Rock", &[(0, "Rock_LOD0"), (1, "Rock_LOD1")]),
+            // Only the meshes carry the suffix.
+            owned("Bush", &[(0, "BushHigh"), (1, "BushLow")]),
+        ]
+    );
+
+    for level in &groups[2].levels {
+        assert_eq!(level.meshes.len(), 1);
+        let mesh = scene.mesh(level.meshes[0]).unwrap();
+        assert!(mesh.name().to_lowercase().starts_with("bush_lod"));
+    }
+
+    assert!(validate_lod_group(&scene, &groups[0]).is_ok());
+    assert!(validate_lod_group(&scene, &groups[2]).is_ok());
+
+    let error = validate_lod_group(&scene, &groups[1]).unwrap_err();
+    assert_eq!((error.level, error.triangles), (1, 12));
+    assert_eq!((error.previous_level, error.previous_triangles), (0, 2));
+    assert_eq!(
+        error.to_string(),
+        "Rock LOD1 has 12 triangles, which isn't fewer than the 2 of LOD0"
+    );
+}
+
+#[test]
+fn test_detect_lods_custom() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/lods.dae").unwrap();
+
+    let convention = LodConvention::Custom(Box::new(|name: &str| match name {
+        "BushHigh" => Some(("Bush".to_owned(), 0)),
+        "BushLow" => Some(("Bush".to_owned(), 1)),
+        _ => None,
+    }));
+    let groups = detect_lods(&scene, convention);
+    assert_eq!(
+        groups.iter().map(summary).collect::<Vec<_>>(),
+        vec![owned("Bush", &[(0, "BushHigh"), (1, "BushLow")])]
+    );
+}
+
+#[test]
+fn test_detect_lods_by_metadata() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/metadata.assbin").unwrap();
+
+    let groups = detect_lods(&scene, LodConvention::Metadata("LOD".to_owned()));
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0].levels[0].level, 1);
+
+    let groups = detect_lods(&scene, LodConvention::Metadata("$lod".to_owned()));
+    assert!(groups.is_empty());
+}
+
+#[test]
+fn test_no_lods() {
+    let importer = Importer::new();
+
+    let scenes = vec![
+        fixtures::unit_cube_with(&importer),
+        importer.read_file("examples/instanced.dae").unwrap(),
+    ];
+    for scene in &scenes {
+        assert!(detect_lods(scene, LodConvention::Suffix).is_empty());
+    }
+}
@@ -1,5 +1,7 @@
 extern crate open_asset_importer as assimp;
 
+use assimp::fixtures;
+use assimp::log::{with_captured_logs, LogCapture, LogLevel, LogRecord};
 use assimp::Importer;
 use assimp::LogStream;
 use std::ffi::CStr;
@@ -16,5 +18,55 @@ fn test_custom_logging() {
     let mut log_stream = LogStream::callback(log_callback);
     log_stream.attach();
     let importer = Importer::new();
-    let _scene = importer.read_file("examples/box.obj");
+    let _scene = fixtures::unit_cube_with(&importer);
+}
+
+#[test]
+fn test_captured_logs() {
+    let importer = Importer::new();
+    let mentions = |records: &[LogRecord], name: &str| {
+        records.iter().any(|record| record.message.contains(name))
+    };
+
+    let (first, first_records) =
+        with_captured_logs(|| importer.read_file("examples/two_materials.obj"));
+    assert!(first.is_ok());
+    assert!(mentions(&first_records, "two_materials.obj"));
+    assert!(first_records.iter().any(|record| record.level.is_some()));
+
+    // The first capture was detached, and the second one only sees its own import.
+    let (second, second_records) = with_captured_logs(|| importer.read_file("examples/spider.obj"));
+    assert!(second.is_ok());
+    assert!(mentions(&second_records, "spider.obj"));
+    assert!(!mentions(&second_records, "two_materials.obj"));
+}
+
+#[test]
+fn test_log_capture() {
+    let importer = Importer::new();
+    let mut capture = LogCapture::new(2);
+    assert!(capture.attached());
+    importer.read_file("examples/spider.obj").unwrap();
+
+    // Only the newest records are kept.
+    let records = capture.drain();
+    assert!(!records.is_empty() && records.len() <= 2);
+    assert!(capture.dropped() > 0);
+    assert!(capture.drain().is_empty());
+
+    capture.detach();
+    assert!(!capture.attached());
+    importer.read_file("examples/spider.obj").unwrap();
+    assert!(capture.drain().is_empty());
+}
+
+#[test]
+fn test_log_record_matches() {
+    let (_, mut records) = with_captured_logs(fixtures::two_material_obj);
+    records.retain(|record| record.matches(LogLevel::Info, "two_materials"));
+    assert!(!records.is_empty());
+    for record in &records {
+        assert!(record.level.unwrap() >= LogLevel::Info);
+        assert!(!record.message.ends_with('\n'));
+    }
 }
@@ -0,0 +1,249 @@
+extern crate open_asset_importer as assimp;
+
+use std::convert::TryInto;
+
+use assimp::fixtures;
+use assimp::scene::{MaterialComponentType, MaterialSource};
+use assimp::{Color3D, Color4D, Importer, Material, MaterialKey, MaterialValue};
+
+#[test]
+fn test_overlay_diffuse_color() {
+    let importer = Importer::new();
+    let scene = fixtures::two_material_obj_with(&importer);
+    let is_red = |m: &Material| match m.get_value(MaterialKey::Name) {
+        Some(MaterialValue::String(name)) => &*name == "Red",
+        _ => false,
+    };
+    let id = scene.materials().position(is_red).unwrap() as u32;
+
+    let green = Color3D::new(0.0, 1.0, 0.0);
+    let mut overlay = scene.material_overlay();
+    overlay.set(
+        id,
+        MaterialKey::Color(MaterialComponentType::Diffuse),
+        MaterialValue::Color3D(green),
+    );
+
+    let overlaid = overlay.material(id).unwrap();
+    assert_eq!(overlaid.diffuse().unwrap().color, green);
+    assert_eq!(
+        overlaid
+            .component(MaterialComponentType::Diffuse)
+            .unwrap()
+            .color,
+        green
+    );
+
+    let original = scene.material(id).unwrap();
+    assert_eq!(
+        original.diffuse().unwrap().color,
+        Color3D::new(1.0, 0.0, 0.0)
+    );
+}
+
+#[test]
+fn test_wrap_w() {
+    use assimp::scene::WrappingMode;
+
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/mapmode_w.assbin").unwrap();
+    let material = scene.material(0).unwrap();
+
+    let textures: Vec<_> = material
+        .component(MaterialComponentType::Diffuse)
+        .unwrap()
+        .textures
+        .collect();
+    assert_eq!(textures.len(), 2);
+
+    assert_eq!(textures[0].wrap_u, Some(WrappingMode::MirrorRepeat));
+    assert_eq!(textures[0].wrap_v, Some(WrappingMode::Clamp));
+    assert_eq!(textures[0].wrap_w, Some(WrappingMode::Decal));
+    assert_eq!(
+        material.get_value(MaterialKey::MappingModeW(MaterialComponentType::Diffuse, 0)),
+        Some(MaterialValue::WrappingMode(WrappingMode::Decal))
+    );
+
+    // The second texture only has U and V modes.
+    assert_eq!(textures[1].wrap_u, Some(WrappingMode::Repeat));
+    assert_eq!(textures[1].wrap_v, Some(WrappingMode::Repeat));
+    assert_eq!(textures[1].wrap_w, None);
+}
+
+#[test]
+fn test_inferred_channels() {
+    use assimp::scene::{BlendOp, ChannelSource};
+
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/lightmapped.assbin").unwrap();
+    let material = scene.material(0).unwrap();
+
+    // Neither texture has a channel in the file, so the lightmap gets the second one.
+    let diffuse: Vec<_> = material.diffuse().unwrap().textures.collect();
+    assert_eq!(diffuse.len(), 2);
+    assert_eq!(&*diffuse[0].path, "base.png");
+    assert_eq!(diffuse[0].channel, 0);
+    assert_eq!(diffuse[0].channel_source, ChannelSource::Inferred);
+    assert_eq!(&*diffuse[1].path, "lightmap.png");
+    assert_eq!(diffuse[1].blend_op, BlendOp::Multiply);
+    assert_eq!(diffuse[1].channel, 1);
+    assert_eq!(diffuse[1].channel_source, ChannelSource::Inferred);
+
+    // The first texture claims channel 1, so the second gets the lowest free channel.
+    let specular: Vec<_> = material.specular().unwrap().textures.collect();
+    assert_eq!(specular.len(), 2);
+    assert_eq!(specular[0].channel, 1);
+    assert_eq!(specular[0].channel_source, ChannelSource::Explicit);
+    assert_eq!(specular[1].channel, 0);
+    assert_eq!(specular[1].channel_source, ChannelSource::Inferred);
+}
+
+#[test]
+fn test_shading_path_hint() {
+    use assimp::scene::ShadingPath;
+
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/pbr.gltf").unwrap();
+    let brushed = scene
+        .materials()
+        .find(|m| match m.get_value(MaterialKey::Name) {
+            Some(MaterialValue::String(name)) => &*name == "Brushed",
+            _ => false,
+        })
+        .unwrap();
+    assert_eq!(brushed.shading_path_hint(), ShadingPath::Pbr);
+
+    let scene = fixtures::two_material_obj_with(&importer);
+    for material in scene.materials() {
+        assert_eq!(material.shading_path_hint(), ShadingPath::Legacy);
+    }
+}
+
+#[test]
+fn test_has() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/lightmapped.assbin").unwrap();
+    let material = scene.material(0).unwrap();
+
+    assert!(material.has(MaterialKey::Name));
+    assert!(material.has(MaterialKey::Texture(MaterialComponentType::Diffuse, 1)));
+    assert!(material.has(MaterialKey::UVWSource(MaterialComponentType::Specular, 0)));
+    // Only the index or the component differs from properties that exist.
+    assert!(!material.has(MaterialKey::Texture(MaterialComponentType::Diffuse, 2)));
+    assert!(!material.has(MaterialKey::UVWSource(MaterialComponentType::Specular, 1)));
+    assert!(!material.has(MaterialKey::UVWSource(MaterialComponentType::Diffuse, 0)));
+    assert!(!material.has(MaterialKey::Color(MaterialComponentType::Diffuse)));
+    assert!(!material.has(MaterialKey::Color(MaterialComponentType::Lightmap)));
+
+    let keys: Vec<_> = material.keys().collect();
+    assert_eq!(keys.len(), material.num_properties() as usize);
+    assert!(keys.contains(&("?mat.name".to_owned(), None, 0)));
+    assert!(keys.contains(&(
+        "$tex.uvwsrc".to_owned(),
+        Some(MaterialComponentType::Specular),
+        0
+    )));
+    assert!(keys.contains(&(
+        "$tex.file".to_owned(),
+        Some(MaterialComponentType::Diffuse),
+        1
+    )));
+}
+
+#[test]
+fn test_color_alpha() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/translucent.gltf").unwrap();
+    let material = scene.material(0).unwrap();
+
+    // The alpha of glTF's `baseColorFactor` is kept.
+    let diffuse = material.get_value(MaterialKey::Color(MaterialComponentType::Diffuse));
+    assert_eq!(
+        diffuse,
+        Some(MaterialValue::Color4D(Color4D::new(0.25, 0.5, 0.75, 0.5)))
+    );
+    let color: Color4D = diffuse.unwrap().try_into().unwrap();
+    assert_eq!(Color3D::from(color), Color3D::new(0.25, 0.5, 0.75));
+
+    let component = material.diffuse().unwrap();
+    assert_eq!(component.color, Color3D::new(0.25, 0.5, 0.75));
+    assert_eq!(component.color_alpha, 0.5);
+
+    // Colors without alpha are opaque.
+    let scene = fixtures::two_material_obj_with(&importer);
+    let material = scene.material(0).unwrap();
+    assert_eq!(material.diffuse().unwrap().color_alpha, 1.0);
+}
+
+#[test]
+fn test_complexity() {
+    use assimp::scene::{BlendOp, ComplexityIssue, ComplexityLimits};
+
+    let importer = Importer::new();
+    let scene = importer
+        .read_file("examples/material_complexity.assbin")
+        .unwrap();
+
+    let simple = scene.material(0).unwrap().complexity();
+    assert_eq!(simple.texture_counts, [(MaterialComponentType::Diffuse, 1)]);
+    // The file doesn't give the texture an operation.
+    assert_eq!(simple.blend_ops, [BlendOp::Multiply]);
+    assert_eq!(simple.distinct_paths, 1);
+    assert!(!simple.non_uv_mapping && !simple.decal_wrapping && !simple.uses_pbr_keys);
+
+    let stuffed = scene.material(1).unwrap().complexity();
+    assert_eq!(
+        stuffed.texture_counts,
+        [
+            (MaterialComponentType::Diffuse, 3),
+            (MaterialComponentType::Specular, 1),
+            (MaterialComponentType::Emissive, 1),
+            (MaterialComponentType::Lightmap, 1),
+        ]
+    );
+    assert_eq!(stuffed.texture_count(MaterialComponentType::Diffuse), 3);
+    assert_eq!(stuffed.texture_count(MaterialComponentType::Opacity), 0);
+    assert_eq!(stuffed.total_textures(), 6);
+    assert_eq!(
+        stuffed.blend_ops,
+        [BlendOp::Multiply, BlendOp::Add, BlendOp::SmoothAdd]
+    );
+    // The emissive texture reuses the first diffuse one.
+    assert_eq!(stuffed.distinct_paths, 5);
+    assert!(stuffed.non_uv_mapping && stuffed.decal_wrapping && stuffed.uses_pbr_keys);
+
+    assert_eq!(scene.material_complexities(), [simple, stuffed]);
+    assert_eq!(
+        scene.complex_materials(&ComplexityLimits::default()),
+        [(
+            1,
+            vec![
+                ComplexityIssue::StackedTextures {
+                    component: MaterialComponentType::Diffuse,
+                    count: 3
+                },
+                ComplexityIssue::BlendOp(BlendOp::Add),
+                ComplexityIssue::BlendOp(BlendOp::SmoothAdd),
+                ComplexityIssue::NonUvMapping,
+                ComplexityIssue::DecalWrapping,
+            ]
+        )]
+    );
+
+    let limits = ComplexityLimits {
+        max_textures_per_component: 3,
+        max_distinct_paths: 4,
+        blend_ops: vec![BlendOp::Multiply, BlendOp::Add, BlendOp::SmoothAdd],
+        allow_non_uv_mapping: true,
+        allow_decal_wrapping: true,
+        allow_pbr: false,
+        ..Default::default()
+    };
+    assert_eq!(
+        scene.complex_materials(&limits),
+        [(
+            1,
+            vec![ComplexityIssue::TooManyPaths(5), ComplexityIssue::Pbr]
+        )]
+    );
+}
@@ -0,0 +1,199 @@
+extern crate autogenerated_assimp_sys as ffi;
+extern crate open_asset_importer as assimp;
+
+use assimp::fixtures;
+use assimp::scene::count_regions;
+use assimp::{Face, Importer, PrimitiveType};
+use std::fmt::Write;
+use std::ptr::{self, NonNull};
+
+/// Generate an ASCII PLY file containing a single mesh with `num_triangles` unconnected
+/// triangles, so the mesh has `num_triangles * 3` vertices.
+fn generate_ply(num_triangles: usize) -> String {
+    let num_vertices = num_triangles * 3;
+    let mut ply = String::new();
+
+    writeln!(ply, "ply").unwrap();
+    writeln!(ply, "format ascii 1.0").unwrap();
+    writeln!(ply, "element vertex {}", num_vertices).unwrap();
+    writeln!(ply, "property float x").unwrap();
+    writeln!(ply, "property float y").unwrap();
+    writeln!(ply, "property float z").unwrap();
+    writeln!(ply, "element face {}", num_triangles).unwrap();
+    writeln!(ply, "property list uchar int vertex_indices").unwrap();
+    writeln!(ply, "end_header").unwrap();
+
+    for i in 0..num_triangles {
+        let x = i as f32;
+        writeln!(ply, "{} 0 0", x).unwrap();
+        writeln!(ply, "{} 1 0", x).unwrap();
+        writeln!(ply, "{} 0 1", x).unwrap();
+    }
+
+    for i in 0..num_triangles {
+        writeln!(ply, "3 {} {} {}", i * 3, i * 3 + 1, i * 3 + 2).unwrap();
+    }
+
+    ply
+}
+
+#[test]
+fn test_small_mesh_u16_indices() {
+    let importer = Importer::new();
+    let scene = fixtures::unit_cube_with(&importer);
+    let mesh = scene.mesh(0).unwrap();
+
+    assert!(mesh.fits_u16_indices());
+    let indices = mesh.indices_u16().unwrap();
+    assert_eq!(indices.len(), mesh.indices_u32().len());
+}
+
+#[test]
+fn test_huge_mesh_u16_refusal() {
+    let ply = generate_ply(70_002 / 3);
+    let importer = Importer::new();
+    let scene = importer
+        .read_memory_with_hint(ply.as_bytes(), "ply")
+        .unwrap();
+    let mesh = scene.mesh(0).unwrap();
+
+    assert_eq!(mesh.num_vertices(), 70_002);
+    assert!(!mesh.fits_u16_indices());
+    assert!(mesh.indices_u16().is_none());
+
+    let indices = mesh.indices_u32();
+    assert_eq!(indices.len(), 70_002);
+    assert_eq!(indices.iter().max(), Some(&70_001));
+}
+
+#[test]
+#[cfg(feature = "assimp-5-1")]
+fn test_texture_coords_names() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/uv_sets.fbx").unwrap();
+    let mesh = scene.mesh(0).unwrap();
+
+    assert_eq!(mesh.texture_coords_name(0), Some("map1"));
+    assert_eq!(mesh.texture_coords_name(1), Some("lightmapUV"));
+    assert_eq!(mesh.texture_coords_name(2), None);
+    assert_eq!(mesh.try_texture_coords_name(1), Ok(Some("lightmapUV")));
+    assert_eq!(
+        &mesh.texture_coords_names()[..2],
+        &[Some("map1"), Some("lightmapUV")]
+    );
+}
+
+#[test]
+fn test_texture_coords_names_missing() {
+    let importer = Importer::new();
+    let scene = fixtures::unit_cube_with(&importer);
+    let mesh = scene.mesh(0).unwrap();
+
+    assert!(mesh.texture_coords_names().iter().all(Option::is_none));
+    assert_eq!(mesh.texture_coords_name(0), None);
+    assert_eq!(mesh.texture_coords_name(100), None);
+}
+
+#[test]
+fn test_smoothing_regions_cube() {
+    let importer = Importer::new();
+    let scene = fixtures::unit_cube_with(&importer);
+    let mesh = scene.mesh(0).unwrap();
+
+    // Every side is its own region at 30°, but one region covers the whole cube at 100°.
+    let regions = mesh.smoothing_regions(30.0);
+    assert_eq!(regions, vec![0, 1, 2, 3, 4, 5]);
+    assert_eq!(count_regions(&regions), 6);
+    let indices = mesh.region_indices(&regions);
+    assert_eq!(indices.len(), 6);
+    assert!(indices.iter().all(|region| region.len() == 4));
+
+    let regions = mesh.smoothing_regions(100.0);
+    assert_eq!(regions, vec![0; 6]);
+    assert_eq!(mesh.region_indices(&regions), vec![mesh.indices_u32()]);
+
+    // Both triangles of a side stay together.
+    let mut importer = Importer::new();
+    importer.triangulate(true);
+    let scene = fixtures::unit_cube_with(&importer);
+    let regions = scene.mesh(0).unwrap().smoothing_regions(30.0);
+    assert_eq!(regions.len(), 12);
+    assert_eq!(count_regions(&regions), 6);
+}
+
+#[test]
+fn test_smoothing_regions_sphere() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/sphere.obj").unwrap();
+    let mesh = scene.mesh(0).unwrap();
+
+    // Neighbouring faces are about 15° apart.
+    assert_eq!(mesh.smoothing_regions(30.0), vec![0; 288]);
+    assert!(count_regions(&mesh.smoothing_regions(10.0)) > 1);
+}
+
+/// A square as two triangles, with a polyline along two of its edges in between.
+const MIXED_OBJ: &str = "\
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+f 1 2 3
+l 3 4 1
+f 1 3 4
+";
+
+#[test]
+fn test_faces_of_type() {
+    let importer = Importer::new();
+    let scene = importer
+        .read_memory_with_hint(MIXED_OBJ.as_bytes(), "obj")
+        .unwrap();
+    assert_eq!(scene.num_meshes(), 1);
+    let mesh = scene.mesh(0).unwrap();
+    assert_eq!(mesh.num_faces(), 4);
+
+    let of_type = |type_| {
+        mesh.faces_of_type(type_)
+            .map(|(index, face)| {
+                assert_eq!(face.primitive_type(), Some(type_));
+                assert_eq!(mesh.face(index).unwrap().indices(), face.indices());
+                index
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(of_type(PrimitiveType::Triangle), vec![0, 3]);
+    assert_eq!(of_type(PrimitiveType::Line), vec![1, 2]);
+    assert!(of_type(PrimitiveType::Point).is_empty());
+    assert!(of_type(PrimitiveType::Polygon).is_empty());
+
+    assert!(!mesh.is_pure(PrimitiveType::Triangle));
+    assert!(!mesh.is_pure(PrimitiveType::Line));
+
+    // Sorting by primitive type splits the mesh into pure ones.
+    let mut importer = Importer::new();
+    importer.sort_by_primitive_type(|args| args.enable = true);
+    let scene = importer
+        .read_memory_with_hint(MIXED_OBJ.as_bytes(), "obj")
+        .unwrap();
+    assert_eq!(scene.num_meshes(), 2);
+    for mesh in scene.meshes() {
+        let type_ = mesh.faces().next().unwrap().primitive_type().unwrap();
+        assert!(mesh.is_pure(type_));
+        assert_eq!(mesh.faces_of_type(type_).count(), 2);
+    }
+
+    let scene = fixtures::unit_cube_with(&importer);
+    assert!(scene.mesh(0).unwrap().is_pure(PrimitiveType::Polygon));
+}
+
+#[test]
+fn test_empty_face_primitive_type() {
+    let raw = ffi::aiFace {
+        mNumIndices: 0,
+        mIndices: ptr::null_mut(),
+    };
+    let face = unsafe { Face::from_raw(NonNull::from(&raw)) };
+    assert_eq!(face.primitive_type(), None);
+    assert!(face.indices().is_empty());
+}
@@ -0,0 +1,155 @@
+extern crate open_asset_importer as assimp;
+
+use std::fmt::Write;
+
+use assimp::math::Vector3D;
+use assimp::scene::mesh_stats::{overdraw_estimate, vertex_cache_stats, OverdrawView};
+use assimp::scene::MeshData;
+use assimp::Importer;
+
+/// A flat grid of `n` by `n` quads in the XY plane, facing +Z, with the triangles in strips
+/// along the rows.
+fn grid(n: u32) -> MeshData {
+    let mut positions = Vec::new();
+    for y in 0..=n {
+        for x in 0..=n {
+            positions.push(Vector3D::new(x as f32, y as f32, 0.0));
+        }
+    }
+    let vertex = |x: u32, y: u32| y * (n + 1) + x;
+    let mut faces = Vec::new();
+    for y in 0..n {
+        for x in 0..n {
+            faces.push(vec![vertex(x, y), vertex(x + 1, y), vertex(x + 1, y + 1)]);
+            faces.push(vec![vertex(x, y), vertex(x + 1, y + 1), vertex(x, y + 1)]);
+        }
+    }
+    MeshData {
+        positions,
+        faces,
+        ..Default::default()
+    }
+}
+
+/// Shuffle the faces of `mesh` with a fixed seed.
+fn shuffled(mut mesh: MeshData) -> MeshData {
+    let mut state = 0x5eedu64;
+    for i in (1..mesh.faces.len()).rev() {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        let j = (state >> 33) as usize % (i + 1);
+        mesh.faces.swap(i, j);
+    }
+    mesh
+}
+
+fn indices(mesh: &MeshData) -> Vec<u32> {
+    mesh.faces.iter().flatten().copied().collect()
+}
+
+#[test]
+fn test_strips_have_lower_acmr_than_shuffled() {
+    let strips = grid(16);
+    let random = shuffled(strips.clone());
+
+    let ordered = vertex_cache_stats(&indices(&strips), 16);
+    let shuffled = vertex_cache_stats(&indices(&random), 16);
+    assert_eq!((ordered.triangles, ordered.vertices), (512, 289));
+    assert_eq!((shuffled.triangles, shuffled.vertices), (512, 289));
+    assert!(ordered.acmr < shuffled.acmr, "{:?} {:?}", ordered, shuffled);
+    assert!(ordered.atvr < shuffled.atvr);
+    // A row uses 34 vertices, more than the cache holds, so every row misses all of them.
+    assert_eq!(ordered.cache_misses, 17 * 16 + 16 * 17);
+
+    // Without a cache, every vertex of every triangle is a miss.
+    let uncached = vertex_cache_stats(&indices(&strips), 0);
+    assert_eq!(uncached.acmr, 3.0);
+    assert_eq!(vertex_cache_stats(&[], 16).acmr, 0.0);
+}
+
+#[test]
+fn test_stats_are_reproducible() {
+    let mesh = shuffled(grid(12));
+    let views = OverdrawView::canonical(32);
+
+    let first = (
+        vertex_cache_stats(&indices(&mesh), 24),
+        overdraw_estimate(&mesh, &views),
+    );
+    for _ in 0..3 {
+        let again = (
+            vertex_cache_stats(&indices(&mesh), 24),
+            overdraw_estimate(&mesh, &views),
+        );
+        assert_eq!(again, first);
+    }
+}
+
+#[test]
+fn test_overdraw_estimate() {
+    let single = grid(8);
+    let estimate = overdraw_estimate(&single, &OverdrawView::canonical(16));
+    // Only the view looking down -Z sees the front of the grid, and every cell once.
+    let seen: Vec<_> = estimate
+        .views
+        .iter()
+        .filter(|view| view.covered_cells > 0)
+        .collect();
+    assert_eq!(seen.len(), 1);
+    assert_eq!(seen[0].view.direction, Vector3D::new(0.0, 0.0, -1.0));
+    assert_eq!((seen[0].covered_cells, seen[0].fragments), (256, 256));
+    assert_eq!(estimate.average, 1.0);
+
+    // A copy of the grid one unit in front of it doubles the overdraw.
+    let mut double = single.clone();
+    let offset = single.positions.len() as u32;
+    double.positions.extend(
+        single
+            .positions
+            .iter()
+            .map(|p| Vector3D::new(p.x, p.y, 1.0)),
+    );
+    double.faces.extend(
+        single
+            .faces
+            .iter()
+            .map(|face| face.iter().map(|i| i + offset).collect::<Vec<_>>()),
+    );
+    let estimate = overdraw_estimate(&double, &OverdrawView::canonical(16));
+    assert_eq!(estimate.average, 2.0);
+}
+
+#[test]
+fn test_cache_locality_report() {
+    // A shuffled grid as an OBJ file.
+    let mesh = shuffled(grid(24));
+    let mut obj = String::new();
+    for p in &mesh.positions {
+        writeln!(obj, "v {} {} {}", p.x, p.y, p.z).unwrap();
+    }
+    for face in &mesh.faces {
+        writeln!(obj, "f {} {} {}", face[0] + 1, face[1] + 1, face[2] + 1).unwrap();
+    }
+
+    let mut importer = Importer::new();
+    importer.join_identical_vertices(true);
+    let scene = importer
+        .read_memory_with_hint(obj.as_bytes(), "obj")
+        .unwrap();
+    importer.improve_cache_locality(|args| args.enable = true);
+    let (scene, report) = importer
+        .apply_postprocessing_with_cache_report(scene)
+        .unwrap();
+
+    assert_eq!(report.before.cache_size, 12);
+    assert_eq!(report.before.meshes.len(), 1);
+    assert_eq!(report.after.total.triangles, report.before.total.triangles);
+    assert!(
+        report.after.total.acmr < report.before.total.acmr,
+        "{:?}",
+        report
+    );
+    assert!(report.acmr_improvement() > 0.0);
+    assert_eq!(scene.vertex_cache_stats(12), report.after);
+}
@@ -0,0 +1,78 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::fixtures;
+use assimp::scene::MetadataOwnedValue;
+use assimp::{Importer, MetadataValue, Node};
+use std::ffi::CString;
+
+fn keys(node: &Node) -> Vec<String> {
+    node.metadata()
+        .map(|(key, _)| key.to_str().unwrap().to_owned())
+        .collect()
+}
+
+fn string(value: &str) -> MetadataOwnedValue {
+    MetadataOwnedValue::Str(CString::new(value).unwrap())
+}
+
+#[test]
+fn test_metadata_overlay() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/metadata.assbin").unwrap();
+    let root = scene.root_node().unwrap();
+    assert_eq!(keys(root), vec!["LOD", "Collision", "Source"]);
+
+    let mut overlay = scene.metadata_overlay();
+    overlay.set(root, "LOD", MetadataOwnedValue::I32(2));
+    overlay.set(root, "Tag", string("lod-group"));
+    overlay.remove(root, "Collision");
+
+    assert_eq!(overlay.get(root, "LOD"), Some(MetadataOwnedValue::I32(2)));
+    assert_eq!(overlay.get(root, "Tag"), Some(string("lod-group")));
+    assert_eq!(overlay.get(root, "Collision"), None);
+    assert_eq!(overlay.get(root, "Source"), Some(string("crate")));
+
+    assert_eq!(
+        overlay.effective_metadata(root),
+        vec![
+            (CString::new("LOD").unwrap(), MetadataOwnedValue::I32(2)),
+            (CString::new("Source").unwrap(), string("crate")),
+            (CString::new("Tag").unwrap(), string("lod-group")),
+        ]
+    );
+
+    // The scene itself is untouched.
+    assert_eq!(keys(root), vec!["LOD", "Collision", "Source"]);
+    match root.metadata().next().unwrap().1.get() {
+        MetadataValue::I32(lod) => assert_eq!(lod, 1),
+        _ => panic!("LOD should be an i32"),
+    }
+
+    overlay.unset(root, "Collision");
+    assert_eq!(
+        overlay.get(root, "Collision"),
+        Some(MetadataOwnedValue::Bool(true))
+    );
+    assert_eq!(overlay.num_overrides(), 2);
+}
+
+#[test]
+fn test_metadata_overlay_without_metadata() {
+    let importer = Importer::new();
+    let scene = fixtures::unit_cube_with(&importer);
+    let root = scene.root_node().unwrap();
+    let child = root.children().next().unwrap();
+
+    let mut overlay = scene.metadata_overlay();
+    overlay.set(child, "Collision", MetadataOwnedValue::Bool(false));
+
+    assert_eq!(
+        overlay.effective_metadata(child),
+        vec![(
+            CString::new("Collision").unwrap(),
+            MetadataOwnedValue::Bool(false)
+        )]
+    );
+    assert!(overlay.effective_metadata(root).is_empty());
+    assert_eq!(child.metadata().count(), 0);
+}
@@ -0,0 +1,74 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::scene::apply_morphs;
+use assimp::{Importer, Vector3D};
+
+#[test]
+fn test_morph_target_deltas() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/morph.gltf").unwrap();
+    let mesh = scene.mesh(0).unwrap();
+    assert_eq!(mesh.num_anim_meshes(), 2);
+
+    let deltas = mesh.morph_target_deltas(1e-6);
+    assert_eq!(deltas.len(), 2);
+    // The grid is 5 by 5. "Bump" raises the center, "Ridge" the middle of the middle row.
+    assert_eq!(deltas[0].name, "Bump");
+    assert_eq!(deltas[0].weight, 0.25);
+    assert_eq!(deltas[0].indices, vec![12]);
+    assert_eq!(
+        deltas[0].position_deltas,
+        vec![Vector3D::new(0.0, 0.0, 1.0)]
+    );
+    assert_eq!(deltas[1].name, "Ridge");
+    assert_eq!(deltas[1].weight, 0.5);
+    assert_eq!(deltas[1].indices, vec![11, 12, 13]);
+    for target in &deltas {
+        assert_eq!(target.position_deltas.len(), target.len());
+        assert_eq!(target.normal_deltas.len(), target.len());
+        assert!(target
+            .normal_deltas
+            .iter()
+            .all(|&normal| normal == Vector3D::new(0.0, 0.0, 0.0)));
+    }
+
+    // Much less than a position for every vertex of every target.
+    let sparse: usize = deltas.iter().map(|target| target.len()).sum();
+    let dense = mesh.num_vertices() as usize * deltas.len();
+    assert!(sparse * 10 < dense);
+
+    // A large epsilon leaves out Ridge's smaller offsets.
+    let coarse = mesh.morph_target_deltas(0.75);
+    assert_eq!(coarse[0].indices, vec![12]);
+    assert!(coarse[1].is_empty());
+}
+
+#[test]
+fn test_apply_morphs() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/morph.gltf").unwrap();
+    let mesh = scene.mesh(0).unwrap();
+    let deltas = mesh.morph_target_deltas(1e-6);
+    let base: Vec<Vector3D> = mesh.positions().collect();
+
+    // A weight of 1 gives the positions of the target.
+    for (index, target) in mesh.anim_meshes().enumerate() {
+        let mut positions = base.clone();
+        apply_morphs(&deltas, &mut positions, &[(index, 1.0)]);
+        assert_eq!(positions.len(), target.num_vertices() as usize);
+        for (p, q) in positions.iter().zip(target.positions()) {
+            assert!((*p - q).length() < 1e-6);
+        }
+    }
+
+    // Weights scale the deltas, and targets add up.
+    let mut positions = base.clone();
+    apply_morphs(&deltas, &mut positions, &[(0, 0.5), (1, 1.0)]);
+    assert_eq!(positions[12].z, 1.0);
+    assert_eq!(positions[11].z, 0.5);
+    assert_eq!(positions[0], base[0]);
+
+    let mut positions = base.clone();
+    apply_morphs(&deltas, &mut positions, &[]);
+    assert_eq!(positions, base);
+}
@@ -0,0 +1,54 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::Importer;
+
+#[test]
+fn test_cstr_names_agree() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/unicode_names.gltf").unwrap();
+
+    let root = scene.root_node().unwrap();
+    for node in root.descendants() {
+        assert_eq!(node.name_cstr().to_str(), Ok(node.name()));
+        assert!(node.eq_name(node.name().as_bytes()));
+    }
+
+    let bone_node = scene.find_node("骨_2".as_bytes()).unwrap();
+    assert_eq!(bone_node.name(), "骨_2");
+    let by_str = root.descendants().find(|node| node.name() == "骨_2");
+    assert_eq!(
+        by_str.map(|node| node as *const _),
+        Some(bone_node as *const _)
+    );
+    // A prefix of the name, cut in the middle of a multibyte character, is a different name.
+    assert!(scene.find_node(&"骨_2".as_bytes()[..2]).is_none());
+
+    let mesh = scene.mesh(0).unwrap();
+    assert_eq!(mesh.name(), "Dreieck_ä");
+    assert!(mesh.eq_name("Dreieck_ä".as_bytes()));
+    assert!(!mesh.eq_name(b"Dreieck_a"));
+    for bone in mesh.bones() {
+        assert_eq!(bone.name_cstr().to_str(), Ok(bone.name()));
+    }
+    let index = mesh.bone_index_by_name("Knochen_α".as_bytes()).unwrap();
+    assert_eq!(mesh.bone(index).unwrap().name(), "Knochen_α");
+    assert_eq!(
+        Some(index as usize),
+        mesh.bones().position(|bone| bone.name() == "Knochen_α")
+    );
+    assert_eq!(mesh.bone_index_by_name(b"Knochen"), None);
+
+    let animation = scene.animations().next().unwrap();
+    assert_eq!(animation.name_cstr().to_str(), Ok("Animación"));
+    assert!(animation.eq_name("Animación".as_bytes()));
+    let channel = animation.channel_for("骨_2".as_bytes()).unwrap();
+    assert_eq!(channel.node_name(), "骨_2");
+    assert_eq!(channel.node_name_cstr().to_str(), Ok(channel.node_name()));
+    assert!(animation.channel_for("Knochen_α".as_bytes()).is_none());
+
+    let material = scene
+        .materials()
+        .find(|material| material.eq_name("Stahl_ö".as_bytes()))
+        .unwrap();
+    assert_eq!(material.name_cstr().to_str(), Ok("Stahl_ö"));
+}
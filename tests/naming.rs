@@ -0,0 +1,168 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::scene::naming::{
+    sanitize, sanitize_names, AllowedChars, Case, NameCategory, NamingRules,
+};
+use assimp::{Error, Importer};
+
+fn sanitized(names: &[(NameCategory, &str)], rules: NamingRules) -> Vec<String> {
+    sanitize_names(names.iter().cloned(), rules)
+        .unwrap()
+        .entries()
+        .iter()
+        .map(|item| item.sanitized.clone())
+        .collect()
+}
+
+#[test]
+fn test_sanitize_duplicates() {
+    let names = [
+        (NameCategory::Node, "Arm L"),
+        (NameCategory::Node, "Arm L"),
+        (NameCategory::Node, "Arm::L"),
+        (NameCategory::Node, "Arm_L_1"),
+        (NameCategory::Mesh, "Arm L"),
+    ];
+    let map = sanitize_names(names.iter().cloned(), NamingRules::default()).unwrap();
+    let ids: Vec<_> = map.entries().iter().map(|item| &*item.sanitized).collect();
+    assert_eq!(ids, ["Arm_L", "Arm_L_1", "Arm_L_2", "Arm_L_1_1", "Arm_L"]);
+
+    // Both directions.
+    assert_eq!(map.get(NameCategory::Node, 2), Some("Arm_L_2"));
+    assert_eq!(map.get(NameCategory::Mesh, 0), Some("Arm_L"));
+    assert_eq!(map.get(NameCategory::Mesh, 1), None);
+    assert_eq!(map.sanitized(NameCategory::Node, "Arm L"), Some("Arm_L"));
+    let item = map.original(NameCategory::Node, "Arm_L_1").unwrap();
+    assert_eq!((item.index, &*item.original), (1, "Arm L"));
+    assert_eq!(map.original(NameCategory::Material, "Arm_L"), None);
+
+    // The mesh can't reuse a node's identifier in a global namespace.
+    let rules = NamingRules {
+        global_namespace: true,
+        ..Default::default()
+    };
+    assert_eq!(sanitized(&names, rules)[4], "Arm_L_3");
+
+    // The result only depends on the names.
+    assert_eq!(
+        sanitize_names(names.iter().cloned(), NamingRules::default()).unwrap(),
+        map
+    );
+}
+
+#[test]
+fn test_sanitize_case() {
+    let names = [
+        (NameCategory::Node, "Hand"),
+        (NameCategory::Node, "hand"),
+        (NameCategory::Node, "HAND"),
+    ];
+    assert_eq!(
+        sanitized(&names, NamingRules::default()),
+        ["Hand", "hand", "HAND"]
+    );
+
+    let rules = NamingRules {
+        case_insensitive: true,
+        ..Default::default()
+    };
+    assert_eq!(sanitized(&names, rules), ["Hand", "hand_1", "HAND_2"]);
+
+    let rules = NamingRules {
+        case: Case::Lower,
+        ..Default::default()
+    };
+    assert_eq!(sanitized(&names, rules), ["hand", "hand_1", "hand_2"]);
+}
+
+#[test]
+fn test_sanitize_truncate() {
+    let long = "ÄÖÜäöüÄÖÜäöü";
+    let names = [
+        (NameCategory::Node, long),
+        (NameCategory::Node, long),
+        (NameCategory::Node, "骨骨骨骨"),
+    ];
+    let rules = |max_len| NamingRules {
+        allowed: AllowedChars::Alphanumeric(String::new()),
+        max_len,
+        ..Default::default()
+    };
+    assert_eq!(sanitized(&names, rules(10)), ["ÄÖÜäö", "ÄÖÜä_1", "骨骨骨"]);
+    assert_eq!(sanitized(&names, rules(9)), ["ÄÖÜä", "ÄÖÜ_1", "骨骨骨"]);
+
+    // With ASCII only, nothing is left of the name.
+    let names = [(NameCategory::Node, long), (NameCategory::Node, "")];
+    let rules = NamingRules {
+        replacement: None,
+        ..Default::default()
+    };
+    assert_eq!(sanitized(&names, rules), ["unnamed", "unnamed_1"]);
+}
+
+#[test]
+fn test_sanitize_suffix_too_long() {
+    let names = [
+        (NameCategory::Node, "Arm"),
+        (NameCategory::Node, "Arm"),
+        (NameCategory::Node, "Arm"),
+    ];
+    let rules = |max_len| NamingRules {
+        max_len,
+        ..Default::default()
+    };
+    // The suffix replaces the whole name.
+    assert_eq!(sanitized(&names, rules(2)), ["Ar", "_1", "_2"]);
+
+    // The suffix doesn't fit at all.
+    let result = sanitize_names(names.iter().cloned(), rules(1));
+    assert!(matches!(result, Err(Error::InvalidConfig { .. })));
+
+    // Every identifier stays within `max_len` until the suffixes run out.
+    let names = vec![(NameCategory::Node, "Arm"); 11];
+    assert!(sanitized(&names[..10], rules(2))
+        .iter()
+        .all(|id| id.len() <= 2));
+    let result = sanitize_names(names.iter().cloned(), rules(2));
+    assert!(matches!(result, Err(Error::InvalidConfig { .. })));
+}
+
+#[test]
+fn test_sanitize_scene() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/unicode_names.gltf").unwrap();
+
+    let map = sanitize(&scene, NamingRules::default()).unwrap();
+    let lookup = |category, name| map.sanitized(category, name).unwrap();
+    assert_eq!(lookup(NameCategory::Node, "Dreieck_ä"), "Dreieck_");
+    assert_eq!(lookup(NameCategory::Node, "骨_2"), "_2");
+    assert_eq!(lookup(NameCategory::Mesh, "Dreieck_ä"), "Dreieck_");
+    assert_eq!(lookup(NameCategory::Material, "Stahl_ö"), "Stahl_");
+    assert_eq!(lookup(NameCategory::Bone, "Knochen_α"), "Knochen_");
+    assert_eq!(lookup(NameCategory::Animation, "Animación"), "Animaci_n");
+    let nodes = map
+        .entries()
+        .iter()
+        .filter(|item| item.category == NameCategory::Node)
+        .count();
+    let root = scene.root_node().unwrap();
+    assert_eq!(nodes, root.descendants().count());
+
+    let rules = NamingRules {
+        global_namespace: true,
+        ..Default::default()
+    };
+    let map = sanitize(&scene, rules).unwrap();
+    assert_eq!(
+        map.sanitized(NameCategory::Node, "Dreieck_ä"),
+        Some("Dreieck_")
+    );
+    assert_eq!(
+        map.sanitized(NameCategory::Mesh, "Dreieck_ä"),
+        Some("Dreieck__1")
+    );
+    assert_eq!(
+        map.sanitized(NameCategory::Bone, "Knochen_α"),
+        Some("Knochen__1")
+    );
+}
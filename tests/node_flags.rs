@@ -0,0 +1,47 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::scene::flags::{register_flag_key, FlagTarget, NodeStandardFlags};
+use assimp::Importer;
+
+#[test]
+fn test_fbx_hidden_node() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/visibility.fbx").unwrap();
+    let root = scene.root_node().unwrap();
+
+    let hidden = root.find(b"Hidden").unwrap().standard_flags();
+    assert_eq!(hidden.visible, Some(false));
+    assert_eq!(hidden.casts_shadows, None);
+
+    let shown = root.find(b"Shown").unwrap().standard_flags();
+    assert_eq!(shown, NodeStandardFlags::default());
+    assert_eq!(root.standard_flags().visible, None);
+
+    let wall = root.find(b"Wall").unwrap().standard_flags();
+    assert_eq!(wall.visible, None);
+    assert_eq!(wall.casts_shadows, Some(false));
+    assert_eq!(wall.receives_shadows, None);
+    assert_eq!(wall.static_flag, None);
+}
+
+#[test]
+fn test_registered_flag_key() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/visibility.fbx").unwrap();
+    let root = scene.root_node().unwrap();
+    let wall = root.find(b"Wall").unwrap();
+    assert!(wall.standard_flags().custom.is_empty());
+
+    let occluder = FlagTarget::Custom("occluder");
+    assert_eq!(register_flag_key("my_engine:occluder", occluder), None);
+    assert_eq!(
+        register_flag_key("My_Engine:Occluder", occluder),
+        Some(occluder)
+    );
+
+    let flags = wall.standard_flags();
+    assert_eq!(flags.custom.get("occluder"), Some(&true));
+    assert_eq!(flags.casts_shadows, Some(false));
+    let hidden = root.find(b"Hidden").unwrap().standard_flags();
+    assert!(hidden.custom.is_empty());
+}
@@ -0,0 +1,129 @@
+#![cfg(feature = "image")]
+
+extern crate open_asset_importer as assimp;
+
+use assimp::scene::profile::{CanonicalSlot, ImportProfile, ProfileRule};
+use assimp::scene::{height_to_normal, WrappingMode};
+use assimp::{Importer, MaterialComponentType, Vector3D};
+use image::{DynamicImage, GrayImage, Luma, Rgba};
+
+const WIDTH: u32 = 16;
+
+/// Heights that rise by 16 levels per pixel to the right, and are the same down each column.
+fn gradient() -> GrayImage {
+    GrayImage::from_fn(WIDTH, 4, |x, _| Luma([(x * 16) as u8]))
+}
+
+fn decode(pixel: &Rgba<u8>) -> Vector3D {
+    let channel = |value: u8| value as f32 / 255.0 * 2.0 - 1.0;
+    Vector3D::new(channel(pixel[0]), channel(pixel[1]), channel(pixel[2]))
+}
+
+#[test]
+fn test_flat_normal_map() {
+    for &wrap in &[WrappingMode::Repeat, WrappingMode::Clamp] {
+        let normals = height_to_normal(&gradient(), 0.0, wrap);
+        assert_eq!(normals.dimensions(), (WIDTH, 4));
+        assert!(normals
+            .pixels()
+            .all(|&pixel| pixel == Rgba([128, 128, 255, 255])));
+    }
+}
+
+#[test]
+fn test_gradient_normals() {
+    // The height rises by 16 / 255 per pixel, so this makes the slope 1, and the normal
+    // leans 45 degrees away from the rise, towards -x.
+    let strength = 255.0 / 16.0;
+    let normals = height_to_normal(&gradient(), strength, WrappingMode::Clamp);
+    let expected = Vector3D::new(-1.0, 0.0, 1.0).normalize();
+    for x in 1..WIDTH - 1 {
+        for y in 0..4 {
+            let normal = decode(normals.get_pixel(x, y));
+            assert!((normal - expected).length() < 0.02, "{:?}", normal);
+        }
+    }
+
+    // Half the strength gives half the slope.
+    let normals = height_to_normal(&gradient(), strength / 2.0, WrappingMode::Clamp);
+    let normal = decode(normals.get_pixel(WIDTH / 2, 1));
+    let expected = Vector3D::new(-0.5, 0.0, 1.0).normalize();
+    assert!((normal - expected).length() < 0.02, "{:?}", normal);
+
+    // A gradient down the image makes the normal lean up it.
+    let rows = GrayImage::from_fn(4, WIDTH, |_, y| Luma([(y * 16) as u8]));
+    let normals = height_to_normal(&rows, strength, WrappingMode::Clamp);
+    let normal = decode(normals.get_pixel(1, WIDTH / 2));
+    let expected = Vector3D::new(0.0, 1.0, 1.0).normalize();
+    assert!((normal - expected).length() < 0.02, "{:?}", normal);
+}
+
+#[test]
+fn test_wrap_modes_at_the_border() {
+    let strength = 255.0 / 16.0;
+    let clamped = height_to_normal(&gradient(), strength, WrappingMode::Clamp);
+    let repeated = height_to_normal(&gradient(), strength, WrappingMode::Repeat);
+    let interior = clamped.get_pixel(WIDTH / 2, 0);
+    assert_eq!(repeated.get_pixel(WIDTH / 2, 0), interior);
+
+    // Clamping repeats the edge pixels, which halves the slope at the border.
+    let clamped_left = decode(clamped.get_pixel(0, 0));
+    assert!(clamped_left.x < 0.0);
+    assert!(clamped_left.x > decode(interior).x);
+
+    // Repeating puts the top of the gradient next to its bottom, a cliff on either side.
+    let repeated_left = decode(repeated.get_pixel(0, 0));
+    let repeated_right = decode(repeated.get_pixel(WIDTH - 1, 0));
+    assert!(repeated_left.x > 0.5);
+    assert!(repeated_right.x > 0.5);
+    assert!(decode(clamped.get_pixel(WIDTH - 1, 0)).x < 0.0);
+}
+
+#[test]
+fn test_resolved_height_map() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/profile.obj").unwrap();
+    let resolver = |_: &_| Some(DynamicImage::ImageLuma8(gradient()));
+
+    // The default profile takes Brick's bump map as a normal map as it is.
+    let resolved = scene.apply_import_profile(&ImportProfile::default());
+    let brick = resolved
+        .iter()
+        .find(|material| material.name == "Brick")
+        .unwrap();
+    let normal_map = brick.normal_map(resolver).unwrap();
+    assert!(!normal_map.converted_from_height);
+    assert_eq!(
+        normal_map.image,
+        resolver(&brick.normal.as_ref().unwrap().texture)
+            .unwrap()
+            .to_rgba8()
+    );
+
+    // This one says bump maps are height maps.
+    let profile = ImportProfile {
+        rules: vec![
+            ProfileRule::new(CanonicalSlot::Normal, MaterialComponentType::Height)
+                .with_height_map(2.0),
+        ],
+    };
+    let resolved = scene.apply_import_profile(&profile);
+    let plaster = resolved
+        .iter()
+        .find(|material| material.name == "Plaster")
+        .unwrap();
+    assert_eq!(plaster.normal.as_ref().unwrap().height_map, Some(2.0));
+    let normal_map = plaster.normal_map(resolver).unwrap();
+    assert!(normal_map.converted_from_height);
+    // OBJ textures repeat.
+    assert_eq!(
+        normal_map.image,
+        height_to_normal(&gradient(), 2.0, WrappingMode::Repeat)
+    );
+
+    let empty = scene.apply_import_profile(&ImportProfile::empty());
+    assert!(empty
+        .iter()
+        .all(|material| material.normal_map(resolver).is_none()));
+    assert!(plaster.normal_map(|_| None).is_none());
+}
@@ -0,0 +1,143 @@
+extern crate autogenerated_assimp_sys as ffi;
+extern crate open_asset_importer as assimp;
+
+use assimp::scene::{NullEntries, Ownership};
+use assimp::{Importer, Scene};
+use std::ptr::{self, NonNull};
+
+/// A scene built in memory with null entries that Assimp itself would never produce.
+struct RawScene {
+    scene: ffi::aiScene,
+    _meshes: Vec<Box<ffi::aiMesh>>,
+    _mesh_ptrs: Vec<*mut ffi::aiMesh>,
+    _root: Box<ffi::aiNode>,
+    _children: Vec<Box<ffi::aiNode>>,
+    _child_ptrs: Vec<*mut ffi::aiNode>,
+}
+
+impl RawScene {
+    /// Five meshes with 1 to 5 vertices, except that the third is null, and a root node with
+    /// two children, the second of which is null.
+    fn new() -> RawScene {
+        let mut meshes: Vec<Box<ffi::aiMesh>> = (1..=5)
+            .map(|num_vertices| {
+                let mut mesh: Box<ffi::aiMesh> = Box::new(unsafe { std::mem::zeroed() });
+                mesh.mNumVertices = num_vertices;
+                mesh
+            })
+            .collect();
+        let mut mesh_ptrs: Vec<*mut ffi::aiMesh> = meshes
+            .iter_mut()
+            .map(|mesh| &mut **mesh as *mut _)
+            .collect();
+        mesh_ptrs[2] = ptr::null_mut();
+
+        let mut root: Box<ffi::aiNode> = Box::new(unsafe { std::mem::zeroed() });
+        let mut children: Vec<Box<ffi::aiNode>> = vec![Box::new(unsafe { std::mem::zeroed() })];
+        let mut child_ptrs: Vec<*mut ffi::aiNode> = vec![&mut *children[0], ptr::null_mut()];
+        children[0].mParent = &mut *root;
+        root.mNumChildren = 2;
+        root.mChildren = child_ptrs.as_mut_ptr();
+
+        let mut scene: ffi::aiScene = unsafe { std::mem::zeroed() };
+        scene.mNumMeshes = 5;
+        scene.mMeshes = mesh_ptrs.as_mut_ptr();
+        scene.mRootNode = &mut *root;
+
+        RawScene {
+            scene,
+            _meshes: meshes,
+            _mesh_ptrs: mesh_ptrs,
+            _root: root,
+            _children: children,
+            _child_ptrs: child_ptrs,
+        }
+    }
+
+    fn scene(&self) -> Scene {
+        unsafe { Scene::from_raw(NonNull::from(&self.scene), Ownership::Borrowed) }
+    }
+}
+
+#[test]
+fn test_iterator_skips_null_entries() {
+    let raw = RawScene::new();
+    let scene = raw.scene();
+
+    let vertices: Vec<u32> = scene.meshes().map(|mesh| mesh.num_vertices()).collect();
+    assert_eq!(vertices, vec![1, 2, 4, 5]);
+
+    assert!(scene.mesh(2).is_none());
+    assert_eq!(scene.mesh(3).unwrap().num_vertices(), 4);
+
+    let root = scene.root_node().unwrap();
+    assert_eq!(root.children().count(), 1);
+    assert_eq!(root.descendants().count(), 2);
+}
+
+#[test]
+fn test_len_matches_yielded_count() {
+    let raw = RawScene::new();
+    let scene = raw.scene();
+
+    let mut meshes = scene.meshes();
+    assert_eq!(meshes.len(), 4);
+    assert_eq!(meshes.size_hint(), (4, Some(4)));
+
+    let mut remaining = 4;
+    while meshes.next().is_some() {
+        remaining -= 1;
+        assert_eq!(meshes.len(), remaining);
+        assert_eq!(meshes.size_hint(), (remaining, Some(remaining)));
+    }
+    assert_eq!(remaining, 0);
+    assert_eq!(meshes.len(), 0);
+}
+
+#[test]
+fn test_strict_iteration() {
+    let raw = RawScene::new();
+    let scene = raw.scene();
+
+    assert_eq!(scene.meshes().null_indices(), vec![2]);
+    assert_eq!(scene.meshes().strict().unwrap_err(), vec![2]);
+
+    // Indices are relative to the next entry.
+    let mut meshes = scene.meshes();
+    meshes.next();
+    assert_eq!(meshes.null_indices(), vec![1]);
+    meshes.next();
+    assert!(meshes.null_indices().is_empty());
+    assert_eq!(meshes.strict().unwrap().len(), 2);
+
+    assert!(scene.materials().strict().unwrap().is_empty());
+}
+
+#[test]
+fn test_scene_null_entries() {
+    let raw = RawScene::new();
+    let scene = raw.scene();
+
+    let found = scene.null_entries();
+    assert_eq!(
+        found,
+        vec![
+            NullEntries {
+                array: "meshes".to_owned(),
+                indices: vec![2],
+            },
+            NullEntries {
+                array: "children of node \"\"".to_owned(),
+                indices: vec![1],
+            },
+        ]
+    );
+    assert_eq!(
+        found[0].to_string(),
+        "null entries in meshes at indices [2]"
+    );
+
+    let importer = Importer::new();
+    let imported = importer.read_file("examples/rigged.dae").unwrap();
+    assert!(imported.null_entries().is_empty());
+}
@@ -0,0 +1,91 @@
+extern crate open_asset_importer as assimp;
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use assimp::io::{IoEvent, MemoryFileIO, ObservedFileIO};
+use assimp::{FileIO, Importer};
+
+/// An owned copy of an `IoEvent`, with the path reduced to the file name.
+#[derive(Debug, Clone, PartialEq)]
+enum Recorded {
+    Opened(String),
+    Read(String, usize, u64),
+    Closed(String),
+}
+
+fn file_name(path: &str) -> String {
+    path.rsplit(&['/', '\\'][..]).next().unwrap().to_owned()
+}
+
+const OBJ: &[u8] = include_bytes!("../examples/profile.obj");
+const MTL: &[u8] = include_bytes!("../examples/profile.mtl");
+const TEXTURE: &[u8] = b"not really a JPEG, but as good as one here";
+
+#[test]
+fn test_observed_import() {
+    let files = MemoryFileIO::new()
+        .with_file("profile.obj", OBJ)
+        .with_file("profile.mtl", MTL)
+        .with_file("brick_albedo.jpg", TEXTURE);
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let recorder = events.clone();
+    let io = ObservedFileIO::new(files.clone(), move |event: IoEvent| {
+        let recorded = match event {
+            IoEvent::FileOpened { path, mode } => {
+                assert!(mode.contains('r'));
+                Recorded::Opened(file_name(path))
+            }
+            IoEvent::FileRead {
+                path,
+                bytes,
+                offset,
+            } => Recorded::Read(file_name(path), bytes, offset),
+            IoEvent::FileClosed { path } => Recorded::Closed(file_name(path)),
+        };
+        recorder.lock().unwrap().push(recorded);
+    });
+
+    let importer = Importer::new();
+    let scene = importer.read_file_with_io("profile.obj", &io).unwrap();
+    assert!(scene.num_materials() >= 2);
+
+    // Resolve a texture through the same IO, as the application would.
+    let mut texture = io.open("brick_albedo.jpg", "rb").unwrap();
+    let mut buf = vec![0; 16];
+    while texture.read(&mut buf).unwrap() > 0 {}
+    texture.close();
+
+    // Opens and closes balance, and the reads of each file are contiguous. Every file is read
+    // in full at least once, whatever else the importer does with it.
+    let events = events.lock().unwrap();
+    let mut open: HashMap<String, u64> = HashMap::new();
+    let mut read_in_full: Vec<String> = Vec::new();
+    for event in events.iter() {
+        match event {
+            Recorded::Opened(name) => {
+                assert!(files.get(name).is_some(), "{}", name);
+                assert!(open.insert(name.clone(), 0).is_none());
+            }
+            Recorded::Read(name, bytes, offset) => {
+                let next = open.get_mut(name).unwrap();
+                assert_eq!(*offset, *next);
+                *next += *bytes as u64;
+                assert!(*next <= files.get(name).unwrap().len() as u64);
+            }
+            Recorded::Closed(name) => {
+                let bytes = open.remove(name).unwrap();
+                if bytes == files.get(name).unwrap().len() as u64 {
+                    read_in_full.push(name.clone());
+                }
+            }
+        }
+    }
+    assert!(open.is_empty());
+    read_in_full.sort();
+    read_in_full.dedup();
+    assert_eq!(
+        read_in_full,
+        ["brick_albedo.jpg", "profile.mtl", "profile.obj"]
+    );
+}
@@ -0,0 +1,148 @@
+extern crate autogenerated_assimp_sys as ffi;
+extern crate open_asset_importer as assimp;
+
+use assimp::fixtures;
+use assimp::scene::Ownership;
+use assimp::{Importer, Scene};
+use std::ffi::CString;
+use std::ptr::NonNull;
+
+/// Import the unit cube using the C API directly, as other code linking to Assimp would.
+fn import_raw() -> NonNull<ffi::aiScene> {
+    let data = fixtures::UNIT_CUBE_OBJ;
+    let hint = CString::new("obj").unwrap();
+    let raw = unsafe {
+        ffi::aiImportFileFromMemory(
+            data.as_ptr() as *const _,
+            data.len() as u32,
+            0,
+            hint.as_ptr(),
+        )
+    };
+    NonNull::new(raw as *mut _).unwrap()
+}
+
+#[test]
+fn test_into_raw_then_release() {
+    let importer = Importer::new();
+    let scene = fixtures::unit_cube_with(&importer);
+    assert_eq!(scene.ownership(), Ownership::Owned);
+
+    let raw = scene.into_raw();
+    assert!(!raw.is_null());
+    assert!(!fixtures::is_owned_scene(raw));
+
+    unsafe { ffi::aiReleaseImport(raw) };
+}
+
+#[test]
+fn test_into_raw_then_from_raw() {
+    let importer = Importer::new();
+    let raw = fixtures::unit_cube_with(&importer).into_raw();
+
+    let scene = unsafe { Scene::from_raw(NonNull::new(raw as *mut _).unwrap(), Ownership::Owned) };
+    if cfg!(debug_assertions) {
+        assert!(fixtures::is_owned_scene(raw));
+    }
+    assert_eq!(scene.num_meshes(), 1);
+
+    drop(scene);
+    assert!(!fixtures::is_owned_scene(raw));
+}
+
+#[test]
+fn test_from_raw_borrowed() {
+    let raw = import_raw();
+
+    {
+        let scene = unsafe { Scene::from_raw(raw, Ownership::Borrowed) };
+        assert_eq!(scene.ownership(), Ownership::Borrowed);
+        assert!(!fixtures::is_owned_scene(raw.as_ptr()));
+
+        let mesh = scene.mesh(0).unwrap();
+        assert_eq!(mesh.num_vertices(), 24);
+        assert_eq!(mesh.faces().count(), 6);
+        assert!(mesh
+            .vertices()
+            .all(|v| v.x.abs() == 0.5 && v.y.abs() == 0.5 && v.z.abs() == 0.5));
+    }
+
+    // Dropping the borrowed scene didn't free it.
+    let still_there = unsafe { Scene::from_raw(raw, Ownership::Borrowed) };
+    assert_eq!(still_there.num_meshes(), 1);
+    drop(still_there);
+
+    unsafe { ffi::aiReleaseImport(raw.as_ptr()) };
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "already owned")]
+fn test_owned_twice_panics() {
+    let importer = Importer::new();
+    let scene = fixtures::unit_cube_with(&importer);
+    let raw = NonNull::new(&*scene as *const _ as *mut _).unwrap();
+
+    let _second = unsafe { Scene::from_raw(raw, Ownership::Owned) };
+}
+
+#[test]
+fn test_try_clone_is_independent() {
+    let mut importer = Importer::new();
+    let original = fixtures::unit_cube_with(&importer);
+    let copy = original.try_clone().unwrap();
+    assert!(copy.is_copy());
+    assert!(!original.is_copy());
+    assert_eq!(copy.ownership(), Ownership::Owned);
+    if cfg!(debug_assertions) {
+        assert!(fixtures::is_owned_scene(&*copy));
+    }
+
+    let positions: Vec<_> = original.mesh(0).unwrap().positions().collect();
+    let indices = original.mesh(0).unwrap().indices_u32();
+    assert_ne!(&*copy as *const ffi::aiScene, &*original as *const _);
+    assert_ne!(
+        copy.mesh(0).unwrap().mVertices,
+        original.mesh(0).unwrap().mVertices
+    );
+
+    // Post-process the imported scene destructively, keeping the copy as the original.
+    importer.make_left_handed(true);
+    let processed = importer.apply_postprocessing(original).unwrap();
+    let processed_positions: Vec<_> = processed.mesh(0).unwrap().positions().collect();
+    assert_ne!(processed_positions, positions);
+
+    let copy_mesh = copy.mesh(0).unwrap();
+    assert_eq!(copy_mesh.positions().collect::<Vec<_>>(), positions);
+    assert_eq!(copy_mesh.indices_u32(), indices);
+
+    drop(processed);
+    drop(copy);
+}
+
+#[test]
+fn test_clone_of_borrowed_scene_is_owned() {
+    let raw = import_raw();
+
+    let borrowed = unsafe { Scene::from_raw(raw, Ownership::Borrowed) };
+    let copy = borrowed.clone();
+    assert_eq!(copy.ownership(), Ownership::Owned);
+    assert_eq!(copy.num_meshes(), borrowed.num_meshes());
+    drop(borrowed);
+
+    // The copy outlives the scene it was copied from.
+    unsafe { ffi::aiReleaseImport(raw.as_ptr()) };
+    assert_eq!(copy.mesh(0).unwrap().num_vertices(), 24);
+}
+
+#[test]
+fn test_postprocessing_a_copy_fails() {
+    let importer = Importer::new();
+    let original = fixtures::unit_cube_with(&importer);
+    let copy = original.try_clone().unwrap();
+    let raw = &*copy as *const ffi::aiScene;
+
+    assert!(importer.apply_postprocessing(copy).is_err());
+    assert!(!fixtures::is_owned_scene(raw));
+    assert_eq!(original.num_meshes(), 1);
+}
@@ -0,0 +1,96 @@
+extern crate autogenerated_assimp_sys as ffi;
+extern crate open_asset_importer as assimp;
+
+use assimp::{Importer, Light, Vector3D};
+use std::ptr::NonNull;
+
+fn close(a: Vector3D, b: Vector3D) -> bool {
+    (a - b).length() < 1e-5
+}
+
+#[test]
+fn test_resolve_nested_light() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/nested_light.dae").unwrap();
+    let light = scene.light().find(|light| light.name() == "Bulb").unwrap();
+
+    let (node, world) = scene.resolve_light(light).unwrap();
+    assert_eq!(node.name(), "Bulb");
+    assert_eq!(node.parent().unwrap().name(), "Inner");
+
+    // Outer translates by (1, 0, 0), Inner by (0, 2, 0) after rotating 90 degrees around Z, and
+    // Bulb by (1, 0, 0).
+    let position = world.transform_point(light.position());
+    assert!(close(position, Vector3D::new(1.0, 3.0, 0.0)));
+    assert_eq!(world, node.world_transform());
+}
+
+#[test]
+fn test_resolve_camera() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/nested_light.dae").unwrap();
+    let camera = scene.camera().next().unwrap();
+
+    let (node, world) = scene.resolve_camera(camera).unwrap();
+    assert_eq!(node.name(), "Eye");
+    let position = world.transform_point(camera.position());
+    assert!(close(position, Vector3D::new(1.0, 0.0, 5.0)));
+    assert_eq!(scene.resolve_camera_all(camera).len(), 1);
+}
+
+#[test]
+fn test_duplicate_names() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/nested_light.dae").unwrap();
+    let lamp = scene.light().find(|light| light.name() == "Lamp").unwrap();
+
+    let all = scene.resolve_light_all(lamp);
+    assert_eq!(all.len(), 2);
+    assert!(close(
+        all[0].1.transform_point(lamp.position()),
+        Vector3D::new(-4.0, 0.0, 0.0)
+    ));
+    assert!(close(
+        all[1].1.transform_point(lamp.position()),
+        Vector3D::new(4.0, 0.0, 0.0)
+    ));
+
+    let (first, _) = scene.resolve_light(lamp).unwrap();
+    assert!(std::ptr::eq(first, all[0].0));
+    assert!(std::ptr::eq(scene.find_node(b"Lamp").unwrap(), first));
+}
+
+#[test]
+fn test_placed_lights_and_cameras() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/nested_light.dae").unwrap();
+
+    let lights = scene.placed_lights();
+    assert_eq!(lights.len(), scene.num_lights() as usize);
+    for placed in &lights {
+        assert_eq!(placed.node.name(), placed.item.name());
+    }
+
+    let cameras = scene.placed_cameras();
+    assert_eq!(cameras.len(), 1);
+    assert_eq!(cameras[0].node.name(), "Eye");
+}
+
+#[test]
+fn test_missing_node() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/nested_light.dae").unwrap();
+
+    let mut raw: ffi::aiLight = unsafe { std::mem::zeroed() };
+    raw.mName.length = 7;
+    for (dst, &src) in raw.mName.data.iter_mut().zip(b"Nowhere") {
+        *dst = src as _;
+    }
+    let light = unsafe { Light::from_raw(NonNull::from(&raw)) };
+
+    assert!(scene.resolve_light(light).is_none());
+    assert!(scene.resolve_light_all(light).is_empty());
+    assert!(scene.find_node(b"Nowhere").is_none());
+    assert!(scene.root_node().unwrap().find(b"Outer").is_some());
+    assert!(scene.root_node().unwrap().find_all(b"Lamp").count() == 2);
+}
@@ -0,0 +1,88 @@
+extern crate autogenerated_assimp_sys as ffi;
+extern crate open_asset_importer as assimp;
+
+use std::ptr::NonNull;
+
+use assimp::fixtures;
+use assimp::math::{Color4D, Color4DIter, Vector3D, Vector3DIter};
+use assimp::Importer;
+
+#[test]
+fn test_positions_as_slice() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/spider.obj").unwrap();
+
+    for mesh in scene.meshes() {
+        let slice = mesh.positions().as_slice().unwrap();
+        let collected: Vec<Vector3D> = mesh.positions().collect();
+        assert_eq!(slice, &collected[..]);
+    }
+}
+
+#[test]
+fn test_as_slice_after_next() {
+    let importer = Importer::new();
+    let scene = fixtures::unit_cube_with(&importer);
+    let mesh = scene.mesh(0).unwrap();
+
+    let mut positions = mesh.positions();
+    let first = positions.next().unwrap();
+    let slice = positions.as_slice().unwrap();
+
+    assert_eq!(first, mesh.position(0).unwrap());
+    assert_eq!(slice.len(), mesh.num_vertices() as usize - 1);
+    assert_eq!(slice[0], mesh.position(1).unwrap());
+}
+
+#[test]
+fn test_missing_array() {
+    let importer = Importer::new();
+    let scene = fixtures::unit_cube_with(&importer);
+    let mesh = scene.mesh(0).unwrap();
+
+    assert!(mesh.vertex_colors(0).as_slice().is_none());
+    assert_eq!(mesh.vertex_colors(0).chunks8().count(), 0);
+    assert!(mesh.vertex_colors(0).chunks8().remainder().is_empty());
+}
+
+#[test]
+fn test_chunks8() {
+    let points: Vec<Vector3D> = (0..19).map(|i| Vector3D::new(i as f32, 0.0, 0.0)).collect();
+    let iter = unsafe {
+        Vector3DIter::new(
+            NonNull::new(points.as_ptr() as *mut ffi::aiVector3D),
+            points.len(),
+        )
+    };
+
+    let chunks = iter.chunks8();
+    assert_eq!(chunks.len(), 2);
+    assert_eq!(chunks.remainder(), &points[16..]);
+
+    let flattened: Vec<Vector3D> = chunks
+        .flat_map(|chunk| chunk.iter().cloned())
+        .chain(iter.chunks8().remainder().iter().cloned())
+        .collect();
+    assert_eq!(flattened, points);
+    assert_eq!(flattened, iter.collect::<Vec<_>>());
+}
+
+#[test]
+fn test_color_chunks8() {
+    let colors: Vec<Color4D> = (0..8)
+        .map(|i| Color4D::new(i as f32, 0.5, 0.25, 1.0))
+        .collect();
+    let iter = unsafe {
+        Color4DIter::new(
+            NonNull::new(colors.as_ptr() as *mut ffi::aiColor4D),
+            colors.len(),
+        )
+    };
+
+    assert_eq!(iter.as_slice().unwrap(), &colors[..]);
+
+    let mut chunks = iter.chunks8();
+    assert_eq!(&chunks.next().unwrap()[..], &colors[..]);
+    assert!(chunks.next().is_none());
+    assert!(chunks.remainder().is_empty());
+}
@@ -0,0 +1,59 @@
+//! Downstream code imports these paths, so most of this test only has to compile to catch a
+//! refactor that breaks one of them.
+
+extern crate open_asset_importer as assimp;
+
+use assimp::prelude::*;
+
+#[test]
+fn test_stable_paths() {
+    let _: Option<&Importer> = None;
+    let _: Option<&Scene> = None;
+    let _: Option<&Mesh> = None;
+    let _: Option<&Node> = None;
+    let _: Option<&Material> = None;
+    let _: Option<MaterialComponentType> = None;
+    let _: Option<&TextureDefinition> = None;
+    let _: Option<&Animation> = None;
+    let _: Option<&NodeAnim> = None;
+    let _: Option<Color3D> = None;
+    let _: Option<Color4D> = None;
+    let _: Option<Matrix3x3> = None;
+    let _: Option<Matrix4x4> = None;
+    let _: Option<Quaternion> = None;
+    let _: Option<Vector2D> = None;
+    let _: Option<Vector3D> = None;
+    let _: Option<PrimitiveType> = None;
+    let _: Option<PrimitiveTypes> = None;
+    let _: Option<&LogStream> = None;
+    let _: Option<&dyn FileIO> = None;
+    let _: Option<&dyn File> = None;
+
+    let _: Option<assimp::Error> = None;
+    let _: Option<assimp::InlineString> = None;
+    let _: Option<assimp::MaterialComponentType> = None;
+    let _: Option<assimp::TextureDefinition> = None;
+    let _: Option<assimp::PrimitiveTypes> = None;
+    let _: Option<assimp::math::Vector3DIter> = None;
+    let _: Option<assimp::math::Color4DIter> = None;
+    let _: Option<assimp::math::Chunks8<f32>> = None;
+    let _: Option<assimp::import::structs::PostProcessSteps> = None;
+    let _: Option<assimp::io::SeekFrom> = None;
+    let _: Option<assimp::log::LogLevel> = None;
+    let _: Option<assimp::scene::MaterialRequirementReport> = None;
+}
+
+#[test]
+fn test_prelude() {
+    let importer = Importer::new();
+    let scene: Scene = importer.read_file("examples/profile.obj").unwrap();
+    let mesh: &Mesh = scene.meshes().next().unwrap();
+    assert!(mesh.primitive_types().contains(PrimitiveTypes::POLYGON));
+    let material: &Material = scene.materials().nth(mesh.material_id() as usize).unwrap();
+    let texture: Option<TextureDefinition> = material.texture(MaterialComponentType::Diffuse, 0);
+    assert!(texture.is_some());
+    let root: &Node = scene.root_node().unwrap();
+    assert_eq!(root.num_children(), 1);
+    let origin: Vector3D = mesh.positions().next().copied().unwrap();
+    assert_eq!(origin, Vector3D::new(0.0, 0.0, 0.0));
+}
@@ -0,0 +1,77 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::import::structs::{ComponentTypes, ImportProperty, PostProcessSteps};
+use assimp::import::SceneProbe;
+use assimp::{Importer, Vector3D};
+
+fn assert_probe_matches_import(file: &str) {
+    let full = Importer::new().read_file(file).unwrap();
+    let expected = SceneProbe::from_scene(&full);
+    let probe = Importer::new().probe(file).unwrap();
+
+    assert_eq!(probe, expected);
+    assert_eq!(probe.num_meshes, full.num_meshes());
+    assert_eq!(probe.mesh_bounds.len(), full.num_meshes() as usize);
+}
+
+#[test]
+fn test_probe_matches_full_import() {
+    assert_probe_matches_import("examples/spider.obj");
+    assert_probe_matches_import("examples/rigged.gltf");
+}
+
+#[test]
+fn test_probe_unit_cube() {
+    let probe = Importer::new().probe("examples/unit_cube.obj").unwrap();
+    assert_eq!(probe.num_meshes, 1);
+    assert_eq!(probe.num_faces, 6);
+    assert_eq!(probe.up_axis, None);
+
+    let bounds = probe.mesh_bounds[0].unwrap();
+    assert_eq!(bounds.min, Vector3D::new(-0.5, -0.5, -0.5));
+    assert_eq!(bounds.max, Vector3D::new(0.5, 0.5, 0.5));
+}
+
+#[test]
+fn test_probe_ignores_importer_steps() {
+    let mut importer = Importer::new();
+    importer.triangulate(true);
+    let probe = importer.probe("examples/unit_cube.obj").unwrap();
+    assert_eq!(probe.num_faces, 6);
+    // The importer itself is unchanged.
+    assert_eq!(importer.effective_flags(), PostProcessSteps::TRIANGULATE);
+}
+
+#[test]
+fn test_probe_missing_file() {
+    assert!(Importer::new()
+        .probe("examples/non_existent_file.obj")
+        .is_err());
+}
+
+#[test]
+fn test_metadata_only() {
+    let mut importer = Importer::new();
+    importer.triangulate(true);
+    importer.metadata_only();
+    assert_eq!(
+        importer.effective_flags(),
+        PostProcessSteps::REMOVE_COMPONENT
+    );
+
+    let removed = ComponentTypes::ANIMATIONS
+        | ComponentTypes::TEXTURES
+        | ComponentTypes::MATERIALS
+        | ComponentTypes::LIGHTS
+        | ComponentTypes::CAMERAS
+        | ComponentTypes::BONE_WEIGHTS
+        | ComponentTypes::COLORS;
+    assert_eq!(
+        importer.property("PP_RVC_FLAGS"),
+        Some(&ImportProperty::Int(removed.bits() as i32))
+    );
+
+    let scene = importer.read_file("examples/rigged.gltf").unwrap();
+    assert_eq!(scene.num_animations(), 0);
+    assert!(scene.meshes().all(|mesh| mesh.num_bones() == 0));
+}
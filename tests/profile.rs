@@ -0,0 +1,117 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::scene::profile::{
+    CanonicalSlot, ColorChannel, ImportProfile, ProfileRule, ResolvedMaterial, TextureCondition,
+};
+use assimp::{Importer, MaterialComponentType};
+
+fn resolve(path: &str, profile: &ImportProfile) -> Vec<ResolvedMaterial> {
+    let importer = Importer::new();
+    let scene = importer.read_file(path).unwrap();
+    let resolved = scene.apply_import_profile(profile);
+    assert_eq!(resolved.len(), scene.num_materials() as usize);
+    resolved
+}
+
+fn find<'a>(resolved: &'a [ResolvedMaterial], name: &str) -> &'a ResolvedMaterial {
+    resolved
+        .iter()
+        .find(|material| material.name == name)
+        .unwrap()
+}
+
+#[test]
+fn test_obj_bump_map_as_normal_map() {
+    let resolved = resolve("examples/profile.obj", &ImportProfile::default());
+
+    let brick = find(&resolved, "Brick");
+    let normal = brick.normal.as_ref().unwrap();
+    assert_eq!(normal.source, MaterialComponentType::Height);
+    assert_eq!(normal.path, "brick_normal.png");
+    assert_eq!(brick.albedo.as_ref().unwrap().path, "brick_albedo.jpg");
+    assert_eq!(brick.slot(CanonicalSlot::Normal), Some(normal));
+    let base_color: [f32; 4] = brick.base_color.unwrap().into();
+    assert_eq!(base_color, [0.8, 0.4, 0.3, 1.0]);
+
+    // A TGA bump map without "normal" in its name is left alone.
+    let plaster = find(&resolved, "Plaster");
+    assert_eq!(plaster.normal, None);
+    assert_eq!(plaster.albedo, None);
+    assert_eq!(plaster.opacity_factor, Some(0.5));
+}
+
+#[test]
+fn test_fbx_normal_map() {
+    let resolved = resolve("examples/profile.fbx", &ImportProfile::default());
+    let material = resolved
+        .iter()
+        .find(|material| material.normal.is_some())
+        .unwrap();
+    let normal = material.normal.as_ref().unwrap();
+    assert_eq!(normal.source, MaterialComponentType::Normals);
+    assert!(normal.path.ends_with("metal_nrm.tga"));
+    assert!(material
+        .albedo
+        .as_ref()
+        .unwrap()
+        .path
+        .ends_with("metal_albedo.png"));
+}
+
+#[test]
+fn test_gltf_normal_map() {
+    let resolved = resolve("examples/profile.gltf", &ImportProfile::default());
+    let brick = find(&resolved, "Brick");
+    let normal = brick.normal.as_ref().unwrap();
+    assert_eq!(normal.source, MaterialComponentType::Normals);
+    assert_eq!(normal.path, "brick_nrm.png");
+    assert_eq!(brick.albedo.as_ref().unwrap().path, "brick_albedo.png");
+
+    // One texture packs metalness and roughness.
+    let metallic = brick.metallic.as_ref().unwrap();
+    let roughness = brick.roughness.as_ref().unwrap();
+    assert_eq!(metallic.path, "brick_mr.png");
+    assert_eq!(roughness.path, "brick_mr.png");
+    assert_eq!(metallic.color_channel, Some(ColorChannel::B));
+    assert_eq!(roughness.color_channel, Some(ColorChannel::G));
+    assert_eq!(brick.occlusion.as_ref().unwrap().path, "brick_ao.png");
+    assert_eq!(brick.metallic_factor, Some(1.0));
+    assert_eq!(brick.roughness_factor, Some(0.5));
+}
+
+#[test]
+fn test_custom_profile() {
+    // Only take bump maps that say they're normal maps, and nothing else.
+    let profile = ImportProfile {
+        rules: vec![
+            ProfileRule::new(CanonicalSlot::Normal, MaterialComponentType::Height)
+                .with_condition(TextureCondition::PathContains("NORMAL".to_owned())),
+        ],
+    };
+    let resolved = resolve("examples/profile.obj", &profile);
+    assert!(find(&resolved, "Brick").normal.is_some());
+    assert!(resolved.iter().all(|material| material.albedo.is_none()));
+
+    let resolved = resolve("examples/profile.obj", &ImportProfile::empty());
+    for material in &resolved {
+        assert!(CanonicalSlot::ALL
+            .iter()
+            .all(|&slot| material.slot(slot).is_none()));
+    }
+}
+
+#[test]
+fn test_texture_conditions() {
+    let png = TextureCondition::Extension(".PNG".to_owned());
+    assert!(png.matches("textures/wall.png"));
+    assert!(!png.matches("textures.png/wall"));
+    assert!(!png.matches("wall"));
+
+    let condition = TextureCondition::AllOf(vec![
+        TextureCondition::PathContains("wall".to_owned()),
+        TextureCondition::Extension("tga".to_owned()),
+    ]);
+    assert!(condition.matches("C:\\art\\Wall_n.tga"));
+    assert!(!condition.matches("wall_n.png"));
+    assert!(TextureCondition::Any.matches(""));
+}
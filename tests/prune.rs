@@ -0,0 +1,130 @@
+extern crate open_asset_importer as assimp;
+
+use std::mem;
+
+use assimp::fixtures;
+use assimp::scene::prune::{prune, PruneFilter, PruneReport};
+use assimp::scene::{MaterialComponentType, SceneSnapshot, TextureTarget};
+use assimp::Importer;
+
+#[test]
+fn test_prune_texture_data() {
+    let importer = Importer::new();
+    let scene = fixtures::embedded_texture_glb_with(&importer);
+    let original = scene.snapshot();
+
+    assert_eq!(original.textures.len(), 1);
+    let size = original.textures[0].data.as_ref().unwrap().len();
+    assert_eq!(size, 76);
+    let reference = original.materials[0]
+        .textures
+        .iter()
+        .find(|texture| texture.component == MaterialComponentType::Diffuse)
+        .unwrap();
+    assert_eq!(reference.path, "*0");
+    assert_eq!(reference.target, TextureTarget::Embedded(0));
+
+    let mut pruned = original.clone();
+    let filter = PruneFilter {
+        texture_data: true,
+        ..Default::default()
+    };
+    let report = prune(&mut pruned, filter);
+    assert_eq!(
+        report,
+        PruneReport {
+            texture_data: size as u64,
+            ..Default::default()
+        }
+    );
+    assert_eq!(report.total(), size as u64);
+
+    // The metadata of the texture is kept, and references to it are marked.
+    let texture = &pruned.textures[0];
+    assert_eq!(texture.data, None);
+    assert_eq!(texture.format_hint, original.textures[0].format_hint);
+    assert_eq!(texture.filename, original.textures[0].filename);
+    for (before, after) in original.materials[0]
+        .textures
+        .iter()
+        .zip(&pruned.materials[0].textures)
+    {
+        assert_eq!(after.path, before.path);
+        assert_eq!(after.target, TextureTarget::Missing(0));
+    }
+
+    // Nothing else changed.
+    let restored = SceneSnapshot {
+        textures: original.textures.clone(),
+        materials: original.materials.clone(),
+        ..pruned.clone()
+    };
+    assert_eq!(restored, original);
+
+    // Pruning again finds nothing left to drop.
+    let filter = PruneFilter {
+        texture_data: true,
+        ..Default::default()
+    };
+    assert_eq!(prune(&mut pruned, filter).total(), 0);
+}
+
+#[test]
+fn test_prune_vertex_data() {
+    let mut importer = Importer::new();
+    importer.calc_tangent_space(|args| args.enable = true);
+    let scene = importer.read_file("examples/handedness.gltf").unwrap();
+    let original = scene.snapshot();
+    let mesh = &original.meshes[0];
+    assert!(mesh.texture_coords[0].is_some());
+    assert!(!mesh.tangents.is_empty());
+    let keys: usize = original.animations[0]
+        .channels
+        .iter()
+        .map(|channel| {
+            mem::size_of_val(&channel.position_keys[..])
+                + mem::size_of_val(&channel.rotation_keys[..])
+                + mem::size_of_val(&channel.scaling_keys[..])
+        })
+        .sum();
+
+    // Keeping the first UV channel and a matching animation drops nothing.
+    let mut pruned = original.clone();
+    let filter = PruneFilter {
+        keep_animations: Some(vec!["Spin".to_owned()]),
+        max_uv_channels: Some(1),
+        ..Default::default()
+    };
+    assert_eq!(prune(&mut pruned, filter).total(), 0);
+    assert_eq!(pruned, original);
+
+    let filter = PruneFilter {
+        keep_animations: Some(vec!["Walk".to_owned()]),
+        max_uv_channels: Some(0),
+        colors: true,
+        tangents: true,
+        ..Default::default()
+    };
+    let report = prune(&mut pruned, filter);
+    let num_vertices = mesh.positions.len();
+    assert_eq!(
+        report,
+        PruneReport {
+            texture_data: 0,
+            animations: keys as u64,
+            uv_channels: (num_vertices * 12) as u64,
+            colors: 0,
+            tangents: (num_vertices * 24) as u64,
+        }
+    );
+
+    assert!(pruned.animations.is_empty());
+    let pruned_mesh = &pruned.meshes[0];
+    assert!(pruned_mesh.texture_coords.iter().all(Option::is_none));
+    assert_eq!(pruned_mesh.texture_coords.len(), mesh.texture_coords.len());
+    assert!(pruned_mesh.tangents.is_empty() && pruned_mesh.bitangents.is_empty());
+    assert_eq!(pruned_mesh.positions, mesh.positions);
+    assert_eq!(pruned_mesh.normals, mesh.normals);
+    assert_eq!(pruned_mesh.faces, mesh.faces);
+    assert_eq!(pruned.root, original.root);
+}
@@ -0,0 +1,228 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::fixtures;
+use assimp::import::Aabb;
+use assimp::scene::quantize::{
+    decode_octahedral_normals, encode_octahedral_normals, quantize_positions, quantize_uvs,
+    DequantizeTransform, UvRange, MAX_OCTAHEDRAL_ERROR_DEGREES,
+};
+use assimp::scene::vertex_buffer::{build_formatted, Attribute, BufferError, Format};
+use assimp::{Importer, Vector3D};
+
+/// A tiny deterministic PRNG, so the inputs are the same on every run.
+struct Lcg(u64);
+
+impl Lcg {
+    /// A float in `-1..1`.
+    fn next(&mut self) -> f32 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 40) as f32 / (1u64 << 23) as f32 - 1.0
+    }
+
+    fn vector(&mut self) -> Vector3D {
+        Vector3D::new(self.next(), self.next(), self.next())
+    }
+}
+
+/// The angle between two vectors in degrees, computed in `f64` so that it's accurate for the
+/// tiny angles of the round trips.
+fn angle(a: Vector3D, b: Vector3D) -> f64 {
+    let (a, b) = (
+        [a.x as f64, a.y as f64, a.z as f64],
+        [b.x as f64, b.y as f64, b.z as f64],
+    );
+    let cross = [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ];
+    let sin = (cross[0] * cross[0] + cross[1] * cross[1] + cross[2] * cross[2]).sqrt();
+    let cos = a[0] * b[0] + a[1] * b[1] + a[2] * b[2];
+    sin.atan2(cos).to_degrees()
+}
+
+#[test]
+fn test_octahedral_round_trip() {
+    let mut rng = Lcg(0x5eed);
+    let normals: Vec<Vector3D> = (0..100_000)
+        .map(|_| rng.vector())
+        .filter(|v| v.length() > 0.01)
+        .map(|v| v.normalize())
+        .collect();
+
+    let encoded = encode_octahedral_normals(&normals);
+    let decoded = decode_octahedral_normals(&encoded);
+    assert_eq!(decoded.len(), normals.len());
+    for (original, decoded) in normals.iter().zip(&decoded) {
+        assert!((decoded.length() - 1.0).abs() < 1e-5);
+        let error = angle(*original, *decoded);
+        assert!(
+            error <= MAX_OCTAHEDRAL_ERROR_DEGREES as f64,
+            "{:?}: {}",
+            original,
+            error
+        );
+    }
+}
+
+#[test]
+fn test_octahedral_edge_cases() {
+    let axes = [
+        Vector3D::new(1.0, 0.0, 0.0),
+        Vector3D::new(-1.0, 0.0, 0.0),
+        Vector3D::new(0.0, 1.0, 0.0),
+        Vector3D::new(0.0, -1.0, 0.0),
+        Vector3D::new(0.0, 0.0, 1.0),
+        Vector3D::new(0.0, 0.0, -1.0),
+    ];
+    let decoded = decode_octahedral_normals(&encode_octahedral_normals(&axes));
+    for (axis, decoded) in axes.iter().zip(&decoded) {
+        assert!(
+            angle(*axis, *decoded) < 1e-4,
+            "{:?} became {:?}",
+            axis,
+            decoded
+        );
+    }
+
+    // Unnormalized vectors keep their direction.
+    let long = Vector3D::new(3.0, -4.0, 12.0);
+    let decoded = decode_octahedral_normals(&encode_octahedral_normals(&[long]))[0];
+    assert!(angle(long, decoded) <= MAX_OCTAHEDRAL_ERROR_DEGREES as f64);
+
+    let degenerate = [
+        Vector3D::new(0.0, 0.0, 0.0),
+        Vector3D::new(f32::NAN, 0.0, 1.0),
+    ];
+    assert_eq!(encode_octahedral_normals(&degenerate), vec![[0, 0], [0, 0]]);
+    assert_eq!(
+        decode_octahedral_normals(&[[0, 0]]),
+        vec![Vector3D::new(0.0, 0.0, 1.0)]
+    );
+}
+
+#[test]
+fn test_uv_round_trip() {
+    let mut rng = Lcg(0x0b5e55ed);
+    let range = UvRange {
+        min: [-2.0, 0.0],
+        max: [2.0, 0.5],
+    };
+    let uvs: Vec<[f32; 2]> = (0..10_000)
+        .map(|_| [rng.next() * 2.0, (rng.next() + 1.0) * 0.25])
+        .collect();
+
+    let quantized = quantize_uvs(&uvs, range);
+    for (uv, quantized) in uvs.iter().zip(&quantized) {
+        let back = range.dequantize(*quantized);
+        assert!((back[0] - uv[0]).abs() <= 4.0 / 131070.0 + 1e-6);
+        assert!((back[1] - uv[1]).abs() <= 0.5 / 131070.0 + 1e-6);
+    }
+
+    assert_eq!(
+        UvRange::of_uvs(&[[0.5, -1.0], [0.25, 3.0]]).unwrap().min,
+        [0.25, -1.0]
+    );
+    assert_eq!(UvRange::of_uvs(&[]), None);
+
+    // Out of range UVs are clamped.
+    let unit = UvRange::default();
+    assert_eq!(
+        quantize_uvs(&[[-1.0, 2.0], [0.0, 1.0]], unit),
+        vec![[0, 65535], [0, 65535]]
+    );
+    assert_eq!(quantize_uvs(&[[0.5, f32::NAN]], unit), vec![[32768, 0]]);
+}
+
+#[test]
+fn test_position_round_trip() {
+    let mut rng = Lcg(0xc0ffee);
+    let positions: Vec<Vector3D> = (0..10_000).map(|_| rng.vector() * 100.0).collect();
+    let aabb = Aabb {
+        min: Vector3D::new(-100.0, -100.0, -100.0),
+        max: Vector3D::new(100.0, 100.0, 100.0),
+    };
+
+    let (quantized, transform) = quantize_positions(&positions, aabb);
+    // Half a step, plus the rounding of `f32`s at this magnitude.
+    let tolerance = 200.0 / 131070.0 + 1e-4;
+    for (position, quantized) in positions.iter().zip(&quantized) {
+        let back = transform.apply(*quantized);
+        assert!((back.x - position.x).abs() <= tolerance);
+        assert!((back.y - position.y).abs() <= tolerance);
+        assert!((back.z - position.z).abs() <= tolerance);
+
+        let matrix = transform.to_matrix();
+        let p = Vector3D::new(
+            quantized[0] as f32,
+            quantized[1] as f32,
+            quantized[2] as f32,
+        );
+        assert!((matrix.transform_point(p) - back).length() < 1e-3);
+    }
+
+    // A flat box quantizes everything to 0 along that axis.
+    let flat = Aabb {
+        min: Vector3D::new(0.0, 0.0, 1.0),
+        max: Vector3D::new(1.0, 1.0, 1.0),
+    };
+    let (quantized, transform) = quantize_positions(&[Vector3D::new(0.5, 0.5, 1.0)], flat);
+    assert_eq!(quantized[0][2], 0);
+    assert_eq!(transform, DequantizeTransform::of_aabb(flat));
+    assert_eq!(transform.apply(quantized[0]).z, 1.0);
+}
+
+#[test]
+fn test_cube() {
+    let mut importer = Importer::new();
+    importer.generate_normals(|x| x.enable = true);
+    let scene = fixtures::unit_cube_with(&importer);
+    let mesh = scene.mesh(0).unwrap();
+    let positions: Vec<Vector3D> = mesh.positions().collect();
+    let normals: Vec<Vector3D> = mesh.normals().collect();
+
+    // The corners of the cube are the corners of its bounds, so they're exact.
+    let aabb = Aabb::of_mesh(mesh).unwrap();
+    let (quantized, transform) = quantize_positions(&positions, aabb);
+    for (position, quantized) in positions.iter().zip(&quantized) {
+        for &component in quantized {
+            assert!(component == 0 || component == 65535);
+        }
+        assert!((transform.apply(*quantized) - *position).length() < 1e-6);
+    }
+
+    // So are the normals, which are all along the axes.
+    let decoded = decode_octahedral_normals(&encode_octahedral_normals(&normals));
+    for (normal, decoded) in normals.iter().zip(&decoded) {
+        assert!((*decoded - *normal).length() < 1e-6);
+    }
+
+    let layout = [
+        (Attribute::Position, Format::Position16),
+        (Attribute::Normal, Format::Octahedral16),
+    ];
+    let buffer = build_formatted(&scene, 0, &layout).unwrap();
+    assert_eq!(buffer.stride, 12);
+    assert_eq!(buffer.attributes[1].offset, 8);
+    assert_eq!(buffer.data.len(), 12 * positions.len());
+    assert_eq!(buffer.position_transform, Some(transform));
+    for (vertex, (position, normal)) in positions.iter().zip(&normals).enumerate() {
+        let vertex = vertex as u32;
+        let back = buffer.get(vertex, 0).unwrap();
+        assert!((Vector3D::new(back[0], back[1], back[2]) - *position).length() < 1e-6);
+        let back = buffer.get(vertex, 1).unwrap();
+        assert!((Vector3D::new(back[0], back[1], back[2]) - *normal).length() < 1e-6);
+    }
+
+    let error = build_formatted(&scene, 0, &[(Attribute::Position, Format::Octahedral16)]);
+    assert_eq!(
+        error.unwrap_err(),
+        BufferError::UnsupportedFormat {
+            attribute: Attribute::Position,
+            format: Format::Octahedral16,
+        }
+    );
+}
@@ -0,0 +1,157 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::fixtures;
+use assimp::scene::raycast::{build_bvh, Hit, Polygons, RaycastError, RaycastOptions, SceneBvh};
+use assimp::{Importer, Matrix4x4, Scene, Vector3D};
+
+fn close(a: Vector3D, b: Vector3D) -> bool {
+    (a - b).length() < 1e-4
+}
+
+fn translation(x: f32, y: f32, z: f32) -> Matrix4x4 {
+    Matrix4x4::from_rows([
+        [1.0, 0.0, 0.0, x],
+        [0.0, 1.0, 0.0, y],
+        [0.0, 0.0, 1.0, z],
+        [0.0, 0.0, 0.0, 1.0],
+    ])
+}
+
+fn cube_index(scene: &Scene) -> u32 {
+    scene
+        .meshes()
+        .position(|mesh| mesh.num_vertices() == 8)
+        .unwrap() as u32
+}
+
+#[test]
+fn test_raycast_face_center() {
+    let importer = Importer::new();
+    let scene = fixtures::unit_cube_with(&importer);
+
+    // The faces of the cube are quads.
+    let opts = RaycastOptions {
+        polygons: Polygons::Reject,
+        ..Default::default()
+    };
+    match build_bvh(&scene, opts) {
+        Err(RaycastError::Polygon { mesh: 0, face: 0 }) => {}
+        other => panic!("expected a polygon error, got {:?}", other.err()),
+    }
+
+    let bvh = build_bvh(&scene, RaycastOptions::default()).unwrap();
+    assert_eq!(bvh.num_triangles(), 12);
+
+    // Straight at the center of the face at z = 0.5, which is the first in the file.
+    let origin = Vector3D::new(0.0, 0.0, 5.0);
+    let direction = Vector3D::new(0.0, 0.0, -1.0);
+    let hit = bvh.intersect(origin, direction).unwrap();
+    assert_eq!((hit.mesh_index, hit.instance, hit.face_index), (0, 0, 0));
+    assert!((hit.t - 4.5).abs() < 1e-5);
+    assert!(close(hit.world_point, Vector3D::new(0.0, 0.0, 0.5)));
+
+    // The length of the direction scales `t`.
+    let hit = bvh.intersect(origin, direction * 2.0).unwrap();
+    assert!((hit.t - 2.25).abs() < 1e-5);
+
+    // The ray leaves through the face at z = -0.5, and each face is hit once even though the
+    // ray goes through the edge between the triangles of its fan.
+    let hits = bvh.intersect_all(origin, direction);
+    let faces: Vec<_> = hits.iter().map(|hit| hit.face_index).collect();
+    assert_eq!(faces, vec![0, 1]);
+    assert!((hits[1].t - 5.5).abs() < 1e-5);
+
+    // Hits behind the origin don't count.
+    let inside = Vector3D::new(0.0, 0.0, 0.0);
+    let hits = bvh.intersect_all(inside, direction);
+    assert_eq!(hits.len(), 1);
+    assert!((hits[0].t - 0.5).abs() < 1e-5);
+}
+
+#[test]
+fn test_raycast_miss() {
+    let importer = Importer::new();
+    let scene = fixtures::unit_cube_with(&importer);
+    let bvh = build_bvh(&scene, RaycastOptions::default()).unwrap();
+
+    let origin = Vector3D::new(2.0, 0.0, 5.0);
+    assert_eq!(bvh.intersect(origin, Vector3D::new(0.0, 0.0, -1.0)), None);
+    assert!(bvh
+        .intersect_all(origin, Vector3D::new(0.0, 0.0, -1.0))
+        .is_empty());
+    // Away from the cube.
+    let origin = Vector3D::new(0.0, 0.0, 5.0);
+    assert_eq!(bvh.intersect(origin, Vector3D::new(0.0, 0.0, 1.0)), None);
+}
+
+/// Interpolate the world positions of the triangle that `hit` refers to.
+fn interpolate(scene: &Scene, bvh: &SceneBvh, hit: &Hit) -> Vector3D {
+    let transform = bvh.transforms(hit.mesh_index).unwrap()[hit.instance as usize];
+    let mesh = scene.mesh(hit.mesh_index).unwrap();
+    let mut point = Vector3D::new(0.0, 0.0, 0.0);
+    for (&index, &weight) in hit.indices.iter().zip(&hit.barycentric) {
+        point = point + transform.transform_point(mesh.position(index).unwrap()) * weight;
+    }
+    point
+}
+
+#[test]
+fn test_raycast_instances() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/instanced.dae").unwrap();
+    let cube = cube_index(&scene);
+    let mut bvh = build_bvh(&scene, RaycastOptions::default()).unwrap();
+    assert_eq!(bvh.num_triangles(), 3 * 12 + 2);
+    assert_eq!(bvh.transforms(cube).unwrap().len(), 3);
+
+    // Down through CubeC, which is 2 units large at y = 6, and CubeB below it at y = 0.
+    let origin = Vector3D::new(0.3, 20.0, -5.2);
+    let direction = Vector3D::new(0.0, -1.0, 0.0);
+    let hits = bvh.intersect_all(origin, direction);
+    let found: Vec<_> = hits
+        .iter()
+        .map(|hit| (hit.mesh_index, hit.instance))
+        .collect();
+    assert_eq!(found, vec![(cube, 2), (cube, 2), (cube, 1), (cube, 1)]);
+    for (hit, &y) in hits.iter().zip(&[7.0, 5.0, 1.0, -1.0]) {
+        assert!((hit.t - (20.0 - y)).abs() < 1e-4);
+        assert!(close(hit.world_point, Vector3D::new(0.3, y, -5.2)));
+
+        // The barycentric coordinates lead back to the hit.
+        assert!((hit.barycentric.iter().sum::<f32>() - 1.0).abs() < 1e-5);
+        assert!(hit.barycentric.iter().all(|&w| w >= -1e-5));
+        assert!(close(interpolate(&scene, &bvh, hit), hit.world_point));
+    }
+    assert_eq!(bvh.intersect(origin, direction), Some(hits[0]));
+
+    // Move CubeC out of the way.
+    let mut transforms = bvh.transforms(cube).unwrap().to_vec();
+    transforms[2] = translation(10.0, 6.0, -5.0);
+    assert_eq!(
+        bvh.refit(cube, &transforms[..2]),
+        Err(RaycastError::TransformCount {
+            mesh: cube,
+            expected: 3,
+            actual: 2,
+        })
+    );
+    let num_meshes = scene.num_meshes();
+    assert_eq!(
+        bvh.refit(num_meshes, &[]),
+        Err(RaycastError::NoSuchMesh(num_meshes))
+    );
+    bvh.refit(cube, &transforms).unwrap();
+
+    let hit = bvh.intersect(origin, direction).unwrap();
+    assert_eq!((hit.mesh_index, hit.instance), (cube, 1));
+    assert!((hit.t - 19.0).abs() < 1e-4);
+    assert_eq!(bvh.intersect_all(origin, direction).len(), 2);
+
+    // And it can be hit where it is now.
+    let hit = bvh
+        .intersect(Vector3D::new(10.2, 20.0, -5.1), direction)
+        .unwrap();
+    assert_eq!((hit.mesh_index, hit.instance), (cube, 2));
+    assert!((hit.t - 13.5).abs() < 1e-4);
+    assert!(close(interpolate(&scene, &bvh, &hit), hit.world_point));
+}
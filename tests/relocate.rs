@@ -0,0 +1,129 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::scene::relocate::{
+    rewrite_texture_paths, rewrite_texture_paths_with, RewriteOptions, TexturePaths,
+};
+use assimp::scene::{MaterialSource, TextureTarget};
+use assimp::{Importer, MaterialComponentType};
+
+/// Moves `textures/x.png` to where a content-addressed store would put it.
+fn to_store(path: &str) -> Option<String> {
+    if path == "textures/x.png" {
+        Some("store\\3f\\3f9a2c.png".to_owned())
+    } else {
+        None
+    }
+}
+
+#[test]
+fn test_rewrite_overlay() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/relocate.gltf").unwrap();
+    let mut overlay = scene.material_overlay();
+    let stored = overlay
+        .materials()
+        .position(|material| material.original().eq_name(b"Stored"))
+        .unwrap() as u32;
+    let painted = overlay
+        .materials()
+        .position(|material| material.original().eq_name(b"Painted"))
+        .unwrap() as u32;
+
+    let changes = rewrite_texture_paths(&mut overlay, to_store);
+    assert!(!changes.is_empty());
+    for change in &changes {
+        assert_eq!(change.material, stored);
+        assert_eq!(change.old, "textures/x.png");
+        assert_eq!(change.new, "store/3f/3f9a2c.png");
+    }
+    assert!(changes
+        .iter()
+        .any(|change| change.component == MaterialComponentType::Diffuse && change.index == 0));
+
+    // Reads through the overlay see the new path, and the other textures are unchanged.
+    let material = overlay.material(stored).unwrap();
+    let diffuse = material.texture(MaterialComponentType::Diffuse, 0).unwrap();
+    assert_eq!(&*diffuse.path, "store/3f/3f9a2c.png");
+    let normals = material.texture(MaterialComponentType::Normals, 0).unwrap();
+    assert_eq!(&*normals.path, "textures/x_normal.png");
+    let original = material
+        .original()
+        .texture(MaterialComponentType::Diffuse, 0)
+        .unwrap();
+    assert_eq!(&*original.path, "textures/x.png");
+
+    // Rewriting again finds nothing left to do.
+    assert!(rewrite_texture_paths(&mut overlay, to_store).is_empty());
+
+    // Embedded references are only passed to the rewriter when asked for.
+    let everything = |_: &str| Some("elsewhere.png".to_owned());
+    let changes = rewrite_texture_paths(&mut overlay, everything);
+    assert!(changes.iter().all(|change| change.material == stored));
+    let changes =
+        rewrite_texture_paths_with(&mut overlay, RewriteOptions { embedded: true }, everything);
+    assert!(!changes.is_empty());
+    for change in &changes {
+        assert_eq!(change.material, painted);
+        assert_eq!(change.old, "*0");
+    }
+    let moved = overlay
+        .material(painted)
+        .unwrap()
+        .texture(MaterialComponentType::Diffuse, 0)
+        .unwrap();
+    assert_eq!(&*moved.path, "elsewhere.png");
+}
+
+#[test]
+fn test_rewrite_snapshot() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/relocate.gltf").unwrap();
+    let mut snapshot = scene.snapshot();
+    let before = snapshot.texture_paths();
+    assert!(before.iter().any(|(_, _, _, path)| path == "*0"));
+
+    let changes = rewrite_texture_paths(&mut snapshot, to_store);
+    assert!(!changes.is_empty());
+    for change in &changes {
+        let material = &snapshot.materials[change.material as usize];
+        assert_eq!(material.name, "Stored");
+        let texture = material
+            .textures
+            .iter()
+            .find(|texture| texture.component == change.component && texture.index == change.index)
+            .unwrap();
+        assert_eq!(texture.path, "store/3f/3f9a2c.png");
+        assert_eq!(texture.target, TextureTarget::External);
+        assert_eq!(change.old, "textures/x.png");
+    }
+
+    // Everything that wasn't rewritten is as it was.
+    let after = snapshot.texture_paths();
+    assert_eq!(before.len(), after.len());
+    let unchanged = before
+        .iter()
+        .zip(&after)
+        .filter(|(before, after)| before == after)
+        .count();
+    assert_eq!(unchanged, before.len() - changes.len());
+
+    // An embedded reference that's rewritten to a file becomes external.
+    let changes =
+        rewrite_texture_paths_with(&mut snapshot, RewriteOptions { embedded: true }, |path| {
+            if path == "*0" {
+                Some("textures/painted.png".to_owned())
+            } else {
+                None
+            }
+        });
+    assert!(!changes.is_empty());
+    let painted = snapshot
+        .materials
+        .iter()
+        .find(|material| material.name == "Painted")
+        .unwrap();
+    for texture in &painted.textures {
+        assert_eq!(texture.path, "textures/painted.png");
+        assert_eq!(texture.target, TextureTarget::External);
+    }
+}
@@ -0,0 +1,95 @@
+extern crate open_asset_importer as assimp;
+
+use std::convert::TryFrom;
+
+use assimp::fixtures;
+use assimp::scene::{
+    BlendMode, CullMode, Material, MaterialKey, MaterialValue, RenderStateHint, Scene,
+};
+use assimp::{Importer, InlineString};
+
+fn material<'a>(scene: &'a Scene, name: &str) -> &'a Material {
+    scene
+        .materials()
+        .find(|material| material.eq_name(name.as_bytes()))
+        .unwrap()
+}
+
+const OPAQUE: RenderStateHint = RenderStateHint {
+    cull: CullMode::Back,
+    blend: BlendMode::Opaque,
+    alpha_cutoff: None,
+    depth_write: true,
+};
+
+#[test]
+fn test_obj_materials() {
+    let importer = Importer::new();
+    let scene = fixtures::two_material_obj_with(&importer);
+    assert_eq!(material(&scene, "Red").render_state_hint(), OPAQUE);
+
+    // `d 0.5` makes the plaster translucent.
+    let scene = importer.read_file("examples/profile.obj").unwrap();
+    assert_eq!(material(&scene, "Brick").render_state_hint(), OPAQUE);
+    let plaster = material(&scene, "Plaster").render_state_hint();
+    assert_eq!(plaster.blend, BlendMode::AlphaBlend);
+    assert_eq!(plaster.alpha_cutoff, None);
+    assert!(!plaster.depth_write);
+}
+
+#[test]
+fn test_gltf_alpha_modes() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/alpha_modes.gltf").unwrap();
+    assert_eq!(
+        material(&scene, "Cutout").render_state_hint(),
+        RenderStateHint {
+            cull: CullMode::Back,
+            blend: BlendMode::AlphaTest,
+            alpha_cutoff: Some(0.4),
+            depth_write: true,
+        }
+    );
+    assert_eq!(
+        material(&scene, "Foliage").render_state_hint(),
+        RenderStateHint {
+            cull: CullMode::None,
+            blend: BlendMode::AlphaTest,
+            alpha_cutoff: Some(0.5),
+            depth_write: true,
+        }
+    );
+    assert_eq!(material(&scene, "Solid").render_state_hint(), OPAQUE);
+
+    let scene = importer.read_file("examples/translucent.gltf").unwrap();
+    assert_eq!(
+        material(&scene, "Glass").render_state_hint(),
+        RenderStateHint {
+            cull: CullMode::Back,
+            blend: BlendMode::AlphaBlend,
+            alpha_cutoff: None,
+            depth_write: false,
+        }
+    );
+}
+
+#[test]
+fn test_overlay_hint() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/alpha_modes.gltf").unwrap();
+    let solid = scene
+        .materials()
+        .position(|material| material.eq_name(b"Solid"))
+        .unwrap() as u32;
+    let mut overlay = scene.material_overlay();
+    overlay.set(solid, MaterialKey::TwoSided, MaterialValue::Bool(true));
+    overlay.set(
+        solid,
+        MaterialKey::GltfAlphaMode,
+        MaterialValue::String(InlineString::try_from("BLEND").ok().unwrap()),
+    );
+    let hint = overlay.material(solid).unwrap().render_state_hint();
+    assert_eq!(hint.cull, CullMode::None);
+    assert_eq!(hint.blend, BlendMode::AlphaBlend);
+    assert_eq!(material(&scene, "Solid").render_state_hint(), OPAQUE);
+}
@@ -0,0 +1,69 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::scene::{MaterialRequirementReport, RequiredAttribute};
+use assimp::Importer;
+
+fn report<'a>(
+    reports: &'a [MaterialRequirementReport],
+    name: &str,
+) -> &'a MaterialRequirementReport {
+    reports.iter().find(|report| report.name == name).unwrap()
+}
+
+#[test]
+fn test_attribute_requirements() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/requirements.gltf").unwrap();
+    let reports = scene.attribute_requirements();
+    assert_eq!(reports.len(), scene.num_materials() as usize);
+    for (index, report) in reports.iter().enumerate() {
+        assert_eq!(report.material_index, index as u32);
+    }
+
+    // Brick is normal mapped, and only the first mesh using it has tangents.
+    let brick = report(&reports, "Brick");
+    assert_eq!(
+        brick.required,
+        [
+            RequiredAttribute::TextureCoords(0),
+            RequiredAttribute::Tangents
+        ]
+    );
+    assert_eq!(brick.meshes.len(), 2);
+    assert!(!brick.satisfied);
+    assert_eq!(brick.meshes[0].mesh_index, 0);
+    assert!(brick.meshes[0].satisfied);
+    assert!(brick.meshes[0].missing.is_empty());
+    let unsatisfied: Vec<_> = brick.unsatisfied().collect();
+    assert_eq!(unsatisfied.len(), 1);
+    assert_eq!(unsatisfied[0].mesh_index, 1);
+    assert_eq!(unsatisfied[0].missing, [RequiredAttribute::Tangents]);
+
+    // Tiles only has a base color texture, on a mesh without UVs.
+    let tiles = report(&reports, "Tiles");
+    assert_eq!(tiles.required, [RequiredAttribute::TextureCoords(0)]);
+    assert!(!tiles.satisfied);
+    assert_eq!(tiles.meshes.len(), 1);
+    assert_eq!(tiles.meshes[0].mesh_index, 2);
+    assert_eq!(
+        tiles.meshes[0].missing,
+        [RequiredAttribute::TextureCoords(0)]
+    );
+
+    // Plain needs nothing, and nothing uses it.
+    let plain = report(&reports, "Plain");
+    assert!(plain.required.is_empty());
+    assert!(plain.meshes.is_empty());
+    assert!(plain.satisfied);
+}
+
+#[test]
+fn test_bump_maps_need_no_tangents() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/profile.obj").unwrap();
+    let reports = scene.attribute_requirements();
+    let brick = report(&reports, "Brick");
+    assert_eq!(brick.required, [RequiredAttribute::TextureCoords(0)]);
+    assert!(brick.satisfied);
+    assert!(!brick.meshes.is_empty());
+}
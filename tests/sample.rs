@@ -0,0 +1,190 @@
+extern crate autogenerated_assimp_sys as ffi;
+extern crate open_asset_importer as assimp;
+
+use assimp::scene::{AttributeSelector, SampleError, SampledValue};
+use assimp::{Color4D, Mesh, Vector3D};
+use std::ptr::NonNull;
+
+/// A mesh built in memory, with a triangle, a quad and a face with an index out of range.
+struct RawMesh {
+    mesh: ffi::aiMesh,
+    _positions: Vec<ffi::aiVector3D>,
+    _normals: Vec<ffi::aiVector3D>,
+    _uvs: Vec<ffi::aiVector3D>,
+    _colors: Vec<ffi::aiColor4D>,
+    _indices: Vec<Vec<u32>>,
+    _faces: Vec<ffi::aiFace>,
+}
+
+fn vector(x: f32, y: f32, z: f32) -> ffi::aiVector3D {
+    ffi::aiVector3D { x, y, z }
+}
+
+impl RawMesh {
+    fn new() -> RawMesh {
+        let mut positions = vec![
+            vector(0.0, 0.0, 0.0),
+            vector(2.0, 0.0, 0.0),
+            vector(0.0, 2.0, 0.0),
+            vector(2.0, 2.0, 0.0),
+        ];
+        let mut normals = vec![
+            vector(1.0, 0.0, 0.0),
+            vector(0.0, 1.0, 0.0),
+            vector(0.0, 0.0, 1.0),
+            vector(0.0, 0.0, 1.0),
+        ];
+        let mut uvs = vec![
+            vector(0.0, 0.0, 0.0),
+            vector(1.0, 0.0, 0.0),
+            vector(0.0, 1.0, 0.0),
+            vector(1.0, 1.0, 0.0),
+        ];
+        let color = |r, g, b, a| ffi::aiColor4D { r, g, b, a };
+        let mut colors = vec![
+            color(1.0, 0.0, 0.0, 1.0),
+            color(0.0, 1.0, 0.0, 1.0),
+            color(0.0, 0.0, 1.0, 0.0),
+            color(1.0, 1.0, 1.0, 1.0),
+        ];
+        let mut indices = vec![vec![0, 1, 2], vec![0, 1, 3, 2], vec![0, 1, 9]];
+        let mut faces: Vec<ffi::aiFace> = indices
+            .iter_mut()
+            .map(|indices| ffi::aiFace {
+                mNumIndices: indices.len() as u32,
+                mIndices: indices.as_mut_ptr(),
+            })
+            .collect();
+
+        let mut mesh: ffi::aiMesh = unsafe { std::mem::zeroed() };
+        mesh.mNumVertices = positions.len() as u32;
+        mesh.mVertices = positions.as_mut_ptr();
+        mesh.mNormals = normals.as_mut_ptr();
+        mesh.mTextureCoords[0] = uvs.as_mut_ptr();
+        mesh.mColors[0] = colors.as_mut_ptr();
+        mesh.mNumFaces = faces.len() as u32;
+        mesh.mFaces = faces.as_mut_ptr();
+
+        RawMesh {
+            mesh,
+            _positions: positions,
+            _normals: normals,
+            _uvs: uvs,
+            _colors: colors,
+            _indices: indices,
+            _faces: faces,
+        }
+    }
+
+    fn mesh(&self) -> &Mesh {
+        unsafe { Mesh::from_raw(NonNull::from(&self.mesh)) }
+    }
+}
+
+fn close(a: Vector3D, b: Vector3D) -> bool {
+    (a - b).length() < 1e-5
+}
+
+fn sample_vector(mesh: &Mesh, bary: [f32; 3], attr: AttributeSelector) -> Vector3D {
+    mesh.sample_attribute(0, bary, attr)
+        .unwrap()
+        .and_then(SampledValue::vector)
+        .unwrap()
+}
+
+#[test]
+fn test_sample_corners() {
+    let raw = RawMesh::new();
+    let mesh = raw.mesh();
+    let corners = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+    for (vertex, &bary) in corners.iter().enumerate() {
+        let vertex = vertex as u32;
+        let sampled = mesh.sample_all(0, bary).unwrap();
+        assert_eq!(sampled.position, mesh.position(vertex).unwrap());
+        assert_eq!(sampled.normal, mesh.normal(vertex));
+        assert_eq!(sampled.texture_coords[0], mesh.texture_coord(0, vertex));
+        assert_eq!(sampled.colors[0], mesh.vertex_color(0, vertex));
+        assert_eq!(
+            sampled.position,
+            sample_vector(mesh, bary, AttributeSelector::Position)
+        );
+    }
+}
+
+#[test]
+fn test_sample_centroid() {
+    let raw = RawMesh::new();
+    let mesh = raw.mesh();
+    let third = 1.0 / 3.0;
+    let centroid = [third, third, third];
+
+    let position = sample_vector(mesh, centroid, AttributeSelector::Position);
+    assert!(close(position, Vector3D::new(2.0 / 3.0, 2.0 / 3.0, 0.0)));
+    // The average of three perpendicular unit vectors, renormalized.
+    let normal = sample_vector(mesh, centroid, AttributeSelector::Normal);
+    assert!(close(normal, Vector3D::new(1.0, 1.0, 1.0).normalize()));
+    let uv = sample_vector(mesh, centroid, AttributeSelector::TextureCoords(0));
+    assert!(close(uv, Vector3D::new(third, third, 0.0)));
+
+    let color = mesh
+        .sample_attribute(0, centroid, AttributeSelector::Color(0))
+        .unwrap()
+        .and_then(SampledValue::color)
+        .unwrap();
+    let expected = [third, third, third, 2.0 / 3.0];
+    let color: [f32; 4] = color.into();
+    for (a, b) in color.iter().zip(&expected) {
+        assert!((a - b).abs() < 1e-5);
+    }
+
+    // Halfway along the second edge.
+    let sampled = mesh.sample_all(0, [0.0, 0.5, 0.5]).unwrap();
+    assert!(close(sampled.position, Vector3D::new(1.0, 1.0, 0.0)));
+    assert!(close(
+        sampled.texture_coords[0].unwrap(),
+        Vector3D::new(0.5, 0.5, 0.0)
+    ));
+    assert_eq!(sampled.colors[0], Some(Color4D::new(0.0, 0.5, 0.5, 0.5)));
+}
+
+#[test]
+fn test_sample_missing_attributes() {
+    let raw = RawMesh::new();
+    let mesh = raw.mesh();
+    let bary = [0.2, 0.3, 0.5];
+    for &attr in &[
+        AttributeSelector::Tangent,
+        AttributeSelector::Bitangent,
+        AttributeSelector::TextureCoords(1),
+        AttributeSelector::Color(1),
+        // Beyond the channels and sets that Assimp has.
+        AttributeSelector::TextureCoords(100),
+        AttributeSelector::Color(100),
+    ] {
+        assert_eq!(mesh.sample_attribute(0, bary, attr), Ok(None));
+    }
+
+    let sampled = mesh.sample_all(0, bary).unwrap();
+    assert_eq!((sampled.tangent, sampled.bitangent), (None, None));
+    assert!(sampled.texture_coords[1..].iter().all(Option::is_none));
+    assert!(sampled.colors[1..].iter().all(Option::is_none));
+}
+
+#[test]
+fn test_sample_invalid_faces() {
+    let raw = RawMesh::new();
+    let mesh = raw.mesh();
+    let bary = [1.0, 0.0, 0.0];
+    assert_eq!(
+        mesh.sample_all(1, bary),
+        Err(SampleError::NotATriangle {
+            face: 1,
+            num_indices: 4,
+        })
+    );
+    assert_eq!(
+        mesh.sample_attribute(2, bary, AttributeSelector::Position),
+        Err(SampleError::IndexOutOfRange { face: 2, index: 9 })
+    );
+    assert_eq!(mesh.sample_all(3, bary), Err(SampleError::NoSuchFace(3)));
+}
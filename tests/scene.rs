@@ -16,3 +16,38 @@ fn test_scene_properties() {
     assert_eq!(scene.num_lights() as usize, scene.light_iter().len());
     assert_eq!(scene.num_cameras() as usize, scene.camera_iter().len());
 }
+
+#[test]
+fn test_mesh_groups_after_split() {
+    use std::fmt::Write;
+
+    // A single object made of 200 unconnected triangles, i.e. 600 vertices.
+    let mut obj = String::from("o strip\n");
+    for i in 0..200 {
+        writeln!(obj, "v {} 0 0\nv {} 1 0\nv {} 0 1", i, i, i).unwrap();
+    }
+    for i in 0..200 {
+        writeln!(obj, "f {} {} {}", i * 3 + 1, i * 3 + 2, i * 3 + 3).unwrap();
+    }
+
+    let mut importer = Importer::new();
+    importer.split_large_meshes(|x| {
+        x.enable = true;
+        x.vertex_limit = 100;
+    });
+    let scene = importer
+        .read_memory_with_hint(obj.as_bytes(), "obj")
+        .unwrap();
+    assert!(scene.num_meshes() > 1);
+
+    let groups = scene.mesh_groups();
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0].meshes.len(), scene.num_meshes() as usize);
+
+    let total_vertices: u32 = groups[0]
+        .meshes
+        .iter()
+        .map(|&id| scene.mesh(id).unwrap().num_vertices())
+        .sum();
+    assert_eq!(total_vertices, 600);
+}
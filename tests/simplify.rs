@@ -0,0 +1,247 @@
+extern crate open_asset_importer as assimp;
+
+use std::collections::HashMap;
+
+use assimp::scene::simplify::{simplify, simplify_with, SimplifyOptions, SimplifyTarget};
+use assimp::scene::MeshData;
+use assimp::Vector3D;
+
+/// An icosahedron subdivided `levels` times and projected onto the unit sphere, with normals.
+fn sphere(levels: u32) -> MeshData {
+    let t = (1.0 + 5f32.sqrt()) / 2.0;
+    let mut positions: Vec<Vector3D> = [
+        [-1.0, t, 0.0],
+        [1.0, t, 0.0],
+        [-1.0, -t, 0.0],
+        [1.0, -t, 0.0],
+        [0.0, -1.0, t],
+        [0.0, 1.0, t],
+        [0.0, -1.0, -t],
+        [0.0, 1.0, -t],
+        [t, 0.0, -1.0],
+        [t, 0.0, 1.0],
+        [-t, 0.0, -1.0],
+        [-t, 0.0, 1.0],
+    ]
+    .iter()
+    .map(|&p| Vector3D::from(p).normalize())
+    .collect();
+    let mut triangles: Vec<[u32; 3]> = vec![
+        [0, 11, 5],
+        [0, 5, 1],
+        [0, 1, 7],
+        [0, 7, 10],
+        [0, 10, 11],
+        [1, 5, 9],
+        [5, 11, 4],
+        [11, 10, 2],
+        [10, 7, 6],
+        [7, 1, 8],
+        [3, 9, 4],
+        [3, 4, 2],
+        [3, 2, 6],
+        [3, 6, 8],
+        [3, 8, 9],
+        [4, 9, 5],
+        [2, 4, 11],
+        [6, 2, 10],
+        [8, 6, 7],
+        [9, 8, 1],
+    ];
+
+    for _ in 0..levels {
+        let mut midpoints = HashMap::new();
+        let mut midpoint = |a: u32, b: u32, positions: &mut Vec<Vector3D>| {
+            *midpoints.entry((a.min(b), a.max(b))).or_insert_with(|| {
+                let p = positions[a as usize].lerp(positions[b as usize], 0.5);
+                positions.push(p.normalize());
+                positions.len() as u32 - 1
+            })
+        };
+
+        let mut subdivided = Vec::new();
+        for &[a, b, c] in &triangles {
+            let ab = midpoint(a, b, &mut positions);
+            let bc = midpoint(b, c, &mut positions);
+            let ca = midpoint(c, a, &mut positions);
+            subdivided.extend_from_slice(&[[a, ab, ca], [b, bc, ab], [c, ca, bc], [ab, bc, ca]]);
+        }
+        triangles = subdivided;
+    }
+
+    MeshData {
+        normals: positions.clone(),
+        positions,
+        faces: triangles.iter().map(|t| t.to_vec()).collect(),
+        ..Default::default()
+    }
+}
+
+/// The volume enclosed by the triangles of `mesh`.
+fn volume(mesh: &MeshData) -> f32 {
+    mesh.faces
+        .iter()
+        .filter(|face| face.len() == 3)
+        .map(|face| {
+            let [a, b, c] = [
+                mesh.positions[face[0] as usize],
+                mesh.positions[face[1] as usize],
+                mesh.positions[face[2] as usize],
+            ];
+            a.dot(b.cross(c)) / 6.0
+        })
+        .sum()
+}
+
+fn assert_valid(mesh: &MeshData) {
+    for face in &mesh.faces {
+        assert!(face
+            .iter()
+            .all(|&index| (index as usize) < mesh.positions.len()));
+        if face.len() == 3 {
+            assert!(face[0] != face[1] && face[1] != face[2] && face[2] != face[0]);
+        }
+    }
+    assert!(mesh.normals.is_empty() || mesh.normals.len() == mesh.positions.len());
+}
+
+#[test]
+fn test_simplify_sphere() {
+    let mesh = sphere(3);
+    assert_eq!(mesh.faces.len(), 1280);
+
+    let simplified = simplify(&mesh, SimplifyTarget::Ratio(0.25));
+    assert_valid(&simplified);
+    assert!(simplified.faces.len() <= 330, "{}", simplified.faces.len());
+    assert!(simplified.positions.len() < mesh.positions.len() / 2);
+
+    let (before, after) = (volume(&mesh), volume(&simplified));
+    assert!(
+        ((after - before) / before).abs() < 0.05,
+        "{} != {}",
+        after,
+        before
+    );
+    for normal in &simplified.normals {
+        assert!((normal.length() - 1.0).abs() < 1e-4);
+    }
+
+    // The same input always gives the same output.
+    assert_eq!(simplify(&mesh, SimplifyTarget::Ratio(0.25)), simplified);
+}
+
+#[test]
+fn test_simplify_max_error() {
+    let mesh = sphere(2);
+
+    // Every collapse on a sphere has some error.
+    let unchanged = simplify(&mesh, SimplifyTarget::MaxError(0.0));
+    assert_eq!(unchanged.faces, mesh.faces);
+
+    let coarse = simplify(&mesh, SimplifyTarget::MaxError(0.1));
+    assert_valid(&coarse);
+    assert!(coarse.faces.len() < mesh.faces.len());
+    for p in &coarse.positions {
+        assert!((p.length() - 1.0).abs() < 0.1);
+    }
+}
+
+/// A flat grid of `n` by `n` quads split down the middle into two UV islands, with the vertices
+/// along the seam duplicated. The left island maps `x` to `u`, the right one to `u + 10`.
+fn seamed_grid(n: u32) -> MeshData {
+    let mut mesh = MeshData::default();
+    let mut uvs = Vec::new();
+    let half = n / 2;
+
+    for (island, columns) in [(0.0, 0..=half), (10.0, half..=n)].iter() {
+        let first = mesh.positions.len() as u32;
+        let width = columns.end() - columns.start() + 1;
+        for y in 0..=n {
+            for x in columns.clone() {
+                let (x, y) = (x as f32 / n as f32, y as f32 / n as f32);
+                mesh.positions.push(Vector3D::new(x, y, 0.0));
+                uvs.push(Vector3D::new(x + *island, y, 0.0));
+            }
+        }
+        for y in 0..n {
+            for x in 0..width - 1 {
+                let i = first + y * width + x;
+                mesh.faces.push(vec![i, i + 1, i + width + 1]);
+                mesh.faces.push(vec![i, i + width + 1, i + width]);
+            }
+        }
+    }
+    mesh.texture_coords = vec![Some(uvs)];
+    mesh
+}
+
+#[test]
+fn test_simplify_seam() {
+    let mesh = seamed_grid(16);
+    let opts = SimplifyOptions {
+        lock_boundary: true,
+    };
+    let simplified = simplify_with(&mesh, SimplifyTarget::Ratio(0.1), opts);
+    assert_valid(&simplified);
+    assert!(simplified.faces.len() < mesh.faces.len() / 2);
+
+    // Every UV still belongs to the island of its triangle: interpolating along edges of the
+    // flat grid gives exactly the UV of the new position.
+    let uvs = simplified.texture_coords[0].as_ref().unwrap();
+    for face in &simplified.faces {
+        let island = if uvs[face[0] as usize].x >= 10.0 {
+            10.0
+        } else {
+            0.0
+        };
+        for &index in face {
+            let (p, uv) = (simplified.positions[index as usize], uvs[index as usize]);
+            assert!((uv.x - island - p.x).abs() < 1e-5, "{:?} at {:?}", uv, p);
+            assert!((uv.y - p.y).abs() < 1e-5);
+        }
+    }
+
+    // Both sides of the seam keep all of their vertices, so there are no cracks.
+    let seam = |min_u: f32, max_u: f32| {
+        let mut ys: Vec<i32> = simplified
+            .positions
+            .iter()
+            .zip(uvs)
+            .filter(|(p, uv)| (p.x - 0.5).abs() < 1e-6 && uv.x >= min_u && uv.x < max_u)
+            .map(|(p, _)| (p.y * 16.0).round() as i32)
+            .collect();
+        ys.sort();
+        ys
+    };
+    assert_eq!(seam(0.0, 5.0), (0..=16).collect::<Vec<_>>());
+    assert_eq!(seam(5.0, 20.0), (0..=16).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_simplify_malformed() {
+    // Three triangles on one edge, a polygon and a face with an index out of range.
+    let mut mesh = sphere(1);
+    let extra = mesh.positions.len() as u32;
+    mesh.positions.push(Vector3D::new(0.0, 0.0, 2.0));
+    mesh.normals.push(Vector3D::new(0.0, 0.0, 1.0));
+    let edge = mesh.faces[0].clone();
+    mesh.faces.push(vec![edge[0], edge[1], extra]);
+    mesh.faces.push(vec![1, 2, 3, 4]);
+    mesh.faces.push(vec![0, 1, 1000]);
+    mesh.faces.push(vec![5, 5, 6]);
+    // Tangents that don't match the number of vertices are dropped.
+    mesh.tangents = vec![Vector3D::new(1.0, 0.0, 0.0)];
+
+    let simplified = simplify(&mesh, SimplifyTarget::Ratio(0.0));
+    assert_valid(&simplified);
+    assert!(simplified.tangents.is_empty());
+    assert_eq!(
+        simplified
+            .faces
+            .iter()
+            .filter(|face| face.len() == 4)
+            .count(),
+        1
+    );
+    assert!(simplified.faces.len() < mesh.faces.len());
+}
@@ -0,0 +1,38 @@
+#![cfg(feature = "assimp-5-2")]
+
+extern crate open_asset_importer as assimp;
+
+use assimp::Importer;
+
+#[test]
+fn test_gltf_skeleton() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/rigged.gltf").unwrap();
+    let mesh = scene.mesh(0).unwrap();
+
+    assert_eq!(scene.num_skeletons(), 1);
+    let skeleton = scene.skeletons().next().unwrap();
+    assert_eq!(skeleton.num_bones(), mesh.num_bones());
+    assert_eq!(skeleton.bones().count(), mesh.num_bones() as usize);
+
+    let roots: Vec<_> = skeleton
+        .bones()
+        .filter(|bone| bone.parent().is_none())
+        .collect();
+    assert_eq!(roots.len(), 1);
+
+    for bone in skeleton.bones() {
+        if let Some(parent) = bone.parent() {
+            assert!(skeleton.bone(parent).is_some());
+        }
+        assert_eq!(bone.weights().count(), bone.num_weights() as usize);
+    }
+
+    let names: Vec<_> = skeleton
+        .bones()
+        .filter_map(|bone| bone.node())
+        .map(|node| node.name().to_owned())
+        .collect();
+    assert!(names.contains(&"Bone2".to_owned()));
+    assert!(skeleton.bone(skeleton.num_bones()).is_none());
+}
@@ -0,0 +1,228 @@
+extern crate autogenerated_assimp_sys as ffi;
+extern crate open_asset_importer as assimp;
+
+use assimp::scene::skinning::{pack, JointFormat, PackOptions, SkinError, WeightFormat};
+use assimp::{Importer, Mesh};
+use std::ptr::{self, NonNull};
+
+/// A mesh built in memory, for weights that no importer would produce.
+struct RawMesh {
+    mesh: ffi::aiMesh,
+    _bones: Vec<Box<ffi::aiBone>>,
+    _bone_ptrs: Vec<*mut ffi::aiBone>,
+    _weights: Vec<Vec<ffi::aiVertexWeight>>,
+}
+
+impl RawMesh {
+    fn new(num_vertices: u32, bones: &[&[(u32, f32)]]) -> RawMesh {
+        let mut weights: Vec<Vec<ffi::aiVertexWeight>> = bones
+            .iter()
+            .map(|bone| {
+                bone.iter()
+                    .map(|&(vertex, weight)| ffi::aiVertexWeight {
+                        mVertexId: vertex,
+                        mWeight: weight,
+                    })
+                    .collect()
+            })
+            .collect();
+        let mut bones: Vec<Box<ffi::aiBone>> = weights
+            .iter_mut()
+            .map(|weights| {
+                let mut bone: Box<ffi::aiBone> = Box::new(unsafe { std::mem::zeroed() });
+                bone.mNumWeights = weights.len() as u32;
+                bone.mWeights = weights.as_mut_ptr();
+                bone
+            })
+            .collect();
+        let mut bone_ptrs: Vec<*mut ffi::aiBone> =
+            bones.iter_mut().map(|bone| &mut **bone as *mut _).collect();
+
+        let mut mesh: ffi::aiMesh = unsafe { std::mem::zeroed() };
+        mesh.mNumVertices = num_vertices;
+        mesh.mNumBones = bone_ptrs.len() as u32;
+        mesh.mBones = if bone_ptrs.is_empty() {
+            ptr::null_mut()
+        } else {
+            bone_ptrs.as_mut_ptr()
+        };
+
+        RawMesh {
+            mesh,
+            _bones: bones,
+            _bone_ptrs: bone_ptrs,
+            _weights: weights,
+        }
+    }
+
+    fn mesh(&self) -> &Mesh {
+        unsafe { Mesh::from_raw(NonNull::from(&self.mesh)) }
+    }
+}
+
+fn options(joints: JointFormat, weights: WeightFormat, influences: u32) -> PackOptions {
+    PackOptions {
+        joints,
+        weights,
+        influences,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_pack_rigged() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/rigged.dae").unwrap();
+    let mesh = scene.mesh(0).unwrap();
+
+    let skin = pack(mesh, PackOptions::default()).unwrap();
+    assert_eq!(skin.num_vertices, mesh.num_vertices());
+    assert_eq!(skin.joint_stride, 4);
+    assert_eq!(skin.weight_stride, 16);
+    assert_eq!(skin.joints.len(), 4 * mesh.num_vertices() as usize);
+    assert_eq!(skin.weights.len(), 16 * mesh.num_vertices() as usize);
+    assert_eq!(skin.bones, vec![0, 1]);
+
+    // Every weight in the mesh ends up in one of the vertex's slots.
+    for (bone_index, bone) in mesh.bones().enumerate() {
+        for weight in bone.weights() {
+            let found = (0..4).any(|slot| {
+                skin.joint(weight.mVertexId, slot) == Some(bone_index as u32)
+                    && (skin.weight(weight.mVertexId, slot).unwrap() - weight.mWeight).abs() < 1e-6
+            });
+            assert!(found, "weight of bone {} is missing", bone_index);
+        }
+    }
+}
+
+#[test]
+fn test_pack_limit_and_renormalize() {
+    let raw = RawMesh::new(
+        1,
+        &[
+            &[(0, 0.1)],
+            &[(0, 0.2)],
+            &[(0, 0.3)],
+            &[(0, 0.15)],
+            &[(0, 0.25)],
+        ],
+    );
+
+    let skin = pack(raw.mesh(), options(JointFormat::U16, WeightFormat::F32, 4)).unwrap();
+    let joints: Vec<u32> = (0..4).map(|slot| skin.joint(0, slot).unwrap()).collect();
+    assert_eq!(joints, vec![2, 4, 1, 3]);
+
+    let expected = [0.3 / 0.9, 0.25 / 0.9, 0.2 / 0.9, 0.15 / 0.9];
+    for (slot, &expected) in expected.iter().enumerate() {
+        assert!((skin.weight(0, slot as u32).unwrap() - expected).abs() < 1e-6);
+    }
+
+    let skin = pack(raw.mesh(), options(JointFormat::U8, WeightFormat::F32, 2)).unwrap();
+    assert_eq!(skin.joints, vec![2, 4]);
+    assert!((skin.weight(0, 0).unwrap() - 0.3 / 0.55).abs() < 1e-6);
+}
+
+#[test]
+fn test_pack_unorm8_round_trip() {
+    let raw = RawMesh::new(
+        4,
+        &[
+            &[(0, 1.0), (1, 1.0 / 3.0), (2, 0.2), (3, 0.5)],
+            &[(1, 1.0 / 3.0), (2, 0.2), (3, 0.3)],
+            &[(1, 1.0 / 3.0), (2, 0.2)],
+            &[(2, 0.4), (3, 0.2)],
+        ],
+    );
+
+    for &influences in &[2, 4, 8] {
+        let unorm = pack(
+            raw.mesh(),
+            options(JointFormat::U8, WeightFormat::Unorm8, influences),
+        )
+        .unwrap();
+        let float = pack(
+            raw.mesh(),
+            options(JointFormat::U8, WeightFormat::F32, influences),
+        )
+        .unwrap();
+        assert_eq!(unorm.joints, float.joints);
+        assert_eq!(unorm.weight_stride, influences as usize);
+
+        for vertex in 0..4 {
+            let element = &unorm.weights[vertex * unorm.weight_stride..][..unorm.weight_stride];
+            assert_eq!(element.iter().map(|&w| w as u32).sum::<u32>(), 255);
+
+            for slot in 0..influences {
+                let quantized = unorm.weight(vertex as u32, slot).unwrap();
+                let exact = float.weight(vertex as u32, slot).unwrap();
+                assert!((quantized - exact).abs() <= 1.0 / 255.0);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_pack_unweighted_vertices_and_unused_bones() {
+    let raw = RawMesh::new(
+        3,
+        &[
+            &[(0, 0.0), (1, 0.0)],
+            &[(0, 1.0), (2, std::f32::NAN)],
+            &[],
+            &[(2, -1.0), (0, 0.5)],
+        ],
+    );
+
+    let skin = pack(
+        raw.mesh(),
+        options(JointFormat::U8, WeightFormat::Unorm8, 2),
+    )
+    .unwrap();
+    assert_eq!(skin.bones, vec![0, 1, 2, 3]);
+    assert_eq!(skin.joints, vec![1, 3, 0, 0, 0, 0]);
+    assert_eq!(skin.weights, vec![170, 85, 0, 0, 0, 0]);
+
+    let skin = pack(
+        raw.mesh(),
+        PackOptions {
+            drop_unused_bones: true,
+            ..options(JointFormat::U8, WeightFormat::Unorm8, 2)
+        },
+    )
+    .unwrap();
+    assert_eq!(skin.bones, vec![1, 3]);
+    assert_eq!(skin.joints, vec![0, 1, 0, 0, 0, 0]);
+    assert_eq!(skin.weights, vec![170, 85, 0, 0, 0, 0]);
+}
+
+#[test]
+fn test_pack_errors() {
+    let weights: Vec<[(u32, f32); 1]> = (0..257).map(|_| [(0, 1.0)]).collect();
+    let bones: Vec<&[(u32, f32)]> = weights.iter().map(|weights| &weights[..]).collect();
+    let raw = RawMesh::new(1, &bones);
+
+    assert_eq!(
+        pack(raw.mesh(), PackOptions::default()),
+        Err(SkinError::TooManyBones {
+            bones: 257,
+            max: 256
+        })
+    );
+    let skin = pack(raw.mesh(), options(JointFormat::U16, WeightFormat::F32, 8)).unwrap();
+    assert_eq!(skin.bones.len(), 257);
+    // All weights are equal, so the lowest joint indices win.
+    assert_eq!(skin.joint(0, 7), Some(7));
+
+    assert_eq!(
+        pack(raw.mesh(), options(JointFormat::U8, WeightFormat::F32, 3)),
+        Err(SkinError::InvalidInfluenceCount(3))
+    );
+
+    let raw = RawMesh::new(2, &[&[(0, 1.0)], &[(2, 1.0)]]);
+    let error = pack(raw.mesh(), PackOptions::default()).unwrap_err();
+    assert_eq!(error, SkinError::VertexOutOfRange { bone: 1, vertex: 2 });
+    assert_eq!(
+        error.to_string(),
+        "bone 1 has a weight for missing vertex 2"
+    );
+}
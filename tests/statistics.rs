@@ -0,0 +1,67 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::fixtures;
+use assimp::scene::FaceCounts;
+use assimp::Importer;
+
+#[test]
+fn test_unit_cube_statistics() {
+    let importer = Importer::new();
+    let scene = fixtures::unit_cube_with(&importer);
+    let stats = scene.statistics();
+
+    assert_eq!(stats.num_meshes, 1);
+    // The OBJ importer doesn't share vertices between faces.
+    assert_eq!(stats.num_vertices, 24);
+    assert_eq!(stats.num_triangles, 12);
+    assert_eq!(
+        stats.faces,
+        FaceCounts {
+            points: 0,
+            lines: 0,
+            triangles: 0,
+            polygons: 6,
+        }
+    );
+    assert_eq!(stats.num_materials, 1);
+    assert_eq!(stats.num_bones, 0);
+    assert_eq!(stats.max_bone_influences, 0);
+    assert_eq!(stats.num_animations, 0);
+    assert_eq!(stats.total_animation_duration, 0.0);
+    assert_eq!(stats.num_embedded_textures, 0);
+    assert_eq!(stats.num_external_textures, 0);
+    assert_eq!(stats.num_nodes, 2);
+    assert_eq!(stats.max_depth, 2);
+    assert_eq!(stats.uv_channel_counts.iter().collect::<Vec<_>>(), vec![&1]);
+}
+
+#[test]
+fn test_rigged_statistics() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/rigged.dae").unwrap();
+    let stats = scene.statistics();
+
+    assert_eq!(stats.num_meshes, 1);
+    assert_eq!(stats.num_vertices, 6);
+    assert_eq!(stats.num_triangles, 2);
+    assert_eq!(stats.faces.triangles, 2);
+    assert_eq!(stats.num_bones, 2);
+    assert_eq!(stats.max_bone_influences, 2);
+    assert_eq!(stats.num_animations, 1);
+    assert!((stats.total_animation_duration - 1.0).abs() < 1e-6);
+    // Scene -> Armature -> Bone1 -> Bone2, plus the Quad node.
+    assert_eq!(stats.num_nodes, 5);
+    assert_eq!(stats.max_depth, 4);
+}
+
+#[test]
+fn test_statistics_display() {
+    let importer = Importer::new();
+    let scene = fixtures::unit_cube_with(&importer);
+    let table = scene.statistics().to_string();
+
+    assert!(table
+        .lines()
+        .any(|line| line.starts_with("Vertices") && line.ends_with(" 24")));
+    assert_eq!(table.lines().count(), 17);
+}
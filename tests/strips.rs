@@ -0,0 +1,176 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::fixtures;
+use assimp::scene::strips::{
+    stripify, RestartIndex, StripError, StripIndices, StripOptions, StripOutput, StripQuality,
+};
+use assimp::Importer;
+
+/// A triangle rotated so that its smallest index comes first, which keeps its winding.
+fn canonical(t: [u32; 3]) -> [u32; 3] {
+    let first = (0..3).min_by_key(|&i| t[i]).unwrap();
+    [t[first], t[(first + 1) % 3], t[(first + 2) % 3]]
+}
+
+fn sorted_triangles(indices: &[u32]) -> Vec<[u32; 3]> {
+    let mut triangles: Vec<_> = indices
+        .chunks(3)
+        .map(|t| canonical([t[0], t[1], t[2]]))
+        .collect();
+    triangles.sort();
+    triangles
+}
+
+/// Expand strips back to a triangle list, flipping the winding of every odd triangle.
+fn expand(strips: &[Vec<u32>]) -> Vec<[u32; 3]> {
+    let mut triangles = Vec::new();
+    for strip in strips {
+        assert!(strip.len() >= 3);
+        for k in 0..strip.len() - 2 {
+            let t = if k % 2 == 0 {
+                [strip[k], strip[k + 1], strip[k + 2]]
+            } else {
+                [strip[k + 1], strip[k], strip[k + 2]]
+            };
+            triangles.push(canonical(t));
+        }
+    }
+    triangles.sort();
+    triangles
+}
+
+fn split_restart(indices: &[u32], restart_index: u32) -> Vec<Vec<u32>> {
+    indices
+        .split(|&index| index == restart_index)
+        .map(<[u32]>::to_vec)
+        .collect()
+}
+
+fn check(indices: &[u32]) {
+    let expected = sorted_triangles(indices);
+
+    for &quality in &[StripQuality::Fast, StripQuality::Best] {
+        let separate = stripify(
+            indices,
+            StripOptions {
+                output: StripOutput::Separate,
+                quality,
+            },
+        )
+        .unwrap();
+        let strips = match &separate.indices {
+            StripIndices::Separate(strips) => strips.clone(),
+            other => panic!("expected separate strips, got {:?}", other),
+        };
+        assert_eq!(expand(&strips), expected);
+
+        let stats = separate.statistics;
+        assert_eq!(stats.strip_count, strips.len());
+        assert_eq!(stats.indices_before, indices.len());
+        assert_eq!(
+            stats.indices_after,
+            strips.iter().map(Vec::len).sum::<usize>()
+        );
+        let average = expected.len() as f32 / strips.len() as f32;
+        assert!((stats.average_strip_length - average).abs() < 1e-6);
+
+        for &restart in &[RestartIndex::U32, RestartIndex::U16] {
+            let stream = stripify(
+                indices,
+                StripOptions {
+                    output: StripOutput::Restart(restart),
+                    quality,
+                },
+            )
+            .unwrap();
+            let (stream_indices, restart_index) = match &stream.indices {
+                StripIndices::Restart {
+                    indices,
+                    restart_index,
+                } => (indices, *restart_index),
+                other => panic!("expected a restart stream, got {:?}", other),
+            };
+            assert_eq!(restart_index, restart.value());
+            assert_eq!(
+                expand(&split_restart(stream_indices, restart_index)),
+                expected
+            );
+            assert_eq!(
+                stream.statistics.indices_after,
+                stats.indices_after + stats.strip_count - 1
+            );
+        }
+    }
+}
+
+/// A `size` by `size` grid of quads, each split into two counter-clockwise triangles.
+fn grid(size: u32) -> Vec<u32> {
+    let vertex = |x: u32, y: u32| y * (size + 1) + x;
+    let mut indices = Vec::new();
+    for y in 0..size {
+        for x in 0..size {
+            let (a, b, c, d) = (
+                vertex(x, y),
+                vertex(x + 1, y),
+                vertex(x + 1, y + 1),
+                vertex(x, y + 1),
+            );
+            indices.extend_from_slice(&[a, b, c, a, c, d]);
+        }
+    }
+    indices
+}
+
+#[test]
+fn test_stripify_cube() {
+    let mut importer = Importer::new();
+    importer.triangulate(true);
+    let scene = fixtures::unit_cube_with(&importer);
+    let indices = scene.mesh(0).unwrap().indices_u32();
+    assert_eq!(indices.len(), 36);
+
+    check(&indices);
+}
+
+#[test]
+fn test_stripify_grid() {
+    let indices = grid(16);
+    check(&indices);
+
+    // A grid is easy to strip, so the strips should be much longer than single triangles.
+    let best = stripify(&indices, StripOptions::default()).unwrap();
+    assert!(best.statistics.average_strip_length > 4.0);
+    assert!(best.statistics.indices_after < indices.len());
+}
+
+#[test]
+fn test_stripify_skips_degenerate_triangles() {
+    let result = stripify(&[0, 1, 2, 3, 3, 4], StripOptions::default()).unwrap();
+    assert_eq!(result.indices, StripIndices::Separate(vec![vec![0, 1, 2]]));
+    assert_eq!(result.statistics.indices_before, 6);
+}
+
+#[test]
+fn test_stripify_errors() {
+    assert_eq!(
+        stripify(&[0, 1, 2, 3], StripOptions::default()),
+        Err(StripError::NotTriangles { len: 4 })
+    );
+
+    let opts = StripOptions {
+        output: StripOutput::Restart(RestartIndex::U16),
+        quality: StripQuality::Fast,
+    };
+    assert_eq!(
+        stripify(&[0, 1, 65535], opts),
+        Err(StripError::IndexTooLarge {
+            index: 65535,
+            restart_index: 65535
+        })
+    );
+    assert!(stripify(&[0, 1, 65535], StripOptions::default()).is_ok());
+
+    let empty = stripify(&[], StripOptions::default()).unwrap();
+    assert_eq!(empty.statistics.strip_count, 0);
+    assert_eq!(empty.statistics.average_strip_length, 0.0);
+}
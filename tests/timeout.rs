@@ -0,0 +1,122 @@
+extern crate open_asset_importer as assimp;
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use assimp::fixtures;
+use assimp::import::{orphaned_imports, set_max_orphaned_imports, TimeoutError};
+use assimp::{Error, File, FileIO, Importer};
+
+/// Serves the fixtures from memory, stalling for `stall` each time it's opened.
+struct StallingIO {
+    stall: Duration,
+}
+
+impl FileIO for StallingIO {
+    fn open(&self, file_path: &str, mode: &str) -> Option<Box<dyn File>> {
+        thread::sleep(self.stall);
+        fixtures::file_io().open(file_path, mode)
+    }
+}
+
+fn wait_for_orphans(count: usize) {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while orphaned_imports() != count {
+        assert!(Instant::now() < deadline, "orphaned imports didn't finish");
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+fn timeout_returns_promptly() {
+    let importer = Importer::new();
+    let io = StallingIO {
+        stall: Duration::from_millis(500),
+    };
+
+    let start = Instant::now();
+    let result =
+        importer.read_file_with_io_and_timeout("unit_cube.obj", io, Duration::from_millis(50));
+    assert!(matches!(result, Err(TimeoutError::Timeout)));
+    assert!(start.elapsed() < Duration::from_millis(400));
+    assert_eq!(orphaned_imports(), 1);
+
+    // The orphaned import finishes and frees its scene in the background.
+    wait_for_orphans(0);
+}
+
+fn finishes_within_timeout() {
+    let mut importer = Importer::new();
+    importer.triangulate(true);
+    let io = StallingIO {
+        stall: Duration::from_millis(10),
+    };
+
+    let scene = importer
+        .read_file_with_io_and_timeout("unit_cube.obj", io, Duration::from_secs(10))
+        .unwrap();
+    assert!(scene.num_meshes() > 0);
+
+    let scene = importer
+        .read_file_with_timeout("examples/unit_cube.obj", Duration::from_secs(10))
+        .unwrap();
+    assert!(scene.num_meshes() > 0);
+    assert_eq!(orphaned_imports(), 0);
+}
+
+fn failed_import() {
+    let importer = Importer::new();
+
+    match importer.read_file_with_timeout("examples/non_existent_file.obj", Duration::from_secs(10))
+    {
+        Err(TimeoutError::Failed(Error::ImportFailed { message })) => assert!(!message.is_empty()),
+        Err(other) => panic!("expected a failed import, got {:?}", other),
+        Ok(_) => panic!("expected a failed import"),
+    }
+
+    // The crate's errors come through as they are.
+    let mut importer = Importer::new();
+    importer.denied_formats(&["obj"]);
+    match importer.read_file_with_timeout("examples/unit_cube.obj", Duration::from_secs(10)) {
+        Err(TimeoutError::Failed(Error::FormatNotAllowed { format })) => {
+            assert_eq!(format.as_deref(), Some("obj"))
+        }
+        Err(other) => panic!("expected a refused format, got {:?}", other),
+        Ok(_) => panic!("expected a refused format"),
+    }
+}
+
+fn busy_when_too_many_orphans() {
+    set_max_orphaned_imports(2);
+    let importer = Importer::new();
+    let stall = || StallingIO {
+        stall: Duration::from_millis(500),
+    };
+
+    for _ in 0..2 {
+        let result = importer.read_file_with_io_and_timeout(
+            "unit_cube.obj",
+            stall(),
+            Duration::from_millis(10),
+        );
+        assert!(matches!(result, Err(TimeoutError::Timeout)));
+    }
+    let result =
+        importer.read_file_with_io_and_timeout("unit_cube.obj", stall(), Duration::from_millis(10));
+    assert!(matches!(result, Err(TimeoutError::Busy)));
+
+    wait_for_orphans(0);
+    let result = importer.read_file_with_timeout("examples/unit_cube.obj", Duration::from_secs(10));
+    assert!(result.is_ok());
+
+    set_max_orphaned_imports(assimp::import::DEFAULT_MAX_ORPHANED_IMPORTS);
+}
+
+/// The orphaned import count is shared by the whole process, so these can't run at the same
+/// time as each other.
+#[test]
+fn test_read_file_with_timeout() {
+    timeout_returns_promptly();
+    finishes_within_timeout();
+    failed_import();
+    busy_when_too_many_orphans();
+}
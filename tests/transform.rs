@@ -0,0 +1,158 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::fixtures;
+use assimp::math::{transform_normals, transform_points, Matrix4x4, Vector3D};
+use assimp::Importer;
+
+/// The distance between two floats in units in the last place.
+fn ulps(a: f32, b: f32) -> u32 {
+    let key = |f: f32| {
+        let bits = f.to_bits() as i32;
+        if bits < 0 {
+            i32::MIN.wrapping_sub(bits)
+        } else {
+            bits
+        }
+    };
+    (key(a) as i64 - key(b) as i64).abs() as u32
+}
+
+fn assert_within_ulp(a: &[Vector3D], b: &[Vector3D]) {
+    assert_eq!(a.len(), b.len());
+    for (a, b) in a.iter().zip(b) {
+        assert!(ulps(a.x, b.x) <= 1 && ulps(a.y, b.y) <= 1 && ulps(a.z, b.z) <= 1);
+    }
+}
+
+fn close(a: Vector3D, b: Vector3D) -> bool {
+    (a - b).length() < 1e-5
+}
+
+fn points(count: usize) -> Vec<Vector3D> {
+    (0..count)
+        .map(|i| {
+            let i = i as f32;
+            Vector3D::new(i.sin() * 10.0, i.cos(), -i * 0.37)
+        })
+        .collect()
+}
+
+fn matrix() -> Matrix4x4 {
+    // A rotation around Z, non-uniform scaling and a translation.
+    Matrix4x4::new(
+        0.0, -2.0, 0.0, 1.0, 1.0, 0.0, 0.0, 2.0, 0.0, 0.0, 3.0, 3.0, 0.0, 0.0, 0.0, 1.0,
+    )
+}
+
+#[test]
+fn test_transform_points_matches_scalar() {
+    let matrix = matrix();
+    let mut dst = vec![Vector3D::new(9.0, 9.0, 9.0); 100];
+
+    // Lengths that do and don't divide into the unrolled chunks.
+    for &count in &[0, 1, 3, 4, 5, 8, 1023] {
+        let src = points(count);
+        transform_points(&matrix, &src, &mut dst);
+
+        let expected: Vec<Vector3D> = src.iter().map(|p| matrix.transform_point(*p)).collect();
+        assert_within_ulp(&dst, &expected);
+    }
+}
+
+#[test]
+fn test_transform_normals_matches_scalar() {
+    let matrix = matrix();
+    let normal_matrix = matrix.normal_matrix();
+    let src = points(1021);
+    let mut dst = Vec::new();
+    transform_normals(&matrix, &src, &mut dst);
+
+    let expected: Vec<Vector3D> = src
+        .iter()
+        .map(|n| normal_matrix.transform_vector(*n).normalize())
+        .collect();
+    assert_within_ulp(&dst, &expected);
+    assert!(dst.iter().all(|n| (n.length() - 1.0).abs() < 1e-5));
+}
+
+#[test]
+fn test_translation_affects_points_not_normals() {
+    let translation = Matrix4x4::new(
+        1.0, 0.0, 0.0, 5.0, 0.0, 1.0, 0.0, -2.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 0.0, 1.0,
+    );
+    let src = vec![Vector3D::new(0.0, 0.0, 1.0), Vector3D::new(0.0, 0.0, 0.0)];
+    let mut dst = Vec::new();
+
+    transform_points(&translation, &src, &mut dst);
+    assert_eq!(
+        dst,
+        vec![Vector3D::new(5.0, -2.0, 2.0), Vector3D::new(5.0, -2.0, 1.0)]
+    );
+
+    transform_normals(&translation, &src, &mut dst);
+    assert_eq!(dst, src);
+}
+
+#[test]
+fn test_normals_stay_perpendicular() {
+    // A plane through the origin, scaled non-uniformly: the normal has to tilt the other way.
+    let scale = Matrix4x4::new(
+        2.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0,
+    );
+    let tangent = Vector3D::new(1.0, -1.0, 0.0);
+    let normal = Vector3D::new(1.0, 1.0, 0.0).normalize();
+
+    let mut normals = Vec::new();
+    transform_normals(&scale, &[normal], &mut normals);
+    let tangent = scale.transform_vector(tangent);
+    assert!(normals[0].dot(tangent).abs() < 1e-6);
+    assert!(close(normals[0], Vector3D::new(1.0, 2.0, 0.0).normalize()));
+
+    // Mirroring keeps normals pointing out of the surface.
+    let mirror = Matrix4x4::new(
+        -1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0,
+    );
+    transform_normals(&mirror, &[Vector3D::new(1.0, 0.0, 0.0)], &mut normals);
+    assert!(close(normals[0], Vector3D::new(-1.0, 0.0, 0.0)));
+}
+
+#[test]
+fn test_affine_inverse() {
+    let matrix = matrix();
+    let inverse = matrix.affine_inverse().unwrap();
+    for &p in &points(16) {
+        assert!(close(inverse.transform_point(matrix.transform_point(p)), p));
+        assert!(close(matrix.transform_point(inverse.transform_point(p)), p));
+    }
+    let identity = (matrix * inverse).rows();
+    for (row, expected) in identity.iter().zip(Matrix4x4::identity().rows().iter()) {
+        for (value, expected) in row.iter().zip(expected) {
+            assert!((value - expected).abs() < 1e-6);
+        }
+    }
+
+    // Flattening the Z axis can't be undone.
+    let flatten = Matrix4x4::new(
+        1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0,
+    );
+    assert_eq!(flatten.affine_inverse(), None);
+}
+
+#[test]
+fn test_mesh_positions_transformed() {
+    let importer = Importer::new();
+    let scene = fixtures::unit_cube_with(&importer);
+    let mesh = scene.mesh(0).unwrap();
+    let matrix = matrix();
+
+    let positions = mesh.positions_transformed(&matrix);
+    let expected: Vec<Vector3D> = mesh
+        .positions()
+        .map(|p| matrix.transform_point(p))
+        .collect();
+    assert_within_ulp(&positions, &expected);
+
+    let normals = mesh.normals_transformed(&matrix);
+    assert_eq!(normals.len(), mesh.normals().len());
+    assert!(normals.iter().all(|n| (n.length() - 1.0).abs() < 1e-5));
+}
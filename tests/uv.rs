@@ -0,0 +1,186 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::scene::uv::{generate_lightmap_uvs, AtlasError, AtlasOptions, LightmapUvs};
+use assimp::scene::MeshData;
+use assimp::Vector3D;
+
+/// A cube with its 8 corners shared between the faces, which are quads.
+fn cube() -> MeshData {
+    let mut positions = Vec::new();
+    for &x in &[-1.0, 1.0] {
+        for &y in &[-1.0, 1.0] {
+            for &z in &[-1.0, 1.0] {
+                positions.push(Vector3D::new(x, y, z));
+            }
+        }
+    }
+    MeshData {
+        positions,
+        faces: vec![
+            vec![0, 1, 3, 2],
+            vec![4, 6, 7, 5],
+            vec![0, 4, 5, 1],
+            vec![2, 3, 7, 6],
+            vec![0, 2, 6, 4],
+            vec![1, 5, 7, 3],
+        ],
+        ..Default::default()
+    }
+}
+
+/// The chart covering the center of every texel, panicking if two charts cover the same one.
+fn rasterize(uvs: &LightmapUvs, resolution: usize) -> Vec<Option<u32>> {
+    let mut texels = vec![None; resolution * resolution];
+    for (triangle, &chart) in uvs.indices.chunks(3).zip(&uvs.charts) {
+        let [a, b, c] = [
+            uvs.uvs[triangle[0] as usize],
+            uvs.uvs[triangle[1] as usize],
+            uvs.uvs[triangle[2] as usize],
+        ];
+        let edge = |p: [f32; 2], q: [f32; 2], r: [f32; 2]| {
+            (q[0] - p[0]) * (r[1] - p[1]) - (q[1] - p[1]) * (r[0] - p[0])
+        };
+        let area = edge(a, b, c);
+        for y in 0..resolution {
+            for x in 0..resolution {
+                let p = [
+                    (x as f32 + 0.5) / resolution as f32,
+                    (y as f32 + 0.5) / resolution as f32,
+                ];
+                let inside = [edge(a, b, p), edge(b, c, p), edge(c, a, p)]
+                    .iter()
+                    .all(|&e| e * area > 0.0);
+                if inside {
+                    let texel = &mut texels[y * resolution + x];
+                    if let Some(other) = *texel {
+                        assert_eq!(other, chart);
+                    }
+                    *texel = Some(chart);
+                }
+            }
+        }
+    }
+    texels
+}
+
+#[test]
+fn test_cube_lightmap_uvs() {
+    let mesh = cube();
+    let opts = AtlasOptions {
+        resolution: 64,
+        padding: 2,
+        ..Default::default()
+    };
+    let uvs = generate_lightmap_uvs(&mesh, opts).unwrap();
+
+    // One chart per side, which has its own four vertices.
+    assert_eq!(uvs.num_charts, 6);
+    assert_eq!(uvs.uvs.len(), 24);
+    assert_eq!(uvs.charts, vec![0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5]);
+    assert!(uvs
+        .uvs
+        .iter()
+        .all(|uv| uv.iter().all(|&c| (0.0..=1.0).contains(&c))));
+
+    // The remap table leads back to the original triangles.
+    let original: Vec<u32> = mesh
+        .faces
+        .iter()
+        .flat_map(|f| vec![f[0], f[1], f[2], f[0], f[2], f[3]])
+        .collect();
+    let remapped: Vec<u32> = uvs.indices.iter().map(|&i| uvs.remap[i as usize]).collect();
+    assert_eq!(remapped, original);
+
+    let texels = rasterize(&uvs, 64);
+    let mut seen: Vec<u32> = texels.iter().filter_map(|&chart| chart).collect();
+    seen.sort();
+    seen.dedup();
+    assert_eq!(seen, vec![0, 1, 2, 3, 4, 5]);
+
+    // The bounds of the charts are at least `padding` texels apart.
+    let bounds: Vec<[f32; 4]> = (0..6)
+        .map(|chart| {
+            let mut b = [1.0f32, 1.0, 0.0, 0.0];
+            for (triangle, &c) in uvs.indices.chunks(3).zip(&uvs.charts) {
+                if c == chart {
+                    for &i in triangle {
+                        let [u, v] = uvs.uvs[i as usize];
+                        b = [b[0].min(u), b[1].min(v), b[2].max(u), b[3].max(v)];
+                    }
+                }
+            }
+            b
+        })
+        .collect();
+    let gap = 2.0 / 64.0 - 1e-5;
+    for (i, a) in bounds.iter().enumerate() {
+        assert!(a[0] >= gap && a[1] >= gap && a[2] <= 1.0 - gap && a[3] <= 1.0 - gap);
+        for b in &bounds[i + 1..] {
+            let apart = a[2] + gap <= b[0]
+                || b[2] + gap <= a[0]
+                || a[3] + gap <= b[1]
+                || b[3] + gap <= a[1];
+            assert!(apart, "{:?} {:?}", a, b);
+        }
+    }
+}
+
+#[test]
+fn test_flat_lightmap_uvs() {
+    // A flat grid is a single chart, so no vertices are split.
+    let n = 4;
+    let mut mesh = MeshData::default();
+    for y in 0..=n {
+        for x in 0..=n {
+            mesh.positions
+                .push(Vector3D::new(x as f32, y as f32 * 0.5, x as f32));
+        }
+    }
+    for y in 0..n {
+        for x in 0..n {
+            let i = y * (n + 1) + x;
+            mesh.faces.push(vec![i, i + 1, i + n + 2]);
+            mesh.faces.push(vec![i, i + n + 2, i + n + 1]);
+        }
+    }
+
+    let uvs = generate_lightmap_uvs(&mesh, AtlasOptions::default()).unwrap();
+    assert_eq!(uvs.num_charts, 1);
+    assert_eq!(uvs.uvs.len(), mesh.positions.len());
+    rasterize(&uvs, 32);
+}
+
+#[test]
+fn test_lightmap_uv_errors() {
+    let mut mesh = cube();
+    mesh.faces.push(vec![0, 1]);
+    let tiny = AtlasOptions {
+        resolution: 8,
+        padding: 2,
+        ..Default::default()
+    };
+    assert_eq!(
+        generate_lightmap_uvs(&mesh, tiny).unwrap_err(),
+        AtlasError::DoesNotFit { charts: 6 }
+    );
+
+    mesh.faces.push(vec![0, 1, 8]);
+    assert_eq!(
+        generate_lightmap_uvs(&mesh, AtlasOptions::default()).unwrap_err(),
+        AtlasError::IndexOutOfRange { face: 7, index: 8 }
+    );
+
+    let mut mesh = cube();
+    mesh.positions[3].0.z = f32::INFINITY;
+    assert_eq!(
+        generate_lightmap_uvs(&mesh, AtlasOptions::default()).unwrap_err(),
+        AtlasError::NonFinite { vertex: 3 }
+    );
+
+    // Points and lines have no area to map.
+    mesh.faces = vec![vec![0], vec![0, 1]];
+    assert_eq!(
+        generate_lightmap_uvs(&mesh, AtlasOptions::default()).unwrap_err(),
+        AtlasError::NoTriangles
+    );
+}
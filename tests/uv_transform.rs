@@ -0,0 +1,150 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::math::{Vector2D, Vector3D};
+use assimp::scene::uv_transform::bake_uv_transforms;
+use assimp::scene::{MaterialComponentType, SceneSnapshot, UvTransform};
+use assimp::Importer;
+
+fn import() -> SceneSnapshot {
+    let importer = Importer::new();
+    let scene = importer
+        .read_file("examples/texture_transform.gltf")
+        .unwrap();
+    scene.snapshot()
+}
+
+fn index_of(snapshot: &SceneSnapshot, name: &str) -> (u32, u32) {
+    let material = snapshot
+        .materials
+        .iter()
+        .position(|m| m.name == name)
+        .unwrap();
+    let mesh = snapshot.meshes.iter().position(|m| m.name == name).unwrap();
+    (material as u32, mesh as u32)
+}
+
+fn uvs(snapshot: &SceneSnapshot, mesh: u32) -> Vec<Vector3D> {
+    snapshot.meshes[mesh as usize].texture_coords[0]
+        .clone()
+        .unwrap()
+}
+
+#[test]
+fn test_bake_gltf_texture_transform() {
+    let mut snapshot = import();
+    let (tiled, tiled_mesh) = index_of(&snapshot, "Tiled");
+    let before = uvs(&snapshot, tiled_mesh);
+    let textures = &snapshot.materials[tiled as usize].textures;
+    assert!(textures
+        .iter()
+        .any(|t| t.component == MaterialComponentType::Normals));
+    assert!(textures
+        .iter()
+        .all(|t| t.channel == 0 && t.uv_transform.is_some()));
+
+    let report = bake_uv_transforms(&mut snapshot);
+
+    let baked: Vec<_> = report
+        .baked
+        .iter()
+        .filter(|b| b.material == tiled)
+        .collect();
+    assert_eq!(baked.len(), 1);
+    assert_eq!((baked[0].channel, &baked[0].meshes), (0, &vec![tiled_mesh]));
+    assert!(snapshot.materials[tiled as usize]
+        .textures
+        .iter()
+        .all(|t| t.uv_transform.is_none()));
+
+    // KHR_texture_transform maps glTF's UVs, whose V points down, to
+    // `offset + scale * uv`, here `(0.25, 0.5) + (2, 3) * uv`. Assimp flips V on import, so
+    // the texel a corner samples is at `1 - v` of the glTF UV.
+    let after = uvs(&snapshot, tiled_mesh);
+    assert_eq!(after.len(), 4);
+    for (before, after) in before.iter().zip(&after) {
+        let (u, v) = (before.x, 1.0 - before.y);
+        let expected = (0.25 + 2.0 * u, 1.0 - (0.5 + 3.0 * v));
+        assert!(
+            (after.x - expected.0).abs() < 1e-5,
+            "{:?} {:?}",
+            after,
+            expected
+        );
+        assert!(
+            (after.y - expected.1).abs() < 1e-5,
+            "{:?} {:?}",
+            after,
+            expected
+        );
+    }
+    // The corner at glTF UV (1, 1) samples twice across and three times down the texture,
+    // offset by a quarter and a half.
+    let corner = before
+        .iter()
+        .position(|uv| uv.x == 1.0 && uv.y == 0.0)
+        .unwrap();
+    assert!((after[corner].x - 2.25).abs() < 1e-5);
+    assert!((after[corner].y + 2.5).abs() < 1e-5);
+
+    // Baking again changes nothing, since the transforms were removed.
+    let baked_once = snapshot.clone();
+    let report = bake_uv_transforms(&mut snapshot);
+    assert!(report.baked.iter().all(|b| b.material != tiled));
+    assert_eq!(
+        snapshot.meshes[tiled_mesh as usize],
+        baked_once.meshes[tiled_mesh as usize]
+    );
+}
+
+#[test]
+fn test_conflicting_transforms_are_reported() {
+    let mut snapshot = import();
+    let original = snapshot.clone();
+    let (conflicting, conflicting_mesh) = index_of(&snapshot, "Conflicting");
+
+    let report = bake_uv_transforms(&mut snapshot);
+
+    assert!(report.baked.iter().all(|b| b.material != conflicting));
+    let conflicts: Vec<_> = report
+        .conflicts
+        .iter()
+        .filter(|c| c.material == conflicting)
+        .collect();
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0].channel, 0);
+    // The base color has a transform and the emissive texture doesn't.
+    let textures = &conflicts[0].textures;
+    assert!(textures.iter().any(|&(component, _, transform)| {
+        component == MaterialComponentType::Emissive && transform.is_none()
+    }));
+    let scaled = textures
+        .iter()
+        .find_map(|&(_, _, transform)| transform)
+        .unwrap();
+    assert_eq!(scaled.scaling, Vector2D::new(2.0, 2.0));
+
+    // Neither the UVs nor the material changed.
+    let index = conflicting_mesh as usize;
+    assert_eq!(snapshot.meshes[index], original.meshes[index]);
+    let index = conflicting as usize;
+    assert_eq!(snapshot.materials[index], original.materials[index]);
+}
+
+#[test]
+fn test_uv_transform_order() {
+    let transform = UvTransform {
+        translation: Vector2D::new(0.1, 0.0),
+        scaling: Vector2D::new(2.0, 1.0),
+        rotation: std::f32::consts::FRAC_PI_2,
+    };
+    // Scaled to (1, 0.5), rotated a quarter turn around (0.5, 0.5) to (0.5, 1), then moved.
+    let uv = transform.apply(Vector3D::new(0.5, 0.5, 0.25));
+    assert!((uv.x - 0.6).abs() < 1e-6, "{:?}", uv);
+    assert!((uv.y - 1.0).abs() < 1e-6, "{:?}", uv);
+    assert_eq!(uv.z, 0.25);
+
+    assert!(UvTransform::default().is_identity());
+    assert!(!transform.is_identity());
+    let uv = Vector3D::new(0.3, 0.7, 0.0);
+    assert_eq!(UvTransform::default().apply(uv), uv);
+}
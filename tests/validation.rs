@@ -0,0 +1,77 @@
+extern crate open_asset_importer as assimp;
+
+use std::thread;
+
+use assimp::fixtures;
+use assimp::scene::ValidationSeverity;
+use assimp::{Error, Importer};
+
+fn validating_importer() -> Importer {
+    let mut importer = Importer::new();
+    importer.validate_data_structure(true);
+    importer
+}
+
+#[test]
+fn test_validation_error_findings() {
+    // The only mesh uses material 5, but there's only one material.
+    match validating_importer().read_file("examples/bad_material_index.assbin") {
+        Err(Error::ValidationFailed { message, findings }) => {
+            assert!(message.contains("mMaterialIndex"));
+            let error = findings
+                .iter()
+                .find(|finding| finding.severity == ValidationSeverity::Error)
+                .unwrap();
+            assert!(error.message.contains("mMaterialIndex"));
+            assert!(!error.message.starts_with("Validation failed"));
+            assert_eq!(error.subject.as_deref(), Some("aiMesh::mMaterialIndex"));
+        }
+        other => panic!("expected a validation error, got {:?}", other.err()),
+    }
+}
+
+#[test]
+fn test_validation_warning_findings() {
+    // The mesh has a vertex that no face refers to.
+    let scene = validating_importer()
+        .read_file("examples/unreferenced_vertex.assbin")
+        .unwrap();
+    assert!(scene.has_validation_warning());
+    let findings = scene.validation_findings();
+    assert!(findings.iter().any(|finding| {
+        finding.severity == ValidationSeverity::Warning
+            && finding.message.contains("unreferenced vertices")
+    }));
+    assert_eq!(&scene.import_diagnostics().validation[..], findings);
+}
+
+#[test]
+fn test_no_findings_without_validation() {
+    let importer = Importer::new();
+    let scene = fixtures::unit_cube_with(&importer);
+    assert!(scene.validation_findings().is_empty());
+
+    let scene = fixtures::unit_cube_with(&validating_importer());
+    assert!(scene.is_validated());
+    assert!(scene.validation_findings().is_empty());
+}
+
+#[test]
+fn test_findings_of_other_threads() {
+    // Import a file that fails validation and one that passes on two threads at once, a few
+    // times over. The findings of each import are its own.
+    let broken = thread::spawn(|| {
+        for _ in 0..20 {
+            let result = validating_importer().read_file("examples/bad_material_index.assbin");
+            assert!(matches!(result, Err(Error::ValidationFailed { .. })));
+        }
+    });
+    let valid = thread::spawn(|| {
+        for _ in 0..20 {
+            let scene = fixtures::unit_cube_with(&validating_importer());
+            assert!(scene.validation_findings().is_empty());
+        }
+    });
+    broken.join().unwrap();
+    valid.join().unwrap();
+}
@@ -0,0 +1,91 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::scene::vertex_buffer::{build, Attribute, BufferError};
+use assimp::{Importer, MaterialComponentType};
+
+/// The U and V of `channel` of every vertex of the first mesh.
+fn uvs(scene: &assimp::Scene, channel: u32) -> Vec<Vec<f32>> {
+    scene
+        .mesh(0)
+        .unwrap()
+        .texture_coords(channel)
+        .map(|uv| {
+            let uv: [f32; 3] = uv.into();
+            uv[..2].to_vec()
+        })
+        .collect()
+}
+
+#[test]
+fn test_uv_for_component() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/uv_semantics.gltf").unwrap();
+
+    // Channel 0 is the lightmap UV, and the base color texture declares channel 1.
+    let layout = [
+        Attribute::Position,
+        Attribute::UvForComponent(MaterialComponentType::Diffuse),
+    ];
+    let buffer = build(&scene, 0, &layout).unwrap();
+    assert_eq!(buffer.stride, 20);
+    assert_eq!(buffer.num_vertices, 3);
+    assert_eq!(buffer.data.len(), 60);
+    assert_eq!(buffer.attributes[1].offset, 12);
+    assert_eq!(buffer.attributes[1].channel, Some(1));
+
+    let channel_0 = uvs(&scene, 0);
+    let channel_1 = uvs(&scene, 1);
+    assert_ne!(channel_0, channel_1);
+    for vertex in 0..3 {
+        assert_eq!(buffer.get(vertex, 1).unwrap(), channel_1[vertex as usize]);
+    }
+
+    // An explicit index still works, and gives the same bytes for the same channel.
+    let explicit = build(&scene, 0, &[Attribute::Position, Attribute::Uv(1)]).unwrap();
+    assert_eq!(explicit.data, buffer.data);
+    let lightmap = build(&scene, 0, &[Attribute::Uv(0)]).unwrap();
+    assert_eq!(lightmap.get(1, 0).unwrap(), channel_0[1]);
+}
+
+#[test]
+fn test_unresolved_semantics() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/uv_semantics.gltf").unwrap();
+
+    let attribute = Attribute::UvForComponent(MaterialComponentType::Emissive);
+    let error = build(&scene, 0, &[attribute.clone()]).unwrap_err();
+    assert!(matches!(&error, BufferError::NoTexture { attribute: a, .. } if *a == attribute));
+    assert!(error.to_string().contains("Emissive"));
+
+    let attribute = Attribute::NamedUv("map1".to_owned());
+    let error = build(&scene, 0, &[attribute.clone()]).unwrap_err();
+    assert_eq!(error, BufferError::NoNamedChannel { attribute });
+    assert!(error.to_string().contains("`map1`"));
+
+    let error = build(&scene, 0, &[Attribute::Normal, Attribute::Uv(2)]).unwrap_err();
+    assert!(matches!(
+        error,
+        BufferError::MissingAttribute {
+            attribute: Attribute::Normal,
+            ..
+        }
+    ));
+    let error = build(&scene, 0, &[Attribute::Uv(2)]).unwrap_err();
+    assert_eq!(error.to_string(), "the mesh has no UV channel 2");
+
+    assert_eq!(
+        build(&scene, 1, &[Attribute::Position]).unwrap_err(),
+        BufferError::MeshOutOfRange { mesh: 1, len: 1 }
+    );
+}
+
+#[test]
+fn test_color() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/uv_semantics.gltf").unwrap();
+
+    let error = build(&scene, 0, &[Attribute::Color(0)]).unwrap_err();
+    assert_eq!(error.to_string(), "the mesh has no color set 0");
+    // Out of range of Assimp's color sets isn't a panic.
+    assert!(build(&scene, 0, &[Attribute::Color(100)]).is_err());
+}
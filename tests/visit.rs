@@ -0,0 +1,161 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::scene::{Material, Mesh, MeshData, Node, SceneVisitor, VisitControl, VisitOptions};
+use assimp::{Importer, Matrix4x4, Vector3D};
+
+#[derive(Default)]
+struct Counter {
+    nodes: u32,
+    max_depth: u32,
+    meshes: u32,
+    positions: u32,
+    position_chunks: u32,
+    indices: u32,
+    index_chunks: u32,
+    materials: u32,
+    /// Stop at the start of this mesh.
+    stop_at: Option<u32>,
+}
+
+fn close(a: &Matrix4x4, b: &Matrix4x4) -> bool {
+    let (a, b) = (a.rows(), b.rows());
+    (0..4).all(|i| (0..4).all(|j| (a[i][j] - b[i][j]).abs() < 1e-4))
+}
+
+impl SceneVisitor for Counter {
+    fn on_node(&mut self, node: &Node, depth: u32, world_transform: &Matrix4x4) -> VisitControl {
+        assert!(close(world_transform, &node.world_transform()));
+        self.nodes += 1;
+        self.max_depth = self.max_depth.max(depth);
+        VisitControl::Continue
+    }
+
+    fn on_mesh_begin(&mut self, index: u32, _mesh: &Mesh) -> VisitControl {
+        if self.stop_at == Some(index) {
+            return VisitControl::Stop;
+        }
+        self.meshes += 1;
+        VisitControl::Continue
+    }
+
+    fn on_positions_chunk(&mut self, positions: &[Vector3D]) -> VisitControl {
+        self.positions += positions.len() as u32;
+        self.position_chunks += 1;
+        VisitControl::Continue
+    }
+
+    fn on_indices_chunk(&mut self, indices: &[u32]) -> VisitControl {
+        self.indices += indices.len() as u32;
+        self.index_chunks += 1;
+        VisitControl::Continue
+    }
+
+    fn on_material(&mut self, _index: u32, _material: &Material) -> VisitControl {
+        self.materials += 1;
+        VisitControl::Continue
+    }
+}
+
+#[test]
+fn test_visit_counts() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/instanced.dae").unwrap();
+    let root = scene.root_node().unwrap();
+
+    let mut counter = Counter::default();
+    let opts = VisitOptions {
+        positions_chunk_len: 5,
+        indices_chunk_len: 7,
+    };
+    assert_eq!(scene.visit(&mut counter, opts), VisitControl::Continue);
+    assert_eq!(counter.nodes as usize, root.descendants().count());
+    assert!(counter.max_depth >= 1);
+    assert_eq!(counter.meshes, scene.num_meshes());
+    assert_eq!(counter.materials, scene.num_materials());
+
+    let (mut positions, mut position_chunks, mut indices) = (0, 0, 0);
+    for mesh in scene.meshes() {
+        positions += mesh.num_vertices();
+        position_chunks += (mesh.num_vertices() + 4) / 5;
+        indices += mesh.indices_u32().len() as u32;
+    }
+    assert_eq!(counter.positions, positions);
+    assert_eq!(counter.position_chunks, position_chunks);
+    assert_eq!(counter.indices, indices);
+    // No chunk is longer than 7.
+    assert!(counter.index_chunks >= (indices + 6) / 7);
+
+    // Stopping skips everything after the callback that stopped.
+    let mut counter = Counter {
+        stop_at: Some(0),
+        ..Default::default()
+    };
+    assert_eq!(
+        scene.visit(&mut counter, VisitOptions::default()),
+        VisitControl::Stop
+    );
+    assert_eq!(counter.nodes as usize, root.descendants().count());
+    assert_eq!(
+        (counter.meshes, counter.positions, counter.materials),
+        (0, 0, 0)
+    );
+}
+
+/// Rebuilds the positions and triangles of each mesh from the chunks.
+#[derive(Default)]
+struct Writer {
+    meshes: Vec<MeshData>,
+    max_chunk: usize,
+}
+
+impl SceneVisitor for Writer {
+    fn on_mesh_begin(&mut self, index: u32, mesh: &Mesh) -> VisitControl {
+        assert_eq!(index as usize, self.meshes.len());
+        self.meshes.push(MeshData {
+            name: mesh.name().to_owned(),
+            material_index: mesh.material_id(),
+            ..Default::default()
+        });
+        VisitControl::Continue
+    }
+
+    fn on_positions_chunk(&mut self, positions: &[Vector3D]) -> VisitControl {
+        self.max_chunk = self.max_chunk.max(positions.len());
+        let mesh = self.meshes.last_mut().unwrap();
+        mesh.positions.extend_from_slice(positions);
+        VisitControl::Continue
+    }
+
+    fn on_indices_chunk(&mut self, indices: &[u32]) -> VisitControl {
+        assert_eq!(indices.len() % 3, 0);
+        let mesh = self.meshes.last_mut().unwrap();
+        mesh.faces
+            .extend(indices.chunks(3).map(|face| face.to_vec()));
+        VisitControl::Continue
+    }
+}
+
+#[test]
+fn test_visit_rebuilds_snapshot() {
+    let mut importer = Importer::new();
+    importer.triangulate(true);
+    let scene = importer.read_file("examples/instanced.dae").unwrap();
+    let snapshot = scene.snapshot();
+
+    for &len in &[1, 4, 64, 65536] {
+        let mut writer = Writer::default();
+        let opts = VisitOptions {
+            positions_chunk_len: len,
+            indices_chunk_len: len,
+        };
+        scene.visit(&mut writer, opts);
+        assert!(writer.max_chunk <= len);
+        assert_eq!(writer.meshes.len(), snapshot.meshes.len());
+        for (rebuilt, expected) in writer.meshes.iter().zip(&snapshot.meshes) {
+            assert_eq!(rebuilt.name, expected.name);
+            assert_eq!(rebuilt.material_index, expected.material_index);
+            assert_eq!(rebuilt.positions, expected.positions);
+            assert_eq!(rebuilt.faces, expected.faces);
+        }
+    }
+}
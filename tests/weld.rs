@@ -0,0 +1,91 @@
+extern crate open_asset_importer as assimp;
+
+use assimp::fixtures;
+use assimp::scene::weld::{cross_mesh_weld, smooth_normals, WeldMap};
+use assimp::{Importer, Scene, Vector3D};
+
+fn mesh_with_faces(scene: &Scene, num_faces: u32) -> u32 {
+    scene
+        .meshes()
+        .position(|mesh| mesh.num_faces() == num_faces)
+        .unwrap() as u32
+}
+
+fn position(scene: &Scene, mesh: u32, vertex: u32) -> Vector3D {
+    scene.mesh(mesh).unwrap().position(vertex).unwrap()
+}
+
+#[test]
+fn test_cross_mesh_weld() {
+    let importer = Importer::new();
+    let scene = importer.read_file("examples/split_cube.obj").unwrap();
+    assert_eq!(scene.num_meshes(), 2);
+    let (sides, top) = (mesh_with_faces(&scene, 5), mesh_with_faces(&scene, 1));
+
+    let welds = cross_mesh_weld(&scene, 1e-5);
+    // Each corner of the top is welded to the two side faces that meet there.
+    let mut corners = Vec::new();
+    for vertex in 0..scene.mesh(top).unwrap().num_vertices() {
+        let p = position(&scene, top, vertex);
+        let welded = welds.get(top, vertex);
+        assert_eq!(welded.len(), 2);
+        for other in welded {
+            assert_eq!(other.mesh, sides);
+            assert_eq!(position(&scene, sides, other.vertex), p);
+        }
+        if !corners.contains(&p) {
+            corners.push(p);
+        }
+    }
+    assert_eq!(corners.len(), 4);
+    assert!(corners.iter().all(|p| p.y == 0.5));
+    assert_eq!(welds.len(), 4 + 8);
+
+    // Welds go both ways, and nothing below the top is welded.
+    for (vertex, welded) in welds.iter() {
+        assert!(position(&scene, vertex.mesh, vertex.vertex).y == 0.5);
+        for other in welded {
+            assert!(welds.get(other.mesh, other.vertex).contains(&vertex));
+        }
+    }
+
+    assert_eq!(cross_mesh_weld(&scene, 0.0), welds);
+
+    // A single mesh has nothing to weld to.
+    let scene = fixtures::unit_cube_with(&importer);
+    assert!(cross_mesh_weld(&scene, 1e-5).is_empty());
+}
+
+#[test]
+fn test_smooth_normals_across_seam() {
+    let importer = Importer::new();
+    let whole = fixtures::unit_cube_with(&importer);
+    let whole_normals = smooth_normals(&whole, &WeldMap::default());
+    assert_eq!(whole_normals.len(), 1);
+    let whole_positions: Vec<Vector3D> = whole.mesh(0).unwrap().positions().collect();
+    let expected = |p: Vector3D| {
+        let vertex = whole_positions.iter().position(|&q| q == p).unwrap();
+        whole_normals[0][vertex]
+    };
+    // Every corner of the cube is shared by three faces.
+    for (&p, &normal) in whole_positions.iter().zip(&whole_normals[0]) {
+        assert!((normal - (p * 2.0).normalize()).length() < 1e-5);
+    }
+
+    let split = importer.read_file("examples/split_cube.obj").unwrap();
+    let top = mesh_with_faces(&split, 1);
+    let welds = cross_mesh_weld(&split, 1e-5);
+    let normals = smooth_normals(&split, &welds);
+    for (index, mesh) in split.meshes().enumerate() {
+        assert_eq!(normals[index].len(), mesh.num_vertices() as usize);
+        for (p, &normal) in mesh.positions().zip(&normals[index]) {
+            assert!((normal - expected(p)).length() < 1e-5);
+        }
+    }
+
+    // Without the welds, the top is flat.
+    let normals = smooth_normals(&split, &WeldMap::default());
+    for &normal in &normals[top as usize] {
+        assert!((normal - Vector3D::new(0.0, 1.0, 0.0)).length() < 1e-5);
+    }
+}